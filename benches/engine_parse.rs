@@ -0,0 +1,73 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parse-time benchmarks for engine response parsing
+//!
+//! Each engine's `response()` is run against the captured responses in
+//! `tests/fixtures/engines/` (the same corpus as the golden tests), so a
+//! change to an engine's HTML handling shows up as a timing regression here.
+//!
+//! Run with `cargo bench --bench engine_parse`; compare against a saved run
+//! with `--save-baseline <name>` / `--baseline <name>`.
+
+use std::hint::black_box;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use seesea_core::derive::RequestResponseEngine;
+use seesea_core::search::engines::*;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/engines").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("missing fixture {}: {}", path.display(), e))
+}
+
+/// Benchmark `engine.response()` on one fixture
+///
+/// `into_response` turns the fixture body into the engine's response type.
+fn bench_engine<E, F>(c: &mut Criterion, id: &str, engine: E, fixture_name: &str, into_response: F)
+where
+    E: RequestResponseEngine,
+    F: Fn(String) -> E::Response,
+{
+    let body = fixture(fixture_name);
+    let mut group = c.benchmark_group("engine_parse");
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_function(id, |b| {
+        // Cloning the body is setup, not part of the measurement
+        b.iter_batched(
+            || into_response(body.clone()),
+            |response| engine.response(black_box(response)).expect("fixture parses"),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn engine_parse(c: &mut Criterion) {
+    bench_engine(c, "bing", BingEngine::new(), "bing.html", |body| body);
+    bench_engine(c, "bing_images", BingImagesEngine::new(), "bing_images.html", |body| body);
+    bench_engine(c, "baidu_json", BaiduEngine::new(), "baidu.json", |body| (body, None));
+    bench_engine(c, "baidu_serp", BaiduEngine::new(), "baidu_serp.html", |body| (body, None));
+    bench_engine(c, "yandex", YandexEngine::new(), "yandex.html", |body| (body, None));
+    bench_engine(c, "sogou", SogouEngine::new(), "sogou.html", |body| body);
+    bench_engine(c, "sogou_wechat", SogouWechatEngine::new(), "sogou_wechat.html", |body| body);
+    bench_engine(c, "sogou_videos", SogouVideosEngine::new(), "sogou_videos.html", |body| body);
+    bench_engine(c, "so", SoEngine::new(), "so.html", |body| body);
+    bench_engine(c, "bilibili", BilibiliEngine::new(), "bilibili.json", |body| body);
+    bench_engine(c, "unsplash", UnsplashEngine::new(), "unsplash.json", |body| body);
+}
+
+criterion_group!(benches, engine_parse);
+criterion_main!(benches);
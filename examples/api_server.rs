@@ -1,7 +1,6 @@
 //! SeeSea API 服务器示例
 
 use std::sync::Arc;
-use tokio::sync::RwLock;
 
 use SeeSea::{
     api::ApiInterface,
@@ -23,7 +22,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut network_config = NetworkConfig::default();
     network_config.pool.max_idle_connections = 200;
     let network = Arc::new(NetworkInterface::new(network_config)?);
-    let cache = Arc::new(RwLock::new(CacheInterface::new(CacheImplConfig::default())?));
+    let cache = Arc::new(CacheInterface::new(CacheImplConfig::default())?);
     
     let api = ApiInterface::from_config(SearchConfig::default(), network, cache)?;
     let app = api.build_router();
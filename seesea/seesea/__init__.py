@@ -75,6 +75,8 @@ try:
         unregister_engine,
         list_engines,
         has_engine,
+        load_engines_from_dir,
+        import_searxng_engines,
     )
 except ImportError as e:
     import warnings
@@ -91,6 +93,8 @@ except ImportError as e:
     unregister_engine = None
     list_engines = None
     has_engine = None
+    load_engines_from_dir = None
+    import_searxng_engines = None
 
 # Python 高层接口
 from .search import SearchClient
@@ -112,6 +116,7 @@ from .cli import cli as cli_main
 from .types import (
     SearchResponse,
     SearchResultItem,
+    EngineResult,
     EngineState,
     CacheInfo,
     SearchStats,
@@ -241,6 +246,7 @@ __all__ = [
     # 类型定义（类型安全）
     'SearchResponse',
     'SearchResultItem',
+    'EngineResult',
     'EngineState',
     'CacheInfo',
     'SearchStats',
@@ -261,6 +267,8 @@ __all__ = [
     'unregister_engine',
     'list_engines',
     'has_engine',
+    'load_engines_from_dir',
+    'import_searxng_engines',
     
     # 工具函数
     'format_results',
@@ -18,11 +18,12 @@ SeeSea Search Client - 搜索客户端
 提供简单易用的搜索接口
 """
 
-from typing import Dict, List, Optional, Any
+from typing import AsyncIterator, Callable, Dict, List, Optional, Any
 from seesea_core import PySearchClient
 from .types import (
     SearchResponse,
     SearchResultItem,
+    EngineResult,
     EngineState,
     CacheInfo,
     SearchStats,
@@ -102,6 +103,64 @@ class SearchClient:
         )
         return SearchResponse.from_dict(result_dict)
     
+    async def search_async(
+        self,
+        query: str,
+        page: Optional[int] = 1,
+        page_size: Optional[int] = 10,
+        language: Optional[str] = None,
+        region: Optional[str] = None,
+        engines: Optional[List[str]] = None,
+        force: Optional[bool] = False,
+        cache_timeline: Optional[int] = None,
+    ) -> SearchResponse:
+        """
+        异步搜索，参数和返回值与 search() 相同
+        
+        搜索在 Rust 运行时中执行，不会阻塞事件循环，可以与其他协程并发。
+        
+        示例:
+            >>> import asyncio
+            >>> async def main():
+            ...     client = SearchClient()
+            ...     a, b = await asyncio.gather(
+            ...         client.search_async("rust"),
+            ...         client.search_async("python"),
+            ...     )
+            ...     print(a.total_count, b.total_count)
+            >>> asyncio.run(main())
+        """
+        result_dict = await self._client.search_async(
+            query,
+            page,
+            page_size,
+            language,
+            region,
+            engines,
+            force,
+            cache_timeline,
+        )
+        return SearchResponse.from_dict(result_dict)
+    
+    async def search_stream(
+        self,
+        query: str,
+        page: Optional[int] = 1,
+        page_size: Optional[int] = 10,
+        engines: Optional[List[str]] = None,
+    ) -> AsyncIterator[EngineResult]:
+        """
+        流式搜索的异步迭代器版本，每个引擎完成时产出一个 EngineResult
+        
+        提前退出循环时，尚未完成的引擎请求会被取消。
+        
+        示例:
+            >>> async for result in client.search_stream("rust"):
+            ...     print(f"{result.engine}: {len(result.items)} 个结果")
+        """
+        async for result_dict in self._client.search_stream(query, page, page_size, engines):
+            yield EngineResult.from_dict(result_dict)
+    
     def clear_cache(self) -> None:
         """
         清除所有缓存
@@ -268,6 +327,26 @@ class SearchClient:
         """
         self._client.invalidate_engine(engine_name)
     
+    def add_result_stage(
+        self,
+        callback: Callable[[List[Dict[str, Any]]], List[Dict[str, Any]]],
+        name: str = "python",
+        before: Optional[str] = None,
+    ) -> None:
+        """
+        注册结果后处理回调（例如内部重排模型）
+        
+        回调接收结果字典列表，返回新的列表：可以调整顺序、修改 score/title/content，
+        未返回的结果会被丢弃。回调出错时结果保持不变。
+        
+        Args:
+            callback: 后处理回调
+            name: 阶段名（用于日志）
+            before: 内置阶段名（dedup/scoring/language/sort/filter/diversity），
+                在该阶段之前执行；为 None 时在最后执行
+        """
+        self._client.add_result_stage(callback, name, before)
+    
     def list_global_engines(self) -> List[str]:
         """
         列出全局模式下的引擎
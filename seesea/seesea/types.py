@@ -34,6 +34,10 @@ class SearchResultItem:
         score: 相关性评分 (0.0-1.0)
         display_url: 显示用的 URL（可选）
         site_name: 网站名称（可选）
+        engine: 来源引擎（可选）
+        result_type: 结果类型，如 "web"、"image"、"video"（可选）
+        thumbnail: 缩略图 URL（可选）
+        published_date: 发布时间，RFC 3339 格式（可选）
     """
     title: str
     url: str
@@ -41,6 +45,10 @@ class SearchResultItem:
     score: float
     display_url: Optional[str] = None
     site_name: Optional[str] = None
+    engine: Optional[str] = None
+    result_type: Optional[str] = None
+    thumbnail: Optional[str] = None
+    published_date: Optional[str] = None
     
     @classmethod
     def from_dict(cls, data: Dict[str, Any]) -> 'SearchResultItem':
@@ -52,12 +60,49 @@ class SearchResultItem:
             score=data.get('score', 0.0),
             display_url=data.get('display_url'),
             site_name=data.get('site_name'),
+            engine=data.get('engine'),
+            result_type=data.get('result_type'),
+            thumbnail=data.get('thumbnail'),
+            published_date=data.get('published_date'),
         )
     
     def __repr__(self) -> str:
         return f"<SearchResultItem title='{self.title[:50]}...' url='{self.url}' score={self.score:.2f}>"
 
 
+@dataclass
+class EngineResult:
+    """
+    流式搜索中单个引擎的结果
+    
+    Attributes:
+        engine: 引擎名称
+        total_results: 引擎报告的结果总数（可选）
+        items: 该引擎返回的结果项
+    """
+    engine: str
+    items: List[SearchResultItem] = field(default_factory=list)
+    total_results: Optional[int] = None
+    
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> 'EngineResult':
+        """从字典创建引擎结果"""
+        return cls(
+            engine=data.get('engine', ''),
+            items=[SearchResultItem.from_dict(item) for item in data.get('items', [])],
+            total_results=data.get('total_results'),
+        )
+    
+    def __repr__(self) -> str:
+        return f"<EngineResult engine='{self.engine}' items={len(self.items)}>"
+    
+    def __len__(self) -> int:
+        return len(self.items)
+    
+    def __iter__(self):
+        return iter(self.items)
+
+
 @dataclass
 class SearchResponse:
     """
@@ -0,0 +1,358 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 管理 API 处理器
+//!
+//! 为管理面板提供引擎状态、缓存统计、最近错误和慢查询数据，仅在内网路由器注册
+
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::api::on::ApiState;
+use crate::config::{check_config_str, ConfigFormat};
+use crate::api::memory::{allocator_report, collect_memory_metrics, MemoryMetrics};
+use crate::api::types::{deserialize_flag, ApiErrorResponse};
+use crate::search::{EngineAnomaly, EngineCircuitState, EngineErrorRecord, EngineState, LatencyPercentiles, SlowQuery, TopQuery, DEFAULT_SELFTEST_QUERIES};
+use crate::search::engine_manager::ENGINE_CIRCUIT_COOLDOWN;
+
+/// 引擎状态详情
+#[derive(Debug, Serialize)]
+pub struct AdminEngineState {
+    /// 引擎名称
+    pub name: String,
+    /// 是否启用
+    pub enabled: bool,
+    /// 当前是否可用
+    pub available: bool,
+    /// 是否临时禁用
+    pub temporarily_disabled: bool,
+    /// 距离解除禁用的剩余秒数
+    pub disabled_remaining_secs: Option<u64>,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 总请求数
+    pub total_requests: u64,
+    /// 成功请求数
+    pub successful_requests: u64,
+    /// 失败请求数
+    pub failed_requests: u64,
+    /// 平均响应时间（毫秒）
+    pub avg_response_time_ms: u64,
+    /// 引擎熔断器状态
+    pub circuit_state: EngineCircuitState,
+    /// 熔断器打开时距离进入半开状态的剩余秒数
+    pub circuit_half_open_in_secs: Option<u64>,
+    /// 延迟百分位（成功和超时请求，还没有样本时为 None）
+    pub latency: Option<LatencyPercentiles>,
+    /// 当前使用的超时（毫秒）
+    pub timeout_ms: Option<u64>,
+}
+
+impl From<&EngineState> for AdminEngineState {
+    fn from(state: &EngineState) -> Self {
+        let now = Instant::now();
+        Self {
+            name: state.name.clone(),
+            enabled: state.enabled,
+            available: state.is_available(),
+            temporarily_disabled: state.temporarily_disabled,
+            disabled_remaining_secs: state.disabled_until
+                .filter(|until| *until > now)
+                .map(|until| (until - now).as_secs()),
+            consecutive_failures: state.consecutive_failures,
+            total_requests: state.total_requests,
+            successful_requests: state.successful_requests,
+            failed_requests: state.failed_requests,
+            avg_response_time_ms: state.avg_response_time_ms,
+            circuit_state: state.circuit_state(),
+            circuit_half_open_in_secs: state.circuit_opened_at
+                .map(|opened_at| opened_at + ENGINE_CIRCUIT_COOLDOWN)
+                .filter(|until| *until > now)
+                .map(|until| (until - now).as_secs()),
+            latency: None,
+            timeout_ms: None,
+        }
+    }
+}
+
+/// 单次自检最多的探测查询数
+const MAX_SELFTEST_QUERIES: usize = 5;
+
+/// 自检请求（请求体可省略）
+#[derive(Debug, Default, Deserialize)]
+pub struct SelfTestRequest {
+    /// 探测查询（为空时使用 `rust` 和 `新闻`）
+    #[serde(default)]
+    pub queries: Vec<String>,
+    /// 要测试的引擎（为空时测试所有启用的引擎）
+    #[serde(default)]
+    pub engines: Vec<String>,
+}
+
+/// 缓存统计
+#[derive(Debug, Serialize)]
+pub struct AdminCacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+    /// 命中率
+    pub hit_rate: f64,
+    /// 写入次数
+    pub writes: u64,
+    /// 删除次数
+    pub deletes: u64,
+    /// 总键数
+    pub total_keys: u64,
+    /// 估算的总大小（字节）
+    pub estimated_size_bytes: u64,
+    /// 过期清理次数
+    pub evictions: u64,
+}
+
+/// 堆内存报告查询参数
+#[derive(Debug, Default, Deserialize)]
+pub struct HeapQuery {
+    /// 附带 jemalloc 的完整统计（`detail=1`）
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub detail: bool,
+}
+
+/// 堆内存报告
+#[derive(Debug, Serialize)]
+pub struct AdminHeapReport {
+    /// 正在处理的请求数
+    pub in_flight_requests: u64,
+    /// 内存用量（未启用 `jemalloc` 特性时没有 `allocator`）
+    #[serde(flatten)]
+    pub memory: MemoryMetrics,
+    /// jemalloc 的完整统计（各 arena、size class 和互斥锁，仅 `detail=1` 且启用 `jemalloc` 时提供）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<serde_json::Value>,
+}
+
+/// 慢查询排行查询参数
+#[derive(Debug, Deserialize)]
+pub struct TopQueriesQuery {
+    /// 返回的最大条数
+    #[serde(default = "default_top_queries_limit")]
+    pub limit: usize,
+}
+
+fn default_top_queries_limit() -> usize {
+    20
+}
+
+/// 处理引擎状态请求（`/api/admin/engines` 与 `/api/admin/engines/state`）
+pub async fn handle_admin_engines(
+    State(state): State<ApiState>,
+) -> Response {
+    let engines: Vec<AdminEngineState> = state.search.engine_state_snapshots().await
+        .iter()
+        .map(|snapshot| AdminEngineState {
+            latency: state.search.engine_latency(&snapshot.name),
+            timeout_ms: Some(state.search.engine_timeout(&snapshot.name).as_millis() as u64),
+            ..AdminEngineState::from(snapshot)
+        })
+        .collect();
+    (StatusCode::OK, Json(engines)).into_response()
+}
+
+/// 处理最近引擎异常请求（最新的在前）
+pub async fn handle_admin_anomalies(
+    State(state): State<ApiState>,
+) -> Response {
+    let anomalies: Vec<EngineAnomaly> = state.search.anomaly_detector().recent();
+    (StatusCode::OK, Json(anomalies)).into_response()
+}
+
+/// 处理缓存统计请求
+pub async fn handle_admin_cache(
+    State(state): State<ApiState>,
+) -> Response {
+    match &state.cache {
+        Some(cache) => {
+            let stats = cache.manager().stats();
+            let response = AdminCacheStats {
+                hits: stats.hits,
+                misses: stats.misses,
+                hit_rate: stats.hit_rate(),
+                writes: stats.writes,
+                deletes: stats.deletes,
+                total_keys: stats.total_keys,
+                estimated_size_bytes: stats.estimated_size_bytes,
+                evictions: stats.evictions,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        None => {
+            let error = ApiErrorResponse {
+                code: "CACHE_UNAVAILABLE".to_string(),
+                message: "缓存不可用".to_string(),
+                details: None,
+            };
+            (StatusCode::SERVICE_UNAVAILABLE, Json(error)).into_response()
+        }
+    }
+}
+
+/// 处理堆内存报告请求（调试用）
+pub async fn handle_admin_heap(
+    State(state): State<ApiState>,
+    Query(query): Query<HeapQuery>,
+) -> Response {
+    let report = AdminHeapReport {
+        in_flight_requests: state.metrics.in_flight_requests(),
+        memory: collect_memory_metrics(&state).await,
+        detail: if query.detail { allocator_report() } else { None },
+    };
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// 处理配置验证请求
+///
+/// 请求体为完整的配置文本，`Content-Type` 含 `json` 时按 JSON 解析，否则按 TOML 解析；
+/// 总是返回 200 和验证结果，调用方根据 `is_valid` 判断
+pub async fn handle_admin_config_validate(
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+    let format = if is_json { ConfigFormat::Json } else { ConfigFormat::Toml };
+    (StatusCode::OK, Json(check_config_str(&body, format))).into_response()
+}
+
+/// 处理最近慢查询请求（最新的在前）
+pub async fn handle_admin_slow_queries(
+    State(state): State<ApiState>,
+) -> Response {
+    let slow_queries: Vec<SlowQuery> = state.search.slow_queries().recent();
+    (StatusCode::OK, Json(slow_queries)).into_response()
+}
+
+/// 处理慢查询排行请求（按查询聚合，次数多的在前）
+pub async fn handle_admin_top_queries(
+    State(state): State<ApiState>,
+    Query(query): Query<TopQueriesQuery>,
+) -> Response {
+    let top: Vec<TopQuery> = state.search.slow_queries().top(query.limit);
+    (StatusCode::OK, Json(top)).into_response()
+}
+
+/// 处理最近错误请求
+pub async fn handle_admin_errors(
+    State(state): State<ApiState>,
+) -> Response {
+    let errors: Vec<EngineErrorRecord> = state.search.recent_errors();
+    (StatusCode::OK, Json(errors)).into_response()
+}
+
+/// 处理引擎脚本重新加载请求
+///
+/// 重新扫描 `engines_dir`，返回加载、移除和失败的脚本
+pub async fn handle_admin_engines_reload(
+    State(state): State<ApiState>,
+) -> Response {
+    match state.search.reload_engines().await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "ENGINE_RELOAD_FAILED".to_string(),
+                message: "引擎脚本重新加载失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            (StatusCode::SERVICE_UNAVAILABLE, Json(error)).into_response()
+        }
+    }
+}
+
+/// 处理引擎自检请求：用探测查询直接调用引擎，报告结果数、耗时和解析警告
+pub async fn handle_admin_selftest(
+    State(state): State<ApiState>,
+    request: Option<Json<SelfTestRequest>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let queries: Vec<String> = if request.queries.is_empty() {
+        DEFAULT_SELFTEST_QUERIES.iter().map(|q| q.to_string()).collect()
+    } else {
+        request.queries.iter().map(|q| q.trim().to_string()).collect()
+    };
+    if queries.len() > MAX_SELFTEST_QUERIES
+        || queries.iter().any(|q| q.is_empty() || q.chars().count() > state.validation.max_query_length)
+    {
+        return invalid_selftest(format!("at most {} non-empty queries are allowed", MAX_SELFTEST_QUERIES));
+    }
+    if request.engines.len() > state.validation.max_engines {
+        return invalid_selftest(format!("at most {} engines are allowed", state.validation.max_engines));
+    }
+    for engine in &request.engines {
+        if let Err(e) = state.search.check_engine(engine) {
+            return invalid_selftest(e.to_string());
+        }
+    }
+
+    let report = state.search.self_test(&queries, &request.engines).await;
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+fn invalid_selftest(details: String) -> Response {
+    let error = ApiErrorResponse {
+        code: "INVALID_SELFTEST_REQUEST".to_string(),
+        message: "自检查询或引擎无效".to_string(),
+        details: Some(details),
+    };
+    (StatusCode::BAD_REQUEST, Json(error)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_admin_engine_state_from() {
+        let mut state = EngineState::new("bing".to_string());
+        state.temporarily_disabled = true;
+        state.disabled_until = Some(Instant::now() + Duration::from_secs(120));
+        state.consecutive_failures = 2;
+
+        let admin = AdminEngineState::from(&state);
+        assert_eq!(admin.name, "bing");
+        assert!(!admin.available);
+        assert!(admin.disabled_remaining_secs.unwrap() > 100);
+        assert_eq!(admin.consecutive_failures, 2);
+
+        let admin = AdminEngineState::from(&EngineState::new("baidu".to_string()));
+        assert!(admin.available);
+        assert!(admin.disabled_remaining_secs.is_none());
+        assert_eq!(admin.circuit_state, EngineCircuitState::Closed);
+        assert!(admin.circuit_half_open_in_secs.is_none());
+
+        let mut state = EngineState::new("yandex".to_string());
+        state.circuit_opened_at = Some(Instant::now());
+        let admin = AdminEngineState::from(&state);
+        assert!(!admin.available);
+        assert_eq!(admin.circuit_state, EngineCircuitState::Open);
+        assert!(admin.circuit_half_open_in_secs.unwrap() > 20);
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 保存的搜索（提醒）处理器
+//!
+//! 保存的搜索由后台调度器按 `schedule` 定期执行，新出现的结果可通过
+//! `/api/alerts/{id}/results` 查询，也会通知订阅了该保存的搜索的 webhook。仅在内网路由器注册。
+
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::{CacheError, SavedSearch};
+
+/// 最短执行间隔
+const MIN_ALERT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 默认执行间隔
+const DEFAULT_ALERT_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 默认返回的结果数
+const DEFAULT_RESULT_LIMIT: usize = 50;
+
+/// 执行间隔：秒数，或带单位的字符串（如 `30m`、`6h`、`1d`）
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AlertSchedule {
+    /// 秒数
+    Seconds(u64),
+    /// 带单位的字符串
+    Text(String),
+}
+
+impl AlertSchedule {
+    /// 解析为时间间隔
+    pub fn interval(&self) -> Option<Duration> {
+        let secs = match self {
+            AlertSchedule::Seconds(secs) => *secs,
+            AlertSchedule::Text(text) => {
+                let text = text.trim();
+                let split = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+                let (value, unit) = text.split_at(split);
+                let value: u64 = value.parse().ok()?;
+                let unit_secs = match unit.trim() {
+                    "" | "s" => 1,
+                    "m" => 60,
+                    "h" => 3600,
+                    "d" => 86400,
+                    _ => return None,
+                };
+                value.checked_mul(unit_secs)?
+            }
+        };
+        Some(Duration::from_secs(secs))
+    }
+}
+
+/// 新建保存的搜索请求
+#[derive(Debug, Deserialize)]
+pub struct CreateAlertRequest {
+    /// 查询
+    pub query: String,
+    /// 名称（默认为查询）
+    pub name: Option<String>,
+    /// 使用的引擎（为空表示默认引擎）
+    #[serde(default)]
+    pub engines: Vec<String>,
+    /// 执行间隔（默认 1 小时）
+    pub schedule: Option<AlertSchedule>,
+}
+
+/// 保存的搜索信息
+#[derive(Debug, Serialize)]
+pub struct AlertInfo {
+    /// ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 查询
+    pub query: String,
+    /// 使用的引擎
+    pub engines: Vec<String>,
+    /// 执行间隔（秒）
+    pub interval_secs: u64,
+    /// 是否启用
+    pub enabled: bool,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 上次执行时间
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// 下次执行时间
+    pub next_run_at: DateTime<Utc>,
+    /// 上次执行的错误
+    pub last_error: Option<String>,
+}
+
+impl From<SavedSearch> for AlertInfo {
+    fn from(search: SavedSearch) -> Self {
+        let next_run_at = search.next_run_at();
+        Self {
+            id: search.id,
+            name: search.name,
+            query: search.query,
+            engines: search.engines,
+            interval_secs: search.interval_secs,
+            enabled: search.enabled,
+            created_at: search.created_at,
+            last_run_at: search.last_run_at,
+            next_run_at,
+            last_error: search.last_error,
+        }
+    }
+}
+
+/// 结果查询参数
+#[derive(Debug, Deserialize)]
+pub struct AlertResultsQuery {
+    /// 只返回该时间之后发现的结果（RFC 3339）
+    pub since: Option<DateTime<Utc>>,
+    /// 返回条数
+    pub limit: Option<usize>,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+fn unavailable() -> Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "ALERT_STORE_UNAVAILABLE", "保存的搜索存储不可用", None)
+}
+
+fn storage_error(e: CacheError) -> Response {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, "ALERT_STORE_ERROR", "保存的搜索存储操作失败", Some(e.to_string()))
+}
+
+fn not_found() -> Response {
+    error_response(StatusCode::NOT_FOUND, "ALERT_NOT_FOUND", "保存的搜索不存在", None)
+}
+
+/// 处理保存的搜索列表请求
+pub async fn handle_alert_list(
+    State(state): State<ApiState>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    match alerts.store().list() {
+        Ok(searches) => {
+            let alerts: Vec<AlertInfo> = searches.into_iter().map(AlertInfo::from).collect();
+            (StatusCode::OK, Json(alerts)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理新建保存的搜索请求（首次执行由后台调度器在下一次检查时进行）
+pub async fn handle_alert_create(
+    State(state): State<ApiState>,
+    Json(request): Json<CreateAlertRequest>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    let query = request.query.trim();
+    if query.is_empty() || query.chars().count() > state.validation.max_query_length {
+        return error_response(StatusCode::BAD_REQUEST, "INVALID_ALERT_QUERY", "查询不能为空且不能超过长度限制", None);
+    }
+    if request.engines.len() > state.validation.max_engines {
+        return error_response(StatusCode::BAD_REQUEST, "INVALID_ALERT_ENGINES", "指定的引擎过多", None);
+    }
+    for engine in &request.engines {
+        if let Err(e) = state.search.check_engine(engine) {
+            return error_response(StatusCode::BAD_REQUEST, "INVALID_ALERT_ENGINES", "引擎不存在", Some(e.to_string()));
+        }
+    }
+    let interval = match &request.schedule {
+        Some(schedule) => match schedule.interval() {
+            Some(interval) if interval >= MIN_ALERT_INTERVAL => interval,
+            _ => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ALERT_SCHEDULE",
+                    "schedule 必须是不少于 60 秒的秒数或带单位的间隔（如 30m、6h、1d）",
+                    None,
+                );
+            }
+        },
+        None => DEFAULT_ALERT_INTERVAL,
+    };
+
+    let name = request.name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| query.to_string());
+    match alerts.store().create(name, query, request.engines, interval) {
+        Ok(search) => (StatusCode::CREATED, Json(AlertInfo::from(search))).into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理查询单个保存的搜索请求
+pub async fn handle_alert_get(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    match alerts.store().find(&id) {
+        Ok(Some(search)) => (StatusCode::OK, Json(AlertInfo::from(search))).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理删除保存的搜索请求
+pub async fn handle_alert_delete(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    match alerts.store().delete(&id) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({ "deleted": true }))).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理新结果查询请求
+pub async fn handle_alert_results(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<AlertResultsQuery>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    let search = match alerts.store().find(&id) {
+        Ok(Some(search)) => search,
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_RESULT_LIMIT);
+    match alerts.store().results(&id, query.since, limit) {
+        Ok(results) => {
+            let response = serde_json::json!({
+                "alert": AlertInfo::from(search),
+                "results": results,
+            });
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理立即执行请求，返回本次发现的新结果
+pub async fn handle_alert_run(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(alerts) = &state.alerts else {
+        return unavailable();
+    };
+    let mut search = match alerts.store().find(&id) {
+        Ok(Some(search)) => search,
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    };
+    match alerts.run(&mut search).await {
+        Ok(results) => {
+            let response = serde_json::json!({
+                "alert": AlertInfo::from(search),
+                "new_results": results,
+            });
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::test_support::{bing_transport, internal_router};
+    use crate::search::SearchConfig;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn json(response: Response) -> serde_json::Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_schedule_parsing() {
+        assert_eq!(AlertSchedule::Seconds(90).interval(), Some(Duration::from_secs(90)));
+        assert_eq!(AlertSchedule::Text("30m".to_string()).interval(), Some(Duration::from_secs(1800)));
+        assert_eq!(AlertSchedule::Text("1d".to_string()).interval(), Some(Duration::from_secs(86400)));
+        assert_eq!(AlertSchedule::Text("1w".to_string()).interval(), None);
+        assert_eq!(AlertSchedule::Text("h".to_string()).interval(), None);
+    }
+
+    #[tokio::test]
+    async fn test_alert_endpoints() {
+        let router = internal_router(SearchConfig::default(), bing_transport(&[("https://example.com/new", "New", "Fresh")]));
+
+        let post = |body: &'static str| {
+            Request::post("/api/alerts").header("content-type", "application/json").body(Body::from(body)).unwrap()
+        };
+        let response = router.clone().oneshot(post(r#"{"query": "rust", "engines": ["bing"], "schedule": "10s"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = router.clone().oneshot(post(r#"{"query": "rust", "engines": ["bing"], "schedule": "6h"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = json(response).await;
+        assert_eq!(created["interval_secs"], 21600);
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let response = router.clone()
+            .oneshot(Request::post(format!("/api/alerts/{}/run", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let run = json(response).await;
+        assert_eq!(run["new_results"].as_array().unwrap().len(), 1);
+        assert!(run["alert"]["last_run_at"].is_string());
+
+        let response = router.clone()
+            .oneshot(Request::get(format!("/api/alerts/{}/results", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["results"][0]["url"], "https://example.com/new");
+
+        let response = router.clone()
+            .oneshot(Request::delete(format!("/api/alerts/{}", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router
+            .oneshot(Request::get(format!("/api/alerts/{}", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 答案生成处理器
+//!
+//! `POST /api/answer` 先执行一次搜索，再把排名靠前的结果交给配置的 OpenAI 兼容接口，
+//! 返回带 `[n]` 引用的答案和被引用结果的链接。需在搜索配置的 `[synthesis]` 中显式启用。
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::middleware::{query_cost, EnginesUsed, QueryCost};
+use crate::api::types::ApiErrorResponse;
+use crate::api::validation::ValidatedJson;
+use crate::derive::SearchQuery;
+use crate::search::{AnswerSource, SearchRequest, TokenUsage};
+
+/// 答案生成请求
+#[derive(Debug, Deserialize)]
+pub struct AnswerRequest {
+    /// 问题（同时作为搜索查询）
+    pub query: String,
+    /// 指定使用的引擎（为空则使用所有引擎）
+    #[serde(default)]
+    pub engines: Vec<String>,
+    /// 交给模型的最多结果数（不超过配置的 `max_sources`）
+    #[serde(default)]
+    pub max_sources: Option<usize>,
+}
+
+/// 答案生成响应
+#[derive(Debug, Serialize)]
+pub struct AnswerResponse {
+    /// 问题
+    pub query: String,
+    /// 答案文本（含 `[n]` 引用标记）
+    pub answer: String,
+    /// 答案引用的来源
+    pub citations: Vec<AnswerSource>,
+    /// 交给模型的全部来源
+    pub sources: Vec<AnswerSource>,
+    /// 接口报告的模型名
+    pub model: Option<String>,
+    /// token 用量
+    pub usage: Option<TokenUsage>,
+    /// 使用的引擎
+    pub engines_used: Vec<String>,
+    /// 总耗时（毫秒，含搜索和生成）
+    pub query_time_ms: u64,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+/// 处理答案生成请求
+pub async fn handle_answer(
+    State(state): State<ApiState>,
+    ValidatedJson(params): ValidatedJson<AnswerRequest>,
+) -> Response {
+    let Some(synthesizer) = state.search.synthesizer().cloned() else {
+        return error_response(StatusCode::NOT_FOUND, "ANSWER_DISABLED", "答案生成未启用", None);
+    };
+    let start = std::time::Instant::now();
+    let max_sources = params.max_sources.unwrap_or(synthesizer.config().max_sources);
+    let request = SearchRequest {
+        query: SearchQuery {
+            query: params.query.clone(),
+            ..Default::default()
+        },
+        engines: params.engines.clone(),
+        ..Default::default()
+    };
+
+    let response = match state.search.search(&request).await {
+        Ok(response) => response,
+        Err(e) => {
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "SEARCH_ERROR", "搜索失败", Some(e.to_string()));
+        }
+    };
+    let engines_used = response.engines_used.clone();
+    let mut items: Vec<_> = response.results.into_iter().flat_map(|r| r.items).collect();
+    items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    if items.is_empty() {
+        return error_response(StatusCode::NOT_FOUND, "ANSWER_NO_RESULTS", "没有可用于生成答案的搜索结果", None);
+    }
+
+    let answer = match synthesizer.synthesize(&params.query, &items, Some(max_sources)).await {
+        Ok(answer) => answer,
+        Err(e) => {
+            return error_response(StatusCode::BAD_GATEWAY, "ANSWER_UPSTREAM_ERROR", "答案生成接口调用失败", Some(e.to_string()));
+        }
+    };
+    let body = AnswerResponse {
+        query: params.query,
+        citations: answer.cited_sources().cloned().collect(),
+        answer: answer.answer,
+        sources: answer.sources,
+        model: answer.model,
+        usage: answer.usage,
+        query_time_ms: start.elapsed().as_millis() as u64,
+        engines_used,
+    };
+    let cost = QueryCost(query_cost(body.engines_used.len(), 1));
+    let engines = EnginesUsed(body.engines_used.len());
+    let mut response = (StatusCode::OK, Json(body)).into_response();
+    response.extensions_mut().insert(engines);
+    response.extensions_mut().insert(cost);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::test_support::{bing_results, internal_router};
+    use crate::api::on::ApiInterface;
+    use crate::net::client::{MockResponse, MockTransport};
+    use crate::search::{SearchConfig, SearchInterface, SynthesisConfig};
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn answer_request(body: serde_json::Value) -> Request<Body> {
+        Request::post("/api/answer")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_answer_endpoint() {
+        let html = bing_results(&[
+            ("https://www.rust-lang.org/", "Rust Programming Language", "A language empowering everyone"),
+            ("https://doc.rust-lang.org/book/", "The Rust Book", "Learn Rust"),
+        ]);
+        let completion = r#"{"model": "test-model", "choices": [{"message": {"role": "assistant", "content": "Rust empowers everyone [1]."}}]}"#;
+        let transport = Arc::new(MockTransport::new()
+            .respond("www.bing.com/search", MockResponse::ok(html))
+            .respond("/v1/chat/completions", MockResponse::ok(completion)));
+        let config = SearchConfig {
+            synthesis: SynthesisConfig { enabled: true, fetch_pages: false, ..SynthesisConfig::default() },
+            ..SearchConfig::default()
+        };
+        let router = internal_router(config, transport);
+
+        let response = router.clone()
+            .oneshot(answer_request(serde_json::json!({"query": "rust", "engines": ["bing"]})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["answer"], "Rust empowers everyone [1].");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["sources"].as_array().unwrap().len(), 2);
+        assert_eq!(body["citations"].as_array().unwrap().len(), 1);
+        assert_eq!(body["citations"][0]["index"], 1);
+
+        let response = router
+            .oneshot(answer_request(serde_json::json!({"query": ""})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_answer_disabled_by_default() {
+        let search = SearchInterface::new(SearchConfig::default()).unwrap();
+        let router = ApiInterface::new(Arc::new(search), "0.1.0".to_string()).build_internal_router();
+        let response = router
+            .oneshot(answer_request(serde_json::json!({"query": "rust"})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! API 密钥管理处理器
+//!
+//! 提供 API 密钥的创建、列表、吊销和轮换，仅在内网路由器注册。
+//! 密钥明文只在创建和轮换的响应中返回一次。
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::{ApiKeyRateLimit, ApiKeyRecord, CacheError};
+
+/// 创建密钥请求
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    /// 名称
+    pub name: String,
+    /// 每秒请求数覆盖
+    pub requests_per_second: Option<u32>,
+    /// 突发请求容量覆盖（默认为每秒请求数的 2 倍）
+    pub burst_size: Option<u32>,
+}
+
+/// 密钥信息（不含哈希）
+#[derive(Debug, Serialize)]
+pub struct ApiKeyInfo {
+    /// 密钥 ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 明文前缀
+    pub key_prefix: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 吊销时间
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// 限流覆盖
+    pub rate_limit: Option<ApiKeyRateLimit>,
+    /// 累计请求数
+    pub total_requests: u64,
+    /// 最近使用时间
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKeyRecord> for ApiKeyInfo {
+    fn from(record: ApiKeyRecord) -> Self {
+        Self {
+            id: record.id,
+            name: record.name,
+            key_prefix: record.key_prefix,
+            created_at: record.created_at,
+            revoked_at: record.revoked_at,
+            rate_limit: record.rate_limit,
+            total_requests: record.total_requests,
+            last_used_at: record.last_used_at,
+        }
+    }
+}
+
+/// 新密钥响应
+#[derive(Debug, Serialize)]
+pub struct NewApiKeyResponse {
+    /// 密钥明文（仅返回一次）
+    pub key: String,
+    /// 密钥信息
+    pub info: ApiKeyInfo,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+fn unavailable() -> Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "API_KEY_STORE_UNAVAILABLE", "API 密钥存储不可用", None)
+}
+
+fn storage_error(e: CacheError) -> Response {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, "API_KEY_STORE_ERROR", "API 密钥存储操作失败", Some(e.to_string()))
+}
+
+fn not_found() -> Response {
+    error_response(StatusCode::NOT_FOUND, "API_KEY_NOT_FOUND", "API 密钥不存在或已吊销", None)
+}
+
+/// 处理密钥列表请求
+pub async fn handle_api_key_list(
+    State(state): State<ApiState>,
+) -> Response {
+    let Some(store) = &state.api_keys else {
+        return unavailable();
+    };
+    match store.list() {
+        Ok(records) => {
+            let keys: Vec<ApiKeyInfo> = records.into_iter().map(ApiKeyInfo::from).collect();
+            (StatusCode::OK, Json(keys)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理创建密钥请求
+pub async fn handle_api_key_create(
+    State(state): State<ApiState>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Response {
+    let Some(store) = &state.api_keys else {
+        return unavailable();
+    };
+    if request.name.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "INVALID_API_KEY_NAME", "密钥名称不能为空", None);
+    }
+
+    let rate_limit = request.requests_per_second.map(|rps| ApiKeyRateLimit {
+        requests_per_second: rps.max(1),
+        burst_size: request.burst_size.unwrap_or(rps.saturating_mul(2)).max(1),
+    });
+
+    match store.create(request.name.trim(), rate_limit) {
+        Ok((record, key)) => {
+            let response = NewApiKeyResponse { key, info: record.into() };
+            (StatusCode::CREATED, Json(response)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理吊销密钥请求
+pub async fn handle_api_key_revoke(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(store) = &state.api_keys else {
+        return unavailable();
+    };
+    match store.revoke(&id) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({ "revoked": true }))).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理轮换密钥请求
+pub async fn handle_api_key_rotate(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(store) = &state.api_keys else {
+        return unavailable();
+    };
+    match store.rotate(&id) {
+        Ok(Some((record, key))) => {
+            let response = NewApiKeyResponse { key, info: record.into() };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Ok(None) => not_found(),
+        Err(e) => storage_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::ApiInterface;
+    use crate::search::{SearchConfig, SearchInterface};
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_api_key_endpoints() {
+        let search = Arc::new(SearchInterface::new(SearchConfig::default()).unwrap());
+        let router = ApiInterface::new(search, "0.1.0".to_string()).build_internal_router();
+
+        let response = router.clone()
+            .oneshot(
+                Request::post("/api/admin/api-keys")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"name": "handler_test", "requests_per_second": 5}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert!(body["key"].as_str().unwrap().starts_with("sk_"));
+        assert!(body["info"].get("key_hash").is_none());
+        assert_eq!(body["info"]["rate_limit"]["burst_size"], 10);
+        let id = body["info"]["id"].as_str().unwrap().to_string();
+
+        let revoke = |id: String| {
+            Request::post(format!("/api/admin/api-keys/{}/revoke", id))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = router.clone().oneshot(revoke(id.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router.oneshot(revoke(id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS 榜单处理器
+//!
+//! 以 JSON 形式提供后台定期生成的 RSS 榜单（`GET /api/boards/{name}`），
+//! HTML 页面 `/boards/{name}` 见 [`super::static_files`]。
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+
+use crate::api::middleware::EntityTag;
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::CacheError;
+use crate::rss::RssRanking;
+
+/// 榜单列表响应
+#[derive(Debug, Serialize)]
+pub struct BoardListResponse {
+    /// 榜单名称
+    pub boards: Vec<String>,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+/// 读取榜单失败的原因
+pub(crate) enum BoardError {
+    /// 未配置榜单或缓存不可用
+    Disabled,
+    /// 榜单不存在
+    NotFound,
+    /// 读取缓存失败
+    Cache(CacheError),
+}
+
+impl IntoResponse for BoardError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Disabled => error_response(StatusCode::NOT_FOUND, "BOARDS_DISABLED", "RSS 榜单未启用", None),
+            Self::NotFound => error_response(StatusCode::NOT_FOUND, "BOARD_NOT_FOUND", "榜单不存在", None),
+            Self::Cache(e) => error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "BOARD_ERROR",
+                "榜单生成失败",
+                Some(e.to_string()),
+            ),
+        }
+    }
+}
+
+/// 读取榜单
+pub(crate) fn load_board(state: &ApiState, name: &str) -> Result<Arc<RssRanking>, BoardError> {
+    let boards = state.boards.as_ref().ok_or(BoardError::Disabled)?;
+    boards.get(name).map_err(BoardError::Cache)?.ok_or(BoardError::NotFound)
+}
+
+/// 榜单的实体标签（榜单名称和生成时间不变时内容不变）
+pub(crate) fn board_etag(board: &RssRanking, variant: &str) -> EntityTag {
+    EntityTag::from_bytes(format!("{}\n{}\n{}", variant, board.name, board.timestamp).as_bytes())
+}
+
+/// 处理榜单列表请求
+pub async fn handle_board_list(State(state): State<ApiState>) -> Response {
+    let boards = state.boards.as_ref().map(|boards| boards.names()).unwrap_or_default();
+    (StatusCode::OK, Json(BoardListResponse { boards })).into_response()
+}
+
+/// 处理单个榜单请求
+pub async fn handle_board(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Response {
+    match load_board(&state, &name) {
+        Ok(board) => {
+            let etag = board_etag(&board, "json");
+            let mut response = (StatusCode::OK, Json(board.as_ref())).into_response();
+            response.extensions_mut().insert(etag);
+            response
+        }
+        Err(e) => e.into_response(),
+    }
+}
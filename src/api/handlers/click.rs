@@ -0,0 +1,144 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 点击追踪处理器
+//!
+//! 处理 `GET /r` 跳转请求：校验签名后记录被点击结果的引擎和位置，
+//! 再重定向到目标 URL。点击数据只保存在本地，不会发送给上游引擎。
+
+use axum::{
+    extract::{State, Query},
+    response::{IntoResponse, Redirect, Response},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::HistoryScope;
+use crate::utils::{hmac_sha256_hex, verify_hmac_sha256_hex};
+
+/// 点击跳转参数
+#[derive(Debug, Deserialize)]
+pub struct ClickQuery {
+    /// 目标 URL
+    pub u: String,
+    /// 查询 ID
+    pub qid: String,
+    /// 结果位置（从 0 开始）
+    pub pos: usize,
+    /// 来源引擎
+    pub e: String,
+    /// 签名
+    pub sig: String,
+}
+
+/// 计算点击参数签名
+pub fn sign_click(secret: &str, url: &str, qid: &str, position: usize, engine: &str) -> String {
+    hmac_sha256_hex(secret.as_bytes(), click_message(url, qid, position, engine).as_bytes())
+}
+
+/// 校验点击参数签名（常量时间比较）
+pub fn verify_click(secret: &str, query: &ClickQuery) -> bool {
+    let message = click_message(&query.u, &query.qid, query.pos, &query.e);
+    verify_hmac_sha256_hex(secret.as_bytes(), message.as_bytes(), &query.sig)
+}
+
+fn click_message(url: &str, qid: &str, position: usize, engine: &str) -> String {
+    format!("{}\n{}\n{}\n{}", url, qid, position, engine)
+}
+
+/// 生成点击跳转链接
+pub fn build_click_url(secret: &str, url: &str, qid: &str, position: usize, engine: &str) -> String {
+    format!(
+        "/r?u={}&qid={}&pos={}&e={}&sig={}",
+        urlencoding::encode(url),
+        urlencoding::encode(qid),
+        position,
+        urlencoding::encode(engine),
+        sign_click(secret, url, qid, position, engine),
+    )
+}
+
+/// 处理点击跳转请求
+pub async fn handle_click_redirect(
+    State(state): State<ApiState>,
+    scope: Option<Extension<HistoryScope>>,
+    Query(params): Query<ClickQuery>,
+) -> Response {
+    let clicks = match &state.clicks {
+        Some(clicks) if clicks.is_enabled() => clicks,
+        _ => {
+            let error = ApiErrorResponse {
+                code: "CLICK_TRACKING_DISABLED".to_string(),
+                message: "点击追踪未启用".to_string(),
+                details: None,
+            };
+            return (StatusCode::NOT_FOUND, Json(error)).into_response();
+        }
+    };
+
+    // 签名校验防止被用作开放重定向
+    let is_http = crate::url_utils::is_http_url(&params.u);
+    if !is_http || !verify_click(&clicks.config().secret, &params) {
+        let error = ApiErrorResponse {
+            code: "INVALID_CLICK_LINK".to_string(),
+            message: "点击链接无效".to_string(),
+            details: None,
+        };
+        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+    }
+
+    if let Err(e) = clicks.record_click(&params.e, params.pos) {
+        tracing::warn!("Failed to record click: {}", e);
+    }
+
+    // 同时记录到搜索历史，用于为常点击的域名加分
+    if let (Some(history), Some(Extension(scope))) = (&state.history, scope)
+        && let Err(e) = history.record_click(&scope, &params.u)
+    {
+        tracing::warn!("Failed to record history click: {}", e);
+    }
+
+    Redirect::to(&params.u).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_signature() {
+        let sig = sign_click("secret", "https://example.com/", "q1", 3, "bing");
+        let mut query = ClickQuery {
+            u: "https://example.com/".to_string(),
+            qid: "q1".to_string(),
+            pos: 3,
+            e: "bing".to_string(),
+            sig,
+        };
+        assert!(verify_click("secret", &query));
+        assert!(!verify_click("other", &query));
+
+        query.u = "https://evil.example.com/".to_string();
+        assert!(!verify_click("secret", &query));
+    }
+
+    #[test]
+    fn test_build_click_url() {
+        let url = build_click_url("secret", "https://example.com/a?b=c", "q1", 0, "bing");
+        assert!(url.starts_with("/r?u=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc&qid=q1&pos=0&e=bing&sig="));
+    }
+}
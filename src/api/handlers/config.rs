@@ -23,7 +23,25 @@ use axum::{
 };
 use serde_json::json;
 
+use crate::api::middleware::{MagicLinkError, MagicLinkOptions, MagicLinkPermission};
 use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::HistoryScope;
+
+/// 构造 400 错误响应
+fn bad_request(code: &str, message: &str) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details: None,
+    };
+    (StatusCode::BAD_REQUEST, Json(error)).into_response()
+}
+
+/// 读取请求体中的 token 参数
+fn token_param(params: &serde_json::Value) -> Option<&str> {
+    params.get("token").and_then(|v| v.as_str())
+}
 
 /// 处理魔法链接生成请求
 pub async fn handle_magic_link_generate(
@@ -35,11 +53,103 @@ pub async fn handle_magic_link_generate(
         .unwrap_or("general")
         .to_string();
     
-    let token = state.magic_link.generate_token(purpose);
+    // 可选：绑定搜索历史作用域，实现多用户历史隔离
+    let history_scope = match params.get("history_scope").and_then(|v| v.as_str()) {
+        Some(name) => match HistoryScope::new(name) {
+            Some(scope) => Some(scope),
+            None => return bad_request("INVALID_HISTORY_SCOPE", "历史作用域只能包含字母、数字、- 和 _"),
+        },
+        None => None,
+    };
+
+    // 可选：权限范围（search / rss-admin / metrics-read），为空表示不限制
+    let mut permissions = Vec::new();
+    if let Some(scopes) = params.get("scopes").and_then(|v| v.as_array()) {
+        for scope in scopes {
+            match scope.as_str().and_then(MagicLinkPermission::parse) {
+                Some(permission) => permissions.push(permission),
+                None => return bad_request("INVALID_MAGIC_LINK_SCOPE", "权限范围只能是 search、rss-admin 或 metrics-read"),
+            }
+        }
+    }
+
+    let daily_quota = params.get("daily_quota").and_then(|v| v.as_u64());
+    let daily_budget = params.get("daily_budget").and_then(|v| v.as_u64());
+    let expires_in = params.get("expires_in")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(state.magic_link.config().expiration);
+    // 设置了权限范围或配额的令牌默认可重复使用
+    let reusable = params.get("reusable")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(!permissions.is_empty() || daily_quota.is_some() || daily_budget.is_some());
+
+    let token = state.magic_link.generate_token_with_options(MagicLinkOptions {
+        purpose,
+        history_scope,
+        permissions,
+        daily_quota,
+        daily_budget,
+        expires_in: Some(expires_in),
+        reusable,
+    });
     
     (StatusCode::OK, Json(json!({
         "token": token,
-        "expires_in": 300,
+        "expires_in": expires_in,
         "url": format!("/api/search?magic_token={}", token)
     }))).into_response()
 }
+
+/// 处理魔法链接列表请求
+pub async fn handle_magic_link_list(
+    State(state): State<ApiState>,
+) -> Response {
+    (StatusCode::OK, Json(state.magic_link.list_tokens())).into_response()
+}
+
+/// 处理魔法链接吊销请求
+pub async fn handle_magic_link_revoke(
+    State(state): State<ApiState>,
+    Json(params): Json<serde_json::Value>,
+) -> Response {
+    let Some(token) = token_param(&params) else {
+        return bad_request("MISSING_TOKEN", "缺少 token 参数");
+    };
+
+    if state.magic_link.revoke_token(token) {
+        (StatusCode::OK, Json(json!({ "revoked": true }))).into_response()
+    } else {
+        let error = ApiErrorResponse {
+            code: "MAGIC_LINK_NOT_FOUND".to_string(),
+            message: "魔法链接不存在".to_string(),
+            details: None,
+        };
+        (StatusCode::NOT_FOUND, Json(error)).into_response()
+    }
+}
+
+/// 处理魔法链接续期请求
+pub async fn handle_magic_link_renew(
+    State(state): State<ApiState>,
+    Json(params): Json<serde_json::Value>,
+) -> Response {
+    let Some(token) = token_param(&params) else {
+        return bad_request("MISSING_TOKEN", "缺少 token 参数");
+    };
+    let expires_in = params.get("expires_in")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(state.magic_link.config().expiration);
+
+    match state.magic_link.renew_token(token, expires_in) {
+        Ok(()) => (StatusCode::OK, Json(json!({ "expires_in": expires_in }))).into_response(),
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: e.code().to_string(),
+                message: format!("无法续期魔法链接: {}", e),
+                details: None,
+            };
+            let status = if e == MagicLinkError::Invalid { StatusCode::NOT_FOUND } else { StatusCode::BAD_REQUEST };
+            (status, Json(error)).into_response()
+        }
+    }
+}
@@ -14,7 +14,11 @@
 
 //! 健康检查处理器
 //!
-//! 处理健康检查相关的 API 请求
+//! 处理健康检查相关的 API 请求：`/health/live` 只表示进程存活，
+//! `/health/ready` 逐项检查缓存、引擎、RSS 存储和后台调度器，任一项不可用时返回 503
+
+use std::collections::BTreeMap;
+use std::time::Instant;
 
 use axum::{
     extract::State,
@@ -24,7 +28,10 @@ use axum::{
 };
 
 use crate::api::on::ApiState;
-use crate::api::types::ApiHealthResponse;
+use crate::api::types::{ApiHealthResponse, ApiLivenessResponse, ApiReadinessResponse, DependencyHealth};
+
+/// 缓存探测使用的键（不存在也无妨，只验证存储可读）
+const CACHE_PROBE_KEY: &str = "health:probe";
 
 /// 处理健康检查请求
 pub async fn handle_health(
@@ -41,3 +48,150 @@ pub async fn handle_health(
     
     (StatusCode::OK, Json(health)).into_response()
 }
+
+/// 处理存活检查请求（进程能响应即为存活）
+pub async fn handle_liveness(
+    State(state): State<ApiState>,
+) -> Response {
+    let live = ApiLivenessResponse {
+        status: "alive".to_string(),
+        version: state.version.clone(),
+        uptime_seconds: state.metrics.get_realtime_metrics().await.uptime_seconds,
+    };
+
+    (StatusCode::OK, Json(live)).into_response()
+}
+
+/// 处理就绪检查请求
+pub async fn handle_readiness(
+    State(state): State<ApiState>,
+) -> Response {
+    let mut checks = BTreeMap::new();
+    checks.insert("cache".to_string(), check_cache(&state));
+    checks.insert("engines".to_string(), check_engines(&state).await);
+    checks.insert("rss".to_string(), check_rss(&state));
+    checks.insert("scheduler".to_string(), check_scheduler(&state));
+
+    let ready = checks.values().all(DependencyHealth::is_up);
+    let readiness = ApiReadinessResponse {
+        status: if ready { "ready" } else { "not_ready" }.to_string(),
+        version: state.version.clone(),
+        checks,
+    };
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status, Json(readiness)).into_response()
+}
+
+/// 缓存已打开且可读
+fn check_cache(state: &ApiState) -> DependencyHealth {
+    let start = Instant::now();
+    let Some(cache) = &state.cache else {
+        return DependencyHealth::down(start.elapsed(), "缓存未打开");
+    };
+    match cache.manager().get(CACHE_PROBE_KEY) {
+        Ok(_) => DependencyHealth::up(start.elapsed(), "缓存可读"),
+        Err(e) => DependencyHealth::down(start.elapsed(), format!("缓存读取失败: {}", e)),
+    }
+}
+
+/// 至少有 K 个引擎未被临时禁用
+async fn check_engines(state: &ApiState) -> DependencyHealth {
+    let start = Instant::now();
+    let engines = state.search.list_engines();
+    let snapshots = state.search.engine_state_snapshots().await;
+    let available = engines
+        .iter()
+        .filter(|name| {
+            snapshots
+                .iter()
+                .find(|snapshot| &snapshot.name == *name)
+                .is_none_or(|snapshot| snapshot.is_available())
+        })
+        .count();
+
+    let required = state.health.min_available_engines;
+    let details = format!("{}/{} 个引擎可用（至少需要 {} 个）", available, engines.len(), required);
+    if available >= required {
+        DependencyHealth::up(start.elapsed(), details)
+    } else {
+        DependencyHealth::down(start.elapsed(), details)
+    }
+}
+
+/// RSS 存储可读
+fn check_rss(state: &ApiState) -> DependencyHealth {
+    let start = Instant::now();
+    let Some(rss) = &state.rss else {
+        return DependencyHealth::down(start.elapsed(), "RSS 存储不可用（缓存未打开）");
+    };
+    match rss.list_persistent_feeds() {
+        Ok(feeds) => DependencyHealth::up(start.elapsed(), format!("{} 个持久化 feed", feeds.len())),
+        Err(e) => DependencyHealth::down(start.elapsed(), format!("RSS 存储读取失败: {}", e)),
+    }
+}
+
+/// 后台调度器已启动且仍在运行
+fn check_scheduler(state: &ApiState) -> DependencyHealth {
+    let start = Instant::now();
+    if state.alerts.is_none() {
+        return DependencyHealth::down(start.elapsed(), "调度器不可用（缓存未打开）");
+    }
+    if state.background.is_running() {
+        DependencyHealth::up(start.elapsed(), "调度器运行中")
+    } else {
+        DependencyHealth::down(start.elapsed(), "调度器未运行")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::ApiInterface;
+    use crate::api::types::HealthConfig;
+    use crate::search::{SearchConfig, SearchInterface};
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    async fn get(api: &ApiInterface, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = api.build_internal_router()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_liveness_and_readiness() {
+        let search = Arc::new(SearchInterface::new(SearchConfig::default()).unwrap());
+        let api = ApiInterface::new(search.clone(), "0.1.0".to_string());
+
+        let (status, live) = get(&api, "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(live["status"], "alive");
+
+        // 后台调度器尚未启动
+        let (status, ready) = get(&api, "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(ready["status"], "not_ready");
+        assert_eq!(ready["checks"]["scheduler"]["status"], "down");
+        assert_eq!(ready["checks"]["cache"]["status"], "up");
+        assert!(ready["checks"]["cache"]["latency_ms"].is_number());
+
+        api.spawn_background_tasks();
+        let (status, ready) = get(&api, "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ready["status"], "ready");
+
+        let api = ApiInterface::new(search, "0.1.0".to_string())
+            .with_health_config(HealthConfig { min_available_engines: usize::MAX });
+        api.spawn_background_tasks();
+        let (status, ready) = get(&api, "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(ready["checks"]["engines"]["status"], "down");
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 搜索历史处理器
+//!
+//! 处理搜索历史的查询和清除请求。历史作用域由请求扩展提供：
+//! 内网路由固定为本地作用域，外网路由由魔法链接令牌绑定。
+
+use std::sync::Arc;
+use axum::{
+    extract::{State, Query},
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::{HistoryCache, HistoryScope, SearchHistoryEntry};
+
+/// 默认返回的历史条目数
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// 历史查询参数
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// 返回条目数上限
+    pub limit: Option<usize>,
+}
+
+/// 历史列表响应
+#[derive(Debug, Serialize)]
+pub struct HistoryListResponse {
+    /// 历史条目（最新的在前）
+    pub entries: Vec<SearchHistoryEntry>,
+    /// 条目数
+    pub total: usize,
+}
+
+/// 历史清除响应
+#[derive(Debug, Serialize)]
+pub struct HistoryClearResponse {
+    /// 是否成功
+    pub success: bool,
+    /// 清除的条目数
+    pub cleared_entries: usize,
+}
+
+/// 获取可用的历史缓存和作用域，不可用时返回错误
+fn resolve_history(
+    state: &ApiState,
+    scope: Option<Extension<HistoryScope>>,
+) -> Result<(Arc<HistoryCache>, HistoryScope), (StatusCode, ApiErrorResponse)> {
+    let history = match &state.history {
+        Some(history) if history.is_enabled() => Arc::clone(history),
+        _ => {
+            let error = ApiErrorResponse {
+                code: "HISTORY_DISABLED".to_string(),
+                message: "搜索历史未启用".to_string(),
+                details: None,
+            };
+            return Err((StatusCode::FORBIDDEN, error));
+        }
+    };
+
+    match scope {
+        Some(Extension(scope)) => Ok((history, scope)),
+        None => {
+            let error = ApiErrorResponse {
+                code: "HISTORY_SCOPE_REQUIRED".to_string(),
+                message: "需要绑定历史作用域的魔法链接".to_string(),
+                details: None,
+            };
+            Err((StatusCode::FORBIDDEN, error))
+        }
+    }
+}
+
+/// 处理获取搜索历史请求
+pub async fn handle_history_list(
+    State(state): State<ApiState>,
+    scope: Option<Extension<HistoryScope>>,
+    Query(params): Query<HistoryQuery>,
+) -> Response {
+    let (history, scope) = match resolve_history(&state, scope) {
+        Ok(resolved) => resolved,
+        Err((status, error)) => return (status, Json(error)).into_response(),
+    };
+
+    let limit = params.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    match history.list(&scope, limit) {
+        Ok(entries) => {
+            let total = entries.len();
+            (StatusCode::OK, Json(HistoryListResponse { entries, total })).into_response()
+        }
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "HISTORY_ERROR".to_string(),
+                message: "读取搜索历史失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
+
+/// 处理清除搜索历史请求
+pub async fn handle_history_clear(
+    State(state): State<ApiState>,
+    scope: Option<Extension<HistoryScope>>,
+) -> Response {
+    let (history, scope) = match resolve_history(&state, scope) {
+        Ok(resolved) => resolved,
+        Err((status, error)) => return (status, Json(error)).into_response(),
+    };
+
+    match history.clear(&scope) {
+        Ok(cleared_entries) => {
+            let response = HistoryClearResponse {
+                success: true,
+                cleared_entries,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "HISTORY_ERROR".to_string(),
+                message: "清除搜索历史失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
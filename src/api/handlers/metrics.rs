@@ -24,8 +24,10 @@ use axum::{
 };
 use serde_json::json;
 
+use crate::api::i18n::Locale;
+use crate::api::memory::collect_memory_metrics;
 use crate::api::on::ApiState;
-use crate::api::types::{ApiStatsResponse, ApiEngineInfo};
+use crate::api::types::{ApiStatsResponse, ApiEngineInfo, ApiInstanceInfo};
 
 /// 处理统计信息请求
 pub async fn handle_stats(
@@ -70,10 +72,29 @@ pub async fn handle_version(
     (StatusCode::OK, Json(version_info)).into_response()
 }
 
+/// 处理实例信息请求（类似 SearXNG 的 `/config`，供公共实例目录收录）
+pub async fn handle_instance(
+    State(state): State<ApiState>,
+) -> Response {
+    let info = ApiInstanceInfo {
+        name: state.ui.instance_name.clone(),
+        version: state.version.clone(),
+        description: state.instance.description.clone(),
+        contact: state.instance.contact.clone(),
+        engine_count: state.search.list_engines().len(),
+        categories: state.search.engine_categories().await,
+        locales: Locale::ALL.to_vec(),
+    };
+
+    (StatusCode::OK, Json(info)).into_response()
+}
+
 /// 处理指标请求（Prometheus格式）
 pub async fn handle_metrics(
     State(state): State<ApiState>,
 ) -> Response {
+    // 内存指标只在采集时更新，渲染前先刷新
+    collect_memory_metrics(&state).await;
     if let Some(metrics) = state.metrics.get_prometheus_metrics() {
         (StatusCode::OK, metrics).into_response()
     } else {
@@ -88,6 +109,7 @@ pub async fn handle_metrics(
 pub async fn handle_realtime_metrics(
     State(state): State<ApiState>,
 ) -> Response {
-    let metrics = state.metrics.get_realtime_metrics().await;
+    let mut metrics = state.metrics.get_realtime_metrics().await;
+    metrics.memory = collect_memory_metrics(&state).await;
     (StatusCode::OK, Json(metrics)).into_response()
 }
@@ -22,14 +22,30 @@ pub mod config;
 pub mod metrics;
 pub mod rss;
 pub mod cache;
+pub mod history;
+pub mod click;
+pub mod admin;
+pub mod apikeys;
+pub mod news;
+pub mod tools;
+pub mod answer;
+pub mod webhooks;
+pub mod alerts;
+pub mod usage;
+pub mod boards;
+#[cfg(feature = "preview")]
+pub mod preview;
 pub mod static_files;
 
 // Re-export handlers for convenient use
 pub use search::{handle_search, handle_search_post};
-pub use health::handle_health;
-pub use config::handle_magic_link_generate;
+pub use health::{handle_health, handle_liveness, handle_readiness};
+pub use config::{
+    handle_magic_link_generate, handle_magic_link_list,
+    handle_magic_link_revoke, handle_magic_link_renew
+};
 pub use metrics::{
-    handle_stats, handle_engines_list, handle_version,
+    handle_stats, handle_engines_list, handle_version, handle_instance,
     handle_metrics, handle_realtime_metrics
 };
-pub use static_files::{handle_index, handle_favicon};
+pub use static_files::{handle_index, handle_search_page, handle_board_page, handle_robots_txt, handle_favicon, handle_admin_dashboard, handle_admin_js};
@@ -0,0 +1,199 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 新闻聚合处理器
+//!
+//! 只查询 `news` 分类的引擎，并合并已抓取的 RSS 条目，
+//! 再把不同来源对同一事件的报道聚为一簇返回。
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::api::validation::ValidatedQuery;
+use crate::derive::SearchQuery;
+use crate::rss::parse_feed_date;
+use crate::search::news::{cluster_news, NewsArticle, NewsCluster, DEFAULT_CLUSTER_THRESHOLD};
+use crate::search::{SearchRequest, SOURCE_ENGINE_KEY};
+
+/// 默认返回的新闻簇数
+const DEFAULT_NEWS_LIMIT: usize = 20;
+
+/// 参与聚类的 RSS 条目上限
+const MAX_RSS_ITEMS: usize = 200;
+
+fn default_include_rss() -> bool {
+    true
+}
+
+/// 新闻查询参数
+#[derive(Debug, Deserialize)]
+pub struct NewsQuery {
+    /// 查询关键词
+    pub q: String,
+    /// 返回的新闻簇数上限
+    pub limit: Option<usize>,
+    /// 聚类相似度阈值（0-1，越大越严格）
+    pub threshold: Option<f64>,
+    /// 是否合并已抓取的 RSS 条目
+    #[serde(default = "default_include_rss")]
+    pub include_rss: bool,
+}
+
+/// 新闻聚合响应
+#[derive(Debug, Serialize)]
+pub struct NewsResponse {
+    /// 查询
+    pub query: String,
+    /// 新闻簇（报道多的在前）
+    pub clusters: Vec<NewsCluster>,
+    /// 参与聚类的报道数
+    pub total_articles: usize,
+    /// 使用的引擎
+    pub engines_used: Vec<String>,
+    /// 是否包含 RSS 条目
+    pub rss_included: bool,
+}
+
+/// 处理新闻聚合请求
+pub async fn handle_news(
+    State(state): State<ApiState>,
+    ValidatedQuery(params): ValidatedQuery<NewsQuery>,
+) -> Response {
+    let engines = state.search.engines_in_category("news").await;
+    let mut articles = Vec::new();
+    let mut engines_used = Vec::new();
+
+    if !engines.is_empty() {
+        let request = SearchRequest {
+            query: SearchQuery {
+                query: params.q.clone(),
+                ..Default::default()
+            },
+            engines,
+            ..Default::default()
+        };
+        match state.search.search(&request).await {
+            Ok(response) => {
+                engines_used = response.engines_used;
+                for result in response.results {
+                    for item in result.items {
+                        let source = item.metadata.get(SOURCE_ENGINE_KEY)
+                            .cloned()
+                            .unwrap_or_else(|| result.engine_name.clone());
+                        articles.push(NewsArticle {
+                            title: item.title,
+                            url: item.url,
+                            summary: item.content,
+                            source,
+                            published: item.published_date,
+                        });
+                    }
+                }
+            }
+            Err(e) => {
+                let error = ApiErrorResponse {
+                    code: "SEARCH_ERROR".to_string(),
+                    message: "新闻搜索失败".to_string(),
+                    details: Some(e.to_string()),
+                };
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+            }
+        }
+    }
+
+    let rss = state.rss.as_ref().filter(|_| params.include_rss);
+    if let Some(rss) = rss {
+        let keywords: Vec<String> = params.q.split_whitespace().map(str::to_string).collect();
+        match rss.search_fulltext(&keywords, false, Some(MAX_RSS_ITEMS)) {
+            Ok(items) => {
+                for (feed_url, item) in items {
+                    let source = rss.get_meta(&feed_url).ok().flatten()
+                        .and_then(|meta| meta.name)
+                        .unwrap_or(feed_url);
+                    articles.push(NewsArticle {
+                        title: item.title,
+                        url: item.link,
+                        summary: item.description.unwrap_or_default(),
+                        source,
+                        published: item.pub_date.as_deref().and_then(parse_feed_date),
+                    });
+                }
+            }
+            Err(e) => tracing::warn!("Failed to search stored RSS items: {}", e),
+        }
+    }
+
+    let total_articles = articles.len();
+    let threshold = params.threshold.unwrap_or(DEFAULT_CLUSTER_THRESHOLD);
+    let mut clusters = cluster_news(articles, threshold);
+    clusters.truncate(params.limit.unwrap_or(DEFAULT_NEWS_LIMIT));
+
+    let response = NewsResponse {
+        query: params.q,
+        clusters,
+        total_articles,
+        engines_used,
+        rss_included: rss.is_some(),
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::on::test_support::internal_router;
+    use crate::net::client::{MockResponse, MockTransport};
+    use crate::search::SearchConfig;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_news_endpoint_clusters_news_engine_results() {
+        let html = r#"<ul class="news-list">
+            <li id="sogou_vr_0"><h3><a href="https://mp.weixin.qq.com/s/a">量子计算新突破：中国团队实现 504 比特超导芯片</a></h3><div class="s-p"><span class="all-time-y2">科技日报</span><span class="s2">1722470400</span></div></li>
+            <li id="sogou_vr_1"><h3><a href="https://mp.weixin.qq.com/s/b">量子计算新突破！中国团队实现504比特超导芯片</a></h3><div class="s-p"><span class="all-time-y2">人民网</span><span class="s2">1722384000</span></div></li>
+            <li id="sogou_vr_2"><h3><a href="https://mp.weixin.qq.com/s/c">新能源汽车 7 月销量创新高</a></h3></li>
+        </ul>"#;
+        let transport = Arc::new(MockTransport::new().respond("weixin.sogou.com/weixin", MockResponse::ok(html)));
+        let router = internal_router(SearchConfig::default(), transport);
+
+        let response = router.clone()
+            .oneshot(Request::get("/api/news?q=%E9%87%8F%E5%AD%90%E8%AE%A1%E7%AE%97&include_rss=false").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["engines_used"], serde_json::json!(["sogou_wechat"]));
+        assert_eq!(body["total_articles"], 3);
+        let clusters = body["clusters"].as_array().unwrap();
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0]["articles"].as_array().unwrap().len(), 2);
+        assert_eq!(clusters[0]["earliest"], "2024-07-31T00:00:00Z");
+
+        let response = router
+            .oneshot(Request::get("/api/news?q=&include_rss=false").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果预览处理器（需启用 `preview` 特性）
+//!
+//! 处理 `GET /api/preview` 请求：校验签名后返回目标页面的 JPEG 缩略图。
+//! 签名只由搜索结果生成，防止接口被用来让服务器访问任意地址。
+
+use axum::{
+    extract::{State, Query},
+    response::{IntoResponse, Response},
+    http::{header, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::utils::{hmac_sha256_hex, verify_hmac_sha256_hex};
+use crate::api::middleware::EntityTag;
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+
+/// 预览参数
+#[derive(Debug, Deserialize)]
+pub struct PreviewQuery {
+    /// 目标 URL
+    pub url: String,
+    /// 签名
+    pub sig: String,
+}
+
+/// 计算预览链接签名
+pub fn sign_preview(secret: &str, url: &str) -> String {
+    hmac_sha256_hex(secret.as_bytes(), format!("preview\n{}", url).as_bytes())
+}
+
+/// 校验预览链接签名（常量时间比较）
+pub fn verify_preview(secret: &str, query: &PreviewQuery) -> bool {
+    verify_hmac_sha256_hex(secret.as_bytes(), format!("preview\n{}", query.url).as_bytes(), &query.sig)
+}
+
+/// 生成预览链接
+pub fn build_preview_url(secret: &str, url: &str) -> String {
+    format!(
+        "/api/preview?url={}&sig={}",
+        urlencoding::encode(url),
+        sign_preview(secret, url),
+    )
+}
+
+/// 处理结果预览请求
+pub async fn handle_preview(
+    State(state): State<ApiState>,
+    Query(params): Query<PreviewQuery>,
+) -> Response {
+    let preview = match &state.preview {
+        Some(preview) if preview.is_enabled() => preview,
+        _ => {
+            let error = ApiErrorResponse {
+                code: "PREVIEW_DISABLED".to_string(),
+                message: "结果预览未启用".to_string(),
+                details: None,
+            };
+            return (StatusCode::NOT_FOUND, Json(error)).into_response();
+        }
+    };
+
+    let is_http = crate::url_utils::is_http_url(&params.url);
+    if !is_http || !verify_preview(&preview.config().secret, &params) {
+        let error = ApiErrorResponse {
+            code: "INVALID_PREVIEW_LINK".to_string(),
+            message: "预览链接无效".to_string(),
+            details: None,
+        };
+        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+    }
+
+    match preview.capture(&params.url).await {
+        Ok(image) => {
+            // 截图按 URL 长期缓存，浏览器端同样可以长期缓存
+            let cache_control = format!("public, max-age={}, immutable", preview.config().cache_ttl_secs);
+            let mut response = (
+                [
+                    (header::CONTENT_TYPE, "image/jpeg".to_string()),
+                    (header::CACHE_CONTROL, cache_control),
+                ],
+                image,
+            )
+                .into_response();
+            response.extensions_mut().insert(EntityTag::from_bytes(params.url.as_bytes()));
+            response
+        }
+        Err(e) => {
+            tracing::warn!("Failed to capture preview for {}: {}", params.url, e);
+            let error = ApiErrorResponse {
+                code: "PREVIEW_FAILED".to_string(),
+                message: "页面截图失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            (StatusCode::BAD_GATEWAY, Json(error)).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_signature() {
+        let url = "https://example.com/page?a=1";
+        let link = build_preview_url("secret", url);
+        assert!(link.starts_with("/api/preview?url=https%3A%2F%2Fexample.com%2Fpage%3Fa%3D1&sig="));
+
+        let query = PreviewQuery { url: url.to_string(), sig: sign_preview("secret", url) };
+        assert!(verify_preview("secret", &query));
+        assert!(!verify_preview("other", &query));
+
+        let tampered = PreviewQuery { url: "https://evil.example/".to_string(), sig: query.sig.clone() };
+        assert!(!verify_preview("secret", &tampered));
+    }
+}
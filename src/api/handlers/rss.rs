@@ -21,9 +21,14 @@ use axum::{
     response::{IntoResponse, Response},
     http::StatusCode,
 };
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use crate::api::on::ApiState;
 use crate::api::types::ApiErrorResponse;
+use crate::api::validation::{ValidatedJson, ValidatedQuery};
+use crate::derive::rss::{RssAlternateSource, RssEnclosure};
+use crate::rss::{discover_feeds, item_published_date, FeedCandidate, ForbiddenAddress};
 
 /// RSS Feed 请求
 #[derive(Debug, Deserialize)]
@@ -64,8 +69,73 @@ pub struct RssFeedItemResponse {
     pub author: Option<String>,
     pub published: Option<String>,
     pub categories: Vec<String>,
+    pub tags: Vec<String>,
+    /// 转载同一内容的其他来源
+    pub alternate_sources: Vec<RssAlternateSource>,
+    /// 附件（播客音频/视频等）
+    pub enclosures: Vec<RssEnclosure>,
+}
+
+fn default_items_limit() -> usize {
+    50
+}
+
+/// 按标签筛选条目的查询参数
+#[derive(Debug, Deserialize)]
+pub struct RssItemsQuery {
+    /// 标签（不区分大小写，为空时返回全部条目）
+    pub tag: Option<String>,
+    /// 最大返回条目数
+    #[serde(default = "default_items_limit")]
+    pub limit: usize,
+}
+
+/// 已缓存的 RSS 条目
+#[derive(Debug, Serialize)]
+pub struct RssTaggedItem {
+    /// 来源 feed URL
+    pub feed_url: String,
+    #[serde(flatten)]
+    pub item: RssFeedItemResponse,
+}
+
+/// 标签分面计数
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// 条目列表响应
+#[derive(Debug, Serialize)]
+pub struct RssItemsResponse {
+    /// 匹配的条目（按发布时间从新到旧）
+    pub items: Vec<RssTaggedItem>,
+    /// 匹配的条目总数
+    pub total: usize,
+    /// 匹配条目中出现最多的标签
+    pub facets: Vec<TagCount>,
+}
+
+/// feed 自动发现请求
+#[derive(Debug, Deserialize)]
+pub struct RssDiscoverRequest {
+    /// 网站地址
+    pub url: String,
 }
 
+/// feed 自动发现响应
+#[derive(Debug, Serialize)]
+pub struct RssDiscoverResponse {
+    /// 请求的网站地址
+    pub url: String,
+    /// 候选 feed（页面声明的在前）
+    pub feeds: Vec<FeedCandidate>,
+}
+
+/// 分面中返回的标签数
+const MAX_FACETS: usize = 20;
+
 /// 模板添加请求
 #[derive(Debug, Deserialize)]
 pub struct TemplateAddRequest {
@@ -98,10 +168,101 @@ pub async fn handle_rss_feeds_list(
     (StatusCode::OK, Json(response)).into_response()
 }
 
+/// 处理按标签筛选已缓存 RSS 条目的请求
+pub async fn handle_rss_items(
+    State(state): State<ApiState>,
+    ValidatedQuery(params): ValidatedQuery<RssItemsQuery>,
+) -> Response {
+    let Some(rss) = &state.rss else {
+        let error = ApiErrorResponse {
+            code: "CACHE_UNAVAILABLE".to_string(),
+            message: "缓存不可用".to_string(),
+            details: None,
+        };
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(error)).into_response();
+    };
+    let mut items = match rss.list_items() {
+        Ok(items) => items,
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "RSS_CACHE_ERROR".to_string(),
+                message: "读取 RSS 缓存失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+        }
+    };
+
+    if let Some(tag) = params.tag.as_deref().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()) {
+        items.retain(|(_, item)| item.tags.contains(&tag));
+    }
+    items.sort_by_key(|(_, item)| std::cmp::Reverse(item_published_date(item)));
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for tag in items.iter().flat_map(|(_, item)| &item.tags) {
+        *counts.entry(tag).or_insert(0) += 1;
+    }
+    let mut facets: Vec<TagCount> = counts
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag: tag.to_string(), count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    facets.truncate(MAX_FACETS);
+
+    let total = items.len();
+    let items = items
+        .into_iter()
+        .take(params.limit)
+        .map(|(feed_url, item)| RssTaggedItem {
+            feed_url,
+            item: RssFeedItemResponse {
+                title: item.title,
+                link: item.link,
+                description: item.description,
+                author: item.author,
+                published: item.pub_date,
+                categories: item.categories,
+                tags: item.tags,
+                alternate_sources: item.alternate_sources,
+                enclosures: item.enclosures,
+            },
+        })
+        .collect();
+    (StatusCode::OK, Json(RssItemsResponse { items, total, facets })).into_response()
+}
+
+/// 处理 feed 自动发现请求
+pub async fn handle_rss_discover(
+    State(state): State<ApiState>,
+    ValidatedJson(request): ValidatedJson<RssDiscoverRequest>,
+) -> Response {
+    match discover_feeds(state.search.transport().as_ref(), &request.url).await {
+        Ok(feeds) => (StatusCode::OK, Json(RssDiscoverResponse { url: request.url, feeds })).into_response(),
+        Err(e) if e.downcast_ref::<ForbiddenAddress>().is_some() => {
+            let error = ApiErrorResponse {
+                code: "RSS_DISCOVERY_FORBIDDEN".to_string(),
+                message: "不允许访问非公网地址".to_string(),
+                details: None,
+            };
+            (StatusCode::BAD_REQUEST, Json(error)).into_response()
+        }
+        Err(e) => {
+            // 失败原因只记录日志，不返回给调用方，避免被用来探测网络
+            tracing::debug!("Feed discovery for {} failed: {}", request.url, e);
+            let error = ApiErrorResponse {
+                code: "RSS_DISCOVERY_FAILED".to_string(),
+                message: "获取网站页面失败".to_string(),
+                details: None,
+            };
+            (StatusCode::BAD_GATEWAY, Json(error)).into_response()
+        }
+    }
+}
+
 /// 处理获取特定RSS feed请求
 pub async fn handle_rss_fetch(
     State(_state): State<ApiState>,
-    Json(_request): Json<RssFetchRequest>,
+    ValidatedJson(_request): ValidatedJson<RssFetchRequest>,
 ) -> Response {
     // TODO: 实现RSS feed获取逻辑
     let error = ApiErrorResponse {
@@ -126,7 +287,7 @@ pub async fn handle_rss_templates_list(
 /// 处理从模板添加RSS feeds请求
 pub async fn handle_rss_template_add(
     State(_state): State<ApiState>,
-    Json(_request): Json<TemplateAddRequest>,
+    ValidatedJson(_request): ValidatedJson<TemplateAddRequest>,
 ) -> Response {
     // TODO: 实现从模板添加feeds
     let error = ApiErrorResponse {
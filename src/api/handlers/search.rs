@@ -16,23 +16,61 @@
 //!
 //! 处理搜索相关的 API 请求
 
+use std::collections::{HashMap, HashSet};
 use axum::{
-    extract::{State, Query, Json},
+    body::Body,
+    extract::{State, Json},
     response::{IntoResponse, Response},
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
+    Extension,
 };
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::api::on::ApiState;
-use crate::api::types::{ApiSearchRequest, ApiSearchResponse, ApiSearchResultItem, ApiErrorResponse};
-use crate::search::SearchRequest;
+use crate::api::middleware::{query_cost, ClientCountry, EnginesUsed, EntityTag, QueryCost};
+use crate::api::handlers::click::build_click_url;
+use crate::api::telemetry::new_query_id;
+use crate::api::validation::{ValidatedJson, ValidatedQuery};
+use crate::api::types::{ApiSearchRequest, ApiSearchResponse, ApiSearchResultItem, ApiErrorResponse, ResponseProjection, ResultHighlights};
+use crate::cache::{CacheInterface, ClickStatsCache, HistoryScope};
+use crate::derive::{SearchQuery, SearchResult, SearchResultItem};
+use crate::search::stages::{ScoringStage, SortStage};
+use crate::url_utils::dedup_key;
+use crate::search::{
+    SearchRequest, AggregationStrategy, EngineCounts, SearchStatus, ResultContext, ResultStage, SortBy, SOURCE_ENGINE_KEY, ARCHIVE_URL_KEY, SUMMARY_KEY,
+    diversify_by_domain, highlight_terms, refine_items,
+};
+
+/// 按查询 ID 保存的聚合结果的保留时间（供 `refine` 在结果中细化搜索）
+const QUERY_RESULTS_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// 保存的聚合结果中记录查询字符串的元数据键
+const QUERY_TEXT_KEY: &str = "query";
+
+/// 保存的聚合结果中记录所用引擎（逗号分隔）的元数据键
+const ENGINES_USED_KEY: &str = "engines_used";
+
+/// NDJSON 流式输出的内容类型
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
 
 /// 处理 GET 搜索请求
 pub async fn handle_search(
     State(state): State<ApiState>,
-    Query(params): Query<ApiSearchRequest>,
+    scope: Option<Extension<HistoryScope>>,
+    country: Option<Extension<ClientCountry>>,
+    ValidatedQuery(params): ValidatedQuery<ApiSearchRequest>,
 ) -> Response {
-    match execute_search(&state, params).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+    if let Some(query_id) = params.refine.clone() {
+        return refine_response(&state, params, &query_id);
+    }
+    let country = country.map(|Extension(c)| c);
+    if params.is_ndjson() {
+        return ndjson_response(state, params, country.as_ref()).await;
+    }
+    let projection = params.get_projection();
+    match execute_search(&state, params, scope.map(|Extension(s)| s), country.as_ref()).await {
+        Ok(response) => search_response(response, projection.as_ref()),
         Err(e) => {
             let error = ApiErrorResponse {
                 code: "SEARCH_ERROR".to_string(),
@@ -47,10 +85,20 @@ pub async fn handle_search(
 /// 处理 POST 搜索请求
 pub async fn handle_search_post(
     State(state): State<ApiState>,
-    Json(params): Json<ApiSearchRequest>,
+    scope: Option<Extension<HistoryScope>>,
+    country: Option<Extension<ClientCountry>>,
+    ValidatedJson(params): ValidatedJson<ApiSearchRequest>,
 ) -> Response {
-    match execute_search(&state, params).await {
-        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+    if let Some(query_id) = params.refine.clone() {
+        return refine_response(&state, params, &query_id);
+    }
+    let country = country.map(|Extension(c)| c);
+    if params.is_ndjson() {
+        return ndjson_response(state, params, country.as_ref()).await;
+    }
+    let projection = params.get_projection();
+    match execute_search(&state, params, scope.map(|Extension(s)| s), country.as_ref()).await {
+        Ok(response) => search_response(response, projection.as_ref()),
         Err(e) => {
             let error = ApiErrorResponse {
                 code: "SEARCH_ERROR".to_string(),
@@ -62,63 +110,402 @@ pub async fn handle_search_post(
     }
 }
 
+/// 序列化搜索响应（指定字段投影时只输出选中的结果字段）
+///
+/// 响应附带由响应体得出的实体标签，由 HTTP 缓存中间件输出 ETag，结果不变时重新验证返回 304；
+/// 查询成本（命中缓存时为 0）交给预算中间件记账
+fn search_response(response: ApiSearchResponse, projection: Option<&ResponseProjection>) -> Response {
+    let engines_used = EnginesUsed(response.engines_used.len());
+    let cost = QueryCost(if response.cached { 0 } else { query_cost(response.engines_used.len(), 1) });
+    let entity_tag = search_entity_tag(&response, projection);
+    let mut response = match projection {
+        Some(projection) => (StatusCode::OK, Json(projection.apply(&response))).into_response(),
+        None => (StatusCode::OK, Json(response)).into_response(),
+    };
+    response.extensions_mut().insert(engines_used);
+    response.extensions_mut().insert(cost);
+    response.extensions_mut().insert(entity_tag);
+    response
+}
+
+/// NDJSON 流式搜索：每个引擎返回后立即把这批结果聚合（清理、安全标记、评分、过滤），
+/// 每行输出一个此前未输出过的结果项；失败时最后一行为错误对象
+///
+/// 客户端断开后，下一批结果写入失败时取消仍在进行的引擎请求
+async fn ndjson_response(state: ApiState, params: ApiSearchRequest, country: Option<&ClientCountry>) -> Response {
+    let cancellation = CancellationToken::new();
+    let request = match search_request(&state, &params, country, cancellation.clone()).await {
+        Ok(request) => request,
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "SEARCH_ERROR".to_string(),
+                message: "搜索失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        }
+    };
+    let engines_used = EnginesUsed(request.engines.len());
+    let cost = QueryCost(query_cost(request.engines.len(), 1));
+    let projection = params.get_projection();
+    let highlight = params.highlight.then(|| highlight_terms(&request.query.query));
+    let aggregator = state.search.aggregator_for(
+        &request,
+        AggregationStrategy::Merged,
+        params.get_sort_by(),
+        click_engine_weights(&state),
+    );
+
+    let (sender, receiver) = futures::channel::mpsc::unbounded::<Result<Vec<u8>, std::convert::Infallible>>();
+    let query_id = new_query_id();
+    tokio::spawn(async move {
+        let clicks = state.clicks.as_ref().filter(|c| c.is_enabled());
+        let mut seen = HashSet::new();
+        let streamed = state.search.search_streaming(&request, |result, engine_name| {
+            let batch = aggregator.aggregate_with_scoring(vec![result], &request.query);
+            let mut items: Vec<ApiSearchResultItem> = batch.items
+                .into_iter()
+                .filter(|item| seen.insert(dedup_key(&item.url)))
+                .map(|item| api_result_item(item, &engine_name))
+                .collect();
+            if let Some(clicks) = clicks {
+                attach_click_urls(clicks, &mut items, &query_id);
+            }
+            #[cfg(feature = "preview")]
+            attach_preview_urls(&state, &mut items);
+            if let Some(terms) = &highlight {
+                attach_highlights(&mut items, terms);
+            }
+
+            let mut lines = Vec::new();
+            for item in &items {
+                let written = match &projection {
+                    Some(projection) => serde_json::to_writer(&mut lines, &projection.apply_item(item)),
+                    None => serde_json::to_writer(&mut lines, item),
+                };
+                if written.is_ok() {
+                    lines.push(b'\n');
+                }
+            }
+            if !lines.is_empty() && sender.unbounded_send(Ok(lines)).is_err() {
+                cancellation.cancel();
+            }
+        });
+        if let Err(e) = streamed.instrument(tracing::info_span!("search", query_id = %query_id)).await {
+            let error = ApiErrorResponse {
+                code: "SEARCH_ERROR".to_string(),
+                message: "搜索失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            if let Ok(mut line) = serde_json::to_vec(&error) {
+                line.push(b'\n');
+                let _ = sender.unbounded_send(Ok(line));
+            }
+        }
+    });
+
+    let mut response = Response::new(Body::from_stream(receiver));
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(NDJSON_CONTENT_TYPE));
+    response.extensions_mut().insert(engines_used);
+    response.extensions_mut().insert(cost);
+    response
+}
+
+/// 根据响应体生成实体标签（排除每次请求都不同的 query_id、耗时和是否命中缓存）
+fn search_entity_tag(response: &ApiSearchResponse, projection: Option<&ResponseProjection>) -> EntityTag {
+    let mut value = match projection {
+        Some(projection) => projection.apply(response),
+        None => serde_json::to_value(response).unwrap_or_default(),
+    };
+    if let Some(object) = value.as_object_mut() {
+        object.remove("query_id");
+        object.remove("query_time_ms");
+        object.remove("cached");
+    }
+    EntityTag::from_bytes(value.to_string().as_bytes())
+}
+
+/// 在之前的搜索结果中细化搜索：按关键词过滤缓存的结果并重新排序，不重新查询引擎
+fn refine_response(state: &ApiState, params: ApiSearchRequest, refined_from: &str) -> Response {
+    let start_time = std::time::Instant::now();
+    let stored = match state.cache.as_ref().map(|cache| cache.results().get_query_results(refined_from)) {
+        Some(Ok(Some(stored))) => stored,
+        Some(Err(e)) => {
+            let error = ApiErrorResponse {
+                code: "SEARCH_ERROR".to_string(),
+                message: "读取查询结果失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+        }
+        _ => {
+            let error = ApiErrorResponse {
+                code: "QUERY_NOT_FOUND".to_string(),
+                message: "查询结果不存在或已过期，请重新搜索".to_string(),
+                details: Some(format!("query_id: {}", refined_from)),
+            };
+            return (StatusCode::NOT_FOUND, Json(error)).into_response();
+        }
+    };
+
+    let terms = params.get_query().unwrap_or_default();
+    let original = stored.metadata.get(QUERY_TEXT_KEY).cloned().unwrap_or_default();
+    let engines_used: Vec<String> = stored.metadata.get(ENGINES_USED_KEY)
+        .map(|engines| engines.split(',').filter(|e| !e.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+
+    // 过滤后按原查询加细化关键词重新评分
+    let mut items = stored.items;
+    refine_items(&mut items, &terms);
+    let query = SearchQuery { query: format!("{} {}", original, terms).trim().to_string(), ..Default::default() };
+    let context = ResultContext { query: &query, sort_by: params.get_sort_by() };
+    ScoringStage::new(None, HashMap::new()).process(&mut items, &context);
+    SortStage.process(&mut items, &context);
+
+    // 细化后的结果同样可以继续细化
+    let query_id = new_query_id();
+    let refined = SearchResult {
+        engine_name: "aggregated".to_string(),
+        total_results: Some(items.len()),
+        elapsed_ms: 0,
+        items,
+        pagination: None,
+        suggestions: Vec::new(),
+        metadata: HashMap::new(),
+    };
+    if let Some(cache) = &state.cache {
+        store_query_results(cache, &query_id, &query.query, std::slice::from_ref(&refined), &engines_used);
+    }
+
+    let mut results: Vec<ApiSearchResultItem> = refined.items.into_iter()
+        .map(|item| api_result_item(item, &refined.engine_name))
+        .collect();
+    if let Some(clicks) = state.clicks.as_ref().filter(|c| c.is_enabled()) {
+        attach_click_urls(clicks, &mut results, &query_id);
+    }
+    #[cfg(feature = "preview")]
+    attach_preview_urls(state, &mut results);
+    if params.highlight {
+        attach_highlights(&mut results, &highlight_terms(&query.query));
+    }
+
+    let projection = params.get_projection();
+    let response = ApiSearchResponse {
+        query_id,
+        query: terms,
+        total_count: results.len(),
+        results,
+        page: params.page,
+        page_size: params.page_size,
+        engines_used,
+        query_time_ms: start_time.elapsed().as_millis() as u64,
+        cached: true,
+        status: SearchStatus::Complete,
+        engine_counts: EngineCounts::default(),
+        translated_query: None,
+        answer: None,
+        spell_hints: Vec::new(),
+        refined_from: Some(refined_from.to_string()),
+    };
+    search_response(response, projection.as_ref())
+}
+
+/// 按查询 ID 保存聚合结果（记录来源引擎、查询字符串和所用引擎）
+fn store_query_results(
+    cache: &CacheInterface,
+    query_id: &str,
+    query: &str,
+    results: &[SearchResult],
+    engines_used: &[String],
+) {
+    let items: Vec<SearchResultItem> = results.iter()
+        .flat_map(|result| result.items.iter().map(|item| {
+            let mut item = item.clone();
+            item.metadata.entry(SOURCE_ENGINE_KEY.to_string()).or_insert_with(|| result.engine_name.clone());
+            item
+        }))
+        .collect();
+    let stored = SearchResult {
+        engine_name: "aggregated".to_string(),
+        total_results: Some(items.len()),
+        elapsed_ms: 0,
+        items,
+        pagination: None,
+        suggestions: Vec::new(),
+        metadata: HashMap::from([
+            (QUERY_TEXT_KEY.to_string(), query.to_string()),
+            (ENGINES_USED_KEY.to_string(), engines_used.join(",")),
+        ]),
+    };
+    if let Err(e) = cache.results().set_query_results(query_id, &stored, Some(QUERY_RESULTS_TTL)) {
+        tracing::warn!("Failed to store results for query {}: {}", query_id, e);
+    }
+}
+
+/// 转换为 API 结果项（移动结果项的字段，不再复制）
+fn api_result_item(mut item: SearchResultItem, engine_name: &str) -> ApiSearchResultItem {
+    let video = item.video();
+    let image = item.image();
+    let place = item.place();
+    let torrent = item.torrent();
+    let podcast = item.podcast();
+    let flags = item.security_flags();
+    ApiSearchResultItem {
+        engine: item.metadata.remove(SOURCE_ENGINE_KEY)
+            .unwrap_or_else(|| engine_name.to_string()),
+        title: item.title,
+        url: item.url,
+        description: Some(item.content),
+        thumbnail: item.thumbnail,
+        score: Some(item.score),
+        click_url: None,
+        preview_url: None,
+        video,
+        image,
+        place,
+        torrent,
+        podcast,
+        archive_url: item.metadata.remove(ARCHIVE_URL_KEY),
+        summary: item.metadata.remove(SUMMARY_KEY),
+        highlights: None,
+        flags,
+    }
+}
+
+/// 为每个结果生成签名跳转链接并记录展示次数
+fn attach_click_urls(clicks: &ClickStatsCache, results: &mut [ApiSearchResultItem], query_id: &str) {
+    let secret = &clicks.config().secret;
+    let mut impressions: HashMap<String, u64> = HashMap::new();
+    for (position, item) in results.iter_mut().enumerate() {
+        item.click_url = Some(build_click_url(secret, &item.url, query_id, position, &item.engine));
+        *impressions.entry(item.engine.clone()).or_insert(0) += 1;
+    }
+    if let Err(e) = clicks.record_impressions(&impressions) {
+        tracing::warn!("Failed to record impressions: {}", e);
+    }
+}
+
+/// 为每个结果计算查询词在标题和摘要中的高亮区间
+fn attach_highlights(results: &mut [ApiSearchResultItem], terms: &[Vec<char>]) {
+    for item in results.iter_mut() {
+        item.highlights = Some(ResultHighlights::for_item(item, terms));
+    }
+}
+
+/// 启用结果预览时，为每个网页结果生成签名预览链接
+#[cfg(feature = "preview")]
+fn attach_preview_urls(state: &ApiState, results: &mut [ApiSearchResultItem]) {
+    let Some(preview) = state.preview.as_ref().filter(|p| p.is_enabled()) else {
+        return;
+    };
+    let secret = &preview.config().secret;
+    for item in results.iter_mut() {
+        if crate::url_utils::is_http_url(&item.url) {
+            item.preview_url = Some(crate::api::handlers::preview::build_preview_url(secret, &item.url));
+        }
+    }
+}
+
 /// 执行搜索
-async fn execute_search(
+pub(crate) async fn execute_search(
     state: &ApiState,
     params: ApiSearchRequest,
+    scope: Option<HistoryScope>,
+    country: Option<&ClientCountry>,
 ) -> Result<ApiSearchResponse, Box<dyn std::error::Error + Send + Sync>> {
     let start_time = std::time::Instant::now();
 
-    // 转换为内部搜索查询
-    let search_query = params.to_search_query()
-        .map_err(|e| format!("参数错误: {}", e))?;
+    // 客户端断开连接时 axum 会丢弃处理器 future，drop guard 随之取消令牌，
+    // 中止进行中的引擎请求（包括提前返回后仍在后台执行的引擎）
+    let cancellation = CancellationToken::new();
+    let cancel_on_disconnect = cancellation.clone().drop_guard();
+    let request = search_request(state, &params, country, cancellation).await?;
 
-    // 获取引擎列表
-    let engines = params.get_engines();
+    // 执行搜索（按请求的排序方式聚合）
+    // 启用点击追踪时，根据历史点击率调整引擎权重
+    let clicks = state.clicks.as_ref().filter(|c| c.is_enabled());
+    let engine_weights = click_engine_weights(state);
 
-    // 创建搜索请求 - 设置合理的最大结果数以防止资源耗尽
-    let request = SearchRequest {
-        query: search_query,
-        engines,
-        timeout: None,
-        max_results: Some(1000), // 限制最大结果数为1000
-        force: false,
-        cache_timeline: Some(3600),
+    // 搜索 span 携带 query_id，引擎和缓存 span 作为其子 span
+    let query_id = new_query_id();
+    let search_span = tracing::info_span!("search", query_id = %query_id);
+
+    // 即时答案只在第一页给出，与引擎搜索并行
+    let query_text = request.query.query.clone();
+    let answer = async {
+        if params.page <= 1 { state.search.answer(&query_text).await } else { None }
     };
+    let (response, answer) = tokio::join!(
+        state.search.search_with_engine_weights(
+            &request,
+            AggregationStrategy::Merged,
+            params.get_sort_by(),
+            engine_weights,
+        ).instrument(search_span),
+        answer,
+    );
+    let mut response = response?;
+    // 搜索已完成，之后不再因连接关闭而取消后台引擎
+    cancel_on_disconnect.disarm();
 
-    // 执行搜索
-    let response = state.search.search(&request).await?;
+    // 启用搜索历史时，为该作用域点击过的域名加分
+    let history = state.history.as_ref().filter(|h| h.is_enabled());
+    if let (Some(history), Some(scope)) = (history, scope.as_ref()) {
+        for search_result in &mut response.results {
+            if let Err(e) = history.boost_clicked_domains(scope, &mut search_result.items) {
+                tracing::warn!("Failed to boost clicked domains: {}", e);
+            }
+        }
+    }
     
+    // 保存聚合结果，之后可以用 `refine=<query_id>` 在结果中细化搜索
+    let query_text = params.get_query().unwrap_or_default();
+    if let Some(cache) = &state.cache {
+        store_query_results(cache, &query_id, &query_text, &response.results, &response.engines_used);
+    }
+
     // 转换结果 - 收集所有结果
     let mut results = Vec::new();
-    for search_result in &response.results {
-        for item in &search_result.items {
-            results.push(ApiSearchResultItem {
-                title: item.title.clone(),
-                url: item.url.clone(),
-                description: Some(item.content.clone()),
-                engine: search_result.engine_name.clone(),
-                score: Some(item.score),
-            });
-        }
+    for search_result in std::mem::take(&mut response.results) {
+        let engine_name = search_result.engine_name;
+        results.extend(search_result.items.into_iter().map(|item| api_result_item(item, &engine_name)));
     }
     
-    // 按分数降序排序，确保最相关的结果在前面
-    results.sort_by(|a, b| {
-        let score_a = a.score.unwrap_or(0.0);
-        let score_b = b.score.unwrap_or(0.0);
-        score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
-    });
+    // 按分数降序排序，确保最相关的结果在前面（按日期排序时保留聚合器给出的顺序）；
+    // 历史加分可能改变顺序，排序后重新按域名多样性重排
+    if params.get_sort_by() == SortBy::Relevance {
+        results.sort_by(|a, b| {
+            let score_a = a.score.unwrap_or(0.0);
+            let score_b = b.score.unwrap_or(0.0);
+            score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let max_per_domain = state.search.config().max_per_domain_for(&request);
+        results = diversify_by_domain(results, max_per_domain, |item| (item.url.as_str(), item.score.unwrap_or(0.0)));
+    }
+    
+    // 启用点击追踪时，为每个结果生成签名跳转链接并记录展示次数
+    if let Some(clicks) = clicks {
+        attach_click_urls(clicks, &mut results, &query_id);
+    }
+    #[cfg(feature = "preview")]
+    attach_preview_urls(state, &mut results);
+    if params.highlight {
+        attach_highlights(&mut results, &highlight_terms(&request.query.query));
+    }
     
     let elapsed = start_time.elapsed().as_millis() as u64;
 
-    // 获取实际的查询字符串
-    let query_text = params.get_query().unwrap_or_default();
-    
     // 返回所有结果，让前端进行分页
     let total_count = results.len();
 
+    if let (Some(history), Some(scope)) = (history, scope.as_ref())
+        && let Err(e) = history.record_search(scope, &query_text, total_count)
+    {
+        tracing::warn!("Failed to record search history: {}", e);
+    }
+
     Ok(ApiSearchResponse {
+        query_id,
         query: query_text,
         results,
         total_count,
@@ -127,5 +514,63 @@ async fn execute_search(
         engines_used: response.engines_used,
         query_time_ms: elapsed,
         cached: response.cached,
+        status: response.engine_counts.status(),
+        engine_counts: response.engine_counts,
+        translated_query: response.translated_query,
+        answer,
+        spell_hints: response.spell_hints,
+        refined_from: None,
+    })
+}
+
+/// 启用点击追踪时按历史点击率得出的引擎权重
+fn click_engine_weights(state: &ApiState) -> HashMap<String, f64> {
+    match state.clicks.as_ref().filter(|c| c.is_enabled()) {
+        Some(clicks) => clicks.engine_weights().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load engine click weights: {}", e);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    }
+}
+
+/// 按 API 参数创建内部搜索请求
+async fn search_request(
+    state: &ApiState,
+    params: &ApiSearchRequest,
+    country: Option<&ClientCountry>,
+    cancellation: CancellationToken,
+) -> Result<SearchRequest, Box<dyn std::error::Error + Send + Sync>> {
+    // 转换为内部搜索查询
+    let search_query = params.to_search_query()
+        .map_err(|e| format!("参数错误: {}", e))?;
+
+    // 获取引擎列表（指定分类时使用该分类的引擎，否则按客户端所在国家选择默认引擎）
+    let engines = match params.get_category() {
+        Some(category) => {
+            let engines = state.search.engines_in_category(category).await;
+            if engines.is_empty() {
+                return Err(format!("参数错误: 分类 {} 没有可用引擎", category).into());
+            }
+            engines
+        }
+        None => params.get_engines_for_country(country),
+    };
+
+    // 创建搜索请求 - 设置合理的最大结果数以防止资源耗尽
+    Ok(SearchRequest {
+        query: search_query,
+        engines,
+        timeout: None,
+        max_results: Some(1000), // 限制最大结果数为1000
+        force: false,
+        cache_timeline: Some(3600),
+        early_response: params.get_early_response(),
+        filter: params.get_result_filter(),
+        engine_weights: params.get_engine_weights().map_err(|e| format!("参数错误: {}", e))?,
+        max_per_domain: params.max_per_domain.map(|n| n as usize),
+        lang_strict: params.lang_strict,
+        security_strict: params.security_strict,
+        cancellation: Some(cancellation),
     })
 }
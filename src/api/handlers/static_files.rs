@@ -14,19 +14,565 @@
 
 //! 静态文件处理器
 //!
-//! 提供首页和静态资源服务
+//! 提供首页、结果页、管理面板和静态资源服务。首页和结果页由 askama 模板渲染，
+//! 实例名称、标语、页脚链接和界面语言来自 [`UiConfig`]。结果页 `/search?q=`
+//! 与 `/api/search` 走同一条聚合流程，不依赖前端脚本。
 
+use std::collections::HashMap;
+
+use askama::Template;
 use axum::{
-    response::{Html, IntoResponse},
-    http::StatusCode,
+    extract::{Path, Query, RawQuery, State},
+    response::{Html, IntoResponse, Redirect, Response},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    Extension,
+};
+use serde::Serialize;
+
+use crate::api::handlers::boards::{board_etag, load_board};
+use crate::api::handlers::search::execute_search;
+use crate::api::i18n::Locale;
+use crate::api::middleware::{query_cost, ClientCountry, EnginesUsed, QueryCost};
+use crate::api::on::ApiState;
+use crate::api::types::{ApiSearchRequest, ApiSearchResultItem, UiConfig, DEFAULT_ROBOTS_TXT};
+use crate::api::validation::Validate;
+use crate::cache::HistoryScope;
+use crate::derive::RssEnclosureKind;
+
+/// 首页模板（static/html/index.html）
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate<'a> {
+    locale: Locale,
+    ui: &'a UiConfig,
+    tagline: &'a str,
+    version: &'a str,
+    text: &'static UiText,
+    /// 切换到其他语言的链接：(语言标签, 语言名称)
+    other_locales: Vec<(&'static str, &'static str)>,
+    /// 页面脚本使用的文案（JSON）
+    script_text: String,
+}
+
+/// 结果页模板（static/html/results.html）
+#[derive(Template)]
+#[template(path = "results.html")]
+struct ResultsTemplate<'a> {
+    locale: Locale,
+    ui: &'a UiConfig,
+    version: &'a str,
+    text: &'static UiText,
+    query: &'a str,
+    category: &'a str,
+    tabs: Vec<CategoryTab>,
+    /// 校验或搜索失败时的错误描述
+    error: Option<String>,
+    summary: String,
+    results: Vec<ResultCard>,
+    /// 图片和视频分类以网格展示
+    grid: bool,
+    /// 摘要是已清理的 HTML 片段（`sanitize.keep_ui_tags`），按 HTML 输出
+    rich_snippets: bool,
+    page_info: String,
+    prev_href: Option<String>,
+    next_href: Option<String>,
+}
+
+/// 榜单页模板（static/html/board.html）
+#[derive(Template)]
+#[template(path = "board.html")]
+struct BoardTemplate<'a> {
+    locale: Locale,
+    ui: &'a UiConfig,
+    version: &'a str,
+    text: &'static UiText,
+    name: &'a str,
+    updated: String,
+    entries: Vec<BoardEntry>,
+}
+
+/// 榜单条目
+struct BoardEntry {
+    rank: usize,
+    title: String,
+    link: String,
+    /// 评分和匹配的关键词
+    meta: String,
+    /// 可播放的附件（播客）
+    media: Option<MediaPlayer>,
+}
+
+/// 内嵌播放器（播客音频/视频）
+struct MediaPlayer {
+    url: String,
+    video: bool,
+}
+
+impl MediaPlayer {
+    fn new(url: &str, kind: RssEnclosureKind) -> Option<Self> {
+        kind.is_playable().then(|| Self { url: url.to_string(), video: kind == RssEnclosureKind::Video })
+    }
+}
+
+/// 结果页的分类标签页
+struct CategoryTab {
+    label: &'static str,
+    href: String,
+    active: bool,
+}
+
+/// 结果卡片
+struct ResultCard {
+    title: String,
+    /// 点击链接（启用点击追踪时为跳转链接）
+    href: String,
+    url: String,
+    description: String,
+    engine: String,
+    thumbnail: Option<String>,
+    /// 视频时长（`m:ss` 或 `h:mm:ss`）
+    duration: Option<String>,
+    /// 页面截图预览链接（启用结果预览时提供）
+    preview: Option<String>,
+    /// 可播放的媒体（播客结果）
+    media: Option<MediaPlayer>,
+    /// 安全标记徽标
+    flags: Vec<&'static str>,
+}
+
+impl From<ApiSearchResultItem> for ResultCard {
+    fn from(item: ApiSearchResultItem) -> Self {
+        let thumbnail = item.thumbnail.or_else(|| item.image.and_then(|image| image.image_url));
+        Self {
+            href: item.click_url.unwrap_or_else(|| item.url.clone()),
+            title: item.title,
+            url: item.url,
+            description: item.description.unwrap_or_default(),
+            engine: item.engine,
+            thumbnail,
+            duration: item.video.and_then(|video| video.duration_secs)
+                .or_else(|| item.podcast.as_ref().and_then(|podcast| podcast.duration_secs))
+                .map(format_duration),
+            preview: item.preview_url,
+            media: item.podcast.and_then(|podcast| MediaPlayer::new(&podcast.media_url, podcast.kind)),
+            flags: item.flags.iter().map(|flag| flag.as_str()).collect(),
+        }
+    }
+}
+
+/// 结果页的分类：(分类, 是否以网格展示)
+const RESULT_CATEGORIES: [(&str, bool); 5] =
+    [("general", false), ("images", true), ("videos", true), ("news", false), ("podcasts", false)];
+
+/// 首页文案
+struct UiText {
+    tagline: &'static str,
+    repo_link: &'static str,
+    search_placeholder: &'static str,
+    search_button: &'static str,
+    page_size_label: &'static str,
+    engine_count_label: &'static str,
+    all_engines: &'static str,
+    engines_suffix: &'static str,
+    searching: &'static str,
+    search_error: &'static str,
+    prev_page: &'static str,
+    next_page: &'static str,
+    built_with: &'static str,
+    health_link: &'static str,
+    engines_link: &'static str,
+    tab_all: &'static str,
+    tab_images: &'static str,
+    tab_videos: &'static str,
+    tab_news: &'static str,
+    tab_podcasts: &'static str,
+    results_summary: &'static str,
+    page_label: &'static str,
+    board_updated: &'static str,
+    board_empty: &'static str,
+    script: ScriptText,
+}
+
+/// 页面脚本中的文案，`{name}` 为占位符
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptText {
+    results_count: &'static str,
+    results_time: &'static str,
+    score: &'static str,
+    no_results: &'static str,
+    page_info: &'static str,
+    untitled: &'static str,
+}
+
+const ZH_CN_TEXT: UiText = UiText {
+    tagline: "免费、开源、强大的搜索引擎",
+    repo_link: "📦 GitHub 仓库",
+    search_placeholder: "搜索任何内容...",
+    search_button: "🔍 搜索",
+    page_size_label: "每页结果:",
+    engine_count_label: "引擎数量:",
+    all_engines: "全部引擎",
+    engines_suffix: "个引擎",
+    searching: "正在搜索",
+    search_error: "搜索时出现错误",
+    prev_page: "← 上一页",
+    next_page: "下一页 →",
+    built_with: "基于 Rust 构建",
+    health_link: "API 健康检查",
+    engines_link: "引擎列表",
+    tab_all: "全部",
+    tab_images: "图片",
+    tab_videos: "视频",
+    tab_news: "新闻",
+    tab_podcasts: "播客",
+    results_summary: "{count} 个结果，耗时 {ms} ms",
+    page_label: "第 {page} 页",
+    board_updated: "更新于 {time}",
+    board_empty: "榜单暂无条目",
+    script: ScriptText {
+        results_count: "找到 {count} 个结果 (使用引擎: {engines})",
+        results_time: "耗时: {ms}ms",
+        score: "⭐ 评分: {score}",
+        no_results: "没有找到相关结果",
+        page_info: "第 {page} 页 / 共 {pages} 页",
+        untitled: "无标题",
+    },
 };
 
-/// 嵌入的 HTML 首页内容
-const INDEX_HTML: &str = include_str!("../../../static/html/index.html");
+const EN_TEXT: UiText = UiText {
+    tagline: "A free, open-source and powerful search engine",
+    repo_link: "📦 GitHub repository",
+    search_placeholder: "Search anything...",
+    search_button: "🔍 Search",
+    page_size_label: "Results per page:",
+    engine_count_label: "Engines:",
+    all_engines: "All engines",
+    engines_suffix: " engines",
+    searching: "Searching",
+    search_error: "An error occurred while searching",
+    prev_page: "← Previous",
+    next_page: "Next →",
+    built_with: "built with Rust",
+    health_link: "API health",
+    engines_link: "Engines",
+    tab_all: "All",
+    tab_images: "Images",
+    tab_videos: "Videos",
+    tab_news: "News",
+    tab_podcasts: "Podcasts",
+    results_summary: "{count} results in {ms} ms",
+    page_label: "Page {page}",
+    board_updated: "Updated {time}",
+    board_empty: "No items on this board yet",
+    script: ScriptText {
+        results_count: "{count} results (engines: {engines})",
+        results_time: "Took {ms}ms",
+        score: "⭐ Score: {score}",
+        no_results: "No results found",
+        page_info: "Page {page} of {pages}",
+        untitled: "Untitled",
+    },
+};
+
+impl UiText {
+    fn of(locale: Locale) -> &'static UiText {
+        match locale {
+            Locale::ZhCn => &ZH_CN_TEXT,
+            Locale::En => &EN_TEXT,
+        }
+    }
+
+    /// 分类标签页的名称
+    fn category_label(&self, category: &str) -> &'static str {
+        match category {
+            "images" => self.tab_images,
+            "videos" => self.tab_videos,
+            "news" => self.tab_news,
+            "podcasts" => self.tab_podcasts,
+            _ => self.tab_all,
+        }
+    }
+}
+
+/// 替换文案中的 `{name}` 占位符
+fn fill(template: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// 格式化视频时长
+fn format_duration(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// 结果页链接
+fn search_page_href(query: &str, category: &str, page: u32, locale: Locale) -> String {
+    let page = page.to_string();
+    let params = serde_urlencoded::to_string([
+        ("q", query),
+        ("category", category),
+        ("page", page.as_str()),
+        ("lang", locale.tag()),
+    ])
+    .unwrap_or_default();
+    format!("/search?{}", params)
+}
+
+/// 页面语言：依次取 `lang` 查询参数、`Accept-Language` 和配置的默认语言
+fn page_locale(ui: &UiConfig, lang: Option<&str>, headers: &HeaderMap) -> Locale {
+    lang.and_then(Locale::parse).unwrap_or_else(|| {
+        Locale::negotiate_or(
+            headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()),
+            ui.default_locale,
+        )
+    })
+}
+
+/// 输出带语言头的 HTML 响应
+fn html_response(status: StatusCode, html: String, locale: Locale) -> Response {
+    let mut response = (status, Html(html)).into_response();
+    response.headers_mut().insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.tag()));
+    response.headers_mut().insert(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+/// 语言的本地名称（用于语言切换链接）
+fn locale_name(locale: Locale) -> &'static str {
+    match locale {
+        Locale::ZhCn => "中文",
+        Locale::En => "English",
+    }
+}
+
+/// 渲染首页
+fn render_index(ui: &UiConfig, locale: Locale, version: &str) -> Result<String, askama::Error> {
+    let text = UiText::of(locale);
+    IndexTemplate {
+        locale,
+        ui,
+        tagline: ui.tagline.as_deref().unwrap_or(text.tagline),
+        version,
+        text,
+        other_locales: Locale::ALL
+            .into_iter()
+            .filter(|other| *other != locale)
+            .map(|other| (other.tag(), locale_name(other)))
+            .collect(),
+        script_text: serde_json::to_string(&text.script).unwrap_or_else(|_| "{}".to_string()),
+    }
+    .render()
+}
+
+/// 嵌入的管理面板页面
+const ADMIN_HTML: &[u8] = include_bytes!("../../../static/html/admin.html");
+
+/// 嵌入的管理面板脚本
+const ADMIN_JS: &[u8] = include_bytes!("../../../static/js/admin.js");
 
 /// 处理首页请求
-pub async fn handle_index() -> impl IntoResponse {
-    Html(INDEX_HTML)
+///
+/// 界面语言依次取 `lang` 查询参数、`Accept-Language` 和配置的默认语言
+pub async fn handle_index(
+    State(state): State<ApiState>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let locale = page_locale(&state.ui, params.get("lang").map(String::as_str), &headers);
+    match render_index(&state.ui, locale, &state.version) {
+        Ok(html) => html_response(StatusCode::OK, html, locale),
+        Err(e) => {
+            tracing::error!("Failed to render index page: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "failed to render index page").into_response()
+        }
+    }
+}
+
+/// 处理 RSS 榜单页请求（`/boards/{name}`，另有 `lang`）
+pub async fn handle_board_page(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Response {
+    let board = match load_board(&state, &name) {
+        Ok(board) => board,
+        Err(e) => return e.into_response(),
+    };
+    let locale = page_locale(&state.ui, params.get("lang").map(String::as_str), &headers);
+    let text = UiText::of(locale);
+    let time = chrono::DateTime::from_timestamp(board.timestamp as i64, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let template = BoardTemplate {
+        locale,
+        ui: &state.ui,
+        version: &state.version,
+        text,
+        name: &board.name,
+        updated: fill(text.board_updated, &[("time", time)]),
+        entries: board
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, scored)| BoardEntry {
+                rank: i + 1,
+                title: scored.item.title.clone(),
+                link: scored.item.link.clone(),
+                meta: std::iter::once(format!("{:.1}", scored.score))
+                    .chain(scored.matched_keywords.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" · "),
+                media: scored.item.playable_enclosure().and_then(|enclosure| MediaPlayer::new(&enclosure.url, enclosure.kind)),
+            })
+            .collect(),
+    };
+
+    match template.render() {
+        Ok(html) => {
+            let mut response = html_response(StatusCode::OK, html, locale);
+            response.extensions_mut().insert(board_etag(&board, locale.tag()));
+            response
+        }
+        Err(e) => {
+            tracing::error!("Failed to render board page: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "failed to render board page").into_response()
+        }
+    }
+}
+
+/// 处理结果页请求（`/search?q=`，参数与 `/api/search` 相同，另有 `lang`）
+///
+/// 查询为空时重定向到首页；校验或搜索失败时在页面中显示错误
+pub async fn handle_search_page(
+    State(state): State<ApiState>,
+    scope: Option<Extension<HistoryScope>>,
+    country: Option<Extension<ClientCountry>>,
+    RawQuery(raw): RawQuery,
+    headers: HeaderMap,
+) -> Response {
+    let raw = raw.unwrap_or_default();
+    let params: HashMap<String, String> = serde_urlencoded::from_str(&raw).unwrap_or_default();
+    let locale = page_locale(&state.ui, params.get("lang").map(String::as_str), &headers);
+    let text = UiText::of(locale);
+
+    let request = serde_urlencoded::from_str::<ApiSearchRequest>(&raw).map_err(|e| e.to_string());
+    let query = request.as_ref().ok().and_then(|r| r.get_query().ok()).unwrap_or_default();
+    if query.trim().is_empty() {
+        return Redirect::to(&format!("/?lang={}", locale.tag())).into_response();
+    }
+    let category = request.as_ref().ok()
+        .and_then(|r| r.category.as_deref())
+        .and_then(|c| RESULT_CATEGORIES.iter().find(|(name, _)| *name == c))
+        .map_or("general", |(name, _)| *name);
+    let grid = RESULT_CATEGORIES.iter().any(|(name, grid)| *name == category && *grid);
+    let page = request.as_ref().map_or(1, |r| r.page.max(1));
+
+    let mut page_template = ResultsTemplate {
+        locale,
+        ui: &state.ui,
+        version: &state.version,
+        text,
+        query: &query,
+        category,
+        tabs: RESULT_CATEGORIES
+            .iter()
+            .map(|(name, _)| CategoryTab {
+                label: text.category_label(name),
+                href: search_page_href(&query, name, 1, locale),
+                active: *name == category,
+            })
+            .collect(),
+        error: None,
+        summary: String::new(),
+        results: Vec::new(),
+        grid,
+        rich_snippets: state.search.config().sanitize.keep_ui_tags,
+        page_info: fill(text.page_label, &[("page", page.to_string())]),
+        prev_href: (page > 1).then(|| search_page_href(&query, category, page - 1, locale)),
+        next_href: None,
+    };
+
+    let request = request.and_then(|mut request| {
+        request.category = Some(category.to_string());
+        request.validate(&state.validation).map_err(|e| e.message)?;
+        Ok(request)
+    });
+    let (status, extensions) = match request {
+        Err(message) => {
+            page_template.error = Some(message);
+            (StatusCode::BAD_REQUEST, None)
+        }
+        Ok(request) => {
+            let page_size = request.page_size as usize;
+            match execute_search(&state, request, scope.map(|Extension(s)| s), country.map(|Extension(c)| c).as_ref()).await {
+                Ok(response) => {
+                    page_template.summary = fill(text.results_summary, &[
+                        ("count", response.total_count.to_string()),
+                        ("ms", response.query_time_ms.to_string()),
+                    ]);
+                    if response.results.len() >= page_size && page_size > 0 {
+                        page_template.next_href = Some(search_page_href(&query, category, page + 1, locale));
+                    }
+                    page_template.results = response.results.into_iter().take(page_size).map(ResultCard::from).collect();
+                    let engines = response.engines_used.len();
+                    let cost = if response.cached { 0 } else { query_cost(engines, 1) };
+                    (StatusCode::OK, Some((EnginesUsed(engines), QueryCost(cost))))
+                }
+                Err(e) => {
+                    page_template.error = Some(e.to_string());
+                    (StatusCode::INTERNAL_SERVER_ERROR, None)
+                }
+            }
+        }
+    };
+
+    match page_template.render() {
+        Ok(html) => {
+            let mut response = html_response(status, html, locale);
+            if let Some((engines_used, cost)) = extensions {
+                response.extensions_mut().insert(engines_used);
+                response.extensions_mut().insert(cost);
+            }
+            response
+        }
+        Err(e) => {
+            tracing::error!("Failed to render results page: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "failed to render results page").into_response()
+        }
+    }
+}
+
+/// 处理管理面板页面请求（仅内网）
+pub async fn handle_admin_dashboard() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [("content-type", "text/html; charset=utf-8")],
+        ADMIN_HTML,
+    )
+}
+
+/// 处理管理面板脚本请求（仅内网）
+pub async fn handle_admin_js() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [("content-type", "application/javascript; charset=utf-8")],
+        ADMIN_JS,
+    )
+}
+
+/// 处理 robots.txt 请求（默认禁止抓取结果页和 API）
+pub async fn handle_robots_txt(State(state): State<ApiState>) -> impl IntoResponse {
+    let body = state.instance.robots_txt.clone().unwrap_or_else(|| DEFAULT_ROBOTS_TXT.to_string());
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain; charset=utf-8")],
+        body,
+    )
 }
 
 /// 处理 favicon 请求（返回空图标避免 404）
@@ -43,10 +589,75 @@ pub async fn handle_favicon() -> impl IntoResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::api::types::FooterLink;
+
+    #[test]
+    fn test_index_renders_locale_and_branding() {
+        let html = render_index(&UiConfig::default(), Locale::ZhCn, "0.3.0").unwrap();
+        assert!(html.contains(r#"<html lang="zh-CN">"#));
+        assert!(html.contains("SeeSea v0.3.0"));
+        assert!(html.contains("免费、开源、强大的搜索引擎"));
+        assert!(html.contains(r#"href="/?lang=en""#));
+
+        let ui = UiConfig {
+            instance_name: "Tide <Search>".to_string(),
+            footer_links: vec![FooterLink { label: "Privacy".to_string(), url: "/privacy".to_string() }],
+            show_repo_link: false,
+            ..Default::default()
+        };
+        let html = render_index(&ui, Locale::En, "1.0.0").unwrap();
+        assert!(html.contains(r#"<html lang="en">"#));
+        assert!(html.contains("Tide &#60;Search&#62; v1.0.0"));
+        assert!(html.contains(r#"<a href="/privacy">Privacy</a>"#));
+        assert!(html.contains("Search anything..."));
+        assert!(!html.contains("github.com/nostalgiatan"));
+    }
 
     #[test]
-    fn test_index_html_not_empty() {
-        assert!(!INDEX_HTML.is_empty());
-        assert!(INDEX_HTML.contains("SeeSea"));
+    fn test_admin_assets_embedded() {
+        let html = std::str::from_utf8(ADMIN_HTML).unwrap();
+        assert!(html.contains("/admin/admin.js"));
+        let js = std::str::from_utf8(ADMIN_JS).unwrap();
+        assert!(js.contains("/api/metrics/realtime"));
+        assert!(js.contains("/api/admin/engines"));
     }
+
+    #[tokio::test]
+    async fn test_search_page_renders_results() {
+        use crate::api::on::ApiInterface;
+        use crate::net::client::{MockResponse, MockTransport};
+        use crate::search::{SearchConfig, SearchInterface};
+        use axum::body::{to_bytes, Body};
+        use axum::http::Request;
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let html = r#"<ol id="b_results">
+            <li class="b_algo"><h2><a href="https://tokio.rs/">Tokio &lt;runtime&gt;</a></h2><p>Rust async runtime</p></li>
+        </ol>"#;
+        let transport = Arc::new(MockTransport::new().respond("www.bing.com/search", MockResponse::ok(html)));
+        let search = SearchInterface::new(SearchConfig::default()).unwrap().with_transport(transport);
+        let router = ApiInterface::new(Arc::new(search), "0.1.0".to_string()).build_internal_router();
+
+        let response = router.clone()
+            .oneshot(Request::get("/search?q=tokio&engines=bing&lang=en").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "en");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let page = String::from_utf8(body.to_vec()).unwrap();
+        assert!(page.contains(r#"<a href="https://tokio.rs/" target="_blank" rel="noopener noreferrer">Tokio &#60;runtime&#62;</a>"#));
+        assert!(page.contains("1 results in"));
+        assert!(page.contains(r#"href="/search?q=tokio&#38;category=images&#38;page=1&#38;lang=en">Images</a>"#));
+
+        // 空查询回到首页
+        let response = router
+            .oneshot(Request::get("/search?q=").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers()[header::LOCATION], "/?lang=zh-CN");
+    }
+
 }
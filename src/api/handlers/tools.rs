@@ -0,0 +1,260 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! LLM 工具兼容接口
+//!
+//! 模仿常见智能体框架使用的「搜索工具」格式：输入查询和 `max_results`，
+//! 输出 `{title, url, snippet}` 列表。已有的智能体代码只需修改 base URL
+//! 即可改用 SeeSea。
+//!
+//! - `GET /v1/tools` 返回 OpenAI function calling 格式的工具定义
+//! - `POST /v1/tools/web_search` 执行搜索，请求体可以是 `{"query": ..., "max_results": ...}`，
+//!   也可以是模型生成的工具调用 `{"name": "web_search", "arguments": "{\"query\": ...}"}`
+
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::api::validation::ValidatedJson;
+use crate::derive::SearchQuery;
+use crate::search::SearchRequest;
+
+/// 工具名称
+pub const WEB_SEARCH_TOOL: &str = "web_search";
+
+/// 默认返回的结果数
+const DEFAULT_MAX_RESULTS: usize = 5;
+
+/// 工具参数
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WebSearchArgs {
+    /// 查询关键词
+    pub query: String,
+    /// 返回的结果数上限
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+/// web_search 请求
+///
+/// 直接提供 `query` 时忽略 `arguments`；
+/// `arguments` 可以是 JSON 对象，也可以是 OpenAI 工具调用中的 JSON 字符串
+#[derive(Debug, Deserialize)]
+pub struct WebSearchToolRequest {
+    /// 查询关键词
+    #[serde(default)]
+    pub query: Option<String>,
+    /// 返回的结果数上限
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// 工具调用参数
+    #[serde(default)]
+    pub arguments: Option<serde_json::Value>,
+}
+
+impl WebSearchToolRequest {
+    /// 解析出工具参数
+    pub fn args(&self) -> Result<WebSearchArgs, String> {
+        if let Some(query) = &self.query {
+            return Ok(WebSearchArgs {
+                query: query.clone(),
+                max_results: self.max_results,
+            });
+        }
+        let mut args: WebSearchArgs = match &self.arguments {
+            Some(serde_json::Value::String(raw)) => serde_json::from_str(raw),
+            Some(value) => serde_json::from_value(value.clone()),
+            None => return Err("缺少 query 或 arguments".to_string()),
+        }
+        .map_err(|e| format!("arguments 无效: {}", e))?;
+        args.max_results = args.max_results.or(self.max_results);
+        Ok(args)
+    }
+}
+
+/// 单个搜索结果
+#[derive(Debug, Serialize)]
+pub struct WebSearchToolResult {
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+    /// 摘要
+    pub snippet: String,
+}
+
+/// web_search 响应
+#[derive(Debug, Serialize)]
+pub struct WebSearchToolResponse {
+    /// 查询
+    pub query: String,
+    /// 搜索结果（按相关性排序）
+    pub results: Vec<WebSearchToolResult>,
+}
+
+/// 返回工具定义（OpenAI function calling 格式）
+pub async fn handle_tools_list() -> Response {
+    let tools = serde_json::json!({
+        "tools": [{
+            "type": "function",
+            "function": {
+                "name": WEB_SEARCH_TOOL,
+                "description": "Search the web and return a list of results with title, url and snippet.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "The search query"
+                        },
+                        "max_results": {
+                            "type": "integer",
+                            "description": "Maximum number of results to return",
+                            "default": DEFAULT_MAX_RESULTS
+                        }
+                    },
+                    "required": ["query"]
+                }
+            }
+        }]
+    });
+    (StatusCode::OK, Json(tools)).into_response()
+}
+
+/// 处理 web_search 工具调用
+pub async fn handle_web_search_tool(
+    State(state): State<ApiState>,
+    ValidatedJson(params): ValidatedJson<WebSearchToolRequest>,
+) -> Response {
+    // 校验阶段已确认参数可以解析
+    let Ok(args) = params.args() else {
+        return StatusCode::UNPROCESSABLE_ENTITY.into_response();
+    };
+    let max_results = args.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let request = SearchRequest {
+        query: SearchQuery {
+            query: args.query.clone(),
+            ..Default::default()
+        },
+        max_results: Some(max_results),
+        ..Default::default()
+    };
+
+    match state.search.search(&request).await {
+        Ok(response) => {
+            let mut items: Vec<_> = response.results.into_iter().flat_map(|r| r.items).collect();
+            items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+            let results = items
+                .into_iter()
+                .take(max_results)
+                .map(|item| WebSearchToolResult {
+                    title: item.title,
+                    url: item.url,
+                    snippet: item.content,
+                })
+                .collect();
+            let response = WebSearchToolResponse {
+                query: args.query,
+                results,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            let error = ApiErrorResponse {
+                code: "SEARCH_ERROR".to_string(),
+                message: "搜索失败".to_string(),
+                details: Some(e.to_string()),
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::test_support::{bing_transport, internal_router};
+    use crate::search::SearchConfig;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_args_from_tool_call() {
+        let request: WebSearchToolRequest = serde_json::from_value(serde_json::json!({
+            "name": "web_search",
+            "arguments": "{\"query\": \"rust\", \"max_results\": 3}"
+        }))
+        .unwrap();
+        assert_eq!(request.args().unwrap(), WebSearchArgs { query: "rust".to_string(), max_results: Some(3) });
+
+        let request: WebSearchToolRequest =
+            serde_json::from_value(serde_json::json!({"arguments": {"query": "rust"}, "max_results": 2})).unwrap();
+        assert_eq!(request.args().unwrap().max_results, Some(2));
+
+        let request: WebSearchToolRequest = serde_json::from_value(serde_json::json!({"arguments": "not json"})).unwrap();
+        assert!(request.args().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_web_search_tool_endpoint() {
+        let router = internal_router(SearchConfig::default(), bing_transport(&[
+            ("https://www.rust-lang.org/", "Rust Programming Language", "A language empowering everyone"),
+            ("https://doc.rust-lang.org/book/", "The Rust Book", "Learn Rust"),
+        ]));
+
+        let body = serde_json::json!({"query": "rust", "max_results": 1}).to_string();
+        let response = router.clone()
+            .oneshot(
+                Request::post("/v1/tools/web_search")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0]["url"].as_str().unwrap().contains("rust-lang.org"));
+        assert!(results[0]["snippet"].is_string());
+
+        let response = router.clone()
+            .oneshot(
+                Request::post("/v1/tools/web_search")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"max_results": 1}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = router
+            .oneshot(Request::get("/v1/tools").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["tools"][0]["function"]["name"], WEB_SEARCH_TOOL);
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 用量处理器
+//!
+//! 返回调用方（API 密钥或魔法链接令牌）当天的查询成本、每日预算和最近 7 天的用量。
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::api::middleware::budget_reset_at;
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::DailyUsage;
+
+/// 返回的历史天数（含当天）
+const USAGE_HISTORY_DAYS: u64 = 7;
+
+/// 用量响应
+#[derive(Debug, Serialize)]
+pub struct UsageResponse {
+    /// 调用方类型：`api_key` 或 `magic_link`
+    pub subject: &'static str,
+    /// 每日预算（None 表示不限）
+    pub daily_budget: Option<u64>,
+    /// 当天已用成本
+    pub used_today: u64,
+    /// 当天剩余预算（不限时为 None）
+    pub remaining: Option<u64>,
+    /// 当天计费请求数
+    pub requests_today: u64,
+    /// 预算重置时间（下一个 UTC 零点）
+    pub resets_at: DateTime<Utc>,
+    /// 最近几天的用量（最新的在前）
+    pub history: Vec<DailyUsage>,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+/// 处理用量查询请求
+pub async fn handle_usage(State(state): State<ApiState>, headers: HeaderMap, uri: Uri) -> Response {
+    let budget = &state.budget;
+    let usage = match budget.usage() {
+        Some(usage) if budget.config().enabled => usage,
+        _ => return error_response(StatusCode::NOT_FOUND, "QUERY_BUDGET_DISABLED", "查询成本预算未启用", None),
+    };
+    let Some(subject) = budget.resolve(&headers, uri.query()) else {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "USAGE_SUBJECT_REQUIRED",
+            "需要有效的 API 密钥（Authorization: ApiKey ...）或魔法链接令牌",
+            None,
+        );
+    };
+
+    match usage.recent(&subject.id, USAGE_HISTORY_DAYS) {
+        Ok(history) => {
+            let today = history.first().copied().unwrap_or_default();
+            let response = UsageResponse {
+                subject: subject.kind,
+                daily_budget: subject.daily_budget,
+                used_today: today.cost,
+                remaining: subject.daily_budget.map(|budget| budget.saturating_sub(today.cost)),
+                requests_today: today.requests,
+                resets_at: budget_reset_at(),
+                history,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "USAGE_ERROR", "读取用量失败", Some(e.to_string())),
+    }
+}
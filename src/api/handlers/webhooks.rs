@@ -0,0 +1,297 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Webhook 管理处理器
+//!
+//! 注册、列出和删除 webhook，查看投递日志，以及发送测试通知。仅在内网路由器注册。
+//! 签名密钥只在创建时提交，之后不会在响应中返回。
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::{CacheError, WebhookRecord, WebhookSource};
+use crate::notify::NotificationItem;
+
+/// 默认返回的投递日志条数
+const DEFAULT_DELIVERY_LIMIT: usize = 50;
+
+/// 注册 webhook 请求
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    /// 名称
+    pub name: String,
+    /// 接收通知的 URL
+    pub url: String,
+    /// 通知来源
+    pub source: WebhookSource,
+    /// 来源目标（榜单名称或保存的搜索 ID）
+    pub target: String,
+    /// 评分阈值
+    #[serde(default)]
+    pub min_score: f64,
+    /// 签名密钥
+    pub secret: Option<String>,
+}
+
+/// webhook 信息（不含密钥）
+#[derive(Debug, Serialize)]
+pub struct WebhookInfo {
+    /// webhook ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 接收通知的 URL
+    pub url: String,
+    /// 通知来源
+    pub source: WebhookSource,
+    /// 来源目标
+    pub target: String,
+    /// 评分阈值
+    pub min_score: f64,
+    /// 是否设置了签名密钥
+    pub signed: bool,
+    /// 是否启用
+    pub enabled: bool,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookRecord> for WebhookInfo {
+    fn from(record: WebhookRecord) -> Self {
+        Self {
+            id: record.id,
+            name: record.name,
+            url: record.url,
+            source: record.source,
+            target: record.target,
+            min_score: record.min_score,
+            signed: record.secret.is_some(),
+            enabled: record.enabled,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// 投递日志查询参数
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    /// 返回条数
+    pub limit: Option<usize>,
+}
+
+fn error_response(status: StatusCode, code: &str, message: &str, details: Option<String>) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details,
+    };
+    (status, Json(error)).into_response()
+}
+
+fn unavailable() -> Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "WEBHOOK_STORE_UNAVAILABLE", "webhook 存储不可用", None)
+}
+
+fn storage_error(e: CacheError) -> Response {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, "WEBHOOK_STORE_ERROR", "webhook 存储操作失败", Some(e.to_string()))
+}
+
+fn not_found() -> Response {
+    error_response(StatusCode::NOT_FOUND, "WEBHOOK_NOT_FOUND", "webhook 不存在", None)
+}
+
+/// 处理 webhook 列表请求
+pub async fn handle_webhook_list(
+    State(state): State<ApiState>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return unavailable();
+    };
+    match webhooks.store().list() {
+        Ok(records) => {
+            let hooks: Vec<WebhookInfo> = records.into_iter().map(WebhookInfo::from).collect();
+            (StatusCode::OK, Json(hooks)).into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理注册 webhook 请求
+pub async fn handle_webhook_create(
+    State(state): State<ApiState>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return unavailable();
+    };
+    if request.name.trim().is_empty() || request.target.trim().is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "INVALID_WEBHOOK", "名称和来源目标不能为空", None);
+    }
+    if !crate::url_utils::is_http_url(&request.url)
+        || request.url.len() > state.validation.max_url_length
+    {
+        return error_response(StatusCode::BAD_REQUEST, "INVALID_WEBHOOK_URL", "url 必须以 http:// 或 https:// 开头", None);
+    }
+
+    let secret = request.secret.filter(|s| !s.is_empty());
+    match webhooks.store().create(request.name.trim(), request.url, request.source, request.target.trim(), request.min_score, secret) {
+        Ok(record) => (StatusCode::CREATED, Json(WebhookInfo::from(record))).into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理删除 webhook 请求
+pub async fn handle_webhook_delete(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return unavailable();
+    };
+    match webhooks.store().delete(&id) {
+        Ok(true) => (StatusCode::OK, Json(serde_json::json!({ "deleted": true }))).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理投递日志请求
+pub async fn handle_webhook_deliveries(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<DeliveryQuery>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return unavailable();
+    };
+    match webhooks.store().find(&id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT);
+    match webhooks.store().deliveries(&id, limit) {
+        Ok(deliveries) => (StatusCode::OK, Json(deliveries)).into_response(),
+        Err(e) => storage_error(e),
+    }
+}
+
+/// 处理测试通知请求（发送一条示例条目，不影响已通知记录）
+pub async fn handle_webhook_test(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(webhooks) = &state.webhooks else {
+        return unavailable();
+    };
+    let record = match webhooks.store().find(&id) {
+        Ok(Some(record)) => record,
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    };
+    let item = NotificationItem {
+        title: "SeeSea webhook test".to_string(),
+        url: "https://example.com/seesea-webhook-test".to_string(),
+        score: record.min_score,
+        snippet: None,
+    };
+    let delivery = webhooks.deliver(&record, vec![item]).await;
+    (StatusCode::OK, Json(delivery)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::on::ApiInterface;
+    use crate::net::client::{MockResponse, MockTransport};
+    use crate::search::{SearchConfig, SearchInterface};
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    async fn json(response: axum::response::Response) -> serde_json::Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    /// 启动本地 webhook 接收端，返回地址和收到的请求数
+    async fn spawn_receiver() -> (std::net::SocketAddr, Arc<AtomicUsize>) {
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = received.clone();
+        let app = axum::Router::new().route("/tech", axum::routing::post(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { StatusCode::OK }
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (addr, received)
+    }
+
+    #[tokio::test]
+    async fn test_webhook_endpoints() {
+        // 引擎传输层不接收 webhook 投递
+        let transport = Arc::new(MockTransport::new().respond("127.0.0.1", MockResponse::ok("")));
+        let search = SearchInterface::new(SearchConfig::default()).unwrap().with_transport(transport.clone());
+        let router = ApiInterface::new(Arc::new(search), "0.1.0".to_string()).build_internal_router();
+        let (addr, received) = spawn_receiver().await;
+
+        let body = serde_json::json!({
+            "name": "tech", "url": format!("http://{}/tech", addr), "source": "rss_ranking",
+            "target": "tech", "min_score": 2.5, "secret": "s3cret",
+        }).to_string();
+        let response = router.clone()
+            .oneshot(Request::post("/api/webhooks").header("content-type", "application/json").body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = json(response).await;
+        assert_eq!(created["signed"], true);
+        assert!(created.get("secret").is_none());
+        let id = created["id"].as_str().unwrap().to_string();
+
+        let response = router.clone()
+            .oneshot(Request::post(format!("/api/webhooks/{}/test", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(json(response).await["success"], true);
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+        assert!(transport.requests().is_empty());
+
+        let response = router.clone()
+            .oneshot(Request::get(format!("/api/webhooks/{}/deliveries", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(json(response).await.as_array().unwrap().len(), 1);
+
+        let response = router.clone()
+            .oneshot(Request::delete(format!("/api/webhooks/{}", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router
+            .oneshot(Request::get(format!("/api/webhooks/{}/deliveries", id)).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 国际化
+//!
+//! 错误消息目录与 `Accept-Language` 协商。API 错误响应的 `code` 保持稳定、可供程序判断，
+//! `message` 按客户端语言从目录中取得；目录中没有的代码保留处理器给出的原始消息。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// 支持的语言（配置中写作语言标签，如 `zh-CN`、`en`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Locale {
+    /// 简体中文（默认）
+    #[default]
+    ZhCn,
+    /// 英语
+    En,
+}
+
+impl Locale {
+    /// 所有支持的语言
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    /// BCP 47 语言标签
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::En => "en",
+        }
+    }
+
+    /// 解析语言标签（只看主标签，如 `zh-TW` 视为中文，`en-GB` 视为英语）
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Self::ZhCn),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    /// 按 `Accept-Language` 协商语言
+    ///
+    /// 按 q 值从高到低选第一个支持的语言，`*` 或都不支持时返回默认语言
+    pub fn negotiate(accept_language: Option<&str>) -> Self {
+        Self::negotiate_or(accept_language, Self::default())
+    }
+
+    /// 按 `Accept-Language` 协商语言，`*` 或都不支持时返回 `fallback`
+    pub fn negotiate_or(accept_language: Option<&str>, fallback: Self) -> Self {
+        let Some(header) = accept_language else {
+            return fallback;
+        };
+        let mut ranges: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let q = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && q > 0.0).then_some((tag, q))
+            })
+            .collect();
+        // 稳定排序，q 值相同时保持客户端给出的顺序
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| if tag == "*" { Some(fallback) } else { Self::parse(tag) })
+            .unwrap_or(fallback)
+    }
+
+    /// 粗略判断文本所用的语言（含汉字即视为中文）
+    pub fn of_text(text: &str) -> Self {
+        if text.chars().any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c)) {
+            Self::ZhCn
+        } else {
+            Self::En
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(tag: String) -> Result<Self, Self::Error> {
+        Self::parse(&tag).ok_or_else(|| format!("不支持的语言: {}（可选 zh-CN、en）", tag))
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.tag().to_string()
+    }
+}
+
+/// 错误消息目录：(错误代码, 中文, 英文)
+const ERROR_MESSAGES: &[(&str, &str, &str)] = &[
+    ("AUTH_FAILED", "认证失败", "Authentication failed"),
+    ("AUTH_REQUIRED", "需要认证", "Authentication required"),
+    ("MAGIC_LINK_INVALID", "魔法链接无效、已过期或已使用", "Magic link is invalid, expired or already used"),
+    ("MAGIC_LINK_FORBIDDEN", "魔法链接无权访问此端点", "Magic link is not permitted for this endpoint"),
+    ("MAGIC_LINK_QUOTA_EXCEEDED", "魔法链接今日配额已用完", "Magic link daily quota exceeded"),
+    ("MAGIC_LINK_NOT_FOUND", "魔法链接不存在", "Magic link not found"),
+    ("MISSING_TOKEN", "缺少 token 参数", "Missing token parameter"),
+    ("INVALID_HISTORY_SCOPE", "历史作用域只能包含字母、数字、- 和 _", "History scope may only contain letters, digits, - and _"),
+    ("INVALID_MAGIC_LINK_SCOPE", "权限范围只能是 search、rss-admin 或 metrics-read", "Scopes must be search, rss-admin or metrics-read"),
+    ("RATE_LIMIT_EXCEEDED", "请求过于频繁，请稍后再试", "Too many requests, please try again later"),
+    ("CIRCUIT_BREAKER_OPEN", "服务暂时不可用，请稍后再试", "Service temporarily unavailable, please try again later"),
+    ("IP_BLOCKED", "您的IP地址已被封禁", "Your IP address has been blocked"),
+    ("COUNTRY_BLOCKED", "您所在的地区无法访问此服务", "This service is not available in your region"),
+    ("QUERY_BUDGET_EXCEEDED", "今日查询预算已用完", "Daily query budget exhausted"),
+    ("QUERY_BUDGET_DISABLED", "查询成本预算未启用", "Query budgets are not enabled"),
+    ("USAGE_SUBJECT_REQUIRED", "需要有效的 API 密钥或魔法链接令牌", "A valid API key or magic link token is required"),
+    ("USAGE_ERROR", "读取用量失败", "Failed to read usage"),
+    ("SEARCH_ERROR", "搜索失败", "Search failed"),
+    ("ANSWER_DISABLED", "答案生成未启用", "Answer synthesis is not enabled"),
+    ("ANSWER_NO_RESULTS", "没有可用于生成答案的搜索结果", "No search results to answer from"),
+    ("ANSWER_UPSTREAM_ERROR", "答案生成接口调用失败", "The answer model endpoint failed"),
+    ("QUERY_NOT_FOUND", "查询结果不存在或已过期，请重新搜索", "Query results not found or expired, please search again"),
+    ("VALIDATION_ERROR", "请求参数无效", "Invalid request parameter"),
+    ("INVALID_QUERY", "查询参数无效", "Invalid query parameters"),
+    ("INVALID_BODY", "请求体无效", "Invalid request body"),
+    ("PAYLOAD_TOO_LARGE", "请求体过大", "Request body too large"),
+    ("HISTORY_DISABLED", "搜索历史未启用", "Search history is not enabled"),
+    ("HISTORY_SCOPE_REQUIRED", "需要绑定历史作用域的魔法链接", "A magic link bound to a history scope is required"),
+    ("HISTORY_ERROR", "搜索历史操作失败", "Search history operation failed"),
+    ("CLICK_TRACKING_DISABLED", "点击追踪未启用", "Click tracking is not enabled"),
+    ("INVALID_CLICK_LINK", "点击链接无效", "Invalid click link"),
+    ("PREVIEW_DISABLED", "结果预览未启用", "Result preview is not enabled"),
+    ("INVALID_PREVIEW_LINK", "预览链接无效", "Invalid preview link"),
+    ("PREVIEW_FAILED", "页面截图失败", "Failed to capture page preview"),
+    ("BOARDS_DISABLED", "RSS 榜单未启用", "RSS boards are not enabled"),
+    ("BOARD_NOT_FOUND", "榜单不存在", "Board not found"),
+    ("BOARD_ERROR", "榜单生成失败", "Failed to generate board"),
+    ("CACHE_UNAVAILABLE", "缓存不可用", "Cache unavailable"),
+    ("RSS_DISCOVERY_FAILED", "获取网站页面失败", "Failed to fetch the website"),
+    ("RSS_DISCOVERY_FORBIDDEN", "不允许访问非公网地址", "Access to non-public addresses is not allowed"),
+    ("RSS_CACHE_ERROR", "读取 RSS 缓存失败", "Failed to read the RSS cache"),
+    ("ENGINE_RELOAD_FAILED", "引擎脚本重新加载失败", "Failed to reload engine scripts"),
+    ("INVALID_SELFTEST_REQUEST", "自检查询或引擎无效", "Invalid self-test queries or engines"),
+    ("NOT_IMPLEMENTED", "功能尚未实现", "Not implemented yet"),
+    ("API_KEY_STORE_UNAVAILABLE", "API 密钥存储不可用", "API key store unavailable"),
+    ("API_KEY_STORE_ERROR", "API 密钥存储操作失败", "API key store operation failed"),
+    ("API_KEY_NOT_FOUND", "API 密钥不存在或已吊销", "API key not found or revoked"),
+    ("INVALID_API_KEY_NAME", "密钥名称不能为空", "Key name must not be empty"),
+    ("WEBHOOK_STORE_UNAVAILABLE", "webhook 存储不可用", "Webhook store unavailable"),
+    ("WEBHOOK_STORE_ERROR", "webhook 存储操作失败", "Webhook store operation failed"),
+    ("WEBHOOK_NOT_FOUND", "webhook 不存在", "Webhook not found"),
+    ("INVALID_WEBHOOK", "名称和来源目标不能为空", "Name and source targets must not be empty"),
+    ("INVALID_WEBHOOK_URL", "url 必须以 http:// 或 https:// 开头", "url must start with http:// or https://"),
+    ("ALERT_STORE_UNAVAILABLE", "保存的搜索存储不可用", "Saved search store unavailable"),
+    ("ALERT_STORE_ERROR", "保存的搜索存储操作失败", "Saved search store operation failed"),
+    ("ALERT_NOT_FOUND", "保存的搜索不存在", "Saved search not found"),
+    ("INVALID_ALERT_QUERY", "查询不能为空且不能超过长度限制", "Query must not be empty or exceed the length limit"),
+    ("INVALID_ALERT_ENGINES", "指定的引擎无效", "Invalid engines"),
+    (
+        "INVALID_ALERT_SCHEDULE",
+        "schedule 必须是不少于 60 秒的秒数或带单位的间隔（如 30m、6h、1d）",
+        "schedule must be at least 60 seconds, given in seconds or with a unit (e.g. 30m, 6h, 1d)",
+    ),
+];
+
+/// 查找错误代码在指定语言下的消息
+pub fn error_message(code: &str, locale: Locale) -> Option<&'static str> {
+    ERROR_MESSAGES.iter().find(|(c, _, _)| *c == code).map(|(_, zh, en)| match locale {
+        Locale::ZhCn => *zh,
+        Locale::En => *en,
+    })
+}
+
+/// 将错误消息本地化
+///
+/// 原始消息已经是目标语言时原样保留（处理器给出的消息通常更具体），否则使用目录中的消息；
+/// 返回 None 表示无需改写
+pub fn localize_error(code: &str, message: &str, locale: Locale) -> Option<&'static str> {
+    if Locale::of_text(message) == locale {
+        return None;
+    }
+    error_message(code, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_locale() {
+        assert_eq!(Locale::negotiate(None), Locale::ZhCn);
+        assert_eq!(Locale::negotiate(Some("en-US,en;q=0.9")), Locale::En);
+        assert_eq!(Locale::negotiate(Some("fr;q=1.0, zh-TW;q=0.5, en;q=0.8")), Locale::En);
+        assert_eq!(Locale::negotiate(Some("de, *;q=0.1")), Locale::ZhCn);
+        assert_eq!(Locale::negotiate(Some("en;q=0, zh")), Locale::ZhCn);
+        assert_eq!(Locale::negotiate_or(Some("fr"), Locale::En), Locale::En);
+    }
+
+    #[test]
+    fn test_localize_error() {
+        // 英文客户端得到目录中的英文消息
+        assert_eq!(localize_error("RATE_LIMIT_EXCEEDED", "请求过于频繁，请稍后再试", Locale::En), Some("Too many requests, please try again later"));
+        // 中文客户端得到中文消息，替换掉代码处写的英文
+        assert_eq!(localize_error("MAGIC_LINK_INVALID", "Magic link expired", Locale::ZhCn), Some("魔法链接无效、已过期或已使用"));
+        // 已是目标语言的具体消息保留
+        assert_eq!(localize_error("VALIDATION_ERROR", "query 不能为空", Locale::ZhCn), None);
+        // 未知代码不改写
+        assert_eq!(localize_error("SOMETHING_ELSE", "失败", Locale::En), None);
+
+        let codes: std::collections::HashSet<_> = ERROR_MESSAGES.iter().map(|(code, _, _)| code).collect();
+        assert_eq!(codes.len(), ERROR_MESSAGES.len());
+    }
+}
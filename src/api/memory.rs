@@ -0,0 +1,148 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 内存用量指标
+//!
+//! 汇总 RSS 缓存大小、引擎实例缓存条目数和分配器统计（启用 `jemalloc` 特性时），
+//! 供 `/api/metrics/realtime`、Prometheus 指标和 `GET /api/admin/heap` 使用。
+//! 未启用 `jemalloc` 时使用系统分配器，没有分配器统计。
+
+use metrics::gauge;
+use serde::{Deserialize, Serialize};
+
+use crate::api::on::ApiState;
+
+/// 使用 jemalloc 作为全局分配器
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+/// 分配器统计（字节）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocatorStats {
+    /// 分配器名称
+    pub allocator: String,
+    /// 应用已分配的字节数
+    pub allocated_bytes: u64,
+    /// 活跃页面占用的字节数（不小于 allocated）
+    pub active_bytes: u64,
+    /// 分配器元数据占用的字节数
+    pub metadata_bytes: u64,
+    /// 常驻物理内存的字节数
+    pub resident_bytes: u64,
+    /// 已映射的字节数
+    pub mapped_bytes: u64,
+    /// 已保留但未归还给操作系统的字节数
+    pub retained_bytes: u64,
+}
+
+/// 内存用量指标
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryMetrics {
+    /// 缓存的 RSS feed 数（RSS 缓存不可用时省略）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_cache_feeds: Option<u64>,
+    /// RSS 缓存的键值总字节数（RSS 缓存不可用时省略）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rss_cache_bytes: Option<u64>,
+    /// 引擎实例缓存的条目数
+    pub engine_cache_entries: u64,
+    /// 分配器统计（仅启用 `jemalloc` 特性时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocator: Option<AllocatorStats>,
+}
+
+/// 读取分配器统计（未启用 `jemalloc` 特性或读取失败时为 None）
+#[cfg(feature = "jemalloc")]
+pub fn allocator_stats() -> Option<AllocatorStats> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // jemalloc 缓存统计值，推进 epoch 后才会刷新
+    epoch::advance().ok()?;
+    Some(AllocatorStats {
+        allocator: "jemalloc".to_string(),
+        allocated_bytes: stats::allocated::read().ok()? as u64,
+        active_bytes: stats::active::read().ok()? as u64,
+        metadata_bytes: stats::metadata::read().ok()? as u64,
+        resident_bytes: stats::resident::read().ok()? as u64,
+        mapped_bytes: stats::mapped::read().ok()? as u64,
+        retained_bytes: stats::retained::read().ok()? as u64,
+    })
+}
+
+/// 读取分配器统计（未启用 `jemalloc` 特性或读取失败时为 None）
+#[cfg(not(feature = "jemalloc"))]
+pub fn allocator_stats() -> Option<AllocatorStats> {
+    None
+}
+
+/// jemalloc 的完整统计报告（`malloc_stats_print` 的 JSON 输出，含各 arena 和 size class）
+#[cfg(feature = "jemalloc")]
+pub fn allocator_report() -> Option<serde_json::Value> {
+    let mut options = tikv_jemalloc_ctl::stats_print::Options::default();
+    options.json_format = true;
+    let mut output = Vec::new();
+    tikv_jemalloc_ctl::stats_print::stats_print(&mut output, options).ok()?;
+    serde_json::from_slice(&output).ok()
+}
+
+/// jemalloc 的完整统计报告（未启用 `jemalloc` 特性时为 None）
+#[cfg(not(feature = "jemalloc"))]
+pub fn allocator_report() -> Option<serde_json::Value> {
+    None
+}
+
+/// 采集内存用量指标，同时更新对应的 Prometheus 指标
+pub async fn collect_memory_metrics(state: &ApiState) -> MemoryMetrics {
+    let (engine_cache_entries, _) = state.search.get_engine_cache_stats().await;
+    let mut memory = MemoryMetrics {
+        engine_cache_entries: engine_cache_entries as u64,
+        allocator: allocator_stats(),
+        ..Default::default()
+    };
+    gauge!("seesea_engine_cache_entries").set(engine_cache_entries as f64);
+
+    if let Some(rss) = &state.rss {
+        match rss.size() {
+            Ok(size) => {
+                memory.rss_cache_feeds = Some(size.feeds);
+                memory.rss_cache_bytes = Some(size.bytes);
+                gauge!("seesea_rss_cache_bytes").set(size.bytes as f64);
+            }
+            Err(e) => tracing::warn!("Failed to measure RSS cache size: {}", e),
+        }
+    }
+    if let Some(allocator) = &memory.allocator {
+        gauge!("seesea_allocator_allocated_bytes").set(allocator.allocated_bytes as f64);
+        gauge!("seesea_allocator_resident_bytes").set(allocator.resident_bytes as f64);
+    }
+    memory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocator_stats() {
+        let stats = allocator_stats();
+        assert_eq!(stats.is_some(), cfg!(feature = "jemalloc"));
+        if let Some(stats) = stats {
+            assert_eq!(stats.allocator, "jemalloc");
+            assert!(stats.allocated_bytes > 0);
+            assert!(stats.active_bytes >= stats.allocated_bytes);
+            assert!(allocator_report().is_some_and(|report| report.get("jemalloc").is_some()));
+        }
+    }
+}
@@ -16,13 +16,17 @@
 //!
 //! 提供实时服务器指标收集和导出功能
 
+use axum::{extract::{Request, State}, middleware::Next, response::Response};
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 use tokio::sync::RwLock;
 
+use super::memory::MemoryMetrics;
+
 /// 指标配置
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
@@ -71,6 +75,14 @@ pub struct RealtimeMetrics {
     
     /// 启动时间
     pub uptime_seconds: u64,
+
+    /// 正在处理的请求数
+    #[serde(default)]
+    pub in_flight_requests: u64,
+
+    /// 内存用量（RSS 缓存、引擎实例缓存和分配器统计，由指标处理器采集）
+    #[serde(default)]
+    pub memory: MemoryMetrics,
 }
 
 /// 指标收集器
@@ -83,6 +95,9 @@ pub struct MetricsCollector {
     
     /// 启动时间
     start_time: Instant,
+
+    /// 正在处理的请求数
+    in_flight: Arc<AtomicU64>,
     
     /// 配置
     config: MetricsConfig,
@@ -106,6 +121,27 @@ impl MetricsCollector {
             describe_counter!("seesea_ip_blocked", "Number of IP blocked requests");
             describe_gauge!("seesea_active_connections", "Current active connections");
             describe_histogram!("seesea_response_time_ms", "Response time in milliseconds");
+            describe_gauge!("seesea_requests_in_flight", "Requests currently being handled");
+
+            // 内存指标（在请求指标时采集）
+            describe_gauge!("seesea_rss_cache_bytes", "Total key and value bytes of the RSS cache");
+            describe_gauge!("seesea_engine_cache_entries", "Number of cached engine instances");
+            describe_gauge!("seesea_allocator_allocated_bytes", "Bytes allocated by the application (jemalloc)");
+            describe_gauge!("seesea_allocator_resident_bytes", "Bytes in physically resident pages (jemalloc)");
+            
+            // 引擎指标（由 SearchInterface 记录）
+            describe_counter!("seesea_engine_requests_total", "Engine requests by engine and outcome");
+            describe_histogram!("seesea_engine_latency_ms", "Engine request latency in milliseconds");
+            describe_counter!("seesea_engine_zero_results_total", "Engine responses with zero results");
+            describe_gauge!("seesea_engine_disabled", "Whether the engine is temporarily disabled (1) or not (0)");
+            
+            // 缓存指标（由 CacheManager 记录）
+            describe_counter!("seesea_cache_hits_total", "Cache hits by cache kind");
+            describe_counter!("seesea_cache_misses_total", "Cache misses by cache kind");
+            
+            // 出站 HTTP 指标（由 HttpClient 记录）
+            describe_counter!("seesea_http_responses_total", "Outbound HTTP responses by method and status code");
+            describe_counter!("seesea_http_errors_total", "Outbound HTTP request errors by method and kind");
             
             handle
         } else {
@@ -116,6 +152,7 @@ impl MetricsCollector {
             prometheus_handle,
             realtime_metrics: Arc::new(RwLock::new(RealtimeMetrics::default())),
             start_time: Instant::now(),
+            in_flight: Arc::new(AtomicU64::new(0)),
             config,
         }
     }
@@ -227,10 +264,23 @@ impl MetricsCollector {
         gauge!("seesea_active_connections").set(metrics.active_connections as f64);
     }
 
+    /// 开始处理一个请求，返回的守卫释放时计数减一
+    pub fn begin_request(&self) -> InFlightRequest {
+        let count = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+        gauge!("seesea_requests_in_flight").set(count as f64);
+        InFlightRequest { in_flight: self.in_flight.clone() }
+    }
+
+    /// 正在处理的请求数
+    pub fn in_flight_requests(&self) -> u64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
     /// 获取实时指标
     pub async fn get_realtime_metrics(&self) -> RealtimeMetrics {
         let mut metrics = self.realtime_metrics.read().await.clone();
         metrics.uptime_seconds = self.start_time.elapsed().as_secs();
+        metrics.in_flight_requests = self.in_flight_requests();
         metrics
     }
 
@@ -246,6 +296,28 @@ impl MetricsCollector {
     }
 }
 
+/// 正在处理的请求守卫
+pub struct InFlightRequest {
+    in_flight: Arc<AtomicU64>,
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        let count = self.in_flight.fetch_sub(1, Ordering::Relaxed).saturating_sub(1);
+        gauge!("seesea_requests_in_flight").set(count as f64);
+    }
+}
+
+/// 统计正在处理的请求数（流式响应在响应头发出后即视为处理完毕）
+pub async fn in_flight_middleware(
+    State(metrics): State<Arc<MetricsCollector>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let _in_flight = metrics.begin_request();
+    next.run(request).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,6 +359,18 @@ mod tests {
         assert_eq!(metrics.active_connections, 1);
     }
 
+    #[tokio::test]
+    async fn test_in_flight_requests() {
+        let collector = MetricsCollector::new(MetricsConfig::default());
+        let first = collector.begin_request();
+        let second = collector.begin_request();
+        assert_eq!(collector.get_realtime_metrics().await.in_flight_requests, 2);
+
+        drop(first);
+        drop(second);
+        assert_eq!(collector.in_flight_requests(), 0);
+    }
+
     #[tokio::test]
     async fn test_metrics_reset() {
         let config = MetricsConfig::default();
@@ -21,6 +21,7 @@ use axum::{
     http::{header::AUTHORIZATION, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
@@ -28,6 +29,9 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::api::secrets::SecretString;
+use crate::cache::ApiKeyStore;
+
 /// 认证配置
 #[derive(Debug, Clone)]
 pub struct AuthConfig {
@@ -35,23 +39,21 @@ pub struct AuthConfig {
     pub enabled: bool,
     
     /// JWT 密钥
-    pub jwt_secret: String,
+    pub jwt_secret: SecretString,
     
     /// JWT 过期时间（秒）
     pub jwt_expiration: u64,
     
-    /// API 密钥列表
-    pub api_keys: Vec<String>,
+    /// API 密钥列表（静态配置，另可通过 [`AuthState::with_key_store`] 使用持久化密钥）
+    pub api_keys: Vec<SecretString>,
 }
 
 impl Default for AuthConfig {
     fn default() -> Self {
-        // Warning: Default secret should be changed in production
-        tracing::warn!("Using default JWT secret - CHANGE THIS IN PRODUCTION!");
-        
+        // 随机生成的密钥在重启后失效，生产环境应通过 `SecretsConfig` 配置
         Self {
             enabled: false,
-            jwt_secret: format!("jwt_default_secret_{}", Uuid::new_v4()),
+            jwt_secret: SecretString::new(format!("jwt_default_secret_{}", Uuid::new_v4())),
             jwt_expiration: 3600, // 1 hour
             api_keys: Vec::new(),
         }
@@ -77,21 +79,35 @@ pub struct AuthState {
     encoding_key: EncodingKey,
     /// 解码密钥
     decoding_key: DecodingKey,
+    /// 持久化 API 密钥存储
+    key_store: Option<Arc<ApiKeyStore>>,
 }
 
 impl AuthState {
     /// 创建新的认证状态
     pub fn new(config: AuthConfig) -> Self {
-        let encoding_key = EncodingKey::from_secret(config.jwt_secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_bytes());
+        let encoding_key = EncodingKey::from_secret(config.jwt_secret.expose().as_bytes());
+        let decoding_key = DecodingKey::from_secret(config.jwt_secret.expose().as_bytes());
         
         Self {
             config,
             encoding_key,
             decoding_key,
+            key_store: None,
         }
     }
 
+    /// 获取配置
+    pub fn config(&self) -> &AuthConfig {
+        &self.config
+    }
+
+    /// 设置持久化 API 密钥存储
+    pub fn with_key_store(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.key_store = Some(store);
+        self
+    }
+
     /// 生成JWT令牌
     pub fn generate_token(&self, subject: String) -> Result<String, jsonwebtoken::errors::Error> {
         let now = SystemTime::now()
@@ -121,7 +137,31 @@ impl AuthState {
 
     /// 验证API密钥
     pub fn verify_api_key(&self, api_key: &str) -> bool {
-        self.config.api_keys.iter().any(|k| k == api_key)
+        self.api_key_subject(api_key).is_some()
+    }
+
+    /// 验证API密钥并返回主题
+    ///
+    /// 静态配置的密钥主题为 `api_key`，持久化密钥为 `api_key:<id>`（同时记录使用次数）
+    fn api_key_subject(&self, api_key: &str) -> Option<String> {
+        if self.config.api_keys.iter().any(|k| k.expose() == api_key) {
+            return Some("api_key".to_string());
+        }
+
+        let store = self.key_store.as_ref()?;
+        match store.verify(api_key) {
+            Ok(Some(record)) => {
+                if let Err(e) = store.record_usage(&record.key_hash) {
+                    tracing::warn!("Failed to record API key usage: {}", e);
+                }
+                Some(format!("api_key:{}", record.id))
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to verify API key: {}", e);
+                None
+            }
+        }
     }
 
     /// 验证认证头
@@ -134,14 +174,14 @@ impl AuthState {
 
         // API Key
         if let Some(api_key) = auth_header.strip_prefix("ApiKey ") {
-            if self.verify_api_key(api_key) {
+            if let Some(sub) = self.api_key_subject(api_key) {
                 // 为API Key创建虚拟Claims
                 let now = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .unwrap()
                     .as_secs();
                 return Ok(Claims {
-                    sub: "api_key".to_string(),
+                    sub,
                     exp: now + 3600,
                     iat: now,
                 });
@@ -178,10 +218,11 @@ pub async fn jwt_auth_middleware(
             Err(e) => {
                 return (
                     StatusCode::UNAUTHORIZED,
-                    serde_json::json!({
+                    Json(serde_json::json!({
                         "code": "AUTH_FAILED",
-                        "message": format!("认证失败: {}", e)
-                    }).to_string()
+                        "message": "认证失败",
+                        "details": e.to_string()
+                    }))
                 ).into_response();
             }
         }
@@ -190,10 +231,10 @@ pub async fn jwt_auth_middleware(
     // 没有Authorization头
     (
         StatusCode::UNAUTHORIZED,
-        serde_json::json!({
+        Json(serde_json::json!({
             "code": "AUTH_REQUIRED",
             "message": "需要认证"
-        }).to_string()
+        }))
     ).into_response()
 }
 
@@ -213,7 +254,7 @@ mod tests {
     fn test_jwt_generation_and_verification() {
         let config = AuthConfig {
             enabled: true,
-            jwt_secret: "test_secret".to_string(),
+            jwt_secret: "test_secret".into(),
             jwt_expiration: 3600,
             api_keys: vec![],
         };
@@ -229,9 +270,9 @@ mod tests {
     fn test_api_key_verification() {
         let config = AuthConfig {
             enabled: true,
-            jwt_secret: "test_secret".to_string(),
+            jwt_secret: "test_secret".into(),
             jwt_expiration: 3600,
-            api_keys: vec!["test_key".to_string(), "another_key".to_string()],
+            api_keys: vec!["test_key".into(), "another_key".into()],
         };
         let state = AuthState::new(config);
 
@@ -244,9 +285,9 @@ mod tests {
     fn test_auth_header_verification() {
         let config = AuthConfig {
             enabled: true,
-            jwt_secret: "test_secret".to_string(),
+            jwt_secret: "test_secret".into(),
             jwt_expiration: 3600,
-            api_keys: vec!["valid_key".to_string()],
+            api_keys: vec!["valid_key".into()],
         };
         let state = AuthState::new(config);
 
@@ -267,5 +308,29 @@ mod tests {
         let auth_header = "Invalid format";
         assert!(state.verify_auth_header(auth_header).is_err());
     }
+
+    #[test]
+    fn test_persistent_api_key() {
+        use crate::cache::CacheManager;
+
+        let store = Arc::new(ApiKeyStore::new(
+            CacheManager::temporary(),
+        ));
+        let (record, key) = store.create("auth_test", None).unwrap();
+        let state = AuthState::new(AuthConfig {
+            enabled: true,
+            jwt_secret: "test_secret".into(),
+            jwt_expiration: 3600,
+            api_keys: vec![],
+        })
+        .with_key_store(store.clone());
+
+        let claims = state.verify_auth_header(&format!("ApiKey {}", key)).unwrap();
+        assert_eq!(claims.sub, format!("api_key:{}", record.id));
+        assert_eq!(store.verify(&key).unwrap().unwrap().total_requests, 1);
+
+        store.revoke(&record.id).unwrap();
+        assert!(state.verify_auth_header(&format!("ApiKey {}", key)).is_err());
+    }
 }
 
@@ -0,0 +1,298 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 查询成本预算中间件
+//!
+//! 每次搜索的成本为实际请求的引擎数 × 页数，命中缓存的响应和在结果中细化搜索不计成本。
+//! 携带 API 密钥或魔法链接令牌的请求按调用方累计当天（UTC）的成本，
+//! 达到运营方配置的每日预算后返回 429，次日零点重置。匿名请求只受限流约束。
+
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Days, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::magiclink::MagicLinkState;
+use super::ratelimit::magic_token_in_query;
+use crate::api::secrets::SecretString;
+use crate::api::types::ApiErrorResponse;
+use crate::cache::{hash_api_key, ApiKeyStore, UsageStore};
+
+/// 用量查询路径（超出预算后仍可访问）
+pub const USAGE_PATH: &str = "/api/usage";
+
+/// 查询成本预算配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryBudgetConfig {
+    /// 是否启用（默认关闭，关闭时不记录用量也不限制）
+    pub enabled: bool,
+    /// API 密钥的默认每日预算（None 表示不限）
+    pub api_key_daily: Option<u64>,
+    /// 魔法链接令牌的默认每日预算（生成令牌时可单独指定）
+    pub magic_link_daily: Option<u64>,
+    /// 按 API 密钥 ID 配置的每日预算，覆盖默认值，用于分级访问
+    pub api_key_tiers: HashMap<String, u64>,
+}
+
+/// 计算查询成本：请求的引擎数 × 页数
+pub fn query_cost(engines: usize, pages: usize) -> u64 {
+    (engines * pages) as u64
+}
+
+/// 处理器产生的查询成本
+///
+/// 由搜索处理器写入响应扩展，预算中间件据此记账
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryCost(pub u64);
+
+/// 计费的调用方
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetSubject {
+    /// 用量存储中的键（`key:` 或 `token:` 加哈希，不含明文）
+    pub id: String,
+    /// 调用方类型：`api_key` 或 `magic_link`
+    pub kind: &'static str,
+    /// 每日预算（None 表示不限）
+    pub daily_budget: Option<u64>,
+}
+
+/// 查询成本预算状态
+pub struct QueryBudgetState {
+    config: QueryBudgetConfig,
+    /// 用量存储（缓存不可用时为 None，此时不记账）
+    usage: Option<Arc<UsageStore>>,
+    /// 持久化 API 密钥存储
+    key_store: Option<Arc<ApiKeyStore>>,
+    /// 静态配置的 API 密钥（哈希）
+    static_key_hashes: HashSet<String>,
+    /// 魔法链接状态
+    magic_links: Option<Arc<MagicLinkState>>,
+}
+
+impl QueryBudgetState {
+    /// 创建预算状态
+    pub fn new(config: QueryBudgetConfig) -> Self {
+        Self {
+            config,
+            usage: None,
+            key_store: None,
+            static_key_hashes: HashSet::new(),
+            magic_links: None,
+        }
+    }
+
+    /// 设置用量存储
+    pub fn with_usage_store(mut self, usage: Arc<UsageStore>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// 设置持久化 API 密钥存储
+    pub fn with_key_store(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.key_store = Some(store);
+        self
+    }
+
+    /// 设置静态配置的 API 密钥（与 `AuthConfig.api_keys` 一致）
+    pub fn with_static_api_keys(mut self, keys: &[SecretString]) -> Self {
+        self.static_key_hashes = keys.iter().map(|k| hash_api_key(k.expose())).collect();
+        self
+    }
+
+    /// 设置魔法链接状态
+    pub fn with_magic_links(mut self, magic_links: Arc<MagicLinkState>) -> Self {
+        self.magic_links = Some(magic_links);
+        self
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &QueryBudgetConfig {
+        &self.config
+    }
+
+    /// 获取用量存储
+    pub fn usage(&self) -> Option<&Arc<UsageStore>> {
+        self.usage.as_ref()
+    }
+
+    /// 确定请求的计费调用方（只认有效的 API 密钥和魔法链接令牌）
+    pub fn resolve(&self, headers: &HeaderMap, query: Option<&str>) -> Option<BudgetSubject> {
+        if let Some(api_key) = headers
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("ApiKey "))
+        {
+            let hash = hash_api_key(api_key);
+            if self.static_key_hashes.contains(&hash) {
+                return Some(BudgetSubject {
+                    id: format!("key:{}", hash),
+                    kind: "api_key",
+                    daily_budget: self.config.api_key_daily,
+                });
+            }
+            if let Some(record) = self.key_store.as_ref().and_then(|s| s.verify(api_key).ok().flatten()) {
+                return Some(BudgetSubject {
+                    id: format!("key:{}", record.key_hash),
+                    kind: "api_key",
+                    daily_budget: self.config.api_key_tiers.get(&record.id).copied().or(self.config.api_key_daily),
+                });
+            }
+        }
+
+        let magic_links = self.magic_links.as_ref()?;
+        let token = query.and_then(magic_token_in_query)?;
+        magic_links.is_token_active(&token).then(|| BudgetSubject {
+            id: format!("token:{}", hash_api_key(&token)),
+            kind: "magic_link",
+            daily_budget: magic_links.token_budget(&token).or(self.config.magic_link_daily),
+        })
+    }
+}
+
+/// 下一个 UTC 零点（预算重置时间）
+pub fn budget_reset_at() -> DateTime<Utc> {
+    let tomorrow = Utc::now().date_naive().checked_add_days(Days::new(1)).unwrap_or_default();
+    tomorrow.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// 查询成本预算中间件
+pub async fn query_budget_middleware(
+    State(state): State<Arc<QueryBudgetState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !state.config.enabled {
+        return next.run(req).await;
+    }
+    let (Some(usage), Some(subject)) = (state.usage.clone(), state.resolve(req.headers(), req.uri().query())) else {
+        return next.run(req).await;
+    };
+
+    // 超出预算后只能查询用量
+    if let Some(budget) = subject.daily_budget
+        && req.uri().path() != USAGE_PATH
+    {
+        match usage.today(&subject.id) {
+            Ok(today) if today.cost >= budget => return budget_exceeded_response(budget),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to read query usage: {}", e),
+        }
+    }
+
+    let mut response = next.run(req).await;
+    if let Some(QueryCost(cost)) = response.extensions().get::<QueryCost>().copied() {
+        if let Err(e) = usage.record(&subject.id, cost) {
+            tracing::warn!("Failed to record query usage: {}", e);
+        }
+        response.headers_mut().insert("x-query-cost", HeaderValue::from(cost));
+    }
+    response
+}
+
+/// 创建超出预算响应
+fn budget_exceeded_response(budget: u64) -> Response {
+    let error = ApiErrorResponse {
+        code: "QUERY_BUDGET_EXCEEDED".to_string(),
+        message: "今日查询预算已用完".to_string(),
+        details: Some(format!("daily budget: {}, resets at: {}", budget, budget_reset_at().to_rfc3339())),
+    };
+    let retry_after = (budget_reset_at() - Utc::now()).num_seconds().max(1) as u64;
+    let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(error)).into_response();
+    response.headers_mut().insert("Retry-After", HeaderValue::from(retry_after));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheManager;
+    use super::super::magiclink::{MagicLinkConfig, MagicLinkOptions};
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_budget_enforced_per_subject() {
+        let manager = CacheManager::temporary();
+        let keys = Arc::new(ApiKeyStore::new(manager.clone()));
+        let (gold, gold_key) = keys.create("gold", None).unwrap();
+        let (_, basic_key) = keys.create("basic", None).unwrap();
+        let magic_links = Arc::new(MagicLinkState::new(MagicLinkConfig::default()));
+        let token = magic_links.generate_token_with_options(MagicLinkOptions {
+            daily_budget: Some(2),
+            reusable: true,
+            ..MagicLinkOptions::new("budget")
+        });
+
+        let state = Arc::new(
+            QueryBudgetState::new(QueryBudgetConfig {
+                enabled: true,
+                api_key_daily: Some(3),
+                api_key_tiers: HashMap::from([(gold.id.clone(), 100)]),
+                ..Default::default()
+            })
+            .with_usage_store(Arc::new(UsageStore::new(manager)))
+            .with_key_store(keys)
+            .with_magic_links(magic_links),
+        );
+
+        // 每次搜索请求 2 个引擎
+        let router = Router::new()
+            .route("/api/search", get(|| async {
+                let mut response = StatusCode::OK.into_response();
+                response.extensions_mut().insert(QueryCost(query_cost(2, 1)));
+                response
+            }))
+            .route(USAGE_PATH, get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn_with_state(state.clone(), query_budget_middleware));
+        let call = |uri: String, key: Option<&str>| {
+            let router = router.clone();
+            let mut request = Request::get(uri);
+            if let Some(key) = key {
+                request = request.header(AUTHORIZATION, format!("ApiKey {}", key));
+            }
+            let request = request.body(Body::empty()).unwrap();
+            async move { router.oneshot(request).await.unwrap() }
+        };
+
+        let response = call("/api/search".to_string(), Some(&basic_key)).await;
+        assert_eq!(response.headers()["x-query-cost"], "2");
+        assert_eq!(call("/api/search".to_string(), Some(&basic_key)).await.status(), StatusCode::OK);
+        let response = call("/api/search".to_string(), Some(&basic_key)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+        assert_eq!(call(USAGE_PATH.to_string(), Some(&basic_key)).await.status(), StatusCode::OK);
+
+        // 分级密钥使用自己的预算，匿名请求不计费
+        for _ in 0..3 {
+            assert_eq!(call("/api/search".to_string(), Some(&gold_key)).await.status(), StatusCode::OK);
+        }
+        assert_eq!(call("/api/search".to_string(), None).await.status(), StatusCode::OK);
+
+        let uri = format!("/api/search?magic_token={}", token);
+        assert_eq!(call(uri.clone(), None).await.status(), StatusCode::OK);
+        assert_eq!(call(uri, None).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let subject = state.resolve(&HeaderMap::new(), Some(&format!("magic_token={}", token))).unwrap();
+        assert_eq!((subject.kind, subject.daily_budget), ("magic_link", Some(2)));
+        assert_eq!(state.usage().unwrap().today(&subject.id).unwrap().cost, 2);
+    }
+}
@@ -14,19 +14,54 @@
 
 //! 熔断器中间件
 //!
-//! 提供熔断保护功能
+//! 提供熔断保护功能。熔断器按路由组划分（见 [`route_group`]），
+//! 某一组（如抓取失效订阅源的 RSS 接口）连续失败只会熔断该组，不影响其他接口
 
 use axum::{
     extract::Request,
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
+use dashmap::DashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+use super::shared::SharedStateStore;
+
+/// 共享状态中的熔断标记键前缀（`<前缀>:<路由组>`，存在即表示集群内该组已熔断）
+const SHARED_OPEN_KEY: &str = "seesea:circuit:open";
+/// 共享状态中的连续失败计数键前缀
+const SHARED_FAILURES_KEY: &str = "seesea:circuit:failures";
+
+/// 未指定路由组时使用的组名
+pub const DEFAULT_ROUTE_GROUP: &str = "default";
+
+/// 根据请求路径确定熔断器所属的路由组
+///
+/// 搜索、RSS、新闻、答案生成、LLM 工具各自独立熔断，其余接口共用 `default` 组
+pub fn route_group(path: &str) -> &'static str {
+    let matches = |prefix: &str| {
+        path == prefix || path.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+    };
+    if matches("/api/search") || matches("/search") {
+        "search"
+    } else if matches("/api/rss") {
+        "rss"
+    } else if matches("/api/news") {
+        "news"
+    } else if matches("/api/answer") {
+        "answer"
+    } else if matches("/v1/tools") {
+        "tools"
+    } else {
+        DEFAULT_ROUTE_GROUP
+    }
+}
+
 /// 熔断器状态
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CircuitState {
@@ -76,8 +111,10 @@ impl Default for CircuitBreakerConfig {
     }
 }
 
-/// 熔断器状态管理
+/// 熔断器状态管理（单个路由组）
 pub struct CircuitBreakerState {
+    /// 路由组名称
+    group: String,
     /// 当前状态
     state: AtomicU8,
     /// 失败计数
@@ -88,20 +125,43 @@ pub struct CircuitBreakerState {
     last_state_change: Arc<RwLock<Instant>>,
     /// 配置
     config: CircuitBreakerConfig,
+    /// 共享状态（设置后失败计数和熔断在所有实例之间生效）
+    shared_store: Option<Arc<dyn SharedStateStore>>,
 }
 
 impl CircuitBreakerState {
-    /// 创建新的熔断器状态
+    /// 创建新的熔断器状态（属于 `default` 路由组）
     pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self::for_group(DEFAULT_ROUTE_GROUP, config)
+    }
+
+    /// 创建指定路由组的熔断器状态
+    pub fn for_group(group: impl Into<String>, config: CircuitBreakerConfig) -> Self {
         Self {
+            group: group.into(),
             state: AtomicU8::new(CircuitState::Closed as u8),
             failure_count: AtomicU64::new(0),
             success_count: AtomicU64::new(0),
             last_state_change: Arc::new(RwLock::new(Instant::now())),
             config,
+            shared_store: None,
         }
     }
 
+    /// 设置共享状态，使多个实例共用失败计数和熔断状态
+    ///
+    /// 任一实例熔断后，其他实例在超时时间内同样拒绝请求；半开探测由熔断的实例执行。
+    /// 共享状态不可用时回退到本实例的计数
+    pub fn with_shared_store(mut self, store: Arc<dyn SharedStateStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// 路由组名称
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
     /// 获取当前状态
     pub fn get_state(&self) -> CircuitState {
         CircuitState::from(self.state.load(Ordering::SeqCst))
@@ -119,6 +179,11 @@ impl CircuitBreakerState {
             CircuitState::Closed => {
                 // 重置失败计数
                 self.failure_count.store(0, Ordering::SeqCst);
+                if let Some(store) = &self.shared_store
+                    && let Err(e) = store.delete(&self.shared_key(SHARED_FAILURES_KEY)).await
+                {
+                    tracing::warn!("重置共享熔断计数失败: {}", e);
+                }
             }
             _ => {}
         }
@@ -128,7 +193,17 @@ impl CircuitBreakerState {
     pub async fn record_failure(&self) {
         match self.get_state() {
             CircuitState::Closed => {
-                let count = self.failure_count.fetch_add(1, Ordering::SeqCst) + 1;
+                let local = self.failure_count.fetch_add(1, Ordering::SeqCst) + 1;
+                let count = match &self.shared_store {
+                    Some(store) => match store.increment(&self.shared_key(SHARED_FAILURES_KEY), self.open_duration()).await {
+                        Ok(count) => count,
+                        Err(e) => {
+                            tracing::warn!("共享熔断计数不可用，使用本实例计数: {}", e);
+                            local
+                        }
+                    },
+                    None => local,
+                };
                 if count >= self.config.failure_threshold {
                     self.transition_to_open().await;
                 }
@@ -143,7 +218,7 @@ impl CircuitBreakerState {
     /// 检查是否允许请求
     pub async fn allow_request(&self) -> bool {
         match self.get_state() {
-            CircuitState::Closed => true,
+            CircuitState::Closed => !self.shared_open().await,
             CircuitState::Open => {
                 // 检查是否超时，可以尝试半开
                 let last_change = self.last_state_change.read().await;
@@ -159,13 +234,54 @@ impl CircuitBreakerState {
         }
     }
 
+    /// 该路由组在共享状态中的键
+    fn shared_key(&self, prefix: &str) -> String {
+        format!("{}:{}", prefix, self.group)
+    }
+
+    /// 熔断持续时间
+    fn open_duration(&self) -> Duration {
+        Duration::from_secs(self.config.timeout.max(1))
+    }
+
+    /// 其他实例是否已在共享状态中熔断
+    async fn shared_open(&self) -> bool {
+        let Some(store) = &self.shared_store else {
+            return false;
+        };
+        match store.get(&self.shared_key(SHARED_OPEN_KEY)).await {
+            Ok(value) => value.is_some(),
+            Err(e) => {
+                tracing::warn!("读取共享熔断状态失败: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 同步熔断状态到共享状态
+    async fn publish_state(&self, open: bool) {
+        let Some(store) = &self.shared_store else {
+            return;
+        };
+        let open_key = self.shared_key(SHARED_OPEN_KEY);
+        let result = if open {
+            store.set(&open_key, "1", self.open_duration()).await
+        } else {
+            store.delete(&open_key).await
+        };
+        if let Err(e) = result.and(store.delete(&self.shared_key(SHARED_FAILURES_KEY)).await) {
+            tracing::warn!("同步共享熔断状态失败: {}", e);
+        }
+    }
+
     /// 转换到关闭状态
     async fn transition_to_closed(&self) {
         self.state.store(CircuitState::Closed as u8, Ordering::SeqCst);
         self.failure_count.store(0, Ordering::SeqCst);
         self.success_count.store(0, Ordering::SeqCst);
         *self.last_state_change.write().await = Instant::now();
-        tracing::info!("Circuit breaker transitioned to CLOSED state");
+        self.publish_state(false).await;
+        tracing::info!("Circuit breaker '{}' transitioned to CLOSED state", self.group);
     }
 
     /// 转换到打开状态
@@ -174,7 +290,8 @@ impl CircuitBreakerState {
         self.failure_count.store(0, Ordering::SeqCst);
         self.success_count.store(0, Ordering::SeqCst);
         *self.last_state_change.write().await = Instant::now();
-        tracing::warn!("Circuit breaker transitioned to OPEN state");
+        self.publish_state(true).await;
+        tracing::warn!("Circuit breaker '{}' transitioned to OPEN state", self.group);
     }
 
     /// 转换到半开状态
@@ -182,28 +299,81 @@ impl CircuitBreakerState {
         self.state.store(CircuitState::HalfOpen as u8, Ordering::SeqCst);
         self.success_count.store(0, Ordering::SeqCst);
         *self.last_state_change.write().await = Instant::now();
-        tracing::info!("Circuit breaker transitioned to HALF-OPEN state");
+        tracing::info!("Circuit breaker '{}' transitioned to HALF-OPEN state", self.group);
+    }
+}
+
+/// 按路由组划分的熔断器集合
+pub struct CircuitBreakerRegistry {
+    /// 各路由组的熔断器（首次请求时创建）
+    breakers: DashMap<&'static str, Arc<CircuitBreakerState>>,
+    /// 所有路由组共用的配置
+    config: CircuitBreakerConfig,
+    /// 共享状态
+    shared_store: Option<Arc<dyn SharedStateStore>>,
+}
+
+impl CircuitBreakerRegistry {
+    /// 创建熔断器集合
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            breakers: DashMap::new(),
+            config,
+            shared_store: None,
+        }
+    }
+
+    /// 设置共享状态，各路由组在共享存储中使用独立的键
+    pub fn with_shared_store(mut self, store: Arc<dyn SharedStateStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// 获取（必要时创建）路由组的熔断器
+    pub fn breaker(&self, group: &'static str) -> Arc<CircuitBreakerState> {
+        self.breakers
+            .entry(group)
+            .or_insert_with(|| {
+                let mut breaker = CircuitBreakerState::for_group(group, self.config.clone());
+                if let Some(store) = &self.shared_store {
+                    breaker = breaker.with_shared_store(store.clone());
+                }
+                Arc::new(breaker)
+            })
+            .clone()
+    }
+
+    /// 各路由组当前的熔断状态（按组名排序）
+    pub fn states(&self) -> Vec<(String, CircuitState)> {
+        let mut states: Vec<_> = self.breakers
+            .iter()
+            .map(|entry| (entry.key().to_string(), entry.value().get_state()))
+            .collect();
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+        states
     }
 }
 
 /// 熔断器中间件
 pub async fn circuit_breaker_middleware(
-    axum::extract::State(state): axum::extract::State<Arc<CircuitBreakerState>>,
+    axum::extract::State(registry): axum::extract::State<Arc<CircuitBreakerRegistry>>,
     req: Request,
     next: Next,
 ) -> Response {
-    if !state.config.enabled {
+    if !registry.config.enabled {
         return next.run(req).await;
     }
+    let state = registry.breaker(route_group(req.uri().path()));
 
     // 检查是否允许请求
     if !state.allow_request().await {
         return (
             StatusCode::SERVICE_UNAVAILABLE,
-            serde_json::json!({
+            Json(serde_json::json!({
                 "code": "CIRCUIT_BREAKER_OPEN",
-                "message": "服务暂时不可用，请稍后再试"
-            }).to_string()
+                "message": "服务暂时不可用，请稍后再试",
+                "details": format!("route group: {}", state.group())
+            }))
         ).into_response();
     }
 
@@ -263,4 +433,69 @@ mod tests {
         // 打开状态不允许请求
         assert!(!state.allow_request().await);
     }
+
+    #[tokio::test]
+    async fn test_shared_circuit_breaker_trips_all_instances() {
+        use super::super::shared::MemoryStateStore;
+
+        let store: Arc<dyn SharedStateStore> = Arc::new(MemoryStateStore::new());
+        let config = CircuitBreakerConfig {
+            failure_threshold: 3,
+            ..Default::default()
+        };
+        let first = CircuitBreakerState::new(config.clone()).with_shared_store(store.clone());
+        let second = CircuitBreakerState::new(config).with_shared_store(store.clone());
+
+        // 失败计数在实例之间累加
+        first.record_failure().await;
+        second.record_failure().await;
+        assert!(first.allow_request().await);
+        first.record_failure().await;
+        assert_eq!(first.get_state(), CircuitState::Open);
+
+        // 未熔断的实例同样拒绝请求
+        assert_eq!(second.get_state(), CircuitState::Closed);
+        assert!(!second.allow_request().await);
+
+        // 熔断的实例恢复后清除共享标记
+        first.transition_to_closed().await;
+        assert!(second.allow_request().await);
+    }
+
+    #[test]
+    fn test_route_group() {
+        assert_eq!(route_group("/api/search"), "search");
+        assert_eq!(route_group("/search"), "search");
+        assert_eq!(route_group("/api/rss/fetch"), "rss");
+        assert_eq!(route_group("/api/news"), "news");
+        assert_eq!(route_group("/api/answer"), "answer");
+        assert_eq!(route_group("/v1/tools/web_search"), "tools");
+        assert_eq!(route_group("/api/searchx"), DEFAULT_ROUTE_GROUP);
+        assert_eq!(route_group("/api/health"), DEFAULT_ROUTE_GROUP);
+    }
+
+    #[tokio::test]
+    async fn test_registry_isolates_route_groups() {
+        use super::super::shared::MemoryStateStore;
+
+        let store: Arc<dyn SharedStateStore> = Arc::new(MemoryStateStore::new());
+        let registry = CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            ..Default::default()
+        })
+        .with_shared_store(store);
+
+        let rss = registry.breaker("rss");
+        rss.record_failure().await;
+        rss.record_failure().await;
+        assert!(!rss.allow_request().await);
+
+        // 其他路由组（包括共享状态中的标记）不受影响
+        assert!(registry.breaker("search").allow_request().await);
+        assert!(Arc::ptr_eq(&rss, &registry.breaker("rss")));
+        assert_eq!(
+            registry.states(),
+            vec![("rss".to_string(), CircuitState::Open), ("search".to_string(), CircuitState::Closed)]
+        );
+    }
 }
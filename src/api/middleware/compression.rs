@@ -0,0 +1,93 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 响应压缩中间件
+//!
+//! 基于 tower-http 的 [`CompressionLayer`]，按客户端的 `Accept-Encoding` 使用 gzip 或 Brotli 压缩响应体。
+//! 100 条以上结果的搜索响应通常能压缩 5-10 倍；小响应、图片和 SSE 流不压缩。
+
+use tower_http::compression::predicate::{And, DefaultPredicate, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+
+/// 压缩判断条件：默认条件（排除图片、gRPC 和 SSE）且响应体超过最小大小
+pub type CompressionPredicate = And<DefaultPredicate, SizeAbove>;
+
+/// 响应压缩配置
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 最小压缩大小（字节），更小的响应原样返回
+    pub min_size: u16,
+    /// 是否允许 gzip
+    pub gzip: bool,
+    /// 是否允许 Brotli
+    pub brotli: bool,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_size: 1024,
+            gzip: true,
+            brotli: true,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// 关闭压缩
+    pub fn disabled() -> Self {
+        Self { enabled: false, ..Default::default() }
+    }
+
+    /// 创建压缩层（未启用时不协商任何编码，响应原样返回）
+    pub fn layer(&self) -> CompressionLayer<CompressionPredicate> {
+        CompressionLayer::new()
+            .gzip(self.enabled && self.gzip)
+            .br(self.enabled && self.brotli)
+            .compress_when(DefaultPredicate::new().and(SizeAbove::new(self.min_size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{header, Request}, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn content_encoding(config: &CompressionConfig, accept: &str, body: &str) -> Option<String> {
+        let body = body.to_string();
+        let app = Router::new().route("/", get(move || async move { body })).layer(config.layer());
+        let request = Request::builder().uri("/").header(header::ACCEPT_ENCODING, accept).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        response.headers().get(header::CONTENT_ENCODING).map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_compression_negotiation() {
+        let large = &"result ".repeat(1000);
+        let config = CompressionConfig::default();
+        assert_eq!(content_encoding(&config, "gzip", large).await.as_deref(), Some("gzip"));
+        assert_eq!(content_encoding(&config, "br, gzip", large).await.as_deref(), Some("br"));
+        assert_eq!(content_encoding(&config, "identity", large).await, None);
+        // 小于最小大小的响应不压缩
+        assert_eq!(content_encoding(&config, "gzip", "small").await, None);
+
+        let gzip_only = CompressionConfig { brotli: false, ..Default::default() };
+        assert_eq!(content_encoding(&gzip_only, "br, gzip", large).await.as_deref(), Some("gzip"));
+        assert_eq!(content_encoding(&CompressionConfig::disabled(), "gzip", large).await, None);
+    }
+}
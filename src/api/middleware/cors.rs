@@ -14,21 +14,240 @@
 
 //! CORS 中间件
 //!
-//! 处理跨域资源共享 (CORS)
+//! 处理跨域资源共享 (CORS)。允许的源支持精确列表和正则表达式，
+//! 并可在运行时重新加载；方法、头部和凭证在构建路由器时确定。
 
-use axum::http::{header, Method};
-use tower_http::cors::{CorsLayer, Any};
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use regex::Regex;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders, Any};
+
+/// 通配符
+const WILDCARD: &str = "*";
+
+/// CORS 配置
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// 是否启用（关闭时不返回任何 CORS 头，浏览器将拒绝跨域请求）
+    pub enabled: bool,
+    /// 允许的源（`*` 表示任意源）
+    pub allowed_origins: Vec<String>,
+    /// 允许的源正则表达式（匹配完整的 Origin 头）
+    pub allowed_origin_patterns: Vec<String>,
+    /// 允许的方法（`*` 表示任意方法）
+    pub allowed_methods: Vec<String>,
+    /// 允许的请求头（`*` 表示任意请求头）
+    pub allowed_headers: Vec<String>,
+    /// 暴露给浏览器的响应头
+    pub exposed_headers: Vec<String>,
+    /// 是否允许携带凭证
+    pub allow_credentials: bool,
+    /// 预检请求缓存时间（秒）
+    pub max_age_secs: Option<u64>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_origins: vec![WILDCARD.to_string()],
+            allowed_origin_patterns: Vec::new(),
+            allowed_methods: vec!["GET".to_string(), "POST".to_string(), "OPTIONS".to_string()],
+            allowed_headers: vec![header::CONTENT_TYPE.to_string(), header::AUTHORIZATION.to_string()],
+            exposed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: None,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// 使用指定的源列表创建配置，其余字段使用默认值
+    pub fn with_origins(origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins: origins,
+            ..Default::default()
+        }
+    }
+
+    /// 关闭 CORS 的配置
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// 编译后的源匹配规则
+#[derive(Debug, Default)]
+struct OriginRules {
+    /// 是否允许任意源
+    any: bool,
+    /// 精确匹配的源
+    exact: Vec<String>,
+    /// 正则匹配规则
+    patterns: Vec<Regex>,
+}
+
+impl OriginRules {
+    fn compile(origins: &[String], patterns: &[String]) -> Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(&format!("^(?:{})$", p)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            any: origins.iter().any(|o| o == WILDCARD),
+            exact: origins
+                .iter()
+                .filter(|o| o.as_str() != WILDCARD)
+                .map(|o| o.trim_end_matches('/').to_ascii_lowercase())
+                .collect(),
+            patterns,
+        })
+    }
+
+    fn matches(&self, origin: &str) -> bool {
+        self.any
+            || self.exact.iter().any(|o| o.eq_ignore_ascii_case(origin))
+            || self.patterns.iter().any(|p| p.is_match(origin))
+    }
+}
+
+/// CORS 状态
+///
+/// 持有配置和可热更新的源匹配规则
+pub struct CorsState {
+    /// 配置（方法、头部和凭证）
+    config: CorsConfig,
+    /// 源匹配规则
+    origins: RwLock<OriginRules>,
+}
+
+impl CorsState {
+    /// 创建 CORS 状态
+    ///
+    /// 无效的正则表达式会被忽略并记录警告。允许任意源时不能同时允许凭证
+    /// （否则任何网站都能以用户身份发起跨域请求），此时关闭凭证并记录警告
+    pub fn new(mut config: CorsConfig) -> Self {
+        if config.allow_credentials && config.allowed_origins.iter().any(|o| o == WILDCARD) {
+            tracing::warn!("CORS 允许任意源时不能允许凭证，已关闭 allow_credentials");
+            config.allow_credentials = false;
+        }
+        let origins = OriginRules::compile(&config.allowed_origins, &config.allowed_origin_patterns)
+            .unwrap_or_else(|e| {
+                tracing::warn!("CORS 源正则表达式无效，已忽略: {}", e);
+                OriginRules::compile(&config.allowed_origins, &[]).unwrap_or_default()
+            });
+        Self {
+            config,
+            origins: RwLock::new(origins),
+        }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &CorsConfig {
+        &self.config
+    }
+
+    /// 运行时重新加载允许的源列表和正则表达式
+    ///
+    /// 正则表达式无效时返回错误，原有规则保持不变。
+    /// 允许凭证时忽略通配符源并记录警告
+    pub fn reload_origins(&self, origins: &[String], patterns: &[String]) -> Result<(), regex::Error> {
+        let mut rules = OriginRules::compile(origins, patterns)?;
+        if self.config.allow_credentials && rules.any {
+            tracing::warn!("CORS 已允许凭证，忽略通配符源");
+            rules.any = false;
+        }
+        *self.origins.write().unwrap_or_else(|e| e.into_inner()) = rules;
+        Ok(())
+    }
+
+    /// 判断源是否被允许
+    pub fn is_origin_allowed(&self, origin: &HeaderValue) -> bool {
+        let Ok(origin) = origin.to_str() else {
+            return false;
+        };
+        self.origins
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .matches(origin)
+    }
+
+    /// 构建 CORS 层
+    ///
+    /// 源通过谓词在每次请求时检查，因此 [`CorsState::reload_origins`] 会立即生效。
+    /// 启用凭证时通配符方法/头部改为镜像请求，以满足 CORS 规范
+    pub fn layer(self: &Arc<Self>) -> CorsLayer {
+        let config = &self.config;
+        if !config.enabled {
+            return CorsLayer::new();
+        }
+
+        let state = Arc::clone(self);
+        let mut layer = CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(move |origin, _| state.is_origin_allowed(origin)))
+            .allow_credentials(config.allow_credentials);
+
+        layer = if config.allowed_methods.iter().any(|m| m == WILDCARD) {
+            if config.allow_credentials {
+                layer.allow_methods(AllowMethods::mirror_request())
+            } else {
+                layer.allow_methods(Any)
+            }
+        } else {
+            layer.allow_methods(parse_list(&config.allowed_methods, |m| Method::from_bytes(m.as_bytes()).ok()))
+        };
+
+        layer = if config.allowed_headers.iter().any(|h| h == WILDCARD) {
+            if config.allow_credentials {
+                layer.allow_headers(AllowHeaders::mirror_request())
+            } else {
+                layer.allow_headers(Any)
+            }
+        } else {
+            layer.allow_headers(parse_list(&config.allowed_headers, |h| HeaderName::from_bytes(h.as_bytes()).ok()))
+        };
+
+        if config.exposed_headers.iter().any(|h| h == WILDCARD) {
+            if !config.allow_credentials {
+                layer = layer.expose_headers(ExposeHeaders::any());
+            }
+        } else if !config.exposed_headers.is_empty() {
+            layer = layer.expose_headers(parse_list(&config.exposed_headers, |h| HeaderName::from_bytes(h.as_bytes()).ok()));
+        }
+
+        if let Some(secs) = config.max_age_secs {
+            layer = layer.max_age(Duration::from_secs(secs));
+        }
+
+        layer
+    }
+}
+
+/// 解析配置列表，忽略无效项并记录警告
+fn parse_list<T>(items: &[String], parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let parsed = parse(item.trim());
+            if parsed.is_none() {
+                tracing::warn!("忽略无效的 CORS 配置项: {}", item);
+            }
+            parsed
+        })
+        .collect()
+}
 
 /// 创建 CORS 中间件
 ///
 /// # Returns
 ///
-/// 返回配置好的 CORS 层
+/// 返回使用默认配置（允许任意源）的 CORS 层
 pub fn create_cors_layer() -> CorsLayer {
-    CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_origin(Any)
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+    Arc::new(CorsState::new(CorsConfig::default())).layer()
 }
 
 #[cfg(test)]
@@ -40,4 +259,81 @@ mod tests {
         let _layer = create_cors_layer();
         // CORS layer created successfully
     }
+
+    #[test]
+    fn test_origin_matching() {
+        let state = CorsState::new(CorsConfig {
+            allowed_origins: vec!["https://example.com/".to_string()],
+            allowed_origin_patterns: vec![r"https://[a-z]+\.example\.org".to_string()],
+            ..Default::default()
+        });
+        assert!(state.is_origin_allowed(&HeaderValue::from_static("https://example.com")));
+        assert!(state.is_origin_allowed(&HeaderValue::from_static("https://app.example.org")));
+        assert!(!state.is_origin_allowed(&HeaderValue::from_static("https://evil.com")));
+        // 正则需匹配完整的源
+        assert!(!state.is_origin_allowed(&HeaderValue::from_static("https://app.example.org.evil.com")));
+    }
+
+    #[test]
+    fn test_reload_origins() {
+        let state = CorsState::new(CorsConfig::with_origins(vec!["https://a.com".to_string()]));
+        let origin = HeaderValue::from_static("https://b.com");
+        assert!(!state.is_origin_allowed(&origin));
+
+        state.reload_origins(&["https://b.com".to_string()], &[]).unwrap();
+        assert!(state.is_origin_allowed(&origin));
+
+        // 无效正则不会影响现有规则
+        assert!(state.reload_origins(&[], &["(".to_string()]).is_err());
+        assert!(state.is_origin_allowed(&origin));
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_origin_drops_credentials() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let state = Arc::new(CorsState::new(CorsConfig {
+            allow_credentials: true,
+            ..Default::default()
+        }));
+        assert!(!state.config().allow_credentials);
+
+        let router = axum::Router::new()
+            .route("/", axum::routing::get(|| async { "ok" }))
+            .layer(state.layer());
+        let request = axum::http::Request::get("/")
+            .header(header::ORIGIN, "https://evil.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+
+        // 允许凭证时重新加载的通配符源被忽略
+        let state = CorsState::new(CorsConfig {
+            allowed_origins: vec!["https://a.com".to_string()],
+            allow_credentials: true,
+            ..Default::default()
+        });
+        state.reload_origins(&[WILDCARD.to_string(), "https://b.com".to_string()], &[]).unwrap();
+        assert!(state.is_origin_allowed(&HeaderValue::from_static("https://b.com")));
+        assert!(!state.is_origin_allowed(&HeaderValue::from_static("https://evil.com")));
+    }
+
+    #[test]
+    fn test_layer_with_credentials_and_wildcards() {
+        // 凭证与通配符同时配置时不应 panic
+        let state = Arc::new(CorsState::new(CorsConfig {
+            allowed_origins: vec!["https://example.com".to_string()],
+            allowed_methods: vec![WILDCARD.to_string()],
+            allowed_headers: vec![WILDCARD.to_string()],
+            exposed_headers: vec![WILDCARD.to_string()],
+            allow_credentials: true,
+            max_age_secs: Some(600),
+            ..Default::default()
+        }));
+        // tower-http 在应用层时校验规则组合
+        let _router: axum::Router = axum::Router::new().layer(state.layer());
+        let _disabled = Arc::new(CorsState::new(CorsConfig::disabled())).layer();
+    }
 }
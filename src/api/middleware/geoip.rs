@@ -0,0 +1,227 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GeoIP 国家查询
+//!
+//! 使用 `maxminddb` 读取 MaxMind DB（`.mmdb`）格式的数据库（如 GeoLite2-Country），
+//! 只提供按 IP 查询国家代码的功能。
+
+use maxminddb::{geoip2, Reader};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// GeoIP 错误类型
+#[derive(Debug, error_derive::Error)]
+pub enum GeoIpError {
+    /// 读取文件失败
+    #[error("读取 GeoIP 数据库失败: {0}")]
+    Io(String),
+
+    /// 数据库格式无效
+    #[error("GeoIP 数据库格式无效: {0}")]
+    InvalidDatabase(String),
+}
+
+type Result<T> = std::result::Result<T, GeoIpError>;
+
+impl From<maxminddb::MaxMindDbError> for GeoIpError {
+    fn from(e: maxminddb::MaxMindDbError) -> Self {
+        GeoIpError::InvalidDatabase(e.to_string())
+    }
+}
+
+/// 客户端所在国家（ISO 3166-1 alpha-2，大写）
+///
+/// 由 IP 过滤中间件解析后放入请求扩展，供搜索处理器选择默认引擎
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCountry(pub String);
+
+impl ClientCountry {
+    /// 是否为中国大陆客户端
+    pub fn is_china(&self) -> bool {
+        self.0 == "CN"
+    }
+}
+
+/// GeoIP 数据库
+///
+/// 整个文件加载到内存中
+pub struct GeoIpDatabase {
+    reader: Reader<Vec<u8>>,
+}
+
+impl std::fmt::Debug for GeoIpDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let metadata = self.reader.metadata();
+        f.debug_struct("GeoIpDatabase")
+            .field("database_type", &metadata.database_type)
+            .field("ip_version", &metadata.ip_version)
+            .field("node_count", &metadata.node_count)
+            .finish()
+    }
+}
+
+impl GeoIpDatabase {
+    /// 从文件加载数据库
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let buffer = std::fs::read(path.as_ref())
+            .map_err(|e| GeoIpError::Io(format!("{}: {}", path.as_ref().display(), e)))?;
+        Self::from_bytes(buffer)
+    }
+
+    /// 从内存数据加载数据库
+    pub fn from_bytes(buffer: Vec<u8>) -> Result<Self> {
+        Ok(Self { reader: Reader::from_source(buffer)? })
+    }
+
+    /// 数据库类型
+    pub fn database_type(&self) -> &str {
+        &self.reader.metadata().database_type
+    }
+
+    /// 查询 IP 所在国家（ISO 代码，大写）
+    ///
+    /// 优先使用 `country`，缺失时退回 `registered_country`
+    pub fn lookup_country(&self, ip: IpAddr) -> Result<Option<String>> {
+        let ip = match ip {
+            // IPv4 数据库只能查询 IPv4（含 IPv4 映射）地址
+            IpAddr::V6(v6) if self.reader.metadata().ip_version == 4 => match v6.to_ipv4_mapped() {
+                Some(v4) => IpAddr::V4(v4),
+                None => return Ok(None),
+            },
+            ip => ip,
+        };
+        let Some(record) = self.reader.lookup(ip)?.decode::<geoip2::Country>()? else {
+            return Ok(None);
+        };
+
+        Ok(record.country.iso_code
+            .or(record.registered_country.iso_code)
+            .map(str::to_ascii_uppercase))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+    const DATA_SECTION_SEPARATOR: usize = 16;
+
+    fn encode_str(out: &mut Vec<u8>, s: &str) {
+        assert!(s.len() < 29);
+        out.push((2 << 5) | s.len() as u8);
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    fn encode_uint16(out: &mut Vec<u8>, n: u16) {
+        out.push((5 << 5) | 2);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+
+    fn encode_uint32(out: &mut Vec<u8>, n: u32) {
+        out.push((6 << 5) | 4);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+
+    fn encode_map_header(out: &mut Vec<u8>, size: u8) {
+        out.push((7 << 5) | size);
+    }
+
+    fn encode_country(out: &mut Vec<u8>, iso: &str) {
+        encode_map_header(out, 1);
+        encode_str(out, "country");
+        encode_map_header(out, 1);
+        encode_str(out, "iso_code");
+        encode_str(out, iso);
+    }
+
+    /// 构造一个 IPv4 测试数据库：0.0.0.0/1 → CN，128.0.0.0/2 → US，其余无数据
+    pub(crate) fn test_database() -> Vec<u8> {
+        let node_count: u32 = 2;
+        let mut data = Vec::new();
+        let cn_offset = data.len() as u32;
+        encode_country(&mut data, "cn");
+        let us_offset = data.len() as u32;
+        encode_country(&mut data, "US");
+
+        let data_record = |offset: u32| node_count + DATA_SECTION_SEPARATOR as u32 + offset;
+        let records = [
+            (data_record(cn_offset), 1), // 节点 0：首位 0 → CN，首位 1 → 节点 1
+            (data_record(us_offset), node_count), // 节点 1：次位 0 → US，次位 1 → 无数据
+        ];
+
+        let mut buffer = Vec::new();
+        for (left, right) in records {
+            buffer.extend_from_slice(&left.to_be_bytes()[1..]);
+            buffer.extend_from_slice(&right.to_be_bytes()[1..]);
+        }
+        buffer.extend_from_slice(&[0u8; DATA_SECTION_SEPARATOR]);
+        buffer.extend_from_slice(&data);
+        buffer.extend_from_slice(METADATA_MARKER);
+
+        encode_map_header(&mut buffer, 9);
+        encode_str(&mut buffer, "binary_format_major_version");
+        encode_uint16(&mut buffer, 2);
+        encode_str(&mut buffer, "binary_format_minor_version");
+        encode_uint16(&mut buffer, 0);
+        encode_str(&mut buffer, "build_epoch");
+        // 扩展类型：uint64（9 - 7），值为 0
+        buffer.extend_from_slice(&[0, 2]);
+        encode_str(&mut buffer, "description");
+        encode_map_header(&mut buffer, 0);
+        encode_str(&mut buffer, "languages");
+        // 扩展类型：数组（11 - 7），0 个元素
+        buffer.extend_from_slice(&[0, 4]);
+        encode_str(&mut buffer, "node_count");
+        encode_uint32(&mut buffer, node_count);
+        encode_str(&mut buffer, "record_size");
+        encode_uint16(&mut buffer, 24);
+        encode_str(&mut buffer, "ip_version");
+        encode_uint16(&mut buffer, 4);
+        encode_str(&mut buffer, "database_type");
+        encode_str(&mut buffer, "Test-Country");
+        buffer
+    }
+
+    #[test]
+    fn test_lookup_country() {
+        let db = GeoIpDatabase::from_bytes(test_database()).unwrap();
+        assert_eq!(db.database_type(), "Test-Country");
+
+        let lookup = |ip: &str| db.lookup_country(ip.parse().unwrap()).unwrap();
+        assert_eq!(lookup("1.2.3.4").as_deref(), Some("CN"));
+        assert_eq!(lookup("130.1.1.1").as_deref(), Some("US"));
+        assert_eq!(lookup("200.1.1.1"), None);
+        // IPv4 映射地址按 IPv4 查询，其余 IPv6 地址在 IPv4 数据库中无数据
+        assert_eq!(lookup("::ffff:1.2.3.4").as_deref(), Some("CN"));
+        assert_eq!(lookup("2001:db8::1"), None);
+    }
+
+    #[test]
+    fn test_invalid_database() {
+        assert!(GeoIpDatabase::from_bytes(b"not a database".to_vec()).is_err());
+
+        // 元数据完整但缺少搜索树和数据段
+        let full = test_database();
+        let marker = full.windows(METADATA_MARKER.len()).position(|w| w == METADATA_MARKER).unwrap();
+        assert!(GeoIpDatabase::from_bytes(full[marker..].to_vec()).is_err());
+
+        // 记录指向搜索树与数据段之间的分隔区时返回错误而不是越界
+        let mut corrupt = test_database();
+        corrupt[..3].copy_from_slice(&(2u32 + 5).to_be_bytes()[1..]);
+        let db = GeoIpDatabase::from_bytes(corrupt).unwrap();
+        assert!(db.lookup_country("1.2.3.4".parse().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP 缓存中间件
+//!
+//! 处理器为可缓存的响应写入 [`EntityTag`] 扩展（如搜索响应），
+//! 中间件据此输出强 `ETag`，请求的 `If-None-Match` 匹配时直接返回 304；
+//! 同时按路由为成功响应添加配置的 `Cache-Control`。
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// HTTP 缓存配置
+#[derive(Debug, Clone)]
+pub struct HttpCacheConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 按路由配置的 `Cache-Control`（精确路径，或以 `*` 结尾的前缀，最长匹配优先）
+    pub cache_control: HashMap<String, String>,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        // 搜索响应可能带有点击追踪链接，只允许浏览器缓存，并且每次用 ETag 重新验证
+        let cache_control = [
+            ("/api/search", "private, no-cache"),
+            ("/api/engines", "public, max-age=300"),
+            ("/api/version", "public, max-age=3600"),
+            // 榜单由后台定期重新生成，可以短时间共享缓存
+            ("/boards/*", "public, max-age=300"),
+            ("/api/boards/*", "public, max-age=300"),
+        ]
+        .into_iter()
+        .map(|(route, value)| (route.to_string(), value.to_string()))
+        .collect();
+        Self { enabled: true, cache_control }
+    }
+}
+
+/// 响应实体标签
+///
+/// 由处理器写入响应扩展，内容为带引号的强 ETag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTag(pub String);
+
+impl EntityTag {
+    /// 根据响应内容生成强 ETag
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let digest = Sha256::digest(bytes);
+        let hex: String = digest.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+        Self(format!("\"{}\"", hex))
+    }
+
+    /// `If-None-Match` 是否匹配该标签（支持多个标签和 `*`，忽略弱标签前缀）
+    pub fn matches(&self, if_none_match: &str) -> bool {
+        if_none_match.split(',').map(str::trim).any(|tag| {
+            tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.0
+        })
+    }
+}
+
+/// HTTP 缓存状态
+pub struct HttpCacheState {
+    config: HttpCacheConfig,
+}
+
+impl HttpCacheState {
+    /// 创建 HTTP 缓存状态
+    pub fn new(config: HttpCacheConfig) -> Self {
+        Self { config }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &HttpCacheConfig {
+        &self.config
+    }
+
+    /// 路径对应的 `Cache-Control`
+    pub fn cache_control(&self, path: &str) -> Option<&str> {
+        self.config
+            .cache_control
+            .iter()
+            .filter(|(route, _)| match route.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => path == route.as_str(),
+            })
+            .max_by_key(|(route, _)| route.len())
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP 缓存中间件
+///
+/// 只处理 GET/HEAD 的成功响应；处理器仍会执行，304 只节省响应体的传输
+pub async fn http_cache_middleware(
+    State(state): State<Arc<HttpCacheState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.config.enabled || !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return next.run(req).await;
+    }
+
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let cache_control = state
+        .cache_control(req.uri().path())
+        .and_then(|value| HeaderValue::from_str(value).ok());
+
+    let mut response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let etag = response
+        .extensions()
+        .get::<EntityTag>()
+        .and_then(|tag| Some((tag.clone(), HeaderValue::from_str(&tag.0).ok()?)));
+    if let Some((tag, value)) = etag {
+        if if_none_match.as_deref().is_some_and(|header| tag.matches(header)) {
+            let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+            not_modified.headers_mut().insert(header::ETAG, value);
+            if let Some(cache_control) = cache_control {
+                not_modified.headers_mut().insert(header::CACHE_CONTROL, cache_control);
+            }
+            return not_modified;
+        }
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    if let Some(cache_control) = cache_control {
+        response.headers_mut().entry(header::CACHE_CONTROL).or_insert(cache_control);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let state = Arc::new(HttpCacheState::new(HttpCacheConfig::default()));
+        Router::new()
+            .route("/api/search", get(|| async {
+                let mut response = "cached".into_response();
+                response.extensions_mut().insert(EntityTag::from_bytes(b"cached"));
+                response
+            }))
+            .route("/api/engines", get(|| async { "engines" }))
+            .layer(middleware::from_fn_with_state(state, http_cache_middleware))
+    }
+
+    async fn get_with(uri: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(tag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, tag);
+        }
+        app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[test]
+    fn test_entity_tag_matching() {
+        let tag = EntityTag::from_bytes(b"payload");
+        assert!(tag.0.starts_with('"') && tag.0.len() == 34);
+        assert!(tag.matches(&tag.0));
+        assert!(tag.matches(&format!("\"other\", W/{}", tag.0)));
+        assert!(tag.matches("*"));
+        assert!(!tag.matches("\"other\""));
+    }
+
+    #[tokio::test]
+    async fn test_etag_and_not_modified() {
+        let response = get_with("/api/search", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "private, no-cache");
+
+        let response = get_with("/api/search", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+        // 没有实体标签的路由只添加 Cache-Control
+        let response = get_with("/api/engines", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::ETAG).is_none());
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "public, max-age=300");
+    }
+
+    #[test]
+    fn test_cache_control_route_matching() {
+        let mut config = HttpCacheConfig::default();
+        config.cache_control.insert("/api/*".to_string(), "no-store".to_string());
+        let state = HttpCacheState::new(config);
+        assert_eq!(state.cache_control("/api/search"), Some("private, no-cache"));
+        assert_eq!(state.cache_control("/api/stats"), Some("no-store"));
+        assert_eq!(state.cache_control("/health"), None);
+    }
+}
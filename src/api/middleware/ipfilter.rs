@@ -14,18 +14,24 @@
 
 //! IP过滤中间件
 //!
-//! 提供IP黑名单和白名单功能
+//! 提供IP黑名单和白名单功能，并可加载 GeoIP 数据库按国家放行或拒绝。
+//! 解析出的客户端国家以 [`ClientCountry`] 扩展传给后续处理器。
 
 use axum::{
     extract::Request,
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
 use dashmap::DashMap;
 use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::geoip::{ClientCountry, GeoIpDatabase};
+use super::ratelimit::TrustedProxies;
+
 /// IP过滤配置
 #[derive(Debug, Clone)]
 pub struct IpFilterConfig {
@@ -34,6 +40,20 @@ pub struct IpFilterConfig {
     
     /// 是否启用
     pub enabled: bool,
+
+    /// GeoIP 数据库路径（MaxMind DB 格式）
+    pub geoip_database: Option<PathBuf>,
+
+    /// 允许的国家代码（非空时只允许这些国家）
+    pub country_allowlist: Vec<String>,
+
+    /// 拒绝的国家代码
+    pub country_denylist: Vec<String>,
+
+    /// 受信任的反向代理（IP 或 CIDR），规则与限流相同
+    ///
+    /// 为空时始终使用直连地址，忽略 `X-Forwarded-For` / `X-Real-IP`
+    pub trusted_proxies: Vec<String>,
 }
 
 impl Default for IpFilterConfig {
@@ -41,6 +61,10 @@ impl Default for IpFilterConfig {
         Self {
             whitelist_mode: false,
             enabled: true,
+            geoip_database: None,
+            country_allowlist: Vec::new(),
+            country_denylist: Vec::new(),
+            trusted_proxies: Vec::new(),
         }
     }
 }
@@ -53,15 +77,73 @@ pub struct IpFilterState {
     whitelist: Arc<DashMap<IpAddr, String>>,
     /// 配置
     config: IpFilterConfig,
+    /// GeoIP 数据库
+    geoip: Option<Arc<GeoIpDatabase>>,
+    /// 受信任代理
+    trusted_proxies: TrustedProxies,
 }
 
 impl IpFilterState {
     /// 创建新的IP过滤状态
+    ///
+    /// 配置了 GeoIP 数据库路径时尝试加载，失败时记录警告并禁用国家规则
     pub fn new(config: IpFilterConfig) -> Self {
+        let geoip = config.geoip_database.as_ref().and_then(|path| {
+            match GeoIpDatabase::open(path) {
+                Ok(db) => {
+                    tracing::info!("GeoIP database loaded: {} ({})", path.display(), db.database_type());
+                    Some(Arc::new(db))
+                }
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    None
+                }
+            }
+        });
+        let trusted_proxies = TrustedProxies::parse(&config.trusted_proxies);
         Self {
             blacklist: Arc::new(DashMap::new()),
             whitelist: Arc::new(DashMap::new()),
             config,
+            geoip,
+            trusted_proxies,
+        }
+    }
+
+    /// 使用已加载的 GeoIP 数据库
+    pub fn with_geoip(mut self, db: Arc<GeoIpDatabase>) -> Self {
+        self.geoip = Some(db);
+        self
+    }
+
+    /// 是否加载了 GeoIP 数据库
+    pub fn has_geoip(&self) -> bool {
+        self.geoip.is_some()
+    }
+
+    /// 查询IP所在国家
+    pub fn country_of(&self, ip: &IpAddr) -> Option<ClientCountry> {
+        let db = self.geoip.as_ref()?;
+        match db.lookup_country(*ip) {
+            Ok(country) => country.map(ClientCountry),
+            Err(e) => {
+                tracing::debug!("GeoIP lookup failed for {}: {}", ip, e);
+                None
+            }
+        }
+    }
+
+    /// 检查国家是否被允许
+    ///
+    /// 无法确定国家时，只有配置了允许列表才会拒绝
+    pub fn is_country_allowed(&self, country: Option<&ClientCountry>) -> bool {
+        let matches = |list: &[String], code: &str| list.iter().any(|c| c.eq_ignore_ascii_case(code));
+        match country {
+            Some(ClientCountry(code)) => {
+                !matches(&self.config.country_denylist, code)
+                    && (self.config.country_allowlist.is_empty() || matches(&self.config.country_allowlist, code))
+            }
+            None => self.config.country_allowlist.is_empty(),
         }
     }
 
@@ -114,31 +196,47 @@ impl IpFilterState {
 /// IP过滤中间件
 pub async fn ip_filter_middleware(
     axum::extract::State(state): axum::extract::State<Arc<IpFilterState>>,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Response {
     if !state.config.enabled {
         return next.run(req).await;
     }
 
-    // 提取客户端IP
-    if let Some(ip) = extract_client_ip(&req) {
+    // 提取客户端IP（只采信受信任代理转发的地址）
+    if let Some(ip) = state.trusted_proxies.client_ip(&req) {
         if !state.is_allowed(&ip) {
             return (
                 StatusCode::FORBIDDEN,
-                serde_json::json!({
+                Json(serde_json::json!({
                     "code": "IP_BLOCKED",
                     "message": "您的IP地址已被封禁"
-                }).to_string()
+                }))
             ).into_response();
         }
+
+        if state.has_geoip() {
+            let country = state.country_of(&ip);
+            if !state.is_country_allowed(country.as_ref()) {
+                return (
+                    StatusCode::FORBIDDEN,
+                    Json(serde_json::json!({
+                        "code": "COUNTRY_BLOCKED",
+                        "message": "您所在的地区无法访问此服务"
+                    }))
+                ).into_response();
+            }
+            if let Some(country) = country {
+                req.extensions_mut().insert(country);
+            }
+        }
     }
 
     next.run(req).await
 }
 
 /// 提取客户端IP
-fn extract_client_ip(req: &Request) -> Option<IpAddr> {
+pub(crate) fn extract_client_ip(req: &Request) -> Option<IpAddr> {
     // 尝试从X-Forwarded-For获取
     if let Some(forwarded) = req.headers().get("x-forwarded-for") {
         if let Ok(forwarded_str) = forwarded.to_str() {
@@ -165,6 +263,8 @@ fn extract_client_ip(req: &Request) -> Option<IpAddr> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::extract::ConnectInfo;
+    use std::net::SocketAddr;
 
     #[test]
     fn test_ip_filter_config_default() {
@@ -203,4 +303,92 @@ mod tests {
         state.remove_from_whitelist(&ip);
         assert!(!state.is_allowed(&ip));
     }
+
+    fn geoip_state(config: IpFilterConfig) -> Arc<IpFilterState> {
+        let db = GeoIpDatabase::from_bytes(super::super::geoip::tests::test_database()).unwrap();
+        Arc::new(IpFilterState::new(config).with_geoip(Arc::new(db)))
+    }
+
+    #[test]
+    fn test_country_rules() {
+        let state = geoip_state(IpFilterConfig {
+            country_denylist: vec!["us".to_string()],
+            ..Default::default()
+        });
+        let cn = state.country_of(&"1.2.3.4".parse().unwrap());
+        assert_eq!(cn, Some(ClientCountry("CN".to_string())));
+        assert!(state.is_country_allowed(cn.as_ref()));
+        assert!(!state.is_country_allowed(state.country_of(&"130.1.1.1".parse().unwrap()).as_ref()));
+        assert!(state.is_country_allowed(None));
+
+        let state = geoip_state(IpFilterConfig {
+            country_allowlist: vec!["CN".to_string()],
+            ..Default::default()
+        });
+        assert!(state.is_country_allowed(Some(&ClientCountry("CN".to_string()))));
+        assert!(!state.is_country_allowed(Some(&ClientCountry("US".to_string()))));
+        // 配置允许列表时，无法确定国家的请求被拒绝
+        assert!(!state.is_country_allowed(None));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_sets_client_country() {
+        use axum::{body::Body, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        let state = geoip_state(IpFilterConfig {
+            country_denylist: vec!["US".to_string()],
+            ..Default::default()
+        });
+        let router = Router::new()
+            .route("/", get(|country: Option<Extension<ClientCountry>>| async move {
+                country.map(|Extension(c)| c.0).unwrap_or_default()
+            }))
+            .layer(axum::middleware::from_fn_with_state(state, ip_filter_middleware));
+
+        let request = |ip: &str, forwarded: Option<&str>| {
+            let mut builder = axum::http::Request::get("/");
+            if let Some(forwarded) = forwarded {
+                builder = builder.header("x-forwarded-for", forwarded);
+            }
+            let mut req = builder.body(Body::empty()).unwrap();
+            req.extensions_mut().insert(ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 1234)));
+            req
+        };
+
+        let response = router.clone().oneshot(request("1.2.3.4", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"CN");
+
+        let response = router.clone().oneshot(request("130.1.1.1", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // 未配置受信任代理时，伪造的转发头不能绕过国家规则
+        let response = router.oneshot(request("130.1.1.1", Some("1.2.3.4"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_uses_forwarded_ip_from_trusted_proxy() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let state = geoip_state(IpFilterConfig {
+            country_denylist: vec!["US".to_string()],
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        });
+        let router = Router::new()
+            .route("/", get(|| async { StatusCode::OK }))
+            .layer(axum::middleware::from_fn_with_state(state, ip_filter_middleware));
+
+        let mut req = axum::http::Request::get("/")
+            .header("x-forwarded-for", "130.1.1.1")
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(ConnectInfo(SocketAddr::new("10.0.0.1".parse().unwrap(), 1234)));
+        let response = router.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 错误消息本地化中间件
+//!
+//! 按请求的 `Accept-Language` 协商语言，将 `ApiErrorResponse` 形式的错误响应中的
+//! `message` 改写为客户端语言（见 [`crate::api::i18n`]），`code` 不变；
+//! 响应附带 `Content-Language` 和 `Vary: Accept-Language`。
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::i18n::{localize_error, Locale};
+
+/// 改写错误响应时读取的最大响应体（超过时原样返回）
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// 改写 `ApiErrorResponse` 形式 JSON 中的消息，返回最终消息的语言和改写后的响应体
+/// （无需改写时响应体为 None；不是该形式时返回 None）
+fn localize_body(body: &[u8], locale: Locale) -> Option<(Locale, Option<Vec<u8>>)> {
+    let mut value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let object = value.as_object_mut()?;
+    let code = object.get("code")?.as_str()?;
+    let original = object.get("message")?.as_str()?;
+    let Some(message) = localize_error(code, original, locale) else {
+        return Some((Locale::of_text(original), None));
+    };
+    object.insert("message".to_string(), serde_json::Value::String(message.to_string()));
+    Some((locale, serde_json::to_vec(&value).ok()))
+}
+
+/// 错误消息本地化中间件
+///
+/// 应位于所有可能返回错误响应的中间件之外、响应压缩之内
+pub async fn localize_errors_middleware(req: Request<Body>, next: Next) -> Response {
+    let locale = Locale::negotiate(
+        req.headers().get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()),
+    );
+    let response = next.run(req).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !(response.status().is_client_error() || response.status().is_server_error()) || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let Some((language, rewritten)) = localize_body(&bytes, locale) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    parts.headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    parts.headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(language.tag()));
+    match rewritten {
+        Some(rewritten) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(rewritten))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::ApiErrorResponse;
+    use axum::{http::StatusCode, middleware, response::IntoResponse, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/limited", get(|| async {
+                let error = ApiErrorResponse {
+                    code: "RATE_LIMIT_EXCEEDED".to_string(),
+                    message: "请求过于频繁，请稍后再试".to_string(),
+                    details: None,
+                };
+                (StatusCode::TOO_MANY_REQUESTS, Json(error)).into_response()
+            }))
+            .layer(middleware::from_fn(localize_errors_middleware))
+    }
+
+    async fn error_for(accept_language: Option<&str>) -> (Option<String>, ApiErrorResponse) {
+        let mut request = Request::builder().uri("/limited");
+        if let Some(value) = accept_language {
+            request = request.header(header::ACCEPT_LANGUAGE, value);
+        }
+        let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let language = response.headers().get(header::CONTENT_LANGUAGE).map(|v| v.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (language, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_error_message_follows_accept_language() {
+        let (language, error) = error_for(Some("en-US,en;q=0.9,zh;q=0.5")).await;
+        assert_eq!(language.as_deref(), Some("en"));
+        assert_eq!(error.code, "RATE_LIMIT_EXCEEDED");
+        assert_eq!(error.message, "Too many requests, please try again later");
+
+        let (language, error) = error_for(None).await;
+        assert_eq!(language.as_deref(), Some("zh-CN"));
+        assert_eq!(error.message, "请求过于频繁，请稍后再试");
+    }
+}
@@ -14,16 +14,129 @@
 
 //! 日志中间件
 //!
-//! 记录 API 请求和响应日志
+//! 记录 API 请求和响应日志，并提供结构化（JSON）访问日志：
+//! 支持查询字符串脱敏和采样，避免高流量实例被日志淹没。
 
 use axum::{
     body::Body,
+    extract::ConnectInfo,
     http::Request,
     middleware::Next,
     response::Response,
 };
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Instant;
 
+use super::ipfilter::extract_client_ip;
+use super::requestid::RequestId;
+
+/// 访问日志的 tracing target
+pub const ACCESS_LOG_TARGET: &str = "seesea::access";
+
+/// 脱敏后的占位符
+const REDACTED: &str = "[REDACTED]";
+
+/// 查询字符串脱敏方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryRedaction {
+    /// 不脱敏
+    None,
+    /// 只脱敏指定参数的值
+    Params(Vec<String>),
+    /// 完全不记录查询字符串
+    Full,
+}
+
+impl Default for QueryRedaction {
+    fn default() -> Self {
+        // 默认脱敏搜索词和各类令牌
+        Self::Params(
+            ["q", "query", "magic_token", "token", "api_key", "u", "sig"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        )
+    }
+}
+
+/// 访问日志配置
+#[derive(Debug, Clone)]
+pub struct AccessLogConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 查询字符串脱敏方式
+    pub redaction: QueryRedaction,
+    /// 采样率（0.0 ~ 1.0）
+    pub sample_rate: f64,
+    /// 是否总是记录错误响应（状态码 >= 400），不受采样影响
+    pub always_log_errors: bool,
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            redaction: QueryRedaction::default(),
+            sample_rate: 1.0,
+            always_log_errors: true,
+        }
+    }
+}
+
+/// 访问日志状态
+pub struct AccessLogState {
+    /// 配置
+    config: AccessLogConfig,
+}
+
+impl AccessLogState {
+    /// 创建访问日志状态
+    pub fn new(config: AccessLogConfig) -> Self {
+        Self { config }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &AccessLogConfig {
+        &self.config
+    }
+
+    /// 判断该请求是否需要记录
+    fn should_log(&self, status: u16) -> bool {
+        if self.config.always_log_errors && status >= 400 {
+            return true;
+        }
+        self.config.sample_rate >= 1.0 || fastrand::f64() < self.config.sample_rate
+    }
+}
+
+/// 处理器使用的引擎数量
+///
+/// 由搜索处理器写入响应扩展，访问日志据此记录 `engine_count`
+#[derive(Debug, Clone, Copy)]
+pub struct EnginesUsed(pub usize);
+
+/// 按配置对查询字符串脱敏
+pub fn redact_query(query: &str, redaction: &QueryRedaction) -> Option<String> {
+    if query.is_empty() {
+        return None;
+    }
+    match redaction {
+        QueryRedaction::None => Some(query.to_string()),
+        QueryRedaction::Full => None,
+        QueryRedaction::Params(params) => {
+            let redacted: Vec<String> = query
+                .split('&')
+                .map(|pair| match pair.split_once('=') {
+                    Some((key, _)) if params.iter().any(|p| p == key) => format!("{}={}", key, REDACTED),
+                    _ => pair.to_string(),
+                })
+                .collect();
+            Some(redacted.join("&"))
+        }
+    }
+}
+
 /// 日志中间件处理器
 ///
 /// # Arguments
@@ -41,13 +154,13 @@ pub async fn logging_middleware(
     let start = Instant::now();
     let method = req.method().clone();
     let uri = req.uri().clone();
-    
+
     // 处理请求
     let response = next.run(req).await;
-    
+
     let elapsed = start.elapsed();
     let status = response.status();
-    
+
     // 记录日志
     tracing::info!(
         method = %method,
@@ -56,7 +169,53 @@ pub async fn logging_middleware(
         elapsed_ms = elapsed.as_millis(),
         "API request processed"
     );
-    
+
+    response
+}
+
+/// 结构化访问日志中间件
+///
+/// 每个请求输出一行 JSON（target 为 [`ACCESS_LOG_TARGET`]），
+/// 包含方法、路径、脱敏后的查询、状态码、耗时、客户端 IP、引擎数量和请求 ID
+pub async fn access_log_middleware(
+    axum::extract::State(state): axum::extract::State<Arc<AccessLogState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.config.enabled {
+        return next.run(req).await;
+    }
+
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let query = req.uri().query().and_then(|q| redact_query(q, &state.config.redaction));
+    let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+    let client_ip = extract_client_ip(&req)
+        .map(|ip| ip.to_string())
+        .or_else(|| {
+            req.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        });
+
+    let response = next.run(req).await;
+
+    let status = response.status().as_u16();
+    if state.should_log(status) {
+        let entry = serde_json::json!({
+            "method": method,
+            "path": path,
+            "query": query,
+            "status": status,
+            "latency_ms": start.elapsed().as_secs_f64() * 1000.0,
+            "client_ip": client_ip,
+            "engine_count": response.extensions().get::<EnginesUsed>().map(|e| e.0),
+            "request_id": request_id,
+        });
+        tracing::info!(target: ACCESS_LOG_TARGET, "{}", entry);
+    }
+
     response
 }
 
@@ -69,4 +228,30 @@ mod tests {
         // Test that the middleware function is callable
         // Actual testing would require setting up a full axum app
     }
+
+    #[test]
+    fn test_redact_query() {
+        let query = "q=secret+stuff&page=2&magic_token=abc";
+        assert_eq!(
+            redact_query(query, &QueryRedaction::default()).unwrap(),
+            "q=[REDACTED]&page=2&magic_token=[REDACTED]"
+        );
+        assert_eq!(redact_query(query, &QueryRedaction::None).unwrap(), query);
+        assert!(redact_query(query, &QueryRedaction::Full).is_none());
+        assert!(redact_query("", &QueryRedaction::None).is_none());
+    }
+
+    #[test]
+    fn test_access_log_sampling() {
+        let state = AccessLogState::new(AccessLogConfig {
+            sample_rate: 0.0,
+            ..Default::default()
+        });
+        assert!(!state.should_log(200));
+        // 错误响应不受采样影响
+        assert!(state.should_log(500));
+
+        let state = AccessLogState::new(AccessLogConfig::default());
+        assert!(state.should_log(200));
+    }
 }
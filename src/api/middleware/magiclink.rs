@@ -14,22 +14,31 @@
 
 //! 魔法链接中间件
 //!
-//! 提供一次性魔法链接认证功能
+//! 提供魔法链接认证功能。默认令牌为一次性且不限权限；
+//! 也可以生成带权限范围、每日配额的可复用令牌，并支持续期和吊销。
 
 use axum::{
     extract::Request,
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
 use dashmap::DashMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::api::secrets::SecretString;
+use crate::cache::HistoryScope;
+
+/// 过期令牌在被清理前的保留时间
+const CLEANUP_GRACE: Duration = Duration::from_secs(60);
+
 /// 魔法链接配置
 #[derive(Debug, Clone)]
 pub struct MagicLinkConfig {
@@ -40,31 +49,222 @@ pub struct MagicLinkConfig {
     pub expiration: u64,
     
     /// 密钥
-    pub secret: String,
+    pub secret: SecretString,
 }
 
+/// 默认生成的密钥前缀
+const DEFAULT_SECRET_PREFIX: &str = "magic_link_default_secret_";
+
 impl Default for MagicLinkConfig {
     fn default() -> Self {
-        // Warning: Default secret should be changed in production
-        tracing::warn!("Using default MagicLink secret - CHANGE THIS IN PRODUCTION!");
-        
+        // 随机生成的密钥只适合内网；外网启用魔法链接时 `ApiInterface::serve` 拒绝使用默认密钥
         Self {
             enabled: true,
             expiration: 300, // 5 minutes
-            secret: format!("magic_link_default_secret_{}", Uuid::new_v4()),
+            secret: SecretString::new(format!("{}{}", DEFAULT_SECRET_PREFIX, Uuid::new_v4())),
+        }
+    }
+}
+
+impl MagicLinkConfig {
+    /// 是否仍在使用默认生成的密钥
+    pub fn uses_default_secret(&self) -> bool {
+        self.secret.expose().starts_with(DEFAULT_SECRET_PREFIX)
+    }
+}
+
+/// 魔法链接权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MagicLinkPermission {
+    /// 仅搜索（搜索、搜索历史、点击跳转和引擎列表）
+    SearchOnly,
+    /// RSS 管理
+    RssAdmin,
+    /// 只读指标（指标、统计和健康检查）
+    MetricsRead,
+}
+
+impl MagicLinkPermission {
+    /// 从字符串解析权限范围
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "search" | "search-only" => Some(Self::SearchOnly),
+            "rss-admin" => Some(Self::RssAdmin),
+            "metrics-read" => Some(Self::MetricsRead),
+            _ => None,
+        }
+    }
+
+    /// 判断该权限是否允许访问指定路径
+    pub fn allows_path(&self, path: &str) -> bool {
+        match self {
+            Self::SearchOnly => {
+                path.starts_with("/api/search")
+                    || path.starts_with("/api/history")
+                    || path == "/r"
+                    || path == "/api/engines"
+            }
+            Self::RssAdmin => path.starts_with("/api/rss/"),
+            Self::MetricsRead => {
+                path.starts_with("/api/metrics")
+                    || path == "/api/stats"
+                    || path == "/api/health"
+                    || path == "/health"
+                    || path.starts_with("/health/")
+            }
+        }
+    }
+}
+
+/// 魔法链接生成选项
+#[derive(Debug, Clone, Default)]
+pub struct MagicLinkOptions {
+    /// 用途描述
+    pub purpose: String,
+    /// 绑定的搜索历史作用域
+    pub history_scope: Option<HistoryScope>,
+    /// 权限范围（为空表示不限制）
+    pub permissions: Vec<MagicLinkPermission>,
+    /// 每日请求配额（按 UTC 日期重置，None 表示不限）
+    pub daily_quota: Option<u64>,
+    /// 每日查询成本预算（启用预算时生效，None 时使用配置的默认值）
+    pub daily_budget: Option<u64>,
+    /// 有效期（秒），None 时使用配置的默认值
+    pub expires_in: Option<u64>,
+    /// 是否可重复使用（否则验证一次后失效）
+    pub reusable: bool,
+}
+
+impl MagicLinkOptions {
+    /// 创建只设置用途的选项
+    pub fn new(purpose: impl Into<String>) -> Self {
+        Self {
+            purpose: purpose.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 魔法链接验证错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MagicLinkError {
+    /// 令牌不存在或已吊销
+    Invalid,
+    /// 令牌已过期
+    Expired,
+    /// 一次性令牌已使用
+    AlreadyUsed,
+    /// 令牌无权访问该路径
+    Forbidden,
+    /// 超出每日配额
+    QuotaExceeded,
+}
+
+impl MagicLinkError {
+    /// 对应的 HTTP 状态码
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::Invalid | Self::Expired | Self::AlreadyUsed => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// 对应的错误代码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Invalid | Self::Expired | Self::AlreadyUsed => "MAGIC_LINK_INVALID",
+            Self::Forbidden => "MAGIC_LINK_FORBIDDEN",
+            Self::QuotaExceeded => "MAGIC_LINK_QUOTA_EXCEEDED",
         }
     }
 }
 
+impl fmt::Display for MagicLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::Invalid => "Invalid magic link",
+            Self::Expired => "Magic link expired",
+            Self::AlreadyUsed => "Magic link already used",
+            Self::Forbidden => "Magic link not permitted for this endpoint",
+            Self::QuotaExceeded => "Magic link daily quota exceeded",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for MagicLinkError {}
+
+/// 验证通过的魔法链接
+#[derive(Debug, Clone)]
+pub struct VerifiedMagicLink {
+    /// 用途描述
+    pub purpose: String,
+    /// 绑定的搜索历史作用域
+    pub history_scope: Option<HistoryScope>,
+}
+
 /// 魔法链接信息
 #[derive(Debug, Clone)]
 struct MagicLinkInfo {
     /// 创建时间
-    created_at: Instant,
+    created_at: DateTime<Utc>,
+    /// 过期时间
+    expires_at: Instant,
     /// 用途描述
     purpose: String,
     /// 是否已使用
     used: bool,
+    /// 绑定的搜索历史作用域（未绑定时不记录历史）
+    history_scope: Option<HistoryScope>,
+    /// 权限范围（为空表示不限制）
+    permissions: Vec<MagicLinkPermission>,
+    /// 每日请求配额
+    daily_quota: Option<u64>,
+    /// 每日查询成本预算
+    daily_budget: Option<u64>,
+    /// 是否可重复使用
+    reusable: bool,
+    /// 配额计数所属日期（UTC）
+    quota_day: NaiveDate,
+    /// 当日已用请求数
+    used_today: u64,
+}
+
+impl MagicLinkInfo {
+    fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+}
+
+/// 魔法链接摘要（用于管理列表）
+#[derive(Debug, Clone, Serialize)]
+pub struct MagicLinkSummary {
+    /// 令牌
+    pub token: String,
+    /// 用途描述
+    pub purpose: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 剩余有效期（秒）
+    pub expires_in: u64,
+    /// 是否已过期
+    pub expired: bool,
+    /// 是否已使用（仅一次性令牌）
+    pub used: bool,
+    /// 是否可重复使用
+    pub reusable: bool,
+    /// 权限范围
+    pub permissions: Vec<MagicLinkPermission>,
+    /// 每日请求配额
+    pub daily_quota: Option<u64>,
+    /// 每日查询成本预算
+    pub daily_budget: Option<u64>,
+    /// 当日已用请求数
+    pub used_today: u64,
+    /// 绑定的搜索历史作用域
+    pub history_scope: Option<String>,
 }
 
 /// 魔法链接状态
@@ -84,68 +284,193 @@ impl MagicLinkState {
         }
     }
 
+    /// 获取配置
+    pub fn config(&self) -> &MagicLinkConfig {
+        &self.config
+    }
+
     /// 生成新的魔法链接令牌
     pub fn generate_token(&self, purpose: String) -> String {
+        self.generate_token_with_options(MagicLinkOptions::new(purpose))
+    }
+
+    /// 生成绑定搜索历史作用域的魔法链接令牌
+    ///
+    /// 使用该令牌的请求会读写对应作用域的搜索历史
+    pub fn generate_token_with_scope(&self, purpose: String, history_scope: Option<HistoryScope>) -> String {
+        self.generate_token_with_options(MagicLinkOptions {
+            history_scope,
+            ..MagicLinkOptions::new(purpose)
+        })
+    }
+
+    /// 按选项生成魔法链接令牌
+    pub fn generate_token_with_options(&self, options: MagicLinkOptions) -> String {
         // 生成随机UUID
         let uuid = Uuid::new_v4().to_string();
         
         // 使用密钥、UUID和时间戳计算安全哈希作为令牌
         let mut hasher = Sha256::new();
         hasher.update(uuid.as_bytes());
-        hasher.update(self.config.secret.as_bytes());
+        hasher.update(self.config.secret.expose().as_bytes());
         hasher.update(Utc::now().timestamp().to_string().as_bytes());
         let token = format!("{:x}", hasher.finalize());
         
+        let now = Utc::now();
+        let expires_in = options.expires_in.unwrap_or(self.config.expiration);
         let info = MagicLinkInfo {
-            created_at: Instant::now(),
-            purpose,
+            created_at: now,
+            expires_at: Instant::now() + Duration::from_secs(expires_in),
+            purpose: options.purpose,
             used: false,
+            history_scope: options.history_scope,
+            permissions: options.permissions,
+            daily_quota: options.daily_quota,
+            daily_budget: options.daily_budget,
+            reusable: options.reusable,
+            quota_day: now.date_naive(),
+            used_today: 0,
         };
         
         self.links.insert(token.clone(), info);
         
-        // 启动清理任务
+        // 启动清理任务（令牌续期后会继续等待）
         let links = self.links.clone();
-        let expiration = self.config.expiration;
         let token_clone = token.clone();
         tokio::spawn(async move {
-            tokio::time::sleep(Duration::from_secs(expiration + 60)).await;
-            links.remove(&token_clone);
+            while let Some(expires_at) = links.get(&token_clone).map(|info| info.expires_at) {
+                tokio::time::sleep_until((expires_at + CLEANUP_GRACE).into()).await;
+                if links
+                    .remove_if(&token_clone, |_, info| info.expires_at + CLEANUP_GRACE <= Instant::now())
+                    .is_some()
+                {
+                    break;
+                }
+            }
         });
         
         token
     }
 
+    /// 判断令牌当前是否可用（不消耗使用次数和配额）
+    pub fn is_token_active(&self, token: &str) -> bool {
+        self.links
+            .get(token)
+            .is_some_and(|info| !info.is_expired() && (info.reusable || !info.used))
+    }
+
+    /// 令牌单独指定的每日查询成本预算
+    pub fn token_budget(&self, token: &str) -> Option<u64> {
+        self.links.get(token).and_then(|info| info.daily_budget)
+    }
+
     /// 验证魔法链接令牌
     pub fn verify_token(&self, token: &str) -> Result<String, String> {
-        if let Some(mut entry) = self.links.get_mut(token) {
-            let info = entry.value_mut();
-            
-            // 检查是否过期
-            if info.created_at.elapsed() > Duration::from_secs(self.config.expiration) {
-                return Err("Magic link expired".to_string());
-            }
-            
-            // 检查是否已使用
-            if info.used {
-                return Err("Magic link already used".to_string());
-            }
-            
-            // 标记为已使用
-            info.used = true;
-            
-            Ok(info.purpose.clone())
-        } else {
-            Err("Invalid magic link".to_string())
+        self.verify_token_with_scope(token).map(|(purpose, _)| purpose)
+    }
+
+    /// 验证魔法链接令牌，同时返回绑定的搜索历史作用域
+    pub fn verify_token_with_scope(&self, token: &str) -> Result<(String, Option<HistoryScope>), String> {
+        self.verify_request(token, None)
+            .map(|link| (link.purpose, link.history_scope))
+            .map_err(|e| e.to_string())
+    }
+
+    /// 验证魔法链接令牌对指定路径的访问
+    ///
+    /// 检查过期、一次性使用、权限范围和每日配额；`path` 为 None 时跳过权限检查
+    pub fn verify_request(&self, token: &str, path: Option<&str>) -> Result<VerifiedMagicLink, MagicLinkError> {
+        let mut entry = self.links.get_mut(token).ok_or(MagicLinkError::Invalid)?;
+        let info = entry.value_mut();
+
+        // 检查是否过期
+        if info.is_expired() {
+            return Err(MagicLinkError::Expired);
         }
+
+        // 检查是否已使用
+        if !info.reusable && info.used {
+            return Err(MagicLinkError::AlreadyUsed);
+        }
+
+        // 检查权限范围
+        if let Some(path) = path
+            && !info.permissions.is_empty()
+            && !info.permissions.iter().any(|p| p.allows_path(path))
+        {
+            return Err(MagicLinkError::Forbidden);
+        }
+
+        // 检查每日配额（按 UTC 日期重置）
+        let today = Utc::now().date_naive();
+        if info.quota_day != today {
+            info.quota_day = today;
+            info.used_today = 0;
+        }
+        if let Some(quota) = info.daily_quota
+            && info.used_today >= quota
+        {
+            return Err(MagicLinkError::QuotaExceeded);
+        }
+
+        // 标记为已使用
+        info.used = true;
+        info.used_today += 1;
+
+        Ok(VerifiedMagicLink {
+            purpose: info.purpose.clone(),
+            history_scope: info.history_scope.clone(),
+        })
+    }
+
+    /// 续期令牌，新的有效期从当前时间起算
+    ///
+    /// 已过期或已吊销的令牌不能续期
+    pub fn renew_token(&self, token: &str, expires_in: u64) -> Result<(), MagicLinkError> {
+        let mut entry = self.links.get_mut(token).ok_or(MagicLinkError::Invalid)?;
+        if entry.is_expired() {
+            return Err(MagicLinkError::Expired);
+        }
+        entry.expires_at = Instant::now() + Duration::from_secs(expires_in);
+        Ok(())
+    }
+
+    /// 吊销令牌，返回令牌是否存在
+    pub fn revoke_token(&self, token: &str) -> bool {
+        self.links.remove(token).is_some()
+    }
+
+    /// 列出所有令牌（按创建时间倒序）
+    pub fn list_tokens(&self) -> Vec<MagicLinkSummary> {
+        let now = Instant::now();
+        let mut tokens: Vec<MagicLinkSummary> = self.links
+            .iter()
+            .map(|entry| {
+                let info = entry.value();
+                MagicLinkSummary {
+                    token: entry.key().clone(),
+                    purpose: info.purpose.clone(),
+                    created_at: info.created_at,
+                    expires_in: info.expires_at.saturating_duration_since(now).as_secs(),
+                    expired: info.is_expired(),
+                    used: info.used,
+                    reusable: info.reusable,
+                    permissions: info.permissions.clone(),
+                    daily_quota: info.daily_quota,
+                    daily_budget: info.daily_budget,
+                    used_today: info.used_today,
+                    history_scope: info.history_scope.as_ref().map(|s| s.as_str().to_string()),
+                }
+            })
+            .collect();
+        tokens.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        tokens
     }
 
     /// 清理过期的链接
     pub fn cleanup_expired(&self) {
-        let expiration = Duration::from_secs(self.config.expiration);
-        self.links.retain(|_, info| {
-            info.created_at.elapsed() < expiration + Duration::from_secs(60)
-        });
+        let now = Instant::now();
+        self.links.retain(|_, info| info.expires_at + CLEANUP_GRACE > now);
     }
 
     /// 获取活跃链接数量
@@ -164,7 +489,7 @@ struct MagicLinkQuery {
 /// 魔法链接中间件
 pub async fn magic_link_middleware(
     axum::extract::State(state): axum::extract::State<Arc<MagicLinkState>>,
-    req: Request,
+    mut req: Request,
     next: Next,
 ) -> Response {
     if !state.config.enabled {
@@ -177,20 +502,25 @@ pub async fn magic_link_middleware(
     
     if let Ok(query) = serde_urlencoded::from_str::<MagicLinkQuery>(query_str) {
         if let Some(token) = query.token {
-            match state.verify_token(&token) {
-                Ok(_purpose) => {
+            match state.verify_request(&token, Some(req.uri().path())) {
+                Ok(link) => {
                     // 魔法链接验证成功，添加标记到请求扩展
                     // 这样后续的认证中间件可以跳过
                     tracing::info!("Magic link verified successfully");
+                    // 令牌绑定了历史作用域时，后续处理器据此读写搜索历史
+                    if let Some(scope) = link.history_scope {
+                        req.extensions_mut().insert(scope);
+                    }
                     return next.run(req).await;
                 }
                 Err(e) => {
                     return (
-                        StatusCode::UNAUTHORIZED,
-                        serde_json::json!({
-                            "code": "MAGIC_LINK_INVALID",
-                            "message": format!("魔法链接无效: {}", e)
-                        }).to_string()
+                        e.status_code(),
+                        Json(serde_json::json!({
+                            "code": e.code(),
+                            "message": "魔法链接验证失败",
+                            "details": e.to_string()
+                        }))
                     ).into_response();
                 }
             }
@@ -217,7 +547,7 @@ mod tests {
         let config = MagicLinkConfig {
             enabled: true,
             expiration: 300,
-            secret: "test_secret".to_string(),
+            secret: "test_secret".into(),
         };
         let state = MagicLinkState::new(config);
 
@@ -234,6 +564,97 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_magic_link_history_scope() {
+        let config = MagicLinkConfig {
+            enabled: true,
+            expiration: 300,
+            secret: "test_secret".into(),
+        };
+        let state = MagicLinkState::new(config);
+
+        let scope = HistoryScope::new("alice").unwrap();
+        let token = state.generate_token_with_scope("history".to_string(), Some(scope.clone()));
+        let (purpose, bound) = state.verify_token_with_scope(&token).unwrap();
+        assert_eq!(purpose, "history");
+        assert_eq!(bound, Some(scope));
+
+        let token = state.generate_token("plain".to_string());
+        assert_eq!(state.verify_token_with_scope(&token).unwrap().1, None);
+    }
+
+    fn test_state() -> MagicLinkState {
+        MagicLinkState::new(MagicLinkConfig {
+            enabled: true,
+            expiration: 300,
+            secret: "test_secret".into(),
+        })
+    }
+
+    #[test]
+    fn test_magic_link_permission_paths() {
+        assert_eq!(MagicLinkPermission::parse("search"), Some(MagicLinkPermission::SearchOnly));
+        assert_eq!(MagicLinkPermission::parse("unknown"), None);
+
+        assert!(MagicLinkPermission::SearchOnly.allows_path("/api/search"));
+        assert!(!MagicLinkPermission::SearchOnly.allows_path("/api/rss/fetch"));
+        assert!(MagicLinkPermission::RssAdmin.allows_path("/api/rss/fetch"));
+        assert!(MagicLinkPermission::MetricsRead.allows_path("/api/metrics"));
+        assert!(!MagicLinkPermission::MetricsRead.allows_path("/api/search"));
+    }
+
+    #[tokio::test]
+    async fn test_magic_link_scoped_and_quota() {
+        let state = test_state();
+        let token = state.generate_token_with_options(MagicLinkOptions {
+            permissions: vec![MagicLinkPermission::SearchOnly],
+            daily_quota: Some(2),
+            reusable: true,
+            ..MagicLinkOptions::new("search")
+        });
+
+        assert_eq!(
+            state.verify_request(&token, Some("/api/rss/fetch")).unwrap_err(),
+            MagicLinkError::Forbidden
+        );
+        assert!(state.verify_request(&token, Some("/api/search")).is_ok());
+        assert!(state.verify_request(&token, Some("/api/search")).is_ok());
+        assert_eq!(
+            state.verify_request(&token, Some("/api/search")).unwrap_err(),
+            MagicLinkError::QuotaExceeded
+        );
+
+        let listed = state.list_tokens();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].used_today, 2);
+        assert_eq!(listed[0].permissions, vec![MagicLinkPermission::SearchOnly]);
+    }
+
+    #[tokio::test]
+    async fn test_magic_link_renew_and_revoke() {
+        let state = test_state();
+        let token = state.generate_token_with_options(MagicLinkOptions {
+            expires_in: Some(0),
+            reusable: true,
+            ..MagicLinkOptions::new("short")
+        });
+        assert_eq!(state.verify_request(&token, None).unwrap_err(), MagicLinkError::Expired);
+        // 已过期的令牌不能续期
+        assert_eq!(state.renew_token(&token, 600).unwrap_err(), MagicLinkError::Expired);
+
+        let token = state.generate_token_with_options(MagicLinkOptions {
+            reusable: true,
+            ..MagicLinkOptions::new("long")
+        });
+        state.renew_token(&token, 3600).unwrap();
+        let summary = state.list_tokens().into_iter().find(|t| t.token == token).unwrap();
+        assert!(summary.expires_in > 300);
+
+        assert!(state.revoke_token(&token));
+        assert!(!state.revoke_token(&token));
+        assert_eq!(state.verify_request(&token, None).unwrap_err(), MagicLinkError::Invalid);
+    }
+
     #[test]
     fn test_magic_link_invalid_token() {
         let config = MagicLinkConfig::default();
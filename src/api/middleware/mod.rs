@@ -22,7 +22,14 @@ pub mod logging;
 pub mod auth;
 pub mod circuitbreaker;
 pub mod ipfilter;
+pub mod geoip;
 pub mod magiclink;
+pub mod shared;
+pub mod httpcache;
+pub mod compression;
+pub mod requestid;
+pub mod budget;
+pub mod locale;
 
 pub use cors::*;
 pub use ratelimit::*;
@@ -30,4 +37,11 @@ pub use logging::*;
 pub use auth::*;
 pub use circuitbreaker::*;
 pub use ipfilter::*;
+pub use geoip::*;
 pub use magiclink::*;
+pub use shared::*;
+pub use httpcache::*;
+pub use compression::*;
+pub use requestid::*;
+pub use budget::*;
+pub use locale::*;
@@ -14,23 +14,261 @@
 
 //! 限流中间件
 //!
-//! 提供 API 请求速率限制功能
+//! 提供 API 请求速率限制功能。响应带有 `X-RateLimit-Limit`、`X-RateLimit-Remaining` 和
+//! `X-RateLimit-Reset`（额度恢复所需秒数），被拒绝时另有 `Retry-After`，便于客户端自行限速。
 
 use axum::{
-    extract::Request,
-    http::{HeaderValue, StatusCode},
+    extract::{ConnectInfo, Request},
+    http::{header::{AUTHORIZATION, RETRY_AFTER}, HeaderMap, HeaderName, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
 use dashmap::DashMap;
 use governor::{
-    clock::DefaultClock,
+    clock::{Clock, DefaultClock},
+    middleware::{StateInformationMiddleware, StateSnapshot},
     state::{direct::NotKeyed, InMemoryState},
-    Quota, RateLimiter,
+    NotUntil, Quota, RateLimiter,
 };
-use std::net::IpAddr;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
 use std::num::NonZeroU32;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::magiclink::MagicLinkState;
+use super::shared::{SharedStateResult, SharedStateStore};
+use crate::api::secrets::SecretString;
+use crate::cache::{hash_api_key, ApiKeyRateLimit, ApiKeyStore};
+
+/// 直接限流器类型（判定时返回剩余额度）
+type DirectLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, StateInformationMiddleware>;
+
+/// 单次判定结果
+type LimiterOutcome = Result<StateSnapshot, NotUntil<<DefaultClock as Clock>::Instant>>;
+
+/// 限额响应头
+static RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+/// 剩余额度响应头
+static RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+/// 额度恢复秒数响应头
+static RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// 限流档位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitTier {
+    /// 每秒请求数
+    pub requests_per_second: u32,
+    /// 突发请求容量
+    pub burst_size: u32,
+}
+
+impl RateLimitTier {
+    /// 创建限流档位
+    pub fn new(requests_per_second: u32, burst_size: u32) -> Self {
+        Self { requests_per_second, burst_size }
+    }
+
+    fn limiter(&self) -> DirectLimiter {
+        let quota = Quota::per_second(NonZeroU32::new(self.requests_per_second.max(1)).unwrap())
+            .allow_burst(NonZeroU32::new(self.burst_size.max(1)).unwrap());
+        RateLimiter::direct(quota).with_middleware::<StateInformationMiddleware>()
+    }
+
+    /// 以 `cost` 个额度检查本地限流器（成本不超过突发容量，否则永远无法放行）
+    fn outcome(&self, limiter: &DirectLimiter, cost: u32) -> LimiterOutcome {
+        let cost = NonZeroU32::new(cost.clamp(1, self.burst_size.max(1))).unwrap();
+        limiter.check_n(cost).expect("cost is clamped to the burst size")
+    }
+
+    fn check(&self, limiter: &DirectLimiter, cost: u32) -> RateLimitStatus {
+        self.status(self.outcome(limiter, cost))
+    }
+
+    /// 本地限流器的判定结果
+    fn status(&self, outcome: LimiterOutcome) -> RateLimitStatus {
+        let limit = self.burst_size.max(1) as u64;
+        match outcome {
+            Ok(snapshot) => {
+                let remaining = snapshot.remaining_burst_capacity() as u64;
+                RateLimitStatus {
+                    allowed: true,
+                    limit,
+                    remaining,
+                    // 按速率补满已用掉的额度所需的时间
+                    reset_secs: (limit - remaining.min(limit)).div_ceil(self.requests_per_second.max(1) as u64),
+                    retry_after_secs: 0,
+                }
+            }
+            Err(not_until) => {
+                let wait = not_until.wait_time_from(DefaultClock::default().now());
+                let retry_after_secs = wait.as_millis().div_ceil(1000).max(1) as u64;
+                RateLimitStatus {
+                    allowed: false,
+                    limit,
+                    remaining: 0,
+                    reset_secs: limit.div_ceil(self.requests_per_second.max(1) as u64).max(retry_after_secs),
+                    retry_after_secs,
+                }
+            }
+        }
+    }
+
+    /// 共享状态下的固定窗口：窗口长度约为装满突发容量所需的时间，
+    /// 窗口内允许 `requests_per_second × 窗口秒数` 个请求，长期速率与本地限流一致
+    fn window(&self) -> (u64, u64) {
+        let rate = self.requests_per_second.max(1) as u64;
+        let window_secs = (self.burst_size.max(1) as u64).div_ceil(rate).max(1);
+        (window_secs, rate * window_secs)
+    }
+}
+
+impl From<ApiKeyRateLimit> for RateLimitTier {
+    fn from(limit: ApiKeyRateLimit) -> Self {
+        Self::new(limit.requests_per_second, limit.burst_size)
+    }
+}
+
+/// 限流判定结果，写入响应头
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// 是否放行
+    pub allowed: bool,
+    /// 限额（突发容量或共享窗口内的请求数）
+    pub limit: u64,
+    /// 剩余额度
+    pub remaining: u64,
+    /// 额度完全恢复（共享状态下为窗口结束）所需秒数
+    pub reset_secs: u64,
+    /// 被拒绝时建议的重试等待秒数
+    pub retry_after_secs: u64,
+}
+
+impl RateLimitStatus {
+    /// 写入限流响应头（被拒绝时另写 `Retry-After`）
+    pub fn apply(&self, headers: &mut HeaderMap) {
+        headers.insert(RATE_LIMIT_LIMIT.clone(), HeaderValue::from(self.limit));
+        headers.insert(RATE_LIMIT_REMAINING.clone(), HeaderValue::from(self.remaining));
+        headers.insert(RATE_LIMIT_RESET.clone(), HeaderValue::from(self.reset_secs));
+        if !self.allowed {
+            headers.insert(RETRY_AFTER, HeaderValue::from(self.retry_after_secs));
+        }
+    }
+}
+
+impl RateLimitStatus {
+    /// 取两次判定中更严格的一个：任一拒绝即拒绝，都放行时取剩余额度较少的
+    fn stricter(self, other: Self) -> Self {
+        match (self.allowed, other.allowed) {
+            (false, _) => self,
+            (true, false) => other,
+            (true, true) if other.remaining < self.remaining => other,
+            (true, true) => self,
+        }
+    }
+}
+
+/// 路由类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteClass {
+    /// 搜索（`/api/search*`、`/search`、`/api/news`、`/api/answer`）
+    Search,
+    /// 指标和健康检查（`/api/metrics*`、`/api/stats`、`/api/health`、`/health*`）
+    Metrics,
+    /// RSS（`/api/rss/*`）
+    Rss,
+    /// 其他路由
+    Other,
+}
+
+impl RouteClass {
+    /// 按请求路径归类
+    pub fn of(path: &str) -> Self {
+        if path.starts_with("/api/search") || path == "/search" || path == "/api/news" || path == "/api/answer" {
+            Self::Search
+        } else if path.starts_with("/api/metrics")
+            || path == "/api/stats"
+            || path == "/api/health"
+            || path == "/health"
+            || path.starts_with("/health/")
+        {
+            Self::Metrics
+        } else if path.starts_with("/api/rss/") {
+            Self::Rss
+        } else {
+            Self::Other
+        }
+    }
+
+    /// 类别名称（用于共享状态的键）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Metrics => "metrics",
+            Self::Rss => "rss",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// 请求成本规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestCost {
+    /// HTTP 方法（为空时匹配所有方法）
+    #[serde(default)]
+    pub method: Option<String>,
+    /// 路径前缀
+    pub path: String,
+    /// 每次请求消耗的额度
+    pub cost: u32,
+}
+
+impl RequestCost {
+    /// 创建成本规则
+    pub fn new(method: Option<&str>, path: impl Into<String>, cost: u32) -> Self {
+        Self { method: method.map(str::to_string), path: path.into(), cost }
+    }
+
+    fn matches(&self, method: &str, path: &str) -> bool {
+        self.method.as_ref().is_none_or(|m| m.eq_ignore_ascii_case(method)) && path.starts_with(&self.path)
+    }
+}
+
+/// 按路由类别的限流和请求成本（外网配置中的 `[external.route_limits]`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RouteLimitConfig {
+    /// 各路由类别的档位，按限流键独立计数，叠加在全局和键档位之上（未配置的类别不额外限制）
+    pub tiers: HashMap<RouteClass, RateLimitTier>,
+    /// 请求成本规则，按顺序取第一条匹配（未匹配的请求成本为 1）
+    pub costs: Vec<RequestCost>,
+}
+
+impl Default for RouteLimitConfig {
+    fn default() -> Self {
+        Self {
+            tiers: HashMap::new(),
+            // 抓取 RSS 会访问外部站点，生成答案还要抓取结果页面并调用模型，都比普通请求昂贵得多
+            costs: vec![
+                RequestCost::new(Some("POST"), "/api/rss/fetch", 5),
+                RequestCost::new(Some("POST"), "/api/answer", 10),
+            ],
+        }
+    }
+}
+
+impl RouteLimitConfig {
+    /// 请求的成本
+    pub fn cost(&self, method: &str, path: &str) -> u32 {
+        self.costs
+            .iter()
+            .find(|rule| rule.matches(method, path))
+            .map_or(1, |rule| rule.cost.max(1))
+    }
+}
 
 /// 限流配置
 #[derive(Debug, Clone)]
@@ -43,6 +281,24 @@ pub struct RateLimitConfig {
 
     /// 是否启用
     pub enabled: bool,
+
+    /// API 密钥档位（每个密钥独立计数，可被单密钥覆盖替代）
+    pub api_key_tier: RateLimitTier,
+
+    /// 魔法链接令牌档位（每个令牌独立计数）
+    pub token_tier: RateLimitTier,
+
+    /// 客户端 IP 档位（每个 IP 独立计数）
+    pub ip_tier: RateLimitTier,
+
+    /// 受信任的反向代理（IP 或 CIDR）
+    ///
+    /// 仅当直连地址属于该列表时才使用 `X-Forwarded-For` / `X-Real-IP` 确定客户端 IP；
+    /// 为空时始终使用直连地址
+    pub trusted_proxies: Vec<String>,
+
+    /// 按路由类别的档位和请求成本
+    pub routes: RouteLimitConfig,
 }
 
 impl Default for RateLimitConfig {
@@ -51,58 +307,361 @@ impl Default for RateLimitConfig {
             requests_per_second: 100,
             burst_size: 200,
             enabled: true,
+            api_key_tier: RateLimitTier::new(50, 100),
+            token_tier: RateLimitTier::new(20, 40),
+            // 每个IP的限流为全局的10%
+            ip_tier: RateLimitTier::new(10, 20),
+            trusted_proxies: Vec::new(),
+            routes: RouteLimitConfig::default(),
         }
     }
 }
 
+/// 限流键
+///
+/// 按优先级依次为 API 密钥、魔法链接令牌和客户端 IP；密钥和令牌只保存哈希
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    /// API 密钥（哈希）
+    ApiKey(String),
+    /// 魔法链接令牌（哈希）
+    MagicToken(String),
+    /// 客户端 IP
+    Ip(IpAddr),
+}
+
+impl RateLimitKey {
+    /// 共享状态中使用的键
+    fn shared_key(&self) -> String {
+        match self {
+            Self::ApiKey(hash) => format!("key:{}", hash),
+            Self::MagicToken(hash) => format!("token:{}", hash),
+            Self::Ip(ip) => format!("ip:{}", ip),
+        }
+    }
+}
+
+/// 共享限流计数器的键前缀
+const SHARED_KEY_PREFIX: &str = "seesea:ratelimit:";
+
+/// 受信任代理网段
+#[derive(Debug, Clone, Copy)]
+struct ProxyNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl ProxyNet {
+    /// 解析 IP 或 CIDR
+    fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr.trim().parse::<IpAddr>().ok()?, Some(prefix.trim().parse::<u8>().ok()?)),
+            None => (value.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { addr, prefix })
+    }
+
+    fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(*ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(*ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 受信任的反向代理列表
+///
+/// 只在直连地址属于受信任代理时才采信 `X-Forwarded-For` / `X-Real-IP`，
+/// 限流和 IP 过滤共用同一套客户端 IP 解析规则
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TrustedProxies(Vec<ProxyNet>);
+
+impl TrustedProxies {
+    /// 解析 IP 或 CIDR 列表，忽略无效条目
+    pub(crate) fn parse(values: &[String]) -> Self {
+        Self(values
+            .iter()
+            .filter_map(|p| {
+                let net = ProxyNet::parse(p);
+                if net.is_none() {
+                    tracing::warn!("忽略无效的受信任代理: {}", p);
+                }
+                net
+            })
+            .collect())
+    }
+
+    /// 确定客户端 IP
+    ///
+    /// 直连地址属于受信任代理时，从右向左跳过 `X-Forwarded-For` 中的受信任代理，
+    /// 取第一个不受信任的地址
+    pub(crate) fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() else {
+            // 服务未以 into_make_service_with_connect_info 启动时无法得到直连地址
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| tracing::warn!("请求缺少连接地址信息，无法按客户端 IP 限流和过滤"));
+            return None;
+        };
+        let peer = addr.ip();
+        if !self.contains(&peer) {
+            return Some(peer);
+        }
+
+        if let Some(forwarded) = req.headers().get("x-forwarded-for").and_then(|h| h.to_str().ok()) {
+            let hops: Vec<IpAddr> = forwarded
+                .split(',')
+                .filter_map(|ip| ip.trim().parse().ok())
+                .collect();
+            if let Some(ip) = hops.iter().rev().find(|ip| !self.contains(ip)) {
+                return Some(*ip);
+            }
+            if let Some(ip) = hops.first() {
+                return Some(*ip);
+            }
+        }
+
+        req.headers()
+            .get("x-real-ip")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|ip| ip.trim().parse().ok())
+            .or(Some(peer))
+    }
+
+    fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(ip))
+    }
+}
+
 /// 限流器状态
 pub struct RateLimiterState {
     /// 全局限流器
-    global_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
-    /// IP级别限流器映射
-    ip_limiters: Arc<DashMap<IpAddr, Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>,
+    global_limiter: Arc<DirectLimiter>,
+    /// 按限流键划分的限流器（记录创建时的档位，档位变化时重建）
+    limiters: Arc<DashMap<RateLimitKey, (RateLimitTier, Arc<DirectLimiter>)>>,
+    /// 按路由类别和限流键划分的限流器
+    route_limiters: Arc<DashMap<(RouteClass, RateLimitKey), Arc<DirectLimiter>>>,
     /// 配置
     config: RateLimitConfig,
+    /// 受信任代理
+    trusted_proxies: TrustedProxies,
+    /// 静态配置的 API 密钥（哈希）
+    static_key_hashes: HashSet<String>,
+    /// 持久化 API 密钥存储（用于识别密钥和读取单个密钥的限流覆盖）
+    key_store: Option<Arc<ApiKeyStore>>,
+    /// 魔法链接状态（用于识别有效令牌）
+    magic_links: Option<Arc<MagicLinkState>>,
+    /// 共享状态（设置后限流在所有实例之间生效）
+    shared_store: Option<Arc<dyn SharedStateStore>>,
 }
 
 impl RateLimiterState {
     /// 创建新的限流器状态
     pub fn new(config: RateLimitConfig) -> Self {
-        let quota = Quota::per_second(
-            NonZeroU32::new(config.requests_per_second).unwrap_or(NonZeroU32::new(100).unwrap())
-        )
-        .allow_burst(
-            NonZeroU32::new(config.burst_size).unwrap_or(NonZeroU32::new(200).unwrap())
+        let global_limiter = Arc::new(
+            RateLimitTier::new(config.requests_per_second, config.burst_size).limiter()
         );
-        
-        let global_limiter = Arc::new(RateLimiter::direct(quota));
+        let trusted_proxies = TrustedProxies::parse(&config.trusted_proxies);
         
         Self {
             global_limiter,
-            ip_limiters: Arc::new(DashMap::new()),
+            limiters: Arc::new(DashMap::new()),
+            route_limiters: Arc::new(DashMap::new()),
             config,
+            trusted_proxies,
+            static_key_hashes: HashSet::new(),
+            key_store: None,
+            magic_links: None,
+            shared_store: None,
         }
     }
 
-    /// 获取或创建IP限流器
-    fn get_or_create_limiter(&self, ip: IpAddr) -> Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
-        self.ip_limiters
-            .entry(ip)
-            .or_insert_with(|| {
-                // 每个IP的限流为全局的10%，但至少1请求/秒
-                let per_ip_rate = std::cmp::max(1, self.config.requests_per_second / 10);
-                let per_ip_burst = std::cmp::max(2, self.config.burst_size / 10);
-                
-                let quota = Quota::per_second(
-                    NonZeroU32::new(per_ip_rate).unwrap()
-                )
-                .allow_burst(
-                    NonZeroU32::new(per_ip_burst).unwrap()
-                );
-                Arc::new(RateLimiter::direct(quota))
-            })
+    /// 设置共享状态，使多个实例共用限流计数
+    ///
+    /// 共享状态不可用时回退到本实例的限流器
+    pub fn with_shared_store(mut self, store: Arc<dyn SharedStateStore>) -> Self {
+        self.shared_store = Some(store);
+        self
+    }
+
+    /// 设置持久化 API 密钥存储，使单个密钥的限流覆盖生效
+    pub fn with_key_store(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.key_store = Some(store);
+        self
+    }
+
+    /// 设置静态配置的 API 密钥（与 `AuthConfig.api_keys` 一致）
+    pub fn with_static_api_keys(mut self, keys: &[SecretString]) -> Self {
+        self.static_key_hashes = keys.iter().map(|k| hash_api_key(k.expose())).collect();
+        self
+    }
+
+    /// 设置魔法链接状态，使携带有效令牌的请求按令牌限流
+    pub fn with_magic_links(mut self, magic_links: Arc<MagicLinkState>) -> Self {
+        self.magic_links = Some(magic_links);
+        self
+    }
+
+    /// 确定请求的限流键和档位
+    ///
+    /// 只有有效的密钥和令牌才会作为限流键，避免随机伪造的密钥绕过 IP 限流
+    pub fn resolve_key(&self, req: &Request) -> Option<(RateLimitKey, RateLimitTier)> {
+        if let Some(api_key) = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("ApiKey "))
+        {
+            let hash = hash_api_key(api_key);
+            if self.static_key_hashes.contains(&hash) {
+                return Some((RateLimitKey::ApiKey(hash), self.config.api_key_tier));
+            }
+            if let Some(record) = self.key_store.as_ref().and_then(|s| s.verify(api_key).ok().flatten()) {
+                let tier = record.rate_limit.map(RateLimitTier::from).unwrap_or(self.config.api_key_tier);
+                return Some((RateLimitKey::ApiKey(record.key_hash), tier));
+            }
+        }
+
+        if let Some(magic_links) = &self.magic_links
+            && let Some(token) = magic_token(req)
+            && magic_links.is_token_active(&token)
+        {
+            return Some((RateLimitKey::MagicToken(hash_api_key(&token)), self.config.token_tier));
+        }
+
+        self.client_ip(req).map(|ip| (RateLimitKey::Ip(ip), self.config.ip_tier))
+    }
+
+    /// 确定客户端 IP（仅采信受信任代理转发的地址）
+    pub fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        self.trusted_proxies.client_ip(req)
+    }
+
+    /// 获取或创建限流键对应的限流器
+    fn get_or_create_limiter(&self, key: RateLimitKey, tier: RateLimitTier) -> Arc<DirectLimiter> {
+        let mut entry = self.limiters
+            .entry(key)
+            .or_insert_with(|| (tier, Arc::new(tier.limiter())));
+        // 档位变化（如密钥的限流覆盖被修改）时重建限流器
+        if entry.0 != tier {
+            *entry = (tier, Arc::new(tier.limiter()));
+        }
+        entry.1.clone()
+    }
+
+    /// 获取或创建路由类别和限流键对应的限流器
+    fn get_or_create_route_limiter(&self, class: RouteClass, key: RateLimitKey, tier: RateLimitTier) -> Arc<DirectLimiter> {
+        self.route_limiters
+            .entry((class, key))
+            .or_insert_with(|| Arc::new(tier.limiter()))
             .clone()
     }
+
+    /// 全局档位
+    fn global_tier(&self) -> RateLimitTier {
+        RateLimitTier::new(self.config.requests_per_second, self.config.burst_size)
+    }
+
+    /// 检查本实例的限流器
+    ///
+    /// 返回的额度描述请求所属键的档位（没有限流键时为全局档位）；
+    /// 请求所属的路由类别配置了档位时取两者中更严格的一个
+    fn check_local(&self, key: Option<(RateLimitKey, RateLimitTier)>, class: RouteClass, cost: u32) -> RateLimitStatus {
+        let global = self.global_tier().outcome(&self.global_limiter, cost);
+        match key {
+            Some((key, tier)) => match global {
+                Ok(_) => {
+                    let status = tier.check(&self.get_or_create_limiter(key.clone(), tier), cost);
+                    match self.config.routes.tiers.get(&class) {
+                        Some(route_tier) if status.allowed => status.stricter(
+                            route_tier.check(&self.get_or_create_route_limiter(class, key, *route_tier), cost),
+                        ),
+                        _ => status,
+                    }
+                }
+                // 全局限流拒绝时按全局的等待时间重试
+                Err(not_until) => RateLimitStatus {
+                    limit: tier.burst_size.max(1) as u64,
+                    ..self.global_tier().status(Err(not_until))
+                },
+            },
+            None => self.global_tier().status(global),
+        }
+    }
+
+    /// 检查共享状态中的限流计数
+    async fn check_shared(
+        &self,
+        store: &dyn SharedStateStore,
+        key: Option<&(RateLimitKey, RateLimitTier)>,
+        class: RouteClass,
+        cost: u32,
+    ) -> SharedStateResult<RateLimitStatus> {
+        let global = shared_allow(store, "global", self.global_tier(), cost).await?;
+        if !global.allowed {
+            return Ok(global);
+        }
+        let Some((key, tier)) = key else {
+            return Ok(global);
+        };
+        let status = shared_allow(store, &key.shared_key(), *tier, cost).await?;
+        match self.config.routes.tiers.get(&class) {
+            Some(route_tier) if status.allowed => {
+                let name = format!("route:{}:{}", class.as_str(), key.shared_key());
+                Ok(status.stricter(shared_allow(store, &name, *route_tier, cost).await?))
+            }
+            _ => Ok(status),
+        }
+    }
+}
+
+/// 在当前固定窗口内按成本计数，返回是否未超出限额和窗口内的剩余额度
+async fn shared_allow(
+    store: &dyn SharedStateStore,
+    name: &str,
+    tier: RateLimitTier,
+    cost: u32,
+) -> SharedStateResult<RateLimitStatus> {
+    let (window_secs, limit) = tier.window();
+    let cost = (cost as u64).clamp(1, limit);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    // 键中带窗口序号，所有实例按同一时钟对齐窗口
+    let key = format!("{}{}:{}", SHARED_KEY_PREFIX, name, now / window_secs);
+    let count = store.increment_by(&key, cost, Duration::from_secs(window_secs)).await?;
+    let reset_secs = window_secs - now % window_secs;
+    Ok(RateLimitStatus {
+        allowed: count <= limit,
+        limit,
+        remaining: limit.saturating_sub(count),
+        reset_secs,
+        retry_after_secs: reset_secs,
+    })
+}
+
+/// 魔法链接查询参数
+#[derive(Debug, Deserialize)]
+struct MagicTokenQuery {
+    magic_token: Option<String>,
+}
+
+/// 读取查询字符串中的魔法链接令牌
+fn magic_token(req: &Request) -> Option<String> {
+    magic_token_in_query(req.uri().query()?)
+}
+
+/// 从查询字符串中解析魔法链接令牌
+pub(crate) fn magic_token_in_query(query: &str) -> Option<String> {
+    serde_urlencoded::from_str::<MagicTokenQuery>(query)
+        .ok()?
+        .magic_token
 }
 
 /// 限流中间件
@@ -115,65 +674,44 @@ pub async fn rate_limit_middleware(
         return next.run(req).await;
     }
 
-    // 检查全局限流
-    if state.global_limiter.check().is_err() {
-        return create_rate_limit_response();
-    }
-
-    // 提取客户端IP
-    if let Some(ip) = extract_client_ip(&req) {
-        let limiter = state.get_or_create_limiter(ip);
-        if limiter.check().is_err() {
-            return create_rate_limit_response();
-        }
+    // 先检查全局限流，再按 API 密钥 / 魔法链接令牌 / 客户端 IP 限流，最后按路由类别限流
+    let key = state.resolve_key(&req);
+    let path = req.uri().path();
+    let class = RouteClass::of(path);
+    let cost = state.config.routes.cost(req.method().as_str(), path);
+    let status = match &state.shared_store {
+        Some(store) => match state.check_shared(store.as_ref(), key.as_ref(), class, cost).await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!("共享限流状态不可用，使用本实例限流: {}", e);
+                state.check_local(key, class, cost)
+            }
+        },
+        None => state.check_local(key, class, cost),
+    };
+    if !status.allowed {
+        return create_rate_limit_response(&status);
     }
 
-    next.run(req).await
+    // 放行的响应同样带上剩余额度
+    let mut response = next.run(req).await;
+    status.apply(response.headers_mut());
+    response
 }
 
 /// 创建限流响应
-fn create_rate_limit_response() -> Response {
+fn create_rate_limit_response(status: &RateLimitStatus) -> Response {
     let mut response = (
         StatusCode::TOO_MANY_REQUESTS,
-        serde_json::json!({
+        Json(serde_json::json!({
             "code": "RATE_LIMIT_EXCEEDED",
             "message": "请求过于频繁，请稍后再试"
-        }).to_string()
+        }))
     ).into_response();
-    
-    response.headers_mut().insert(
-        "Retry-After",
-        HeaderValue::from_static("60"),
-    );
-    
+    status.apply(response.headers_mut());
     response
 }
 
-/// 提取客户端IP
-fn extract_client_ip(req: &Request) -> Option<IpAddr> {
-    // 尝试从X-Forwarded-For获取
-    if let Some(forwarded) = req.headers().get("x-forwarded-for") {
-        if let Ok(forwarded_str) = forwarded.to_str() {
-            if let Some(ip_str) = forwarded_str.split(',').next() {
-                if let Ok(ip) = ip_str.trim().parse() {
-                    return Some(ip);
-                }
-            }
-        }
-    }
-
-    // 尝试从X-Real-IP获取
-    if let Some(real_ip) = req.headers().get("x-real-ip") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            if let Ok(ip) = ip_str.parse() {
-                return Some(ip);
-            }
-        }
-    }
-
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,9 +724,225 @@ mod tests {
         assert!(config.enabled);
     }
 
+    #[test]
+    fn test_route_class_and_cost() {
+        assert_eq!(RouteClass::of("/api/search"), RouteClass::Search);
+        assert_eq!(RouteClass::of("/api/metrics/realtime"), RouteClass::Metrics);
+        assert_eq!(RouteClass::of("/health/ready"), RouteClass::Metrics);
+        assert_eq!(RouteClass::of("/api/rss/fetch"), RouteClass::Rss);
+        assert_eq!(RouteClass::of("/api/answer"), RouteClass::Search);
+        assert_eq!(RouteClass::of("/api/engines"), RouteClass::Other);
+
+        let routes = RouteLimitConfig::default();
+        assert_eq!(routes.cost("POST", "/api/rss/fetch"), 5);
+        assert_eq!(routes.cost("GET", "/api/rss/fetch"), 1);
+        assert_eq!(routes.cost("POST", "/api/answer"), 10);
+        assert_eq!(routes.cost("GET", "/api/health"), 1);
+    }
+
+    #[test]
+    fn test_route_tier_and_cost_limits() {
+        let state = RateLimiterState::new(RateLimitConfig {
+            ip_tier: RateLimitTier::new(1, 10),
+            routes: RouteLimitConfig {
+                tiers: HashMap::from([(RouteClass::Metrics, RateLimitTier::new(1, 2))]),
+                costs: vec![RequestCost::new(Some("POST"), "/api/rss/fetch", 4)],
+            },
+            ..Default::default()
+        });
+        let key = || state.resolve_key(&request(None, "", "1.1.1.1:1", None));
+
+        // 指标类路由受更严格的档位限制，剩余额度取更严格的一个
+        let status = state.check_local(key(), RouteClass::Metrics, 1);
+        assert!(status.allowed);
+        assert_eq!((status.limit, status.remaining), (2, 1));
+        assert!(state.check_local(key(), RouteClass::Metrics, 1).allowed);
+        assert!(!state.check_local(key(), RouteClass::Metrics, 1).allowed);
+        // 其他类别只受 IP 档位限制
+        assert_eq!(state.check_local(key(), RouteClass::Search, 1).remaining, 6);
+
+        // 昂贵的请求一次消耗多个额度
+        let cost = state.config.routes.cost("POST", "/api/rss/fetch");
+        assert_eq!(state.check_local(key(), RouteClass::Rss, cost).remaining, 2);
+        assert!(!state.check_local(key(), RouteClass::Rss, cost).allowed);
+    }
+
     #[test]
     fn test_rate_limiter_state_creation() {
         let config = RateLimitConfig::default();
         let _state = RateLimiterState::new(config);
     }
+
+    fn request(auth: Option<&str>, query: &str, peer: &str, forwarded: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(format!("/api/search?{}", query));
+        if let Some(auth) = auth {
+            builder = builder.header("authorization", auth);
+        }
+        if let Some(forwarded) = forwarded {
+            builder = builder.header("x-forwarded-for", forwarded);
+        }
+        let mut req = builder.body(axum::body::Body::empty()).unwrap();
+        req.extensions_mut().insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        req
+    }
+
+    #[test]
+    fn test_proxy_net() {
+        let net = ProxyNet::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!net.contains(&"11.0.0.1".parse().unwrap()));
+        assert!(ProxyNet::parse("::1").unwrap().contains(&"::1".parse().unwrap()));
+        assert!(ProxyNet::parse("0.0.0.0/0").unwrap().contains(&"8.8.8.8".parse().unwrap()));
+        assert!(ProxyNet::parse("10.0.0.0/33").is_none());
+        assert!(ProxyNet::parse("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_client_ip_trusted_proxies() {
+        let state = RateLimiterState::new(RateLimitConfig::default());
+        // 未配置受信任代理时忽略 X-Forwarded-For
+        let req = request(None, "", "203.0.113.9:1234", Some("1.2.3.4"));
+        assert_eq!(state.client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+
+        let state = RateLimiterState::new(RateLimitConfig {
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        });
+        let req = request(None, "", "10.0.0.1:1234", Some("1.2.3.4, 5.6.7.8, 10.0.0.2"));
+        assert_eq!(state.client_ip(&req), Some("5.6.7.8".parse().unwrap()));
+        // 直连地址不受信任时不使用转发头
+        let req = request(None, "", "203.0.113.9:1234", Some("1.2.3.4"));
+        assert_eq!(state.client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_key_priority() {
+        use super::super::magiclink::{MagicLinkConfig, MagicLinkOptions};
+
+        let magic_links = Arc::new(MagicLinkState::new(MagicLinkConfig {
+            enabled: true,
+            expiration: 300,
+            secret: "test_secret".into(),
+        }));
+        let token = magic_links.generate_token_with_options(MagicLinkOptions {
+            reusable: true,
+            ..MagicLinkOptions::new("rate")
+        });
+        let config = RateLimitConfig::default();
+        let state = RateLimiterState::new(config.clone())
+            .with_static_api_keys(&["static_key".into()])
+            .with_magic_links(magic_links);
+
+        let query = format!("magic_token={}", token);
+        let (key, tier) = state.resolve_key(&request(Some("ApiKey static_key"), &query, "1.1.1.1:1", None)).unwrap();
+        assert!(matches!(key, RateLimitKey::ApiKey(_)));
+        assert_eq!(tier, config.api_key_tier);
+
+        // 无效密钥回退到令牌
+        let (key, tier) = state.resolve_key(&request(Some("ApiKey forged"), &query, "1.1.1.1:1", None)).unwrap();
+        assert!(matches!(key, RateLimitKey::MagicToken(_)));
+        assert_eq!(tier, config.token_tier);
+
+        // 无效令牌回退到 IP
+        let (key, tier) = state.resolve_key(&request(None, "magic_token=forged", "1.1.1.1:1", None)).unwrap();
+        assert_eq!(key, RateLimitKey::Ip("1.1.1.1".parse().unwrap()));
+        assert_eq!(tier, config.ip_tier);
+    }
+
+    #[test]
+    fn test_api_key_rate_limit_override() {
+        use crate::cache::CacheManager;
+
+        let store = Arc::new(ApiKeyStore::new(
+            CacheManager::temporary(),
+        ));
+        let limit = ApiKeyRateLimit { requests_per_second: 1, burst_size: 2 };
+        let (_, limited_key) = store.create("limited", Some(limit)).unwrap();
+        let (_, plain_key) = store.create("plain", None).unwrap();
+        let state = RateLimiterState::new(RateLimitConfig::default()).with_key_store(store);
+
+        let auth = |key: &str| format!("ApiKey {}", key);
+        let (key, tier) = state.resolve_key(&request(Some(&auth(&limited_key)), "", "1.1.1.1:1", None)).unwrap();
+        assert_eq!(tier, RateLimitTier::new(1, 2));
+        let limiter = state.get_or_create_limiter(key.clone(), tier);
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_err());
+        // 同一密钥复用同一个限流器
+        assert!(state.get_or_create_limiter(key, tier).check().is_err());
+
+        let (_, tier) = state.resolve_key(&request(Some(&auth(&plain_key)), "", "1.1.1.1:1", None)).unwrap();
+        assert_eq!(tier, RateLimitConfig::default().api_key_tier);
+    }
+
+    #[tokio::test]
+    async fn test_shared_store_limits_across_instances() {
+        use super::super::shared::MemoryStateStore;
+
+        assert_eq!(RateLimitTier::new(10, 20).window(), (2, 20));
+        assert_eq!(RateLimitTier::new(100, 50).window(), (1, 100));
+
+        let store: Arc<dyn SharedStateStore> = Arc::new(MemoryStateStore::new());
+        let config = RateLimitConfig {
+            ip_tier: RateLimitTier::new(2, 2),
+            ..Default::default()
+        };
+        // 两个实例共用同一个计数
+        let first = RateLimiterState::new(config.clone()).with_shared_store(store.clone());
+        let second = RateLimiterState::new(config).with_shared_store(store.clone());
+
+        let key = first.resolve_key(&request(None, "", "1.1.1.1:1", None));
+        let other = second.resolve_key(&request(None, "", "2.2.2.2:1", None));
+        let second_of = || SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        loop {
+            let started = second_of();
+            let results = [
+                first.check_shared(store.as_ref(), key.as_ref(), RouteClass::Search, 1).await.unwrap(),
+                second.check_shared(store.as_ref(), key.as_ref(), RouteClass::Search, 1).await.unwrap(),
+                first.check_shared(store.as_ref(), key.as_ref(), RouteClass::Search, 1).await.unwrap(),
+                // 其他 IP 不受影响
+                second.check_shared(store.as_ref(), other.as_ref(), RouteClass::Search, 1).await.unwrap(),
+            ];
+            // 跨越窗口边界时计数被重置，换一个窗口重试
+            if second_of() == started {
+                assert_eq!(results.map(|status| status.allowed), [true, true, false, true]);
+                assert_eq!(results.map(|status| status.remaining), [1, 0, 0, 1]);
+                break;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers() {
+        use axum::{middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let state = Arc::new(RateLimiterState::new(RateLimitConfig {
+            ip_tier: RateLimitTier::new(1, 2),
+            ..Default::default()
+        }));
+        let app = Router::new()
+            .route("/api/search", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(state, rate_limit_middleware));
+        let header = |response: &Response, name: &str| {
+            response.headers().get(name).map(|v| v.to_str().unwrap().parse::<u64>().unwrap())
+        };
+
+        let response = app.clone().oneshot(request(None, "", "1.1.1.1:1", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header(&response, "x-ratelimit-limit"), Some(2));
+        assert_eq!(header(&response, "x-ratelimit-remaining"), Some(1));
+        assert_eq!(header(&response, "x-ratelimit-reset"), Some(1));
+        assert_eq!(header(&response, "retry-after"), None);
+
+        let response = app.clone().oneshot(request(None, "", "1.1.1.1:1", None)).await.unwrap();
+        assert_eq!(header(&response, "x-ratelimit-remaining"), Some(0));
+        assert_eq!(header(&response, "x-ratelimit-reset"), Some(2));
+
+        let response = app.oneshot(request(None, "", "1.1.1.1:1", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(header(&response, "x-ratelimit-remaining"), Some(0));
+        assert_eq!(header(&response, "retry-after"), Some(1));
+    }
+
 }
@@ -0,0 +1,166 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 请求 ID 中间件
+//!
+//! 为每个请求分配 `x-request-id`（合法的传入值原样沿用，例如由反向代理生成），
+//! 写入请求扩展、根 span 和访问日志，并在响应头中返回；错误响应的
+//! `ApiErrorResponse.details` 会附上请求 ID，用户反馈“搜索失败”时可据此找到服务端链路。
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::telemetry::new_query_id;
+
+/// 请求 ID 头
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 传入请求 ID 的最大长度
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// 改写错误响应时读取的最大响应体（超过时原样返回）
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// 当前请求的 ID（写入请求扩展）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// 沿用合法的传入 ID，否则生成新的 ID
+    ///
+    /// 只接受字母、数字和 `-_.:`，防止日志注入
+    pub fn from_header(value: Option<&HeaderValue>) -> Self {
+        value
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+            })
+            .map(|id| Self(id.to_string()))
+            .unwrap_or_else(|| Self(new_query_id()))
+    }
+}
+
+/// 在 `ApiErrorResponse` 形式的 JSON 中附上请求 ID（不是该形式时返回 None）
+fn append_request_id(body: &[u8], request_id: &str) -> Option<Vec<u8>> {
+    let mut value: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let object = value.as_object_mut()?;
+    if !(object.get("code")?.is_string() && object.get("message")?.is_string()) {
+        return None;
+    }
+    let details = match object.get("details").and_then(serde_json::Value::as_str) {
+        Some(details) => format!("{} (request_id: {})", details, request_id),
+        None => format!("request_id: {}", request_id),
+    };
+    object.insert("details".to_string(), serde_json::Value::String(details));
+    serde_json::to_vec(&value).ok()
+}
+
+/// 请求 ID 中间件
+///
+/// 应位于链路追踪层之外，根 span 才能记录请求 ID
+pub async fn request_id_middleware(mut req: Request<Body>, next: Next) -> Response {
+    let request_id = RequestId::from_header(req.headers().get(&REQUEST_ID_HEADER));
+    let header_value = HeaderValue::from_str(&request_id.0).ok();
+    if let Some(value) = &header_value {
+        req.headers_mut().insert(REQUEST_ID_HEADER.clone(), value.clone());
+    }
+    req.extensions_mut().insert(request_id.clone());
+
+    let mut response = next.run(req).await;
+    if let Some(value) = header_value {
+        response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !(response.status().is_client_error() || response.status().is_server_error()) || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    match append_request_id(&bytes, &request_id.0) {
+        Some(rewritten) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(rewritten))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::ApiErrorResponse;
+    use axum::{http::StatusCode, middleware, response::IntoResponse, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/fail", get(|| async {
+                let error = ApiErrorResponse {
+                    code: "SEARCH_ERROR".to_string(),
+                    message: "搜索失败".to_string(),
+                    details: Some("timeout".to_string()),
+                };
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+            }))
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    #[test]
+    fn test_request_id_from_header() {
+        let valid = HeaderValue::from_static("req-123.abc");
+        assert_eq!(RequestId::from_header(Some(&valid)).0, "req-123.abc");
+
+        let invalid = HeaderValue::from_static("bad id\"");
+        assert_eq!(RequestId::from_header(Some(&invalid)).0.len(), 32);
+        assert_eq!(RequestId::from_header(None).0.len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_propagation() {
+        let request = Request::builder().uri("/ok").header("x-request-id", "abc-1").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"], "abc-1");
+
+        let response = app().oneshot(Request::builder().uri("/ok").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.headers()["x-request-id"].len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_error_details_include_request_id() {
+        let request = Request::builder().uri("/fail").header("x-request-id", "abc-2").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ApiErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, "SEARCH_ERROR");
+        assert_eq!(error.details.as_deref(), Some("timeout (request_id: abc-2)"));
+    }
+}
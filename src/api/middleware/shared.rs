@@ -0,0 +1,371 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 中间件共享状态
+//!
+//! 多个实例部署在负载均衡后面时，限流计数和熔断状态需要放在所有实例都能访问的
+//! 存储中才能在集群范围内生效。[`SharedStateStore`] 抽象了所需的少量操作，
+//! [`RedisStateStore`] 通过 RESP 协议直接访问 Redis，[`MemoryStateStore`]
+//! 用于单实例和测试。
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufStream};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+
+use crate::config::cache::RedisConfig;
+
+/// 共享状态操作结果
+pub type SharedStateResult<T> = std::result::Result<T, String>;
+
+/// 共享状态存储
+#[async_trait]
+pub trait SharedStateStore: Send + Sync {
+    /// 计数器加一并返回新值；键首次创建时设置 `ttl` 过期时间
+    async fn increment(&self, key: &str, ttl: Duration) -> SharedStateResult<u64> {
+        self.increment_by(key, 1, ttl).await
+    }
+
+    /// 计数器加 `amount` 并返回新值；键首次创建时设置 `ttl` 过期时间
+    async fn increment_by(&self, key: &str, amount: u64, ttl: Duration) -> SharedStateResult<u64>;
+
+    /// 读取键的值
+    async fn get(&self, key: &str) -> SharedStateResult<Option<String>>;
+
+    /// 写入键的值并设置过期时间
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> SharedStateResult<()>;
+
+    /// 删除键
+    async fn delete(&self, key: &str) -> SharedStateResult<()>;
+}
+
+/// 进程内共享状态（单实例部署或测试使用）
+#[derive(Default)]
+pub struct MemoryStateStore {
+    entries: DashMap<String, (String, Instant)>,
+}
+
+impl MemoryStateStore {
+    /// 创建空的进程内存储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn live_value(&self, key: &str) -> Option<String> {
+        let entry = self.entries.get(key)?;
+        (entry.1 > Instant::now()).then(|| entry.0.clone())
+    }
+}
+
+#[async_trait]
+impl SharedStateStore for MemoryStateStore {
+    async fn increment_by(&self, key: &str, amount: u64, ttl: Duration) -> SharedStateResult<u64> {
+        let now = Instant::now();
+        let mut entry = self.entries
+            .entry(key.to_string())
+            .or_insert_with(|| ("0".to_string(), now + ttl));
+        if entry.1 <= now {
+            *entry = ("0".to_string(), now + ttl);
+        }
+        let count = entry.0.parse::<u64>().map_err(|e| format!("值不是整数: {}", e))? + amount;
+        entry.0 = count.to_string();
+        Ok(count)
+    }
+
+    async fn get(&self, key: &str) -> SharedStateResult<Option<String>> {
+        Ok(self.live_value(key))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> SharedStateResult<()> {
+        self.entries.insert(key.to_string(), (value.to_string(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> SharedStateResult<()> {
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+/// RESP 应答
+#[derive(Debug, Clone, PartialEq)]
+enum RespValue {
+    Simple(String),
+    /// 错误应答（命令失败，连接仍可继续使用）
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+}
+
+/// 计数器加一并在键没有过期时间时设置过期时间，两步在 Redis 中原子执行，
+/// 不会因为设置过期时间失败而留下永不过期的计数
+const INCREMENT_SCRIPT: &str = "local count = redis.call('INCRBY', KEYS[1], ARGV[1]) \
+if redis.call('PTTL', KEYS[1]) < 0 then redis.call('PEXPIRE', KEYS[1], ARGV[2]) end \
+return count";
+
+/// Redis 共享状态
+///
+/// 使用最多 `pool_size` 条连接的连接池，命令并发执行；读写出错或超时的连接被丢弃，
+/// Redis 返回的错误应答不影响连接。暂不支持 TLS 和集群模式
+pub struct RedisStateStore {
+    config: RedisConfig,
+    /// 空闲连接
+    idle: std::sync::Mutex<Vec<BufStream<TcpStream>>>,
+    /// 连接数上限
+    permits: Semaphore,
+}
+
+impl RedisStateStore {
+    /// 创建 Redis 共享状态（首次使用时才建立连接）
+    pub fn new(config: RedisConfig) -> SharedStateResult<Self> {
+        if config.use_tls {
+            return Err("共享状态暂不支持 TLS 连接 Redis".to_string());
+        }
+        if config.cluster_config.is_some() {
+            tracing::warn!("共享状态不支持 Redis 集群，仅连接 {}:{}", config.host, config.port);
+        }
+        let permits = Semaphore::new(config.pool_size.max(1));
+        Ok(Self { config, idle: std::sync::Mutex::new(Vec::new()), permits })
+    }
+
+    async fn connect(&self) -> SharedStateResult<BufStream<TcpStream>> {
+        let address = format!("{}:{}", self.config.host, self.config.port);
+        let stream = tokio::time::timeout(self.timeout(), TcpStream::connect(&address))
+            .await
+            .map_err(|_| format!("连接 Redis {} 超时", address))?
+            .map_err(|e| format!("连接 Redis {} 失败: {}", address, e))?;
+        let mut stream = BufStream::new(stream);
+
+        if let Some(password) = &self.config.password {
+            expect_ok(round_trip(&mut stream, &[b"AUTH", password.as_bytes()]).await?)?;
+        }
+        if self.config.database != 0 {
+            let database = self.config.database.to_string();
+            expect_ok(round_trip(&mut stream, &[b"SELECT", database.as_bytes()]).await?)?;
+        }
+        Ok(stream)
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.config.timeout.max(1))
+    }
+
+    /// 执行一条命令
+    ///
+    /// 从连接池取出空闲连接（没有时新建），命令完成后放回；错误应答作为错误返回
+    async fn command(&self, args: &[&[u8]]) -> SharedStateResult<RespValue> {
+        let _permit = tokio::time::timeout(self.timeout(), self.permits.acquire())
+            .await
+            .map_err(|_| "等待 Redis 连接超时".to_string())?
+            .map_err(|_| "Redis 连接池已关闭".to_string())?;
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut stream = match idle {
+            Some(stream) => stream,
+            None => self.connect().await?,
+        };
+
+        let value = tokio::time::timeout(self.timeout(), round_trip(&mut stream, args))
+            .await
+            .map_err(|_| "Redis 命令超时".to_string())??;
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).push(stream);
+        match value {
+            RespValue::Error(message) => Err(format!("Redis 错误: {}", message)),
+            value => Ok(value),
+        }
+    }
+}
+
+#[async_trait]
+impl SharedStateStore for RedisStateStore {
+    async fn increment_by(&self, key: &str, amount: u64, ttl: Duration) -> SharedStateResult<u64> {
+        let amount = amount.to_string();
+        let ttl = ttl.as_millis().max(1).to_string();
+        let args: [&[u8]; 6] = [b"EVAL", INCREMENT_SCRIPT.as_bytes(), b"1", key.as_bytes(), amount.as_bytes(), ttl.as_bytes()];
+        match self.command(&args).await? {
+            RespValue::Integer(count) => Ok(count.max(0) as u64),
+            other => Err(format!("EVAL 返回了意外的应答: {:?}", other)),
+        }
+    }
+
+    async fn get(&self, key: &str) -> SharedStateResult<Option<String>> {
+        match self.command(&[b"GET", key.as_bytes()]).await? {
+            RespValue::Bulk(value) => Ok(value.map(|v| String::from_utf8_lossy(&v).into_owned())),
+            other => Err(format!("GET 返回了意外的应答: {:?}", other)),
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> SharedStateResult<()> {
+        let ttl = ttl.as_millis().max(1).to_string();
+        expect_ok(self.command(&[b"SET", key.as_bytes(), value.as_bytes(), b"PX", ttl.as_bytes()]).await?)
+    }
+
+    async fn delete(&self, key: &str) -> SharedStateResult<()> {
+        self.command(&[b"DEL", key.as_bytes()]).await.map(|_| ())
+    }
+}
+
+fn expect_ok(value: RespValue) -> SharedStateResult<()> {
+    match value {
+        RespValue::Simple(s) if s == "OK" => Ok(()),
+        RespValue::Error(message) => Err(format!("Redis 错误: {}", message)),
+        other => Err(format!("Redis 返回了意外的应答: {:?}", other)),
+    }
+}
+
+/// 发送命令并读取应答
+async fn round_trip(stream: &mut BufStream<TcpStream>, args: &[&[u8]]) -> SharedStateResult<RespValue> {
+    stream.write_all(&encode_command(args)).await.map_err(|e| format!("写入 Redis 失败: {}", e))?;
+    stream.flush().await.map_err(|e| format!("写入 Redis 失败: {}", e))?;
+    read_value(stream).await
+}
+
+/// 编码 RESP 命令数组
+fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut buffer = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buffer.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buffer.extend_from_slice(arg);
+        buffer.extend_from_slice(b"\r\n");
+    }
+    buffer
+}
+
+/// 读取一个 RESP 应答（不支持数组，本模块的命令不会返回数组）
+async fn read_value<R: AsyncBufRead + Unpin>(reader: &mut R) -> SharedStateResult<RespValue> {
+    let mut line = String::new();
+    let read = reader.read_line(&mut line).await.map_err(|e| format!("读取 Redis 应答失败: {}", e))?;
+    if read == 0 {
+        return Err("Redis 连接已关闭".to_string());
+    }
+    let line = line.trim_end_matches(['\r', '\n']);
+    let (kind, body) = line.split_at_checked(1).ok_or("Redis 应答为空")?;
+
+    match kind {
+        "+" => Ok(RespValue::Simple(body.to_string())),
+        "-" => Ok(RespValue::Error(body.to_string())),
+        ":" => body.parse().map(RespValue::Integer).map_err(|e| format!("无效的整数应答: {}", e)),
+        "$" => {
+            let length: i64 = body.parse().map_err(|e| format!("无效的字符串长度: {}", e))?;
+            if length < 0 {
+                return Ok(RespValue::Bulk(None));
+            }
+            let mut data = vec![0; length as usize + 2];
+            reader.read_exact(&mut data).await.map_err(|e| format!("读取 Redis 应答失败: {}", e))?;
+            data.truncate(length as usize);
+            Ok(RespValue::Bulk(Some(data)))
+        }
+        _ => Err(format!("不支持的 Redis 应答类型: {}", line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resp_encoding_and_parsing() {
+        assert_eq!(encode_command(&[b"GET", b"k"]), b"*2\r\n$3\r\nGET\r\n$1\r\nk\r\n");
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let parse = |input: &'static [u8]| runtime.block_on(async move {
+            let mut reader = input;
+            read_value(&mut reader).await
+        });
+        assert_eq!(parse(b"+OK\r\n"), Ok(RespValue::Simple("OK".to_string())));
+        assert_eq!(parse(b":42\r\n"), Ok(RespValue::Integer(42)));
+        assert_eq!(parse(b"$5\r\nhe\r\no\r\n"), Ok(RespValue::Bulk(Some(b"he\r\no".to_vec()))));
+        assert_eq!(parse(b"$-1\r\n"), Ok(RespValue::Bulk(None)));
+        assert_eq!(parse(b"-ERR wrong\r\n"), Ok(RespValue::Error("ERR wrong".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_expiry() {
+        let store = MemoryStateStore::new();
+        assert_eq!(store.increment("k", Duration::from_millis(30)).await, Ok(1));
+        assert_eq!(store.increment("k", Duration::from_millis(30)).await, Ok(2));
+        assert_eq!(store.increment_by("k", 3, Duration::from_millis(30)).await, Ok(5));
+        store.set("s", "v", Duration::from_millis(30)).await.unwrap();
+        assert_eq!(store.get("s").await, Ok(Some("v".to_string())));
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(store.get("s").await, Ok(None));
+        assert_eq!(store.increment("k", Duration::from_millis(30)).await, Ok(1));
+    }
+
+    /// 在本地端口启动假 Redis：EVAL 返回 1，GET 返回错误应答，记录连接数和收到的命令
+    async fn fake_redis() -> (RedisConfig, std::sync::Arc<std::sync::atomic::AtomicUsize>, std::sync::Arc<std::sync::Mutex<Vec<Vec<String>>>>) {
+        use std::sync::atomic::Ordering;
+        use std::sync::Arc;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let connections = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let commands = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (accepted, received) = (connections.clone(), commands.clone());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let received = received.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufStream::new(socket);
+                    loop {
+                        let mut header = String::new();
+                        if stream.read_line(&mut header).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                        let count: usize = header.trim()[1..].parse().unwrap();
+                        let mut args = Vec::new();
+                        for _ in 0..count {
+                            let Ok(RespValue::Bulk(Some(arg))) = read_value(&mut stream).await else {
+                                return;
+                            };
+                            args.push(String::from_utf8(arg).unwrap());
+                        }
+                        let reply: &[u8] = match args[0].as_str() {
+                            "EVAL" => b":1\r\n",
+                            _ => b"-ERR unknown command\r\n",
+                        };
+                        received.lock().unwrap().push(args);
+                        stream.write_all(reply).await.unwrap();
+                        stream.flush().await.unwrap();
+                    }
+                });
+            }
+        });
+        let config = RedisConfig { host: "127.0.0.1".to_string(), port, pool_size: 2, ..Default::default() };
+        (config, connections, commands)
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_atomic_increment_and_error_reply() {
+        use std::sync::atomic::Ordering;
+
+        let (config, connections, commands) = fake_redis().await;
+        let store = RedisStateStore::new(config).unwrap();
+
+        // 计数和过期时间在同一个脚本中设置
+        assert_eq!(store.increment("rl:k", Duration::from_secs(60)).await, Ok(1));
+        let eval = commands.lock().unwrap()[0].clone();
+        assert_eq!(eval[0], "EVAL");
+        assert!(eval[1].contains("INCRBY") && eval[1].contains("PEXPIRE"));
+        assert_eq!(&eval[2..], ["1", "rl:k", "1", "60000"]);
+
+        // 错误应答作为错误返回，连接继续复用
+        assert!(store.get("rl:k").await.unwrap_err().contains("ERR unknown command"));
+        assert_eq!(store.increment("rl:k", Duration::from_secs(60)).await, Ok(1));
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+}
@@ -22,9 +22,18 @@ pub mod on;
 pub mod handlers;
 pub mod middleware;
 pub mod metrics;
+pub mod memory;
 pub mod network;
+pub mod secrets;
+pub mod telemetry;
+pub mod validation;
+pub mod i18n;
 
 pub use types::*;
 pub use on::*;
 pub use metrics::*;
 pub use network::*;
+pub use validation::{Validate, ValidatedJson, ValidatedQuery, ValidationError, ValidationLimits};
+pub use i18n::Locale;
+pub use secrets::{ResolvedSecrets, SecretError, SecretString, SecretsConfig};
+pub use telemetry::{TelemetryConfig, TelemetryGuard, init_tracing};
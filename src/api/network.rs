@@ -17,6 +17,11 @@
 //! 提供内网和外网的分离配置
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config::cache::RedisConfig;
+use super::middleware::{QueryBudgetConfig, RouteLimitConfig};
+use super::secrets::SecretsConfig;
 
 /// 网络模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -81,6 +86,38 @@ pub struct ExternalNetworkConfig {
     
     /// 是否启用魔法链接
     pub enable_magic_link: bool,
+    
+    /// 受信任的反向代理（IP 或 CIDR），用于从 X-Forwarded-For 确定客户端 IP
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// GeoIP 数据库路径（MaxMind DB 格式），用于国家过滤和默认引擎选择
+    #[serde(default)]
+    pub geoip_database: Option<PathBuf>,
+
+    /// 允许的国家代码（非空时只允许这些国家）
+    #[serde(default)]
+    pub country_allowlist: Vec<String>,
+
+    /// 拒绝的国家代码
+    #[serde(default)]
+    pub country_denylist: Vec<String>,
+
+    /// 共享状态（Redis），多实例部署时使限流和熔断在集群范围内生效
+    #[serde(default)]
+    pub shared_state: Option<RedisConfig>,
+
+    /// 查询成本预算（按 API 密钥 / 魔法链接令牌的每日预算，默认关闭）
+    #[serde(default)]
+    pub query_budget: QueryBudgetConfig,
+
+    /// 按路由类别（搜索、指标、RSS）的限流档位和请求成本
+    #[serde(default)]
+    pub route_limits: RouteLimitConfig,
+
+    /// JWT、魔法链接和静态 API 密钥的来源（内联、文件或环境变量）
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
 impl Default for ExternalNetworkConfig {
@@ -95,6 +132,14 @@ impl Default for ExternalNetworkConfig {
             enable_ip_filter: true,
             enable_jwt_auth: false, // 默认不启用JWT，避免影响现有用户
             enable_magic_link: true,
+            trusted_proxies: Vec::new(),
+            geoip_database: None,
+            country_allowlist: Vec::new(),
+            country_denylist: Vec::new(),
+            shared_state: None,
+            query_budget: QueryBudgetConfig::default(),
+            route_limits: RouteLimitConfig::default(),
+            secrets: SecretsConfig::default(),
         }
     }
 }
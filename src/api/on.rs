@@ -16,35 +16,58 @@
 //!
 //! 提供高层次的 HTTP API 接口供外部调用
 
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
 use axum::{
     Router,
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post},
 };
+use tower_http::trace::TraceLayer;
 
-use crate::cache::CacheInterface;
+use crate::cache::{
+    CacheInterface,
+    HistoryCache, HistoryConfig, HistoryScope,
+    ClickStatsCache, ClickTrackingConfig,
+    ApiKeyStore, RssCache,
+};
 use crate::net::NetworkInterface;
+use crate::notify::{AlertScheduler, WebhookDispatcher};
+use crate::rss::{BoardsConfig, RssBoards};
 use crate::search::SearchInterface;
 use super::handlers::{
-    rss, cache,
+    rss, cache, history, click, admin, apikeys, news, tools, answer, webhooks, alerts, usage, boards,
     handle_search, handle_search_post,
-    handle_health,
-    handle_stats, handle_engines_list, handle_version,
+    handle_health, handle_liveness, handle_readiness,
+    handle_stats, handle_engines_list, handle_version, handle_instance,
     handle_metrics, handle_realtime_metrics,
-    handle_magic_link_generate,
-    handle_index, handle_favicon,
+    handle_magic_link_generate, handle_magic_link_list,
+    handle_magic_link_revoke, handle_magic_link_renew,
+    handle_index, handle_search_page, handle_board_page, handle_robots_txt, handle_favicon, handle_admin_dashboard, handle_admin_js,
 };
 use super::middleware::{
-    cors, 
+    CorsState, CorsConfig, 
     RateLimiterState, RateLimitConfig, rate_limit_middleware,
-    CircuitBreakerState, CircuitBreakerConfig, circuit_breaker_middleware,
+    CircuitBreakerRegistry, CircuitBreakerConfig, circuit_breaker_middleware,
     IpFilterState, IpFilterConfig, ip_filter_middleware,
     AuthState, AuthConfig, jwt_auth_middleware,
     MagicLinkState, MagicLinkConfig, magic_link_middleware,
+    AccessLogState, AccessLogConfig, access_log_middleware,
+    HttpCacheState, HttpCacheConfig, http_cache_middleware,
+    CompressionConfig, request_id_middleware, localize_errors_middleware,
+    RedisStateStore, SharedStateStore,
+    QueryBudgetState, query_budget_middleware, USAGE_PATH,
 };
 use super::network::{NetworkConfig, NetworkMode};
-use super::metrics::{MetricsCollector, MetricsConfig};
+use super::secrets::{ResolvedSecrets, SecretError};
+use super::metrics::{in_flight_middleware, MetricsCollector, MetricsConfig};
+use super::telemetry::make_request_span;
+use super::types::{HealthConfig, InstanceConfig, UiConfig};
+use super::validation::ValidationLimits;
+
+/// 后台检查到期的保存的搜索的间隔
+const ALERT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
 
 /// 服务器配置
 #[derive(Debug, Clone)]
@@ -81,6 +104,56 @@ pub struct ApiState {
     pub metrics: Arc<MetricsCollector>,
     /// 魔法链接状态
     pub magic_link: Arc<MagicLinkState>,
+    /// 搜索历史（缓存不可用时为 None）
+    pub history: Option<Arc<HistoryCache>>,
+    /// 点击统计（缓存不可用时为 None）
+    pub clicks: Option<Arc<ClickStatsCache>>,
+    /// 共享的缓存接口，与搜索接口是同一实例（缓存不可用时为 None）
+    pub cache: Option<Arc<CacheInterface>>,
+    /// 持久化 API 密钥（缓存不可用时为 None）
+    pub api_keys: Option<Arc<ApiKeyStore>>,
+    /// 已抓取的 RSS 条目（缓存不可用时为 None）
+    pub rss: Option<Arc<RssCache>>,
+    /// webhook 通知（缓存不可用时为 None）
+    pub webhooks: Option<Arc<WebhookDispatcher>>,
+    /// 保存的搜索调度器（缓存不可用时为 None）
+    pub alerts: Option<Arc<AlertScheduler>>,
+    /// RSS 榜单（未配置或缓存不可用时为 None）
+    pub boards: Option<Arc<RssBoards>>,
+    /// 请求校验限制
+    pub validation: ValidationLimits,
+    /// 就绪检查配置
+    pub health: HealthConfig,
+    /// 首页界面配置
+    pub ui: Arc<UiConfig>,
+    /// 实例信息配置（robots.txt、联系方式）
+    pub instance: Arc<InstanceConfig>,
+    /// 已启动的后台任务
+    pub background: Arc<BackgroundTasks>,
+    /// 查询成本预算（外网路由按 API 密钥 / 魔法链接令牌记账和限制）
+    pub budget: Arc<QueryBudgetState>,
+    /// 结果页面截图预览（未启用时为 None）
+    #[cfg(feature = "preview")]
+    pub preview: Option<Arc<crate::search::PreviewService>>,
+}
+
+/// 已启动的后台任务句柄，供就绪检查判断调度器是否仍在运行
+#[derive(Debug, Default)]
+pub struct BackgroundTasks {
+    handles: std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>,
+}
+
+impl BackgroundTasks {
+    /// 记录一个后台任务
+    pub fn push(&self, handle: tokio::task::JoinHandle<()>) {
+        self.handles.lock().unwrap_or_else(|e| e.into_inner()).push(handle);
+    }
+
+    /// 至少启动了一个后台任务且都没有退出
+    pub fn is_running(&self) -> bool {
+        let handles = self.handles.lock().unwrap_or_else(|e| e.into_inner());
+        !handles.is_empty() && handles.iter().all(|handle| !handle.is_finished())
+    }
 }
 
 /// API 接口
@@ -91,9 +164,16 @@ pub struct ApiInterface {
     network_config: NetworkConfig,
     /// 中间件状态
     rate_limiter: Arc<RateLimiterState>,
-    circuit_breaker: Arc<CircuitBreakerState>,
+    circuit_breaker: Arc<CircuitBreakerRegistry>,
     ip_filter: Arc<IpFilterState>,
     auth_state: Arc<AuthState>,
+    access_log: Arc<AccessLogState>,
+    http_cache: Arc<HttpCacheState>,
+    compression: CompressionConfig,
+    internal_cors: Arc<CorsState>,
+    external_cors: Arc<CorsState>,
+    /// 加载密钥失败的原因，[`ApiInterface::serve`] 据此拒绝启动
+    secrets_error: Option<SecretError>,
 }
 
 impl ApiInterface {
@@ -111,6 +191,19 @@ impl ApiInterface {
         Self::with_network_config(search, version, NetworkConfig::default())
     }
 
+    /// 检测到引擎响应异常时投递 `engine.anomaly` webhook
+    fn notify_anomalies(search: &SearchInterface, webhooks: Arc<WebhookDispatcher>) {
+        search.anomaly_detector().subscribe(Arc::new(move |anomaly| {
+            let webhooks = webhooks.clone();
+            let anomaly = anomaly.clone();
+            tokio::spawn(async move {
+                if let Err(e) = webhooks.notify_anomaly(&anomaly).await {
+                    tracing::warn!("Failed to notify engine anomaly: {}", e);
+                }
+            });
+        }));
+    }
+
     /// 使用网络配置创建 API 接口
     pub fn with_network_config(
         search: Arc<SearchInterface>,
@@ -118,53 +211,282 @@ impl ApiInterface {
         network_config: NetworkConfig,
     ) -> Self {
         let metrics = Arc::new(MetricsCollector::new(MetricsConfig::default()));
-        let magic_link = Arc::new(MagicLinkState::new(MagicLinkConfig::default()));
-        
-        let state = ApiState {
-            search,
-            version,
-            metrics,
-            magic_link,
+        let (secrets, secrets_error) = match network_config.external.secrets.resolve() {
+            Ok(secrets) => (secrets, None),
+            Err(e) => {
+                tracing::error!("加载密钥失败: {}", e);
+                (ResolvedSecrets::default(), Some(e))
+            }
         };
+        let mut magic_link_config = MagicLinkConfig::default();
+        if let Some(secret) = secrets.magic_link_secret {
+            magic_link_config.secret = secret;
+        }
+        let magic_link = Arc::new(MagicLinkState::new(magic_link_config));
+        // 所有缓存子存储都建立在搜索接口持有的同一个缓存实例上
+        let cache = search.cache().cloned();
+        if cache.is_none() {
+            tracing::warn!("缓存不可用，搜索历史、点击统计、API 密钥和 RSS 缓存已禁用");
+        }
+        let history = Self::create_history(cache.as_ref(), HistoryConfig::default());
+        let clicks = Self::create_click_stats(cache.as_ref(), ClickTrackingConfig::default());
+        let api_keys = cache.as_ref().map(|cache| Arc::new(cache.api_keys()));
+        let rss = cache.as_ref().map(|cache| Arc::new(cache.rss()));
+        // webhook 必须送达登记的地址，不走引擎传输层（录制/回放或浏览器伪装）
+        let webhooks = cache.as_ref().map(|cache| {
+            Arc::new(WebhookDispatcher::new(Arc::new(cache.webhooks()), search.http_client().clone()))
+        });
+        let alerts = cache.as_ref().zip(webhooks.as_ref()).map(|(cache, webhooks)| {
+            Arc::new(AlertScheduler::new(search.clone(), Arc::new(cache.alerts())).with_webhooks(webhooks.clone()))
+        });
+        if let Some(webhooks) = &webhooks {
+            Self::notify_anomalies(&search, webhooks.clone());
+        }
+
 
         // 根据网络配置初始化中间件
-        let rate_limiter = Arc::new(RateLimiterState::new(RateLimitConfig {
+        let mut rate_limiter = RateLimiterState::new(RateLimitConfig {
             enabled: network_config.external.enable_rate_limit,
+            trusted_proxies: network_config.external.trusted_proxies.clone(),
+            routes: network_config.external.route_limits.clone(),
             ..Default::default()
-        }));
+        })
+        .with_magic_links(magic_link.clone());
         
-        let circuit_breaker = Arc::new(CircuitBreakerState::new(CircuitBreakerConfig {
+        let mut circuit_breaker = CircuitBreakerRegistry::new(CircuitBreakerConfig {
             enabled: network_config.external.enable_circuit_breaker,
             ..Default::default()
-        }));
+        });
+
+        // 多实例部署时限流计数和熔断状态放在共享存储中
+        if let Some(redis) = &network_config.external.shared_state {
+            match RedisStateStore::new(redis.clone()) {
+                Ok(store) => {
+                    let store: Arc<dyn SharedStateStore> = Arc::new(store);
+                    rate_limiter = rate_limiter.with_shared_store(store.clone());
+                    circuit_breaker = circuit_breaker.with_shared_store(store);
+                }
+                Err(e) => tracing::warn!("共享状态不可用，限流和熔断仅在本实例生效: {}", e),
+            }
+        }
+        let circuit_breaker = Arc::new(circuit_breaker);
         
         let ip_filter = Arc::new(IpFilterState::new(IpFilterConfig {
             enabled: network_config.external.enable_ip_filter,
+            geoip_database: network_config.external.geoip_database.clone(),
+            country_allowlist: network_config.external.country_allowlist.clone(),
+            country_denylist: network_config.external.country_denylist.clone(),
+            trusted_proxies: network_config.external.trusted_proxies.clone(),
             ..Default::default()
         }));
         
-        let auth_state = Arc::new(AuthState::new(AuthConfig {
+        let mut auth_config = AuthConfig {
             enabled: network_config.external.enable_jwt_auth,
+            api_keys: secrets.api_keys,
             ..Default::default()
-        }));
+        };
+        match secrets.jwt_secret {
+            Some(secret) => auth_config.jwt_secret = secret,
+            None if auth_config.enabled => {
+                tracing::warn!("未配置 JWT 密钥，使用随机生成的密钥，重启后已签发的令牌失效");
+            }
+            None => {}
+        }
+        let mut auth_state = AuthState::new(auth_config);
+        rate_limiter = rate_limiter.with_static_api_keys(&auth_state.config().api_keys);
+
+        // 持久化 API 密钥同时用于认证和单密钥限流
+        if let Some(store) = &api_keys {
+            rate_limiter = rate_limiter.with_key_store(store.clone());
+            auth_state = auth_state.with_key_store(store.clone());
+        }
+        let rate_limiter = Arc::new(rate_limiter);
+        let auth_state = Arc::new(auth_state);
+
+        // 查询成本按 API 密钥 / 魔法链接令牌记账，用量与其他子存储共用缓存
+        let mut budget = QueryBudgetState::new(network_config.external.query_budget.clone())
+            .with_magic_links(magic_link.clone())
+            .with_static_api_keys(&auth_state.config().api_keys);
+        if let Some(store) = &api_keys {
+            budget = budget.with_key_store(store.clone());
+        }
+        if let Some(cache) = &cache {
+            budget = budget.with_usage_store(Arc::new(cache.usage()));
+        }
+        let budget = Arc::new(budget);
+
+        let state = ApiState {
+            search,
+            version,
+            metrics,
+            magic_link: magic_link.clone(),
+            history,
+            clicks,
+            cache,
+            api_keys: api_keys.clone(),
+            rss,
+            webhooks,
+            alerts,
+            boards: None,
+            validation: ValidationLimits::default(),
+            health: HealthConfig::default(),
+            ui: Arc::new(UiConfig::default()),
+            instance: Arc::new(InstanceConfig::default()),
+            background: Arc::new(BackgroundTasks::default()),
+            budget,
+            #[cfg(feature = "preview")]
+            preview: None,
+        };
 
         Self {
             state,
-            network_config,
             rate_limiter,
             circuit_breaker,
             ip_filter,
             auth_state,
+            access_log: Arc::new(AccessLogState::new(AccessLogConfig::default())),
+            http_cache: Arc::new(HttpCacheState::new(HttpCacheConfig::default())),
+            compression: CompressionConfig::default(),
+            // 内网默认不返回 CORS 头，避免任意网页读取本机 API
+            internal_cors: Arc::new(CorsState::new(CorsConfig::disabled())),
+            external_cors: Arc::new(CorsState::new(CorsConfig::with_origins(
+                network_config.external.cors_origins.clone(),
+            ))),
+            network_config,
+            secrets_error,
         }
     }
 
+    /// 设置 CORS 配置
+    ///
+    /// `mode` 为 [`NetworkMode::Dual`] 时同时应用于内网和外网路由器
+    pub fn with_cors_config(mut self, mode: NetworkMode, config: CorsConfig) -> Self {
+        let state = Arc::new(CorsState::new(config));
+        match mode {
+            NetworkMode::Internal => self.internal_cors = state,
+            NetworkMode::External => self.external_cors = state,
+            NetworkMode::Dual => {
+                self.internal_cors = state.clone();
+                self.external_cors = state;
+            }
+        }
+        self
+    }
+
+    /// 获取指定网络模式的 CORS 状态（可用于运行时重新加载允许的源）
+    ///
+    /// `mode` 为 [`NetworkMode::Dual`] 时返回外网的 CORS 状态
+    pub fn cors_state(&self, mode: NetworkMode) -> Arc<CorsState> {
+        match mode {
+            NetworkMode::Internal => self.internal_cors.clone(),
+            NetworkMode::External | NetworkMode::Dual => self.external_cors.clone(),
+        }
+    }
+
+    /// 设置请求校验限制（包括全局请求体大小上限）
+    pub fn with_validation_limits(mut self, limits: ValidationLimits) -> Self {
+        self.state.validation = limits;
+        self
+    }
+
+    /// 设置就绪检查配置
+    pub fn with_health_config(mut self, config: HealthConfig) -> Self {
+        self.state.health = config;
+        self
+    }
+
+    /// 设置首页界面配置（实例名称、默认语言和页脚链接）
+    pub fn with_ui_config(mut self, config: UiConfig) -> Self {
+        self.state.ui = Arc::new(config);
+        self
+    }
+
+    /// 设置实例信息配置（robots.txt、联系方式和描述）
+    pub fn with_instance_config(mut self, config: InstanceConfig) -> Self {
+        self.state.instance = Arc::new(config);
+        self
+    }
+
+    /// 设置结构化访问日志配置（脱敏与采样）
+    pub fn with_access_log(mut self, config: AccessLogConfig) -> Self {
+        self.access_log = Arc::new(AccessLogState::new(config));
+        self
+    }
+
+    /// 设置 HTTP 缓存配置（ETag 和按路由的 Cache-Control）
+    pub fn with_http_cache(mut self, config: HttpCacheConfig) -> Self {
+        self.http_cache = Arc::new(HttpCacheState::new(config));
+        self
+    }
+
+    /// 设置响应压缩配置（最小压缩大小和允许的编码）
+    pub fn with_compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = config;
+        self
+    }
+
+    /// 设置搜索历史配置
+    ///
+    /// 搜索历史默认关闭，需通过此方法显式启用
+    pub fn with_history_config(mut self, config: HistoryConfig) -> Self {
+        self.state.history = Self::create_history(self.state.cache.as_ref(), config);
+        self
+    }
+
+    fn create_history(cache: Option<&Arc<CacheInterface>>, config: HistoryConfig) -> Option<Arc<HistoryCache>> {
+        cache.map(|cache| Arc::new(HistoryCache::new(cache.shared_manager(), config)))
+    }
+
+    /// 设置点击追踪配置
+    ///
+    /// 点击追踪默认关闭；启用后搜索结果会附带 `/r` 跳转链接，
+    /// 点击统计会用于调整聚合时的引擎权重
+    pub fn with_click_tracking(mut self, config: ClickTrackingConfig) -> Self {
+        self.state.clicks = Self::create_click_stats(self.state.cache.as_ref(), config);
+        self
+    }
+
+    fn create_click_stats(cache: Option<&Arc<CacheInterface>>, config: ClickTrackingConfig) -> Option<Arc<ClickStatsCache>> {
+        cache.map(|cache| Arc::new(ClickStatsCache::new(cache.shared_manager(), config)))
+    }
+
+    /// 设置 RSS 榜单配置
+    ///
+    /// 每个 [`crate::rss::RankingConfig`] 对应一个 `/boards/{name}` 页面，
+    /// 榜单由后台任务按 `refresh_interval_secs` 用已缓存的 RSS 条目重新生成
+    pub fn with_rss_boards(mut self, config: BoardsConfig) -> Self {
+        self.state.boards = self.state.rss.as_ref().map(|rss| Arc::new(RssBoards::new(config, rss.clone())));
+        self
+    }
+
+    /// 设置结果预览配置（需启用 `preview` 特性）
+    ///
+    /// 启用后搜索结果会附带签名的 `/api/preview` 链接，截图按 URL 缓存
+    #[cfg(feature = "preview")]
+    pub fn with_preview(mut self, config: crate::search::PreviewConfig) -> Self {
+        let cache = self.state.cache.as_ref().map(|cache| cache.shared_manager());
+        self.state.preview = Some(Arc::new(crate::search::PreviewService::new(config, cache)));
+        self
+    }
+
+    /// 结果预览路由（需启用 `preview` 特性）
+    #[cfg(feature = "preview")]
+    fn preview_routes() -> Router<ApiState> {
+        Router::new().route("/api/preview", get(super::handlers::preview::handle_preview))
+    }
+
+    #[cfg(not(feature = "preview"))]
+    fn preview_routes() -> Router<ApiState> {
+        Router::new()
+    }
+
     /// 从配置创建 API 接口
     ///
     /// # Arguments
     ///
     /// * `search_config` - 搜索配置
     /// * `network` - 网络接口
-    /// * `cache` - 缓存接口
+    /// * `cache` - 缓存接口，注入搜索接口后由搜索和所有 API 处理器共享
     ///
     /// # Returns
     ///
@@ -172,9 +494,9 @@ impl ApiInterface {
     pub fn from_config(
         search_config: crate::search::SearchConfig,
         _network: Arc<NetworkInterface>,
-        _cache: Arc<RwLock<CacheInterface>>,
+        cache: Arc<CacheInterface>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let search = Arc::new(SearchInterface::new(search_config)?);
+        let search = Arc::new(SearchInterface::new(search_config)?.with_cache(cache));
         Ok(Self::new(search, env!("CARGO_PKG_VERSION").to_string()))
     }
 
@@ -197,10 +519,24 @@ impl ApiInterface {
             // 首页路由
             .route("/", get(handle_index))
             .route("/favicon.ico", get(handle_favicon))
+            .route("/robots.txt", get(handle_robots_txt))
             
             // 搜索相关路由
+            .route("/search", get(handle_search_page))
             .route("/api/search", get(handle_search))
             .route("/api/search", post(handle_search_post))
+            .route("/api/news", get(news::handle_news))
+            .route("/api/answer", post(answer::handle_answer))
+            
+            // LLM 工具兼容路由
+            .route("/v1/tools", get(tools::handle_tools_list))
+            .route("/v1/tools/web_search", post(tools::handle_web_search_tool))
+            
+            // 点击跳转路由（需启用点击追踪）
+            .route("/r", get(click::handle_click_redirect))
+            
+            // 结果预览路由（需启用 preview 特性）
+            .merge(Self::preview_routes())
             
             // 引擎信息路由
             .route("/api/engines", get(handle_engines_list))
@@ -208,23 +544,36 @@ impl ApiInterface {
             // RSS 相关路由
             .route("/api/rss/feeds", get(rss::handle_rss_feeds_list))
             .route("/api/rss/fetch", post(rss::handle_rss_fetch))
+            .route("/api/rss/items", get(rss::handle_rss_items))
+            .route("/api/rss/discover", post(rss::handle_rss_discover))
             .route("/api/rss/templates", get(rss::handle_rss_templates_list))
             .route("/api/rss/template/add", post(rss::handle_rss_template_add))
             
+            // RSS 榜单路由
+            .route("/boards/{name}", get(handle_board_page))
+            .route("/api/boards", get(boards::handle_board_list))
+            .route("/api/boards/{name}", get(boards::handle_board))
+            
             // 缓存管理路由
             .route("/api/cache/stats", get(cache::handle_cache_stats))
             .route("/api/cache/clear", post(cache::handle_cache_clear))
             .route("/api/cache/cleanup", post(cache::handle_cache_cleanup))
             
+            // 搜索历史路由（内网使用本地作用域）
+            .route("/api/history", get(history::handle_history_list).delete(history::handle_history_clear))
+            
             // 统计信息路由
             .route("/api/stats", get(handle_stats))
             
             // 健康检查路由
             .route("/api/health", get(handle_health))
             .route("/health", get(handle_health))
+            .route("/health/live", get(handle_liveness))
+            .route("/health/ready", get(handle_readiness))
             
             // 版本信息路由
             .route("/api/version", get(handle_version))
+            .route("/instance", get(handle_instance))
             
             // 指标路由
             .route("/api/metrics", get(handle_metrics))
@@ -232,8 +581,65 @@ impl ApiInterface {
             
             // 魔法链接管理路由（仅内网）
             .route("/api/magic-link/generate", post(handle_magic_link_generate))
+            .route("/api/magic-link/list", get(handle_magic_link_list))
+            .route("/api/magic-link/revoke", post(handle_magic_link_revoke))
+            .route("/api/magic-link/renew", post(handle_magic_link_renew))
+            
+            // 管理面板路由（仅内网）
+            .route("/admin", get(handle_admin_dashboard))
+            .route("/admin/admin.js", get(handle_admin_js))
+            .route("/api/admin/engines", get(admin::handle_admin_engines))
+            .route("/api/admin/engines/state", get(admin::handle_admin_engines))
+            .route("/api/admin/engines/reload", post(admin::handle_admin_engines_reload))
+            .route("/api/admin/cache", get(admin::handle_admin_cache))
+            .route("/api/admin/errors", get(admin::handle_admin_errors))
+            .route("/api/admin/anomalies", get(admin::handle_admin_anomalies))
+            .route("/api/admin/slow-queries", get(admin::handle_admin_slow_queries))
+            .route("/api/admin/top-queries", get(admin::handle_admin_top_queries))
+            .route("/api/admin/heap", get(admin::handle_admin_heap))
+            .route("/api/admin/config/validate", post(admin::handle_admin_config_validate))
+            .route("/api/admin/selftest", post(admin::handle_admin_selftest))
+            .route("/api/admin/api-keys", get(apikeys::handle_api_key_list).post(apikeys::handle_api_key_create))
+            .route("/api/admin/api-keys/{id}/revoke", post(apikeys::handle_api_key_revoke))
+            .route("/api/admin/api-keys/{id}/rotate", post(apikeys::handle_api_key_rotate))
+            
+            // Webhook 管理路由（仅内网）
+            .route("/api/webhooks", get(webhooks::handle_webhook_list).post(webhooks::handle_webhook_create))
+            .route("/api/webhooks/{id}", delete(webhooks::handle_webhook_delete))
+            .route("/api/webhooks/{id}/deliveries", get(webhooks::handle_webhook_deliveries))
+            .route("/api/webhooks/{id}/test", post(webhooks::handle_webhook_test))
+            
+            // 保存的搜索路由（仅内网）
+            .route("/api/alerts", get(alerts::handle_alert_list).post(alerts::handle_alert_create))
+            .route("/api/alerts/{id}", get(alerts::handle_alert_get).delete(alerts::handle_alert_delete))
+            .route("/api/alerts/{id}/results", get(alerts::handle_alert_results))
+            .route("/api/alerts/{id}/run", post(alerts::handle_alert_run))
             
             .with_state(self.state.clone())
+            .layer(DefaultBodyLimit::max(self.state.validation.max_body_bytes))
+            .layer(axum::middleware::from_fn_with_state(
+                self.http_cache.clone(),
+                http_cache_middleware,
+            ))
+            // 错误消息按 Accept-Language 本地化（须在压缩之内）
+            .layer(axum::middleware::from_fn(localize_errors_middleware))
+            .layer(self.compression.layer())
+            .layer(axum::Extension(HistoryScope::local()))
+            .layer(self.internal_cors.layer())
+            // 正在处理的请求计数
+            .layer(axum::middleware::from_fn_with_state(
+                self.state.metrics.clone(),
+                in_flight_middleware,
+            ))
+            // 结构化访问日志
+            .layer(axum::middleware::from_fn_with_state(
+                self.access_log.clone(),
+                access_log_middleware,
+            ))
+            // 每个请求一个根 span
+            .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
+            // 请求 ID（最外层，根 span 和访问日志都能读到）
+            .layer(axum::middleware::from_fn(request_id_middleware))
     }
 
     /// 构建外网路由器（带安全限制）
@@ -248,10 +654,24 @@ impl ApiInterface {
             // 首页路由
             .route("/", get(handle_index))
             .route("/favicon.ico", get(handle_favicon))
+            .route("/robots.txt", get(handle_robots_txt))
             
             // 搜索相关路由
+            .route("/search", get(handle_search_page))
             .route("/api/search", get(handle_search))
             .route("/api/search", post(handle_search_post))
+            .route("/api/news", get(news::handle_news))
+            .route("/api/answer", post(answer::handle_answer))
+            
+            // LLM 工具兼容路由
+            .route("/v1/tools", get(tools::handle_tools_list))
+            .route("/v1/tools/web_search", post(tools::handle_web_search_tool))
+            
+            // 点击跳转路由（需启用点击追踪）
+            .route("/r", get(click::handle_click_redirect))
+            
+            // 结果预览路由（需启用 preview 特性）
+            .merge(Self::preview_routes())
             
             // 引擎信息路由
             .route("/api/engines", get(handle_engines_list))
@@ -259,6 +679,16 @@ impl ApiInterface {
             // RSS 相关路由（可能需要认证）
             .route("/api/rss/feeds", get(rss::handle_rss_feeds_list))
             .route("/api/rss/fetch", post(rss::handle_rss_fetch))
+            .route("/api/rss/items", get(rss::handle_rss_items))
+            .route("/api/rss/discover", post(rss::handle_rss_discover))
+            
+            // RSS 榜单路由
+            .route("/boards/{name}", get(handle_board_page))
+            .route("/api/boards", get(boards::handle_board_list))
+            .route("/api/boards/{name}", get(boards::handle_board))
+            
+            // 搜索历史路由（作用域由魔法链接令牌提供）
+            .route("/api/history", get(history::handle_history_list).delete(history::handle_history_clear))
             
             // 统计信息路由
             .route("/api/stats", get(handle_stats))
@@ -266,14 +696,31 @@ impl ApiInterface {
             // 健康检查路由
             .route("/api/health", get(handle_health))
             .route("/health", get(handle_health))
+            .route("/health/live", get(handle_liveness))
+            .route("/health/ready", get(handle_readiness))
             
             // 版本信息路由
             .route("/api/version", get(handle_version))
+            .route("/instance", get(handle_instance))
             
             // 指标路由（只读）
             .route("/api/metrics", get(handle_metrics))
             
+            // 查询成本用量路由（按 API 密钥 / 魔法链接令牌）
+            .route(USAGE_PATH, get(usage::handle_usage))
+            
             .with_state(self.state.clone())
+            .layer(DefaultBodyLimit::max(self.state.validation.max_body_bytes))
+            // ETag 与 Cache-Control（最内层，只作用于处理器的响应）
+            .layer(middleware::from_fn_with_state(
+                self.http_cache.clone(),
+                http_cache_middleware,
+            ))
+            // 查询成本记账与每日预算
+            .layer(middleware::from_fn_with_state(
+                self.state.budget.clone(),
+                query_budget_middleware,
+            ))
             
             // 应用中间件（顺序很重要）
             // 1. 魔法链接（最先检查，可以绕过认证）
@@ -301,8 +748,26 @@ impl ApiInterface {
                 self.rate_limiter.clone(),
                 rate_limit_middleware,
             ))
+            // 错误消息按 Accept-Language 本地化（包住上面所有会返回错误的中间件）
+            .layer(middleware::from_fn(localize_errors_middleware))
+            // gzip/Brotli 响应压缩（在本地化之外，改写的是未压缩的响应体）
+            .layer(self.compression.layer())
             // 6. CORS
-            .layer(cors::create_cors_layer())
+            .layer(self.external_cors.layer())
+            // 正在处理的请求计数（包括被拒绝的请求）
+            .layer(middleware::from_fn_with_state(
+                self.state.metrics.clone(),
+                in_flight_middleware,
+            ))
+            // 7. 结构化访问日志（记录包括被拒绝在内的所有请求）
+            .layer(middleware::from_fn_with_state(
+                self.access_log.clone(),
+                access_log_middleware,
+            ))
+            // 8. 链路追踪（每个请求一个根 span）
+            .layer(TraceLayer::new_for_http().make_span_with(make_request_span))
+            // 9. 请求 ID（最外层，根 span 和访问日志都能读到）
+            .layer(middleware::from_fn(request_id_middleware))
     }
 
     /// 启动服务器
@@ -314,7 +779,18 @@ impl ApiInterface {
     /// # Returns
     ///
     /// 返回结果
-    pub async fn serve(&self, _config: ServerConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn serve(&self, config: ServerConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // 显式配置的 CORS 源覆盖外网路由器的源列表（保留已配置的正则）
+        if config.cors_origins != ServerConfig::default().cors_origins {
+            self.external_cors.reload_origins(
+                &config.cors_origins,
+                &self.external_cors.config().allowed_origin_patterns,
+            )?;
+        }
+
+        self.check_secrets()?;
+        self.spawn_background_tasks();
+
         // 根据网络模式启动服务器
         match self.network_config.mode {
             NetworkMode::Internal => {
@@ -329,6 +805,48 @@ impl ApiInterface {
         }
     }
 
+    /// 检查密钥能否安全地对外服务
+    ///
+    /// 密钥加载失败，或外网启用魔法链接却仍使用默认生成的密钥时返回错误
+    pub fn check_secrets(&self) -> Result<(), SecretError> {
+        let serves_external = self.network_config.external.enabled
+            && matches!(self.network_config.mode, NetworkMode::External | NetworkMode::Dual);
+        self.check_secrets_for(serves_external)
+    }
+
+    /// 检查密钥能否安全地启动外网路由器
+    ///
+    /// 与 [`ApiInterface::check_secrets`] 相同，但不论网络模式都按对外服务检查，
+    /// 供直接使用 [`ApiInterface::build_external_router`] 启动服务的调用方使用
+    pub fn check_external_secrets(&self) -> Result<(), SecretError> {
+        self.check_secrets_for(true)
+    }
+
+    fn check_secrets_for(&self, serves_external: bool) -> Result<(), SecretError> {
+        if let Some(e) = &self.secrets_error {
+            return Err(e.clone());
+        }
+        if serves_external
+            && self.network_config.external.enable_magic_link
+            && self.state.magic_link.config().uses_default_secret()
+        {
+            return Err(SecretError::DefaultMagicLinkSecret);
+        }
+        Ok(())
+    }
+
+    /// 启动后台任务（执行到期的保存的搜索），需在 tokio 运行时中调用
+    ///
+    /// [`ApiInterface::serve`] 会自动调用；自行用路由器启动服务时需要手动调用一次
+    pub fn spawn_background_tasks(&self) {
+        if let Some(alerts) = &self.state.alerts {
+            self.state.background.push(alerts.clone().spawn(ALERT_CHECK_INTERVAL));
+        }
+        if let Some(boards) = self.state.boards.as_ref().filter(|b| !b.config().rankings.is_empty()) {
+            self.state.background.push(boards.clone().spawn());
+        }
+    }
+
     /// 启动内网服务器
     async fn serve_internal(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let app = self.build_internal_router();
@@ -342,7 +860,7 @@ impl ApiInterface {
         println!("   - 无安全限制");
         
         let listener = tokio::net::TcpListener::bind(&addr).await?;
-        axum::serve(listener, app).await?;
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
         
         Ok(())
     }
@@ -365,7 +883,7 @@ impl ApiInterface {
         self.print_metrics_dashboard().await;
         
         let listener = tokio::net::TcpListener::bind(&addr).await?;
-        axum::serve(listener, app).await?;
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
         
         Ok(())
     }
@@ -388,7 +906,7 @@ impl ApiInterface {
             
             let internal_listener = tokio::net::TcpListener::bind(&internal_addr).await?;
             tokio::spawn(async move {
-                axum::serve(internal_listener, internal_app).await
+                axum::serve(internal_listener, internal_app.into_make_service_with_connect_info::<SocketAddr>()).await
             });
         }
         
@@ -410,7 +928,7 @@ impl ApiInterface {
             self.print_metrics_dashboard().await;
             
             let external_listener = tokio::net::TcpListener::bind(&external_addr).await?;
-            axum::serve(external_listener, external_app).await?;
+            axum::serve(external_listener, external_app.into_make_service_with_connect_info::<SocketAddr>()).await?;
         }
         
         Ok(())
@@ -450,6 +968,35 @@ impl ApiInterface {
     }
 }
 
+/// 处理器测试共用的夹具：搜索接口使用模拟传输层，经由内部路由发请求
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+    use crate::search::SearchConfig;
+
+    /// Bing 结果页，每项为 (URL, 标题, 摘要)
+    pub(crate) fn bing_results(results: &[(&str, &str, &str)]) -> String {
+        let items: String = results.iter()
+            .map(|(url, title, snippet)| {
+                format!(r#"<li class="b_algo"><h2><a href="{}">{}</a></h2><p>{}</p></li>"#, url, title, snippet)
+            })
+            .collect();
+        format!(r#"<ol id="b_results">{}</ol>"#, items)
+    }
+
+    /// 对 Bing 搜索返回给定结果的模拟传输层
+    pub(crate) fn bing_transport(results: &[(&str, &str, &str)]) -> Arc<MockTransport> {
+        Arc::new(MockTransport::new().respond("www.bing.com/search", MockResponse::ok(bing_results(results))))
+    }
+
+    /// 使用给定搜索配置和传输层的内部路由
+    pub(crate) fn internal_router(config: SearchConfig, transport: Arc<MockTransport>) -> Router {
+        let search = SearchInterface::new(config).unwrap().with_transport(transport);
+        ApiInterface::new(Arc::new(search), "0.1.0".to_string()).build_internal_router()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -461,12 +1008,12 @@ mod tests {
     async fn test_api_interface_creation() {
         let search_config = SearchConfig::default();
         let network = Arc::new(NetworkInterface::new(NetworkConfig::default()).unwrap());
-        let cache = Arc::new(RwLock::new(
-            CacheInterface::new(CacheImplConfig::default()).unwrap(),
-        ));
+        let cache = Arc::new(CacheInterface::new(CacheImplConfig::default()).unwrap());
 
-        let api = ApiInterface::from_config(search_config, network, cache);
-        assert!(api.is_ok());
+        let api = ApiInterface::from_config(search_config, network, cache.clone()).unwrap();
+        // 搜索接口和 API 状态共用注入的缓存实例
+        assert!(Arc::ptr_eq(api.state.search.cache().unwrap(), &cache));
+        assert!(Arc::ptr_eq(api.state.cache.as_ref().unwrap(), &cache));
     }
 
     #[test]
@@ -480,4 +1027,177 @@ mod tests {
         let _external_router = api.build_external_router();
         // Routers are built successfully
     }
+
+    #[test]
+    fn test_external_mode_requires_magic_link_secret() {
+        let search = Arc::new(SearchInterface::new(SearchConfig::default()).unwrap());
+
+        let api = ApiInterface::new(search.clone(), "0.1.0".to_string());
+        assert_eq!(api.check_secrets(), Err(SecretError::DefaultMagicLinkSecret));
+
+        let mut network_config = super::NetworkConfig::default();
+        network_config.external.secrets.magic_link_secret = Some("a-configured-magic-link-secret".into());
+        let api = ApiInterface::with_network_config(search.clone(), "0.1.0".to_string(), network_config);
+        assert!(api.check_secrets().is_ok());
+
+        // 仅内网时默认密钥可用
+        let network_config = super::NetworkConfig { mode: NetworkMode::Internal, ..Default::default() };
+        let api = ApiInterface::with_network_config(search, "0.1.0".to_string(), network_config);
+        assert!(api.check_secrets().is_ok());
+        // 直接启动外网路由器时仍需配置密钥
+        assert_eq!(api.check_external_secrets(), Err(SecretError::DefaultMagicLinkSecret));
+    }
+
+    #[tokio::test]
+    async fn test_cors_per_network_mode() {
+        use axum::body::Body;
+        use axum::http::{header, Request};
+        use tower::ServiceExt;
+
+        let search = Arc::new(
+            SearchInterface::new(SearchConfig::default()).unwrap()
+        );
+        let api = ApiInterface::new(search, "0.1.0".to_string())
+            .with_cors_config(
+                NetworkMode::External,
+                CorsConfig::with_origins(vec!["https://allowed.example".to_string()]),
+            );
+
+        let request = |origin: &'static str| {
+            Request::get("/api/version")
+                .header(header::ORIGIN, origin)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let allow_origin = |response: &axum::response::Response| {
+            response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+        };
+
+        let response = api.build_external_router().oneshot(request("https://allowed.example")).await.unwrap();
+        assert_eq!(allow_origin(&response).unwrap(), "https://allowed.example");
+
+        let response = api.build_external_router().oneshot(request("https://other.example")).await.unwrap();
+        assert!(allow_origin(&response).is_none());
+
+        // 运行时重新加载源列表
+        api.cors_state(NetworkMode::External)
+            .reload_origins(&[], &[r"https://[a-z]+\.example".to_string()])
+            .unwrap();
+        let response = api.build_external_router().oneshot(request("https://other.example")).await.unwrap();
+        assert_eq!(allow_origin(&response).unwrap(), "https://other.example");
+
+        // 内网默认不返回 CORS 头
+        let response = api.build_internal_router().oneshot(request("https://allowed.example")).await.unwrap();
+        assert!(allow_origin(&response).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_refine_search_results() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+        use super::test_support::{bing_transport, internal_router};
+
+        let transport = bing_transport(&[
+            ("https://tokio.rs/", "Tokio", "Rust async runtime"),
+            ("https://www.rust-lang.org/", "Rust", "Rust language"),
+        ]);
+        let router = internal_router(SearchConfig::default(), transport.clone());
+        let get = |uri: String| {
+            let router = router.clone();
+            async move {
+                let response = router.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+            }
+        };
+
+        let (status, first) = get("/api/search?q=rust&engines=bing".to_string()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["results"].as_array().unwrap().len(), 2);
+        let query_id = first["query_id"].as_str().unwrap();
+
+        let (status, refined) = get(format!("/api/search?q=async&refine={}", query_id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(refined["refined_from"], query_id);
+        assert_eq!(refined["results"].as_array().unwrap().len(), 1);
+        assert_eq!(refined["results"][0]["url"], "https://tokio.rs/");
+        assert_eq!(refined["results"][0]["engine"], first["results"][0]["engine"]);
+        // 细化不会重新查询引擎
+        assert_eq!(transport.requests().len(), 1);
+
+        let (status, missing) = get("/api/search?q=async&refine=0123abcd".to_string()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(missing["code"], "QUERY_NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_search_etag_not_modified() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::{header, Request, StatusCode};
+        use tower::ServiceExt;
+        use super::test_support::{bing_transport, internal_router};
+
+        let transport = bing_transport(&[("https://www.rust-lang.org/", "Rust", "Rust language")]);
+        let router = internal_router(SearchConfig::default(), transport.clone());
+
+        let response = router.clone()
+            .oneshot(Request::get("/api/search?q=rust&engines=bing").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        // 第二次请求命中结果缓存，结果不变时返回 304
+        let response = router
+            .oneshot(
+                Request::get("/api/search?q=rust&engines=bing")
+                    .header(header::IF_NONE_MATCH, &etag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        assert!(to_bytes(response.into_body(), usize::MAX).await.unwrap().is_empty());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_robots_and_instance_endpoints() {
+        use axum::body::{to_bytes, Body};
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let search = Arc::new(SearchInterface::new(SearchConfig::default()).unwrap());
+        let api = ApiInterface::new(search.clone(), "0.1.0".to_string());
+        let router = api.build_external_router();
+        let response = router.oneshot(Request::get("/robots.txt").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(std::str::from_utf8(&body).unwrap().contains("Disallow: /search"));
+
+        let router = ApiInterface::new(search, "0.1.0".to_string())
+            .with_instance_config(InstanceConfig {
+                robots_txt: Some("User-agent: *\nDisallow: /\n".to_string()),
+                contact: Some("mailto:ops@example.org".to_string()),
+                description: None,
+            })
+            .build_external_router();
+        let response = router.clone().oneshot(Request::get("/robots.txt").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(to_bytes(response.into_body(), usize::MAX).await.unwrap(), "User-agent: *\nDisallow: /\n");
+
+        let response = router.oneshot(Request::get("/instance").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let info: serde_json::Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(info["name"], "SeeSea");
+        assert_eq!(info["version"], "0.1.0");
+        assert_eq!(info["contact"], "mailto:ops@example.org");
+        assert!(info["engine_count"].as_u64().unwrap() > 0);
+        assert!(info["categories"]["general"].as_u64().unwrap() > 0);
+        assert_eq!(info["locales"], serde_json::json!(["zh-CN", "en"]));
+    }
+
 }
@@ -0,0 +1,299 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 密钥管理
+//!
+//! JWT 密钥、魔法链接密钥和静态 API 密钥除了内联在配置中，还可以从文件（如 Docker/Kubernetes
+//! 挂载的 secret）或指定的环境变量读取。加载后的密钥保存在 [`SecretString`] 中：
+//! `Debug` 输出脱敏，释放时清零内存。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
+
+/// 内存中的密钥，`Debug` 输出脱敏，释放时清零
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(Zeroizing<String>);
+
+impl SecretString {
+    /// 包装密钥
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(Zeroizing::new(secret.into()))
+    }
+
+    /// 取出密钥明文
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString([REDACTED])")
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+// 序列化输出明文，保证配置可以往返（如环境变量覆盖时的重新反序列化）
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.expose())
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// 密钥加载错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretError {
+    /// 同一个密钥配置了多个来源
+    Conflict(&'static str),
+    /// 读取密钥文件失败或文件为空
+    File {
+        /// 配置字段
+        field: &'static str,
+        /// 文件路径
+        path: PathBuf,
+        /// 错误原因
+        reason: String,
+    },
+    /// 引用的环境变量未设置或为空
+    Env {
+        /// 配置字段
+        field: &'static str,
+        /// 环境变量名
+        var: String,
+    },
+    /// 外网启用了魔法链接但仍在使用默认生成的密钥
+    DefaultMagicLinkSecret,
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict(field) => write!(
+                f,
+                "{} 只能从一个来源设置（{}、{}_file 或 {}_env 三选一）",
+                field, field, field, field
+            ),
+            Self::File { field, path, reason } => {
+                write!(f, "{}_file: 无法读取 {}: {}", field, path.display(), reason)
+            }
+            Self::Env { field, var } => write!(f, "{}_env: 环境变量 {} 未设置或为空", field, var),
+            Self::DefaultMagicLinkSecret => f.write_str(
+                "外网启用了魔法链接但未配置密钥，请设置 secrets.magic_link_secret_file 或 secrets.magic_link_secret_env",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {}
+
+/// 外网密钥配置（`[external.secrets]`）
+///
+/// 每个密钥可以内联、从文件读取（`*_file`）或从环境变量读取（`*_env`），三者只能选一
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// JWT 签名密钥（内联，不推荐）
+    pub jwt_secret: Option<SecretString>,
+    /// 包含 JWT 签名密钥的文件
+    pub jwt_secret_file: Option<PathBuf>,
+    /// 包含 JWT 签名密钥的环境变量名
+    pub jwt_secret_env: Option<String>,
+    /// 魔法链接密钥（内联，不推荐）
+    pub magic_link_secret: Option<SecretString>,
+    /// 包含魔法链接密钥的文件
+    pub magic_link_secret_file: Option<PathBuf>,
+    /// 包含魔法链接密钥的环境变量名
+    pub magic_link_secret_env: Option<String>,
+    /// 静态 API 密钥（内联，不推荐）
+    pub api_keys: Vec<SecretString>,
+    /// 每行一个 API 密钥的文件（忽略空行和 `#` 注释）
+    pub api_keys_file: Option<PathBuf>,
+    /// 包含逗号分隔 API 密钥的环境变量名
+    pub api_keys_env: Option<String>,
+}
+
+/// 加载后的密钥
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedSecrets {
+    /// JWT 签名密钥（未配置时为 None）
+    pub jwt_secret: Option<SecretString>,
+    /// 魔法链接密钥（未配置时为 None）
+    pub magic_link_secret: Option<SecretString>,
+    /// 静态 API 密钥
+    pub api_keys: Vec<SecretString>,
+}
+
+impl SecretsConfig {
+    /// 从配置的来源加载全部密钥
+    pub fn resolve(&self) -> Result<ResolvedSecrets, SecretError> {
+        let jwt_secret = resolve_secret(
+            "jwt_secret",
+            self.jwt_secret.as_ref(),
+            self.jwt_secret_file.as_deref(),
+            self.jwt_secret_env.as_deref(),
+        )?;
+        let magic_link_secret = resolve_secret(
+            "magic_link_secret",
+            self.magic_link_secret.as_ref(),
+            self.magic_link_secret_file.as_deref(),
+            self.magic_link_secret_env.as_deref(),
+        )?;
+
+        let inline = (!self.api_keys.is_empty()).then_some(&self.api_keys);
+        let sources = [inline.is_some(), self.api_keys_file.is_some(), self.api_keys_env.is_some()];
+        if sources.iter().filter(|set| **set).count() > 1 {
+            return Err(SecretError::Conflict("api_keys"));
+        }
+        let api_keys = if let Some(path) = &self.api_keys_file {
+            let content = read_secret_file("api_keys", path)?;
+            split_keys(content.expose(), '\n')
+        } else if let Some(var) = &self.api_keys_env {
+            split_keys(read_secret_env("api_keys", var)?.expose(), ',')
+        } else {
+            self.api_keys.clone()
+        };
+
+        Ok(ResolvedSecrets { jwt_secret, magic_link_secret, api_keys })
+    }
+}
+
+/// 按内联值、文件、环境变量之一加载单个密钥
+fn resolve_secret(
+    field: &'static str,
+    inline: Option<&SecretString>,
+    file: Option<&Path>,
+    env: Option<&str>,
+) -> Result<Option<SecretString>, SecretError> {
+    match (inline.filter(|s| !s.is_empty()), file, env) {
+        (None, None, None) => Ok(None),
+        (Some(secret), None, None) => Ok(Some(secret.clone())),
+        (None, Some(path), None) => read_secret_file(field, path).map(Some),
+        (None, None, Some(var)) => read_secret_env(field, var).map(Some),
+        _ => Err(SecretError::Conflict(field)),
+    }
+}
+
+/// 读取密钥文件，去掉首尾空白（挂载的 secret 通常以换行结尾）
+fn read_secret_file(field: &'static str, path: &Path) -> Result<SecretString, SecretError> {
+    let content = Zeroizing::new(std::fs::read_to_string(path).map_err(|e| SecretError::File {
+        field,
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?);
+    let secret = content.trim();
+    if secret.is_empty() {
+        return Err(SecretError::File { field, path: path.to_path_buf(), reason: "文件为空".to_string() });
+    }
+    Ok(SecretString::new(secret))
+}
+
+fn read_secret_env(field: &'static str, var: &str) -> Result<SecretString, SecretError> {
+    let value = Zeroizing::new(std::env::var(var).unwrap_or_default());
+    let secret = value.trim();
+    if secret.is_empty() {
+        return Err(SecretError::Env { field, var: var.to_string() });
+    }
+    Ok(SecretString::new(secret))
+}
+
+fn split_keys(content: &str, separator: char) -> Vec<SecretString> {
+    content
+        .split(separator)
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && !key.starts_with('#'))
+        .map(SecretString::new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_secret_string_is_redacted() {
+        let secret = SecretString::new("hunter2");
+        assert_eq!(format!("{:?}", secret), "SecretString([REDACTED])");
+        assert_eq!(secret.expose(), "hunter2");
+    }
+
+    #[test]
+    fn test_resolve_from_file_and_env() {
+        let mut jwt = tempfile::NamedTempFile::new().unwrap();
+        writeln!(jwt, "file-secret").unwrap();
+        let mut keys = tempfile::NamedTempFile::new().unwrap();
+        writeln!(keys, "# 生产密钥\nkey-a\n\nkey-b").unwrap();
+
+        temp_env::with_var("SEESEA_TEST_MAGIC_SECRET", Some("env-secret"), || {
+            let config = SecretsConfig {
+                jwt_secret_file: Some(jwt.path().to_path_buf()),
+                magic_link_secret_env: Some("SEESEA_TEST_MAGIC_SECRET".to_string()),
+                api_keys_file: Some(keys.path().to_path_buf()),
+                ..Default::default()
+            };
+            let secrets = config.resolve().unwrap();
+            assert_eq!(secrets.jwt_secret.unwrap().expose(), "file-secret");
+            assert_eq!(secrets.magic_link_secret.unwrap().expose(), "env-secret");
+            let keys: Vec<&str> = secrets.api_keys.iter().map(SecretString::expose).collect();
+            assert_eq!(keys, vec!["key-a", "key-b"]);
+        });
+    }
+
+    #[test]
+    fn test_resolve_errors() {
+        let config = SecretsConfig {
+            jwt_secret: Some("inline".into()),
+            jwt_secret_env: Some("SEESEA_TEST_JWT".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.resolve().unwrap_err(), SecretError::Conflict("jwt_secret"));
+
+        let config = SecretsConfig {
+            magic_link_secret_env: Some("SEESEA_TEST_UNSET_SECRET".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(config.resolve(), Err(SecretError::Env { field: "magic_link_secret", .. })));
+
+        let config = SecretsConfig {
+            api_keys_file: Some(PathBuf::from("/nonexistent/seesea/api_keys")),
+            ..Default::default()
+        };
+        assert!(config.resolve().unwrap_err().to_string().starts_with("api_keys_file"));
+    }
+}
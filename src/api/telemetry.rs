@@ -0,0 +1,151 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 链路追踪模块
+//!
+//! 初始化 tracing 订阅者，并在启用 `otel` 特性时通过 OTLP 导出 span。
+//! 每个 API 请求对应一个根 span，搜索、引擎请求/解析和缓存查询为其子 span；
+//! 搜索 span 携带 `query_id`，同一 ID 会在 API 响应中返回，便于关联客户端问题与服务端链路；
+//! 根 span 携带 `x-request-id`，错误响应的 `details` 中也会附上该 ID。
+
+use axum::{body::Body, http::Request};
+use tracing::Span;
+
+use super::middleware::RequestId;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// 链路追踪配置
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// 服务名称
+    pub service_name: String,
+    /// OTLP HTTP 端点（如 `http://localhost:4318/v1/traces`），为 None 时不导出
+    pub otlp_endpoint: Option<String>,
+    /// 默认日志过滤规则（可被 `RUST_LOG` 覆盖）
+    pub log_filter: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            service_name: "seesea".to_string(),
+            otlp_endpoint: None,
+            log_filter: "info".to_string(),
+        }
+    }
+}
+
+/// 链路追踪守卫
+///
+/// 销毁时刷新并关闭 OTLP 导出器，应在程序退出前保持存活
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("关闭 OTLP 导出器失败: {}", e);
+        }
+    }
+}
+
+/// 初始化全局 tracing 订阅者
+///
+/// 未启用 `otel` 特性时配置的 OTLP 端点会被忽略（并输出警告）
+pub fn init_tracing(config: &TelemetryConfig) -> Result<TelemetryGuard, Box<dyn std::error::Error + Send + Sync>> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(&config.log_filter));
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+
+    #[cfg(feature = "otel")]
+    if let Some(endpoint) = &config.otlp_endpoint {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_otlp::WithExportConfig;
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()?;
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                opentelemetry_sdk::Resource::builder()
+                    .with_service_name(config.service_name.clone())
+                    .build(),
+            )
+            .build();
+        let tracer = provider.tracer("seesea");
+
+        registry
+            .with(tracing_opentelemetry::layer().with_tracer(tracer))
+            .try_init()?;
+        return Ok(TelemetryGuard { provider: Some(provider) });
+    }
+
+    registry.try_init()?;
+
+    if config.otlp_endpoint.is_some() {
+        tracing::warn!("已配置 OTLP 端点，但未启用 otel 特性，链路数据不会导出");
+    }
+
+    Ok(TelemetryGuard {
+        #[cfg(feature = "otel")]
+        provider: None,
+    })
+}
+
+/// 生成查询 ID
+pub fn new_query_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// 为 API 请求创建根 span
+///
+/// 只记录方法、路径和请求 ID，不记录查询字符串（可能包含搜索词或令牌）
+pub fn make_request_span(request: &Request<Body>) -> Span {
+    let request_id = request.extensions().get::<RequestId>().map(|id| id.0.as_str()).unwrap_or_default();
+    tracing::info_span!(
+        "http_request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_config_default() {
+        let config = TelemetryConfig::default();
+        assert_eq!(config.service_name, "seesea");
+        assert!(config.otlp_endpoint.is_none());
+    }
+
+    #[test]
+    fn test_new_query_id() {
+        let a = new_query_id();
+        let b = new_query_id();
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+    }
+}
@@ -17,8 +17,15 @@
 //! 定义所有 API 相关的数据结构和类型
 
 use serde::{Deserialize, Serialize};
-use crate::derive::SearchQuery;
+use crate::derive::{ImageMetadata, PlaceMetadata, PodcastMetadata, ResultType, SearchQuery, SecurityFlag, TimeRange, TorrentMetadata, VideoMetadata};
 use crate::search::engine_config::EngineListConfig;
+use crate::api::middleware::ClientCountry;
+use crate::api::i18n::Locale;
+use crate::search::aggregator::{parse_engine_weights, SortBy};
+use crate::search::highlight::{find_highlights, HighlightRange};
+use crate::search::{Answer, EarlyResponse, EngineCounts, ResultFilter, SearchStatus};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 /// API 搜索请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,13 +63,185 @@ pub struct ApiSearchRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub safe_search: Option<String>,
 
-    /// 时间范围（可选）
+    /// 时间范围（可选，如 `day`、`w`；`filters.time_range` 优先）
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub time_range: Option<String>,
+    pub time_range: Option<TimeRange>,
 
     /// 指定搜索引擎（可选，逗号分隔）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub engines: Option<String>,
+
+    /// 搜索分类（可选，如 `map`）：未指定 engines 时使用该分类下的全部可用引擎
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+
+    /// 排序方式（可选）：relevance（默认）或 date（严格按发布时间）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+
+    /// 输出格式（可选）：`json`（默认）或 `ndjson`（每个引擎返回后立即逐行输出新的聚合结果）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+
+    /// 提前返回（可选）：已有这么多引擎返回结果时立即响应
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_engines: Option<u32>,
+
+    /// 提前返回（可选）：最长等待时间（毫秒），到时返回已完成的结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wait_ms: Option<u64>,
+
+    /// 结构化过滤条件（可选，仅 POST JSON 请求体）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<SearchFilters>,
+
+    /// 结果字段选择（可选，逗号分隔，如 `title,url,score`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+
+    /// 精简模式（可选，`compact=1`）：结果不含摘要和元数据
+    #[serde(default, deserialize_with = "deserialize_flag", skip_serializing_if = "std::ops::Not::not")]
+    pub compact: bool,
+
+    /// 引擎权重（可选，如 `bing:2.0,baidu:0.5`），覆盖配置中同名引擎的默认权重
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_weights: Option<String>,
+
+    /// 每个域名的结果上限（可选，覆盖配置，0 表示不做多样性重排）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_domain: Option<u32>,
+
+    /// 严格语言过滤（可选，`lang_strict=1`）：丢弃而不是降权与 `language` 不符的结果
+    #[serde(default, deserialize_with = "deserialize_flag", skip_serializing_if = "std::ops::Not::not")]
+    pub lang_strict: bool,
+
+    /// 严格安全模式（可选，`security_strict=1`）：丢弃带 `insecure`、`shortener`、`tracker` 标记的结果
+    #[serde(default, deserialize_with = "deserialize_flag", skip_serializing_if = "std::ops::Not::not")]
+    pub security_strict: bool,
+
+    /// 在结果中搜索（可选）：之前响应的 `query_id`，`q` 作为细化关键词，不重新查询引擎
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refine: Option<String>,
+
+    /// 查询词高亮（可选，`highlight=1`）：结果带 `highlights` 字段，给出查询词在标题和摘要中的字节区间
+    #[serde(default, deserialize_with = "deserialize_flag", skip_serializing_if = "std::ops::Not::not")]
+    pub highlight: bool,
+}
+
+/// 解析开关参数：接受 JSON 布尔值、数字以及 `1`/`true`/`yes`/`on` 字符串
+pub(crate) fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Number(i64),
+        Text(String),
+    }
+
+    match Flag::deserialize(deserializer)? {
+        Flag::Bool(value) => Ok(value),
+        Flag::Number(value) => Ok(value != 0),
+        Flag::Text(value) => match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "" | "0" | "false" | "no" | "off" => Ok(false),
+            other => Err(serde::de::Error::custom(format!("无效的开关值: {}", other))),
+        },
+    }
+}
+
+/// 结果项可选择的字段（`content` 是 `description` 的别名）
+pub const PROJECTABLE_FIELDS: &[&str] = &[
+    "title", "url", "description", "content", "thumbnail", "engine", "score", "click_url",
+    "preview_url", "video", "image", "place", "torrent", "podcast", "archive_url",
+    "summary", "highlights", "flags",
+];
+
+/// 精简模式保留的字段
+const COMPACT_FIELDS: &[&str] = &["title", "url", "engine", "score", "click_url"];
+
+/// 响应字段投影：只序列化选中的结果字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseProjection {
+    /// 保留的结果字段
+    fields: Vec<String>,
+}
+
+impl ResponseProjection {
+    /// 保留的结果字段
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// 按投影序列化搜索响应（响应级字段不变，只裁剪 `results` 中的每一项）
+    pub fn apply(&self, response: &ApiSearchResponse) -> serde_json::Value {
+        let mut value = serde_json::to_value(response).unwrap_or_default();
+        if let Some(items) = value.get_mut("results").and_then(serde_json::Value::as_array_mut) {
+            for item in items.iter_mut() {
+                self.retain_fields(item);
+            }
+        }
+        value
+    }
+
+    /// 按投影序列化单个结果项
+    pub fn apply_item(&self, item: &ApiSearchResultItem) -> serde_json::Value {
+        let mut value = serde_json::to_value(item).unwrap_or_default();
+        self.retain_fields(&mut value);
+        value
+    }
+
+    fn retain_fields(&self, item: &mut serde_json::Value) {
+        if let Some(item) = item.as_object_mut() {
+            item.retain(|key, _| self.fields.iter().any(|field| field == key));
+        }
+    }
+}
+
+/// 结构化过滤条件
+///
+/// POST 请求体中的 `filters` 对象，未知字段和非法取值直接拒绝，
+/// 无需在查询字符串里拼写 `site:` 之类的语法
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchFilters {
+    /// 时间范围：any、hour、day、week、month、year
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_range: Option<TimeRange>,
+    /// 只保留这些站点（含子域名）的结果
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include_sites: Vec<String>,
+    /// 排除这些站点（含子域名）的结果
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude_sites: Vec<String>,
+    /// 只保留这些类型的结果（如 web、news、video）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub result_types: Vec<ResultType>,
+    /// 最低分数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f64>,
+    /// 语言偏好（按优先级，第一个传给引擎）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+}
+
+impl SearchFilters {
+    /// 转换为聚合后应用的结果过滤条件
+    pub fn to_result_filter(&self) -> ResultFilter {
+        let filter = ResultFilter {
+            include_sites: self.include_sites.clone(),
+            exclude_sites: self.exclude_sites.clone(),
+            result_types: self.result_types.clone(),
+            min_score: self.min_score,
+            published_after: None,
+        };
+        match self.time_range {
+            Some(time_range) => filter.with_time_range(time_range),
+            None => filter,
+        }
+    }
 }
 
 fn default_page() -> u32 {
@@ -85,24 +264,96 @@ impl ApiSearchRequest {
 
     /// 转换为内部 SearchQuery
     pub fn to_search_query(&self) -> Result<SearchQuery, String> {
-        let query_text = self.get_query()?;
+        let mut query_text = self.get_query()?;
+        let filters = self.filters.as_ref();
+        // 只限定一个站点时同时交给引擎的 site: 语法，提高召回
+        if let Some([site]) = filters.map(|f| f.include_sites.as_slice()) {
+            query_text = format!("{} site:{}", query_text, crate::search::filter::normalize_site(site));
+        }
 
-        let mut query = SearchQuery {
-            query: query_text,
-            page: self.page as usize,
-            page_size: self.page_size as usize,
-            ..Default::default()
-        };
+        let mut builder = SearchQuery::builder()
+            .query(query_text)
+            .page(self.page as usize)
+            .page_size(self.page_size as usize);
+        if let Some(lang) = self.language.clone().or_else(|| filters.and_then(|f| f.languages.first().cloned())) {
+            builder = builder.language(lang);
+        }
+        if let Some(region) = &self.region {
+            builder = builder.region(region.clone());
+        }
+        if let Some(time_range) = filters.and_then(|f| f.time_range).or(self.time_range) {
+            builder = builder.time_range(time_range);
+        }
 
-        if let Some(ref lang) = self.language {
-            query.language = Some(lang.clone());
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// 获取响应字段投影（未指定 fields 且未启用精简模式时为 None）
+    ///
+    /// 同时指定时取两者的交集；`content` 视为 `description`
+    pub fn get_projection(&self) -> Option<ResponseProjection> {
+        let mut fields: Vec<String> = match &self.fields {
+            Some(fields) => fields
+                .split(',')
+                .map(|f| f.trim().to_ascii_lowercase())
+                .filter(|f| !f.is_empty())
+                .map(|f| if f == "content" { "description".to_string() } else { f })
+                .collect(),
+            None if self.compact => COMPACT_FIELDS.iter().map(|f| f.to_string()).collect(),
+            None => return None,
+        };
+        if self.compact {
+            fields.retain(|f| COMPACT_FIELDS.contains(&f.as_str()));
         }
+        fields.sort();
+        fields.dedup();
+        Some(ResponseProjection { fields })
+    }
 
-        if let Some(ref region) = self.region {
-            query.region = Some(region.clone());
+    /// 获取引擎权重（未指定时为空）
+    pub fn get_engine_weights(&self) -> Result<HashMap<String, f64>, String> {
+        match &self.engine_weights {
+            Some(weights) => parse_engine_weights(weights),
+            None => Ok(HashMap::new()),
         }
+    }
+
+    /// 获取结果过滤条件（未指定 filters 时为空）
+    pub fn get_result_filter(&self) -> ResultFilter {
+        self.filters.as_ref().map(SearchFilters::to_result_filter).unwrap_or_default()
+    }
+
+    /// 获取提前返回条件（两个参数都未指定时为 None）
+    pub fn get_early_response(&self) -> Option<EarlyResponse> {
+        let early = EarlyResponse {
+            min_engines: self.min_engines.map(|n| n as usize),
+            max_wait: self.max_wait_ms.map(Duration::from_millis),
+        };
+        early.is_active().then_some(early)
+    }
 
-        Ok(query)
+    /// 获取排序方式（未指定或无法识别时按相关性排序）
+    pub fn get_sort_by(&self) -> SortBy {
+        self.sort
+            .as_deref()
+            .and_then(SortBy::from_param)
+            .unwrap_or(SortBy::Relevance)
+    }
+
+    /// 是否请求 NDJSON 流式输出
+    pub fn is_ndjson(&self) -> bool {
+        self.format.as_deref().is_some_and(|format| format.trim().eq_ignore_ascii_case("ndjson"))
+    }
+
+    /// 获取指定的搜索分类（`general` 与未指定等价，显式指定 engines 时忽略分类）
+    pub fn get_category(&self) -> Option<&str> {
+        if self.engines.is_some() {
+            return None;
+        }
+        self.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case("general"))
     }
 
     /// 获取搜索引擎列表
@@ -112,33 +363,35 @@ impl ApiSearchRequest {
     /// 2. 如果指定了 engine_count 参数，根据引擎延迟选择低延迟引擎
     /// 3. 默认使用全部引擎（从统一的引擎配置模块获取）
     pub fn get_engines(&self) -> Vec<String> {
+        self.get_engines_for_country(None)
+    }
+
+    /// 根据客户端所在国家获取搜索引擎列表
+    ///
+    /// 未指定 engines 参数时，中国大陆客户端默认使用中国模式引擎，
+    /// 其他客户端（或国家未知）使用全局引擎；engine_count 在此基础上截取
+    pub fn get_engines_for_country(&self, country: Option<&ClientCountry>) -> Vec<String> {
         if let Some(ref engines_str) = self.engines {
             // 自定义引擎列表
-            engines_str
+            return engines_str
                 .split(',')
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
-                .collect()
-        } else {
-            // 使用统一的引擎配置模块获取所有引擎
-            let config = EngineListConfig::default();
-            let all_engines = config.global_engines;
-            
-            if let Some(count) = self.engine_count {
-                // 根据引擎数量限制引擎列表
-                // 引擎按默认顺序排列（配置中已按延迟优化排序）
-                let count = count as usize;
-                // 只有当 count > 0 且 count 小于总引擎数时才限制
-                // 否则返回全部引擎
-                if count > 0 && count < all_engines.len() {
-                    all_engines.into_iter().take(count).collect()
-                } else {
-                    all_engines
-                }
-            } else {
-                // 默认使用全部引擎
-                all_engines
+                .collect();
+        }
+
+        // 使用统一的引擎配置模块获取默认引擎
+        let config = EngineListConfig::default();
+        let china = country.is_some_and(ClientCountry::is_china);
+        let all_engines = config.default_engines_for_region(china);
+
+        // 引擎按默认顺序排列（配置中已按延迟优化排序）
+        // 只有当 count > 0 且 count 小于总引擎数时才限制，否则返回全部引擎
+        match self.engine_count.map(|count| count as usize) {
+            Some(count) if count > 0 && count < all_engines.len() => {
+                all_engines.into_iter().take(count).collect()
             }
+            _ => all_engines,
         }
     }
 }
@@ -146,6 +399,9 @@ impl ApiSearchRequest {
 /// API 搜索响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSearchResponse {
+    /// 查询 ID（与服务端链路追踪中的 query_id 一致）
+    pub query_id: String,
+    
     /// 查询字符串
     pub query: String,
     
@@ -169,6 +425,30 @@ pub struct ApiSearchResponse {
     
     /// 是否来自缓存
     pub cached: bool,
+
+    /// 搜索完整程度（`complete`、`partial`、`degraded`），客户端可据此重试或提示结果不全
+    #[serde(default)]
+    pub status: SearchStatus,
+
+    /// 选中引擎的执行概况（成功、失败、禁用、跳过、仍在后台执行的数量）
+    #[serde(default)]
+    pub engine_counts: EngineCounts,
+
+    /// 翻译后的查询（启用翻译后搜索且实际发出了翻译查询时存在）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translated_query: Option<String>,
+
+    /// 即时答案（汇率换算、股票报价等，查询匹配时存在）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<Answer>,
+
+    /// 拼写提示（“你是不是要找”，查询包含常见拼写错误时存在）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spell_hints: Vec<String>,
+
+    /// 细化搜索所基于的查询 ID（`refine` 请求时存在）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refined_from: Option<String>,
 }
 
 /// API 搜索结果项
@@ -183,6 +463,10 @@ pub struct ApiSearchResultItem {
     /// 结果描述/摘要
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// 缩略图（图片和视频结果常有）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
     
     /// 来源引擎
     pub engine: String,
@@ -190,6 +474,73 @@ pub struct ApiSearchResultItem {
     /// 评分（用于排序）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub score: Option<f64>,
+    
+    /// 点击追踪跳转链接（仅在启用点击追踪时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_url: Option<String>,
+
+    /// 页面截图预览链接（仅在启用 `preview` 特性和结果预览时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+
+    /// 视频元数据（仅视频结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoMetadata>,
+
+    /// 图片元数据（仅图片结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageMetadata>,
+
+    /// 地点元数据（仅 map 分类结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<PlaceMetadata>,
+
+    /// 种子元数据（仅 files_p2p 分类结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torrent: Option<TorrentMetadata>,
+
+    /// 播客元数据（仅 podcasts 分类结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub podcast: Option<PodcastMetadata>,
+
+    /// Wayback Machine 最新快照（仅在启用快照链接且存在快照时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_url: Option<String>,
+
+    /// 页面摘要（仅在启用结果摘要时为靠前的结果提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    /// 查询词高亮区间（仅在请求 `highlight=1` 时提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<ResultHighlights>,
+
+    /// 安全标记（`insecure` 明文 HTTP、`shortener` 短链接、`tracker` 跟踪链接）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<SecurityFlag>,
+}
+
+/// 结果项的查询词高亮区间
+///
+/// 每个区间是对应字段 UTF-8 字节偏移的 `[start, end)`，按起点排序且互不重叠
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultHighlights {
+    /// 标题中的匹配
+    #[serde(default)]
+    pub title: Vec<HighlightRange>,
+    /// 摘要（`description`）中的匹配
+    #[serde(default)]
+    pub description: Vec<HighlightRange>,
+}
+
+impl ResultHighlights {
+    /// 计算结果项标题和摘要中的高亮区间
+    pub fn for_item(item: &ApiSearchResultItem, terms: &[Vec<char>]) -> Self {
+        Self {
+            title: find_highlights(&item.title, terms),
+            description: item.description.as_deref().map(|d| find_highlights(d, terms)).unwrap_or_default(),
+        }
+    }
 }
 
 /// API 错误响应
@@ -222,6 +573,149 @@ pub struct ApiHealthResponse {
     pub total_engines: usize,
 }
 
+/// 就绪检查配置
+#[derive(Debug, Clone)]
+pub struct HealthConfig {
+    /// 就绪所需的最少可用引擎数（K）
+    pub min_available_engines: usize,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { min_available_engines: 1 }
+    }
+}
+
+/// 首页界面配置（实例名称、语言和页脚链接），运营者无需重新编译即可定制
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// 实例名称（显示在标题、Logo 和页脚）
+    pub instance_name: String,
+    /// 标语（None 时使用当前语言的默认标语）
+    pub tagline: Option<String>,
+    /// 默认语言（请求未指定 `lang` 且 `Accept-Language` 不匹配时使用）
+    pub default_locale: Locale,
+    /// 是否显示项目仓库链接
+    pub show_repo_link: bool,
+    /// 附加的页脚链接（排在内置链接之后）
+    pub footer_links: Vec<FooterLink>,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            instance_name: "SeeSea".to_string(),
+            tagline: None,
+            default_locale: Locale::default(),
+            show_repo_link: true,
+            footer_links: Vec::new(),
+        }
+    }
+}
+
+/// 实例信息配置（`/robots.txt` 和 `/instance`）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstanceConfig {
+    /// 自定义 robots.txt 内容（None 时使用 [`DEFAULT_ROBOTS_TXT`]）
+    pub robots_txt: Option<String>,
+    /// 运营者联系方式（邮箱或 URL）
+    pub contact: Option<String>,
+    /// 实例描述
+    pub description: Option<String>,
+}
+
+/// 默认 robots.txt：允许首页，禁止抓取结果页、API 和点击跳转
+pub const DEFAULT_ROBOTS_TXT: &str = "User-agent: *\nAllow: /$\nDisallow: /search\nDisallow: /api/\nDisallow: /r\n";
+
+/// 实例信息（`/instance` 的响应），供公共实例目录收录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiInstanceInfo {
+    /// 实例名称
+    pub name: String,
+    /// 版本号
+    pub version: String,
+    /// 实例描述
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 运营者联系方式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact: Option<String>,
+    /// 可用引擎数
+    pub engine_count: usize,
+    /// 各分类的可用引擎数
+    pub categories: BTreeMap<String, usize>,
+    /// 界面支持的语言
+    pub locales: Vec<Locale>,
+}
+
+/// 页脚链接
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FooterLink {
+    /// 链接文字
+    pub label: String,
+    /// 链接地址
+    pub url: String,
+}
+
+/// 存活检查响应（`/health/live`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiLivenessResponse {
+    /// 固定为 `alive`
+    pub status: String,
+
+    /// 版本号
+    pub version: String,
+
+    /// 运行时间（秒）
+    pub uptime_seconds: u64,
+}
+
+/// 单个依赖的检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    /// `up` 或 `down`
+    pub status: String,
+
+    /// 检查耗时（毫秒）
+    pub latency_ms: f64,
+
+    /// 附加说明
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl DependencyHealth {
+    /// 依赖可用
+    pub fn up(latency: Duration, details: impl Into<String>) -> Self {
+        Self { status: "up".to_string(), latency_ms: latency.as_secs_f64() * 1000.0, details: Some(details.into()) }
+    }
+
+    /// 依赖不可用
+    pub fn down(latency: Duration, details: impl Into<String>) -> Self {
+        Self { status: "down".to_string(), latency_ms: latency.as_secs_f64() * 1000.0, details: Some(details.into()) }
+    }
+
+    /// 是否可用
+    pub fn is_up(&self) -> bool {
+        self.status == "up"
+    }
+}
+
+/// 就绪检查响应（`/health/ready`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiReadinessResponse {
+    /// `ready` 或 `not_ready`
+    pub status: String,
+
+    /// 版本号
+    pub version: String,
+
+    /// 各依赖的检查结果（cache、engines、rss、scheduler）
+    pub checks: BTreeMap<String, DependencyHealth>,
+}
+
 /// API 引擎信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiEngineInfo {
@@ -340,6 +834,24 @@ mod tests {
         assert_eq!(engines.len(), config.global_engines.len());
     }
 
+    #[test]
+    fn test_api_search_request_engines_for_country() {
+        let config = EngineListConfig::default();
+        let cn = ClientCountry("CN".to_string());
+        let us = ClientCountry("US".to_string());
+
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "test"}"#).unwrap();
+        assert_eq!(request.get_engines_for_country(Some(&cn)), config.china_engines);
+        assert_eq!(request.get_engines_for_country(Some(&us)), config.global_engines);
+
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "test", "n": 2}"#).unwrap();
+        assert_eq!(request.get_engines_for_country(Some(&cn)), config.china_engines[..2]);
+
+        // 显式指定的引擎不受地区影响
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "test", "engines": "yandex"}"#).unwrap();
+        assert_eq!(request.get_engines_for_country(Some(&cn)), vec!["yandex"]);
+    }
+
     #[test]
     fn test_api_search_request_to_search_query() {
         let request = ApiSearchRequest {
@@ -353,6 +865,20 @@ mod tests {
             safe_search: None,
             time_range: None,
             engines: None,
+            category: None,
+            sort: None,
+            format: None,
+            min_engines: None,
+            max_wait_ms: None,
+            filters: None,
+            fields: None,
+            compact: false,
+            engine_weights: None,
+            max_per_domain: None,
+            lang_strict: false,
+            security_strict: false,
+            refine: None,
+            highlight: false,
         };
 
         let query = request.to_search_query().unwrap();
@@ -362,6 +888,151 @@ mod tests {
         assert_eq!(query.language, Some("en".to_string()));
     }
 
+    #[test]
+    fn test_api_search_request_filters() {
+        let request: ApiSearchRequest = serde_json::from_str(r#"{
+            "q": "async runtime",
+            "filters": {
+                "time_range": "week",
+                "include_sites": ["https://www.docs.rs/"],
+                "result_types": ["web", "news"],
+                "min_score": 0.2,
+                "languages": ["en", "zh"]
+            }
+        }"#).unwrap();
+
+        let query = request.to_search_query().unwrap();
+        assert_eq!(query.query, "async runtime site:docs.rs");
+        assert_eq!(query.time_range, Some(TimeRange::Week));
+        assert_eq!(query.language.as_deref(), Some("en"));
+
+        let filter = request.get_result_filter();
+        assert_eq!(filter.result_types, vec![ResultType::Web, ResultType::News]);
+        assert_eq!(filter.min_score, Some(0.2));
+        assert!(filter.published_after.is_some());
+
+        // 未知字段和非法取值直接拒绝
+        assert!(serde_json::from_str::<ApiSearchRequest>(r#"{"q": "x", "filters": {"site": "a.com"}}"#).is_err());
+        assert!(serde_json::from_str::<ApiSearchRequest>(r#"{"q": "x", "filters": {"time_range": "decade"}}"#).is_err());
+        assert!(serde_json::from_str::<ApiSearchRequest>(r#"{"q": "x"}"#).unwrap().get_result_filter().is_empty());
+
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "x", "time_range": "d"}"#).unwrap();
+        assert_eq!(request.to_search_query().unwrap().time_range, Some(TimeRange::Day));
+    }
+
+    #[test]
+    fn test_api_search_request_projection() {
+        let request: ApiSearchRequest = serde_urlencoded::from_str("q=rust&fields=title,url,score").unwrap();
+        let projection = request.get_projection().unwrap();
+        assert_eq!(projection.fields(), ["score", "title", "url"]);
+
+        let response = ApiSearchResponse {
+            query_id: "q1".to_string(),
+            query: "rust".to_string(),
+            results: vec![ApiSearchResultItem {
+                title: "Rust".to_string(),
+                url: "https://www.rust-lang.org/".to_string(),
+                description: Some("A language empowering everyone".to_string()),
+                thumbnail: None,
+                engine: "bing".to_string(),
+                score: Some(0.9),
+                click_url: None,
+                preview_url: None,
+                video: None,
+                image: None,
+                place: None,
+                torrent: None,
+                podcast: None,
+                archive_url: Some("https://web.archive.org/web/2024/https://www.rust-lang.org/".to_string()),
+                summary: None,
+                highlights: None,
+                flags: Vec::new(),
+            }],
+            total_count: 1,
+            page: 1,
+            page_size: 10,
+            engines_used: vec!["bing".to_string()],
+            query_time_ms: 5,
+            cached: false,
+            status: SearchStatus::Complete,
+            engine_counts: EngineCounts::default(),
+            translated_query: None,
+            answer: None,
+            spell_hints: Vec::new(),
+            refined_from: None,
+        };
+        let value = projection.apply(&response);
+        assert_eq!(value["total_count"], 1);
+        assert_eq!(value["results"][0].as_object().unwrap().len(), 3);
+        assert_eq!(value["results"][0]["score"], 0.9);
+
+        // 精简模式去掉摘要和元数据；与 fields 同时指定时取交集
+        let request: ApiSearchRequest = serde_urlencoded::from_str("q=rust&compact=1").unwrap();
+        let value = request.get_projection().unwrap().apply(&response);
+        let item = value["results"][0].as_object().unwrap();
+        assert!(item.contains_key("engine") && !item.contains_key("description") && !item.contains_key("archive_url"));
+
+        let request: ApiSearchRequest = serde_urlencoded::from_str("q=rust&compact=true&fields=content,url").unwrap();
+        assert_eq!(request.get_projection().unwrap().fields(), ["url"]);
+
+        let request: ApiSearchRequest = serde_urlencoded::from_str("q=rust&compact=0").unwrap();
+        assert!(request.get_projection().is_none());
+    }
+
+    #[test]
+    fn test_api_search_request_category() {
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "paris", "category": "map"}"#).unwrap();
+        assert_eq!(request.get_category(), Some("map"));
+
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "paris", "category": "general"}"#).unwrap();
+        assert_eq!(request.get_category(), None);
+
+        // 显式指定的引擎优先于分类
+        let request: ApiSearchRequest =
+            serde_json::from_str(r#"{"q": "paris", "category": "map", "engines": "bing"}"#).unwrap();
+        assert_eq!(request.get_category(), None);
+    }
+
+    #[test]
+    fn test_api_search_request_sort() {
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "test", "sort": "date"}"#).unwrap();
+        assert_eq!(request.get_sort_by(), SortBy::Time);
+
+        let request: ApiSearchRequest = serde_json::from_str(r#"{"q": "test"}"#).unwrap();
+        assert_eq!(request.get_sort_by(), SortBy::Relevance);
+    }
+
+    #[test]
+    fn test_result_highlights() {
+        let request: ApiSearchRequest = serde_urlencoded::from_str("q=rust&highlight=1").unwrap();
+        assert!(request.highlight);
+
+        let item = ApiSearchResultItem {
+            title: "Rust 所有权".to_string(),
+            url: "https://doc.rust-lang.org/book/".to_string(),
+            description: Some("理解所有权是学习 Rust 的关键".to_string()),
+            thumbnail: None,
+            engine: "bing".to_string(),
+            score: None,
+            click_url: None,
+            preview_url: None,
+            video: None,
+            image: None,
+            place: None,
+            torrent: None,
+            podcast: None,
+            archive_url: None,
+            summary: None,
+            highlights: None,
+            flags: Vec::new(),
+        };
+        let terms = crate::search::highlight_terms("rust 所有权");
+        let highlights = ResultHighlights::for_item(&item, &terms);
+        assert_eq!(highlights.title, vec![[0, 4], [5, 14]]);
+        assert_eq!(highlights.description, vec![[6, 15], [25, 29]]);
+        assert_eq!(serde_json::to_value(&highlights).unwrap()["title"], serde_json::json!([[0, 4], [5, 14]]));
+    }
+
     #[test]
     fn test_api_stats_response_cache_hit_rate() {
         use crate::search::SearchStatsResult;
@@ -0,0 +1,462 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 请求校验
+//!
+//! 在进入处理器之前校验搜索和 RSS 请求（查询长度、引擎数量、分页范围等），
+//! 校验失败时返回 422 和结构化的 [`ApiErrorResponse`]；请求体超过全局上限时返回 413。
+
+use axum::{
+    extract::{FromRequest, FromRequestParts, Json, Query, Request},
+    extract::rejection::JsonRejection,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+
+use crate::api::handlers::answer::AnswerRequest;
+use crate::api::handlers::news::NewsQuery;
+use crate::api::handlers::rss::{RssDiscoverRequest, RssFetchRequest, RssItemsQuery, TemplateAddRequest};
+use crate::api::handlers::tools::WebSearchToolRequest;
+use crate::api::on::ApiState;
+use crate::api::types::{ApiErrorResponse, ApiSearchRequest, PROJECTABLE_FIELDS};
+use crate::derive::region_country;
+use crate::search::aggregator::parse_engine_weights;
+
+/// 查询 ID 最大长度
+const MAX_QUERY_ID_LEN: usize = 64;
+
+/// 校验限制
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationLimits {
+    /// 查询字符串最大长度（字符数）
+    pub max_query_length: usize,
+    /// 单次请求最多指定的引擎数
+    pub max_engines: usize,
+    /// 最大页码
+    pub max_page: u32,
+    /// 每页最大结果数
+    pub max_page_size: u32,
+    /// 请求体大小上限（字节）
+    pub max_body_bytes: usize,
+    /// URL 最大长度
+    pub max_url_length: usize,
+    /// 列表参数（关键词、分类）最大项数
+    pub max_list_items: usize,
+    /// RSS 单次获取的最大条目数
+    pub max_rss_items: usize,
+    /// 提前返回的最长等待时间（毫秒）
+    pub max_wait_ms: u64,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        Self {
+            max_query_length: 512,
+            max_engines: 20,
+            max_page: 100,
+            max_page_size: 100,
+            max_body_bytes: 64 * 1024,
+            max_url_length: 2048,
+            max_list_items: 50,
+            max_rss_items: 500,
+            max_wait_ms: 30_000,
+        }
+    }
+}
+
+/// 校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// 出错的字段
+    pub field: &'static str,
+    /// 错误描述
+    pub message: String,
+}
+
+impl ValidationError {
+    /// 创建校验错误
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ValidationError {
+    fn into_response(self) -> Response {
+        let error = ApiErrorResponse {
+            code: "VALIDATION_ERROR".to_string(),
+            message: self.message,
+            details: Some(format!("field: {}", self.field)),
+        };
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(error)).into_response()
+    }
+}
+
+/// 可校验的请求
+pub trait Validate {
+    /// 按限制校验请求
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError>;
+}
+
+fn check_len(field: &'static str, value: &str, max: usize) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, format!("{} 不能为空", field)));
+    }
+    if value.chars().count() > max {
+        return Err(ValidationError::new(field, format!("{} 长度不能超过 {}", field, max)));
+    }
+    Ok(())
+}
+
+fn check_range(field: &'static str, value: u64, max: u64) -> Result<(), ValidationError> {
+    if value == 0 || value > max {
+        return Err(ValidationError::new(field, format!("{} 必须在 1 到 {} 之间", field, max)));
+    }
+    Ok(())
+}
+
+fn check_list(field: &'static str, len: usize, max: usize) -> Result<(), ValidationError> {
+    if len > max {
+        return Err(ValidationError::new(field, format!("{} 最多 {} 项", field, max)));
+    }
+    Ok(())
+}
+
+impl Validate for ApiSearchRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        let query = self.get_query().map_err(|e| ValidationError::new("query", e))?;
+        check_len("query", &query, limits.max_query_length)?;
+        check_range("page", u64::from(self.page), u64::from(limits.max_page))?;
+        check_range("page_size", u64::from(self.page_size), u64::from(limits.max_page_size))?;
+        if let Some(engines) = &self.engines {
+            let count = engines.split(',').filter(|e| !e.trim().is_empty()).count();
+            check_list("engines", count, limits.max_engines)?;
+        }
+        if let Some(category) = &self.category {
+            check_len("category", category, limits.max_query_length)?;
+        }
+        if let Some(format) = &self.format
+            && !["json", "ndjson"].contains(&format.trim().to_ascii_lowercase().as_str())
+        {
+            return Err(ValidationError::new("format", "format 必须是 json 或 ndjson"));
+        }
+        if let Some(min_engines) = self.min_engines {
+            check_range("min_engines", u64::from(min_engines), limits.max_engines as u64)?;
+        }
+        if let Some(max_wait_ms) = self.max_wait_ms {
+            check_range("max_wait_ms", max_wait_ms, limits.max_wait_ms)?;
+        }
+        if let Some(fields) = &self.fields {
+            let fields: Vec<&str> = fields.split(',').map(str::trim).filter(|f| !f.is_empty()).collect();
+            check_list("fields", fields.len(), limits.max_list_items)?;
+            if let Some(unknown) = fields.iter().find(|f| !PROJECTABLE_FIELDS.contains(&f.to_ascii_lowercase().as_str())) {
+                return Err(ValidationError::new("fields", format!("未知字段 {}，可选：{}", unknown, PROJECTABLE_FIELDS.join(", "))));
+            }
+        }
+        if let Some(weights) = &self.engine_weights {
+            let weights = parse_engine_weights(weights).map_err(|e| ValidationError::new("engine_weights", e))?;
+            check_list("engine_weights", weights.len(), limits.max_engines)?;
+        }
+        if let Some(filters) = &self.filters {
+            check_list("filters.include_sites", filters.include_sites.len(), limits.max_list_items)?;
+            check_list("filters.exclude_sites", filters.exclude_sites.len(), limits.max_list_items)?;
+            check_list("filters.languages", filters.languages.len(), limits.max_list_items)?;
+            for site in filters.include_sites.iter().chain(&filters.exclude_sites) {
+                check_len("filters.sites", site, limits.max_url_length)?;
+            }
+            if filters.min_score.is_some_and(|score| !score.is_finite()) {
+                return Err(ValidationError::new("filters.min_score", "min_score 必须是有限数值"));
+            }
+        }
+        if let Some(region) = &self.region
+            && region_country(region).is_none()
+        {
+            return Err(ValidationError::new("region", "region 必须是国家代码（如 US）或语言-国家代码（如 en-US）"));
+        }
+        if let Some(query_id) = &self.refine {
+            check_len("refine", query_id, MAX_QUERY_ID_LEN)?;
+            if !query_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(ValidationError::new("refine", "refine 必须是搜索响应中的 query_id"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Validate for RssFetchRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        check_len("url", &self.url, limits.max_url_length)?;
+        if !crate::url_utils::is_http_url(&self.url) {
+            return Err(ValidationError::new("url", "url 必须以 http:// 或 https:// 开头"));
+        }
+        if let Some(max_items) = self.max_items {
+            check_range("max_items", max_items as u64, limits.max_rss_items as u64)?;
+        }
+        check_list("filter_keywords", self.filter_keywords.len(), limits.max_list_items)
+    }
+}
+
+impl Validate for RssDiscoverRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        check_len("url", &self.url, limits.max_url_length)?;
+        let Some(url) = crate::url_utils::parse_http_url(&self.url) else {
+            return Err(ValidationError::new("url", "url 必须以 http:// 或 https:// 开头"));
+        };
+        // 域名在抓取前解析后再检查，这里只拦截明显的内网地址
+        let private = match url.host() {
+            Some(url::Host::Ipv4(ip)) => !crate::rss::is_public_ip(ip.into()),
+            Some(url::Host::Ipv6(ip)) => !crate::rss::is_public_ip(ip.into()),
+            Some(url::Host::Domain(domain)) => {
+                let domain = domain.to_ascii_lowercase();
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+            None => true,
+        };
+        if private {
+            return Err(ValidationError::new("url", "url 不能指向内网地址"));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for RssItemsQuery {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        if let Some(tag) = &self.tag {
+            check_len("tag", tag, limits.max_query_length)?;
+        }
+        check_range("limit", self.limit as u64, u64::from(limits.max_page_size))
+    }
+}
+
+impl Validate for NewsQuery {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        check_len("q", &self.q, limits.max_query_length)?;
+        if let Some(limit) = self.limit {
+            check_range("limit", limit as u64, u64::from(limits.max_page_size))?;
+        }
+        if let Some(threshold) = self.threshold
+            && !(threshold > 0.0 && threshold <= 1.0)
+        {
+            return Err(ValidationError::new("threshold", "threshold 必须在 0 到 1 之间"));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for AnswerRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        check_len("query", &self.query, limits.max_query_length)?;
+        check_list("engines", self.engines.len(), limits.max_engines)?;
+        if let Some(max_sources) = self.max_sources {
+            check_range("max_sources", max_sources as u64, u64::from(limits.max_page_size))?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for WebSearchToolRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        let args = self.args().map_err(|e| ValidationError::new("arguments", e))?;
+        check_len("query", &args.query, limits.max_query_length)?;
+        if let Some(max_results) = args.max_results {
+            check_range("max_results", max_results as u64, u64::from(limits.max_page_size))?;
+        }
+        Ok(())
+    }
+}
+
+impl Validate for TemplateAddRequest {
+    fn validate(&self, limits: &ValidationLimits) -> Result<(), ValidationError> {
+        check_len("name", &self.name, limits.max_query_length)?;
+        check_list("categories", self.categories.len(), limits.max_list_items)
+    }
+}
+
+/// 校验后的 JSON 请求体
+///
+/// 请求体过大返回 413，无法解析或校验失败返回 422
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> FromRequest<ApiState> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &ApiState) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(json_rejection)?;
+        value.validate(&state.validation).map_err(IntoResponse::into_response)?;
+        Ok(Self(value))
+    }
+}
+
+/// 校验后的查询参数
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T> FromRequestParts<ApiState> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &ApiState,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| invalid_input("INVALID_QUERY", "查询参数无效", e.body_text()))?;
+        value.validate(&state.validation).map_err(IntoResponse::into_response)?;
+        Ok(Self(value))
+    }
+}
+
+fn invalid_input(code: &str, message: &str, details: String) -> Response {
+    let error = ApiErrorResponse {
+        code: code.to_string(),
+        message: message.to_string(),
+        details: Some(details),
+    };
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(error)).into_response()
+}
+
+fn json_rejection(rejection: JsonRejection) -> Response {
+    if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        let error = ApiErrorResponse {
+            code: "PAYLOAD_TOO_LARGE".to_string(),
+            message: "请求体过大".to_string(),
+            details: Some(rejection.body_text()),
+        };
+        return (StatusCode::PAYLOAD_TOO_LARGE, Json(error)).into_response();
+    }
+    invalid_input("INVALID_BODY", "请求体无效", rejection.body_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::on::ApiInterface;
+    use crate::search::{SearchConfig, SearchInterface};
+    use axum::body::{to_bytes, Body};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    fn search_request(json: &str) -> ApiSearchRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_search_request_validation() {
+        let limits = ValidationLimits::default();
+        assert!(search_request(r#"{"q": "rust"}"#).validate(&limits).is_ok());
+
+        let field = |json: &str| search_request(json).validate(&limits).unwrap_err().field;
+        assert_eq!(field(r#"{"page": 1}"#), "query");
+        assert_eq!(field(r#"{"q": "   "}"#), "query");
+        assert_eq!(field(&format!(r#"{{"q": "{}"}}"#, "a".repeat(513))), "query");
+        assert_eq!(field(r#"{"q": "rust", "page": 0}"#), "page");
+        assert_eq!(field(r#"{"q": "rust", "page": 101}"#), "page");
+        assert_eq!(field(r#"{"q": "rust", "page_size": 1000}"#), "page_size");
+        assert_eq!(field(r#"{"q": "rust", "region": "nowhere"}"#), "region");
+        assert!(search_request(r#"{"q": "rust", "region": "en-GB"}"#).validate(&limits).is_ok());
+
+        assert_eq!(field(r#"{"q": "rust", "fields": "title,secret"}"#), "fields");
+        assert!(search_request(r#"{"q": "rust", "fields": "Title, url"}"#).validate(&limits).is_ok());
+
+        assert_eq!(field(r#"{"q": "rust", "engine_weights": "bing=2"}"#), "engine_weights");
+        assert!(search_request(r#"{"q": "rust", "engine_weights": "bing:2.0,baidu:0.5"}"#).validate(&limits).is_ok());
+
+        assert_eq!(field(r#"{"q": "tokio", "refine": "../etc"}"#), "refine");
+        assert!(search_request(r#"{"q": "tokio", "refine": "0f3a9c"}"#).validate(&limits).is_ok());
+
+        assert_eq!(field(r#"{"q": "rust", "format": "xml"}"#), "format");
+        assert!(search_request(r#"{"q": "rust", "format": "NDJSON"}"#).is_ndjson());
+
+        let engines = vec!["bing"; 21].join(",");
+        assert_eq!(field(&format!(r#"{{"q": "rust", "engines": "{}"}}"#, engines)), "engines");
+    }
+
+    #[test]
+    fn test_rss_request_validation() {
+        let limits = ValidationLimits::default();
+        let request = |json: &str| serde_json::from_str::<RssFetchRequest>(json).unwrap().validate(&limits);
+        assert!(request(r#"{"url": "https://example.com/feed.xml"}"#).is_ok());
+        assert_eq!(request(r#"{"url": "file:///etc/passwd"}"#).unwrap_err().field, "url");
+        assert_eq!(request(r#"{"url": "https://a.com", "max_items": 0}"#).unwrap_err().field, "max_items");
+
+        let discover = |url: &str| RssDiscoverRequest { url: url.to_string() }.validate(&limits);
+        assert!(discover("https://example.com/").is_ok());
+        for url in ["http://127.0.0.1/", "http://localhost:8080/", "http://[::1]/", "http://192.168.0.1/"] {
+            assert_eq!(discover(url).unwrap_err().field, "url", "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_news_query_validation() {
+        let limits = ValidationLimits::default();
+        let query = |q: &str, limit: Option<usize>, threshold: Option<f64>| {
+            NewsQuery { q: q.to_string(), limit, threshold, include_rss: true }.validate(&limits)
+        };
+        assert!(query("rust", Some(10), Some(0.6)).is_ok());
+        assert_eq!(query(" ", None, None).unwrap_err().field, "q");
+        assert_eq!(query("rust", Some(0), None).unwrap_err().field, "limit");
+        assert_eq!(query("rust", None, Some(1.5)).unwrap_err().field, "threshold");
+    }
+
+    #[tokio::test]
+    async fn test_validation_responses() {
+        let search = Arc::new(SearchInterface::new(SearchConfig::default()).unwrap());
+        let router = ApiInterface::new(search, "0.1.0".to_string()).build_internal_router();
+        let post = |uri: &str, body: Body| {
+            axum::http::Request::post(uri)
+                .header("content-type", "application/json")
+                .body(body)
+                .unwrap()
+        };
+
+        let response = router.clone()
+            .oneshot(post("/api/search", Body::from(r#"{"q": "rust", "page": 0}"#)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+
+        // 语法错误同样返回 422 而不是 400
+        let response = router.clone()
+            .oneshot(post("/api/rss/fetch", Body::from("{not json")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = router.clone()
+            .oneshot(axum::http::Request::get("/api/search?q=rust&page_size=0").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let oversized = format!(r#"{{"q": "{}"}}"#, "a".repeat(ValidationLimits::default().max_body_bytes));
+        let response = router
+            .oneshot(post("/api/search", Body::from(oversized)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -20,8 +20,10 @@ use clap::{Parser, Subcommand};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use seesea_core::config::{ConfigFormat, check_config_str};
 use seesea_core::derive::{SearchQuery, SearchResultItem};
 use seesea_core::search::{SearchInterface, SearchConfig, SearchRequest};
 use seesea_core::search::engine_config::EngineMode;
@@ -73,6 +75,16 @@ enum Commands {
         #[arg(short, long)]
         global: bool,
     },
+
+    /// 检查配置文件（解析并验证跨字段约束）
+    CheckConfig {
+        /// 配置文件路径（.toml 或 .json）
+        file: PathBuf,
+
+        /// 以 JSON 输出验证结果
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[tokio::main]
@@ -89,6 +101,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(Commands::Interactive { global }) => {
             interactive_mode(global).await?;
         }
+        Some(Commands::CheckConfig { file, json }) => {
+            if !check_config(&file, json)? {
+                std::process::exit(1);
+            }
+        }
         None => {
             // 默认进入交互模式
             interactive_mode(false).await?;
@@ -166,6 +183,13 @@ async fn execute_search(
         max_results: Some(100),
         force: false,
         cache_timeline: Some(3600),
+        early_response: None,
+        filter: Default::default(),
+        engine_weights: Default::default(),
+        max_per_domain: None,
+        lang_strict: false,
+        security_strict: false,
+        cancellation: None,
     };
 
     // 执行搜索
@@ -370,6 +394,41 @@ async fn list_engines(show_stats: bool) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
+/// 检查配置文件，返回配置是否有效
+fn check_config(path: &Path, json: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    let format = ConfigFormat::from_path(path)
+        .ok_or_else(|| format!("不支持的配置文件格式: {}（仅支持 .toml 与 .json）", path.display()))?;
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取配置文件 {} 失败: {}", path.display(), e))?;
+    let result = check_config_str(&content, format);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(result.is_valid);
+    }
+
+    println!("{} {}", "🔧 检查配置".bright_cyan().bold(), path.display());
+    println!("{}", "━".repeat(60).bright_black());
+    for error in &result.errors {
+        println!("  {} {}", "✗".bright_red().bold(), error);
+    }
+    for warning in &result.warnings {
+        println!("  {} {}", "!".bright_yellow().bold(), warning);
+    }
+    if result.is_valid {
+        println!("{} 配置有效（{} 条警告）", "✓".bright_green().bold(), result.warnings.len());
+    } else {
+        println!(
+            "{} 配置无效：{} 个错误，{} 条警告",
+            "✗".bright_red().bold(),
+            result.errors.len(),
+            result.warnings.len()
+        );
+    }
+
+    Ok(result.is_valid)
+}
+
 /// 交互式搜索模式
 async fn interactive_mode(use_global: bool) -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "🌊 SeeSea 交互式搜索".bright_cyan().bold());
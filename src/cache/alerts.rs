@@ -0,0 +1,282 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 保存的搜索存储
+//!
+//! 保存的搜索（查询、引擎和执行间隔）保存在 sled 的持久化存储树中（清空缓存不会影响），
+//! 每次执行发现的新结果作为带保留期的缓存条目保存。
+//! 每个保存的搜索按 URL 哈希记录已见过的结果，只有首次出现的结果才会存为新结果。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// 保存的搜索键前缀（后接 ID）
+const ALERT_PREFIX: &str = "alert:";
+
+/// 新结果键前缀（后接 ID 和发现时间）
+const ALERT_RESULT_PREFIX: &str = "alert_result:";
+
+/// 已见结果键前缀（后接 ID 和 URL 哈希）
+const ALERT_SEEN_PREFIX: &str = "alert_seen:";
+
+/// 新结果和已见记录的保留时间
+const ALERT_RESULT_RETENTION: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// 保存的搜索
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    /// ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 查询
+    pub query: String,
+    /// 使用的引擎（为空表示默认引擎）
+    pub engines: Vec<String>,
+    /// 执行间隔（秒）
+    pub interval_secs: u64,
+    /// 是否启用
+    pub enabled: bool,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 上次执行时间
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// 上次执行的错误
+    pub last_error: Option<String>,
+}
+
+impl SavedSearch {
+    /// 下次执行时间（从未执行过时为创建时间）
+    pub fn next_run_at(&self) -> DateTime<Utc> {
+        match self.last_run_at {
+            Some(last) => last + chrono::Duration::seconds(self.interval_secs.min(i64::MAX as u64) as i64),
+            None => self.created_at,
+        }
+    }
+
+    /// 是否到了执行时间
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.next_run_at() <= now
+    }
+}
+
+/// 保存的搜索发现的新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertResult {
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+    /// 摘要
+    pub content: String,
+    /// 来源引擎
+    pub engine: String,
+    /// 评分
+    pub score: f64,
+    /// 发现时间
+    pub found_at: DateTime<Utc>,
+}
+
+/// 保存的搜索存储
+///
+/// 封装 CacheManager，保存的搜索存于持久化存储树，新结果和已见记录存于缓存
+pub struct AlertStore {
+    manager: Arc<CacheManager>,
+    store: sled::Tree,
+}
+
+fn db_error(e: sled::Error) -> CacheError {
+    CacheError::DatabaseError(format!("访问保存的搜索存储失败: {}", e))
+}
+
+impl AlertStore {
+    /// 创建保存的搜索存储
+    pub fn new(manager: Arc<CacheManager>) -> Self {
+        let store = manager.store().clone();
+        Self { manager, store }
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+        bincode::serde::encode_to_vec(value, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize saved search: {}", e)))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .ok()
+            .map(|(value, _)| value)
+    }
+
+    fn url_hash(url: &str) -> String {
+        format!("{:x}", Sha256::digest(url.as_bytes()))
+    }
+
+    fn keys(&self, prefix: &str) -> Result<Vec<String>> {
+        self.manager.scan_prefix(prefix).keys()
+            .map(|item| {
+                item.map(|key| String::from_utf8_lossy(&key).into_owned())
+                    .map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))
+            })
+            .collect()
+    }
+
+    /// 保存（新建或更新）
+    pub fn save(&self, search: &SavedSearch) -> Result<()> {
+        self.store.insert(format!("{}{}", ALERT_PREFIX, search.id), Self::encode(search)?).map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 新建保存的搜索
+    pub fn create(
+        &self,
+        name: impl Into<String>,
+        query: impl Into<String>,
+        engines: Vec<String>,
+        interval: Duration,
+    ) -> Result<SavedSearch> {
+        let search = SavedSearch {
+            id: Uuid::new_v4().simple().to_string(),
+            name: name.into(),
+            query: query.into(),
+            engines,
+            interval_secs: interval.as_secs(),
+            enabled: true,
+            created_at: Utc::now(),
+            last_run_at: None,
+            last_error: None,
+        };
+        self.save(&search)?;
+        Ok(search)
+    }
+
+    /// 按 ID 查找
+    pub fn find(&self, id: &str) -> Result<Option<SavedSearch>> {
+        Ok(self.store.get(format!("{}{}", ALERT_PREFIX, id)).map_err(db_error)?
+            .and_then(|bytes| Self::decode(&bytes)))
+    }
+
+    /// 列出所有保存的搜索（按创建时间排序）
+    pub fn list(&self) -> Result<Vec<SavedSearch>> {
+        let mut searches: Vec<SavedSearch> = Vec::new();
+        for item in self.store.scan_prefix(ALERT_PREFIX).values() {
+            if let Some(search) = Self::decode(&item.map_err(db_error)?) {
+                searches.push(search);
+            }
+        }
+        searches.sort_by_key(|s| s.created_at);
+        Ok(searches)
+    }
+
+    /// 到了执行时间的保存的搜索
+    pub fn due(&self, now: DateTime<Utc>) -> Result<Vec<SavedSearch>> {
+        Ok(self.list()?.into_iter().filter(|s| s.is_due(now)).collect())
+    }
+
+    /// 删除保存的搜索及其结果，返回是否存在
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let existed = self.store.remove(format!("{}{}", ALERT_PREFIX, id)).map_err(db_error)?.is_some();
+        for prefix in [ALERT_RESULT_PREFIX, ALERT_SEEN_PREFIX] {
+            for key in self.keys(&format!("{}{}:", prefix, id))? {
+                self.manager.delete(&key)?;
+            }
+        }
+        Ok(existed)
+    }
+
+    /// 记录一次执行的结果，返回其中首次出现的结果
+    pub fn record_results(&self, id: &str, results: Vec<AlertResult>) -> Result<Vec<AlertResult>> {
+        let mut new_results = Vec::new();
+        for result in results {
+            let hash = Self::url_hash(&result.url);
+            let seen_key = format!("{}{}:{}", ALERT_SEEN_PREFIX, id, hash);
+            if self.manager.get(&seen_key)?.is_some() {
+                continue;
+            }
+            self.manager.set(seen_key, Vec::new(), Some(ALERT_RESULT_RETENTION))?;
+            let timestamp = result.found_at.timestamp_nanos_opt().unwrap_or_default();
+            let key = format!("{}{}:{:020}:{}", ALERT_RESULT_PREFIX, id, timestamp, hash);
+            self.manager.set(key, Self::encode(&result)?, Some(ALERT_RESULT_RETENTION))?;
+            new_results.push(result);
+        }
+        Ok(new_results)
+    }
+
+    /// 新结果（最新发现的在前），`since` 只返回该时间之后发现的结果
+    pub fn results(&self, id: &str, since: Option<DateTime<Utc>>, limit: usize) -> Result<Vec<AlertResult>> {
+        let mut results = Vec::new();
+        for key in self.keys(&format!("{}{}:", ALERT_RESULT_PREFIX, id))?.into_iter().rev() {
+            if results.len() >= limit {
+                break;
+            }
+            let Some(result) = self.manager.get(&key)?.and_then(|bytes| Self::decode::<AlertResult>(&bytes)) else {
+                continue;
+            };
+            // 键按发现时间升序，遇到更早的结果即可停止
+            if since.is_some_and(|since| result.found_at <= since) {
+                break;
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> AlertResult {
+        AlertResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            engine: "bing".to_string(),
+            score: 1.0,
+            found_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_alert_store_records_only_new_results() {
+        let store = AlertStore::new(CacheManager::temporary());
+        let mut search = store.create("rust", "rust release", vec!["bing".to_string()], Duration::from_secs(3600)).unwrap();
+        assert!(search.is_due(Utc::now()));
+
+        let new = store.record_results(&search.id, vec![result("https://a.com/1"), result("https://a.com/2")]).unwrap();
+        assert_eq!(new.len(), 2);
+        let checkpoint = Utc::now();
+        let new = store.record_results(&search.id, vec![result("https://a.com/2"), result("https://a.com/3")]).unwrap();
+        assert_eq!(new.len(), 1);
+
+        assert_eq!(store.results(&search.id, None, 10).unwrap().len(), 3);
+        let since = store.results(&search.id, Some(checkpoint), 10).unwrap();
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].url, "https://a.com/3");
+
+        search.last_run_at = Some(Utc::now());
+        store.save(&search).unwrap();
+        assert!(!store.find(&search.id).unwrap().unwrap().is_due(Utc::now()));
+
+        assert!(store.delete(&search.id).unwrap());
+        assert!(store.results(&search.id, None, 10).unwrap().is_empty());
+        assert!(store.find(&search.id).unwrap().is_none());
+    }
+}
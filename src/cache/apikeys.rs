@@ -0,0 +1,310 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! API 密钥存储
+//!
+//! 在 sled 的持久化存储树中保存 API 密钥（清空缓存不会影响密钥）。密钥只以 SHA-256
+//! 哈希形式存储，明文仅在创建或轮换时返回一次；每个密钥可单独配置限流并记录使用次数。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// API 密钥键前缀（后接密钥哈希）
+const API_KEY_PREFIX: &str = "api_key:";
+
+/// 密钥 ID 索引键前缀（后接密钥 ID，值为密钥哈希）
+const API_KEY_ID_PREFIX: &str = "api_key_id:";
+
+/// API 密钥明文前缀
+const API_KEY_TOKEN_PREFIX: &str = "sk_";
+
+/// 单个密钥的限流覆盖
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyRateLimit {
+    /// 每秒请求数
+    pub requests_per_second: u32,
+    /// 突发请求容量
+    pub burst_size: u32,
+}
+
+/// API 密钥记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyRecord {
+    /// 密钥 ID（用于管理操作）
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 明文前缀（用于识别密钥）
+    pub key_prefix: String,
+    /// 密钥哈希
+    pub key_hash: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 吊销时间
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// 限流覆盖（None 表示使用全局配置）
+    pub rate_limit: Option<ApiKeyRateLimit>,
+    /// 累计请求数
+    pub total_requests: u64,
+    /// 最近使用时间
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyRecord {
+    /// 是否有效（未吊销）
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+}
+
+/// 计算密钥哈希
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// API 密钥存储
+///
+/// 使用 CacheManager 的持久化存储树，以密钥哈希为键存储密钥记录，并按 ID 建立索引
+pub struct ApiKeyStore {
+    store: sled::Tree,
+}
+
+fn db_error(e: sled::Error) -> CacheError {
+    CacheError::DatabaseError(format!("访问 API 密钥存储失败: {}", e))
+}
+
+fn encode(record: &ApiKeyRecord) -> Result<Vec<u8>> {
+    bincode::serde::encode_to_vec(record, bincode::config::standard())
+        .map_err(|e| CacheError::SerializationError(format!("Failed to serialize API key: {}", e)))
+}
+
+fn decode(bytes: &[u8]) -> Option<ApiKeyRecord> {
+    bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+        .ok()
+        .map(|(record, _)| record)
+}
+
+impl ApiKeyStore {
+    /// 创建 API 密钥存储
+    pub fn new(manager: Arc<CacheManager>) -> Self {
+        Self { store: manager.store().clone() }
+    }
+
+    fn record_key(key_hash: &str) -> String {
+        format!("{}{}", API_KEY_PREFIX, key_hash)
+    }
+
+    fn id_key(id: &str) -> String {
+        format!("{}{}", API_KEY_ID_PREFIX, id)
+    }
+
+    fn load(&self, key_hash: &str) -> Result<Option<ApiKeyRecord>> {
+        Ok(self.store.get(Self::record_key(key_hash)).map_err(db_error)?
+            .and_then(|bytes| decode(&bytes)))
+    }
+
+    fn save(&self, record: &ApiKeyRecord) -> Result<()> {
+        self.store.insert(Self::record_key(&record.key_hash), encode(record)?).map_err(db_error)?;
+        self.store.insert(Self::id_key(&record.id), record.key_hash.as_bytes()).map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 原子地修改一条记录（比较并交换，并发修改时重试），记录不存在时返回 None
+    fn update(&self, key_hash: &str, mut f: impl FnMut(&mut ApiKeyRecord)) -> Result<Option<ApiKeyRecord>> {
+        let updated = self.store
+            .update_and_fetch(Self::record_key(key_hash), |old| {
+                let old = old?;
+                // 无法解码或编码时保留原值，避免删除记录
+                let Some(mut record) = decode(old) else {
+                    return Some(old.to_vec());
+                };
+                f(&mut record);
+                Some(encode(&record).unwrap_or_else(|_| old.to_vec()))
+            })
+            .map_err(db_error)?;
+        Ok(updated.and_then(|bytes| decode(&bytes)))
+    }
+
+    fn generate_key() -> String {
+        format!(
+            "{}{}{}",
+            API_KEY_TOKEN_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        )
+    }
+
+    fn insert(&self, id: String, name: String, rate_limit: Option<ApiKeyRateLimit>) -> Result<(ApiKeyRecord, String)> {
+        let key = Self::generate_key();
+        let record = ApiKeyRecord {
+            id,
+            name,
+            key_prefix: key[..API_KEY_TOKEN_PREFIX.len() + 8].to_string(),
+            key_hash: hash_api_key(&key),
+            created_at: Utc::now(),
+            revoked_at: None,
+            rate_limit,
+            total_requests: 0,
+            last_used_at: None,
+        };
+        self.save(&record)?;
+        Ok((record, key))
+    }
+
+    /// 创建新密钥，返回记录和明文（明文只在此时可见）
+    pub fn create(&self, name: impl Into<String>, rate_limit: Option<ApiKeyRateLimit>) -> Result<(ApiKeyRecord, String)> {
+        self.insert(Uuid::new_v4().simple().to_string(), name.into(), rate_limit)
+    }
+
+    /// 按明文查找有效密钥
+    pub fn verify(&self, key: &str) -> Result<Option<ApiKeyRecord>> {
+        Ok(self.load(&hash_api_key(key))?.filter(ApiKeyRecord::is_active))
+    }
+
+    /// 记录一次使用（原子递增，并发请求不会丢失计数）
+    pub fn record_usage(&self, key_hash: &str) -> Result<()> {
+        self.update(key_hash, |record| {
+            record.total_requests += 1;
+            record.last_used_at = Some(Utc::now());
+        })?;
+        Ok(())
+    }
+
+    /// 列出所有密钥（按创建时间排序）
+    pub fn list(&self) -> Result<Vec<ApiKeyRecord>> {
+        let mut records = Vec::new();
+        for item in self.store.scan_prefix(API_KEY_PREFIX).values() {
+            if let Some(record) = decode(&item.map_err(db_error)?) {
+                records.push(record);
+            }
+        }
+        records.sort_by_key(|r| r.created_at);
+        Ok(records)
+    }
+
+    /// 按 ID 查找密钥
+    pub fn find(&self, id: &str) -> Result<Option<ApiKeyRecord>> {
+        match self.store.get(Self::id_key(id)).map_err(db_error)? {
+            Some(hash) => self.load(&String::from_utf8_lossy(&hash)),
+            None => Ok(None),
+        }
+    }
+
+    /// 吊销密钥，返回密钥是否存在且原本有效
+    pub fn revoke(&self, id: &str) -> Result<bool> {
+        let Some(record) = self.find(id)? else {
+            return Ok(false);
+        };
+        let mut revoked = false;
+        self.update(&record.key_hash, |record| {
+            revoked = record.is_active();
+            if revoked {
+                record.revoked_at = Some(Utc::now());
+            }
+        })?;
+        Ok(revoked)
+    }
+
+    /// 轮换密钥：旧密钥立即失效，并以相同 ID、名称和限流配置生成新密钥
+    pub fn rotate(&self, id: &str) -> Result<Option<(ApiKeyRecord, String)>> {
+        let Some(old) = self.find(id)?.filter(ApiKeyRecord::is_active) else {
+            return Ok(None);
+        };
+        // 旧记录删除而不是标记吊销，避免同一 ID 对应多条记录
+        self.store.remove(Self::record_key(&old.key_hash)).map_err(db_error)?;
+        let (mut record, key) = self.insert(old.id, old.name, old.rate_limit)?;
+        record.total_requests = old.total_requests;
+        record.last_used_at = old.last_used_at;
+        self.save(&record)?;
+        Ok(Some((record, key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> ApiKeyStore {
+        ApiKeyStore::new(CacheManager::temporary())
+    }
+
+    #[test]
+    fn test_api_key_create_and_verify() {
+        let store = store();
+        let limit = ApiKeyRateLimit { requests_per_second: 5, burst_size: 10 };
+        let (record, key) = store.create("test", Some(limit)).unwrap();
+
+        assert!(key.starts_with(API_KEY_TOKEN_PREFIX));
+        assert!(key.starts_with(&record.key_prefix));
+        // 只存储哈希
+        assert_ne!(record.key_hash, key);
+        assert_eq!(record.key_hash, hash_api_key(&key));
+
+        let found = store.verify(&key).unwrap().unwrap();
+        assert_eq!(found.id, record.id);
+        assert_eq!(found.rate_limit, Some(limit));
+        assert!(store.verify("sk_unknown").unwrap().is_none());
+
+        store.record_usage(&record.key_hash).unwrap();
+        assert_eq!(store.verify(&key).unwrap().unwrap().total_requests, 1);
+    }
+
+    #[test]
+    fn test_api_key_revoke_and_rotate() {
+        let store = store();
+        let (record, key) = store.create("rotate", None).unwrap();
+
+        let (rotated, new_key) = store.rotate(&record.id).unwrap().unwrap();
+        assert_eq!(rotated.id, record.id);
+        assert_ne!(new_key, key);
+        assert!(store.verify(&key).unwrap().is_none());
+        assert!(store.verify(&new_key).unwrap().is_some());
+
+        assert!(store.revoke(&record.id).unwrap());
+        assert!(!store.revoke(&record.id).unwrap());
+        assert!(store.verify(&new_key).unwrap().is_none());
+        assert!(store.rotate(&record.id).unwrap().is_none());
+        assert!(store.find(&record.id).unwrap().unwrap().revoked_at.is_some());
+    }
+
+    #[test]
+    fn test_api_key_survives_cache_clear_and_counts_concurrent_usage() {
+        let manager = CacheManager::temporary();
+        let store = Arc::new(ApiKeyStore::new(manager.clone()));
+        let (record, key) = store.create("usage", None).unwrap();
+        manager.clear().unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let store = store.clone();
+                let hash = record.key_hash.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        store.record_usage(&hash).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(store.verify(&key).unwrap().unwrap().total_requests, 200);
+    }
+}
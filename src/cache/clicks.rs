@@ -0,0 +1,295 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 点击统计缓存
+//!
+//! 在本地记录各引擎结果的展示次数和点击次数（不会发送给上游引擎），
+//! 并据此计算聚合时使用的引擎权重。默认关闭。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// 点击统计键前缀
+const CLICK_STATS_PREFIX: &str = "click_stats:";
+
+/// 点击追踪配置
+#[derive(Debug, Clone)]
+pub struct ClickTrackingConfig {
+    /// 是否启用（默认关闭）
+    pub enabled: bool,
+    /// 跳转链接签名密钥
+    pub secret: String,
+    /// 统计保留时间（秒）
+    pub retention_secs: u64,
+    /// 参与权重计算所需的最少展示次数
+    pub min_impressions: u64,
+    /// 引擎权重的最大调整幅度（0.2 表示权重范围 0.8 ~ 1.2）
+    pub max_weight_adjustment: f64,
+}
+
+impl Default for ClickTrackingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secret: format!("click_tracking_secret_{}", Uuid::new_v4()),
+            retention_secs: 90 * 24 * 3600, // 90 天
+            min_impressions: 50,
+            max_weight_adjustment: 0.2,
+        }
+    }
+}
+
+/// 单个引擎的点击统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineClickStats {
+    /// 展示次数
+    pub impressions: u64,
+    /// 点击次数
+    pub clicks: u64,
+    /// 各结果位置的点击次数（位置从 0 开始）
+    pub clicks_by_position: BTreeMap<usize, u64>,
+}
+
+impl EngineClickStats {
+    /// 点击率
+    pub fn click_through_rate(&self) -> f64 {
+        if self.impressions == 0 {
+            0.0
+        } else {
+            self.clicks as f64 / self.impressions as f64
+        }
+    }
+}
+
+/// 点击统计缓存
+///
+/// 封装 CacheManager，按引擎存储展示和点击统计
+pub struct ClickStatsCache {
+    manager: Arc<CacheManager>,
+    config: ClickTrackingConfig,
+}
+
+impl ClickStatsCache {
+    /// 创建点击统计缓存实例
+    pub fn new(manager: Arc<CacheManager>, config: ClickTrackingConfig) -> Self {
+        Self { manager, config }
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &ClickTrackingConfig {
+        &self.config
+    }
+
+    fn stats_key(engine: &str) -> String {
+        format!("{}{}", CLICK_STATS_PREFIX, engine)
+    }
+
+    /// 获取单个引擎的统计
+    pub fn get(&self, engine: &str) -> Result<EngineClickStats> {
+        Ok(self.manager.get(&Self::stats_key(engine))?
+            .and_then(|bytes| bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok())
+            .map(|(stats, _)| stats)
+            .unwrap_or_default())
+    }
+
+    /// 原子地修改单个引擎的统计，并发记录时不会丢失计数
+    fn update(&self, engine: &str, f: impl Fn(&mut EngineClickStats)) -> Result<()> {
+        let error = std::cell::Cell::new(None);
+        self.manager.update_and_fetch(
+            &Self::stats_key(engine),
+            Some(Duration::from_secs(self.config.retention_secs)),
+            |old| {
+                let mut stats: EngineClickStats = old
+                    .and_then(|bytes| bincode::serde::decode_from_slice(bytes, bincode::config::standard()).ok())
+                    .map(|(stats, _)| stats)
+                    .unwrap_or_default();
+                f(&mut stats);
+                match bincode::serde::encode_to_vec(&stats, bincode::config::standard()) {
+                    Ok(bytes) => Some(bytes),
+                    Err(e) => {
+                        error.set(Some(CacheError::SerializationError(format!("Failed to serialize click stats: {}", e))));
+                        old.map(<[u8]>::to_vec)
+                    }
+                }
+            },
+        )?;
+        error.into_inner().map_or(Ok(()), Err)
+    }
+
+    /// 记录各引擎的展示次数（未启用时忽略）
+    pub fn record_impressions(&self, impressions: &HashMap<String, u64>) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        for (engine, count) in impressions {
+            self.update(engine, |stats| stats.impressions += count)?;
+        }
+        Ok(())
+    }
+
+    /// 记录一次点击（未启用时忽略）
+    pub fn record_click(&self, engine: &str, position: usize) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        self.update(engine, |stats| {
+            stats.clicks += 1;
+            *stats.clicks_by_position.entry(position).or_insert(0) += 1;
+        })
+    }
+
+    /// 获取所有引擎的统计
+    pub fn all_stats(&self) -> Result<HashMap<String, EngineClickStats>> {
+        let mut all = HashMap::new();
+        for item in self.manager.scan_prefix(CLICK_STATS_PREFIX).keys() {
+            let key = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+            let key = String::from_utf8_lossy(&key).into_owned();
+            if let Some(engine) = key.strip_prefix(CLICK_STATS_PREFIX) {
+                all.insert(engine.to_string(), self.get(engine)?);
+            }
+        }
+        Ok(all)
+    }
+
+    /// 根据点击率计算引擎权重
+    ///
+    /// 权重为 `1 + max_weight_adjustment * clamp((引擎点击率 - 全局点击率) / 全局点击率, -1, 1)`，
+    /// 展示次数不足 `min_impressions` 的引擎不参与调整
+    pub fn engine_weights(&self) -> Result<HashMap<String, f64>> {
+        if !self.config.enabled {
+            return Ok(HashMap::new());
+        }
+        Ok(compute_engine_weights(
+            &self.all_stats()?,
+            self.config.min_impressions,
+            self.config.max_weight_adjustment,
+        ))
+    }
+}
+
+/// 由点击统计计算引擎权重
+fn compute_engine_weights(
+    stats: &HashMap<String, EngineClickStats>,
+    min_impressions: u64,
+    max_adjustment: f64,
+) -> HashMap<String, f64> {
+    let qualified: Vec<_> = stats.iter()
+        .filter(|(_, s)| s.impressions >= min_impressions.max(1))
+        .collect();
+
+    let impressions: u64 = qualified.iter().map(|(_, s)| s.impressions).sum();
+    let clicks: u64 = qualified.iter().map(|(_, s)| s.clicks).sum();
+    if impressions == 0 || clicks == 0 {
+        return HashMap::new();
+    }
+    let global_ctr = clicks as f64 / impressions as f64;
+
+    qualified.into_iter()
+        .map(|(engine, s)| {
+            let relative = ((s.click_through_rate() - global_ctr) / global_ctr).clamp(-1.0, 1.0);
+            (engine.clone(), 1.0 + max_adjustment * relative)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(impressions: u64, clicks: u64) -> EngineClickStats {
+        EngineClickStats { impressions, clicks, ..Default::default() }
+    }
+
+    #[test]
+    fn test_compute_engine_weights() {
+        let all = HashMap::from([
+            ("good".to_string(), stats(100, 30)),
+            ("bad".to_string(), stats(100, 10)),
+            ("new".to_string(), stats(5, 5)),
+        ]);
+        let weights = compute_engine_weights(&all, 50, 0.2);
+
+        assert!(weights["good"] > 1.0 && weights["good"] <= 1.2);
+        assert!(weights["bad"] < 1.0 && weights["bad"] >= 0.8);
+        // 展示次数不足的引擎不参与调整
+        assert!(!weights.contains_key("new"));
+
+        assert!(compute_engine_weights(&HashMap::from([("a".to_string(), stats(100, 0))]), 50, 0.2).is_empty());
+    }
+
+    #[test]
+    fn test_click_stats_disabled_by_default() {
+        let manager = CacheManager::temporary();
+        let cache = ClickStatsCache::new(manager, ClickTrackingConfig::default());
+        let engine = format!("test_engine_{}", Uuid::new_v4().simple());
+
+        cache.record_click(&engine, 0).unwrap();
+        assert_eq!(cache.get(&engine).unwrap().clicks, 0);
+        assert!(cache.engine_weights().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_click_stats_record() {
+        let manager = CacheManager::temporary();
+        let config = ClickTrackingConfig { enabled: true, ..Default::default() };
+        let cache = ClickStatsCache::new(manager, config);
+        let engine = format!("test_engine_{}", Uuid::new_v4().simple());
+
+        cache.record_impressions(&HashMap::from([(engine.clone(), 10)])).unwrap();
+        cache.record_click(&engine, 2).unwrap();
+        cache.record_click(&engine, 2).unwrap();
+
+        let stats = cache.get(&engine).unwrap();
+        assert_eq!(stats.impressions, 10);
+        assert_eq!(stats.clicks, 2);
+        assert_eq!(stats.clicks_by_position.get(&2), Some(&2));
+        assert!((stats.click_through_rate() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_click_stats_concurrent_clicks() {
+        let manager = CacheManager::temporary();
+        let config = ClickTrackingConfig { enabled: true, ..Default::default() };
+        let cache = Arc::new(ClickStatsCache::new(manager, config));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..50 {
+                        cache.record_click("engine", 0).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let stats = cache.get("engine").unwrap();
+        assert_eq!(stats.clicks, 400);
+        assert_eq!(stats.clicks_by_position.get(&0), Some(&400));
+    }
+}
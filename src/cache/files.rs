@@ -0,0 +1,243 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 本地文件索引
+//!
+//! 保存本地文件引擎抽取出的文件名和正文，键为 `file:<绝对路径>`，
+//! 同一目录下的文件共享键前缀，可以按目录扫描。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// 文件索引键前缀
+const FILE_KEY_PREFIX: &str = "file:";
+
+/// 索引条目的保留时间，过期后下次重建索引时重新抽取
+const FILE_INDEX_TTL: Duration = Duration::from_secs(365 * 24 * 3600);
+
+/// 摘要窗口（字符数）
+const SNIPPET_CHARS: usize = 160;
+
+/// 已索引的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// 绝对路径
+    pub path: String,
+    /// 文件名
+    pub name: String,
+    /// 小写扩展名（不含 `.`）
+    pub extension: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 修改时间（Unix 时间戳，秒）
+    pub modified: u64,
+    /// 抽取出的正文（可能被截断）
+    pub text: String,
+}
+
+/// 文件匹配结果
+#[derive(Debug, Clone)]
+pub struct FileMatch {
+    /// 命中的文件
+    pub file: IndexedFile,
+    /// 相关度（文件名命中权重高于正文命中）
+    pub score: f64,
+    /// 第一个命中关键词附近的正文摘要
+    pub snippet: String,
+}
+
+/// 本地文件索引
+///
+/// 封装 CacheManager，提供文件条目的增删查和关键词检索
+pub struct FileIndexCache {
+    manager: Arc<CacheManager>,
+}
+
+impl FileIndexCache {
+    /// 创建文件索引实例
+    pub fn new(manager: Arc<CacheManager>) -> Self {
+        Self { manager }
+    }
+
+    /// 生成文件索引键
+    pub fn generate_key(path: &str) -> String {
+        format!("{}{}", FILE_KEY_PREFIX, path)
+    }
+
+    /// 写入或更新文件条目
+    pub fn upsert(&self, file: &IndexedFile) -> Result<()> {
+        let bytes = bincode::serde::encode_to_vec(file, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize file entry: {}", e)))?;
+        self.manager.set(Self::generate_key(&file.path), bytes, Some(FILE_INDEX_TTL))
+    }
+
+    /// 读取文件条目（已过期的条目视为不存在）
+    pub fn get(&self, path: &str) -> Result<Option<IndexedFile>> {
+        Ok(self.manager.get(&Self::generate_key(path))?.and_then(|bytes| {
+            bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
+                .ok()
+                .map(|(file, _)| file)
+        }))
+    }
+
+    /// 删除文件条目
+    pub fn remove(&self, path: &str) -> Result<bool> {
+        self.manager.delete(&Self::generate_key(path))
+    }
+
+    /// 列出路径以 `dir` 开头的所有已索引路径
+    pub fn paths_under(&self, dir: &str) -> Result<Vec<String>> {
+        self.manager
+            .scan_prefix(&Self::generate_key(dir))
+            .keys()
+            .map(|key| {
+                key.map(|k| String::from_utf8_lossy(&k[FILE_KEY_PREFIX.len()..]).into_owned())
+                    .map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))
+            })
+            .collect()
+    }
+
+    /// 在 `dir` 下的文件中检索关键词
+    ///
+    /// 所有关键词都需命中（文件名、路径或正文，不区分大小写）；结果按相关度降序、
+    /// 修改时间降序排列
+    pub fn search(&self, dir: &str, keywords: &[String], max_results: usize) -> Result<Vec<FileMatch>> {
+        let keywords: Vec<String> = keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut matches = Vec::new();
+        for item in self.manager.scan_prefix(&Self::generate_key(dir)) {
+            let (key, value) = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+            if self.manager.get_metadata(&String::from_utf8_lossy(&key))?.is_some_and(|m| m.is_expired()) {
+                continue;
+            }
+            let Ok((file, _)) = bincode::serde::decode_from_slice::<IndexedFile, _>(&value, bincode::config::standard()) else {
+                continue; // 跳过损坏的数据
+            };
+
+            let name = file.name.to_lowercase();
+            let path = file.path.to_lowercase();
+            let text = file.text.to_lowercase();
+            let mut score = 0.0;
+            let mut all_matched = true;
+            for keyword in &keywords {
+                let in_name = name.contains(keyword.as_str());
+                let hits = text.matches(keyword.as_str()).count();
+                if !in_name && hits == 0 && !path.contains(keyword.as_str()) {
+                    all_matched = false;
+                    break;
+                }
+                score += if in_name { 2.0 } else { 0.0 } + (1.0 + hits as f64).ln();
+            }
+            if !all_matched {
+                continue;
+            }
+
+            let snippet = snippet(&file.text, &keywords);
+            matches.push(FileMatch { file, score, snippet });
+        }
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.file.modified.cmp(&a.file.modified)));
+        matches.truncate(max_results);
+        Ok(matches)
+    }
+}
+
+/// 截取第一个命中关键词附近的正文
+fn snippet(text: &str, keywords: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+    // 大小写转换改变长度时（极少见）退化为从头截取
+    let position = if lower.len() == chars.len() {
+        keywords.iter().filter_map(|keyword| {
+            let keyword: Vec<char> = keyword.chars().collect();
+            lower.windows(keyword.len()).position(|w| w == keyword.as_slice())
+        }).min()
+    } else {
+        None
+    };
+
+    let start = position.map(|p| p.saturating_sub(SNIPPET_CHARS / 4)).unwrap_or(0);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let body: String = chars[start..end].iter().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&body);
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(dir: &str, name: &str, text: &str, modified: u64) -> IndexedFile {
+        IndexedFile {
+            path: format!("{}/{}", dir, name),
+            name: name.to_string(),
+            extension: name.rsplit('.').next().unwrap_or_default().to_string(),
+            size: text.len() as u64,
+            modified,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_file_index_search_and_remove() {
+        let manager = CacheManager::temporary();
+        let index = FileIndexCache::new(manager);
+        let dir = format!("/tmp/seesea_test_{}", uuid::Uuid::new_v4().simple());
+
+        index.upsert(&file(&dir, "rust-notes.md", "所有权与借用。Rust 的生命周期标注。", 10)).unwrap();
+        index.upsert(&file(&dir, "todo.txt", "学习 rust 异步编程", 20)).unwrap();
+        index.upsert(&file(&dir, "python.txt", "asyncio 笔记", 30)).unwrap();
+
+        let keywords = vec!["Rust".to_string()];
+        let matches = index.search(&dir, &keywords, 10).unwrap();
+        let names: Vec<&str> = matches.iter().map(|m| m.file.name.as_str()).collect();
+        assert_eq!(names, ["rust-notes.md", "todo.txt"]);
+        assert_eq!(matches[1].snippet, "学习 rust 异步编程");
+
+        // 所有关键词都需命中
+        let keywords = vec!["rust".to_string(), "异步".to_string()];
+        assert_eq!(index.search(&dir, &keywords, 10).unwrap().len(), 1);
+
+        assert_eq!(index.paths_under(&dir).unwrap().len(), 3);
+        assert!(index.remove(&format!("{}/todo.txt", dir)).unwrap());
+        assert_eq!(index.get(&format!("{}/todo.txt", dir)).unwrap(), None);
+        assert_eq!(index.paths_under(&dir).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_snippet_window() {
+        let text = format!("{}needle{}", "a ".repeat(100), " b".repeat(100));
+        let snippet = snippet(&text, &["needle".to_string()]);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+    }
+}
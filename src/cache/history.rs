@@ -0,0 +1,375 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 搜索历史缓存
+//!
+//! 在本地 sled 存储中按作用域记录搜索历史和点击过的域名。
+//! 默认关闭，需要显式启用；每个作用域（本地用户或魔法链接令牌绑定的用户）
+//! 的历史互相隔离。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use crate::derive::SearchResultItem;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// 搜索历史键前缀
+const HISTORY_KEY_PREFIX: &str = "history:";
+/// 点击域名键前缀
+const HISTORY_CLICK_PREFIX: &str = "history_click:";
+
+/// 作用域最大长度
+const MAX_SCOPE_LEN: usize = 64;
+
+/// 搜索历史配置
+#[derive(Debug, Clone)]
+pub struct HistoryConfig {
+    /// 是否启用（默认关闭）
+    pub enabled: bool,
+    /// 每个作用域保留的最大条目数
+    pub max_entries: usize,
+    /// 历史保留时间（秒）
+    pub retention_secs: u64,
+    /// 点击过的域名的加分系数
+    pub click_boost: f64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 500,
+            retention_secs: 30 * 24 * 3600, // 30 天
+            click_boost: 0.1,
+        }
+    }
+}
+
+/// 历史作用域
+///
+/// 用于隔离不同用户的历史记录，只允许字母、数字、`-` 和 `_`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HistoryScope(String);
+
+impl HistoryScope {
+    /// 创建作用域，名称非法时返回 `None`
+    pub fn new(name: impl Into<String>) -> Option<Self> {
+        let name = name.into();
+        let valid = !name.is_empty()
+            && name.len() <= MAX_SCOPE_LEN
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        valid.then_some(Self(name))
+    }
+
+    /// 本地（内网）作用域
+    pub fn local() -> Self {
+        Self("local".to_string())
+    }
+
+    /// 作用域名称
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// 搜索历史条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    /// 查询字符串
+    pub query: String,
+    /// 搜索时间（Unix 时间戳，秒）
+    pub timestamp: u64,
+    /// 结果数量
+    pub result_count: usize,
+}
+
+/// 搜索历史缓存
+///
+/// 封装 CacheManager，提供按作用域隔离的搜索历史和点击记录
+pub struct HistoryCache {
+    manager: Arc<CacheManager>,
+    config: HistoryConfig,
+}
+
+impl HistoryCache {
+    /// 创建搜索历史缓存实例
+    pub fn new(manager: Arc<CacheManager>, config: HistoryConfig) -> Self {
+        Self { manager, config }
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &HistoryConfig {
+        &self.config
+    }
+
+    /// 生成作用域历史键前缀
+    fn scope_prefix(scope: &HistoryScope) -> String {
+        format!("{}{}:", HISTORY_KEY_PREFIX, scope.as_str())
+    }
+
+    /// 生成点击记录键
+    fn click_key(scope: &HistoryScope, domain: &str) -> String {
+        format!("{}{}:{}", HISTORY_CLICK_PREFIX, scope.as_str(), domain)
+    }
+
+    fn retention(&self) -> Option<Duration> {
+        Some(Duration::from_secs(self.config.retention_secs))
+    }
+
+    /// 记录一次搜索（未启用时忽略）
+    pub fn record_search(&self, scope: &HistoryScope, query: &str, result_count: usize) -> Result<()> {
+        if !self.config.enabled || query.trim().is_empty() {
+            return Ok(());
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::from_secs(0));
+        let entry = SearchHistoryEntry {
+            query: query.trim().to_string(),
+            timestamp: now.as_secs(),
+            result_count,
+        };
+
+        // 以纳秒时间戳作为键后缀，保证按时间有序
+        let key = format!("{}{:020}", Self::scope_prefix(scope), now.as_nanos() as u64);
+        let bytes = bincode::serde::encode_to_vec(&entry, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize history: {}", e)))?;
+        self.manager.set(key, bytes, self.retention())?;
+
+        self.trim(scope)
+    }
+
+    /// 删除超出上限的最旧条目
+    fn trim(&self, scope: &HistoryScope) -> Result<()> {
+        let keys = self.scope_keys(scope)?;
+        if keys.len() > self.config.max_entries {
+            for key in &keys[..keys.len() - self.config.max_entries] {
+                self.manager.delete(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 作用域下的所有历史键（按时间升序）
+    fn scope_keys(&self, scope: &HistoryScope) -> Result<Vec<String>> {
+        self.manager
+            .scan_prefix(&Self::scope_prefix(scope))
+            .keys()
+            .map(|key| {
+                key.map(|k| String::from_utf8_lossy(&k).into_owned())
+                    .map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))
+            })
+            .collect()
+    }
+
+    /// 列出搜索历史（最新的在前）
+    pub fn list(&self, scope: &HistoryScope, limit: usize) -> Result<Vec<SearchHistoryEntry>> {
+        let mut entries = Vec::new();
+
+        for key in self.scope_keys(scope)?.iter().rev() {
+            if entries.len() >= limit {
+                break;
+            }
+            // get 会跳过已过期的条目
+            if let Some(bytes) = self.manager.get(key)?
+                && let Ok((entry, _)) = bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
+            {
+                entries.push(entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// 清空作用域下的搜索历史和点击记录，返回删除的条目数
+    pub fn clear(&self, scope: &HistoryScope) -> Result<usize> {
+        let mut keys = self.scope_keys(scope)?;
+        for item in self.manager.scan_prefix(&format!("{}{}:", HISTORY_CLICK_PREFIX, scope.as_str())).keys() {
+            let key = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+            keys.push(String::from_utf8_lossy(&key).into_owned());
+        }
+
+        let mut count = 0;
+        for key in &keys {
+            if self.manager.delete(key)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// 记录一次结果点击（未启用时忽略）
+    pub fn record_click(&self, scope: &HistoryScope, url: &str) -> Result<()> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let Some(domain) = extract_domain(url) else {
+            return Ok(());
+        };
+
+        let key = Self::click_key(scope, &domain);
+        let count = self.click_count(&key)? + 1;
+        let bytes = bincode::serde::encode_to_vec(count, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize click: {}", e)))?;
+        self.manager.set(key, bytes, self.retention())
+    }
+
+    fn click_count(&self, key: &str) -> Result<u64> {
+        Ok(self.manager.get(key)?
+            .and_then(|bytes| bincode::serde::decode_from_slice::<u64, _>(&bytes, bincode::config::standard()).ok())
+            .map(|(count, _)| count)
+            .unwrap_or(0))
+    }
+
+    /// 获取作用域下点击过的域名及点击次数
+    pub fn clicked_domains(&self, scope: &HistoryScope) -> Result<HashMap<String, u64>> {
+        let prefix = format!("{}{}:", HISTORY_CLICK_PREFIX, scope.as_str());
+        let mut domains = HashMap::new();
+
+        for item in self.manager.scan_prefix(&prefix).keys() {
+            let key = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+            let key = String::from_utf8_lossy(&key).into_owned();
+            let count = self.click_count(&key)?;
+            if count > 0
+                && let Some(domain) = key.strip_prefix(&prefix)
+            {
+                domains.insert(domain.to_string(), count);
+            }
+        }
+
+        Ok(domains)
+    }
+
+    /// 为点击过的域名的结果加分
+    ///
+    /// 加分为 `click_boost * ln(1 + 点击次数)`，返回被加分的结果数
+    pub fn boost_clicked_domains(&self, scope: &HistoryScope, items: &mut [SearchResultItem]) -> Result<usize> {
+        if !self.config.enabled {
+            return Ok(0);
+        }
+        let domains = self.clicked_domains(scope)?;
+        if domains.is_empty() {
+            return Ok(0);
+        }
+
+        let mut boosted = 0;
+        for item in items.iter_mut() {
+            if let Some(count) = extract_domain(&item.url).and_then(|d| domains.get(&d)) {
+                item.score += self.config.click_boost * (1.0 + *count as f64).ln();
+                boosted += 1;
+            }
+        }
+        Ok(boosted)
+    }
+}
+
+/// 提取 URL 的域名（去掉 `www.` 前缀）
+fn extract_domain(url: &str) -> Option<String> {
+    crate::url_utils::domain(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(enabled: bool) -> HistoryCache {
+        let manager = CacheManager::temporary();
+        HistoryCache::new(manager, HistoryConfig { enabled, max_entries: 3, ..Default::default() })
+    }
+
+    fn unique_scope() -> HistoryScope {
+        HistoryScope::new(format!("test_{}", uuid::Uuid::new_v4().simple())).unwrap()
+    }
+
+    #[test]
+    fn test_history_scope_validation() {
+        assert!(HistoryScope::new("alice_01").is_some());
+        assert!(HistoryScope::new("").is_none());
+        assert!(HistoryScope::new("a:b").is_none());
+        assert!(HistoryScope::new("x".repeat(65)).is_none());
+        assert_eq!(HistoryScope::local().as_str(), "local");
+    }
+
+    #[test]
+    fn test_history_disabled_by_default() {
+        let cache = history(false);
+        let scope = unique_scope();
+        cache.record_search(&scope, "rust", 10).unwrap();
+        assert!(!HistoryConfig::default().enabled);
+        assert!(cache.list(&scope, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_history_record_list_and_clear() {
+        let cache = history(true);
+        let scope = unique_scope();
+        let other = unique_scope();
+
+        for q in ["a", "b", "c", "d"] {
+            cache.record_search(&scope, q, 1).unwrap();
+        }
+        cache.record_search(&other, "other", 1).unwrap();
+
+        // 超出上限的最旧条目被删除，最新的在前
+        let entries = cache.list(&scope, 10).unwrap();
+        let queries: Vec<_> = entries.iter().map(|e| e.query.as_str()).collect();
+        assert_eq!(queries, vec!["d", "c", "b"]);
+        assert_eq!(cache.list(&scope, 1).unwrap().len(), 1);
+
+        assert_eq!(cache.clear(&scope).unwrap(), 3);
+        assert!(cache.list(&scope, 10).unwrap().is_empty());
+        assert_eq!(cache.list(&other, 10).unwrap().len(), 1);
+        cache.clear(&other).unwrap();
+    }
+
+    #[test]
+    fn test_boost_clicked_domains() {
+        let cache = history(true);
+        let scope = unique_scope();
+        cache.record_click(&scope, "https://www.rust-lang.org/learn").unwrap();
+        cache.record_click(&scope, "https://rust-lang.org/").unwrap();
+
+        let domains = cache.clicked_domains(&scope).unwrap();
+        assert_eq!(domains.get("rust-lang.org"), Some(&2));
+
+        let make = |url: &str| SearchResultItem {
+            title: String::new(),
+            url: url.to_string(),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score: 1.0,
+            result_type: crate::derive::ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        };
+        let mut items = vec![make("https://rust-lang.org/tools"), make("https://example.com/")];
+        assert_eq!(cache.boost_clicked_domains(&scope, &mut items).unwrap(), 1);
+        assert!(items[0].score > items[1].score);
+
+        cache.clear(&scope).unwrap();
+        assert!(cache.clicked_domains(&scope).unwrap().is_empty());
+    }
+}
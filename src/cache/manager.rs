@@ -71,6 +71,21 @@ pub type Result<T> = std::result::Result<T, CacheError>;
 /// 5. 无需手动管理内存（没有unsafe代码）
 static GLOBAL_CACHE_MANAGER: Lazy<Mutex<Option<Arc<CacheManager>>>> = Lazy::new(|| Mutex::new(None));
 
+/// 根据键前缀得到缓存类型（用作指标标签）
+///
+/// 各子缓存使用 `前缀:` 形式的键，无法识别的键归为 `other`，避免标签基数失控
+fn cache_kind(key: &str) -> &'static str {
+    const KINDS: &[&str] = &[
+        "result", "metadata", "engine_info", "rss", "rss_meta", "semantic", "qvec",
+        "history", "history_click", "click_stats", "file",
+        "webhook_delivery", "webhook_seen", "alert_result", "alert_seen",
+    ];
+    key.split_once(':')
+        .and_then(|(prefix, _)| KINDS.iter().find(|kind| **kind == prefix))
+        .copied()
+        .unwrap_or("other")
+}
+
 /// 缓存管理器
 ///
 /// 基于 sled 实现的高性能缓存管理器（单例模式）
@@ -79,6 +94,8 @@ pub struct CacheManager {
     db: Db,
     /// 元数据树
     metadata_tree: sled::Tree,
+    /// 持久化存储树（不属于缓存，见 [`CacheManager::store`]）
+    store_tree: sled::Tree,
     /// 配置
     config: CacheImplConfig,
     /// 统计信息
@@ -134,6 +151,30 @@ impl CacheManager {
 
     /// 创建新的缓存管理器（内部方法）
     fn create_internal(config: CacheImplConfig) -> Result<Self> {
+        Self::open(config, false)
+    }
+
+    /// 创建独立于全局单例的临时缓存管理器
+    ///
+    /// 数据库位于系统临时目录，实例释放后自动删除，供测试隔离使用
+    #[cfg(test)]
+    pub(crate) fn temporary() -> Arc<Self> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let db_path = std::env::temp_dir().join(format!(
+            "seesea_test_cache_{}_{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let config = CacheImplConfig {
+            db_path: db_path.to_string_lossy().to_string(),
+            ..CacheImplConfig::default()
+        };
+        Arc::new(Self::open(config, true).expect("Failed to create temporary cache manager"))
+    }
+
+    /// 按配置打开 sled 数据库，`temporary` 为真时关闭后删除数据文件
+    fn open(config: CacheImplConfig, temporary: bool) -> Result<Self> {
         // 创建数据库目录
         if let Some(parent) = Path::new(&config.db_path).parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -155,7 +196,8 @@ impl CacheManager {
                 .path(&config.db_path)
                 .cache_capacity(1024 * 1024 * 16) // 16MB 缓存
                 .flush_every_ms(Some(10000)), // 10秒刷新
-        };
+        }
+        .temporary(temporary);
 
         let db = db_config.open().map_err(|e| {
             CacheError::DatabaseError(format!("打开数据库失败: {}", e))
@@ -165,9 +207,14 @@ impl CacheManager {
             CacheError::DatabaseError(format!("打开元数据树失败: {}", e))
         })?;
 
+        let store_tree = db.open_tree("store").map_err(|e| {
+            CacheError::DatabaseError(format!("打开存储树失败: {}", e))
+        })?;
+
         Ok(Self {
             db,
             metadata_tree,
+            store_tree,
             config,
             stats: Arc::new(CacheStats::default()),
             hits: Arc::new(AtomicU64::new(0)),
@@ -194,6 +241,8 @@ impl CacheManager {
     ///
     /// 返回缓存值，如果不存在或已过期则返回 None
     pub fn get(&self, key: &str) -> Result<Option<CacheValue>> {
+        let _span = tracing::debug_span!("cache.lookup", cache = cache_kind(key)).entered();
+
         if !self.config.enabled {
             return Err(CacheError::CacheDisabled);
         }
@@ -202,14 +251,14 @@ impl CacheManager {
         let metadata = match self.get_metadata(key)? {
             Some(meta) => meta,
             None => {
-                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.record_miss(key);
                 return Ok(None);
             }
         };
 
         // 检查是否过期
         if metadata.is_expired() {
-            self.misses.fetch_add(1, Ordering::Relaxed);
+            self.record_miss(key);
             // 不删除过期条目，保持持久化存储
             // 
             // 设计理念：
@@ -229,13 +278,13 @@ impl CacheManager {
 
         match value {
             Some(v) => {
-                self.hits.fetch_add(1, Ordering::Relaxed);
+                self.record_hit(key);
                 // 更新元数据访问信息（异步，不阻塞读取）
                 let _ = self.update_metadata_access(key);
                 Ok(Some(v.to_vec()))
             }
             None => {
-                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.record_miss(key);
                 Ok(None)
             }
         }
@@ -251,6 +300,8 @@ impl CacheManager {
     ///
     /// 返回缓存值和是否过期的标志，如果不存在则返回 None
     pub fn get_include_stale(&self, key: &str) -> Result<Option<(CacheValue, bool)>> {
+        let _span = tracing::debug_span!("cache.lookup", cache = cache_kind(key)).entered();
+
         if !self.config.enabled {
             return Err(CacheError::CacheDisabled);
         }
@@ -259,7 +310,7 @@ impl CacheManager {
         let metadata = match self.get_metadata(key)? {
             Some(meta) => meta,
             None => {
-                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.record_miss(key);
                 return Ok(None);
             }
         };
@@ -275,16 +326,16 @@ impl CacheManager {
         match value {
             Some(v) => {
                 if is_stale {
-                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    self.record_miss(key);
                 } else {
-                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    self.record_hit(key);
                 }
                 // 更新元数据访问信息（异步，不阻塞读取）
                 let _ = self.update_metadata_access(key);
                 Ok(Some((v.to_vec(), is_stale)))
             }
             None => {
-                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.record_miss(key);
                 Ok(None)
             }
         }
@@ -333,6 +384,70 @@ impl CacheManager {
         Ok(())
     }
 
+    /// 原子地读取、修改并写回缓存值
+    ///
+    /// 在同一个 sled 事务中读写数据和元数据，并发修改同一个键时不会丢失更新；
+    /// 事务冲突时 `f` 会被重新调用，收到的旧值为 None 表示不存在或已过期，返回 None 表示删除
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 缓存键
+    /// * `ttl` - 写回后的生存时间，None 表示使用默认值
+    /// * `f` - 由旧值计算新值
+    ///
+    /// # 返回值
+    ///
+    /// 返回写回后的新值
+    pub fn update_and_fetch(
+        &self,
+        key: &str,
+        ttl: Option<Duration>,
+        f: impl Fn(Option<&[u8]>) -> Option<CacheValue>,
+    ) -> Result<Option<CacheValue>> {
+        use sled::transaction::{ConflictableTransactionError, TransactionError};
+        use sled::Transactional;
+
+        if !self.config.enabled {
+            return Err(CacheError::CacheDisabled);
+        }
+
+        let ttl_duration = ttl.or_else(|| Some(Duration::from_secs(self.config.default_ttl_secs)));
+        let value = (&*self.db, &self.metadata_tree).transaction(|(data, metadata)| {
+            // 过期的旧值视为不存在
+            let expired = match metadata.get(key.as_bytes())? {
+                Some(bytes) => bincode::serde::decode_from_slice::<CacheEntryMetadata, _>(&bytes, bincode::config::standard())
+                    .map_or(true, |(meta, _)| meta.is_expired()),
+                None => true,
+            };
+            let old = if expired { None } else { data.get(key.as_bytes())? };
+
+            let value = f(old.as_deref());
+            match &value {
+                Some(v) => {
+                    let meta = bincode::serde::encode_to_vec(CacheEntryMetadata::new(ttl_duration, v.len()), bincode::config::standard())
+                        .map_err(|e| ConflictableTransactionError::Abort(
+                            CacheError::SerializationError(format!("序列化元数据失败: {}", e)),
+                        ))?;
+                    data.insert(key.as_bytes(), v.as_slice())?;
+                    metadata.insert(key.as_bytes(), meta)?;
+                }
+                None => {
+                    data.remove(key.as_bytes())?;
+                    metadata.remove(key.as_bytes())?;
+                }
+            }
+            Ok(value)
+        }).map_err(|e| match e {
+            TransactionError::Abort(e) => e,
+            TransactionError::Storage(e) => CacheError::DatabaseError(format!("写入缓存失败: {}", e)),
+        })?;
+
+        if value.is_some() {
+            self.writes.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(value)
+    }
+
     /// 删除缓存项
     ///
     /// # 参数
@@ -433,8 +548,40 @@ impl CacheManager {
         self.db.iter()
     }
 
+    /// 按键前缀遍历缓存条目
+    ///
+    /// 键按字节序升序返回
+    pub fn scan_prefix(&self, prefix: &str) -> sled::Iter {
+        self.db.scan_prefix(prefix.as_bytes())
+    }
+
+    /// 数据库路径
+    pub fn db_path(&self) -> &str {
+        &self.config.db_path
+    }
+
+    /// 持久化存储树
+    ///
+    /// 保存 API 密钥、webhook 和保存的搜索等不应随缓存丢失的记录，
+    /// 不受缓存开关、[`CacheManager::clear`] 和过期清理影响
+    pub fn store(&self) -> &sled::Tree {
+        &self.store_tree
+    }
+
     // 私有辅助方法
 
+    /// 记录命中（同时导出 Prometheus 指标）
+    fn record_hit(&self, key: &str) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("seesea_cache_hits_total", "cache" => cache_kind(key)).increment(1);
+    }
+
+    /// 记录未命中（同时导出 Prometheus 指标）
+    fn record_miss(&self, key: &str) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        metrics::counter!("seesea_cache_misses_total", "cache" => cache_kind(key)).increment(1);
+    }
+
     pub fn get_metadata(&self, key: &str) -> Result<Option<CacheEntryMetadata>> {
         match self.metadata_tree.get(key.as_bytes()) {
             Ok(Some(data)) => {
@@ -609,4 +756,23 @@ mod tests {
         let stats = manager.stats();
         assert_eq!(stats.hits, 1);
     }
+
+    #[test]
+    fn test_clear_keeps_store() {
+        let manager = CacheManager::temporary();
+        manager.set("result:key".to_string(), b"value".to_vec(), None).unwrap();
+        manager.store().insert("api_key:hash", b"record".to_vec()).unwrap();
+
+        manager.clear().unwrap();
+        assert!(manager.get("result:key").unwrap().is_none());
+        assert!(manager.store().get("api_key:hash").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_cache_kind() {
+        assert_eq!(cache_kind("rss:https://example.com/feed"), "rss");
+        assert_eq!(cache_kind("history_click:local:example.com"), "history_click");
+        assert_eq!(cache_kind("custom:key"), "other");
+        assert_eq!(cache_kind("plain"), "other");
+    }
 }
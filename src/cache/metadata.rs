@@ -216,6 +216,7 @@ mod tests {
                 supports_time_range: false,
                 supports_language_filter: true,
                 supports_region_filter: false,
+                supported_regions: Vec::new(),
                 supports_safe_search: true,
                 rate_limit: Some(60),
             },
@@ -19,6 +19,10 @@
 //! - 引擎元数据缓存
 //! - RSS feed 缓存
 //! - 语义相似度缓存
+//! - 搜索历史（可选，默认关闭）
+//! - 点击统计（可选，默认关闭）
+//! - Webhook 注册与投递日志
+//! - 保存的搜索及其新结果
 //! - 通用键值缓存
 //!
 //! # 特性
@@ -73,6 +77,13 @@ pub mod metadata;
 pub mod rss;
 pub mod semantic;
 pub mod semantic_cache;
+pub mod history;
+pub mod clicks;
+pub mod apikeys;
+pub mod webhooks;
+pub mod alerts;
+pub mod files;
+pub mod usage;
 pub mod on;
 
 // 重新导出主要类型
@@ -80,7 +91,14 @@ pub use types::{CacheImplConfig, CacheMode, CacheStats, CacheEntryMetadata};
 pub use manager::{CacheManager, CacheError, Result};
 pub use result::ResultCache;
 pub use metadata::MetadataCache;
-pub use rss::RssCache;
+pub use rss::{RssCache, RssCacheSize};
 pub use semantic::{SimpleVectorizer, QueryVector};
 pub use semantic_cache::{SemanticCache, SemanticCacheConfig};
+pub use history::{HistoryCache, HistoryConfig, HistoryScope, SearchHistoryEntry};
+pub use clicks::{ClickStatsCache, ClickTrackingConfig, EngineClickStats};
+pub use apikeys::{ApiKeyStore, ApiKeyRecord, ApiKeyRateLimit, hash_api_key};
+pub use webhooks::{WebhookStore, WebhookRecord, WebhookSource, WebhookDelivery};
+pub use alerts::{AlertStore, AlertResult, SavedSearch};
+pub use files::{FileIndexCache, FileMatch, IndexedFile};
+pub use usage::{DailyUsage, UsageStore};
 pub use on::CacheInterface;
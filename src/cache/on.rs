@@ -17,11 +17,18 @@
 //! 提供缓存模块的公共 API 接口
 
 use crate::cache::manager::{CacheManager, Result};
+use crate::cache::alerts::AlertStore;
+use crate::cache::apikeys::ApiKeyStore;
+use crate::cache::clicks::{ClickStatsCache, ClickTrackingConfig};
+use crate::cache::files::FileIndexCache;
+use crate::cache::history::{HistoryCache, HistoryConfig};
 use crate::cache::metadata::MetadataCache;
 use crate::cache::result::ResultCache;
 use crate::cache::rss::RssCache;
 use crate::cache::semantic_cache::{SemanticCache, SemanticCacheConfig};
+use crate::cache::usage::UsageStore;
 use crate::cache::types::CacheImplConfig;
+use crate::cache::webhooks::WebhookStore;
 use std::sync::Arc;
 
 /// 统一的缓存接口
@@ -32,6 +39,10 @@ pub struct CacheInterface {
     manager: Arc<CacheManager>,
     /// 语义缓存配置
     semantic_config: SemanticCacheConfig,
+    /// 搜索历史配置
+    history_config: HistoryConfig,
+    /// 点击追踪配置
+    click_config: ClickTrackingConfig,
 }
 
 impl CacheInterface {
@@ -55,12 +66,17 @@ impl CacheInterface {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(config: CacheImplConfig) -> Result<Self> {
-        let manager = CacheManager::instance(config)?;
+        Ok(Self::with_manager(CacheManager::instance(config)?))
+    }
 
-        Ok(Self {
+    /// 使用已打开的缓存管理器创建缓存接口
+    pub fn with_manager(manager: Arc<CacheManager>) -> Self {
+        Self {
             manager,
             semantic_config: SemanticCacheConfig::default(),
-        })
+            history_config: HistoryConfig::default(),
+            click_config: ClickTrackingConfig::default(),
+        }
     }
 
     /// 设置语义缓存配置
@@ -69,6 +85,18 @@ impl CacheInterface {
         self
     }
 
+    /// 设置搜索历史配置
+    pub fn with_history_config(mut self, config: HistoryConfig) -> Self {
+        self.history_config = config;
+        self
+    }
+
+    /// 设置点击追踪配置
+    pub fn with_click_tracking_config(mut self, config: ClickTrackingConfig) -> Self {
+        self.click_config = config;
+        self
+    }
+
     /// 获取搜索结果缓存
     pub fn results(&self) -> ResultCache {
         ResultCache::new(Arc::clone(&self.manager))
@@ -89,11 +117,51 @@ impl CacheInterface {
         SemanticCache::new(Arc::clone(&self.manager), self.semantic_config.clone())
     }
 
+    /// 获取搜索历史缓存
+    pub fn history(&self) -> HistoryCache {
+        HistoryCache::new(Arc::clone(&self.manager), self.history_config.clone())
+    }
+
+    /// 获取点击统计缓存
+    pub fn clicks(&self) -> ClickStatsCache {
+        ClickStatsCache::new(Arc::clone(&self.manager), self.click_config.clone())
+    }
+
+    /// 获取本地文件索引
+    pub fn files(&self) -> FileIndexCache {
+        FileIndexCache::new(Arc::clone(&self.manager))
+    }
+
+    /// 获取持久化 API 密钥存储
+    pub fn api_keys(&self) -> ApiKeyStore {
+        ApiKeyStore::new(Arc::clone(&self.manager))
+    }
+
+    /// 获取 webhook 存储
+    pub fn webhooks(&self) -> WebhookStore {
+        WebhookStore::new(Arc::clone(&self.manager))
+    }
+
+    /// 获取保存的搜索存储
+    pub fn alerts(&self) -> AlertStore {
+        AlertStore::new(Arc::clone(&self.manager))
+    }
+
+    /// 获取查询成本用量存储
+    pub fn usage(&self) -> UsageStore {
+        UsageStore::new(Arc::clone(&self.manager))
+    }
+
     /// 获取缓存管理器引用
     pub fn manager(&self) -> &CacheManager {
         &self.manager
     }
 
+    /// 获取共享的缓存管理器（用于以自定义配置创建子缓存）
+    pub fn shared_manager(&self) -> Arc<CacheManager> {
+        Arc::clone(&self.manager)
+    }
+
     /// 清空所有缓存
     pub fn clear_all(&self) -> Result<()> {
         self.manager.clear()
@@ -26,6 +26,9 @@ type Result<T> = std::result::Result<T, CacheError>;
 /// 搜索结果缓存键前缀
 const RESULT_KEY_PREFIX: &str = "result:";
 
+/// 按查询 ID 保存的聚合结果键前缀
+const QUERY_KEY_PREFIX: &str = "query:";
+
 /// 搜索结果缓存
 ///
 /// 封装 CacheManager，提供搜索结果专用的缓存接口
@@ -50,6 +53,8 @@ impl ResultCache {
     /// * `query` - 搜索查询
     /// * `engine_name` - 引擎名称
     ///
+    /// 时间范围、安全搜索级别和引擎参数都会改变结果，同样计入键中
+    ///
     /// # 返回值
     ///
     /// 返回唯一的缓存键字符串
@@ -64,6 +69,10 @@ impl ResultCache {
         query.page_size.hash(&mut hasher);
         query.language.hash(&mut hasher);
         query.region.hash(&mut hasher);
+        query.time_range.hash(&mut hasher);
+        (query.safe_search.clone() as u8).hash(&mut hasher);
+        // HashMap 的遍历顺序不固定，排序后再计算
+        query.params.iter().collect::<std::collections::BTreeMap<_, _>>().hash(&mut hasher);
         engine_name.hash(&mut hasher);
 
         format!("{}{:x}", RESULT_KEY_PREFIX, hasher.finish())
@@ -163,6 +172,30 @@ impl ResultCache {
         self.manager.delete(&key)
     }
 
+    /// 按查询 ID 保存一次搜索的聚合结果（供后续在结果中细化搜索）
+    ///
+    /// # 参数
+    ///
+    /// * `query_id` - 搜索响应中的查询 ID
+    /// * `result` - 聚合后的搜索结果
+    /// * `ttl` - 生存时间，None 表示使用默认值
+    pub fn set_query_results(&self, query_id: &str, result: &SearchResult, ttl: Option<Duration>) -> Result<()> {
+        let data = bincode::serde::encode_to_vec(result, bincode::config::standard()).map_err(|e| {
+            CacheError::SerializationError(format!("序列化搜索结果失败: {}", e))
+        })?;
+        self.manager.set(format!("{}{}", QUERY_KEY_PREFIX, query_id), data, ttl)
+    }
+
+    /// 获取按查询 ID 保存的聚合结果，不存在或已过期时返回 None
+    pub fn get_query_results(&self, query_id: &str) -> Result<Option<SearchResult>> {
+        match self.manager.get(&format!("{}{}", QUERY_KEY_PREFIX, query_id))? {
+            Some(data) => bincode::serde::decode_from_slice(&data, bincode::config::standard())
+                .map(|(result, _)| Some(result))
+                .map_err(|e| CacheError::SerializationError(format!("反序列化搜索结果失败: {}", e))),
+            None => Ok(None),
+        }
+    }
+
     /// 清空所有搜索结果缓存
     pub fn clear_all(&self) -> Result<()> {
         self.manager.clear()
@@ -469,6 +502,17 @@ mod tests {
         query3.page = 2;
         let key3 = ResultCache::generate_key(&query3, engine_name);
         assert_ne!(key1, key3);
+
+        // 时间范围、安全搜索级别和引擎参数不同的查询不能共用缓存
+        let mut query4 = sample_query();
+        query4.time_range = Some(crate::derive::TimeRange::Week);
+        let mut query5 = sample_query();
+        query5.safe_search = SafeSearchLevel::Strict;
+        let mut query6 = sample_query();
+        query6.params.insert("category".to_string(), "news".to_string());
+        for query in [query4, query5, query6] {
+            assert_ne!(key1, ResultCache::generate_key(&query, engine_name));
+        }
     }
 
     #[test]
@@ -492,4 +536,16 @@ mod tests {
         // 获取应该返回 None
         assert!(cache.get(&query, engine_name).unwrap_or(None).is_none());
     }
+
+    #[test]
+    #[serial]
+    fn test_query_results_by_id() {
+        let cache = temp_result_cache();
+        let result = sample_result();
+
+        cache.set_query_results("q1", &result, None).expect("保存查询结果失败");
+        let cached = cache.get_query_results("q1").unwrap().expect("查询结果应存在");
+        assert_eq!(cached.items[0].url, "https://example.com");
+        assert!(cache.get_query_results("q2").unwrap().is_none());
+    }
 }
@@ -17,7 +17,9 @@
 //! 提供 RSS feed 结果的专门缓存功能，支持持久化和自动更新
 
 use crate::cache::manager::{CacheManager, CacheError};
-use crate::derive::rss::RssFeed;
+use crate::derive::rss::{RssAlternateSource, RssFeed, RssFeedItem};
+use crate::rss::dedup::{content_key, hamming_distance, simhash, simhash_bands, SIMHASH_MAX_DISTANCE};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
@@ -27,6 +29,18 @@ type Result<T> = std::result::Result<T, CacheError>;
 /// RSS 缓存键前缀
 const RSS_KEY_PREFIX: &str = "rss:";
 const RSS_META_PREFIX: &str = "rss_meta:";
+/// 内容键索引前缀（内容键 -> 保留的条目）
+const RSS_CONTENT_PREFIX: &str = "rss_content:";
+/// SimHash 分段索引前缀（分段 -> 保留的条目）
+const RSS_SIMHASH_PREFIX: &str = "rss_simhash:";
+
+/// 去重索引中记录的保留条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CanonicalItem {
+    feed_url: String,
+    link: String,
+    simhash: Option<u64>,
+}
 
 /// RSS Feed 缓存元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +57,30 @@ pub struct RssFeedCacheMeta {
     pub update_interval: Option<u64>,
     /// Feed 项目数量
     pub item_count: usize,
+    /// 上次响应的 `ETag`
+    pub etag: Option<String>,
+    /// 上次响应的 `Last-Modified`
+    pub last_modified: Option<String>,
+    /// 源站建议的最短轮询间隔（秒，来自 `Cache-Control` / `<ttl>`）
+    pub refresh_hint: Option<u64>,
+}
+
+/// 记录转载来源，已记录过时返回 false
+fn add_alternate(item: &mut RssFeedItem, source: RssAlternateSource) -> bool {
+    if item.alternate_sources.contains(&source) {
+        return false;
+    }
+    item.alternate_sources.push(source);
+    true
+}
+
+/// RSS 缓存占用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RssCacheSize {
+    /// 缓存的 feed 数
+    pub feeds: u64,
+    /// feed、元数据和去重索引的键值总字节数
+    pub bytes: u64,
 }
 
 /// RSS 结果缓存
@@ -112,6 +150,9 @@ impl RssCache {
             }
         }
 
+        // 跨 feed 去重：其他 feed 已有的同一内容只作为转载来源记录
+        self.dedup_across_feeds(url, &mut deduped_items, ttl)?;
+
         // 合并新旧项目（保留旧的，添加新的）
         for item in existing_items {
             let key = if let Some(ref guid) = item.guid {
@@ -137,7 +178,8 @@ impl RssCache {
             .map_err(|e| CacheError::SerializationError(format!("Failed to serialize feed: {}", e)))?;
         self.manager.set(key, feed_bytes, ttl)?;
 
-        // 存储元数据
+        // 存储元数据（保留上次响应的校验值，由 record_fetch 更新）
+        let previous = self.get_meta(url).ok().flatten();
         let meta = RssFeedCacheMeta {
             url: url.to_string(),
             name: Some(feed.meta.title.clone()),
@@ -145,14 +187,169 @@ impl RssCache {
             persistent,
             update_interval,
             item_count: deduped_items.len(),
+            etag: previous.as_ref().and_then(|meta| meta.etag.clone()),
+            last_modified: previous.as_ref().and_then(|meta| meta.last_modified.clone()),
+            refresh_hint: previous.and_then(|meta| meta.refresh_hint),
         };
-        let meta_bytes = bincode::serde::encode_to_vec(&meta, bincode::config::standard())
+        self.write_meta(&meta_key, &meta)
+    }
+
+    fn write_meta(&self, meta_key: &str, meta: &RssFeedCacheMeta) -> Result<()> {
+        let meta_bytes = bincode::serde::encode_to_vec(meta, bincode::config::standard())
             .map_err(|e| CacheError::SerializationError(format!("Failed to serialize meta: {}", e)))?;
-        self.manager.set(meta_key, meta_bytes, None)?;
+        self.manager.set(meta_key.to_string(), meta_bytes, None)?;
+        Ok(())
+    }
+
+    /// 记录一次抓取的校验值和轮询提示，并把最后更新时间设为现在
+    ///
+    /// 条件请求返回 304 时也应调用，以免在提示的间隔内重复轮询。
+    /// 没有元数据（feed 从未入库）时不做任何事
+    pub fn record_fetch(
+        &self,
+        url: &str,
+        etag: Option<String>,
+        last_modified: Option<String>,
+        refresh_hint: Option<u64>,
+    ) -> Result<()> {
+        let Some(mut meta) = self.get_meta(url)? else {
+            return Ok(());
+        };
+        meta.etag = etag;
+        meta.last_modified = last_modified;
+        meta.refresh_hint = refresh_hint;
+        meta.last_updated = Self::current_timestamp();
+        self.write_meta(&Self::generate_meta_key(url), &meta)
+    }
 
+    /// 跨 feed 去重
+    ///
+    /// 按内容键或 SimHash 找到已入库的同一内容时，从 `items` 中移除该条目，
+    /// 并把它记录为保留条目的转载来源；否则把条目登记为新的保留条目
+    fn dedup_across_feeds(&self, url: &str, items: &mut Vec<RssFeedItem>, ttl: Option<Duration>) -> Result<()> {
+        // 其他 feed 中需要追加转载来源的条目：feed_url -> [(保留条目链接, 来源)]
+        let mut alternates: HashMap<String, Vec<(String, RssAlternateSource)>> = HashMap::new();
+        let mut kept = Vec::with_capacity(items.len());
+
+        for item in items.drain(..) {
+            let key = content_key(&item);
+            let hash = simhash(&item);
+            // 保留条目所在的 feed 已过期时索引失效，当前条目成为新的保留条目
+            let canonical = self
+                .find_canonical(key.as_deref(), hash)?
+                .filter(|canonical| canonical.link != item.link)
+                .filter(|canonical| canonical.feed_url == url || self.is_live(&canonical.feed_url));
+            match canonical {
+                Some(canonical) => {
+                    let source = RssAlternateSource { feed_url: url.to_string(), link: item.link.clone() };
+                    alternates.entry(canonical.feed_url).or_default().push((canonical.link, source));
+                }
+                None => {
+                    let entry = CanonicalItem { feed_url: url.to_string(), link: item.link.clone(), simhash: hash };
+                    self.register_canonical(key.as_deref(), &entry, ttl)?;
+                    kept.push(item);
+                }
+            }
+        }
+        *items = kept;
+
+        for (feed_url, sources) in alternates {
+            if feed_url == url {
+                // 同一 feed 中重复发布的内容
+                for (link, source) in sources {
+                    if let Some(item) = items.iter_mut().find(|item| item.link == link) {
+                        add_alternate(item, source);
+                    }
+                }
+            } else {
+                self.add_alternates(&feed_url, sources)?;
+            }
+        }
         Ok(())
     }
 
+    /// 按内容键或 SimHash 分段查找已登记的保留条目
+    fn find_canonical(&self, key: Option<&str>, hash: Option<u64>) -> Result<Option<CanonicalItem>> {
+        if let Some(key) = key
+            && let Some(entry) = self.read_index(&format!("{}{}", RSS_CONTENT_PREFIX, key))?
+        {
+            return Ok(Some(entry));
+        }
+        let Some(hash) = hash else {
+            return Ok(None);
+        };
+        for (band, value) in simhash_bands(hash).into_iter().enumerate() {
+            let index_key = format!("{}{}:{:04x}", RSS_SIMHASH_PREFIX, band, value);
+            if let Some(entry) = self.read_index(&index_key)?
+                && entry.simhash.is_some_and(|other| hamming_distance(hash, other) <= SIMHASH_MAX_DISTANCE)
+            {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 登记保留条目（分段已被其他条目占用时保留先登记的）
+    fn register_canonical(&self, key: Option<&str>, entry: &CanonicalItem, ttl: Option<Duration>) -> Result<()> {
+        let bytes = bincode::serde::encode_to_vec(entry, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize dedup entry: {}", e)))?;
+        if let Some(key) = key {
+            self.manager.set(format!("{}{}", RSS_CONTENT_PREFIX, key), bytes.clone(), ttl)?;
+        }
+        if let Some(hash) = entry.simhash {
+            for (band, value) in simhash_bands(hash).into_iter().enumerate() {
+                let index_key = format!("{}{}:{:04x}", RSS_SIMHASH_PREFIX, band, value);
+                if self.manager.get(&index_key)?.is_none() {
+                    self.manager.set(index_key, bytes.clone(), ttl)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// feed 是否仍在缓存中且未过期
+    fn is_live(&self, feed_url: &str) -> bool {
+        matches!(
+            self.manager.get_metadata(&Self::generate_feed_key(feed_url)),
+            Ok(Some(meta)) if !meta.is_expired()
+        )
+    }
+
+    fn read_index(&self, key: &str) -> Result<Option<CanonicalItem>> {
+        Ok(self
+            .manager
+            .get(key)?
+            .and_then(|bytes| bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok())
+            .map(|(entry, _)| entry))
+    }
+
+    /// 为其他 feed 中的保留条目追加转载来源（保持该 feed 剩余的过期时间）
+    fn add_alternates(&self, feed_url: &str, sources: Vec<(String, RssAlternateSource)>) -> Result<()> {
+        let Some(mut feed) = self.get(feed_url)? else {
+            return Ok(());
+        };
+        let mut changed = false;
+        for (link, source) in sources {
+            if let Some(item) = feed.items.iter_mut().find(|item| item.link == link) {
+                changed |= add_alternate(item, source);
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+
+        let key = Self::generate_feed_key(feed_url);
+        let now = Self::current_timestamp();
+        let ttl = match self.manager.get_metadata(&key)?.and_then(|meta| meta.expires_at) {
+            Some(expires_at) if expires_at <= now => return Ok(()),
+            Some(expires_at) => Some(Duration::from_secs(expires_at - now)),
+            None => None,
+        };
+        let bytes = bincode::serde::encode_to_vec(&feed, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize feed: {}", e)))?;
+        self.manager.set(key, bytes, ttl)
+    }
+
     /// 从缓存获取 RSS feed
     pub fn get(&self, url: &str) -> Result<Option<RssFeed>> {
         let key = Self::generate_feed_key(url);
@@ -181,7 +378,9 @@ impl RssCache {
     pub fn needs_update(&self, url: &str) -> Result<bool> {
         if let Some(meta) = self.get_meta(url)? {
             if let Some(interval) = meta.update_interval {
-                let elapsed = Self::current_timestamp() - meta.last_updated;
+                // 源站要求更长的缓存时间时，以源站为准
+                let interval = interval.max(meta.refresh_hint.unwrap_or(0));
+                let elapsed = Self::current_timestamp().saturating_sub(meta.last_updated);
                 Ok(elapsed >= interval)
             } else {
                 Ok(false)
@@ -301,6 +500,32 @@ impl RssCache {
         Ok(matched_items)
     }
 
+    /// 列出所有缓存的 RSS 条目，每项为 (feed_url, item)
+    pub fn list_items(&self) -> Result<Vec<(String, crate::derive::rss::RssFeedItem)>> {
+        let mut items = Vec::new();
+        for (feed_url, _) in self.list_all_feeds()? {
+            if let Some(feed) = self.get(&feed_url)? {
+                items.extend(feed.items.into_iter().map(|item| (feed_url.clone(), item)));
+            }
+        }
+        Ok(items)
+    }
+
+    /// 统计 RSS 缓存占用（遍历 RSS 相关的键，不反序列化内容）
+    pub fn size(&self) -> Result<RssCacheSize> {
+        let mut size = RssCacheSize::default();
+        for prefix in [RSS_KEY_PREFIX, RSS_META_PREFIX, RSS_CONTENT_PREFIX, RSS_SIMHASH_PREFIX] {
+            for entry in self.manager.scan_prefix(prefix) {
+                let (key, value) = entry.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+                size.bytes += (key.len() + value.len()) as u64;
+                if prefix == RSS_KEY_PREFIX {
+                    size.feeds += 1;
+                }
+            }
+        }
+        Ok(size)
+    }
+
     /// 列出所有缓存的 RSS feeds（包括过期的）
     ///
     /// # 返回值
@@ -349,6 +574,80 @@ mod tests {
         assert!(meta_key.starts_with(RSS_META_PREFIX));
     }
 
+    fn dedup_item(title: &str, link: &str) -> RssFeedItem {
+        RssFeedItem {
+            title: title.to_string(),
+            link: link.to_string(),
+            description: None,
+            author: None,
+            pub_date: None,
+            content: None,
+            categories: vec![],
+            guid: None,
+            enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    fn dedup_feed(items: Vec<RssFeedItem>) -> RssFeed {
+        RssFeed {
+            meta: crate::derive::rss::RssFeedMeta {
+                title: "dedup".to_string(),
+                link: "https://dedup.example.com".to_string(),
+                description: None,
+                language: None,
+                copyright: None,
+                last_build_date: None,
+                pub_date: None,
+                image: None,
+            },
+            items,
+        }
+    }
+
+    #[test]
+    fn test_dedup_across_feeds_keeps_first_and_records_alternates() {
+        let cache = RssCache::new(CacheManager::temporary());
+        let tech = "https://dedup.example.com/tech.xml";
+        let all = "https://dedup.example.com/all.xml";
+
+        let story = dedup_item("Dedup Story: Rust 9.99 Released", "https://www.dedup.example.com/tech/9-99");
+        cache.set(tech, &dedup_feed(vec![story]), false, None, None).unwrap();
+
+        let same_story = dedup_item("dedup story — rust 9.99 released", "https://dedup.example.com/all/9-99");
+        let other = dedup_item("Dedup Story: unrelated", "https://dedup.example.com/all/other");
+        cache.set(all, &dedup_feed(vec![same_story, other]), false, None, None).unwrap();
+
+        let all_items = cache.get(all).unwrap().unwrap().items;
+        assert_eq!(all_items.len(), 1);
+        assert_eq!(all_items[0].title, "Dedup Story: unrelated");
+
+        let tech_items = cache.get(tech).unwrap().unwrap().items;
+        assert_eq!(
+            tech_items[0].alternate_sources,
+            vec![RssAlternateSource { feed_url: all.to_string(), link: "https://dedup.example.com/all/9-99".to_string() }]
+        );
+
+        // 再次抓取不会重复记录来源
+        let same_story = dedup_item("dedup story — rust 9.99 released", "https://dedup.example.com/all/9-99");
+        cache.set(all, &dedup_feed(vec![same_story]), false, None, None).unwrap();
+        assert_eq!(cache.get(tech).unwrap().unwrap().items[0].alternate_sources.len(), 1);
+    }
+
+    #[test]
+    fn test_rss_cache_size() {
+        let cache = RssCache::new(CacheManager::temporary());
+        let url = "https://size.example.com/feed.xml";
+        cache.set(url, &dedup_feed(vec![dedup_item("Size test item", "https://size.example.com/1")]), false, None, None).unwrap();
+
+        let after = cache.size().unwrap();
+        assert!(after.feeds >= 1);
+        assert!(after.bytes > url.len() as u64);
+        cache.delete(url).unwrap();
+    }
+
     #[test]
     fn test_rss_cache_creation() {
         let config = CacheImplConfig::default();
@@ -0,0 +1,118 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 查询成本用量存储
+//!
+//! 按调用方（API 密钥或魔法链接令牌的哈希）和 UTC 日期累计请求数和查询成本，
+//! 用于每日预算限制和 `/api/usage` 用量查询。计数保留 8 天。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// 用量键前缀（后接调用方和日期）
+const USAGE_KEY_PREFIX: &str = "usage:";
+
+/// 用量保留时间
+const USAGE_RETENTION: Duration = Duration::from_secs(8 * 24 * 3600);
+
+/// 单日用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    /// 日期（UTC）
+    pub date: NaiveDate,
+    /// 计费请求数
+    pub requests: u64,
+    /// 累计查询成本
+    pub cost: u64,
+}
+
+/// 用量存储
+///
+/// 封装 CacheManager，按调用方和日期记录用量
+pub struct UsageStore {
+    manager: Arc<CacheManager>,
+    /// 串行化读-改-写，避免并发请求丢失计数
+    lock: Mutex<()>,
+}
+
+impl UsageStore {
+    /// 创建用量存储
+    pub fn new(manager: Arc<CacheManager>) -> Self {
+        Self { manager, lock: Mutex::new(()) }
+    }
+
+    fn usage_key(subject: &str, date: NaiveDate) -> String {
+        format!("{}{}:{}", USAGE_KEY_PREFIX, subject, date.format("%Y-%m-%d"))
+    }
+
+    /// 获取调用方某一天的用量
+    pub fn get(&self, subject: &str, date: NaiveDate) -> Result<DailyUsage> {
+        Ok(self.manager.get(&Self::usage_key(subject, date))?
+            .and_then(|bytes| bincode::serde::decode_from_slice(&bytes, bincode::config::standard()).ok())
+            .map(|(usage, _)| usage)
+            .unwrap_or(DailyUsage { date, ..Default::default() }))
+    }
+
+    /// 获取调用方当天的用量
+    pub fn today(&self, subject: &str) -> Result<DailyUsage> {
+        self.get(subject, Utc::now().date_naive())
+    }
+
+    /// 记录一次请求的成本，返回当天累计用量
+    pub fn record(&self, subject: &str, cost: u64) -> Result<DailyUsage> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut usage = self.today(subject)?;
+        usage.requests += 1;
+        usage.cost += cost;
+        let bytes = bincode::serde::encode_to_vec(usage, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize usage: {}", e)))?;
+        self.manager.set(Self::usage_key(subject, usage.date), bytes, Some(USAGE_RETENTION))?;
+        Ok(usage)
+    }
+
+    /// 最近若干天（含当天，按日期倒序）的用量
+    pub fn recent(&self, subject: &str, days: u64) -> Result<Vec<DailyUsage>> {
+        let today = Utc::now().date_naive();
+        (0..days)
+            .filter_map(|offset| today.checked_sub_days(Days::new(offset)))
+            .map(|date| self.get(subject, date))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_usage() {
+        let store = UsageStore::new(CacheManager::temporary());
+
+        assert_eq!(store.today("key:a").unwrap().cost, 0);
+        store.record("key:a", 3).unwrap();
+        let usage = store.record("key:a", 2).unwrap();
+        assert_eq!((usage.requests, usage.cost), (2, 5));
+        assert_eq!(store.today("key:b").unwrap().requests, 0);
+
+        let recent = store.recent("key:a", 3).unwrap();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].cost, 5);
+        assert_eq!(recent[1].cost, 0);
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Webhook 存储
+//!
+//! webhook 注册信息保存在 sled 的持久化存储树中（清空缓存不会影响）；
+//! 投递日志和每个 webhook 已通知过的条目（按 URL 哈希记录，同一条目只通知一次）
+//! 作为带保留期的缓存条目保存。
+
+use crate::cache::manager::{CacheManager, CacheError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+type Result<T> = std::result::Result<T, CacheError>;
+
+/// webhook 记录键前缀（后接 ID）
+const WEBHOOK_PREFIX: &str = "webhook:";
+
+/// 投递日志键前缀（后接 webhook ID 和时间戳）
+const DELIVERY_PREFIX: &str = "webhook_delivery:";
+
+/// 已通知条目键前缀（后接 webhook ID 和 URL 哈希）
+const SEEN_PREFIX: &str = "webhook_seen:";
+
+/// 投递日志保留时间
+const DELIVERY_RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// 已通知条目保留时间（过期后同一条目可能再次通知）
+const SEEN_RETENTION: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// 通知来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookSource {
+    /// RSS 榜单（目标为榜单名称）
+    RssRanking,
+    /// 保存的搜索（目标为保存的搜索 ID）
+    SavedSearch,
+    /// 引擎响应异常（目标为引擎名，`*` 表示所有引擎）
+    EngineAnomaly,
+}
+
+impl WebhookSource {
+    /// 事件名称
+    pub fn event(&self) -> &'static str {
+        match self {
+            WebhookSource::RssRanking => "rss_ranking.new_items",
+            WebhookSource::SavedSearch => "saved_search.new_items",
+            WebhookSource::EngineAnomaly => "engine.anomaly",
+        }
+    }
+}
+
+/// webhook 记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRecord {
+    /// webhook ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 接收通知的 URL（HTTP POST）
+    pub url: String,
+    /// 通知来源
+    pub source: WebhookSource,
+    /// 来源目标（榜单名称或保存的搜索 ID）
+    pub target: String,
+    /// 只通知评分不低于该阈值的条目
+    pub min_score: f64,
+    /// 签名密钥（设置后请求带 `X-SeeSea-Signature` 头）
+    pub secret: Option<String>,
+    /// 是否启用
+    pub enabled: bool,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+}
+
+/// 一次投递的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    /// webhook ID
+    pub webhook_id: String,
+    /// 事件名称
+    pub event: String,
+    /// 投递时间
+    pub delivered_at: DateTime<Utc>,
+    /// 通知的条目数
+    pub item_count: usize,
+    /// 尝试次数
+    pub attempts: u32,
+    /// 最后一次响应的状态码
+    pub status: Option<u16>,
+    /// 是否成功
+    pub success: bool,
+    /// 最后一次失败的原因
+    pub error: Option<String>,
+}
+
+/// Webhook 存储
+///
+/// 封装 CacheManager，webhook 存于持久化存储树，投递日志和已通知条目存于缓存
+pub struct WebhookStore {
+    manager: Arc<CacheManager>,
+    store: sled::Tree,
+}
+
+fn db_error(e: sled::Error) -> CacheError {
+    CacheError::DatabaseError(format!("访问 webhook 存储失败: {}", e))
+}
+
+impl WebhookStore {
+    /// 创建 webhook 存储
+    pub fn new(manager: Arc<CacheManager>) -> Self {
+        let store = manager.store().clone();
+        Self { manager, store }
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+        bincode::serde::encode_to_vec(value, bincode::config::standard())
+            .map_err(|e| CacheError::SerializationError(format!("Failed to serialize webhook data: {}", e)))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .ok()
+            .map(|(value, _)| value)
+    }
+
+    fn seen_key(webhook_id: &str, url: &str) -> String {
+        format!("{}{}:{:x}", SEEN_PREFIX, webhook_id, Sha256::digest(url.as_bytes()))
+    }
+
+    fn save(&self, record: &WebhookRecord) -> Result<()> {
+        self.store.insert(format!("{}{}", WEBHOOK_PREFIX, record.id), Self::encode(record)?).map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 注册 webhook
+    pub fn create(
+        &self,
+        name: impl Into<String>,
+        url: impl Into<String>,
+        source: WebhookSource,
+        target: impl Into<String>,
+        min_score: f64,
+        secret: Option<String>,
+    ) -> Result<WebhookRecord> {
+        let record = WebhookRecord {
+            id: Uuid::new_v4().simple().to_string(),
+            name: name.into(),
+            url: url.into(),
+            source,
+            target: target.into(),
+            min_score,
+            secret,
+            enabled: true,
+            created_at: Utc::now(),
+        };
+        self.save(&record)?;
+        Ok(record)
+    }
+
+    /// 按 ID 查找 webhook
+    pub fn find(&self, id: &str) -> Result<Option<WebhookRecord>> {
+        Ok(self.store.get(format!("{}{}", WEBHOOK_PREFIX, id)).map_err(db_error)?
+            .and_then(|bytes| Self::decode(&bytes)))
+    }
+
+    /// 列出所有 webhook（按创建时间排序）
+    pub fn list(&self) -> Result<Vec<WebhookRecord>> {
+        let mut records: Vec<WebhookRecord> = Vec::new();
+        for item in self.store.scan_prefix(WEBHOOK_PREFIX).values() {
+            if let Some(record) = Self::decode(&item.map_err(db_error)?) {
+                records.push(record);
+            }
+        }
+        records.sort_by_key(|r| r.created_at);
+        Ok(records)
+    }
+
+    /// 订阅指定来源的已启用 webhook
+    pub fn subscribers(&self, source: WebhookSource, target: &str) -> Result<Vec<WebhookRecord>> {
+        Ok(self.list()?
+            .into_iter()
+            .filter(|r| r.enabled && r.source == source && r.target == target)
+            .collect())
+    }
+
+    /// 启用或停用 webhook，返回 webhook 是否存在
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<bool> {
+        match self.find(id)? {
+            Some(mut record) => {
+                record.enabled = enabled;
+                self.save(&record)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 删除 webhook 及其投递日志和已通知记录，返回 webhook 是否存在
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let existed = self.store.remove(format!("{}{}", WEBHOOK_PREFIX, id)).map_err(db_error)?.is_some();
+        for prefix in [DELIVERY_PREFIX, SEEN_PREFIX] {
+            for item in self.manager.scan_prefix(&format!("{}{}:", prefix, id)).keys() {
+                let key = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+                self.manager.delete(&String::from_utf8_lossy(&key))?;
+            }
+        }
+        Ok(existed)
+    }
+
+    /// 记录一次投递
+    pub fn record_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let timestamp = delivery.delivered_at.timestamp_nanos_opt().unwrap_or_default();
+        let key = format!("{}{}:{:020}", DELIVERY_PREFIX, delivery.webhook_id, timestamp);
+        self.manager.set(key, Self::encode(delivery)?, Some(DELIVERY_RETENTION))
+    }
+
+    /// 最近的投递日志（最新的在前）
+    pub fn deliveries(&self, webhook_id: &str, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        let mut deliveries = Vec::new();
+        // 键按时间戳升序排列，反向遍历得到最新的记录
+        for item in self.manager.scan_prefix(&format!("{}{}:", DELIVERY_PREFIX, webhook_id)).keys().rev() {
+            if deliveries.len() >= limit {
+                break;
+            }
+            let key = item.map_err(|e| CacheError::DatabaseError(format!("遍历缓存失败: {}", e)))?;
+            if let Some(delivery) = self.manager.get(&String::from_utf8_lossy(&key))?
+                .and_then(|bytes| Self::decode(&bytes))
+            {
+                deliveries.push(delivery);
+            }
+        }
+        Ok(deliveries)
+    }
+
+    /// 该 webhook 是否已通知过此 URL
+    pub fn is_seen(&self, webhook_id: &str, url: &str) -> Result<bool> {
+        Ok(self.manager.get(&Self::seen_key(webhook_id, url))?.is_some())
+    }
+
+    /// 标记 URL 已通知
+    pub fn mark_seen<'a>(&self, webhook_id: &str, urls: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        for url in urls {
+            self.manager.set(Self::seen_key(webhook_id, url), Vec::new(), Some(SEEN_RETENTION))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> WebhookStore {
+        WebhookStore::new(CacheManager::temporary())
+    }
+
+    #[test]
+    fn test_webhook_store_lifecycle() {
+        let store = store();
+        let target = format!("board-{}", Uuid::new_v4().simple());
+        let record = store
+            .create("hook", "https://hooks.example.com/a", WebhookSource::RssRanking, &target, 2.0, None)
+            .unwrap();
+
+        assert_eq!(store.subscribers(WebhookSource::RssRanking, &target).unwrap().len(), 1);
+        assert!(store.subscribers(WebhookSource::SavedSearch, &target).unwrap().is_empty());
+
+        assert!(!store.is_seen(&record.id, "https://example.com/1").unwrap());
+        store.mark_seen(&record.id, ["https://example.com/1"]).unwrap();
+        assert!(store.is_seen(&record.id, "https://example.com/1").unwrap());
+
+        for attempts in 1..=2 {
+            store.record_delivery(&WebhookDelivery {
+                webhook_id: record.id.clone(),
+                event: WebhookSource::RssRanking.event().to_string(),
+                delivered_at: Utc::now(),
+                item_count: 1,
+                attempts,
+                status: Some(200),
+                success: true,
+                error: None,
+            }).unwrap();
+        }
+        let deliveries = store.deliveries(&record.id, 10).unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].attempts, 2);
+
+        assert!(store.set_enabled(&record.id, false).unwrap());
+        assert!(store.subscribers(WebhookSource::RssRanking, &target).unwrap().is_empty());
+
+        assert!(store.delete(&record.id).unwrap());
+        assert!(store.find(&record.id).unwrap().is_none());
+        assert!(store.deliveries(&record.id, 10).unwrap().is_empty());
+        assert!(!store.is_seen(&record.id, "https://example.com/1").unwrap());
+    }
+}
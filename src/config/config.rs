@@ -41,6 +41,9 @@ pub struct SeeSeaConfig {
     pub logging: crate::config::logging::LoggingConfig,
     /// 搜索引擎配置
     pub engines: crate::config::engines::EnginesConfig,
+    /// API 内外网监听配置（未配置时使用 API 服务的默认值）
+    #[serde(default)]
+    pub network: Option<crate::api::network::NetworkConfig>,
 }
 
 impl Default for SeeSeaConfig {
@@ -55,6 +58,7 @@ impl Default for SeeSeaConfig {
             api: crate::config::api::ApiConfig::default(),
             logging: crate::config::logging::LoggingConfig::default(),
             engines: crate::config::engines::EnginesConfig::default(),
+            network: None,
         }
     }
 }
@@ -0,0 +1,245 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 环境变量配置覆盖
+//!
+//! `SEESEA_` 前缀的环境变量可以覆盖任意配置字段，路径各段用双下划线分隔，大小写不敏感：
+//!
+//! ```text
+//! SEESEA_SERVER__PORT=9000
+//! SEESEA_CACHE__TTL=600
+//! SEESEA_LOGGING__LEVEL=debug
+//! SEESEA_SEARCH__SUPPORTED_LANGUAGES=en,zh
+//! ```
+//!
+//! 覆盖按字段当前的类型解析（字符串原样保留，数字和布尔值需要能解析，数组接受 JSON 或逗号分隔），
+//! 作为最高优先级的一层叠加在配置文件之上，之后再整体反序列化回 [`SeeSeaConfig`]。
+//! 未设置的可选字段（`null`）无法得知类型，先按 JSON 解析，反序列化失败时再按字符串重试，
+//! 因此 `SEESEA_SERVER__BASE_URL=123` 这样的值也能用于 `Option<String>` 字段。
+
+use serde_json::{Map, Value};
+
+use crate::config::{ConfigError, SeeSeaConfig};
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "SEESEA";
+
+/// 字段路径分隔符
+pub const ENV_SEPARATOR: &str = "__";
+
+/// 有前缀但不是配置字段的保留变量
+const RESERVED_VARS: &[&str] = &["CONFIG_FILE", "UPDATE_FIXTURES"];
+
+/// 环境变量覆盖结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvOverrideReport {
+    /// 已应用的字段路径（如 `server.port`）
+    pub applied: Vec<String>,
+    /// 没有对应配置字段而被忽略的变量名
+    pub ignored: Vec<String>,
+}
+
+/// 用当前进程的 `SEESEA_*` 环境变量覆盖配置
+pub fn apply_env_overrides(config: &mut SeeSeaConfig) -> Result<EnvOverrideReport, ConfigError> {
+    apply_overrides(config, ENV_PREFIX, std::env::vars())
+}
+
+/// 用给定的变量覆盖配置（`prefix` 不含结尾的下划线）
+///
+/// 没有对应字段的变量被忽略并记录在报告中；值无法按字段类型解析，
+/// 或覆盖后的配置无法反序列化时返回错误，错误信息包含变量名。
+pub fn apply_overrides<I>(
+    config: &mut SeeSeaConfig,
+    prefix: &str,
+    vars: I,
+) -> Result<EnvOverrideReport, ConfigError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let prefix = format!("{}_", prefix);
+    let mut vars: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(key, _)| {
+            key.strip_prefix(&prefix)
+                .is_some_and(|rest| !rest.is_empty() && !RESERVED_VARS.contains(&rest))
+        })
+        .collect();
+    if vars.is_empty() {
+        return Ok(EnvOverrideReport::default());
+    }
+    vars.sort();
+
+    let mut root = serde_json::to_value(&*config)
+        .map_err(|e| ConfigError::EnvironmentError(format!("序列化配置失败: {}", e)))?;
+    let mut report = EnvOverrideReport::default();
+    // 未设置的可选字段中按 JSON 解析成非字符串的覆盖（路径和原始值）
+    let mut untyped: Vec<(Vec<String>, String)> = Vec::new();
+
+    for (key, raw) in vars {
+        let path: Vec<String> = key[prefix.len()..]
+            .split(ENV_SEPARATOR)
+            .map(|segment| segment.to_lowercase())
+            .collect();
+        if path.iter().any(|segment| segment.is_empty()) {
+            report.ignored.push(key);
+            continue;
+        }
+
+        match field_mut(&mut root, &path) {
+            Some(field) => {
+                let was_null = field.is_null();
+                *field = parse_value(field, &raw).map_err(|e| {
+                    ConfigError::EnvironmentError(format!("{}: {}", key, e))
+                })?;
+                if was_null && !field.is_string() {
+                    untyped.push((path.clone(), raw.trim().to_string()));
+                }
+                report.applied.push(path.join("."));
+            }
+            None => report.ignored.push(key),
+        }
+    }
+
+    let mut parsed = serde_json::from_value(root.clone());
+    if parsed.is_err() && !untyped.is_empty() {
+        for (path, raw) in untyped {
+            if let Some(field) = field_mut(&mut root, &path) {
+                *field = Value::String(raw);
+            }
+        }
+        parsed = serde_json::from_value(root);
+    }
+    *config = parsed.map_err(|e| {
+        ConfigError::EnvironmentError(format!(
+            "应用覆盖 {} 后配置无效: {}",
+            report.applied.join(", "),
+            e
+        ))
+    })?;
+
+    Ok(report)
+}
+
+/// 按路径查找已有字段；未设置的可选段（`null`）会被展开为空表
+fn field_mut<'a>(root: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    let mut current = root;
+    for (i, segment) in path.iter().enumerate() {
+        if current.is_null() && i > 0 {
+            *current = Value::Object(Map::new());
+            current = current.as_object_mut()?.entry(segment.clone()).or_insert(Value::Null);
+            continue;
+        }
+        current = current.as_object_mut()?.get_mut(segment)?;
+    }
+    Some(current)
+}
+
+/// 按字段当前值的类型解析环境变量
+fn parse_value(current: &Value, raw: &str) -> Result<Value, String> {
+    let raw = raw.trim();
+    match current {
+        Value::String(_) => Ok(Value::String(raw.to_string())),
+        Value::Bool(_) => match raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(format!("\"{}\" 不是有效的布尔值", raw)),
+        },
+        Value::Number(_) => serde_json::from_str::<serde_json::Number>(raw)
+            .map(Value::Number)
+            .map_err(|_| format!("\"{}\" 不是有效的数字", raw)),
+        Value::Array(items) => {
+            if raw.starts_with('[') {
+                return serde_json::from_str(raw).map_err(|e| format!("无效的 JSON 数组: {}", e));
+            }
+            let element = items.first().cloned().unwrap_or(Value::String(String::new()));
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| parse_value(&element, item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+        Value::Object(_) => serde_json::from_str(raw).map_err(|e| format!("需要 JSON 对象: {}", e)),
+        Value::Null => Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_overrides_nested_fields_by_type() {
+        let mut config = SeeSeaConfig::default();
+        let report = apply_overrides(
+            &mut config,
+            ENV_PREFIX,
+            vars(&[
+                ("SEESEA_SERVER__PORT", "9000"),
+                ("SEESEA_SERVER__SECRET_KEY", "12345678901234567890"),
+                ("SEESEA_GENERAL__DEBUG", "on"),
+                ("SEESEA_CACHE__TTL", "600"),
+                ("SEESEA_SEARCH__SUPPORTED_LANGUAGES", "en, zh"),
+                ("PATH", "/usr/bin"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.secret_key, "12345678901234567890");
+        assert!(config.general.debug);
+        assert_eq!(config.cache.ttl, 600);
+        assert_eq!(config.search.supported_languages, vec!["en", "zh"]);
+        assert_eq!(report.applied.len(), 5);
+        assert!(report.ignored.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_ignored_and_bad_values_rejected() {
+        let mut config = SeeSeaConfig::default();
+        let report = apply_overrides(
+            &mut config,
+            ENV_PREFIX,
+            vars(&[("SEESEA_SERVER__PROT", "9000"), ("SEESEA_CONFIG_FILE", "/etc/seesea.toml")]),
+        )
+        .unwrap();
+        assert_eq!(report.ignored, vec!["SEESEA_SERVER__PROT"]);
+
+        let err = apply_overrides(&mut config, ENV_PREFIX, vars(&[("SEESEA_SERVER__PORT", "http")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("SEESEA_SERVER__PORT"));
+    }
+
+    #[test]
+    fn test_partial_unset_optional_section_is_rejected() {
+        let mut config = SeeSeaConfig::default();
+        let err = apply_overrides(&mut config, ENV_PREFIX, vars(&[("SEESEA_NETWORK__MODE", "Dual")]))
+            .unwrap_err();
+        // 只给出部分字段时报告缺失的字段
+        assert!(err.to_string().contains("network.mode"));
+    }
+
+    #[test]
+    fn test_unset_optional_field_accepts_numeric_string() {
+        let mut config = SeeSeaConfig::default();
+        assert!(config.server.base_url.is_none());
+        apply_overrides(&mut config, ENV_PREFIX, vars(&[("SEESEA_SERVER__BASE_URL", "123")])).unwrap();
+        assert_eq!(config.server.base_url.as_deref(), Some("123"));
+    }
+}
@@ -16,7 +16,7 @@
 //!
 //! 提供灵活的配置文件加载功能
 
-use crate::config::{SeeSeaConfig, ConfigError, ConfigLoadResult};
+use crate::config::{SeeSeaConfig, ConfigError, ConfigLoadResult, apply_env_overrides};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use tokio::fs;
@@ -107,6 +107,14 @@ impl ConfigLoader {
 
             // 合并配置
             self.merge_config(&mut final_config, &mut config)?;
+
+            // SEESEA_* 字段覆盖直接作用于合并结果，不经过只合并部分字段的 merge_config
+            if matches!(source, ConfigSource::Environment) {
+                let report = apply_env_overrides(&mut final_config)?;
+                warnings.extend(
+                    report.ignored.iter().map(|key| format!("环境变量 {} 没有对应的配置字段，已忽略", key)),
+                );
+            }
         }
 
         // 应用后处理
@@ -165,6 +173,7 @@ impl ConfigLoader {
 
         // 应用环境变量覆盖
         self.apply_env_overrides(&mut config)?;
+        apply_env_overrides(&mut config)?;
 
         Ok(config)
     }
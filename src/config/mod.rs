@@ -33,12 +33,18 @@ pub mod engines;
 pub mod types;
 
 // 主配置类型
+#[cfg(feature = "native")]
 pub mod config;
 
 // 公共接口
+#[cfg(feature = "native")]
 pub mod on;
+#[cfg(feature = "native")]
 pub mod loader;
+#[cfg(feature = "native")]
 pub mod validator;
+#[cfg(feature = "native")]
+pub mod env;
 
 // 重新导出关键公共类型
 pub use common::{
@@ -55,7 +61,13 @@ pub use api::ApiConfig;
 pub use logging::LoggingConfig;
 pub use engines::EnginesConfig;
 pub use types::Environment;
+#[cfg(feature = "native")]
 pub use config::{SeeSeaConfig, ConfigLoadResult, ConfigSummary, ConfigError, ConfigSource};
+#[cfg(feature = "native")]
 pub use on::{ConfigManager, get_global_config, init_config, init_config_with_env};
+#[cfg(feature = "native")]
 pub use loader::ConfigLoader;
-pub use validator::{ConfigValidator, validate_config};
\ No newline at end of file
+#[cfg(feature = "native")]
+pub use env::{apply_env_overrides, EnvOverrideReport, ENV_PREFIX};
+#[cfg(feature = "native")]
+pub use validator::{ConfigValidator, ConfigFormat, check_config_str, validate_config};
\ No newline at end of file
@@ -17,7 +17,7 @@
 //! 提供配置加载、验证、管理的外部接口
 
 use crate::config::{
-    common::ConfigValidationResult, ConfigError, ConfigLoadResult, SeeSeaConfig,
+    common::ConfigValidationResult, apply_env_overrides, ConfigError, ConfigLoadResult, SeeSeaConfig,
 };
 use crate::config::config::ConfigSummary;
 use std::path::PathBuf;
@@ -38,7 +38,8 @@ impl ConfigManager {
     /// 创建新的配置管理器
     pub async fn new(config_path: Option<PathBuf>) -> Result<Self, ConfigError> {
         let config_path = config_path.unwrap_or_else(|| {
-            std::env::var("SEEA_CONFIG_FILE")
+            std::env::var("SEESEA_CONFIG_FILE")
+                .or_else(|_| std::env::var("SEEA_CONFIG_FILE"))
                 .map(PathBuf::from)
                 .unwrap_or_else(|_| PathBuf::from("config/default.toml"))
         });
@@ -77,6 +78,7 @@ impl ConfigManager {
 
         // 应用环境特定的覆盖
         Self::apply_environment_overrides(&mut config, environment);
+        Self::apply_field_overrides(&mut config)?;
 
         let manager = Self {
             config: Arc::new(RwLock::new(config)),
@@ -89,7 +91,8 @@ impl ConfigManager {
 
     /// 加载配置文件
     pub async fn load_config(&self) -> Result<ConfigLoadResult, ConfigError> {
-        let config = Self::load_from_file(&self.config_path).await?;
+        let mut config = Self::load_from_file(&self.config_path).await?;
+        Self::apply_field_overrides(&mut config)?;
         let validation_result = config.validate();
         let summary = config.get_summary();
 
@@ -190,6 +193,18 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// 应用 `SEESEA_*` 字段覆盖，忽略的变量记录为警告
+    fn apply_field_overrides(config: &mut SeeSeaConfig) -> Result<(), ConfigError> {
+        let report = apply_env_overrides(config)?;
+        if !report.applied.is_empty() {
+            tracing::info!("环境变量覆盖字段: {}", report.applied.join(", "));
+        }
+        for key in &report.ignored {
+            tracing::warn!("环境变量 {} 没有对应的配置字段，已忽略", key);
+        }
+        Ok(())
+    }
+
     /// 应用环境特定的覆盖
     fn apply_environment_overrides(config: &mut SeeSeaConfig, environment: &str) {
         // 从环境变量读取配置覆盖
@@ -16,9 +16,17 @@
 //!
 //! 提供详细的配置验证功能
 
+use std::path::Path;
+
+use crate::api::network::NetworkMode;
 use crate::config::{
     SeeSeaConfig, ConfigValidationResult, Environment, LogLevel, EngineLoadingMode,
+    CommonEngineType,
 };
+use crate::search::engine_config::EngineListConfig;
+
+/// 缓存 TTL 的合理上限（30 天），超过时给出警告
+const MAX_SANE_CACHE_TTL: u64 = 30 * 24 * 3600;
 
 /// 配置验证器
 pub struct ConfigValidator {
@@ -75,6 +83,9 @@ impl ConfigValidator {
         // 兼容性验证
         self.validate_compatibility(config, &mut result);
 
+        // 跨字段约束验证
+        self.validate_cross_fields(config, &mut result);
+
         // 自定义规则验证
         self.validate_custom_rules(config, &mut result);
 
@@ -316,6 +327,78 @@ impl ConfigValidator {
         }
     }
 
+    /// 验证跨字段约束（监听端口、引擎名、缓存 TTL）
+    ///
+    /// 这些约束原本要等到子系统构造时才会失败，这里提前给出带字段路径的错误。
+    fn validate_cross_fields(&self, config: &SeeSeaConfig, result: &mut ConfigValidationResult) {
+        // 双网络模式下内外网监听端口不能冲突
+        if let Some(network) = &config.network {
+            if let Err(e) = network.validate() {
+                result.add_error(format!("network: {}", e));
+            }
+
+            let mut listeners = Vec::new();
+            if network.internal.enabled && network.mode != NetworkMode::External {
+                listeners.push(("network.internal.port", network.internal.port));
+            }
+            if network.external.enabled && network.mode != NetworkMode::Internal {
+                listeners.push(("network.external.port", network.external.port));
+            }
+            if config.api.metrics.enabled {
+                listeners.push(("api.metrics.port", config.api.metrics.port));
+            }
+
+            for (field, port) in &listeners {
+                if *port == 0 {
+                    result.add_error(format!("{} 不能为 0，请指定 1-65535 之间的端口", field));
+                }
+            }
+            for (i, (field, port)) in listeners.iter().enumerate() {
+                if let Some((other, _)) = listeners[..i].iter().find(|(_, p)| p == port) {
+                    result.add_error(format!(
+                        "{} 与 {} 同为 {}，请为两者分配不同的端口",
+                        other, field, port
+                    ));
+                }
+            }
+        }
+
+        // 配置中的引擎必须是已知的内置引擎（自定义/API 引擎由外部声明，跳过）
+        let available = EngineListConfig::default().all_available_engines;
+        let mut names: Vec<&String> = config.engines.engines.keys().collect();
+        names.sort();
+        for name in names {
+            let engine = &config.engines.engines[name];
+            if matches!(engine.base.engine_type, CommonEngineType::Custom | CommonEngineType::Api) {
+                continue;
+            }
+            if !available.contains(name) {
+                result.add_error(format!(
+                    "engines.engines.{}: 未知引擎 \"{}\"，可用引擎: {}",
+                    name,
+                    name,
+                    available.join(", ")
+                ));
+            }
+        }
+
+        // 缓存 TTL 合理性
+        if config.cache.enable_result_cache && config.cache.ttl > 0 {
+            if config.cache.refresh_interval >= config.cache.ttl {
+                result.add_error(format!(
+                    "cache.refresh_interval ({}s) 必须小于 cache.ttl ({}s)，否则缓存条目在刷新前就已过期",
+                    config.cache.refresh_interval, config.cache.ttl
+                ));
+            }
+            if config.cache.ttl > MAX_SANE_CACHE_TTL {
+                result.add_warning(format!(
+                    "cache.ttl ({}s) 超过 30 天，搜索结果可能长期陈旧",
+                    config.cache.ttl
+                ));
+            }
+        }
+    }
+
     /// 验证自定义规则
     fn validate_custom_rules(&self, config: &SeeSeaConfig, result: &mut ConfigValidationResult) {
         for rule in &self.rules {
@@ -604,6 +687,42 @@ pub fn validate_config(config: &crate::config::config::SeeSeaConfig) -> ConfigVa
     validator.validate(config)
 }
 
+/// 配置文本格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML
+    Toml,
+    /// JSON
+    Json,
+}
+
+impl ConfigFormat {
+    /// 根据文件扩展名推断格式（无扩展名按 TOML 处理，不支持的扩展名返回 None）
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension().and_then(|s| s.to_str()) {
+            None | Some("toml") => Some(Self::Toml),
+            Some("json") => Some(Self::Json),
+            Some(_) => None,
+        }
+    }
+}
+
+/// 解析并完整验证一段配置文本
+///
+/// 解析失败时返回带行列位置的解析错误；解析成功则运行全部验证规则。
+/// 供 `seesea check-config` 与 `POST /api/admin/config/validate` 共用。
+pub fn check_config_str(content: &str, format: ConfigFormat) -> ConfigValidationResult {
+    let parsed: Result<SeeSeaConfig, String> = match format {
+        ConfigFormat::Toml => toml::from_str(content).map_err(|e| format!("TOML 解析错误: {}", e)),
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| format!("JSON 解析错误: {}", e)),
+    };
+
+    match parsed {
+        Ok(config) => validate_config(&config),
+        Err(e) => ConfigValidationResult::failure(vec![e]),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -648,4 +767,40 @@ mod tests {
         let score = validator.calculate_performance_score(&config);
         assert!(score >= 40);
     }
+
+    #[test]
+    fn test_dual_mode_port_conflict() {
+        let mut network = crate::api::network::NetworkConfig::default();
+        network.internal.port = network.external.port;
+
+        let mut config = SeeSeaConfig { network: Some(network), ..Default::default() };
+
+        let result = ConfigValidator::new().validate(&config);
+        assert!(result.errors.iter().any(|e| e.contains("network.internal.port 与 network.external.port")));
+
+        config.network.as_mut().unwrap().internal.port = 8081;
+        let result = ConfigValidator::new().validate(&config);
+        assert!(!result.errors.iter().any(|e| e.starts_with("network")));
+    }
+
+    #[test]
+    fn test_cache_refresh_interval_exceeds_ttl() {
+        let mut config = SeeSeaConfig::default();
+        config.cache.enable_result_cache = true;
+        config.cache.ttl = 60;
+        config.cache.refresh_interval = 300;
+
+        let result = ConfigValidator::new().validate(&config);
+        assert!(result.errors.iter().any(|e| e.contains("cache.refresh_interval")));
+    }
+
+    #[test]
+    fn test_check_config_str_reports_parse_error() {
+        let result = check_config_str("[server\nport = 1", ConfigFormat::Toml);
+        assert!(!result.is_valid);
+        assert!(result.errors[0].starts_with("TOML 解析错误"));
+
+        assert_eq!(ConfigFormat::from_path(Path::new("seesea.json")), Some(ConfigFormat::Json));
+        assert_eq!(ConfigFormat::from_path(Path::new("seesea.yaml")), None);
+    }
 }
\ No newline at end of file
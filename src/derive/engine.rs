@@ -15,7 +15,12 @@
 //! 搜索引擎核心 trait 定义
 
 use async_trait::async_trait;
+use tracing::Instrument;
 use crate::derive::types::*;
+use crate::net::client::HttpTransport;
+use crate::net::types::{RefererPolicy, RequestHeadersConfig, RequestOptions};
+use crate::search::engines::utils::cookie_header;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::error::Error;
 
@@ -44,14 +49,8 @@ pub trait SearchEngine: Send + Sync {
 
     /// 验证查询参数
     fn validate_query(&self, query: &SearchQuery) -> Result<(), ValidationError> {
-        // 基础验证
-        if query.query.trim().is_empty() {
-            return Err(ValidationError::EmptyQuery);
-        }
-
-        if query.query.len() > 1000 {
-            return Err(ValidationError::QueryTooLong);
-        }
+        // 基础验证（查询、页码、每页结果数）
+        query.validate()?;
 
         // 页面大小验证
         if query.page_size > self.info().capabilities.max_page_size {
@@ -65,6 +64,8 @@ pub trait SearchEngine: Send + Sync {
             return Err(ValidationError::UnsupportedTimeRange);
         }
 
+        // 不支持的地区不在这里报错，由 strip_unsupported_region 在搜索前去掉
+
         // 自定义参数验证
         for param in query.params.keys() {
             if !self.info().capabilities.supported_params.contains(param) {
@@ -74,6 +75,19 @@ pub trait SearchEngine: Send + Sync {
 
         Ok(())
     }
+
+    /// 去掉引擎不支持的地区
+    ///
+    /// 不支持的地区被忽略而不是报错，避免单个引擎拖垮多引擎搜索
+    fn strip_unsupported_region<'a>(&self, query: &'a SearchQuery) -> Cow<'a, SearchQuery> {
+        match &query.region {
+            Some(region) if !self.info().capabilities.supports_region(region) => {
+                tracing::debug!(engine = %self.info().name, region = %region, "region not supported, ignored");
+                Cow::Owned(SearchQuery { region: None, ..query.clone() })
+            }
+            _ => Cow::Borrowed(query),
+        }
+    }
 }
 
 /// 引擎健康状态
@@ -134,13 +148,15 @@ pub trait BaseEngine: SearchEngine {
     /// 默认搜索实现（使用模板方法模式）
     /// 
     /// 这个方法提供了标准的搜索流程：
-    /// 1. 验证查询参数
+    /// 1. 去掉不支持的地区并验证查询参数
     /// 2. 构建请求 URL
     /// 3. 发送 HTTP 请求
     /// 4. 解析响应
     /// 
     /// 实现者只需要实现抽象方法即可复用这个流程。
     async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        let query = &*self.strip_unsupported_region(query);
+
         // 1. 验证查询参数
         self.validate_query(query)?;
 
@@ -148,10 +164,14 @@ pub trait BaseEngine: SearchEngine {
         let url = self.build_url(query)?;
 
         // 3. 发送 HTTP 请求（抽象方法，由实现者提供）
-        let response = self.http_get(&url).await?;
+        let response = self.http_get(&url)
+            .instrument(tracing::debug_span!("engine.fetch"))
+            .await?;
 
         // 4. 解析响应
-        let result = self.parse_response(response, query).await?;
+        let result = self.parse_response(response, query)
+            .instrument(tracing::debug_span!("engine.parse"))
+            .await?;
 
         Ok(result)
     }
@@ -196,19 +216,31 @@ pub trait RequestResponseEngine: SearchEngine {
     /// 接收响应对象，返回搜索结果项列表
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>>;
 
+    /// 引擎发送请求使用的传输层
+    ///
+    /// 默认搜索实现从这里取得请求头装饰配置，未提供时使用默认配置
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        None
+    }
+
     /// 默认搜索实现（使用 request/response 模式）
     async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
         let start_time = std::time::Instant::now();
         
-        // 1. 准备请求参数
-        let mut params = RequestParams::from_query(query);
+        // 1. 准备请求参数（引擎不支持的地区不传给 request()）
+        let mut params = RequestParams::from_query(&self.strip_unsupported_region(query));
         self.request(&query.query, &mut params)?;
+        let default_headers = RequestHeadersConfig::default();
+        let headers_config = self.transport().and_then(|t| t.request_headers()).unwrap_or(&default_headers);
+        params.decorate(&self.info().name, headers_config);
 
         // 2. 发送请求
-        let resp = self.fetch(&params).await?;
+        let resp = self.fetch(&params)
+            .instrument(tracing::debug_span!("engine.fetch"))
+            .await?;
 
         // 3. 解析响应
-        let items = self.response(resp)?;
+        let items = tracing::debug_span!("engine.parse").in_scope(|| self.response(resp))?;
 
         // 4. 构建搜索结果
         Ok(SearchResult {
@@ -304,4 +336,245 @@ pub trait RetryableEngine: SearchEngine {
 
         Err(last_error.unwrap_or_else(|| "未知错误".into()))
     }
+}
+
+impl RequestParams {
+    /// 按配置装饰请求头（`Accept-Language`、`DNT`、Referer 策略和按引擎追加的请求头）
+    ///
+    /// 由 [`RequestResponseEngine::search`] 在 `request()` 之后调用，引擎已设置的
+    /// `Accept-Language` 和 `DNT` 保持不变
+    pub fn decorate(&mut self, engine: &str, config: &RequestHeadersConfig) {
+        if config.accept_language
+            && !self.has_header("Accept-Language")
+            && let Some(value) = self.language.as_deref().and_then(|lang| accept_language(lang, self.region.as_deref()))
+        {
+            self.headers.insert("Accept-Language".to_string(), value);
+        }
+        if config.dnt && !self.has_header("DNT") {
+            self.headers.insert("DNT".to_string(), "1".to_string());
+        }
+        match config.referer {
+            RefererPolicy::Engine => {}
+            RefererPolicy::Origin => {
+                self.remove_header("Referer");
+                if let Some(origin) = self.url.as_deref().and_then(|url| url::Url::parse(url).ok()) {
+                    self.headers.insert("Referer".to_string(), format!("{}/", origin.origin().ascii_serialization()));
+                }
+            }
+            RefererPolicy::None => self.remove_header("Referer"),
+        }
+        for (name, value) in config.engine_headers.get(engine).into_iter().flatten() {
+            self.remove_header(name);
+            self.headers.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// 转换为传输层的请求选项（请求头和合并为单个 `Cookie` 头的 cookies）
+    pub fn request_options(&self) -> RequestOptions {
+        let mut headers: Vec<(String, String)> = self.headers.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        if let Some(cookie) = cookie_header(&self.cookies) {
+            headers.push(("Cookie".to_string(), cookie));
+        }
+        RequestOptions { headers, ..Default::default() }
+    }
+
+    fn has_header(&self, name: &str) -> bool {
+        self.headers.keys().any(|k| k.eq_ignore_ascii_case(name))
+    }
+
+    fn remove_header(&mut self, name: &str) {
+        self.headers.retain(|k, _| !k.eq_ignore_ascii_case(name));
+    }
+}
+
+/// 由语言和地区生成 `Accept-Language`，如 `zh` + `CN` 得到 `zh-CN,zh;q=0.9`
+fn accept_language(language: &str, region: Option<&str>) -> Option<String> {
+    let mut parts = language.trim().split(['-', '_']);
+    let primary = parts.next().filter(|p| !p.is_empty() && *p != "all")?.to_ascii_lowercase();
+    let region = parts.next().or(region).map(str::trim).filter(|r| !r.is_empty());
+    Some(match region {
+        Some(region) => format!("{}-{},{};q=0.9", primary, region.to_ascii_uppercase(), primary),
+        None => primary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decorate_request_headers() {
+        let mut params = RequestParams {
+            url: Some("https://www.bing.com/search?q=rust".to_string()),
+            language: Some("zh".to_string()),
+            region: Some("cn".to_string()),
+            ..Default::default()
+        };
+        params.headers.insert("referer".to_string(), "https://example.com/".to_string());
+        let config = RequestHeadersConfig {
+            referer: RefererPolicy::Origin,
+            engine_headers: HashMap::from([(
+                "bing".to_string(),
+                HashMap::from([("User-Agent".to_string(), "SeeSea".to_string())]),
+            )]),
+            ..Default::default()
+        };
+        params.decorate("bing", &config);
+
+        assert_eq!(params.headers["Accept-Language"], "zh-CN,zh;q=0.9");
+        assert_eq!(params.headers["DNT"], "1");
+        assert_eq!(params.headers["Referer"], "https://www.bing.com/");
+        assert!(!params.headers.contains_key("referer"));
+        assert_eq!(params.headers["User-Agent"], "SeeSea");
+
+        // 引擎自己设置的 Accept-Language 保持不变，策略为 None 时去掉 Referer
+        let mut params = RequestParams { language: Some("en".to_string()), ..Default::default() };
+        params.headers.insert("Accept-Language".to_string(), "en-GB".to_string());
+        params.headers.insert("Referer".to_string(), "https://example.com/".to_string());
+        let config = RequestHeadersConfig { dnt: false, referer: RefererPolicy::None, ..Default::default() };
+        params.decorate("baidu", &config);
+        assert_eq!(params.headers.len(), 1);
+        assert_eq!(params.headers["Accept-Language"], "en-GB");
+    }
+
+    #[test]
+    fn test_accept_language() {
+        assert_eq!(accept_language("en", None).as_deref(), Some("en"));
+        assert_eq!(accept_language("zh-TW", Some("CN")).as_deref(), Some("zh-TW,zh;q=0.9"));
+        assert_eq!(accept_language("all", None), None);
+    }
+
+    /// 记录收到的地区的测试引擎，只支持美国地区
+    struct RegionProbe {
+        info: EngineInfo,
+        seen: std::sync::Mutex<Vec<Option<String>>>,
+    }
+
+    impl RegionProbe {
+        fn new() -> Self {
+            Self {
+                info: EngineInfo {
+                    name: "probe".to_string(),
+                    engine_type: EngineType::General,
+                    description: String::new(),
+                    status: EngineStatus::Active,
+                    categories: Vec::new(),
+                    capabilities: EngineCapabilities {
+                        result_types: vec![ResultType::Web],
+                        supported_params: Vec::new(),
+                        max_page_size: 10,
+                        supports_pagination: false,
+                        supports_time_range: false,
+                        supports_language_filter: false,
+                        supports_region_filter: true,
+                        supported_regions: vec!["US".to_string()],
+                        supports_safe_search: false,
+                        rate_limit: None,
+                    },
+                    about: AboutInfo::default(),
+                    shortcut: None,
+                    timeout: None,
+                    disabled: false,
+                    inactive: false,
+                    version: None,
+                    last_checked: None,
+                    using_tor_proxy: false,
+                    display_error_messages: false,
+                    tokens: Vec::new(),
+                    max_page: 1,
+                },
+                seen: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+
+        fn seen(&self) -> Vec<Option<String>> {
+            std::mem::take(&mut *self.seen.lock().unwrap())
+        }
+    }
+
+    #[async_trait]
+    impl SearchEngine for RegionProbe {
+        fn info(&self) -> &EngineInfo {
+            &self.info
+        }
+
+        async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+            <Self as RequestResponseEngine>::search(self, query).await
+        }
+    }
+
+    #[async_trait]
+    impl BaseEngine for RegionProbe {
+        type HttpClient = ();
+        type HttpResponse = ();
+
+        fn http_client(&self) -> &Self::HttpClient {
+            &()
+        }
+
+        fn build_url(&self, query: &SearchQuery) -> Result<String, ValidationError> {
+            self.seen.lock().unwrap().push(query.region.clone());
+            Ok("https://probe.example.com/".to_string())
+        }
+
+        async fn http_get(&self, _url: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Ok(())
+        }
+
+        async fn parse_response(&self, _response: (), _query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+            Ok(SearchResult {
+                engine_name: self.info.name.clone(),
+                total_results: None,
+                elapsed_ms: 0,
+                items: Vec::new(),
+                pagination: None,
+                suggestions: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl RequestResponseEngine for RegionProbe {
+        type Response = ();
+
+        fn request(&self, _query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+            self.seen.lock().unwrap().push(params.region.clone());
+            Ok(())
+        }
+
+        async fn fetch(&self, _params: &RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+            Ok(())
+        }
+
+        fn response(&self, _resp: ()) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_region_is_ignored_on_both_paths() {
+        let engine = RegionProbe::new();
+        let query = |region: &str| SearchQuery {
+            query: "rust".to_string(),
+            region: Some(region.to_string()),
+            ..Default::default()
+        };
+
+        for region in ["en-US", "de-DE"] {
+            BaseEngine::search(&engine, &query(region)).await.unwrap();
+            RequestResponseEngine::search(&engine, &query(region)).await.unwrap();
+        }
+        let us = Some("en-US".to_string());
+        assert_eq!(engine.seen(), vec![us.clone(), us, None, None]);
+        assert!(engine.validate_query(&query("de-DE")).is_ok());
+    }
+
+    #[test]
+    fn test_request_options_merges_cookies() {
+        let mut params = RequestParams::default();
+        params.cookies.insert("a".to_string(), "1".to_string());
+        let options = params.request_options();
+        assert_eq!(options.headers, vec![("Cookie".to_string(), "a=1".to_string())]);
+    }
 }
\ No newline at end of file
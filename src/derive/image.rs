@@ -0,0 +1,108 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 图片结果的统一元数据
+//!
+//! 与 [`VideoMetadata`](super::VideoMetadata) 相同，图片引擎把原图地址、尺寸、格式、
+//! 来源页和授权信息整理为 [`ImageMetadata`]，存放在 [`IMAGE_METADATA_KEY`] 键下，
+//! 前端据此排版图片网格、打开大图预览。
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SearchResultItem;
+
+/// 图片元数据在 `SearchResultItem::metadata` 中的键
+pub const IMAGE_METADATA_KEY: &str = "image";
+
+/// 图片元数据
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    /// 原图地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    /// 宽度（像素）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// 高度（像素）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// 格式（小写，如 `jpeg`、`png`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// 图片所在页面
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_page: Option<String>,
+    /// 来源站点名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 作者/摄影师
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// 作者主页
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_url: Option<String>,
+    /// 授权协议名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// 授权协议地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_url: Option<String>,
+    /// 主色调（`#rrggbb`），可用作加载占位
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl ImageMetadata {
+    /// 是否没有任何字段
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl SearchResultItem {
+    /// 读取图片元数据
+    pub fn image(&self) -> Option<ImageMetadata> {
+        self.metadata
+            .get(IMAGE_METADATA_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// 写入图片元数据，全部字段为空时移除
+    pub fn set_image(&mut self, image: &ImageMetadata) {
+        if image.is_empty() {
+            self.metadata.remove(IMAGE_METADATA_KEY);
+        } else if let Ok(json) = serde_json::to_string(image) {
+            self.metadata.insert(IMAGE_METADATA_KEY.to_string(), json);
+        }
+    }
+}
+
+/// 解析分辨率文本：`"1200 × 800"`、`"1200x800"`
+pub fn parse_resolution(text: &str) -> Option<(u32, u32)> {
+    let (width, height) = text.split_once(['×', 'x', 'X', '*'])?;
+    Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(parse_resolution("1200 × 800"), Some((1200, 800)));
+        assert_eq!(parse_resolution("640x480"), Some((640, 480)));
+        assert_eq!(parse_resolution("png"), None);
+        assert_eq!(parse_resolution(""), None);
+    }
+}
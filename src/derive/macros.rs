@@ -203,6 +203,7 @@ macro_rules! engine_info {
                 supports_time_range: $supports_time_range,
                 supports_language_filter: $supports_language_filter,
                 supports_region_filter: $supports_region_filter,
+                supported_regions: Vec::new(),
                 supports_safe_search: $supports_safe_search,
                 rate_limit: Some(60),
             },
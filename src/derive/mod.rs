@@ -30,15 +30,29 @@
 //! - **可扩展**: trait 支持灵活的功能组合
 
 pub mod types;
+#[cfg(feature = "native")]
 pub mod engine;
 pub mod result;
 pub mod query;
 pub mod macros;
 pub mod rss;
+pub mod image;
+pub mod video;
+pub mod torrent;
+pub mod podcast;
+pub mod security;
+pub mod place;
 
 // 重新导出主要类型
 pub use types::*;
+#[cfg(feature = "native")]
 pub use engine::*;
 pub use result::*;
 pub use query::*;
-pub use rss::*;
\ No newline at end of file
+pub use rss::*;
+pub use image::*;
+pub use video::*;
+pub use torrent::*;
+pub use podcast::*;
+pub use security::*;
+pub use place::*;
@@ -0,0 +1,66 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 地点结果的统一元数据
+//!
+//! `map` 分类的引擎把坐标、地点类型和结构化地址整理为 [`PlaceMetadata`]，
+//! 存放在 [`PLACE_METADATA_KEY`] 键下，结果类型为 [`ResultType::Map`](super::ResultType::Map)。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::types::SearchResultItem;
+
+/// 地点元数据在 `SearchResultItem::metadata` 中的键
+pub const PLACE_METADATA_KEY: &str = "place";
+
+/// 地点元数据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaceMetadata {
+    /// 纬度
+    pub lat: f64,
+    /// 经度
+    pub lon: f64,
+    /// 地点类别（OSM 的 class，如 `amenity`、`boundary`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 地点类型（OSM 的 type，如 `restaurant`、`city`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place_type: Option<String>,
+    /// 结构化地址（如 `road`、`city`、`country`）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub address: BTreeMap<String, String>,
+    /// 边界框：南、北、西、东
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bounding_box: Option<[f64; 4]>,
+    /// OpenStreetMap 对象（如 `way/123456`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osm_id: Option<String>,
+}
+
+impl SearchResultItem {
+    /// 读取地点元数据
+    pub fn place(&self) -> Option<PlaceMetadata> {
+        self.metadata
+            .get(PLACE_METADATA_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// 写入地点元数据
+    pub fn set_place(&mut self, place: &PlaceMetadata) {
+        if let Ok(json) = serde_json::to_string(place) {
+            self.metadata.insert(PLACE_METADATA_KEY.to_string(), json);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 播客结果的统一元数据
+//!
+//! 与 [`TorrentMetadata`](super::TorrentMetadata) 相同，`podcasts` 分类的引擎把 RSS 条目中
+//! 可播放的附件（音频或视频）整理为 [`PodcastMetadata`]，存放在 [`PODCAST_METADATA_KEY`] 键下。
+
+use serde::{Deserialize, Serialize};
+
+use super::rss::{RssEnclosure, RssEnclosureKind};
+use super::types::SearchResultItem;
+
+/// 播客元数据在 `SearchResultItem::metadata` 中的键
+pub const PODCAST_METADATA_KEY: &str = "podcast";
+
+/// 播客元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PodcastMetadata {
+    /// 可直接播放的媒体地址
+    pub media_url: String,
+    /// 媒体类型（`audio` 或 `video`）
+    pub kind: RssEnclosureKind,
+    /// MIME 类型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// 文件大小（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_bytes: Option<u64>,
+    /// 播放时长（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// 节目名（来源 feed 标题）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show: Option<String>,
+}
+
+impl PodcastMetadata {
+    /// 从附件创建，附件不可播放时返回 None
+    pub fn from_enclosure(enclosure: &RssEnclosure, show: Option<String>) -> Option<Self> {
+        enclosure.kind.is_playable().then(|| Self {
+            media_url: enclosure.url.clone(),
+            kind: enclosure.kind,
+            mime_type: enclosure.mime_type.clone(),
+            length_bytes: enclosure.length,
+            duration_secs: enclosure.duration_secs,
+            show,
+        })
+    }
+}
+
+impl SearchResultItem {
+    /// 读取播客元数据
+    pub fn podcast(&self) -> Option<PodcastMetadata> {
+        self.metadata
+            .get(PODCAST_METADATA_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// 写入播客元数据
+    pub fn set_podcast(&mut self, podcast: &PodcastMetadata) {
+        if let Ok(json) = serde_json::to_string(podcast) {
+            self.metadata.insert(PODCAST_METADATA_KEY.to_string(), json);
+        }
+    }
+}
@@ -215,7 +215,7 @@ pub trait QueryTransformer {
         params.push(format!("start={}", (query.page - 1) * query.page_size));
 
         if let Some(time_range) = query.time_range {
-            params.push(format!("time_range={:?}", time_range));
+            params.push(format!("time_range={}", time_range));
         }
 
         for (key, value) in &query.params {
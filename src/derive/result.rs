@@ -104,12 +104,7 @@ pub trait ResultFilter {
     /// 规范化URL
     fn normalize_url(&self, url: &str) -> String {
         // 移除协议、www、尾部斜杠等
-        url.to_lowercase()
-            .replace("https://", "")
-            .replace("http://", "")
-            .replace("www.", "")
-            .trim_end_matches('/')
-            .to_string()
+        crate::url_utils::dedup_key(url)
     }
 
     /// 限制结果数量
@@ -40,12 +40,27 @@ pub struct RssFeedItem {
     pub guid: Option<String>,
     /// 附件 (例如图片、音频)
     pub enclosures: Vec<RssEnclosure>,
+    /// 提取的关键词标签（入库时生成，小写）
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 转载同一内容的其他来源（入库去重时记录在保留的条目上）
+    #[serde(default)]
+    pub alternate_sources: Vec<RssAlternateSource>,
     /// 自定义字段
     pub custom_fields: HashMap<String, String>,
 }
 
+/// 转载同一内容的来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RssAlternateSource {
+    /// 来源 feed URL
+    pub feed_url: String,
+    /// 该来源中的条目链接
+    pub link: String,
+}
+
 /// RSS 附件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RssEnclosure {
     /// URL
     pub url: String,
@@ -53,6 +68,62 @@ pub struct RssEnclosure {
     pub mime_type: Option<String>,
     /// 大小（字节）
     pub length: Option<u64>,
+    /// 附件类型（按 MIME 类型判断，缺失时按扩展名）
+    #[serde(default)]
+    pub kind: RssEnclosureKind,
+    /// 播放时长（秒，来自 `itunes:duration`）
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+}
+
+/// RSS 附件类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RssEnclosureKind {
+    /// 音频（播客）
+    Audio,
+    /// 视频
+    Video,
+    /// 图片
+    Image,
+    /// 其他
+    #[default]
+    Other,
+}
+
+impl RssEnclosureKind {
+    /// 按 MIME 类型判断，缺失或无法识别时按 URL 扩展名判断
+    pub fn detect(mime_type: Option<&str>, url: &str) -> Self {
+        if let Some(mime) = mime_type {
+            let mime = mime.trim().to_ascii_lowercase();
+            match mime.split('/').next() {
+                Some("audio") => return Self::Audio,
+                Some("video") => return Self::Video,
+                Some("image") => return Self::Image,
+                _ => {}
+            }
+        }
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).unwrap_or_default();
+        match extension.as_str() {
+            "mp3" | "m4a" | "aac" | "ogg" | "oga" | "opus" | "wav" | "flac" => Self::Audio,
+            "mp4" | "m4v" | "mov" | "webm" | "mkv" => Self::Video,
+            "jpg" | "jpeg" | "png" | "gif" | "webp" => Self::Image,
+            _ => Self::Other,
+        }
+    }
+
+    /// 能否在页面中直接播放
+    pub fn is_playable(self) -> bool {
+        matches!(self, Self::Audio | Self::Video)
+    }
+}
+
+impl RssFeedItem {
+    /// 第一个可播放（音频或视频）的附件
+    pub fn playable_enclosure(&self) -> Option<&RssEnclosure> {
+        self.enclosures.iter().find(|enclosure| enclosure.kind.is_playable())
+    }
 }
 
 /// RSS Feed 元数据
@@ -0,0 +1,73 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果的安全标记
+//!
+//! 聚合器的 `security` 阶段按 URL 给结果打上 [`SecurityFlag`]（明文 HTTP、短链接、跟踪链接），
+//! 以 JSON 数组存放在 [`SECURITY_FLAGS_KEY`] 键下，供界面显示徽标或在严格模式下丢弃。
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SearchResultItem;
+
+/// 安全标记在 `SearchResultItem::metadata` 中的键
+pub const SECURITY_FLAGS_KEY: &str = "security_flags";
+
+/// 安全标记
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecurityFlag {
+    /// 明文 HTTP 地址
+    Insecure,
+    /// 已知的短链接服务
+    Shortener,
+    /// 已知的广告或点击跟踪跳转
+    Tracker,
+}
+
+impl SecurityFlag {
+    /// 标记名（与序列化结果相同）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityFlag::Insecure => "insecure",
+            SecurityFlag::Shortener => "shortener",
+            SecurityFlag::Tracker => "tracker",
+        }
+    }
+}
+
+impl std::fmt::Display for SecurityFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SearchResultItem {
+    /// 读取安全标记
+    pub fn security_flags(&self) -> Vec<SecurityFlag> {
+        self.metadata
+            .get(SECURITY_FLAGS_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// 写入安全标记，没有标记时移除
+    pub fn set_security_flags(&mut self, flags: &[SecurityFlag]) {
+        if flags.is_empty() {
+            self.metadata.remove(SECURITY_FLAGS_KEY);
+        } else if let Ok(json) = serde_json::to_string(flags) {
+            self.metadata.insert(SECURITY_FLAGS_KEY.to_string(), json);
+        }
+    }
+}
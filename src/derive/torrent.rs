@@ -0,0 +1,76 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 种子结果的统一元数据
+//!
+//! 与 [`VideoMetadata`](super::VideoMetadata) 相同，`files_p2p` 分类的引擎把做种数、
+//! 下载数、大小和磁力链接整理为 [`TorrentMetadata`]，存放在 [`TORRENT_METADATA_KEY`] 键下。
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SearchResultItem;
+
+/// 种子元数据在 `SearchResultItem::metadata` 中的键
+pub const TORRENT_METADATA_KEY: &str = "torrent";
+
+/// 种子元数据
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TorrentMetadata {
+    /// 做种数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeders: Option<u64>,
+    /// 下载数（peers 减去做种数）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leechers: Option<u64>,
+    /// 总大小（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// 磁力链接
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub magnet_url: Option<String>,
+    /// .torrent 文件地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torrent_url: Option<String>,
+    /// info hash（小写十六进制）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_hash: Option<String>,
+    /// 文件数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<u64>,
+}
+
+impl TorrentMetadata {
+    /// 是否没有任何字段
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl SearchResultItem {
+    /// 读取种子元数据
+    pub fn torrent(&self) -> Option<TorrentMetadata> {
+        self.metadata
+            .get(TORRENT_METADATA_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// 写入种子元数据，全部字段为空时移除
+    pub fn set_torrent(&mut self, torrent: &TorrentMetadata) {
+        if torrent.is_empty() {
+            self.metadata.remove(TORRENT_METADATA_KEY);
+        } else if let Ok(json) = serde_json::to_string(torrent) {
+            self.metadata.insert(TORRENT_METADATA_KEY.to_string(), json);
+        }
+    }
+}
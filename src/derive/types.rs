@@ -86,6 +86,115 @@ impl Default for SearchQuery {
     }
 }
 
+/// 查询字符串的最大字符数
+pub const MAX_QUERY_CHARS: usize = 1000;
+
+/// 每页结果数上限
+pub const MAX_PAGE_SIZE: usize = 100;
+
+impl SearchQuery {
+    /// 创建查询构建器
+    ///
+    /// ```
+    /// use seesea_core::derive::{SearchQuery, TimeRange};
+    ///
+    /// let query = SearchQuery::builder().query("rust").page(2).time_range(TimeRange::Week).build().unwrap();
+    /// assert_eq!(query.page, 2);
+    /// ```
+    pub fn builder() -> SearchQueryBuilder {
+        SearchQueryBuilder::default()
+    }
+
+    /// 检查查询满足引擎依赖的约束：查询非空且不超过 [`MAX_QUERY_CHARS`] 个字符，
+    /// 页码从 1 开始，每页结果数在 1 到 [`MAX_PAGE_SIZE`] 之间
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.query.trim().is_empty() {
+            return Err(ValidationError::EmptyQuery);
+        }
+        if self.query.chars().count() > MAX_QUERY_CHARS {
+            return Err(ValidationError::QueryTooLong);
+        }
+        if self.page == 0 {
+            return Err(ValidationError::InvalidParameter("page 从 1 开始".to_string()));
+        }
+        if self.page_size == 0 {
+            return Err(ValidationError::InvalidParameter("page_size 不能为 0".to_string()));
+        }
+        if self.page_size > MAX_PAGE_SIZE {
+            return Err(ValidationError::PageSizeTooLarge { max_size: MAX_PAGE_SIZE });
+        }
+        Ok(())
+    }
+}
+
+/// [`SearchQuery`] 构建器，[`build`](Self::build) 时校验查询
+#[derive(Debug, Clone, Default)]
+pub struct SearchQueryBuilder {
+    query: SearchQuery,
+}
+
+impl SearchQueryBuilder {
+    /// 查询关键词（首尾空白会被去掉）
+    pub fn query(mut self, query: impl Into<String>) -> Self {
+        self.query.query = query.into().trim().to_string();
+        self
+    }
+
+    /// 搜索引擎类型
+    pub fn engine_type(mut self, engine_type: EngineType) -> Self {
+        self.query.engine_type = engine_type;
+        self
+    }
+
+    /// 语言偏好
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.query.language = Some(language.into());
+        self
+    }
+
+    /// 地区偏好
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.query.region = Some(region.into());
+        self
+    }
+
+    /// 页码（从 1 开始）
+    pub fn page(mut self, page: usize) -> Self {
+        self.query.page = page;
+        self
+    }
+
+    /// 每页结果数
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.query.page_size = page_size;
+        self
+    }
+
+    /// 安全搜索级别
+    pub fn safe_search(mut self, level: crate::config::common::SafeSearchLevel) -> Self {
+        self.query.safe_search = level;
+        self
+    }
+
+    /// 时间范围（`Any` 等同于不限制）
+    pub fn time_range(mut self, time_range: TimeRange) -> Self {
+        self.query.time_range = (time_range != TimeRange::Any).then_some(time_range);
+        self
+    }
+
+    /// 自定义参数
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.params.insert(key.into(), value.into());
+        self
+    }
+
+    /// 校验并生成查询
+    pub fn build(self) -> Result<SearchQuery, ValidationError> {
+        self.query.validate()?;
+        Ok(self.query)
+    }
+}
+
 /// 请求参数（类似 searxng 的 params）
 ///
 /// 用于构建和传递 HTTP 请求的参数
@@ -105,8 +214,10 @@ pub struct RequestParams {
     pub pageno: usize,
     /// 语言
     pub language: Option<String>,
-    /// 时间范围
-    pub time_range: Option<String>,
+    /// 地区（如 `en-US` 或 `US`，引擎不支持时为 None）
+    pub region: Option<String>,
+    /// 时间范围（`Any` 不会出现，不限时间时为 None）
+    pub time_range: Option<TimeRange>,
     /// 安全搜索级别（0, 1, 2）
     pub safesearch: i32,
     /// 自定义参数
@@ -123,6 +234,7 @@ impl Default for RequestParams {
             cookies: HashMap::new(),
             pageno: 1,
             language: None,
+            region: None,
             time_range: None,
             safesearch: 0,
             custom: HashMap::new(),
@@ -132,11 +244,14 @@ impl Default for RequestParams {
 
 impl RequestParams {
     /// 从 SearchQuery 创建 RequestParams
+    ///
+    /// `pageno` 至少为 1，引擎可以直接用 `pageno - 1` 计算偏移
     pub fn from_query(query: &SearchQuery) -> Self {
         let mut params = Self::default();
-        params.pageno = query.page;
+        params.pageno = query.page.max(1);
         params.language = query.language.clone();
-        params.time_range = query.time_range.map(|tr| format!("{:?}", tr).to_lowercase());
+        params.region = query.region.clone();
+        params.time_range = query.time_range.filter(|tr| *tr != TimeRange::Any);
         
         // 将 SafeSearchLevel 转换为数字
         params.safesearch = match query.safe_search {
@@ -151,8 +266,11 @@ impl RequestParams {
 }
 
 /// 时间范围
+///
+/// 字符串形式（配置、API 参数、引擎请求）统一由 [`FromStr`](std::str::FromStr) 解析，
+/// 引擎拿到的 [`RequestParams::time_range`] 已是枚举，无需各自匹配字符串
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(try_from = "String", into = "String")]
 pub enum TimeRange {
     /// 任何时间
     Any,
@@ -174,6 +292,70 @@ impl Default for TimeRange {
     }
 }
 
+impl TimeRange {
+    /// 时间范围的长度（`Any` 为 None）
+    pub fn duration(self) -> Option<chrono::Duration> {
+        match self {
+            Self::Any => None,
+            Self::Hour => Some(chrono::Duration::hours(1)),
+            Self::Day => Some(chrono::Duration::days(1)),
+            Self::Week => Some(chrono::Duration::weeks(1)),
+            Self::Month => Some(chrono::Duration::days(30)),
+            Self::Year => Some(chrono::Duration::days(365)),
+        }
+    }
+
+    /// 小写名称（与序列化形式一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+}
+
+impl std::fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TimeRange {
+    type Err = String;
+
+    /// 解析时间范围，不区分大小写，接受单字母缩写（`h`、`d`、`w`、`m`、`y`）；
+    /// 空字符串和 `all` 视为 `Any`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "any" | "all" => Ok(Self::Any),
+            "hour" | "h" => Ok(Self::Hour),
+            "day" | "d" => Ok(Self::Day),
+            "week" | "w" => Ok(Self::Week),
+            "month" | "m" => Ok(Self::Month),
+            "year" | "y" => Ok(Self::Year),
+            _ => Err(format!("不支持的时间范围: {}（可选 any、hour、day、week、month、year）", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeRange> for String {
+    fn from(time_range: TimeRange) -> Self {
+        time_range.as_str().to_string()
+    }
+}
+
 /// 结果类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -199,6 +381,7 @@ pub enum ResultType {
     /// 文件
     File,
     /// 地图/位置
+    #[serde(alias = "place")]
     Map,
     /// 其他
     Other,
@@ -288,12 +471,43 @@ pub struct EngineCapabilities {
     pub supports_language_filter: bool,
     /// 是否支持地区过滤
     pub supports_region_filter: bool,
+    /// 支持的地区（ISO 3166-1 国家代码，为空表示不限制）
+    #[serde(default)]
+    pub supported_regions: Vec<String>,
     /// 是否支持安全搜索
     pub supports_safe_search: bool,
     /// 请求频率限制（每分钟请求数）
     pub rate_limit: Option<usize>,
 }
 
+impl EngineCapabilities {
+    /// 检查是否支持指定地区
+    ///
+    /// 地区可以是国家代码（`US`）或语言-国家代码（`en-US`、`zh_CN`）
+    pub fn supports_region(&self, region: &str) -> bool {
+        if !self.supports_region_filter {
+            return false;
+        }
+        if self.supported_regions.is_empty() {
+            return true;
+        }
+        region_country(region)
+            .is_some_and(|country| self.supported_regions.iter().any(|r| r.eq_ignore_ascii_case(&country)))
+    }
+}
+
+/// 从地区代码中提取国家代码（大写）
+///
+/// `en-US`、`zh_cn`、`US` 分别返回 `US`、`CN`、`US`；无法识别时返回 None
+pub fn region_country(region: &str) -> Option<String> {
+    let country = region.trim().rsplit(['-', '_']).next()?;
+    if country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(country.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
 /// 搜索引擎状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -386,7 +600,7 @@ pub struct EngineInfo {
 }
 
 /// 验证错误
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValidationError {
     /// 查询不能为空
     EmptyQuery,
@@ -403,6 +617,9 @@ pub enum ValidationError {
     /// 不支持的参数
     UnsupportedParameter(String),
 
+    /// 不支持的地区
+    UnsupportedRegion(String),
+
     /// 参数值无效
     InvalidParameter(String),
 }
@@ -415,9 +632,74 @@ impl std::fmt::Display for ValidationError {
             ValidationError::PageSizeTooLarge { max_size } => write!(f, "页面大小超出限制，最大{}个结果", max_size),
             ValidationError::UnsupportedTimeRange => write!(f, "不支持时间范围过滤"),
             ValidationError::UnsupportedParameter(param) => write!(f, "不支持的参数: {}", param),
+            ValidationError::UnsupportedRegion(region) => write!(f, "不支持的地区: {}", region),
             ValidationError::InvalidParameter(param) => write!(f, "参数值无效: {}", param),
         }
     }
 }
 
-impl std::error::Error for ValidationError {}
\ No newline at end of file
+impl std::error::Error for ValidationError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_query_builder() {
+        let query = SearchQuery::builder()
+            .query("  rust  ")
+            .page(2)
+            .page_size(20)
+            .language("zh")
+            .time_range(TimeRange::Week)
+            .param("site", "docs.rs")
+            .build()
+            .unwrap();
+        assert_eq!(query.query, "rust");
+        assert_eq!((query.page, query.page_size), (2, 20));
+        assert_eq!(query.language.as_deref(), Some("zh"));
+        assert_eq!(query.time_range, Some(TimeRange::Week));
+        assert_eq!(query.params["site"], "docs.rs");
+
+        let query = SearchQuery::builder().query("rust").time_range(TimeRange::Any).build().unwrap();
+        assert_eq!((query.page, query.time_range), (1, None));
+    }
+
+    #[test]
+    fn test_search_query_builder_validation() {
+        assert_eq!(SearchQuery::builder().query("   ").build().unwrap_err(), ValidationError::EmptyQuery);
+        assert_eq!(
+            SearchQuery::builder().query("a".repeat(MAX_QUERY_CHARS + 1)).build().unwrap_err(),
+            ValidationError::QueryTooLong
+        );
+        assert!(matches!(
+            SearchQuery::builder().query("rust").page(0).build(),
+            Err(ValidationError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            SearchQuery::builder().query("rust").page_size(MAX_PAGE_SIZE + 1).build().unwrap_err(),
+            ValidationError::PageSizeTooLarge { max_size: MAX_PAGE_SIZE }
+        );
+    }
+
+    #[test]
+    fn test_request_params_page_starts_at_one() {
+        let query = SearchQuery { query: "rust".to_string(), page: 0, ..Default::default() };
+        assert_eq!(RequestParams::from_query(&query).pageno, 1);
+    }
+
+    #[test]
+    fn test_time_range_parse() {
+        assert_eq!("Week".parse::<TimeRange>(), Ok(TimeRange::Week));
+        assert_eq!("h".parse::<TimeRange>(), Ok(TimeRange::Hour));
+        assert_eq!("".parse::<TimeRange>(), Ok(TimeRange::Any));
+        assert!("decade".parse::<TimeRange>().is_err());
+
+        assert_eq!(serde_json::to_string(&TimeRange::Month).unwrap(), r#""month""#);
+        assert_eq!(serde_json::from_str::<TimeRange>(r#""d""#).unwrap(), TimeRange::Day);
+
+        let query = SearchQuery { query: "rust".to_string(), time_range: Some(TimeRange::Hour), ..Default::default() };
+        assert_eq!(RequestParams::from_query(&query).time_range, Some(TimeRange::Hour));
+        let query = SearchQuery { query: "rust".to_string(), time_range: Some(TimeRange::Any), ..Default::default() };
+        assert_eq!(RequestParams::from_query(&query).time_range, None);
+    }
+}
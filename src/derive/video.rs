@@ -0,0 +1,195 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 视频结果的统一元数据
+//!
+//! 各视频引擎把时长、播放量、播放器地址整理为 [`VideoMetadata`]，
+//! 以 JSON 形式存放在结果元数据的 [`VIDEO_METADATA_KEY`] 键下，API 层再还原为类型化字段。
+
+use serde::{Deserialize, Serialize};
+
+use super::types::SearchResultItem;
+
+/// 视频元数据在 `SearchResultItem::metadata` 中的键
+pub const VIDEO_METADATA_KEY: &str = "video";
+
+/// 视频元数据
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoMetadata {
+    /// 时长（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// 播放量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_count: Option<u64>,
+    /// 作者/UP 主
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// 可嵌入的播放器地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embed_url: Option<String>,
+}
+
+impl VideoMetadata {
+    /// 是否没有任何字段
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl SearchResultItem {
+    /// 读取视频元数据
+    pub fn video(&self) -> Option<VideoMetadata> {
+        self.metadata
+            .get(VIDEO_METADATA_KEY)
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// 写入视频元数据，全部字段为空时移除
+    pub fn set_video(&mut self, video: &VideoMetadata) {
+        if video.is_empty() {
+            self.metadata.remove(VIDEO_METADATA_KEY);
+        } else if let Ok(json) = serde_json::to_string(video) {
+            self.metadata.insert(VIDEO_METADATA_KEY.to_string(), json);
+        }
+    }
+}
+
+/// 解析时长文本：`"12:34"`、`"1:02:03"` 或纯秒数
+pub fn parse_duration(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let mut seconds = 0u64;
+    let parts: Vec<&str> = text.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        seconds = seconds.checked_mul(60)?.checked_add(part.trim().parse().ok()?)?;
+    }
+    Some(seconds)
+}
+
+/// 解析播放量文本：`"1520342"`、`"1,520,342"`、`"152.3万"`、`"1.2亿次播放"`、`"3.4K views"`
+pub fn parse_view_count(text: &str) -> Option<u64> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    let end = text
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_digit() && *c != '.')
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let number: f64 = text[..end].parse().ok()?;
+    let multiplier = match text[end..].chars().next() {
+        Some('万') => 1e4,
+        Some('亿') => 1e8,
+        Some('K' | 'k') => 1e3,
+        Some('M' | 'm') => 1e6,
+        Some('B' | 'b') => 1e9,
+        _ => 1.0,
+    };
+    Some((number * multiplier).round() as u64)
+}
+
+/// 根据视频页地址推导常见站点的嵌入播放器地址
+pub fn embed_url_for(page_url: &str) -> Option<String> {
+    let url = url::Url::parse(page_url).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").trim_start_matches("m.");
+    let last_segment = url.path_segments()?.rfind(|s| !s.is_empty())?;
+
+    match host {
+        "bilibili.com" => {
+            let id = last_segment;
+            if id.starts_with("BV") {
+                Some(format!("https://player.bilibili.com/player.html?bvid={}&autoplay=0", id))
+            } else {
+                id.strip_prefix("av")
+                    .map(|aid| format!("https://player.bilibili.com/player.html?aid={}&autoplay=0", aid))
+            }
+        }
+        "v.qq.com" => {
+            let vid = last_segment.trim_end_matches(".html");
+            Some(format!("https://v.qq.com/txp/iframe/player.html?vid={}", vid))
+        }
+        "youtube.com" => url
+            .query_pairs()
+            .find(|(k, _)| k == "v")
+            .map(|(_, v)| format!("https://www.youtube-nocookie.com/embed/{}", v)),
+        "youtu.be" => Some(format!("https://www.youtube-nocookie.com/embed/{}", last_segment)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_and_views() {
+        assert_eq!(parse_duration("12:34"), Some(754));
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration("95"), Some(95));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("直播中"), None);
+
+        assert_eq!(parse_view_count("1,520,342"), Some(1_520_342));
+        assert_eq!(parse_view_count("152.3万次播放"), Some(1_523_000));
+        assert_eq!(parse_view_count("1.2亿"), Some(120_000_000));
+        assert_eq!(parse_view_count("3.4K views"), Some(3_400));
+        assert_eq!(parse_view_count("--"), None);
+    }
+
+    #[test]
+    fn test_embed_url_and_metadata_roundtrip() {
+        assert_eq!(
+            embed_url_for("https://www.bilibili.com/video/BV1hp4y1k7SV/").as_deref(),
+            Some("https://player.bilibili.com/player.html?bvid=BV1hp4y1k7SV&autoplay=0")
+        );
+        assert_eq!(
+            embed_url_for("https://v.qq.com/x/page/k3356x0bzi4.html").as_deref(),
+            Some("https://v.qq.com/txp/iframe/player.html?vid=k3356x0bzi4")
+        );
+        assert_eq!(
+            embed_url_for("https://www.youtube.com/watch?v=dQw4w9WgXcQ").as_deref(),
+            Some("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ")
+        );
+        assert_eq!(embed_url_for("https://example.com/video/1"), None);
+
+        let mut item = SearchResultItem {
+            title: "Rust".to_string(),
+            url: "https://www.bilibili.com/video/BV1hp4y1k7SV".to_string(),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score: 1.0,
+            result_type: crate::derive::ResultType::Video,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: Default::default(),
+        };
+        assert_eq!(item.video(), None);
+        let video = VideoMetadata {
+            duration_secs: Some(754),
+            view_count: Some(42),
+            ..Default::default()
+        };
+        item.set_video(&video);
+        assert_eq!(item.metadata[VIDEO_METADATA_KEY], r#"{"duration_secs":754,"view_count":42}"#);
+        assert_eq!(item.video(), Some(video));
+        item.set_video(&VideoMetadata::default());
+        assert!(item.metadata.is_empty());
+    }
+}
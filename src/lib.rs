@@ -24,8 +24,13 @@ pub use error as error_crate;
 
 pub mod error;
 pub mod config;
+#[cfg(feature = "native")]
 pub mod cache;
 pub mod derive;
+pub mod url_utils;
+#[cfg(feature = "native")]
+pub mod utils;
+#[cfg(feature = "native")]
 pub mod net;
 
 // 创建便利的 Error 和 Result 类型别名
@@ -33,17 +38,36 @@ pub type Error = error_crate::ErrorInfo;
 pub type Result<T> = error_crate::Result<T>;
 
 // 重新导出主要类型
+#[cfg(feature = "native")]
 pub use config::{SeeSeaConfig, ConfigManager, ConfigError};
+#[cfg(feature = "native")]
 pub use cache::{CacheInterface, CacheImplConfig, CacheMode};
+pub use derive::{SearchQuery, SearchResult, SearchResultItem};
+#[cfg(feature = "native")]
 pub use derive::{
-    SearchEngine, SearchQuery, SearchResult, EngineInfo,
+    SearchEngine, EngineInfo,
     QueryBuilder, ResultParser,
     RssFeed, RssFeedItem, RssFeedQuery, RssFeedSource,
 };
+#[cfg(feature = "native")]
 pub use net::{NetworkInterface, NetworkConfig, HttpClient};
 pub mod search;
+#[cfg(feature = "native")]
 pub mod api;
+#[cfg(feature = "native")]
 pub mod rss;
+#[cfg(feature = "native")]
+pub mod notify;
+#[cfg(feature = "native")]
+pub mod seesea;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// 库入口和搜索相关的主要类型
+#[cfg(feature = "native")]
+pub use seesea::{SeeSea, SeeSeaBuilder, SeeSeaError};
+#[cfg(feature = "native")]
+pub use search::{SearchInterface, SearchRequest, SearchResponse, SearchConfig};
 
 #[cfg(feature = "python")]
 pub mod python_bindings;
@@ -55,9 +79,10 @@ use pyo3::prelude::*;
 #[cfg(feature = "python")]
 #[pymodule]
 fn seesea_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
-    use python_bindings::{py_search, py_api, py_config, py_cache, py_rss, py_browser, py_engine_registry};
+    use python_bindings::{py_search, py_api, py_config, py_cache, py_rss, py_browser, py_engine_registry, py_engine_loader};
 
     m.add_class::<py_search::PySearchClient>()?;
+    m.add_class::<py_search::PySearchStream>()?;
     m.add_class::<py_api::PyApiServer>()?;
     m.add_class::<py_config::PyConfig>()?;
     m.add_class::<py_cache::PyCacheStats>()?;
@@ -71,6 +96,8 @@ fn seesea_core(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(py_engine_registry::unregister_engine, m)?)?;
     m.add_function(wrap_pyfunction!(py_engine_registry::list_engines, m)?)?;
     m.add_function(wrap_pyfunction!(py_engine_registry::has_engine, m)?)?;
+    m.add_function(wrap_pyfunction!(py_engine_loader::load_engines_from_dir, m)?)?;
+    m.add_function(wrap_pyfunction!(py_engine_loader::import_searxng_engines, m)?)?;
 
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add("__doc__", "SeeSea - Privacy-focused metasearch engine with RSS and browser engine support")?;
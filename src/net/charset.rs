@@ -0,0 +1,134 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 响应正文的字符集检测与转码
+//!
+//! 一些中文站点仍以 GBK / GB2312 输出页面，`Content-Type` 中也常常不带 charset，
+//! 直接按 UTF-8 解码会得到乱码。检测顺序：
+//!
+//! 1. BOM
+//! 2. `Content-Type` 的 `charset`（声明为 UTF-8 但正文不是合法 UTF-8 时继续往下找）
+//! 3. 正文开头的 `<meta charset>` / `<meta http-equiv="Content-Type">` 或 XML 声明的 `encoding`
+//! 4. 合法 UTF-8 按 UTF-8，否则按 GB18030（GBK 的超集）
+
+use encoding_rs::{Encoding, GB18030, UTF_8};
+use once_cell::sync::Lazy;
+use regex::bytes::Regex;
+
+/// 在正文开头查找声明的字节数
+const PRESCAN_BYTES: usize = 4096;
+
+static META_CHARSET_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_\-:.]+)"#).unwrap()
+});
+
+static XML_ENCODING_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)<\?xml[^>]+encoding\s*=\s*["']([a-z0-9_\-:.]+)["']"#).unwrap()
+});
+
+/// `Content-Type` 中的 charset 参数
+fn content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(|c| c == '"' || c == '\'').as_bytes())
+    })
+}
+
+/// 正文开头声明的字符集（HTML meta 或 XML 声明）
+fn declared_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let head = &body[..body.len().min(PRESCAN_BYTES)];
+    [&*META_CHARSET_RE, &*XML_ENCODING_RE]
+        .iter()
+        .find_map(|re| re.captures(head))
+        .and_then(|caps| Encoding::for_label(&caps[1]))
+        // 正文中声明的 UTF-16 按 UTF-8 处理（声明本身是用 ASCII 兼容编码写的）
+        .map(Encoding::output_encoding)
+}
+
+/// 检测正文的字符集
+pub fn detect_charset(content_type: Option<&str>, body: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(body) {
+        return encoding;
+    }
+    let valid_utf8 = std::str::from_utf8(body).is_ok();
+    if let Some(encoding) = content_type.and_then(content_type_charset)
+        && (encoding != UTF_8 || valid_utf8)
+    {
+        return encoding;
+    }
+    if let Some(encoding) = declared_charset(body)
+        && (encoding != UTF_8 || valid_utf8)
+    {
+        return encoding;
+    }
+    if valid_utf8 { UTF_8 } else { GB18030 }
+}
+
+/// 按检测到的字符集把正文解码为字符串（无法解码的字节替换为 U+FFFD）
+pub fn decode_body(content_type: Option<&str>, body: &[u8]) -> String {
+    let encoding = detect_charset(content_type, body);
+    let (text, _, _) = encoding.decode(body);
+    text.into_owned()
+}
+
+/// 读取响应正文并按字符集解码，代替 `Response::text()`（后者只看 `Content-Type`）
+pub async fn response_text(response: reqwest::Response) -> Result<String, reqwest::Error> {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.bytes().await?;
+    Ok(decode_body(content_type.as_deref(), &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gbk(text: &str) -> Vec<u8> {
+        encoding_rs::GBK.encode(text).0.into_owned()
+    }
+
+    #[test]
+    fn test_detect_charset_from_header_and_meta() {
+        let body = gbk("<html><head><meta charset=\"gb2312\"></head><body>搜狗搜索</body></html>");
+        assert_eq!(decode_body(None, &body), "<html><head><meta charset=\"gb2312\"></head><body>搜狗搜索</body></html>");
+
+        let body = gbk("<p>百度一下</p>");
+        assert_eq!(decode_body(Some("text/html; charset=GBK"), &body), "<p>百度一下</p>");
+
+        let meta = gbk(r#"<meta http-equiv="Content-Type" content="text/html; charset=gbk"><p>三六零</p>"#);
+        assert_eq!(detect_charset(Some("text/html"), &meta), encoding_rs::GBK);
+
+        let xml = gbk(r#"<?xml version="1.0" encoding="GB2312"?><rss><title>新闻</title></rss>"#);
+        assert!(decode_body(Some("application/xml"), &xml).contains("<title>新闻</title>"));
+    }
+
+    #[test]
+    fn test_detect_charset_fallbacks() {
+        // 声明为 UTF-8 但实际是 GBK
+        let body = gbk("<p>中文</p>");
+        assert_eq!(decode_body(Some("text/html; charset=utf-8"), &body), "<p>中文</p>");
+        // 没有任何声明
+        assert_eq!(decode_body(None, &body), "<p>中文</p>");
+        // 合法 UTF-8 不受影响
+        assert_eq!(decode_body(None, "<p>中文</p>".as_bytes()), "<p>中文</p>");
+        // BOM 优先
+        assert_eq!(detect_charset(Some("text/html; charset=gbk"), b"\xEF\xBB\xBFok"), UTF_8);
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 浏览器指纹模拟传输层
+//!
+//! 调用 [curl-impersonate](https://github.com/lwthiker/curl-impersonate)
+//! （如 `curl_chrome116`）发送请求，使 TLS ClientHello、HTTP/2 设置和默认请求头
+//! 与真实 Chrome 一致，降低 Bing/百度等引擎按 JA3 指纹返回 403 或验证码的概率。
+//! 需要在系统中安装对应的可执行文件。
+
+use async_trait::async_trait;
+use reqwest::Response;
+use std::net::IpAddr;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::transport::{build_response, HttpTransport, SKIPPED_HEADERS};
+use crate::error::Result;
+use crate::net::resolver::{is_public_ip, resolve_public, ForbiddenAddress};
+use crate::net::types::{ProxyConfig, ProxyType, RequestHeadersConfig, RequestOptions};
+
+/// curl-impersonate 传输层
+pub struct ImpersonateTransport {
+    /// 可执行文件（如 `curl_chrome116`）
+    program: String,
+    /// 额外的命令行参数
+    args: Vec<String>,
+    /// 代理配置
+    proxy: Option<ProxyConfig>,
+    /// 引擎请求头装饰配置
+    request_headers: Option<RequestHeadersConfig>,
+}
+
+impl ImpersonateTransport {
+    /// 使用指定的 curl-impersonate 可执行文件
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            proxy: None,
+            request_headers: None,
+        }
+    }
+
+    /// 通过代理发送请求（未启用的代理配置会被忽略）
+    pub fn with_proxy(mut self, proxy: &ProxyConfig) -> Self {
+        self.proxy = proxy.enabled.then(|| proxy.clone());
+        self
+    }
+
+    /// 设置引擎请求头装饰配置
+    pub fn with_request_headers(mut self, request_headers: &RequestHeadersConfig) -> Self {
+        self.request_headers = Some(request_headers.clone());
+        self
+    }
+
+    fn command_args(&self, method: &str, url: &str, options: &RequestOptions, has_body: bool) -> Vec<String> {
+        let mut args = vec![
+            "--silent".to_string(),
+            "--show-error".to_string(),
+            "--include".to_string(),
+            "--compressed".to_string(),
+            "--request".to_string(),
+            method.to_string(),
+            "--max-time".to_string(),
+            options.timeout.as_secs().max(1).to_string(),
+            "--connect-timeout".to_string(),
+            options.connect_timeout.as_secs().max(1).to_string(),
+        ];
+        if options.follow_redirects && !options.public_only {
+            args.push("--location".to_string());
+            args.push("--max-redirs".to_string());
+            args.push(options.max_redirects.to_string());
+        }
+        if let Some(proxy) = &self.proxy {
+            let scheme = match proxy.proxy_type {
+                ProxyType::Http => "http",
+                ProxyType::Https => "https",
+                ProxyType::Socks5 | ProxyType::Tor => "socks5h",
+            };
+            args.push("--proxy".to_string());
+            args.push(format!("{}://{}", scheme, proxy.address));
+            if let (Some(user), Some(pass)) = (&proxy.username, &proxy.password) {
+                args.push("--proxy-user".to_string());
+                args.push(format!("{}:{}", user, pass));
+            }
+        }
+        for (name, value) in &options.headers {
+            args.push("--header".to_string());
+            args.push(format!("{}: {}", name, value));
+        }
+        if has_body {
+            args.push("--data-binary".to_string());
+            args.push("@-".to_string());
+        }
+        args.extend(self.args.iter().cloned());
+        args.push("--".to_string());
+        args.push(url.to_string());
+        args
+    }
+
+    async fn execute(&self, method: &str, url: &str, body: Option<Vec<u8>>, options: Option<RequestOptions>) -> Result<Response> {
+        let options = options.unwrap_or_default();
+        let mut args = self.command_args(method, url, &options, body.is_some());
+        if options.public_only {
+            args.splice(0..0, pin_public_addresses(url).await?);
+        }
+        let mut child = Command::new(&self.program)
+            .args(args)
+            .stdin(if body.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| crate::error::network_error(format!("Failed to start {}: {}", self.program, e)))?;
+
+        if let (Some(body), Some(mut stdin)) = (body, child.stdin.take()) {
+            stdin
+                .write_all(&body)
+                .await
+                .map_err(|e| crate::error::network_error(format!("Failed to write request body: {}", e)))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| crate::error::network_error(format!("{} failed: {}", self.program, e)))?;
+        if !output.status.success() {
+            return Err(crate::error::network_error(format!(
+                "{} {} request failed: {}",
+                self.program,
+                method,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let (status, headers, body) = parse_output(&output.stdout)
+            .ok_or_else(|| crate::error::network_error(format!("Malformed response from {} for {}", self.program, url)))?;
+        build_response(status, &headers, body)
+    }
+}
+
+/// 检查地址只指向公网主机，返回把 curl 连接固定到检查过的地址的 `--resolve` 参数
+///
+/// curl 自己解析域名时可能被 DNS 重绑定到内网地址，这里把解析结果直接交给 curl
+async fn pin_public_addresses(url: &str) -> Result<Vec<String>> {
+    let parsed = url::Url::parse(url).map_err(|e| crate::error::network_error(format!("Invalid URL {}: {}", url, e)))?;
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    match parsed.host() {
+        Some(url::Host::Domain(domain)) => {
+            let addrs = resolve_public(domain, port)
+                .await
+                .map_err(|e| crate::error::network_error(e.to_string()))?;
+            let ips: Vec<String> = addrs
+                .iter()
+                .map(|addr| match addr.ip() {
+                    IpAddr::V4(ip) => ip.to_string(),
+                    IpAddr::V6(ip) => format!("[{}]", ip),
+                })
+                .collect();
+            Ok(vec!["--resolve".to_string(), format!("{}:{}:{}", domain, port, ips.join(","))])
+        }
+        Some(url::Host::Ipv4(ip)) if is_public_ip(ip.into()) => Ok(Vec::new()),
+        Some(url::Host::Ipv6(ip)) if is_public_ip(ip.into()) => Ok(Vec::new()),
+        _ => Err(crate::error::network_error(ForbiddenAddress(url.to_string()).to_string())),
+    }
+}
+
+/// 状态码、响应头、响应体
+type ParsedOutput = (u16, Vec<(String, String)>, Vec<u8>);
+
+/// 解析 `curl --include` 的输出
+///
+/// 跟随重定向或收到 `100 Continue` 时会有多段响应头，取最后一段
+fn parse_output(output: &[u8]) -> Option<ParsedOutput> {
+    let mut rest = output;
+    let mut last = None;
+    while rest.starts_with(b"HTTP/") {
+        let end = find_subslice(rest, b"\r\n\r\n").map(|i| (i, 4))
+            .or_else(|| find_subslice(rest, b"\n\n").map(|i| (i, 2)))?;
+        let head = std::str::from_utf8(&rest[..end.0]).ok()?;
+        let mut lines = head.lines();
+        let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .collect();
+        last = Some((status, headers));
+        rest = &rest[end.0 + end.1..];
+    }
+    let (status, headers) = last?;
+    Some((status, headers, rest.to_vec()))
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[async_trait]
+impl HttpTransport for ImpersonateTransport {
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
+        self.execute("GET", url, None, options).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        self.execute("POST", url, Some(body), options).await
+    }
+
+    fn request_headers(&self) -> Option<&RequestHeadersConfig> {
+        self.request_headers.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output_with_redirect() {
+        let output = b"HTTP/1.1 302 Found\r\nLocation: /next\r\n\r\nHTTP/2 200\r\ncontent-type: text/html\r\ncontent-encoding: br\r\nset-cookie: a=1\r\n\r\n<html>ok</html>";
+        let (status, headers, body) = parse_output(output).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(headers, vec![
+            ("content-type".to_string(), "text/html".to_string()),
+            ("set-cookie".to_string(), "a=1".to_string()),
+        ]);
+        assert_eq!(body, b"<html>ok</html>");
+
+        assert!(parse_output(b"not http").is_none());
+    }
+
+    #[test]
+    fn test_command_args() {
+        let transport = ImpersonateTransport::new("curl_chrome116", vec!["--http2".to_string()])
+            .with_proxy(&ProxyConfig {
+                proxy_type: ProxyType::Socks5,
+                address: "127.0.0.1:9050".to_string(),
+                enabled: true,
+                ..Default::default()
+            });
+        let options = RequestOptions {
+            headers: vec![("Cookie".to_string(), "SRCHHPGUSR=ADLT=OFF".to_string())],
+            ..Default::default()
+        };
+        let args = transport.command_args("GET", "https://www.bing.com/search?q=rust", &options, false);
+        let joined = args.join(" ");
+        assert!(joined.contains("--proxy socks5h://127.0.0.1:9050"));
+        assert!(joined.contains("--header Cookie: SRCHHPGUSR=ADLT=OFF"));
+        assert!(!joined.contains("--data-binary"));
+        assert_eq!(&args[args.len() - 3..], ["--http2", "--", "https://www.bing.com/search?q=rust"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_impersonate_transport_with_fake_binary() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // 用脚本模拟 curl：把收到的参数和请求体原样作为响应体返回
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("fake_curl");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprintf 'HTTP/1.1 200 OK\\r\\nContent-Type: text/plain\\r\\n\\r\\n'\necho \"$@\"\ncat\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let transport = ImpersonateTransport::new(script.to_string_lossy(), Vec::new());
+        let response = transport.post("https://example.com/api", b"q=rust".to_vec(), None).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "text/plain");
+        let body = response.text().await.unwrap();
+        assert!(body.contains("--request POST"));
+        assert!(body.ends_with("q=rust"));
+
+        // 只连接公网地址时，内网地址在启动 curl 之前就被拒绝
+        let options = RequestOptions { public_only: true, ..Default::default() };
+        assert!(transport.get("http://127.0.0.1/", Some(options.clone())).await.is_err());
+        assert!(transport.get("http://localhost/", Some(options)).await.is_err());
+
+        let missing = ImpersonateTransport::new("/nonexistent/curl_chrome116", Vec::new());
+        assert!(missing.get("https://example.com/", None).await.is_err());
+    }
+}
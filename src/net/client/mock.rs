@@ -0,0 +1,217 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 模拟 HTTP 传输层
+//!
+//! 按 URL 片段返回预设响应并记录收到的请求，用于在不联网的情况下测试引擎的 `fetch()`。
+//!
+//! ```
+//! use seesea_core::net::client::{HttpTransport, MockResponse, MockTransport};
+//!
+//! # tokio_test_block_on(async {
+//! let transport = MockTransport::new()
+//!     .respond("sogou.com/web", MockResponse::ok("<html></html>"));
+//! let response = transport.get("https://www.sogou.com/web?query=rust", None).await.unwrap();
+//! assert_eq!(response.text().await.unwrap(), "<html></html>");
+//! assert_eq!(transport.requests().len(), 1);
+//! # });
+//! # fn tokio_test_block_on<F: std::future::Future>(f: F) -> F::Output {
+//! #     tokio::runtime::Runtime::new().unwrap().block_on(f)
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use reqwest::Response;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::transport::{build_response, HttpTransport};
+use crate::error::Result;
+use crate::net::types::RequestOptions;
+
+/// 预设响应
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    /// 状态码
+    pub status: u16,
+    /// 响应头
+    pub headers: Vec<(String, String)>,
+    /// 响应体
+    pub body: Vec<u8>,
+    /// 返回前的延迟（模拟慢速引擎）
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    /// 指定状态码和响应体
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// 200 响应
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body)
+    }
+
+    /// 添加响应头
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// 延迟返回
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+}
+
+/// 收到的请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// 请求方法
+    pub method: String,
+    /// 请求 URL
+    pub url: String,
+    /// 请求头
+    pub headers: Vec<(String, String)>,
+    /// 请求体
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// 查找请求头（不区分大小写）
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// 模拟传输层
+///
+/// 按注册顺序匹配第一个包含在 URL 中的片段；没有匹配时返回网络错误
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    routes: Vec<(String, MockResponse)>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    /// 创建空的模拟传输层
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// URL 包含 `pattern` 时返回 `response`
+    pub fn respond(mut self, pattern: impl Into<String>, response: MockResponse) -> Self {
+        self.routes.push((pattern.into(), response));
+        self
+    }
+
+    /// 已收到的请求
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    async fn handle(&self, method: &str, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.push(RecordedRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                headers: options.map(|o| o.headers).unwrap_or_default(),
+                body,
+            });
+        }
+        let response = self
+            .routes
+            .iter()
+            .find(|(pattern, _)| url.contains(pattern.as_str()))
+            .map(|(_, response)| response.clone())
+            .ok_or_else(|| crate::error::network_error(format!("No mock response for {} {}", method, url)))?;
+        if !response.delay.is_zero() {
+            tokio::time::sleep(response.delay).await;
+        }
+        build_response(response.status, &response.headers, response.body)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for MockTransport {
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
+        self.handle("GET", url, Vec::new(), options).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        self.handle("POST", url, body, options).await
+    }
+}
+
+/// 在本地端口启动真实 HTTP 服务：`/redirect` 返回 302 跳转到 `/target`，`/target` 返回 200
+///
+/// 用于测试真实 [`HttpClient`](super::HttpClient) 的重定向行为
+#[cfg(test)]
+pub(crate) async fn spawn_redirect_server() -> std::net::SocketAddr {
+    use axum::http::{header, StatusCode};
+    use axum::routing::any;
+
+    let app = axum::Router::new()
+        .route("/redirect", any(|| async { (StatusCode::FOUND, [(header::LOCATION, "/target")]) }))
+        .route("/target", any(|| async { "target" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    addr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_transport() {
+        let transport = MockTransport::new()
+            .respond("/redirect", MockResponse::new(302, "").header("location", "https://example.com/"))
+            .respond("example.com", MockResponse::ok("hello"));
+
+        let options = RequestOptions {
+            headers: vec![("Cookie".to_string(), "a=1".to_string())],
+            ..Default::default()
+        };
+        let response = transport.get("https://example.com/page", Some(options)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "hello");
+
+        // 先注册的片段优先
+        let response = transport.get("https://example.com/redirect", None).await.unwrap();
+        assert_eq!(response.status(), 302);
+        assert_eq!(response.headers()["location"], "https://example.com/");
+
+        assert!(transport.get("https://other.org/", None).await.is_err());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].header("cookie"), Some("a=1"));
+        assert_eq!(requests[2].url, "https://other.org/");
+    }
+}
@@ -16,22 +16,40 @@
 //!
 //! 提供基于 reqwest 的强大 HTTP 客户端封装
 
+pub mod impersonate;
+pub mod mock;
 pub mod pool;
 pub mod proxy;
+pub mod replay;
 pub mod tls;
+pub mod transport;
+
+pub use impersonate::ImpersonateTransport;
+pub use mock::{MockResponse, MockTransport, RecordedRequest};
+pub use replay::{RecordingTransport, ReplayOrRecordTransport, ReplayTransport};
+pub use transport::{create_backend_transport, create_transport, HttpTransport};
 
 use crate::error::Result;
 use crate::net::types::{NetworkConfig, RequestOptions};
 use crate::net::privacy::PrivacyManager;
+use crate::net::resolver::PublicOnlyResolver;
+use reqwest::redirect::Policy;
 use reqwest::{Client, ClientBuilder, Response};
 use std::sync::Arc;
 use std::time::Duration;
 
 /// HTTP 客户端封装
+///
+/// 按 [`RequestOptions`] 选用底层客户端：默认客户端自动跟随重定向，
+/// `follow_redirects` 为假时不跟随，`public_only` 为真时只连接公网地址且不跟随重定向
 #[derive(Clone)]
 pub struct HttpClient {
     /// 底层 reqwest 客户端
     client: Arc<Client>,
+    /// 不跟随重定向的客户端
+    no_redirect_client: Arc<Client>,
+    /// 只连接公网地址、不跟随重定向的客户端
+    public_client: Arc<Client>,
     /// 网络配置
     config: Arc<NetworkConfig>,
     /// 隐私管理器
@@ -49,29 +67,6 @@ impl HttpClient {
     ///
     /// 成功返回配置好的 HttpClient，失败返回错误
     pub fn new(config: NetworkConfig) -> Result<Self> {
-        let mut builder = ClientBuilder::new();
-
-        // 配置连接池
-        builder = builder
-            .pool_max_idle_per_host(config.pool.max_idle_connections)
-            .pool_idle_timeout(Some(Duration::from_secs(config.pool.idle_timeout_secs)));
-
-        // 配置 HTTP/2
-        if config.pool.http2_only {
-            builder = builder.http2_prior_knowledge();
-        }
-
-        // 配置 TLS
-        builder = tls::configure_tls(builder, &config.tls)?;
-
-        // 配置代理
-        if config.proxy.enabled {
-            builder = proxy::configure_proxy(builder, &config.proxy)?;
-        }
-
-        // 配置隐私保护
-        builder = crate::net::privacy::headers::configure_privacy(builder, &config.privacy);
-
         // 创建隐私管理器
         let privacy_manager = Arc::new(PrivacyManager::new(
             config.privacy.clone(),
@@ -79,18 +74,34 @@ impl HttpClient {
             config.doh.clone(),
         ));
 
-        // 构建客户端
-        let client = builder
-            .build()
-            .map_err(|e| crate::error::network_error(format!("Failed to build HTTP client: {}", e)))?;
+        let client = build_client(configure_builder(&config)?)?;
+        let no_redirect_client = build_client(configure_builder(&config)?.redirect(Policy::none()))?;
+        let public_client = build_client(
+            configure_builder(&config)?
+                .redirect(Policy::none())
+                .dns_resolver(Arc::new(PublicOnlyResolver)),
+        )?;
 
         Ok(Self {
             client: Arc::new(client),
+            no_redirect_client: Arc::new(no_redirect_client),
+            public_client: Arc::new(public_client),
             config: Arc::new(config),
             privacy_manager: Some(privacy_manager),
         })
     }
 
+    /// 按请求选项选用底层客户端
+    fn client_for(&self, options: &RequestOptions) -> &Client {
+        if options.public_only {
+            &self.public_client
+        } else if !options.follow_redirects {
+            &self.no_redirect_client
+        } else {
+            &self.client
+        }
+    }
+
     /// 获取隐私管理器
     pub fn privacy_manager(&self) -> Option<&Arc<PrivacyManager>> {
         self.privacy_manager.as_ref()
@@ -109,30 +120,31 @@ impl HttpClient {
     pub async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
         let opts = options.unwrap_or_default();
         
-        let mut request = self.client
+        let mut request = self.client_for(&opts)
             .get(url)
             .timeout(opts.timeout);
 
-        // 添加隐私保护请求头
+        // 添加隐私保护请求头（自定义请求头中已有的跳过）
         if let Some(ref privacy_mgr) = self.privacy_manager {
             let privacy_headers = privacy_mgr.get_privacy_headers(url).await;
             for (key, value) in privacy_headers {
-                request = request.header(&key, &value);
+                if !opts.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case(&key)) {
+                    request = request.header(&key, &value);
+                }
             }
         }
 
-        // 添加自定义请求头（会覆盖隐私头）
+        // 添加自定义请求头
         for (key, value) in opts.headers {
             request = request.header(&key, &value);
         }
 
         // 发送请求
-        request
-            .send()
-            .await
-            .map_err(|e| {
-                crate::error::network_error(format!("GET request failed: {}", e))
-            })
+        let result = request.send().await;
+        record_outbound("GET", &result);
+        result.map_err(|e| {
+            crate::error::network_error(format!("GET request failed: {}", e))
+        })
     }
 
     /// 发送 POST 请求
@@ -149,29 +161,30 @@ impl HttpClient {
     pub async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
         let opts = options.unwrap_or_default();
         
-        let mut request = self.client
+        let mut request = self.client_for(&opts)
             .post(url)
             .timeout(opts.timeout)
             .body(body);
 
-        // 添加隐私保护请求头
+        // 添加隐私保护请求头（自定义请求头中已有的跳过）
         if let Some(ref privacy_mgr) = self.privacy_manager {
             let privacy_headers = privacy_mgr.get_privacy_headers(url).await;
             for (key, value) in privacy_headers {
-                request = request.header(&key, &value);
+                if !opts.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case(&key)) {
+                    request = request.header(&key, &value);
+                }
             }
         }
 
-        // 添加自定义请求头（会覆盖隐私头）
+        // 添加自定义请求头
         for (key, value) in opts.headers {
             request = request.header(&key, &value);
         }
 
         // 发送请求
-        request
-            .send()
-            .await
-            .map_err(|e| crate::error::network_error(format!("POST request failed: {}", e)))
+        let result = request.send().await;
+        record_outbound("POST", &result);
+        result.map_err(|e| crate::error::network_error(format!("POST request failed: {}", e)))
     }
 
     /// 发送 POST JSON 请求
@@ -188,7 +201,7 @@ impl HttpClient {
     pub async fn post_json<T: serde::Serialize>(&self, url: &str, json: &T, options: Option<RequestOptions>) -> Result<Response> {
         let opts = options.unwrap_or_default();
         
-        let mut request = self.client
+        let mut request = self.client_for(&opts)
             .post(url)
             .timeout(opts.timeout)
             .json(json);
@@ -199,10 +212,9 @@ impl HttpClient {
         }
 
         // 发送请求
-        request
-            .send()
-            .await
-            .map_err(|e| crate::error::network_error(format!("POST JSON request failed: {}", e)))
+        let result = request.send().await;
+        record_outbound("POST", &result);
+        result.map_err(|e| crate::error::network_error(format!("POST JSON request failed: {}", e)))
     }
 
     /// 获取网络配置
@@ -216,6 +228,62 @@ impl HttpClient {
     }
 }
 
+/// 按网络配置创建客户端构建器（连接池、TLS、代理、隐私请求头）
+fn configure_builder(config: &NetworkConfig) -> Result<ClientBuilder> {
+    let mut builder = ClientBuilder::new();
+
+    // 配置连接池
+    builder = builder
+        .pool_max_idle_per_host(config.pool.max_idle_connections)
+        .pool_idle_timeout(Some(Duration::from_secs(config.pool.idle_timeout_secs)));
+
+    // 配置 HTTP/2
+    if config.pool.http2_only {
+        builder = builder.http2_prior_knowledge();
+    }
+
+    // 配置 TLS
+    builder = tls::configure_tls(builder, &config.tls)?;
+
+    // 配置代理
+    if config.proxy.enabled {
+        builder = proxy::configure_proxy(builder, &config.proxy)?;
+    }
+
+    // 配置隐私保护
+    Ok(crate::net::privacy::headers::configure_privacy(builder, &config.privacy))
+}
+
+/// 构建客户端
+fn build_client(builder: ClientBuilder) -> Result<Client> {
+    builder
+        .build()
+        .map_err(|e| crate::error::network_error(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// 记录出站请求结果（按状态码或错误类型计数，导出为 Prometheus 指标）
+fn record_outbound(method: &'static str, result: &reqwest::Result<Response>) {
+    match result {
+        Ok(response) => {
+            metrics::counter!(
+                "seesea_http_responses_total",
+                "method" => method,
+                "status" => response.status().as_u16().to_string()
+            ).increment(1);
+        }
+        Err(e) => {
+            let kind = if e.is_timeout() {
+                "timeout"
+            } else if e.is_connect() {
+                "connect"
+            } else {
+                "other"
+            };
+            metrics::counter!("seesea_http_errors_total", "method" => method, "kind" => kind).increment(1);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,4 +301,34 @@ mod tests {
         let client = HttpClient::new(config.clone()).unwrap();
         assert_eq!(client.config().pool.max_idle_connections, config.pool.max_idle_connections);
     }
+
+    #[tokio::test]
+    async fn test_follow_redirects_option() {
+        let addr = mock::spawn_redirect_server().await;
+        let client = HttpClient::new(NetworkConfig::default()).unwrap();
+        let url = format!("http://{}/redirect", addr);
+
+        let followed = client.get(&url, None).await.unwrap();
+        assert_eq!(followed.status(), 200);
+        assert_eq!(followed.text().await.unwrap(), "target");
+
+        let options = RequestOptions { follow_redirects: false, ..Default::default() };
+        let response = client.get(&url, Some(options.clone())).await.unwrap();
+        assert_eq!(response.status(), 302);
+        assert_eq!(response.headers()[reqwest::header::LOCATION], "/target");
+        let response = client.post(&url, b"{}".to_vec(), Some(options)).await.unwrap();
+        assert_eq!(response.status(), 302);
+    }
+
+    #[tokio::test]
+    async fn test_public_only_rejects_private_hosts() {
+        let addr = mock::spawn_redirect_server().await;
+        let client = HttpClient::new(NetworkConfig::default()).unwrap();
+        let url = format!("http://localhost:{}/target", addr.port());
+        assert_eq!(client.get(&url, None).await.unwrap().status(), 200);
+
+        // 解析器拒绝 localhost，请求不会连到本地服务
+        let options = RequestOptions { public_only: true, ..Default::default() };
+        assert!(client.get(&url, Some(options)).await.is_err());
+    }
 }
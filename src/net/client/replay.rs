@@ -0,0 +1,274 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 录制与回放传输层
+//!
+//! [`RecordingTransport`] 把每个响应写入目录，文件名为请求方法、URL 和请求体的 SHA-256；
+//! [`ReplayTransport`] 只读取这些文件，不发送任何网络请求，用于离线开发和确定性测试；
+//! [`ReplayOrRecordTransport`] 有录制时回放，没有时发送请求并录制。
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::transport::{build_response, HttpTransport, SKIPPED_HEADERS};
+use crate::error::Result;
+use crate::net::types::{RequestHeadersConfig, RequestOptions};
+
+/// 录制的一次请求/响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Exchange {
+    method: String,
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// UTF-8 响应体
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+    /// 非 UTF-8 响应体（base64）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_base64: Option<String>,
+}
+
+impl Exchange {
+    fn body_bytes(&self) -> Result<Vec<u8>> {
+        match (&self.body, &self.body_base64) {
+            (Some(text), _) => Ok(text.clone().into_bytes()),
+            (None, Some(encoded)) => STANDARD
+                .decode(encoded)
+                .map_err(|e| crate::error::network_error(format!("Invalid recorded body for {}: {}", self.url, e))),
+            (None, None) => Ok(Vec::new()),
+        }
+    }
+}
+
+/// 录制文件路径
+fn exchange_path(dir: &Path, method: &str, url: &str, body: &[u8]) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(url.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    dir.join(format!("{:x}.json", hasher.finalize()))
+}
+
+/// 发送请求并录制响应
+pub struct RecordingTransport {
+    inner: Arc<dyn HttpTransport>,
+    dir: PathBuf,
+}
+
+impl RecordingTransport {
+    /// 包装传输层，把响应录制到 `dir`
+    pub fn new(inner: Arc<dyn HttpTransport>, dir: impl Into<PathBuf>) -> Self {
+        Self { inner, dir: dir.into() }
+    }
+
+    async fn record(&self, method: &str, url: &str, request_body: &[u8], response: Response) -> Result<Response> {
+        let status = response.status().as_u16();
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| crate::error::network_error(format!("Failed to read response from {}: {}", url, e)))?
+            .to_vec();
+
+        let (text, encoded) = match std::str::from_utf8(&body) {
+            Ok(text) => (Some(text.to_string()), None),
+            Err(_) => (None, Some(STANDARD.encode(&body))),
+        };
+        let exchange = Exchange {
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            headers: headers.clone(),
+            body: text,
+            body_base64: encoded,
+        };
+
+        // 录制失败只记录警告，不影响本次请求
+        let path = exchange_path(&self.dir, method, url, request_body);
+        let written = match serde_json::to_vec_pretty(&exchange) {
+            Ok(json) => tokio::fs::create_dir_all(&self.dir)
+                .await
+                .and(tokio::fs::write(&path, json).await)
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = written {
+            tracing::warn!("Failed to record response to {}: {}", path.display(), e);
+        }
+
+        build_response(status, &headers, body)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
+        let response = self.inner.get(url, options).await?;
+        self.record("GET", url, &[], response).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        let response = self.inner.post(url, body.clone(), options).await?;
+        self.record("POST", url, &body, response).await
+    }
+
+    fn request_headers(&self) -> Option<&RequestHeadersConfig> {
+        self.inner.request_headers()
+    }
+}
+
+/// 回放录制的响应
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    /// 从 `dir` 回放
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    async fn replay(&self, method: &str, url: &str, body: &[u8]) -> Result<Response> {
+        let path = exchange_path(&self.dir, method, url, body);
+        let json = tokio::fs::read(&path).await.map_err(|_| {
+            crate::error::network_error(format!(
+                "No recorded response for {} {} (expected {})",
+                method,
+                url,
+                path.display()
+            ))
+        })?;
+        let exchange: Exchange = serde_json::from_slice(&json)
+            .map_err(|e| crate::error::network_error(format!("Invalid recording {}: {}", path.display(), e)))?;
+        build_response(exchange.status, &exchange.headers, exchange.body_bytes()?)
+    }
+}
+
+/// 有录制时回放，没有时发送请求并录制
+pub struct ReplayOrRecordTransport {
+    recorder: RecordingTransport,
+    replay: ReplayTransport,
+}
+
+impl ReplayOrRecordTransport {
+    /// 包装传输层，录制目录为 `dir`
+    pub fn new(inner: Arc<dyn HttpTransport>, dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            recorder: RecordingTransport::new(inner, dir.clone()),
+            replay: ReplayTransport::new(dir),
+        }
+    }
+
+    async fn is_recorded(&self, method: &str, url: &str, body: &[u8]) -> bool {
+        tokio::fs::try_exists(exchange_path(&self.replay.dir, method, url, body))
+            .await
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayOrRecordTransport {
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
+        if self.is_recorded("GET", url, &[]).await {
+            return self.replay.get(url, options).await;
+        }
+        self.recorder.get(url, options).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        if self.is_recorded("POST", url, &body).await {
+            return self.replay.post(url, body, options).await;
+        }
+        self.recorder.post(url, body, options).await
+    }
+
+    fn request_headers(&self) -> Option<&RequestHeadersConfig> {
+        self.recorder.request_headers()
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn get(&self, url: &str, _options: Option<RequestOptions>) -> Result<Response> {
+        self.replay("GET", url, &[]).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, _options: Option<RequestOptions>) -> Result<Response> {
+        self.replay("POST", url, &body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let mock = Arc::new(
+            MockTransport::new()
+                .respond("/html", MockResponse::ok("<p>你好</p>").header("content-type", "text/html"))
+                .respond("/binary", MockResponse::ok(vec![0xff, 0x00, 0xfe])),
+        );
+        let recorder = RecordingTransport::new(mock.clone(), dir.path());
+
+        let response = recorder.get("https://example.com/html", None).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "<p>你好</p>");
+        recorder.post("https://example.com/binary", b"q=1".to_vec(), None).await.unwrap();
+        assert_eq!(mock.requests().len(), 2);
+
+        let replay = ReplayTransport::new(dir.path());
+        let response = replay.get("https://example.com/html", None).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/html");
+        assert_eq!(response.text().await.unwrap(), "<p>你好</p>");
+
+        let response = replay.post("https://example.com/binary", b"q=1".to_vec(), None).await.unwrap();
+        assert_eq!(&response.bytes().await.unwrap()[..], &[0xff, 0x00, 0xfe]);
+
+        // 请求体不同视为不同的请求
+        assert!(replay.post("https://example.com/binary", b"q=2".to_vec(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_or_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let mock = Arc::new(MockTransport::new().respond("/page", MockResponse::ok("live")));
+        let transport = ReplayOrRecordTransport::new(mock.clone(), dir.path());
+
+        // 第一次联网并录制，之后从录制回放
+        for _ in 0..2 {
+            let response = transport.get("https://example.com/page", None).await.unwrap();
+            assert_eq!(response.text().await.unwrap(), "live");
+        }
+        assert_eq!(mock.requests().len(), 1);
+
+        transport.get("https://example.com/page?q=2", None).await.unwrap();
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP 传输层抽象
+//!
+//! 引擎只依赖 [`HttpTransport`]，不直接依赖 [`HttpClient`]，
+//! 测试时可以换成 [`MockTransport`](super::mock::MockTransport) 而无需联网。
+//! 实际使用的后端由 [`NetworkConfig::backend`] 决定，见 [`create_transport`]；
+//! [`NetworkConfig::mode`] 不为 `live` 时在后端之外套上录制或回放。
+
+use async_trait::async_trait;
+use reqwest::Response;
+use std::sync::Arc;
+
+use super::impersonate::ImpersonateTransport;
+use super::replay::{RecordingTransport, ReplayOrRecordTransport, ReplayTransport};
+use super::HttpClient;
+use crate::error::Result;
+use crate::net::types::{HttpBackend, NetworkConfig, NetworkMode, RequestHeadersConfig, RequestOptions};
+
+/// HTTP 传输层
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    /// 发送 GET 请求
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response>;
+
+    /// 发送 POST 请求
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response>;
+
+    /// 引擎请求头装饰配置（包装其他传输层时转发给内层）
+    fn request_headers(&self) -> Option<&RequestHeadersConfig> {
+        None
+    }
+}
+
+#[async_trait]
+impl HttpTransport for HttpClient {
+    async fn get(&self, url: &str, options: Option<RequestOptions>) -> Result<Response> {
+        HttpClient::get(self, url, options).await
+    }
+
+    async fn post(&self, url: &str, body: Vec<u8>, options: Option<RequestOptions>) -> Result<Response> {
+        HttpClient::post(self, url, body, options).await
+    }
+
+    fn request_headers(&self) -> Option<&RequestHeadersConfig> {
+        Some(&self.config().request_headers)
+    }
+}
+
+/// 重建响应时丢弃的响应头（响应体已被解压，长度和编码不再适用）
+pub(crate) const SKIPPED_HEADERS: &[&str] = &["content-length", "content-encoding", "transfer-encoding", "connection"];
+
+/// 根据 [`NetworkConfig::backend`] 创建传输层
+pub fn create_transport(config: &NetworkConfig) -> Result<Arc<dyn HttpTransport>> {
+    create_backend_transport(config, &config.backend)
+}
+
+/// 使用指定后端创建传输层（代理等其余设置取自 `config`），并按 `config.mode` 录制或回放
+pub fn create_backend_transport(config: &NetworkConfig, backend: &HttpBackend) -> Result<Arc<dyn HttpTransport>> {
+    let dir = &config.recordings_dir;
+    Ok(match config.mode {
+        NetworkMode::Live => backend_transport(config, backend)?,
+        NetworkMode::Record => Arc::new(RecordingTransport::new(backend_transport(config, backend)?, dir.clone())),
+        NetworkMode::Replay => Arc::new(ReplayTransport::new(dir.clone())),
+        NetworkMode::Auto => Arc::new(ReplayOrRecordTransport::new(backend_transport(config, backend)?, dir.clone())),
+    })
+}
+
+/// 创建后端本身的传输层
+fn backend_transport(config: &NetworkConfig, backend: &HttpBackend) -> Result<Arc<dyn HttpTransport>> {
+    Ok(match backend {
+        HttpBackend::Reqwest => Arc::new(HttpClient::new(config.clone())?),
+        HttpBackend::Impersonate { program, args } => {
+            Arc::new(
+                ImpersonateTransport::new(program.clone(), args.clone())
+                    .with_proxy(&config.proxy)
+                    .with_request_headers(&config.request_headers),
+            )
+        }
+        HttpBackend::Record { dir } => {
+            let client = Arc::new(HttpClient::new(config.clone())?);
+            Arc::new(RecordingTransport::new(client, dir.clone()))
+        }
+        HttpBackend::Replay { dir } => Arc::new(ReplayTransport::new(dir.clone())),
+    })
+}
+
+/// 用状态码、响应头和响应体构造 reqwest 响应
+pub(crate) fn build_response(status: u16, headers: &[(String, String)], body: Vec<u8>) -> Result<Response> {
+    let mut builder = http::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let response = builder
+        .body(body)
+        .map_err(|e| crate::error::network_error(format!("Invalid response: {}", e)))?;
+    Ok(Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_create_transport_replay_does_not_touch_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = NetworkConfig {
+            backend: HttpBackend::Replay { dir: dir.path().to_path_buf() },
+            ..Default::default()
+        };
+        let transport = create_transport(&config).unwrap();
+        let err = transport.get("https://www.bing.com/search?q=rust", None).await.unwrap_err();
+        assert!(err.to_string().contains("No recorded response"));
+    }
+
+    #[tokio::test]
+    async fn test_network_mode_replay_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let config: NetworkConfig = toml::from_str(&format!(
+            "mode = \"replay\"\nrecordings_dir = {:?}\n[engine_backends.bing]\ntype = \"impersonate\"\n",
+            dir.path().display().to_string()
+        ))
+        .unwrap();
+        assert_eq!(config.mode, NetworkMode::Replay);
+
+        // 按引擎覆盖的后端同样只回放
+        let transport = create_backend_transport(&config, config.backend_for("bing")).unwrap();
+        let err = transport.get("https://www.bing.com/search?q=rust", None).await.unwrap_err();
+        assert!(err.to_string().contains("No recorded response"));
+    }
+
+    #[test]
+    fn test_backend_from_toml() {
+        let backend: HttpBackend = toml::from_str("type = \"replay\"\ndir = \"recordings\"").unwrap();
+        assert_eq!(backend, HttpBackend::Replay { dir: "recordings".into() });
+        assert_eq!(HttpBackend::default(), HttpBackend::Reqwest);
+    }
+}
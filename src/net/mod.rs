@@ -23,6 +23,7 @@
 //! - 连接池管理
 
 pub mod types;
+pub mod charset;
 pub mod client;
 pub mod privacy;
 pub mod resolver;
@@ -35,4 +36,5 @@ pub use types::{
 };
 
 pub use on::NetworkInterface;
-pub use client::HttpClient;
+pub use client::{HttpClient, HttpTransport};
+pub use charset::{decode_body, detect_charset, response_text};
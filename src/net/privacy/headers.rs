@@ -61,7 +61,6 @@ pub fn generate_fake_headers(url: &str, config: &PrivacyConfig) -> Vec<(String,
     headers.push(("Accept".to_string(), "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7".to_string()));
     headers.push(("Accept-Language".to_string(), "zh-CN,zh;q=0.9,en-US,en;q=0.8".to_string()));
     headers.push(("Accept-Encoding".to_string(), "gzip, deflate, br".to_string()));
-    headers.push(("DNT".to_string(), "1".to_string()));
     headers.push(("Connection".to_string(), "keep-alive".to_string()));
     headers.push(("Upgrade-Insecure-Requests".to_string(), "1".to_string()));
 
@@ -18,6 +18,9 @@
 
 pub mod doh;
 pub mod pool;
+pub mod public;
+
+pub use public::{is_public_ip, resolve_public, ForbiddenAddress, PublicOnlyResolver};
 
 use crate::error::Result;
 use crate::net::types::DohConfig;
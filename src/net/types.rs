@@ -20,8 +20,11 @@
 //! - DNS 配置
 //! - 隐私设置
 //! - 请求选项
+//! - HTTP 传输后端
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// 代理类型枚举
@@ -203,6 +206,8 @@ pub struct RequestOptions {
     pub follow_redirects: bool,
     /// 最大重定向次数
     pub max_redirects: usize,
+    /// 只连接公网地址（主机名解析到非公网地址时拒绝连接，且不自动跟随重定向）
+    pub public_only: bool,
     /// 是否启用压缩
     pub compression: bool,
     /// 自定义请求头
@@ -216,14 +221,105 @@ impl Default for RequestOptions {
             connect_timeout: Duration::from_secs(10),
             follow_redirects: true,
             max_redirects: 10,
+            public_only: false,
             compression: true,
             headers: Vec::new(),
         }
     }
 }
 
+/// HTTP 传输后端
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HttpBackend {
+    /// 基于 reqwest 的默认客户端
+    #[default]
+    Reqwest,
+    /// 调用 curl-impersonate，模拟浏览器的 TLS/HTTP2 指纹
+    Impersonate {
+        /// 可执行文件
+        #[serde(default = "default_impersonate_program")]
+        program: String,
+        /// 额外的命令行参数
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// 正常发送请求，并把响应写入目录（用于之后离线回放）
+    Record {
+        /// 录制目录
+        dir: PathBuf,
+    },
+    /// 只从目录回放录制的响应，不发送任何网络请求
+    Replay {
+        /// 录制目录
+        dir: PathBuf,
+    },
+}
+
+fn default_impersonate_program() -> String {
+    "curl_chrome116".to_string()
+}
+
+/// 网络模式（对所有传输后端生效的录制与回放）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkMode {
+    /// 正常发送请求
+    #[default]
+    Live,
+    /// 正常发送请求，并把响应写入录制目录
+    Record,
+    /// 只从录制目录回放，不发送任何网络请求
+    Replay,
+    /// 有录制时回放，没有时发送请求并录制（首次联网运行，之后可离线）
+    Auto,
+}
+
+/// Referer 策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RefererPolicy {
+    /// 保留引擎自己设置的 Referer（没有时由 `privacy.fake_referer` 决定）
+    #[default]
+    Engine,
+    /// 使用请求目标的站点根地址，如 `https://www.bing.com/`
+    Origin,
+    /// 去掉引擎设置的 Referer（需同时关闭 `privacy.fake_referer` 才完全不发送）
+    None,
+}
+
+/// 引擎请求头装饰配置
+///
+/// 在引擎 `request()` 之后统一应用，引擎无需各自设置这些请求头
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestHeadersConfig {
+    /// 按查询语言和地区设置 `Accept-Language`（引擎自己设置的优先）
+    pub accept_language: bool,
+    /// 发送 `DNT: 1`
+    pub dnt: bool,
+    /// Referer 策略
+    pub referer: RefererPolicy,
+    /// 按引擎追加的请求头（如 `User-Agent`），覆盖引擎自己设置的同名请求头
+    pub engine_headers: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for RequestHeadersConfig {
+    fn default() -> Self {
+        Self {
+            accept_language: true,
+            dnt: true,
+            referer: RefererPolicy::default(),
+            engine_headers: HashMap::new(),
+        }
+    }
+}
+
 /// 网络层配置（总配置）
+///
+/// 反序列化时缺省的部分使用默认值
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NetworkConfig {
     /// 代理配置
     pub proxy: ProxyConfig,
@@ -235,6 +331,23 @@ pub struct NetworkConfig {
     pub privacy: PrivacyConfig,
     /// 连接池配置
     pub pool: PoolConfig,
+    /// HTTP 传输后端
+    pub backend: HttpBackend,
+    /// 按引擎覆盖的传输后端（如只让 bing、baidu 使用 impersonate）
+    pub engine_backends: HashMap<String, HttpBackend>,
+    /// 录制与回放模式
+    pub mode: NetworkMode,
+    /// `mode` 使用的录制目录，文件名为请求方法、URL 和请求体的哈希
+    pub recordings_dir: PathBuf,
+    /// 引擎请求头装饰
+    pub request_headers: RequestHeadersConfig,
+}
+
+impl NetworkConfig {
+    /// 引擎实际使用的传输后端
+    pub fn backend_for(&self, engine: &str) -> &HttpBackend {
+        self.engine_backends.get(engine).unwrap_or(&self.backend)
+    }
 }
 
 impl Default for NetworkConfig {
@@ -245,6 +358,11 @@ impl Default for NetworkConfig {
             doh: DohConfig::default(),
             privacy: PrivacyConfig::default(),
             pool: PoolConfig::default(),
+            backend: HttpBackend::default(),
+            engine_backends: HashMap::new(),
+            mode: NetworkMode::default(),
+            recordings_dir: PathBuf::from("recordings"),
+            request_headers: RequestHeadersConfig::default(),
         }
     }
 }
@@ -281,6 +399,22 @@ mod tests {
         assert!(config.tls.verify_certificates);
     }
 
+    #[test]
+    fn test_engine_backend_override() {
+        let config: NetworkConfig = toml::from_str(
+            "[engine_backends.bing]\ntype = \"impersonate\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.backend_for("baidu"), &HttpBackend::Reqwest);
+        assert_eq!(
+            config.backend_for("bing"),
+            &HttpBackend::Impersonate {
+                program: "curl_chrome116".to_string(),
+                args: Vec::new(),
+            }
+        );
+    }
+
     #[test]
     fn test_request_options_default() {
         let opts = RequestOptions::default();
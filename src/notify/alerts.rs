@@ -0,0 +1,186 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 保存的搜索调度
+//!
+//! 后台任务定期检查到期的保存的搜索并执行，把首次出现的结果存为新结果，
+//! 同时通知订阅该保存的搜索的 webhook。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::cache::{AlertResult, AlertStore, CacheError, SavedSearch, WebhookSource};
+use crate::derive::SearchQuery;
+use crate::search::{SearchInterface, SearchRequest, SOURCE_ENGINE_KEY};
+
+use super::webhook::{NotificationItem, WebhookDispatcher};
+
+/// 每次执行保留的最大结果数
+const MAX_ALERT_RESULTS: usize = 100;
+
+/// 保存的搜索调度器
+pub struct AlertScheduler {
+    search: Arc<SearchInterface>,
+    store: Arc<AlertStore>,
+    webhooks: Option<Arc<WebhookDispatcher>>,
+}
+
+impl AlertScheduler {
+    /// 创建调度器
+    pub fn new(search: Arc<SearchInterface>, store: Arc<AlertStore>) -> Self {
+        Self {
+            search,
+            store,
+            webhooks: None,
+        }
+    }
+
+    /// 新结果同时通知 webhook
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// 保存的搜索存储
+    pub fn store(&self) -> &Arc<AlertStore> {
+        &self.store
+    }
+
+    /// 执行一次保存的搜索，返回新结果
+    ///
+    /// 无论成功与否都会更新上次执行时间，失败原因记录在 `last_error` 中
+    pub async fn run(&self, search: &mut SavedSearch) -> Result<Vec<AlertResult>, CacheError> {
+        let request = SearchRequest {
+            query: SearchQuery {
+                query: search.query.clone(),
+                ..Default::default()
+            },
+            engines: search.engines.clone(),
+            max_results: Some(MAX_ALERT_RESULTS),
+            // 每次执行都需要最新结果
+            force: true,
+            ..Default::default()
+        };
+        let outcome = self.search.search(&request).await;
+        search.last_run_at = Some(Utc::now());
+
+        let new_results = match outcome {
+            Ok(response) => {
+                search.last_error = None;
+                let found_at = Utc::now();
+                let results = response.results.into_iter().flat_map(|result| {
+                    let engine_name = result.engine_name;
+                    result.items.into_iter().map(move |mut item| AlertResult {
+                        engine: item.metadata.remove(SOURCE_ENGINE_KEY).unwrap_or_else(|| engine_name.clone()),
+                        title: item.title,
+                        url: item.url,
+                        content: item.content,
+                        score: item.score,
+                        found_at,
+                    })
+                }).collect();
+                self.store.record_results(&search.id, results)?
+            }
+            Err(e) => {
+                tracing::warn!("Saved search {} failed: {}", search.id, e);
+                search.last_error = Some(e.to_string());
+                Vec::new()
+            }
+        };
+        self.store.save(search)?;
+
+        if let Some(webhooks) = &self.webhooks
+            && !new_results.is_empty()
+        {
+            let items = new_results.iter().map(|result| NotificationItem {
+                title: result.title.clone(),
+                url: result.url.clone(),
+                score: result.score,
+                snippet: Some(result.content.clone()).filter(|c| !c.is_empty()),
+            }).collect();
+            webhooks.notify(WebhookSource::SavedSearch, &search.id, items).await?;
+        }
+        Ok(new_results)
+    }
+
+    /// 执行所有到期的保存的搜索，返回执行的数量
+    pub async fn run_due(&self) -> Result<usize, CacheError> {
+        let due = self.store.due(Utc::now())?;
+        let count = due.len();
+        for mut search in due {
+            if let Err(e) = self.run(&mut search).await {
+                tracing::warn!("Failed to store results of saved search {}: {}", search.id, e);
+            }
+        }
+        Ok(count)
+    }
+
+    /// 启动后台任务，每隔 `tick` 检查一次到期的保存的搜索
+    pub fn spawn(self: Arc<Self>, tick: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tick);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run_due().await {
+                    tracing::warn!("Failed to load saved searches: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::{CacheManager, WebhookStore};
+    use crate::net::client::{MockResponse, MockTransport};
+    use crate::search::SearchConfig;
+
+    #[tokio::test]
+    async fn test_run_stores_new_results_and_notifies_webhooks() {
+        let html = r#"<ol id="b_results">
+            <li class="b_algo"><h2><a href="https://blog.rust-lang.org/1.80">Rust 1.80</a></h2><p>Release notes</p></li>
+        </ol>"#;
+        let transport = Arc::new(MockTransport::new()
+            .respond("www.bing.com/search", MockResponse::ok(html))
+            .respond("hooks.example.com", MockResponse::ok("")));
+        let manager = CacheManager::temporary();
+        let search = SearchInterface::new_with_cache(SearchConfig::default(), None).unwrap().with_transport(transport.clone());
+        let webhooks = Arc::new(WebhookDispatcher::new(Arc::new(WebhookStore::new(manager.clone())), transport.clone()));
+        let scheduler = AlertScheduler::new(Arc::new(search), Arc::new(AlertStore::new(manager)))
+            .with_webhooks(webhooks.clone());
+
+        let mut saved = scheduler.store()
+            .create("rust", "rust release", vec!["bing".to_string()], Duration::from_secs(3600))
+            .unwrap();
+        webhooks.store()
+            .create("alert", "https://hooks.example.com/alert", WebhookSource::SavedSearch, &saved.id, 0.0, None)
+            .unwrap();
+
+        let new = scheduler.run(&mut saved).await.unwrap();
+        assert_eq!(new.len(), 1);
+        assert!(new[0].engine.eq_ignore_ascii_case("bing"));
+        assert!(saved.last_run_at.is_some());
+        assert!(!scheduler.store().find(&saved.id).unwrap().unwrap().is_due(Utc::now()));
+
+        // 第二次执行没有新结果，也不会再通知
+        assert!(scheduler.run(&mut saved).await.unwrap().is_empty());
+        let hooks: Vec<_> = transport.requests().into_iter().filter(|r| r.url.contains("hooks.example.com")).collect();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(scheduler.store().results(&saved.id, None, 10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 通知模块
+//!
+//! 保存的搜索或 RSS 榜单出现评分达到阈值的新条目时，
+//! 向注册的 webhook 发送 HTTP POST 通知（失败时按退避策略重试，并记录投递日志）。
+//! 保存的搜索由 [`AlertScheduler`] 在后台定期执行。
+
+pub mod webhook;
+pub mod alerts;
+
+pub use alerts::AlertScheduler;
+pub use webhook::{NotificationItem, RetryPolicy, WebhookDispatcher, WebhookPayload, SIGNATURE_HEADER};
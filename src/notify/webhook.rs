@@ -0,0 +1,409 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Webhook 投递
+//!
+//! 请求体为 JSON 格式的 [`WebhookPayload`]。webhook 设置了密钥时，
+//! `X-SeeSea-Signature` 头为 `sha256=<HMAC-SHA256(secret, body) 的十六进制>`，接收方可据此校验来源。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::hmac_sha256_hex;
+use crate::cache::{CacheError, WebhookDelivery, WebhookRecord, WebhookSource, WebhookStore};
+use crate::net::client::HttpTransport;
+use crate::net::types::RequestOptions;
+use crate::rss::RssRanking;
+use crate::search::EngineAnomaly;
+
+/// 签名请求头
+pub const SIGNATURE_HEADER: &str = "X-SeeSea-Signature";
+
+/// 事件请求头
+const EVENT_HEADER: &str = "X-SeeSea-Event";
+
+/// 单次投递的超时时间
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 重试策略（指数退避）
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// 最多尝试次数（包括第一次）
+    pub max_attempts: u32,
+    /// 第一次重试前的等待时间
+    pub initial_backoff: Duration,
+    /// 等待时间上限
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 `attempt` 次失败后的等待时间（从 1 开始）
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+/// 通知的条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationItem {
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+    /// 评分
+    pub score: f64,
+    /// 摘要
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// 请求体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// 事件名称（如 `rss_ranking.new_items`）
+    pub event: String,
+    /// webhook ID
+    pub webhook_id: String,
+    /// 通知来源
+    pub source: WebhookSource,
+    /// 来源目标（榜单名称或保存的搜索 ID）
+    pub target: String,
+    /// 新条目（评分降序）
+    pub items: Vec<NotificationItem>,
+    /// 发送时间
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Webhook 分发器
+///
+/// 按来源找到订阅的 webhook，过滤掉低于阈值或已通知过的条目后投递
+pub struct WebhookDispatcher {
+    store: Arc<WebhookStore>,
+    transport: Arc<dyn HttpTransport>,
+    retry: RetryPolicy,
+}
+
+impl WebhookDispatcher {
+    /// 创建分发器
+    pub fn new(store: Arc<WebhookStore>, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            store,
+            transport,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// 设置重试策略
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// webhook 存储
+    pub fn store(&self) -> &Arc<WebhookStore> {
+        &self.store
+    }
+
+    /// 通知 RSS 榜单的新条目
+    pub async fn notify_ranking(&self, ranking: &RssRanking) -> Result<Vec<WebhookDelivery>, CacheError> {
+        let items = ranking.items.iter().map(|scored| NotificationItem {
+            title: scored.item.title.clone(),
+            url: scored.item.link.clone(),
+            score: scored.score,
+            snippet: scored.item.description.clone(),
+        }).collect();
+        self.notify(WebhookSource::RssRanking, &ranking.name, items).await
+    }
+
+    /// 通知引擎响应异常（订阅该引擎和订阅 `*` 的 webhook 都会收到）
+    ///
+    /// 条目评分为异常的严重程度，标题为异常类型，摘要为描述
+    pub async fn notify_anomaly(&self, anomaly: &EngineAnomaly) -> Result<Vec<WebhookDelivery>, CacheError> {
+        let item = NotificationItem {
+            title: format!("{}: {}", anomaly.engine, anomaly.kind.as_str()),
+            url: format!(
+                "/api/admin/anomalies?engine={}&detected_at={}",
+                urlencoding::encode(&anomaly.engine),
+                anomaly.detected_at.timestamp(),
+            ),
+            score: anomaly.severity(),
+            snippet: Some(anomaly.description()),
+        };
+        let mut deliveries = self.notify(WebhookSource::EngineAnomaly, &anomaly.engine, vec![item.clone()]).await?;
+        deliveries.extend(self.notify(WebhookSource::EngineAnomaly, "*", vec![item]).await?);
+        Ok(deliveries)
+    }
+
+    /// 通知来源的新条目，返回本次的投递记录
+    ///
+    /// 只有投递成功后条目才会标记为已通知，失败的条目在下次通知时会再次发送
+    pub async fn notify(
+        &self,
+        source: WebhookSource,
+        target: &str,
+        items: Vec<NotificationItem>,
+    ) -> Result<Vec<WebhookDelivery>, CacheError> {
+        let mut deliveries = Vec::new();
+        for webhook in self.store.subscribers(source, target)? {
+            let mut new_items = Vec::new();
+            for item in &items {
+                if item.score >= webhook.min_score && !self.store.is_seen(&webhook.id, &item.url)? {
+                    new_items.push(item.clone());
+                }
+            }
+            if new_items.is_empty() {
+                continue;
+            }
+            new_items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+            let delivery = self.deliver(&webhook, new_items.clone()).await;
+            if delivery.success {
+                self.store.mark_seen(&webhook.id, new_items.iter().map(|item| item.url.as_str()))?;
+            }
+            deliveries.push(delivery);
+        }
+        Ok(deliveries)
+    }
+
+    /// 投递一次通知（带重试）并记录投递日志
+    pub async fn deliver(&self, webhook: &WebhookRecord, items: Vec<NotificationItem>) -> WebhookDelivery {
+        let payload = WebhookPayload {
+            event: webhook.source.event().to_string(),
+            webhook_id: webhook.id.clone(),
+            source: webhook.source,
+            target: webhook.target.clone(),
+            items,
+            timestamp: Utc::now(),
+        };
+        let mut delivery = WebhookDelivery {
+            webhook_id: webhook.id.clone(),
+            event: payload.event.clone(),
+            delivered_at: payload.timestamp,
+            item_count: payload.items.len(),
+            attempts: 0,
+            status: None,
+            success: false,
+            error: None,
+        };
+
+        match serde_json::to_vec(&payload) {
+            Ok(body) => self.send_with_retry(webhook, body, &mut delivery).await,
+            Err(e) => delivery.error = Some(format!("Failed to serialize payload: {}", e)),
+        }
+
+        if let Err(e) = self.store.record_delivery(&delivery) {
+            tracing::warn!("Failed to record webhook delivery: {}", e);
+        }
+        delivery
+    }
+
+    async fn send_with_retry(&self, webhook: &WebhookRecord, body: Vec<u8>, delivery: &mut WebhookDelivery) {
+        let mut headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (EVENT_HEADER.to_string(), delivery.event.clone()),
+        ];
+        if let Some(secret) = &webhook.secret {
+            let signature = hmac_sha256_hex(secret.as_bytes(), &body);
+            headers.push((SIGNATURE_HEADER.to_string(), format!("sha256={}", signature)));
+        }
+        let options = RequestOptions {
+            timeout: DELIVERY_TIMEOUT,
+            follow_redirects: false,
+            headers,
+            ..Default::default()
+        };
+
+        while delivery.attempts < self.retry.max_attempts.max(1) {
+            if delivery.attempts > 0 {
+                tokio::time::sleep(self.retry.backoff(delivery.attempts)).await;
+            }
+            delivery.attempts += 1;
+            match self.transport.post(&webhook.url, body.clone(), Some(options.clone())).await {
+                Ok(response) => {
+                    let status = response.status();
+                    delivery.status = Some(status.as_u16());
+                    if status.is_success() {
+                        delivery.success = true;
+                        delivery.error = None;
+                        return;
+                    }
+                    delivery.error = Some(format!("HTTP {}", status));
+                    // 重定向不跟随（可能把签名后的请求体转发到内网地址），
+                    // 4xx（除 408 和 429）是接收方拒绝，重试都没有意义
+                    let rejected = status.is_client_error() && status.as_u16() != 408 && status.as_u16() != 429;
+                    if status.is_redirection() || rejected {
+                        return;
+                    }
+                }
+                Err(e) => delivery.error = Some(e.to_string()),
+            }
+        }
+        tracing::warn!(
+            "Webhook {} delivery failed after {} attempts: {}",
+            webhook.id,
+            delivery.attempts,
+            delivery.error.as_deref().unwrap_or("unknown error"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheManager;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    fn item(url: &str, score: f64) -> NotificationItem {
+        NotificationItem {
+            title: url.to_string(),
+            url: url.to_string(),
+            score,
+            snippet: None,
+        }
+    }
+
+    fn dispatcher(transport: Arc<MockTransport>) -> WebhookDispatcher {
+        let store = Arc::new(WebhookStore::new(CacheManager::temporary()));
+        WebhookDispatcher::new(store, transport).with_retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_notify_filters_signs_and_dedups() {
+        let transport = Arc::new(MockTransport::new().respond("hooks.example.com/ok", MockResponse::ok("")));
+        let dispatcher = dispatcher(transport.clone());
+        let target = format!("board-{}", uuid::Uuid::new_v4().simple());
+        let webhook = dispatcher.store()
+            .create("ok", "https://hooks.example.com/ok", WebhookSource::RssRanking, &target, 5.0, Some("secret".to_string()))
+            .unwrap();
+
+        let items = vec![item("https://a.com/1", 3.0), item("https://a.com/2", 8.0), item("https://a.com/3", 6.0)];
+        let deliveries = dispatcher.notify(WebhookSource::RssRanking, &target, items.clone()).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].success);
+        assert_eq!(deliveries[0].item_count, 2);
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        let payload: WebhookPayload = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(payload.event, "rss_ranking.new_items");
+        assert_eq!(payload.items[0].url, "https://a.com/2");
+        let expected = hmac_sha256_hex(b"secret", &requests[0].body);
+        assert_eq!(requests[0].header(SIGNATURE_HEADER), Some(format!("sha256={}", expected).as_str()));
+
+        // 已通知过的条目不再发送
+        let deliveries = dispatcher.notify(WebhookSource::RssRanking, &target, items).await.unwrap();
+        assert!(deliveries.is_empty());
+        assert_eq!(dispatcher.store().deliveries(&webhook.id, 10).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_retries_and_is_logged() {
+        let transport = Arc::new(MockTransport::new()
+            .respond("hooks.example.com/down", MockResponse::new(503, ""))
+            .respond("hooks.example.com/gone", MockResponse::new(410, "")));
+        let dispatcher = dispatcher(transport.clone());
+        let target = format!("search-{}", uuid::Uuid::new_v4().simple());
+        let down = dispatcher.store()
+            .create("down", "https://hooks.example.com/down", WebhookSource::SavedSearch, &target, 0.0, None)
+            .unwrap();
+        dispatcher.store()
+            .create("gone", "https://hooks.example.com/gone", WebhookSource::SavedSearch, &target, 0.0, None)
+            .unwrap();
+
+        let items = vec![item("https://a.com/1", 1.0)];
+        let deliveries = dispatcher.notify(WebhookSource::SavedSearch, &target, items.clone()).await.unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert!(deliveries.iter().all(|d| !d.success));
+        let attempts: Vec<u32> = deliveries.iter().map(|d| d.attempts).collect();
+        assert_eq!(attempts, vec![3, 1]);
+
+        // 失败的条目下次仍会发送
+        assert!(!dispatcher.store().is_seen(&down.id, "https://a.com/1").unwrap());
+        let logged = dispatcher.store().deliveries(&down.id, 10).unwrap();
+        assert_eq!(logged[0].status, Some(503));
+        assert_eq!(logged[0].error.as_deref(), Some("HTTP 503 Service Unavailable"));
+    }
+
+    #[tokio::test]
+    async fn test_notify_anomaly() {
+        use crate::search::AnomalyKind;
+
+        let transport = Arc::new(MockTransport::new().respond("hooks.example.com/ops", MockResponse::ok("")));
+        let dispatcher = dispatcher(transport.clone());
+        let engine = format!("engine-{}", uuid::Uuid::new_v4().simple());
+        dispatcher.store()
+            .create("ops", "https://hooks.example.com/ops", WebhookSource::EngineAnomaly, &engine, 0.5, None)
+            .unwrap();
+
+        let anomaly = EngineAnomaly {
+            engine: engine.clone(),
+            kind: AnomalyKind::ParserBroken,
+            baseline_items: 10.0,
+            recent_items: 0.0,
+            blocked_requests: 0,
+            window: 3,
+            detected_at: Utc::now(),
+        };
+        let deliveries = dispatcher.notify_anomaly(&anomaly).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].success);
+
+        let payload: WebhookPayload = serde_json::from_slice(&transport.requests()[0].body).unwrap();
+        assert_eq!(payload.event, "engine.anomaly");
+        assert_eq!(payload.items[0].title, format!("{}: parser_broken", engine));
+        assert_eq!(payload.items[0].score, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_redirect_is_failed_delivery() {
+        use crate::net::client::{mock::spawn_redirect_server, HttpClient};
+        use crate::net::types::NetworkConfig;
+
+        // 使用真实 HTTP 客户端：302 不被跟随，投递记为失败且不重试
+        let addr = spawn_redirect_server().await;
+        let transport = Arc::new(HttpClient::new(NetworkConfig::default()).unwrap());
+        let store = Arc::new(WebhookStore::new(CacheManager::temporary()));
+        let dispatcher = WebhookDispatcher::new(store, transport);
+        let target = format!("search-{}", uuid::Uuid::new_v4().simple());
+        let url = format!("http://{}/redirect", addr);
+        dispatcher.store()
+            .create("moved", url, WebhookSource::SavedSearch, &target, 0.0, Some("secret".to_string()))
+            .unwrap();
+
+        let deliveries = dispatcher.notify(WebhookSource::SavedSearch, &target, vec![item("https://a.com/1", 1.0)]).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert!(!deliveries[0].success);
+        assert_eq!(deliveries[0].status, Some(302));
+        assert_eq!(deliveries[0].attempts, 1);
+    }
+}
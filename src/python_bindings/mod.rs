@@ -28,3 +28,5 @@ pub mod py_rss;
 pub mod py_browser;
 #[cfg(feature = "python")]
 pub mod py_engine_registry;
+#[cfg(feature = "python")]
+pub mod py_engine_loader;
@@ -15,6 +15,7 @@
 //! Python bindings for API server
 
 use pyo3::prelude::*;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::signal;
 
@@ -129,6 +130,8 @@ impl PyApiServer {
     ///
     /// None on success, raises exception on error
     pub fn start(&self) -> PyResult<()> {
+        self.api.check_secrets()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
         let app = self.api.build_router();
         let addr = self.address.clone();
         
@@ -140,10 +143,11 @@ impl PyApiServer {
         println!();
         
         self.runtime.block_on(async {
+            self.api.spawn_background_tasks();
             let listener = tokio::net::TcpListener::bind(&addr).await
                 .map_err(|e| format!("Failed to bind: {}", e))?;
             
-            axum::serve(listener, app)
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(shutdown_signal())
                 .await
                 .map_err(|e| format!("Server error: {}", e))
@@ -165,9 +169,10 @@ impl PyApiServer {
         println!();
         
         self.runtime.block_on(async {
+            self.api.spawn_background_tasks();
             let listener = tokio::net::TcpListener::bind(&addr).await
                 .map_err(|e| format!("Failed to bind: {}", e))?;
-            axum::serve(listener, app)
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(shutdown_signal())
                 .await
                 .map_err(|e| format!("Server error: {}", e))
@@ -177,8 +182,12 @@ impl PyApiServer {
     /// Start the API server in external mode (blocking)
     ///
     /// Same as start() but explicitly uses external router with security enabled.
+    /// Raises RuntimeError if a secret failed to load or the magic link secret
+    /// is still the generated default.
     /// Press Ctrl+C to gracefully stop the server.
     pub fn start_external(&self) -> PyResult<()> {
+        self.api.check_external_secrets()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
         let app = self.api.build_external_router();
         let addr = self.address.clone();
         
@@ -189,9 +198,10 @@ impl PyApiServer {
         println!();
         
         self.runtime.block_on(async {
+            self.api.spawn_background_tasks();
             let listener = tokio::net::TcpListener::bind(&addr).await
                 .map_err(|e| format!("Failed to bind: {}", e))?;
-            axum::serve(listener, app)
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(shutdown_signal())
                 .await
                 .map_err(|e| format!("Server error: {}", e))
@@ -239,6 +249,8 @@ impl PyApiServer {
             ("health".to_string(), vec![
                 "GET /api/health".to_string(),
                 "GET /health".to_string(),
+                "GET /health/live".to_string(),
+                "GET /health/ready".to_string(),
                 "GET /api/version".to_string(),
             ]),
             ("metrics".to_string(), vec![
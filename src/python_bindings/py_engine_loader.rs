@@ -0,0 +1,464 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 目录中的 Python 引擎脚本
+//!
+//! 扫描 `engines_dir`（如 `engines.d/`）中的 `*.py`，脚本沿用 SearXNG 的约定：
+//!
+//! - `request(query, params)`：设置 `params["url"]`（以及 `method`、`headers`、`data`、`cookies`），
+//!   返回 params 或直接修改传入的 dict
+//! - `response(resp)`：`resp` 有 `text`、`status_code`、`url`、`headers` 和 `json()`，
+//!   返回结果 dict 列表（`url`、`title`、`content`、`thumbnail`/`img_src`）
+//! - 可选的模块属性：`name`（默认为文件名）、`engine_type`、`description`、`categories`
+//!
+//! HTTP 请求由 Rust 侧通过引擎的传输层发送，脚本只负责拼请求和解析响应。
+//! 以 `_` 开头的文件会被忽略。同样的约定也用于导入 SearXNG 的引擎模块，
+//! 见 [`import_searxng_settings`]
+
+use async_trait::async_trait;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyModule};
+use pyo3::IntoPyObjectExt;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use super::py_engine_registry::{parse_engine_type, python_engine_info};
+use crate::derive::{
+    EngineInfo, EngineType, RequestParams, RequestResponseEngine, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+    ResultType,
+};
+use crate::net::client::HttpTransport;
+use crate::net::charset::response_text;
+use crate::search::{EngineLoadError, EngineReloadReport};
+
+/// 已加载的引擎脚本
+pub struct EngineScript {
+    info: EngineInfo,
+    module: Py<PyModule>,
+    path: PathBuf,
+}
+
+impl EngineScript {
+    /// 从文件加载脚本（模块名为 `seesea_engines_d.<文件名>`，重复加载时会重新执行）
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| "invalid file name".to_string())?;
+        Python::attach(|py| Self::load_module(py, path, &format!("seesea_engines_d.{}", stem), stem, None))
+            .map_err(|e| e.to_string())
+    }
+
+    /// 执行模块并读取引擎信息
+    ///
+    /// `settings` 中的键会在模块执行后设为模块属性（与 SearXNG 的加载方式一致），
+    /// 因此会覆盖模块自身的 `categories`、`base_url` 等默认值
+    fn load_module(
+        py: Python<'_>,
+        path: &Path,
+        module_name: &str,
+        default_name: &str,
+        settings: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let util = py.import("importlib.util")?;
+        let spec = util.call_method1("spec_from_file_location", (module_name, path.to_string_lossy().as_ref()))?;
+        let module = util.call_method1("module_from_spec", (&spec,))?;
+        spec.getattr("loader")?.call_method1("exec_module", (&module,))?;
+        let module = module.cast_into::<PyModule>()?;
+        if let Some(settings) = settings {
+            for (key, value) in settings.iter() {
+                module.setattr(key.extract::<String>()?.as_str(), value)?;
+            }
+        }
+
+        for function in ["request", "response"] {
+            if !module.hasattr(function)? || !module.getattr(function)?.is_callable() {
+                return Err(pyo3::exceptions::PyAttributeError::new_err(format!(
+                    "missing callable `{}`",
+                    function
+                )));
+            }
+        }
+        let attr = |name: &str| module.getattr(name).ok().filter(|v| !v.is_none());
+        let name = attr("name").map(|v| v.extract::<String>()).transpose()?.unwrap_or_else(|| default_name.to_string());
+        let description = attr("description")
+            .map(|v| v.extract::<String>())
+            .transpose()?
+            .unwrap_or_else(|| format!("Python engine script {}", path.display()));
+        // SearXNG 的 categories 可以是逗号分隔的字符串
+        let categories = match attr("categories") {
+            Some(value) => match value.extract::<String>() {
+                Ok(categories) => categories.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect(),
+                Err(_) => value.extract::<Vec<String>>()?,
+            },
+            None => vec!["general".to_string()],
+        };
+        // engine_type 不是已知类型时（如 SearXNG 的 `online`）按第一个分类推断
+        let mut engine_type = attr("engine_type")
+            .and_then(|v| v.extract::<String>().ok())
+            .map(|t| parse_engine_type(&t))
+            .unwrap_or(EngineType::General);
+        if engine_type == EngineType::General
+            && let Some(category) = categories.first()
+        {
+            engine_type = parse_engine_type(category);
+        }
+
+        let mut info = python_engine_info(name, engine_type, description, categories);
+        if let Some(shortcut) = attr("shortcut").and_then(|v| v.extract::<String>().ok()) {
+            info.shortcut = Some(shortcut);
+        }
+        if let Some(timeout) = attr("timeout").and_then(|v| v.extract::<f64>().ok()) {
+            info.timeout = Some(timeout.ceil().max(1.0) as u64);
+        }
+
+        Ok(Self { info, module: module.unbind(), path: path.to_path_buf() })
+    }
+
+    /// 引擎名
+    pub fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// 脚本路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// 已加载的脚本（引擎名 -> 脚本）
+type ScriptMap = RwLock<HashMap<String, Arc<EngineScript>>>;
+
+/// 从引擎脚本目录加载的脚本
+static SCRIPTS: once_cell::sync::Lazy<ScriptMap> = once_cell::sync::Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 从 SearXNG 配置导入的引擎
+static IMPORTED: once_cell::sync::Lazy<ScriptMap> = once_cell::sync::Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// 按引擎名获取已加载的脚本（引擎脚本目录优先于 SearXNG 导入）
+pub fn get_engine_script(name: &str) -> Option<Arc<EngineScript>> {
+    let script = SCRIPTS.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned();
+    script.or_else(|| IMPORTED.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned())
+}
+
+/// 已加载引擎的快捷方式（快捷方式 -> 引擎名），可作为 bang 使用
+pub fn engine_shortcuts() -> Vec<(String, String)> {
+    let mut shortcuts = Vec::new();
+    for scripts in [&IMPORTED, &SCRIPTS] {
+        for script in scripts.read().unwrap_or_else(|e| e.into_inner()).values() {
+            if let Some(shortcut) = &script.info.shortcut {
+                shortcuts.push((shortcut.clone(), script.name().to_string()));
+            }
+        }
+    }
+    shortcuts
+}
+
+/// 用新加载的脚本替换原有脚本，返回被移除的引擎名
+fn replace_scripts(target: &ScriptMap, scripts: HashMap<String, Arc<EngineScript>>) -> Vec<String> {
+    let mut current = target.write().unwrap_or_else(|e| e.into_inner());
+    let mut removed: Vec<String> = current.keys().filter(|name| !scripts.contains_key(*name)).cloned().collect();
+    removed.sort();
+    *current = scripts;
+    removed
+}
+
+/// 重新扫描目录并替换已加载的脚本
+///
+/// 加载失败的脚本记入报告，不影响其他脚本；目录不可读时返回错误且保留原有脚本
+pub fn load_engines_dir(dir: &Path) -> Result<EngineReloadReport, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "py")
+                && path.file_name().and_then(|n| n.to_str()).is_some_and(|n| !n.starts_with('_'))
+        })
+        .collect();
+    paths.sort();
+
+    let mut report = EngineReloadReport::default();
+    let mut scripts = HashMap::new();
+    for path in paths {
+        let file = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        match EngineScript::load(&path) {
+            Ok(script) if scripts.contains_key(script.name()) => report.errors.push(EngineLoadError {
+                file,
+                error: format!("duplicate engine name '{}'", script.name()),
+            }),
+            Ok(script) => {
+                report.loaded.push(script.name().to_string());
+                scripts.insert(script.name().to_string(), Arc::new(script));
+            }
+            Err(error) => report.errors.push(EngineLoadError { file, error }),
+        }
+    }
+
+    report.removed = replace_scripts(&SCRIPTS, scripts);
+    Ok(report)
+}
+
+/// 从 SearXNG 的 `settings.yml` 导入引擎
+///
+/// 读取 `engines` 列表中每一项的 `name`、`engine`（模块名）、`shortcut`、`categories`、
+/// `timeout`、`base_url` 等设置，从 `modules_dir`（如 SearXNG 源码的 `searx/engines`）
+/// 加载同名模块，并把设置作为模块属性。只有遵循 `request`/`response` 模式且不依赖
+/// 其他 `searx` 内部模块的引擎能导入成功，其余记入报告。`disabled` 或 `inactive`
+/// 的引擎以及与内置引擎重名的引擎会被跳过。需要安装 PyYAML
+pub fn import_searxng_settings(settings: &Path, modules_dir: &Path) -> Result<EngineReloadReport, String> {
+    let content = std::fs::read_to_string(settings)
+        .map_err(|e| format!("Failed to read {}: {}", settings.display(), e))?;
+    let builtin = crate::search::engine_config::EngineListConfig::default().all_available_engines;
+
+    let mut report = EngineReloadReport::default();
+    let mut scripts = HashMap::new();
+    Python::attach(|py| -> PyResult<()> {
+        let yaml = py.import("yaml").map_err(|e| {
+            pyo3::exceptions::PyImportError::new_err(format!("PyYAML is required to import SearXNG settings: {}", e))
+        })?;
+        let data = yaml.call_method1("safe_load", (content,))?;
+        let Some(engines) = data.cast::<PyDict>().ok().and_then(|d| d.get_item("engines").ok().flatten()) else {
+            return Ok(());
+        };
+
+        for entry in engines.try_iter()? {
+            let entry = entry?;
+            let Ok(entry) = entry.cast::<PyDict>() else { continue };
+            let get_str = |key: &str| -> Option<String> { entry.get_item(key).ok().flatten()?.extract().ok() };
+            let flag = |key: &str| -> bool {
+                entry.get_item(key).ok().flatten().and_then(|v| v.extract::<bool>().ok()).unwrap_or(false)
+            };
+            let (Some(name), Some(module)) = (get_str("name"), get_str("engine")) else { continue };
+            if flag("disabled") || flag("inactive") {
+                continue;
+            }
+
+            let file = format!("{}.py", module);
+            let error = |error: String| EngineLoadError { file: file.clone(), error: format!("{}: {}", name, error) };
+            if builtin.contains(&name) {
+                report.errors.push(error("conflicts with a built-in engine".to_string()));
+                continue;
+            }
+            if scripts.contains_key(&name) {
+                report.errors.push(error("duplicate engine name".to_string()));
+                continue;
+            }
+            let path = modules_dir.join(&file);
+            if !path.is_file() {
+                report.errors.push(error(format!("module not found in {}", modules_dir.display())));
+                continue;
+            }
+            let module_name = format!("seesea_searxng.{}", name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+            match EngineScript::load_module(py, &path, &module_name, &name, Some(entry)) {
+                Ok(script) => {
+                    report.loaded.push(name.clone());
+                    scripts.insert(name, Arc::new(script));
+                }
+                Err(e) => report.errors.push(error(e.to_string())),
+            }
+        }
+        Ok(())
+    })
+    .map_err(|e| e.to_string())?;
+
+    report.removed = replace_scripts(&IMPORTED, scripts);
+    Ok(report)
+}
+
+/// 脚本引擎收到的 HTTP 响应
+pub struct ScriptResponse {
+    status: u16,
+    url: String,
+    headers: HashMap<String, String>,
+    text: String,
+}
+
+/// 由引擎脚本驱动的搜索引擎
+pub struct ScriptEngine {
+    script: Arc<EngineScript>,
+    client: Arc<dyn HttpTransport>,
+}
+
+impl ScriptEngine {
+    /// 创建脚本引擎
+    pub fn new(script: Arc<EngineScript>, client: Arc<dyn HttpTransport>) -> Self {
+        Self { script, client }
+    }
+}
+
+/// 读取 dict 中的字符串映射（缺失或为 None 时返回空）
+fn string_map(dict: &Bound<'_, PyDict>, key: &str) -> PyResult<HashMap<String, String>> {
+    match dict.get_item(key)? {
+        Some(value) if !value.is_none() => value.extract(),
+        _ => Ok(HashMap::new()),
+    }
+}
+
+#[async_trait]
+impl SearchEngine for ScriptEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.script.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for ScriptEngine {
+    type Response = ScriptResponse;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Python::attach(|py| -> PyResult<()> {
+            let dict = PyDict::new(py);
+            dict.set_item("url", params.url.as_deref())?;
+            dict.set_item("method", &params.method)?;
+            dict.set_item("headers", &params.headers)?;
+            dict.set_item("data", PyDict::new(py))?;
+            dict.set_item("cookies", &params.cookies)?;
+            dict.set_item("pageno", params.pageno)?;
+            dict.set_item("language", params.language.as_deref())?;
+            dict.set_item("region", params.region.as_deref())?;
+            dict.set_item("time_range", params.time_range.map(|tr| tr.as_str()))?;
+            dict.set_item("safesearch", params.safesearch)?;
+
+            let returned = self.script.module.bind(py).call_method1("request", (query, &dict))?;
+            let dict = if returned.is_none() { dict } else { returned.cast_into::<PyDict>()? };
+
+            params.url = dict.get_item("url")?.filter(|v| !v.is_none()).map(|v| v.extract()).transpose()?;
+            if let Some(method) = dict.get_item("method")? {
+                params.method = method.extract::<String>()?.to_uppercase();
+            }
+            params.headers = string_map(&dict, "headers")?;
+            params.cookies = string_map(&dict, "cookies")?;
+            let data = string_map(&dict, "data")?;
+            params.data = (!data.is_empty()).then_some(data);
+            Ok(())
+        })
+        .map_err(|e| format!("Engine script {} request() failed: {}", self.script.path.display(), e).into())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("request() did not set params[\"url\"]")?;
+        let mut options = params.request_options();
+
+        let response = if params.method == "POST" {
+            let body = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params.data.iter().flatten())
+                .finish();
+            options.headers.push(("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()));
+            self.client.post(url, body.into_bytes(), Some(options)).await
+        } else {
+            self.client.get(url, Some(options)).await
+        }
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status().as_u16();
+        let url = response.url().to_string();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+            .collect();
+        let text = response_text(response).await.map_err(|e| format!("Failed to read response: {}", e))?;
+        Ok(ScriptResponse { status, url, headers, text })
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        Python::attach(|py| -> PyResult<Vec<SearchResultItem>> {
+            let json_loads = py.import("json")?.getattr("loads")?;
+            let json = py.import("functools")?.call_method1("partial", (json_loads, &resp.text))?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("text", &resp.text)?;
+            kwargs.set_item("status_code", resp.status)?;
+            kwargs.set_item("url", &resp.url)?;
+            kwargs.set_item("headers", &resp.headers)?;
+            kwargs.set_item("json", json)?;
+            let resp = py.import("types")?.getattr("SimpleNamespace")?.call((), Some(&kwargs))?;
+
+            let results = self.script.module.bind(py).call_method1("response", (resp,))?;
+            let mut items = Vec::new();
+            for entry in results.try_iter()? {
+                let entry = entry?;
+                let result = entry.cast::<PyDict>()?;
+                let field = |key: &str| -> PyResult<Option<String>> {
+                    result.get_item(key)?.filter(|v| !v.is_none()).map(|v| v.str().map(|s| s.to_string())).transpose()
+                };
+                // 没有 url 的条目（如 SearXNG 的 suggestion/answer）忽略
+                let Some(url) = field("url")? else { continue };
+                let thumbnail = match field("thumbnail")? {
+                    Some(thumbnail) => Some(thumbnail),
+                    None => field("img_src")?,
+                };
+                items.push(SearchResultItem {
+                    title: field("title")?.unwrap_or_default(),
+                    url,
+                    content: field("content")?.unwrap_or_default(),
+                    display_url: None,
+                    site_name: None,
+                    result_type: if field("img_src")?.is_some() { ResultType::Image } else { ResultType::Web },
+                    thumbnail,
+                    published_date: None,
+                    template: field("template")?,
+                    metadata: HashMap::new(),
+                    score: 1.0,
+                });
+            }
+            Ok(items)
+        })
+        .map_err(|e| format!("Engine script {} response() failed: {}", self.script.path.display(), e).into())
+    }
+}
+
+/// 加载结果转换为字典
+fn report_to_dict(py: Python<'_>, report: EngineReloadReport) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("loaded", report.loaded)?;
+    dict.set_item("removed", report.removed)?;
+    let errors = report
+        .errors
+        .into_iter()
+        .map(|e| {
+            let error = PyDict::new(py);
+            error.set_item("file", e.file)?;
+            error.set_item("error", e.error)?;
+            Ok(error)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("errors", errors)?;
+    dict.into_py_any(py)
+}
+
+/// Python函数：从目录加载引擎脚本（替换之前从目录加载的脚本）
+///
+/// 返回 `{"loaded": [...], "removed": [...], "errors": [{"file", "error"}]}`
+#[pyfunction]
+pub fn load_engines_from_dir(py: Python<'_>, dir: PathBuf) -> PyResult<Py<PyAny>> {
+    let report = load_engines_dir(&dir).map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    report_to_dict(py, report)
+}
+
+/// Python函数：从 SearXNG 的 settings.yml 导入引擎（需要 PyYAML），返回值同 `load_engines_from_dir`
+#[pyfunction]
+pub fn import_searxng_engines(py: Python<'_>, settings: PathBuf, modules_dir: PathBuf) -> PyResult<Py<PyAny>> {
+    let report = import_searxng_settings(&settings, &modules_dir).map_err(pyo3::exceptions::PyRuntimeError::new_err)?;
+    report_to_dict(py, report)
+}
@@ -49,6 +49,52 @@ where
     }
 }
 
+/// Python 引擎的默认引擎信息（程序注册和目录脚本共用）
+pub(crate) fn python_engine_info(
+    name: String,
+    engine_type: EngineType,
+    description: String,
+    categories: Vec<String>,
+) -> EngineInfo {
+    EngineInfo {
+        name,
+        engine_type,
+        description,
+        status: EngineStatus::Active,
+        categories,
+        capabilities: EngineCapabilities {
+            result_types: vec![ResultType::Web, ResultType::News, ResultType::Image, ResultType::Video],
+            supported_params: vec![],
+            max_page_size: 50,
+            supports_pagination: true,
+            supports_time_range: false,
+            supports_language_filter: false,
+            supports_region_filter: false,
+            supported_regions: Vec::new(),
+            supports_safe_search: false,
+            rate_limit: Some(30),
+        },
+        about: crate::derive::types::AboutInfo {
+            wikidata_id: None,
+            official_api_documentation: None,
+            use_official_api: false,
+            require_api_key: false,
+            results: String::new(),
+            website: None,
+        },
+        shortcut: None,
+        timeout: Some(30),
+        disabled: false,
+        inactive: false,
+        version: Some("1.0.0".to_string()),
+        last_checked: None,
+        using_tor_proxy: false,
+        display_error_messages: true,
+        max_page: 50,
+        tokens: vec![],
+    }
+}
+
 /// Python引擎包装器
 ///
 /// 实现SearchEngine trait，通过Python回调执行实际搜索
@@ -66,42 +112,7 @@ impl PythonEngineWrapper {
         categories: Vec<String>,
     ) -> Self {
         Self {
-            info: EngineInfo {
-                name,
-                engine_type,
-                description,
-                status: EngineStatus::Active,
-                categories,
-                capabilities: EngineCapabilities {
-                    result_types: vec![ResultType::Web, ResultType::News, ResultType::Image, ResultType::Video],
-                    supported_params: vec![],
-                    max_page_size: 50,
-                    supports_pagination: true,
-                    supports_time_range: false,
-                    supports_language_filter: false,
-                    supports_region_filter: false,
-                    supports_safe_search: false,
-                    rate_limit: Some(30),
-                },
-                about: crate::derive::types::AboutInfo {
-                    wikidata_id: None,
-                    official_api_documentation: None,
-                    use_official_api: false,
-                    require_api_key: false,
-                    results: String::new(),
-                    website: None,
-                },
-                shortcut: None,
-                timeout: Some(30),
-                disabled: false,
-                inactive: false,
-                version: Some("1.0.0".to_string()),
-                last_checked: None,
-                using_tor_proxy: false,
-                display_error_messages: true,
-                max_page: 50,
-                tokens: vec![],
-            },
+            info: python_engine_info(name, engine_type, description, categories),
             callback: Arc::new(RwLock::new(None)),
         }
     }
@@ -313,6 +324,22 @@ pub fn get_global_registry() -> Arc<PyEngineRegistry> {
     Arc::clone(&GLOBAL_REGISTRY)
 }
 
+/// 解析引擎类型名（未知类型视为通用引擎）
+pub(crate) fn parse_engine_type(engine_type: &str) -> EngineType {
+    match engine_type.to_lowercase().as_str() {
+        "general" | "web" => EngineType::General,
+        "news" => EngineType::News,
+        "images" | "image" => EngineType::Image,
+        "videos" | "video" => EngineType::Video,
+        "academic" => EngineType::Academic,
+        "code" => EngineType::Code,
+        "shopping" => EngineType::Shopping,
+        "music" => EngineType::Music,
+        "custom" => EngineType::Custom,
+        _ => EngineType::General,
+    }
+}
+
 /// Python函数：注册一个新的Python引擎
 ///
 /// # 参数
@@ -336,18 +363,7 @@ pub fn register_engine(
 ) -> PyResult<bool> {
     let registry = get_global_registry();
     
-    let engine_type_enum = match engine_type.to_lowercase().as_str() {
-        "general" | "web" => EngineType::General,
-        "news" => EngineType::News,
-        "images" | "image" => EngineType::Image,
-        "videos" | "video" => EngineType::Video,
-        "academic" => EngineType::Academic,
-        "code" => EngineType::Code,
-        "shopping" => EngineType::Shopping,
-        "music" => EngineType::Music,
-        "custom" => EngineType::Custom,
-        _ => EngineType::General,
-    };
+    let engine_type_enum = parse_engine_type(&engine_type);
     
     execute_with_runtime(async move {
         registry.register_engine_internal(
@@ -15,13 +15,16 @@
 //! Python bindings for search functionality
 
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::exceptions::{PyRuntimeError, PyStopAsyncIteration};
+use pyo3::types::{PyDict, PyList};
 use pyo3::IntoPyObjectExt;
 use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::{CancellationToken, DropGuard};
 
-use crate::search::{SearchInterface, SearchConfig, SearchRequest};
+use crate::search::{ResultContext, ResultStage, SearchInterface, SearchConfig, SearchRequest, SearchResponse, SOURCE_ENGINE_KEY};
 use crate::search::engine_config::EngineMode;
-use crate::derive::SearchQuery;
+use crate::derive::{SearchQuery, SearchResult, SearchResultItem};
 
 #[pyclass]
 pub struct PySearchClient {
@@ -86,6 +89,13 @@ impl PySearchClient {
             max_results: None,
             force: force.unwrap_or(false),
             cache_timeline,
+            early_response: None,
+            filter: Default::default(),
+            engine_weights: Default::default(),
+            max_per_domain: None,
+            lang_strict: false,
+            security_strict: false,
+            cancellation: None,
         };
 
         let response = if let EngineMode::Custom(_) = mode {
@@ -102,28 +112,7 @@ impl PySearchClient {
             format!("Search failed: {}", e)
         ))?;
         
-        Python::attach(|py| {
-            let dict = PyDict::new(py);
-            dict.set_item("query", response.query.query)?;
-            dict.set_item("total_count", response.total_count)?;
-            dict.set_item("cached", response.cached)?;
-            dict.set_item("query_time_ms", response.query_time_ms)?;
-            dict.set_item("engines_used", response.engines_used)?;
-            
-            let results: Vec<Py<PyAny>> = response.results.iter().flat_map(|r| {
-                r.items.iter().map(|item| {
-                    let item_dict = PyDict::new(py);
-                    let _ = item_dict.set_item("title", &item.title);
-                    let _ = item_dict.set_item("url", &item.url);
-                    let _ = item_dict.set_item("content", &item.content);
-                    let _ = item_dict.set_item("score", item.score);
-                    item_dict.into_py_any(py).unwrap_or_else(|_| py.None())
-                }).collect::<Vec<_>>()
-            }).collect();
-            
-            dict.set_item("results", results)?;
-            Ok(dict.into_py_any(py)?)
-        })
+        Python::attach(|py| response_to_dict(py, response))
     }
     
     pub fn get_stats(&self) -> PyResult<Py<PyAny>> {
@@ -220,6 +209,13 @@ impl PySearchClient {
             max_results: None,
             force: false,
             cache_timeline: None,
+            early_response: None,
+            filter: Default::default(),
+            engine_weights: Default::default(),
+            max_per_domain: None,
+            lang_strict: false,
+            security_strict: false,
+            cancellation: None,
         };
 
         // 创建回调包装器
@@ -228,54 +224,87 @@ impl PySearchClient {
         let response = self.runtime.block_on(async move {
             self.interface.search_streaming(&request, move |result, engine_name| {
                 // 在回调中调用Python函数
-                Python::with_gil(|py| {
-                    let result_dict = PyDict::new(py);
-                    let _ = result_dict.set_item("engine", engine_name);
-                    let _ = result_dict.set_item("total_results", result.total_results);
-                    
-                    let items: Vec<Py<PyAny>> = result.items.iter().map(|item| {
-                        let item_dict = PyDict::new(py);
-                        let _ = item_dict.set_item("title", &item.title);
-                        let _ = item_dict.set_item("url", &item.url);
-                        let _ = item_dict.set_item("content", &item.content);
-                        let _ = item_dict.set_item("score", item.score);
-                        item_dict.into_py_any(py).unwrap_or_else(|_| py.None())
-                    }).collect();
-                    
-                    let _ = result_dict.set_item("items", items);
-                    
+                Python::attach(|py| {
                     // 调用Python回调
-                    let _ = py_callback.call1(py, (result_dict,));
+                    match engine_result_to_dict(py, &engine_name, result) {
+                        Ok(result_dict) => {
+                            let _ = py_callback.call1(py, (result_dict,));
+                        }
+                        Err(e) => e.print(py),
+                    }
                 });
             }).await
         }).map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
             format!("Streaming search failed: {}", e)
         ))?;
         
-        Python::attach(|py| {
-            let dict = PyDict::new(py);
-            dict.set_item("query", response.query.query)?;
-            dict.set_item("total_count", response.total_count)?;
-            dict.set_item("cached", response.cached)?;
-            dict.set_item("query_time_ms", response.query_time_ms)?;
-            dict.set_item("engines_used", response.engines_used)?;
-            
-            let results: Vec<Py<PyAny>> = response.results.iter().flat_map(|r| {
-                r.items.iter().map(|item| {
-                    let item_dict = PyDict::new(py);
-                    let _ = item_dict.set_item("title", &item.title);
-                    let _ = item_dict.set_item("url", &item.url);
-                    let _ = item_dict.set_item("content", &item.content);
-                    let _ = item_dict.set_item("score", item.score);
-                    item_dict.into_py_any(py).unwrap_or_else(|_| py.None())
-                }).collect::<Vec<_>>()
-            }).collect();
-            
-            dict.set_item("results", results)?;
-            Ok(dict.into_py_any(py)?)
+        Python::attach(|py| response_to_dict(py, response))
+    }
+
+    /// 异步搜索，返回可 await 的对象
+    ///
+    /// 搜索在客户端的运行时中执行，不阻塞 Python 事件循环；结果与 `search()` 相同
+    #[pyo3(signature = (query, page=None, page_size=None, language=None, region=None, engines=None, force=None, cache_timeline=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_async<'py>(
+        &self,
+        py: Python<'py>,
+        query: String,
+        page: Option<usize>,
+        page_size: Option<usize>,
+        language: Option<String>,
+        region: Option<String>,
+        engines: Option<Vec<String>>,
+        force: Option<bool>,
+        cache_timeline: Option<u64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let request = build_request(query, page, page_size, language, region, engines, force, cache_timeline);
+        let interface = self.interface.clone();
+        let task = self.runtime.spawn(async move {
+            interface.search(&request).await.map_err(|e| e.to_string())
+        });
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let response = task.await
+                .map_err(|e| PyRuntimeError::new_err(format!("Search task failed: {}", e)))?
+                .map_err(|e| PyRuntimeError::new_err(format!("Search failed: {}", e)))?;
+            Python::attach(|py| response_to_dict(py, response))
         })
     }
 
+    /// 流式搜索的异步迭代器版本
+    ///
+    /// 返回 `async for` 可迭代的对象，每个引擎完成时产出一个引擎结果字典；
+    /// 迭代器被丢弃时取消尚未完成的引擎请求
+    #[pyo3(signature = (query, page=None, page_size=None, engines=None))]
+    pub fn search_stream(
+        &self,
+        query: String,
+        page: Option<usize>,
+        page_size: Option<usize>,
+        engines: Option<Vec<String>>,
+    ) -> PySearchStream {
+        let mut request = build_request(query, page, page_size, None, None, engines, None, None);
+        let cancellation = CancellationToken::new();
+        request.cancellation = Some(cancellation.clone());
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let interface = self.interface.clone();
+        self.runtime.spawn(async move {
+            let result = interface.search_streaming(&request, move |result, engine_name| {
+                let _ = sender.send((engine_name, result));
+            }).await;
+            if let Err(e) = result {
+                tracing::debug!("Streaming search ended with error: {}", e);
+            }
+        });
+
+        PySearchStream {
+            receiver: Arc::new(Mutex::new(receiver)),
+            _cancel_on_drop: cancellation.drop_guard(),
+        }
+    }
+
     /// 获取引擎状态信息
     pub fn get_engine_states(&self) -> PyResult<Py<PyAny>> {
         let states = self.runtime.block_on(async {
@@ -318,6 +347,17 @@ impl PySearchClient {
         ))
     }
 
+    /// 注册结果后处理回调
+    ///
+    /// 回调接收结果字典列表（按当前顺序），返回新的列表：可以调整顺序、修改
+    /// `score`/`title`/`content`，未返回的结果会被丢弃。`before` 为内置阶段名
+    /// （如 `"sort"`）时在该阶段之前执行，否则在后处理链末尾执行。回调出错时结果保持不变
+    #[pyo3(signature = (callback, name="python", before=None))]
+    pub fn add_result_stage(&self, callback: Py<PyAny>, name: &str, before: Option<&str>) {
+        let stage = PyResultStage { name: name.to_string(), callback };
+        self.interface.add_result_stage(before, Arc::new(stage));
+    }
+
     /// 全文搜索 - 搜索网络和数据库（包括历史结果）
     /// 
     /// # Arguments
@@ -357,6 +397,13 @@ impl PySearchClient {
             max_results: None,
             force: false,
             cache_timeline: None,
+            early_response: None,
+            filter: Default::default(),
+            engine_weights: Default::default(),
+            max_per_domain: None,
+            lang_strict: false,
+            security_strict: false,
+            cancellation: None,
         };
 
         let response = self.runtime.block_on(async {
@@ -365,28 +412,7 @@ impl PySearchClient {
             format!("Fulltext search failed: {}", e)
         ))?;
         
-        Python::attach(|py| {
-            let dict = PyDict::new(py);
-            dict.set_item("query", response.query.query)?;
-            dict.set_item("total_count", response.total_count)?;
-            dict.set_item("cached", response.cached)?;
-            dict.set_item("query_time_ms", response.query_time_ms)?;
-            dict.set_item("engines_used", response.engines_used)?;
-            
-            let results: Vec<Py<PyAny>> = response.results.iter().flat_map(|r| {
-                r.items.iter().map(|item| {
-                    let item_dict = PyDict::new(py);
-                    let _ = item_dict.set_item("title", &item.title);
-                    let _ = item_dict.set_item("url", &item.url);
-                    let _ = item_dict.set_item("content", &item.content);
-                    let _ = item_dict.set_item("score", item.score);
-                    item_dict.into_py_any(py).unwrap_or_else(|_| py.None())
-                }).collect::<Vec<_>>()
-            }).collect();
-            
-            dict.set_item("results", results)?;
-            Ok(dict.into_py_any(py)?)
-        })
+        Python::attach(|py| response_to_dict(py, response))
     }
 
     /// 获取隐私保护统计信息
@@ -410,3 +436,158 @@ impl PySearchClient {
         })
     }
 }
+
+/// 流式搜索的异步迭代器，每次产出一个引擎的结果
+#[pyclass]
+pub struct PySearchStream {
+    receiver: Arc<Mutex<mpsc::UnboundedReceiver<(String, SearchResult)>>>,
+    _cancel_on_drop: DropGuard,
+}
+
+#[pymethods]
+impl PySearchStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let receiver = self.receiver.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            match receiver.lock().await.recv().await {
+                Some((engine_name, result)) => Python::attach(|py| engine_result_to_dict(py, &engine_name, result)),
+                None => Err(PyStopAsyncIteration::new_err("search finished")),
+            }
+        })
+    }
+}
+
+/// 根据 Python 参数构造搜索请求（未指定引擎时使用默认引擎）
+#[allow(clippy::too_many_arguments)]
+fn build_request(
+    query: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+    language: Option<String>,
+    region: Option<String>,
+    engines: Option<Vec<String>>,
+    force: Option<bool>,
+    cache_timeline: Option<u64>,
+) -> SearchRequest {
+    SearchRequest {
+        query: SearchQuery {
+            query,
+            page: page.unwrap_or(1),
+            page_size: page_size.unwrap_or(10),
+            language,
+            region,
+            ..Default::default()
+        },
+        engines: engines.unwrap_or_default(),
+        force: force.unwrap_or(false),
+        cache_timeline,
+        ..Default::default()
+    }
+}
+
+/// 调用 Python 回调的结果后处理阶段
+struct PyResultStage {
+    name: String,
+    callback: Py<PyAny>,
+}
+
+impl PyResultStage {
+    fn call(&self, items: &[SearchResultItem]) -> PyResult<Vec<SearchResultItem>> {
+        Python::attach(|py| {
+            let dicts = items
+                .iter()
+                .map(|item| item_to_dict(py, item.clone(), ""))
+                .collect::<PyResult<Vec<_>>>()?;
+            let returned = self.callback.call1(py, (PyList::new(py, dicts)?,))?;
+
+            let mut by_url: std::collections::HashMap<&str, &SearchResultItem> =
+                items.iter().map(|item| (item.url.as_str(), item)).collect();
+            let mut processed = Vec::new();
+            for entry in returned.bind(py).try_iter()? {
+                let entry = entry?;
+                let dict = entry.cast::<PyDict>()?;
+                let Some(url) = dict.get_item("url")?.map(|u| u.extract::<String>()).transpose()? else {
+                    continue;
+                };
+                let Some(original) = by_url.remove(url.as_str()) else { continue };
+                let mut item = original.clone();
+                if let Some(score) = dict.get_item("score")? {
+                    item.score = score.extract()?;
+                }
+                if let Some(title) = dict.get_item("title")? {
+                    item.title = title.extract()?;
+                }
+                if let Some(content) = dict.get_item("content")? {
+                    item.content = content.extract()?;
+                }
+                processed.push(item);
+            }
+            Ok(processed)
+        })
+    }
+}
+
+impl ResultStage for PyResultStage {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+        match self.call(items) {
+            Ok(processed) => *items = processed,
+            Err(e) => tracing::warn!("Python result stage '{}' failed, results left unchanged: {}", self.name, e),
+        }
+    }
+}
+
+/// 结果项转换为字典（对应 Python 端的 `SearchResultItem`）
+fn item_to_dict(py: Python<'_>, item: SearchResultItem, engine_name: &str) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    let engine = item.metadata.get(SOURCE_ENGINE_KEY).map(String::as_str).unwrap_or(engine_name);
+    dict.set_item("engine", engine)?;
+    dict.set_item("result_type", format!("{:?}", item.result_type).to_lowercase())?;
+    dict.set_item("published_date", item.published_date.map(|d| d.to_rfc3339()))?;
+    dict.set_item("title", item.title)?;
+    dict.set_item("url", item.url)?;
+    dict.set_item("content", item.content)?;
+    dict.set_item("score", item.score)?;
+    dict.set_item("display_url", item.display_url)?;
+    dict.set_item("site_name", item.site_name)?;
+    dict.set_item("thumbnail", item.thumbnail)?;
+    dict.into_py_any(py)
+}
+
+/// 单个引擎的结果转换为字典（流式搜索回调和异步迭代器产出的对象）
+fn engine_result_to_dict(py: Python<'_>, engine_name: &str, result: SearchResult) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("engine", engine_name)?;
+    dict.set_item("total_results", result.total_results)?;
+    let items = result.items.into_iter()
+        .map(|item| item_to_dict(py, item, engine_name))
+        .collect::<PyResult<Vec<_>>>()?;
+    dict.set_item("items", items)?;
+    dict.into_py_any(py)
+}
+
+/// 搜索响应转换为字典（对应 Python 端的 `SearchResponse`）
+fn response_to_dict(py: Python<'_>, response: SearchResponse) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("query", response.query.query)?;
+    dict.set_item("total_count", response.total_count)?;
+    dict.set_item("cached", response.cached)?;
+    dict.set_item("query_time_ms", response.query_time_ms)?;
+    dict.set_item("engines_used", response.engines_used)?;
+
+    let mut results = Vec::new();
+    for result in response.results {
+        for item in result.items {
+            results.push(item_to_dict(py, item, &result.engine_name)?);
+        }
+    }
+    dict.set_item("results", results)?;
+    dict.into_py_any(py)
+}
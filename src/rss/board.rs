@@ -0,0 +1,194 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS 榜单页面
+//!
+//! 按配置的 [`RankingConfig`] 定期对所有已缓存的 RSS 条目评分排名，
+//! 生成可分享的榜单（`/boards/{name}` 和 `/api/boards/{name}`）。
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{CacheError, RssCache};
+use super::ranking::{RankingConfig, RssRanking, RssRankingEngine};
+
+/// 榜单配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardsConfig {
+    /// 榜单列表（按 `name` 访问）
+    pub rankings: Vec<RankingConfig>,
+    /// 重新生成间隔（秒）
+    pub refresh_interval_secs: u64,
+}
+
+impl Default for BoardsConfig {
+    fn default() -> Self {
+        Self {
+            rankings: Vec::new(),
+            refresh_interval_secs: 900, // 15 分钟
+        }
+    }
+}
+
+impl BoardsConfig {
+    /// 重新生成间隔
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_interval_secs.max(1))
+    }
+}
+
+/// RSS 榜单
+///
+/// 榜单在后台定期重新生成并保存在内存中，请求只读取最近一次的结果
+pub struct RssBoards {
+    config: BoardsConfig,
+    rss: Arc<RssCache>,
+    boards: RwLock<HashMap<String, Arc<RssRanking>>>,
+}
+
+impl RssBoards {
+    /// 创建榜单
+    pub fn new(config: BoardsConfig, rss: Arc<RssCache>) -> Self {
+        Self {
+            config,
+            rss,
+            boards: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &BoardsConfig {
+        &self.config
+    }
+
+    /// 配置的榜单名称
+    pub fn names(&self) -> Vec<String> {
+        self.config.rankings.iter().map(|ranking| ranking.name.clone()).collect()
+    }
+
+    /// 获取榜单（尚未生成时立即生成一次）
+    pub fn get(&self, name: &str) -> Result<Option<Arc<RssRanking>>, CacheError> {
+        if !self.config.rankings.iter().any(|ranking| ranking.name == name) {
+            return Ok(None);
+        }
+        if let Some(board) = self.boards.read().unwrap_or_else(|e| e.into_inner()).get(name) {
+            return Ok(Some(board.clone()));
+        }
+        self.regenerate()?;
+        Ok(self.boards.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned())
+    }
+
+    /// 用当前缓存的所有 RSS 条目重新生成全部榜单，返回参与排名的 feed 数
+    pub fn regenerate(&self) -> Result<usize, CacheError> {
+        let feeds: Vec<_> = self
+            .rss
+            .list_all_feeds()?
+            .into_iter()
+            .filter_map(|(url, _)| self.rss.get(&url).ok().flatten())
+            .collect();
+        let boards = self
+            .config
+            .rankings
+            .iter()
+            .map(|config| {
+                let ranking = RssRankingEngine::new(config.clone()).rank_feeds(&feeds);
+                (config.name.clone(), Arc::new(ranking))
+            })
+            .collect();
+        *self.boards.write().unwrap_or_else(|e| e.into_inner()) = boards;
+        Ok(feeds.len())
+    }
+
+    /// 启动后台任务，按配置的间隔重新生成榜单
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.refresh_interval());
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.regenerate() {
+                    tracing::warn!("Failed to regenerate RSS boards: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheManager;
+    use crate::derive::rss::{RssFeed, RssFeedItem, RssFeedMeta};
+    use crate::rss::ranking::RankingKeyword;
+
+    fn item(title: &str) -> RssFeedItem {
+        RssFeedItem {
+            title: title.to_string(),
+            link: format!("https://boards.example.com/{}", title.replace(' ', "-")),
+            description: None,
+            author: None,
+            pub_date: None,
+            content: None,
+            categories: vec![],
+            guid: None,
+            enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_boards_rank_cached_feeds() {
+        let manager = CacheManager::temporary();
+        let rss = Arc::new(RssCache::new(manager));
+        let feed = RssFeed {
+            meta: RssFeedMeta {
+                title: "Boards".to_string(),
+                link: "https://boards.example.com".to_string(),
+                description: None,
+                language: None,
+                copyright: None,
+                last_build_date: None,
+                pub_date: None,
+                image: None,
+            },
+            items: vec![item("zyxboard rust release"), item("unrelated post"), item("zyxboard zyxboard tips")],
+        };
+        rss.set("https://boards.example.com/feed", &feed, false, None, None).unwrap();
+
+        let boards = RssBoards::new(
+            BoardsConfig {
+                rankings: vec![RankingConfig {
+                    name: "zyx".to_string(),
+                    keywords: vec![RankingKeyword::new("zyxboard", 2.0)],
+                    min_score: 1.0,
+                    max_results: 10,
+                }],
+                ..Default::default()
+            },
+            rss,
+        );
+
+        assert!(boards.get("missing").unwrap().is_none());
+        let board = boards.get("zyx").unwrap().unwrap();
+        assert_eq!(board.name, "zyx");
+        assert_eq!(board.items.len(), 2);
+        assert_eq!(board.items[0].item.title, "zyxboard zyxboard tips");
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS 日期解析
+//!
+//! 将 RSS/Atom 中的日期字符串解析为 UTC 时间，支持：
+//! - RFC 822 / RFC 2822（RSS 2.0 `pubDate`）
+//! - RFC 3339 / ISO 8601（Atom `updated`/`published`）
+//! - 常见中文格式（如 `2024年01月15日 10:30`，按北京时间解释）
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use crate::derive::rss::RssFeedItem;
+
+/// 北京时间偏移（秒）
+const CST_OFFSET_SECS: i32 = 8 * 3600;
+
+/// 不带时区的日期时间格式（按 UTC 解释）
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+/// 带数字时区的日期时间格式
+const OFFSET_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S %z",
+    "%Y-%m-%d %H:%M:%S%z",
+    "%a, %d %b %Y %H:%M %z",
+    "%d %b %Y %H:%M %z",
+];
+
+/// 只有日期的格式
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d"];
+
+/// 中文日期时间格式（按北京时间解释）
+const CHINESE_DATETIME_FORMATS: &[&str] = &[
+    "%Y年%m月%d日 %H:%M:%S",
+    "%Y年%m月%d日 %H:%M",
+    "%Y年%m月%d日%H:%M:%S",
+    "%Y年%m月%d日%H:%M",
+    "%Y年%m月%d日 %H时%M分%S秒",
+    "%Y年%m月%d日 %H时%M分",
+];
+
+/// 中文日期格式（按北京时间解释）
+const CHINESE_DATE_FORMATS: &[&str] = &["%Y年%m月%d日"];
+
+/// 解析 RSS/Atom 日期字符串
+///
+/// 依次尝试 RFC 2822、RFC 3339、带时区格式、中文格式和无时区格式，
+/// 全部失败时返回 `None`。
+pub fn parse_feed_date(raw: &str) -> Option<DateTime<Utc>> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc2822(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    // 部分源使用 "UTC"/"Z" 作为时区名，或星期与日期不一致
+    if let Some(dt) = parse_rfc822_lenient(s) {
+        return Some(dt);
+    }
+
+    for fmt in OFFSET_DATETIME_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(s, fmt) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+
+    if s.contains('年') {
+        return parse_chinese(s);
+    }
+
+    for fmt in NAIVE_DATETIME_FORMATS {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(Utc.from_utc_datetime(&naive));
+        }
+    }
+
+    for fmt in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(s, fmt) {
+            return date.and_hms_opt(0, 0, 0).map(|naive| Utc.from_utc_datetime(&naive));
+        }
+    }
+
+    None
+}
+
+/// 宽松的 RFC 822 解析：去掉星期前缀，将 UTC/Z 统一为 GMT
+fn parse_rfc822_lenient(s: &str) -> Option<DateTime<Utc>> {
+    let without_weekday = match s.find(',') {
+        Some(pos) => s[pos + 1..].trim(),
+        None => s,
+    };
+
+    let with_zone = match ["UTC", "GMT", "Z"]
+        .iter()
+        .find_map(|zone| without_weekday.strip_suffix(zone))
+    {
+        Some(rest) => format!("{} +0000", rest.trim_end()),
+        None => without_weekday.to_string(),
+    };
+
+    for fmt in ["%d %b %Y %H:%M:%S %z", "%d %b %Y %H:%M %z"] {
+        if let Ok(dt) = DateTime::parse_from_str(&with_zone, fmt) {
+            return Some(dt.with_timezone(&Utc));
+        }
+    }
+    None
+}
+
+/// 解析中文日期格式（北京时间）
+fn parse_chinese(s: &str) -> Option<DateTime<Utc>> {
+    let cst = FixedOffset::east_opt(CST_OFFSET_SECS)?;
+
+    for fmt in CHINESE_DATETIME_FORMATS {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return cst.from_local_datetime(&naive).single().map(|dt| dt.with_timezone(&Utc));
+        }
+    }
+
+    for fmt in CHINESE_DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(s, fmt) {
+            let naive = date.and_hms_opt(0, 0, 0)?;
+            return cst.from_local_datetime(&naive).single().map(|dt| dt.with_timezone(&Utc));
+        }
+    }
+
+    None
+}
+
+/// 获取 RSS 项目的发布时间
+pub fn item_published_date(item: &RssFeedItem) -> Option<DateTime<Utc>> {
+    item.pub_date.as_deref().and_then(parse_feed_date)
+}
+
+/// 按发布时间降序排序 RSS 项目（无法解析日期的项目排在最后，保持相对顺序）
+pub fn sort_items_by_date(items: &mut [RssFeedItem]) {
+    items.sort_by_cached_key(|item| std::cmp::Reverse(item_published_date(item)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn test_parse_rfc822() {
+        let dt = parse_feed_date("Mon, 15 Jan 2024 10:30:00 +0000").unwrap();
+        assert_eq!((dt.year(), dt.month(), dt.day(), dt.hour()), (2024, 1, 15, 10));
+
+        let dt = parse_feed_date("Mon, 15 Jan 2024 10:30:00 GMT").unwrap();
+        assert_eq!(dt.hour(), 10);
+
+        let dt = parse_feed_date("15 Jan 2024 18:30:00 +0800").unwrap();
+        assert_eq!(dt.hour(), 10);
+
+        let dt = parse_feed_date("Mon, 15 Jan 2024 10:30:00 UTC").unwrap();
+        assert_eq!(dt.minute(), 30);
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let dt = parse_feed_date("2024-01-15T10:30:00Z").unwrap();
+        assert_eq!((dt.day(), dt.hour()), (15, 10));
+
+        let dt = parse_feed_date("2024-01-15T18:30:00+08:00").unwrap();
+        assert_eq!(dt.hour(), 10);
+
+        let dt = parse_feed_date("2024-01-15T18:30:00+0800").unwrap();
+        assert_eq!(dt.hour(), 10);
+    }
+
+    #[test]
+    fn test_parse_chinese_formats() {
+        let dt = parse_feed_date("2024年01月15日 18:30").unwrap();
+        assert_eq!((dt.day(), dt.hour(), dt.minute()), (15, 10, 30));
+
+        let dt = parse_feed_date("2024年1月15日").unwrap();
+        assert_eq!((dt.month(), dt.day(), dt.hour()), (1, 14, 16));
+    }
+
+    #[test]
+    fn test_parse_naive_formats() {
+        let dt = parse_feed_date("2024-01-15 10:30:00").unwrap();
+        assert_eq!(dt.hour(), 10);
+
+        let dt = parse_feed_date("2024/01/15").unwrap();
+        assert_eq!((dt.day(), dt.hour()), (15, 0));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_feed_date("").is_none());
+        assert!(parse_feed_date("yesterday").is_none());
+        assert!(parse_feed_date("2024-13-45").is_none());
+    }
+
+    #[test]
+    fn test_sort_items_by_date() {
+        let make = |title: &str, date: Option<&str>| RssFeedItem {
+            title: title.to_string(),
+            link: format!("https://example.com/{}", title),
+            description: None,
+            author: None,
+            pub_date: date.map(|d| d.to_string()),
+            content: None,
+            categories: vec![],
+            guid: None,
+            enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: std::collections::HashMap::new(),
+        };
+
+        let mut items = vec![
+            make("old", Some("Mon, 01 Jan 2024 00:00:00 GMT")),
+            make("none", None),
+            make("new", Some("2024-03-01T00:00:00Z")),
+        ];
+        sort_items_by_date(&mut items);
+
+        assert_eq!(items[0].title, "new");
+        assert_eq!(items[1].title, "old");
+        assert_eq!(items[2].title, "none");
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS 跨 feed 内容去重
+//!
+//! 许多 feed 会转载同一篇文章。条目入库时计算两种指纹：
+//! 规范化标题 + 链接域名的内容键（同一站点不同栏目 feed 中的同一篇文章），
+//! 以及摘要的 SimHash（不同站点转载的同一篇文章）。指纹相同或 SimHash
+//! 汉明距离不超过 [`SIMHASH_MAX_DISTANCE`] 的条目只保留最先入库的一条，
+//! 其余作为它的转载来源记录下来。
+
+use sha2::{Digest, Sha256};
+
+use crate::derive::rss::RssFeedItem;
+use super::tags::terms;
+
+/// 判定为重复的最大 SimHash 汉明距离
+pub const SIMHASH_MAX_DISTANCE: u32 = 3;
+
+/// 计算 SimHash 需要的最少词数（摘要过短时容易误判）
+const SIMHASH_MIN_TERMS: usize = 8;
+
+/// 规范化标题：小写，只保留字母、数字和汉字
+pub fn normalize_title(title: &str) -> String {
+    title.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// 链接的域名（去掉 `www.` 前缀）
+fn link_domain(link: &str) -> String {
+    crate::url_utils::domain(link).unwrap_or_default()
+}
+
+/// 条目的内容键：规范化标题 + 链接域名的哈希（标题为空时为 None）
+pub fn content_key(item: &RssFeedItem) -> Option<String> {
+    let title = normalize_title(&item.title);
+    if title.is_empty() {
+        return None;
+    }
+    let digest = Sha256::digest(format!("{}\n{}", link_domain(&item.link), title).as_bytes());
+    Some(digest.iter().take(16).map(|b| format!("{:02x}", b)).collect())
+}
+
+/// FNV-1a 64 位哈希（跨进程稳定）
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// 条目摘要（没有摘要时用正文）的 SimHash，词数不足时为 None
+pub fn simhash(item: &RssFeedItem) -> Option<u64> {
+    let text = item.description.as_deref().or(item.content.as_deref())?;
+    let terms = terms(text);
+    if terms.len() < SIMHASH_MIN_TERMS {
+        return None;
+    }
+    let mut weights = [0i64; 64];
+    for term in &terms {
+        let hash = fnv1a(term);
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    Some(weights.iter().enumerate().fold(0u64, |acc, (bit, weight)| {
+        if *weight > 0 { acc | 1 << bit } else { acc }
+    }))
+}
+
+/// 两个 SimHash 的汉明距离
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// SimHash 的 4 个 16 位分段
+///
+/// 距离不超过 3 的两个 SimHash 至少有一段完全相同，按分段建索引即可找到候选
+pub fn simhash_bands(hash: u64) -> [u16; 4] {
+    [0, 16, 32, 48].map(|shift| (hash >> shift) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn item(title: &str, link: &str, description: &str) -> RssFeedItem {
+        RssFeedItem {
+            title: title.to_string(),
+            link: link.to_string(),
+            description: Some(description.to_string()),
+            author: None,
+            pub_date: None,
+            content: None,
+            categories: vec![],
+            guid: None,
+            enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_content_key_ignores_case_punctuation_and_www() {
+        let a = item("Rust 1.80 Released!", "https://www.example.com/tech/1", "");
+        let b = item("rust 1.80 released", "https://example.com/news/1", "");
+        let c = item("rust 1.80 released", "https://other.org/1", "");
+        assert_eq!(content_key(&a), content_key(&b));
+        assert_ne!(content_key(&a), content_key(&c));
+        assert_eq!(content_key(&item("!!", "https://example.com", "")), None);
+    }
+
+    #[test]
+    fn test_simhash_detects_near_duplicates() {
+        let text = "The Rust team has published version 1.80 of the compiler with LazyCell and LazyLock \
+                    stabilized, exclusive ranges in patterns and many library improvements";
+        let a = simhash(&item("a", "https://a.com/1", text)).unwrap();
+        // 转载时常见的 HTML 包装、大小写和标点差异
+        let b = simhash(&item("b", "https://b.com/1", &format!("<p>{}.</p>", text.to_uppercase()))).unwrap();
+        let c = simhash(&item("c", "https://c.com/1", "Python 3.13 ships an experimental free threaded \
+                    build, a new interactive interpreter and a JIT compiler preview for developers")).unwrap();
+        assert!(hamming_distance(a, b) <= SIMHASH_MAX_DISTANCE);
+        assert!(hamming_distance(a, c) > SIMHASH_MAX_DISTANCE);
+        assert!(simhash(&item("short", "https://a.com/2", "too short")).is_none());
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS feed 自动发现
+//!
+//! 给定网站地址，先在页面中查找 `<link rel="alternate">` 声明的 feed，
+//! 找不到时再依次尝试 `/feed`、`/rss.xml` 等常见路径。地址本身就是 feed 时直接返回。
+//!
+//! 网站地址来自外部请求，抓取前会解析主机名，指向回环、私有、链路本地等
+//! 非公网地址的请求（包括页面声明的 feed、常见路径和重定向目标）一律拒绝。
+
+use std::error::Error;
+use std::net::IpAddr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use url::Url;
+
+use crate::net::client::HttpTransport;
+use crate::net::charset::response_text;
+use crate::net::types::RequestOptions;
+
+pub use crate::net::resolver::{is_public_ip, ForbiddenAddress};
+use crate::net::resolver::resolve_public;
+
+/// 页面中没有声明 feed 时尝试的常见路径
+pub const FALLBACK_FEED_PATHS: &[&str] = &[
+    "/feed", "/rss", "/feed.xml", "/rss.xml", "/atom.xml", "/index.xml", "/feed.json",
+];
+
+static LINK_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<link\b[^>]*>").expect("valid regex"));
+static ATTRIBUTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)([A-Za-z][\w:-]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).expect("valid regex")
+});
+
+/// feed 格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    /// 根据 `<link type>` 判断格式
+    fn from_mime(mime: &str) -> Option<Self> {
+        match mime.trim().to_ascii_lowercase().as_str() {
+            "application/rss+xml" | "application/rdf+xml" => Some(Self::Rss),
+            "application/atom+xml" => Some(Self::Atom),
+            "application/feed+json" | "application/json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// 根据响应内容判断格式
+    pub fn sniff(body: &str) -> Option<Self> {
+        let head: String = body.trim_start().chars().take(1024).collect();
+        if head.contains("<rss") || head.contains("<rdf:RDF") {
+            Some(Self::Rss)
+        } else if head.contains("<feed") {
+            Some(Self::Atom)
+        } else if head.starts_with('{') && head.contains("jsonfeed.org/version") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// 候选 feed 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverySource {
+    /// 给定地址本身就是 feed
+    Direct,
+    /// 页面中的 `<link rel="alternate">`
+    LinkTag,
+    /// 常见路径
+    FallbackPath,
+}
+
+/// 发现的候选 feed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedCandidate {
+    /// feed 地址
+    pub url: String,
+    /// 标题（`<link title>`）
+    pub title: Option<String>,
+    /// 格式
+    pub format: FeedFormat,
+    /// 来源
+    pub source: DiscoverySource,
+}
+
+/// 从 HTML 页面中提取声明的 feed 链接（相对地址按 `base` 解析）
+pub fn find_feed_links(html: &str, base: &Url) -> Vec<FeedCandidate> {
+    let mut candidates: Vec<FeedCandidate> = Vec::new();
+    for tag in LINK_TAG.find_iter(html) {
+        let mut rel = None;
+        let mut mime = None;
+        let mut href = None;
+        let mut title = None;
+        for attr in ATTRIBUTE.captures_iter(tag.as_str()) {
+            let value = attr.get(2).or(attr.get(3)).or(attr.get(4)).map_or("", |m| m.as_str());
+            let value = html_escape::decode_html_entities(value).trim().to_string();
+            match attr[1].to_ascii_lowercase().as_str() {
+                "rel" => rel = Some(value),
+                "type" => mime = Some(value),
+                "href" => href = Some(value),
+                "title" => title = Some(value).filter(|t| !t.is_empty()),
+                _ => {}
+            }
+        }
+        let is_alternate = rel.is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")));
+        let (Some(format), Some(href)) = (mime.as_deref().and_then(FeedFormat::from_mime), href) else {
+            continue;
+        };
+        let Ok(url) = base.join(&href) else {
+            continue;
+        };
+        if is_alternate && !candidates.iter().any(|c| c.url == url.as_str()) {
+            candidates.push(FeedCandidate { url: url.to_string(), title, format, source: DiscoverySource::LinkTag });
+        }
+    }
+    candidates
+}
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 检查地址的主机解析到的所有 IP 都是公网地址
+pub async fn ensure_public_url(url: &Url) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(url::Host::Domain(domain)) => {
+            resolve_public(domain, url.port_or_known_default().unwrap_or(80)).await?;
+            return Ok(());
+        }
+        None => return Err(ForbiddenAddress(String::new()).into()),
+    };
+    if !is_public_ip(ip) {
+        return Err(ForbiddenAddress(url.host_str().unwrap_or_default().to_string()).into());
+    }
+    Ok(())
+}
+
+/// GET 请求地址，成功时返回响应体
+///
+/// 重定向由这里逐跳跟随，每一跳都先检查目标是否为公网地址；
+/// 请求设置 `public_only`，实际连接时同样只使用公网地址，避免检查后被 DNS 重绑定
+async fn fetch_text(transport: &dyn HttpTransport, url: &Url) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let options = RequestOptions { follow_redirects: false, public_only: true, ..Default::default() };
+    let mut url = url.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        ensure_public_url(&url).await?;
+        let response = transport
+            .get(url.as_str(), Some(options.clone()))
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| format!("HTTP {} without location", response.status()))?;
+            url = url.join(location)?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err("只支持 http 和 https 地址".into());
+            }
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+        return Ok(response_text(response).await?);
+    }
+    Err("Too many redirects".into())
+}
+
+/// 发现网站的 feed
+///
+/// 页面本身获取失败或指向非公网地址时返回错误；常见路径逐个探测，失败的路径直接忽略。
+/// 页面声明的 feed 指向非公网地址时不返回
+pub async fn discover_feeds(
+    transport: &dyn HttpTransport,
+    site_url: &str,
+) -> Result<Vec<FeedCandidate>, Box<dyn Error + Send + Sync>> {
+    let base = Url::parse(site_url)?;
+    if !matches!(base.scheme(), "http" | "https") {
+        return Err("只支持 http 和 https 地址".into());
+    }
+
+    let body = fetch_text(transport, &base).await?;
+    if let Some(format) = FeedFormat::sniff(&body) {
+        return Ok(vec![FeedCandidate { url: base.to_string(), title: None, format, source: DiscoverySource::Direct }]);
+    }
+
+    let links = find_feed_links(&body, &base).into_iter().map(|candidate| async move {
+        let url = Url::parse(&candidate.url).ok()?;
+        ensure_public_url(&url).await.ok().map(|_| candidate)
+    });
+    let candidates: Vec<FeedCandidate> = futures::future::join_all(links).await.into_iter().flatten().collect();
+    if !candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    let probes = FALLBACK_FEED_PATHS.iter().filter_map(|path| base.join(path).ok()).map(|url| async move {
+        let body = fetch_text(transport, &url).await.ok()?;
+        let format = FeedFormat::sniff(&body)?;
+        Some(FeedCandidate { url: url.to_string(), title: None, format, source: DiscoverySource::FallbackPath })
+    });
+    Ok(futures::future::join_all(probes).await.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[test]
+    fn test_find_feed_links() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="alternate" type="application/rss+xml" title="Blog &amp; News" href="/feed.xml">
+            <LINK REL='alternate' TYPE='application/atom+xml' HREF='https://cdn.example.com/atom.xml'>
+            <link rel="alternate" hreflang="en" href="/en/">
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+        </head></html>"#;
+        let base = Url::parse("https://example.com/blog/").unwrap();
+        let links = find_feed_links(html, &base);
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].url, "https://example.com/feed.xml");
+        assert_eq!(links[0].title.as_deref(), Some("Blog & News"));
+        assert_eq!(links[0].format, FeedFormat::Rss);
+        assert_eq!(links[1].url, "https://cdn.example.com/atom.xml");
+        assert_eq!(links[1].format, FeedFormat::Atom);
+    }
+
+    #[tokio::test]
+    async fn test_discover_falls_back_to_common_paths() {
+        // 使用公网 IP 字面量，测试不依赖 DNS
+        let transport = MockTransport::new()
+            .respond("93.184.216.34/rss.xml", MockResponse::ok(r#"<?xml version="1.0"?><rss version="2.0"></rss>"#))
+            .respond("93.184.216.34/atom.xml", MockResponse::ok(r#"<feed xmlns="http://www.w3.org/2005/Atom"></feed>"#))
+            .respond("93.184.216.34/feed", MockResponse::new(404, ""))
+            .respond("93.184.216.34/", MockResponse::ok("<html><head><title>No feeds</title></head></html>"));
+
+        let feeds = discover_feeds(&transport, "https://93.184.216.34/").await.unwrap();
+        let urls: Vec<_> = feeds.iter().map(|f| (f.url.as_str(), f.format)).collect();
+        assert_eq!(urls, vec![("https://93.184.216.34/rss.xml", FeedFormat::Rss), ("https://93.184.216.34/atom.xml", FeedFormat::Atom)]);
+        assert!(feeds.iter().all(|f| f.source == DiscoverySource::FallbackPath));
+
+        let feeds = discover_feeds(&transport, "https://93.184.216.34/rss.xml").await.unwrap();
+        assert_eq!(feeds[0].source, DiscoverySource::Direct);
+    }
+
+    #[tokio::test]
+    async fn test_discover_rejects_private_addresses() {
+        let transport = MockTransport::new()
+            .respond("93.184.216.34/moved", MockResponse::new(302, "").header("location", "http://10.0.0.1/admin"))
+            .respond("93.184.216.34/", MockResponse::ok(r#"<html><head>
+                <link rel="alternate" type="application/rss+xml" href="http://192.168.1.1/feed.xml">
+                <link rel="alternate" type="application/atom+xml" href="/atom.xml">
+            </head></html>"#))
+            .respond("/", MockResponse::ok(r#"<rss version="2.0"></rss>"#));
+
+        for url in ["http://127.0.0.1/", "http://[::1]:8080/", "http://169.254.169.254/latest/meta-data/", "http://93.184.216.34/moved"] {
+            let error = discover_feeds(&transport, url).await.unwrap_err();
+            assert!(error.downcast_ref::<ForbiddenAddress>().is_some(), "{}", url);
+        }
+
+        // 页面声明的指向内网的 feed 被丢弃
+        let feeds = discover_feeds(&transport, "https://93.184.216.34/").await.unwrap();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].url, "https://93.184.216.34/atom.xml");
+    }
+
+    #[tokio::test]
+    async fn test_discover_with_http_client_rejects_local_hosts() {
+        use crate::net::client::{mock::spawn_redirect_server, HttpClient};
+        use crate::net::types::NetworkConfig;
+
+        let addr = spawn_redirect_server().await;
+        let client = HttpClient::new(NetworkConfig::default()).unwrap();
+        for url in [format!("http://{}/redirect", addr), format!("http://localhost:{}/redirect", addr.port())] {
+            let error = discover_feeds(&client, &url).await.unwrap_err();
+            assert!(error.downcast_ref::<ForbiddenAddress>().is_some(), "{}", url);
+        }
+
+        // 绕过预先检查时，真实客户端同样拒绝连接本地地址，也不跟随重定向
+        let url = Url::parse(&format!("http://localhost:{}/target", addr.port())).unwrap();
+        let options = RequestOptions { follow_redirects: false, public_only: true, ..Default::default() };
+        assert!(client.get(url.as_str(), Some(options)).await.is_err());
+    }
+}
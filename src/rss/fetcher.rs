@@ -14,47 +14,173 @@
 
 //! RSS feed fetcher
 //!
-//! 提供 RSS feed 获取功能
+//! 提供 RSS feed 获取功能。轮询时带上上次响应的 `ETag` / `Last-Modified`
+//! 发送条件请求，未变化的 feed 只返回 304；响应的 `Cache-Control: max-age`
+//! 和 feed 的 `<ttl>` 作为下次轮询的最短间隔。
 
 use crate::derive::rss::*;
-use crate::net::client::HttpClient;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::RequestOptions;
+use crate::search::sanitize::sanitize_text;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// 条件请求的校验值（来自上次响应）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedValidators {
+    /// `ETag` 响应头
+    pub etag: Option<String>,
+    /// `Last-Modified` 响应头
+    pub last_modified: Option<String>,
+}
+
+impl FeedValidators {
+    /// 是否没有任何校验值
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// 条件请求头
+    fn request_headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push(("If-None-Match".to_string(), etag.clone()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push(("If-Modified-Since".to_string(), last_modified.clone()));
+        }
+        headers
+    }
+}
+
+/// 条件请求的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionalFetch {
+    /// 304：feed 未变化
+    NotModified {
+        validators: FeedValidators,
+        /// 建议的最短轮询间隔（秒）
+        refresh_hint: Option<u64>,
+    },
+    /// 200：新的 feed 内容
+    Modified {
+        body: String,
+        validators: FeedValidators,
+        /// 建议的最短轮询间隔（秒，取 `max-age` 和 `<ttl>` 中较大者）
+        refresh_hint: Option<u64>,
+    },
+}
+
+/// 解析 `Cache-Control` 的 `max-age`（`no-cache` / `no-store` 时为 None）
+fn cache_control_max_age(value: &str) -> Option<u64> {
+    let directives: Vec<String> = value.split(',').map(|d| d.trim().to_ascii_lowercase()).collect();
+    if directives.iter().any(|d| d == "no-cache" || d == "no-store") {
+        return None;
+    }
+    directives.iter().find_map(|d| d.strip_prefix("max-age=")?.trim_matches('"').parse().ok())
+}
+
+/// feed 声明的 `<ttl>`（分钟，只看第一个条目之前的频道部分），换算为秒
+pub fn feed_ttl(content: &str) -> Option<u64> {
+    let channel = content.split("<item").next().unwrap_or(content);
+    let start = channel.find("<ttl>")? + "<ttl>".len();
+    let end = channel[start..].find("</ttl>")? + start;
+    channel[start..end].trim().parse::<u64>().ok().map(|minutes| minutes * 60)
+}
+
+/// 发送条件请求
+pub async fn fetch_conditional(
+    transport: &dyn HttpTransport,
+    url: &str,
+    validators: &FeedValidators,
+) -> Result<ConditionalFetch, Box<dyn std::error::Error + Send + Sync>> {
+    let options = RequestOptions { headers: validators.request_headers(), ..Default::default() };
+    let response = transport.get(url, Some(options)).await
+        .map_err(|e| format!("Failed to fetch RSS feed: {}", e))?;
+
+    let header = |name: &str| {
+        response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+    };
+    let max_age = header("cache-control").as_deref().and_then(cache_control_max_age);
+    let status = response.status();
+
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        // 304 可能不带校验值，沿用请求时的
+        let validators = FeedValidators {
+            etag: header("etag").or_else(|| validators.etag.clone()),
+            last_modified: header("last-modified").or_else(|| validators.last_modified.clone()),
+        };
+        return Ok(ConditionalFetch::NotModified { validators, refresh_hint: max_age });
+    }
+    if !status.is_success() {
+        return Err(format!("Failed to fetch RSS feed: HTTP {}", status).into());
+    }
+
+    let validators = FeedValidators { etag: header("etag"), last_modified: header("last-modified") };
+    let body = response_text(response).await
+        .map_err(|e| format!("Failed to read response text: {}", e))?;
+    let refresh_hint = max_age.max(feed_ttl(&body));
+    Ok(ConditionalFetch::Modified { body, validators, refresh_hint })
+}
+
 /// RSS Feed 获取器
 pub struct RssFetcher {
-    /// HTTP 客户端
-    client: Arc<HttpClient>,
+    /// HTTP 传输层
+    transport: Arc<dyn HttpTransport>,
 }
 
 impl RssFetcher {
     /// 创建新的获取器
     pub fn new(client: Arc<HttpClient>) -> Self {
-        Self { client }
+        Self { transport: client }
+    }
+
+    /// 使用自定义传输层创建获取器（例如测试替身）
+    pub fn with_transport(transport: Arc<dyn HttpTransport>) -> Self {
+        Self { transport }
     }
 
     /// 获取 RSS feed 内容
     pub async fn fetch(&self, url: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        // 使用 HTTP 客户端获取内容
-        let response = self.client.get(url, None).await
-            .map_err(|e| format!("Failed to fetch RSS feed: {}", e))?;
-
-        // 提取响应文本
-        let text = response.text().await
-            .map_err(|e| format!("Failed to read response text: {}", e))?;
+        match fetch_conditional(self.transport.as_ref(), url, &FeedValidators::default()).await? {
+            ConditionalFetch::Modified { body, .. } => Ok(body),
+            ConditionalFetch::NotModified { .. } => Err("Unexpected 304 response for unconditional request".into()),
+        }
+    }
 
-        Ok(text)
+    /// 带校验值获取 RSS feed 内容
+    pub async fn fetch_conditional(
+        &self,
+        url: &str,
+        validators: &FeedValidators,
+    ) -> Result<ConditionalFetch, Box<dyn std::error::Error + Send + Sync>> {
+        fetch_conditional(self.transport.as_ref(), url, validators).await
     }
 
     /// 获取并解析 RSS feed
     pub async fn fetch_and_parse(&self, query: &RssFeedQuery) -> Result<RssFeed, Box<dyn std::error::Error + Send + Sync>> {
-        use crate::rss::parser::RssParser;
-
-        // 获取内容
         let content = self.fetch(&query.url).await?;
+        self.parse_and_filter(&content, query)
+    }
+
+    /// 解析 feed 内容并按查询过滤，同时为条目提取标签
+    pub fn parse_and_filter(&self, content: &str, query: &RssFeedQuery) -> Result<RssFeed, Box<dyn std::error::Error + Send + Sync>> {
+        use crate::rss::parser::RssParser;
 
         // 解析内容
         let parser = RssParser::new();
-        let mut feed = parser.parse(&content)?;
+        let mut feed = parser.parse(content)?;
+
+        // 清理 HTML：标题、摘要和正文转为纯文本
+        for item in &mut feed.items {
+            item.title = sanitize_text(&item.title);
+            item.description = item.description.as_deref().map(sanitize_text).filter(|text| !text.is_empty());
+            item.content = item.content.as_deref().map(sanitize_text).filter(|text| !text.is_empty());
+        }
+
+        // 按发布时间从新到旧排序，数量限制保留最新的条目
+        crate::rss::date::sort_items_by_date(&mut feed.items);
 
         // 应用过滤和限制
         if let Some(max_items) = query.max_items {
@@ -71,6 +197,9 @@ impl RssFetcher {
             });
         }
 
+        // 入库前提取关键词标签
+        crate::rss::tags::tag_items(&mut feed.items, crate::rss::tags::DEFAULT_MAX_TAGS);
+
         Ok(feed)
     }
 }
@@ -86,4 +215,64 @@ mod tests {
         let fetcher = RssFetcher::new(client);
         assert!(true);
     }
+
+    #[test]
+    fn test_parse_and_filter_sanitizes_html() {
+        let fetcher = RssFetcher::with_transport(Arc::new(crate::net::client::MockTransport::new()));
+        let content = r#"<rss><channel><title>Feed</title><item>
+            <title>Tokio &amp; friends</title><link>https://example.com/1</link>
+            <description><![CDATA[<p>Async <b>runtime</b></p><script>alert(1)</script>]]></description>
+            </item></channel></rss>"#;
+        let query = RssFeedQuery { url: "https://example.com/feed".to_string(), max_items: None, ..Default::default() };
+        let feed = fetcher.parse_and_filter(content, &query).unwrap();
+        assert_eq!(feed.items[0].title, "Tokio & friends");
+        assert_eq!(feed.items[0].description.as_deref(), Some("Async runtime"));
+    }
+
+    #[test]
+    fn test_parse_and_filter_keeps_newest_items() {
+        let fetcher = RssFetcher::with_transport(Arc::new(crate::net::client::MockTransport::new()));
+        let content = r#"<rss><channel><title>Feed</title>
+            <item><title>Old</title><link>https://example.com/old</link><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate></item>
+            <item><title>Undated</title><link>https://example.com/undated</link></item>
+            <item><title>New</title><link>https://example.com/new</link><pubDate>2024-03-01T00:00:00Z</pubDate></item>
+            </channel></rss>"#;
+        let query = RssFeedQuery { url: "https://example.com/feed".to_string(), max_items: None, ..Default::default() };
+        let titles = |feed: RssFeed| feed.items.into_iter().map(|item| item.title).collect::<Vec<_>>();
+        assert_eq!(titles(fetcher.parse_and_filter(content, &query).unwrap()), ["New", "Old", "Undated"]);
+
+        let query = RssFeedQuery { max_items: Some(1), ..query };
+        assert_eq!(titles(fetcher.parse_and_filter(content, &query).unwrap()), ["New"]);
+    }
+
+    #[test]
+    fn test_refresh_hints() {
+        assert_eq!(cache_control_max_age("public, max-age=300"), Some(300));
+        assert_eq!(cache_control_max_age("max-age=300, no-cache"), None);
+        assert_eq!(cache_control_max_age("private"), None);
+
+        let feed = "<rss><channel><ttl> 60 </ttl><item><ttl>5</ttl></item></channel></rss>";
+        assert_eq!(feed_ttl(feed), Some(3600));
+        assert_eq!(feed_ttl("<rss><channel><item><ttl>5</ttl></item></channel></rss>"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_conditional_sends_validators() {
+        use crate::net::client::{MockResponse, MockTransport};
+
+        let transport = MockTransport::new().respond(
+            "feed.xml",
+            MockResponse::new(304, "").header("Cache-Control", "max-age=120"),
+        );
+        let validators = FeedValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        let result = fetch_conditional(&transport, "https://example.com/feed.xml", &validators).await.unwrap();
+        assert_eq!(result, ConditionalFetch::NotModified { validators: validators.clone(), refresh_hint: Some(120) });
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.header("If-None-Match"), Some("\"abc\""));
+        assert_eq!(request.header("If-Modified-Since"), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+    }
 }
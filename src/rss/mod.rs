@@ -18,14 +18,24 @@
 
 pub mod types;
 pub mod parser;
+pub mod date;
 pub mod fetcher;
 pub mod template;
 pub mod ranking;
+pub mod board;
+pub mod tags;
+pub mod dedup;
+pub mod discover;
 pub mod on;
 
 pub use types::*;
 pub use parser::*;
+pub use date::*;
 pub use fetcher::*;
 pub use template::*;
 pub use ranking::*;
+pub use board::*;
+pub use tags::*;
+pub use dedup::*;
+pub use discover::*;
 pub use on::*;
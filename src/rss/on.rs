@@ -19,12 +19,15 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::derive::rss::*;
-use crate::net::client::HttpClient;
+use crate::net::client::{HttpClient, HttpTransport};
 use crate::cache::rss::RssCache;
-use super::fetcher::RssFetcher;
+use super::fetcher::{ConditionalFetch, FeedValidators, RssFetcher};
 use super::parser::RssParser;
 use super::template::RssTemplateManager;
 
+/// 临时 RSS 的默认缓存时间（秒）
+const DEFAULT_TTL_SECS: u64 = 3600;
+
 /// RSS Feed 接口
 ///
 /// 统一的 RSS feed 外部接口，封装获取、解析和缓存功能
@@ -60,6 +63,16 @@ impl RssInterface {
         }
     }
 
+    /// 使用自定义传输层创建 RSS 接口（例如测试替身）
+    pub fn with_transport(transport: Arc<dyn HttpTransport>, cache: Option<Arc<RwLock<RssCache>>>) -> Self {
+        Self {
+            fetcher: RssFetcher::with_transport(transport),
+            parser: RssParser::new(),
+            cache,
+            template_manager: None,
+        }
+    }
+
     /// 设置模板目录
     pub fn set_template_dir(&mut self, template_dir: &str) {
         self.template_manager = Some(RssTemplateManager::new(template_dir));
@@ -67,38 +80,8 @@ impl RssInterface {
 
     /// 获取 RSS feed（支持缓存）
     pub async fn fetch(&self, query: &RssFeedQuery) -> Result<RssFeed, Box<dyn std::error::Error + Send + Sync>> {
-        // 检查缓存
-        if let Some(ref cache) = self.cache {
-            let cache_guard = cache.read().await;
-            
-            // 检查是否需要更新
-            let needs_update = cache_guard.needs_update(&query.url)
-                .unwrap_or(true);
-
-            if !needs_update {
-                // 从缓存获取
-                if let Ok(Some(feed)) = cache_guard.get(&query.url) {
-                    return Ok(feed);
-                }
-            }
-        }
-
-        // 获取新数据
-        let feed = self.fetcher.fetch_and_parse(query).await?;
-
-        // 存入缓存（临时 RSS，默认TTL）
-        if let Some(ref cache) = self.cache {
-            let cache_guard = cache.write().await;
-            let _ = cache_guard.set(
-                &query.url,
-                &feed,
-                false, // 临时
-                None,  // 无自动更新间隔
-                Some(std::time::Duration::from_secs(3600)), // 1小时TTL
-            );
-        }
-
-        Ok(feed)
+        // 临时 RSS，默认 1 小时 TTL（源站要求更长时以源站为准），无自动更新间隔
+        self.fetch_cached(query, false, None).await
     }
 
     /// 获取持久化 RSS feed
@@ -107,44 +90,69 @@ impl RssInterface {
         url: &str,
         update_interval: u64,
     ) -> Result<RssFeed, Box<dyn std::error::Error + Send + Sync>> {
+        let query = RssFeedQuery {
+            url: url.to_string(),
+            max_items: None,
+            filter_keywords: vec![],
+            after_date: None,
+        };
+        self.fetch_cached(&query, true, Some(update_interval)).await
+    }
+
+    /// 按缓存状态获取 feed
+    ///
+    /// 未到更新时间时直接返回缓存；否则带上缓存的校验值发送条件请求，
+    /// 304 时只刷新元数据并返回缓存的 feed
+    async fn fetch_cached(
+        &self,
+        query: &RssFeedQuery,
+        persistent: bool,
+        update_interval: Option<u64>,
+    ) -> Result<RssFeed, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(ref cache) = self.cache else {
+            return self.fetcher.fetch_and_parse(query).await;
+        };
+
         // 检查缓存
-        if let Some(ref cache) = self.cache {
+        let (cached, validators) = {
             let cache_guard = cache.read().await;
-            
+
             // 检查是否需要更新
-            let needs_update = cache_guard.needs_update(url)
+            let needs_update = cache_guard.needs_update(&query.url)
                 .unwrap_or(true);
-
-            if !needs_update {
-                // 从缓存获取
-                if let Ok(Some(feed)) = cache_guard.get(url) {
-                    return Ok(feed);
-                }
+            let cached = cache_guard.get(&query.url).ok().flatten();
+            if !needs_update && let Some(feed) = cached {
+                return Ok(feed);
             }
-        }
 
-        // 获取新数据
-        let query = RssFeedQuery {
-            url: url.to_string(),
-            max_items: None,
-            filter_keywords: vec![],
-            after_date: None,
+            // 只有缓存里确实有 feed 时才能接受 304
+            let validators = match (&cached, cache_guard.get_meta(&query.url)) {
+                (Some(_), Ok(Some(meta))) => FeedValidators { etag: meta.etag, last_modified: meta.last_modified },
+                _ => FeedValidators::default(),
+            };
+            (cached, validators)
         };
-        let feed = self.fetcher.fetch_and_parse(&query).await?;
-
-        // 存入缓存（持久化 RSS）
-        if let Some(ref cache) = self.cache {
-            let cache_guard = cache.write().await;
-            let _ = cache_guard.set(
-                url,
-                &feed,
-                true, // 持久化
-                Some(update_interval),
-                None, // 不设置TTL
-            );
-        }
 
-        Ok(feed)
+        match self.fetcher.fetch_conditional(&query.url, &validators).await? {
+            ConditionalFetch::NotModified { validators, refresh_hint } => {
+                let cache_guard = cache.write().await;
+                let _ = cache_guard.record_fetch(&query.url, validators.etag, validators.last_modified, refresh_hint);
+                cached.ok_or_else(|| "Received 304 for a feed that is not cached".into())
+            }
+            ConditionalFetch::Modified { body, validators, refresh_hint } => {
+                let feed = self.fetcher.parse_and_filter(&body, query)?;
+
+                // 存入缓存（持久化 RSS 不设置 TTL）
+                let ttl = (!persistent).then(|| {
+                    std::time::Duration::from_secs(refresh_hint.unwrap_or(0).max(DEFAULT_TTL_SECS))
+                });
+                let cache_guard = cache.write().await;
+                let _ = cache_guard.set(&query.url, &feed, persistent, update_interval, ttl);
+                let _ = cache_guard.record_fetch(&query.url, validators.etag, validators.last_modified, refresh_hint);
+
+                Ok(feed)
+            }
+        }
     }
 
     /// 解析 RSS feed 内容
@@ -210,4 +218,47 @@ mod tests {
         let interface = RssInterface::new(client);
         assert!(true);
     }
+
+    #[tokio::test]
+    async fn test_fetch_persistent_revalidates_with_validators() {
+        use crate::cache::CacheManager;
+        use crate::net::client::{MockResponse, MockTransport};
+
+        let url = "https://conditional.example.com/feed.xml";
+        let manager = CacheManager::temporary();
+        let cache = Arc::new(RwLock::new(RssCache::new(manager)));
+        let _ = cache.read().await.delete(url);
+
+        let body = r#"<?xml version="1.0"?><rss version="2.0"><channel>
+            <title>Conditional</title><link>https://conditional.example.com</link><ttl>30</ttl>
+            <item><title>Conditional fetch item</title><link>https://conditional.example.com/1</link></item>
+            </channel></rss>"#;
+        let first = Arc::new(MockTransport::new().respond(
+            url,
+            MockResponse::ok(body).header("ETag", "\"v1\"").header("Cache-Control", "max-age=600"),
+        ));
+        let interface = RssInterface::with_transport(first.clone(), Some(cache.clone()));
+        let feed = interface.fetch_persistent(url, 0).await.unwrap();
+        assert_eq!(feed.items.len(), 1);
+        assert!(first.requests()[0].header("If-None-Match").is_none());
+
+        let meta = cache.read().await.get_meta(url).unwrap().unwrap();
+        assert_eq!(meta.etag.as_deref(), Some("\"v1\""));
+        // <ttl> 30 分钟比 max-age 更长
+        assert_eq!(meta.refresh_hint, Some(1800));
+
+        // 提示的间隔内不会再请求
+        assert!(!cache.read().await.needs_update(url).unwrap());
+
+        // 到期后发送条件请求，304 时返回缓存
+        let _ = cache.write().await.record_fetch(url, meta.etag.clone(), None, None);
+        let second = Arc::new(MockTransport::new().respond(url, MockResponse::new(304, "")));
+        let interface = RssInterface::with_transport(second.clone(), Some(cache.clone()));
+        let feed = interface.fetch_persistent(url, 0).await.unwrap();
+        assert_eq!(feed.items[0].title, "Conditional fetch item");
+        assert_eq!(second.requests()[0].header("If-None-Match"), Some("\"v1\""));
+        assert_eq!(cache.read().await.get_meta(url).unwrap().unwrap().etag.as_deref(), Some("\"v1\""));
+
+        let _ = cache.read().await.delete(url);
+    }
 }
@@ -77,6 +77,8 @@ impl RssParser {
             categories: vec![],
             guid: None,
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: std::collections::HashMap::new(),
         };
 
@@ -110,6 +112,14 @@ impl RssParser {
             item.guid = Some(guid);
         }
 
+        // 解析enclosure（播客音频/视频），时长来自 itunes:duration
+        let duration = self.extract_full_tag_content(item_content, "itunes:duration")
+            .as_deref()
+            .and_then(parse_duration);
+        item.enclosures = find_tags(item_content, "enclosure")
+            .filter_map(|tag| parse_enclosure(tag, "url", duration))
+            .collect();
+
         Ok(item)
     }
 
@@ -204,6 +214,8 @@ impl RssParser {
             categories: vec![],
             guid: None,
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: std::collections::HashMap::new(),
         };
 
@@ -222,6 +234,8 @@ impl RssParser {
                     categories: vec![],
                     guid: None,
                     enclosures: vec![],
+                    tags: vec![],
+                    alternate_sources: vec![],
                     custom_fields: std::collections::HashMap::new(),
                 };
             } else if trimmed.starts_with("</entry>") {
@@ -236,6 +250,11 @@ impl RssParser {
                     current_item.pub_date = Some(updated);
                 } else if let Some(content) = Self::extract_tag_content(trimmed, "content") {
                     current_item.content = Some(content);
+                } else if trimmed.contains("<link") && trimmed.contains("rel=\"enclosure\"") {
+                    // 播客音频/视频附件
+                    if let Some(enclosure) = find_tags(trimmed, "link").find_map(|tag| parse_enclosure(tag, "href", None)) {
+                        current_item.enclosures.push(enclosure);
+                    }
                 } else if trimmed.contains("<link") && trimmed.contains("href=") {
                     if let Some(href) = Self::extract_attribute(trimmed, "href") {
                         current_item.link = href;
@@ -305,6 +324,73 @@ impl RssParser {
     }
 }
 
+/// 依次返回 `<name ...>` 标签（不含正文）
+fn find_tags<'a>(content: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let open = format!("<{}", name);
+    let mut rest = content;
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(&open)?;
+            let after = &rest[start + open.len()..];
+            let end = after.find('>')?;
+            let tag = &rest[start..start + open.len() + end + 1];
+            rest = &after[end..];
+            // 跳过 `<enclosures>` 这类同前缀的其他标签
+            if after.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+                return Some(tag);
+            }
+        }
+    })
+}
+
+/// 读取标签属性（支持单双引号），并还原 `&amp;`
+fn tag_attribute(tag: &str, name: &str) -> Option<String> {
+    let mut search = tag;
+    while let Some(pos) = search.find(name) {
+        let before = search[..pos].chars().next_back();
+        let after = search[pos + name.len()..].trim_start();
+        search = &search[pos + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=').map(str::trim_start) else {
+            continue;
+        };
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        let end = value.find(quote)?;
+        return Some(value[..end].replace("&amp;", "&"));
+    }
+    None
+}
+
+/// 解析附件标签，`url_attr` 为 RSS 的 `url` 或 Atom 的 `href`
+fn parse_enclosure(tag: &str, url_attr: &str, duration_secs: Option<u64>) -> Option<RssEnclosure> {
+    let url = tag_attribute(tag, url_attr).filter(|url| !url.trim().is_empty())?;
+    let mime_type = tag_attribute(tag, "type").filter(|mime| !mime.trim().is_empty());
+    // 许多播客把未知大小写成 0
+    let length = tag_attribute(tag, "length")
+        .and_then(|length| length.trim().parse::<u64>().ok())
+        .filter(|length| *length > 0);
+    let kind = RssEnclosureKind::detect(mime_type.as_deref(), &url);
+    Some(RssEnclosure { url, mime_type, length, kind, duration_secs })
+}
+
+/// 解析 `itunes:duration`：秒数、`mm:ss` 或 `hh:mm:ss`
+fn parse_duration(value: &str) -> Option<u64> {
+    let mut total = 0u64;
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        // 秒可能带小数
+        let number = part.trim().split('.').next()?.parse::<u64>().ok()?;
+        total = total * 60 + number;
+    }
+    Some(total)
+}
+
 impl Default for RssParser {
     fn default() -> Self {
         Self::new()
@@ -345,4 +431,55 @@ mod tests {
         assert_eq!(feed.items.len(), 1);
         assert_eq!(feed.items[0].title, "Item 1");
     }
+
+    #[test]
+    fn test_parse_podcast_enclosures() {
+        let parser = RssParser::new();
+        let content = r#"<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Podcast</title>
+    <item>
+      <title>Episode 1</title>
+      <link>https://example.com/ep1</link>
+      <itunes:duration>1:02:03</itunes:duration>
+      <enclosure url="https://cdn.example.com/ep1.mp3?a=1&amp;b=2" length="34216300" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 2</title>
+      <enclosure length='0' url='https://cdn.example.com/ep2.mp4' />
+    </item>
+  </channel>
+</rss>"#;
+
+        let feed = parser.parse(content).unwrap();
+        let first = &feed.items[0].enclosures[0];
+        assert_eq!(first.url, "https://cdn.example.com/ep1.mp3?a=1&b=2");
+        assert_eq!(first.mime_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(first.length, Some(34216300));
+        assert_eq!(first.kind, RssEnclosureKind::Audio);
+        assert_eq!(first.duration_secs, Some(3723));
+
+        let second = &feed.items[1].enclosures[0];
+        assert_eq!(second.length, None);
+        assert_eq!(second.kind, RssEnclosureKind::Video);
+        assert_eq!(feed.items[1].playable_enclosure(), Some(second));
+    }
+
+    #[test]
+    fn test_parse_atom_enclosure() {
+        let parser = RssParser::new();
+        let content = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+<title>Atom Podcast</title>
+<entry>
+<title>Episode</title>
+<link href="https://example.com/ep"/>
+<link rel="enclosure" type="audio/ogg" length="1024" href="https://cdn.example.com/ep.ogg"/>
+</entry>
+</feed>"#;
+
+        let feed = parser.parse(content).unwrap();
+        assert_eq!(feed.items[0].link, "https://example.com/ep");
+        assert_eq!(feed.items[0].enclosures[0].url, "https://cdn.example.com/ep.ogg");
+        assert_eq!(feed.items[0].enclosures[0].kind, RssEnclosureKind::Audio);
+    }
 }
@@ -19,6 +19,10 @@
 use serde::{Deserialize, Serialize};
 use crate::derive::rss::{RssFeedItem, RssFeed};
 
+/// 关键词与条目标签完全匹配时额外获得的权重比例
+const TAG_MATCH_BONUS: f64 = 0.5;
+use super::date::item_published_date;
+
 /// 关键词配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RankingKeyword {
@@ -121,16 +125,21 @@ impl RssRankingEngine {
 
         for kw_config in &self.config.keywords {
             let keyword_lower = kw_config.keyword.to_lowercase();
+            // 标签是完整的词，比子串匹配更可靠（如 "rust" 不会匹配到 "trust"）
+            let tag_match = item.tags.contains(&keyword_lower);
             
             // 检查关键词是否在文本中
-            if text.contains(&keyword_lower) {
+            if text.contains(&keyword_lower) || tag_match {
                 // 计算出现次数
-                let count = text.matches(&keyword_lower).count();
+                let count = text.matches(&keyword_lower).count().max(1);
                 
                 // 基于权重和出现次数计算分数
                 // 使用对数缩放避免过多重复关键词导致分数过高
                 let keyword_score = kw_config.weight * (1.0 + (count as f64).ln());
                 score += keyword_score;
+                if tag_match {
+                    score += kw_config.weight * TAG_MATCH_BONUS;
+                }
                 
                 matched_keywords.push(kw_config.keyword.clone());
             } else if kw_config.required {
@@ -161,10 +170,8 @@ impl RssRankingEngine {
             .filter(|scored| scored.score >= self.config.min_score)
             .collect();
 
-        // 按评分降序排序
-        scored_items.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // 按评分降序排序（同分时较新的项目在前）
+        scored_items.sort_by(compare_scored_items);
 
         // 限制结果数量
         scored_items.truncate(self.config.max_results);
@@ -189,10 +196,8 @@ impl RssRankingEngine {
             .filter(|scored| scored.score >= self.config.min_score)
             .collect();
 
-        // 按评分降序排序
-        scored_items.sort_by(|a, b| {
-            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // 按评分降序排序（同分时较新的项目在前）
+        scored_items.sort_by(compare_scored_items);
 
         // 去重（基于链接）
         let mut seen_urls = std::collections::HashSet::new();
@@ -210,6 +215,14 @@ impl RssRankingEngine {
     }
 }
 
+/// 比较两个已评分项目：评分降序，同分时按发布时间降序
+fn compare_scored_items(a: &ScoredRssItem, b: &ScoredRssItem) -> std::cmp::Ordering {
+    b.score
+        .partial_cmp(&a.score)
+        .unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| item_published_date(&b.item).cmp(&item_published_date(&a.item)))
+}
+
 /// 获取当前时间戳
 fn current_timestamp() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -236,6 +249,8 @@ mod tests {
             categories: vec![],
             guid: None,
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: HashMap::new(),
         }
     }
@@ -284,6 +299,25 @@ mod tests {
         assert_eq!(scored2.score, 0.0);
     }
 
+    #[test]
+    fn test_tag_match_outranks_substring() {
+        let config = RankingConfig {
+            name: "test".to_string(),
+            keywords: vec![RankingKeyword::new("rust", 5.0)],
+            min_score: 0.0,
+            max_results: 10,
+        };
+        let engine = RssRankingEngine::new(config);
+
+        let mut tagged = create_test_item("Rust Guide", "Learn Rust");
+        crate::rss::tags::tag_item(&mut tagged, 8);
+        let mut substring = create_test_item("Trust Guide", "Learn to trust");
+        crate::rss::tags::tag_item(&mut substring, 8);
+
+        assert!(engine.score_item(&tagged).score > engine.score_item(&substring).score);
+        assert!(tagged.tags.contains(&"rust".to_string()));
+    }
+
     #[test]
     fn test_ranking() {
         let config = RankingConfig {
@@ -461,5 +495,39 @@ mod tests {
         // Only high-scoring items should pass
         assert!(ranking.items.len() <= 1);
     }
+
+    #[test]
+    fn test_equal_scores_prefer_newer_items() {
+        let config = RankingConfig {
+            name: "recency".to_string(),
+            keywords: vec![RankingKeyword::new("rust", 5.0)],
+            min_score: 0.0,
+            max_results: 10,
+        };
+
+        let engine = RssRankingEngine::new(config);
+
+        let mut older = create_test_item("Rust Old", "rust");
+        older.pub_date = Some("Mon, 01 Jan 2024 00:00:00 GMT".to_string());
+        let mut newer = create_test_item("Rust New", "rust");
+        newer.pub_date = Some("2024-06-01T00:00:00Z".to_string());
+
+        let feed = RssFeed {
+            meta: RssFeedMeta {
+                title: "Feed".to_string(),
+                link: "https://example.com".to_string(),
+                description: None,
+                language: None,
+                copyright: None,
+                last_build_date: None,
+                pub_date: None,
+                image: None,
+            },
+            items: vec![older, newer],
+        };
+
+        let ranking = engine.rank_feed(&feed);
+        assert_eq!(ranking.items[0].item.title, "Rust New");
+    }
 }
 
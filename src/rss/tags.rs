@@ -0,0 +1,200 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RSS 条目标签提取
+//!
+//! 条目入库时从标题和摘要中提取关键词作为标签，用于按标签筛选
+//! （`/api/rss/items?tag=`）和榜单评分。英文按单词切分并去掉停用词；
+//! 中文在启用 `jieba` 特性时使用 jieba 分词，否则按汉字二元组切分。
+//! 标题中的词权重加倍，按得分取前若干个，源提供的分类排在最前。
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::derive::rss::RssFeedItem;
+
+/// 每个条目默认保留的标签数
+pub const DEFAULT_MAX_TAGS: usize = 8;
+
+/// 标题中的词相对摘要的权重
+const TITLE_WEIGHT: f64 = 2.0;
+
+/// 英文停用词
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been", "but",
+    "by", "can", "could", "do", "does", "for", "from", "had", "has", "have", "how", "if", "in", "into",
+    "is", "it", "its", "just", "more", "new", "not", "now", "of", "on", "one", "or", "our", "out",
+    "over", "so", "than", "that", "the", "their", "them", "then", "there", "these", "they", "this",
+    "to", "up", "us", "was", "we", "were", "what", "when", "which", "who", "why", "will", "with",
+    "would", "you", "your",
+    // 摘要中残留的 HTML 片段
+    "amp", "nbsp", "quot", "href", "http", "https", "www", "com", "html",
+];
+
+/// 中文停用词
+const CHINESE_STOPWORDS: &[&str] = &[
+    "一个", "我们", "你们", "他们", "这个", "那个", "这些", "那些", "什么", "没有", "可以", "因为",
+    "所以", "但是", "如果", "已经", "进行", "以及", "通过", "表示", "还是", "就是", "不是", "自己",
+    "今天", "目前", "其中", "相关", "对于", "并且", "或者",
+];
+
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").expect("valid regex"));
+
+#[cfg(feature = "jieba")]
+static JIEBA: Lazy<jieba_rs::Jieba> = Lazy::new(jieba_rs::Jieba::new);
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}')
+}
+
+/// 切分一段汉字
+#[cfg(feature = "jieba")]
+fn cjk_terms(run: &str, out: &mut Vec<String>) {
+    out.extend(
+        JIEBA
+            .cut(run, false)
+            .into_iter()
+            .filter(|word| word.chars().count() >= 2)
+            .map(str::to_string),
+    );
+}
+
+/// 切分一段汉字（未启用 jieba 时按二元组切分）
+#[cfg(not(feature = "jieba"))]
+fn cjk_terms(run: &str, out: &mut Vec<String>) {
+    let chars: Vec<char> = run.chars().collect();
+    out.extend(chars.windows(2).map(|pair| pair.iter().collect::<String>()));
+}
+
+/// 把文本切分为候选词（小写，已去掉停用词和纯数字）
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    let text = HTML_TAG.replace_all(text, " ").to_lowercase();
+    let mut out = Vec::new();
+    let mut word = String::new();
+    let mut cjk = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if is_cjk(c) {
+            cjk.push(c);
+        } else if !cjk.is_empty() {
+            cjk_terms(&cjk, &mut out);
+            cjk.clear();
+        }
+        if c.is_alphanumeric() && !is_cjk(c) || (c == '+' || c == '#') && !word.is_empty() {
+            word.push(c);
+        } else if !word.is_empty() {
+            out.push(std::mem::take(&mut word));
+        }
+    }
+    out.retain(|term| {
+        let latin = !term.chars().any(is_cjk);
+        !(latin && (term.len() < 2 || ENGLISH_STOPWORDS.contains(&term.as_str())))
+            && !CHINESE_STOPWORDS.contains(&term.as_str())
+            && !term.chars().all(|c| c.is_ascii_digit())
+    });
+    out
+}
+
+/// 从标题和正文中提取关键词，按得分降序返回最多 `max_tags` 个
+pub fn extract_keywords(title: &str, body: &str, max_tags: usize) -> Vec<String> {
+    // 词 -> (得分, 首次出现位置)
+    let mut scores: HashMap<String, (f64, usize)> = HashMap::new();
+    let weighted = terms(title)
+        .into_iter()
+        .map(|term| (term, TITLE_WEIGHT))
+        .chain(terms(body).into_iter().map(|term| (term, 1.0)));
+    for (position, (term, weight)) in weighted.enumerate() {
+        let entry = scores.entry(term).or_insert((0.0, position));
+        entry.0 += weight;
+    }
+
+    let mut ranked: Vec<_> = scores.into_iter().collect();
+    ranked.sort_by(|(_, (score_a, pos_a)), (_, (score_b, pos_b))| {
+        score_b.partial_cmp(score_a).unwrap_or(std::cmp::Ordering::Equal).then(pos_a.cmp(pos_b))
+    });
+    ranked.into_iter().take(max_tags).map(|(term, _)| term).collect()
+}
+
+/// 为条目生成标签：源提供的分类在前，之后是提取的关键词（已有标签的条目保持不变）
+pub fn tag_item(item: &mut RssFeedItem, max_tags: usize) {
+    if !item.tags.is_empty() {
+        return;
+    }
+    let mut tags: Vec<String> = Vec::new();
+    let categories = item.categories.iter().map(|c| c.trim().to_lowercase());
+    let body = item.description.as_deref().unwrap_or_default();
+    for tag in categories.chain(extract_keywords(&item.title, body, max_tags)) {
+        if tags.len() >= max_tags {
+            break;
+        }
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    item.tags = tags;
+}
+
+/// 为一组条目生成标签
+pub fn tag_items(items: &mut [RssFeedItem], max_tags: usize) {
+    for item in items {
+        tag_item(item, max_tags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_keywords_english() {
+        let tags = extract_keywords(
+            "Rust 1.80 released with LazyLock",
+            "<p>The Rust team announces Rust 1.80. LazyLock and LazyCell are now stable.</p>",
+            3,
+        );
+        assert_eq!(tags[0], "rust");
+        assert!(tags.contains(&"lazylock".to_string()));
+        assert!(!tags.iter().any(|t| t == "the" || t == "p" || t == "80"));
+    }
+
+    #[test]
+    fn test_extract_keywords_chinese() {
+        let tags = extract_keywords("华为发布鸿蒙系统", "华为今天发布了新版鸿蒙系统", 5);
+        assert!(tags.iter().any(|t| t == "华为"));
+        assert!(tags.iter().any(|t| t == "鸿蒙"));
+    }
+
+    #[test]
+    fn test_tag_item_prefers_categories() {
+        let mut item = RssFeedItem {
+            title: "Tokio 1.40 adds task dumps".to_string(),
+            link: "https://tokio.rs/blog".to_string(),
+            description: Some("Tokio task dumps help debug async code".to_string()),
+            author: None,
+            pub_date: None,
+            content: None,
+            categories: vec!["Rust".to_string()],
+            guid: None,
+            enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: HashMap::new(),
+        };
+        tag_item(&mut item, 4);
+        assert_eq!(item.tags[0], "rust");
+        assert_eq!(item.tags[1], "tokio");
+        assert_eq!(item.tags.len(), 4);
+    }
+}
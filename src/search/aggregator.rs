@@ -16,10 +16,27 @@
 //!
 //! 负责合并、去重、排序多个搜索引擎的结果
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use crate::derive::{SearchResult, SearchResultItem, SearchQuery};
-use super::scoring::{score_and_sort_results, ScoringWeights};
-use super::standardization::{standardize_results, deduplicate_by_url};
+use super::filter::ResultFilter;
+use super::scoring::ScoringWeights;
+use super::stages::{
+    sort_items, DedupStage, DiversityStage, FilterStage, LanguageFilterStage, PositionedStage, ResultContext, ResultStage,
+    ScoringStage, SecurityStage, SortStage,
+};
+use super::sanitize::SanitizeConfig;
+use super::security::SecurityFlagsConfig;
+use super::standardization::standardize_results;
+
+/// 结果元数据中记录来源引擎的键
+pub const SOURCE_ENGINE_KEY: &str = "engine";
+
+/// 结果元数据中记录引擎自身相关性分数（0-1）的键，评分时优先使用该分数
+pub const ENGINE_SCORE_KEY: &str = "engine_score";
+
+/// 多样性重排时，同一域名每多一条已选结果，候选分数乘以该系数
+const DOMAIN_DIVERSITY_DECAY: f64 = 0.8;
 
 /// 聚合策略
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,7 +62,96 @@ pub enum SortBy {
     Source,
 }
 
+impl SortBy {
+    /// 从请求参数解析排序方式（`relevance`/`date`/`time`/`source`）
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "relevance" | "score" => Some(Self::Relevance),
+            "date" | "time" => Some(Self::Time),
+            "source" => Some(Self::Source),
+            _ => None,
+        }
+    }
+}
+
+/// 解析引擎权重参数（如 `bing:2.0,baidu:0.5`）
+///
+/// 权重必须是非负的有限数，同一引擎出现多次时以最后一次为准
+pub fn parse_engine_weights(value: &str) -> Result<HashMap<String, f64>, String> {
+    let mut weights = HashMap::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (engine, weight) = entry
+            .split_once(':')
+            .ok_or_else(|| format!("引擎权重 {} 格式错误，应为 引擎:权重", entry))?;
+        let engine = engine.trim();
+        if engine.is_empty() {
+            return Err(format!("引擎权重 {} 缺少引擎名", entry));
+        }
+        let weight: f64 = weight
+            .trim()
+            .parse()
+            .map_err(|_| format!("引擎 {} 的权重 {} 不是数字", engine, weight.trim()))?;
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(format!("引擎 {} 的权重必须是非负数", engine));
+        }
+        weights.insert(engine.to_lowercase(), weight);
+    }
+    Ok(weights)
+}
+
+/// 提取结果 URL 的域名（去掉 `www.` 前缀，无法解析时使用原 URL）
+fn result_domain(url: &str) -> String {
+    crate::url_utils::domain(url).unwrap_or_else(|| url.to_string())
+}
+
+/// 按域名多样性重排结果（MMR 风格）
+///
+/// `items` 应已按分数降序排列。每一步从各域名的最高分候选中选出
+/// `分数 × 0.8^该域名已选条数` 最大的一条，同一域名最多选 `max_per_domain` 条，
+/// 超出的结果按原顺序排在最后（不丢弃）。`max_per_domain` 为 0 时不重排
+pub fn diversify_by_domain<T>(items: Vec<T>, max_per_domain: usize, key: impl Fn(&T) -> (&str, f64)) -> Vec<T> {
+    if max_per_domain == 0 || items.len() < 2 {
+        return items;
+    }
+
+    let total = items.len();
+    let mut groups: Vec<VecDeque<(usize, f64, T)>> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for (position, item) in items.into_iter().enumerate() {
+        let (url, score) = key(&item);
+        let domain = result_domain(url);
+        let index = *group_index.entry(domain).or_insert_with(|| {
+            groups.push(VecDeque::new());
+            groups.len() - 1
+        });
+        groups[index].push_back((position, score, item));
+    }
+
+    let mut selected = vec![0usize; groups.len()];
+    let mut diversified = Vec::with_capacity(total);
+    loop {
+        // 分数相同时取先出现的域名
+        let best = groups
+            .iter()
+            .enumerate()
+            .filter(|(index, group)| !group.is_empty() && selected[*index] < max_per_domain)
+            .map(|(index, group)| (index, group[0].1 * DOMAIN_DIVERSITY_DECAY.powi(selected[index] as i32)))
+            .min_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let Some((index, _)) = best else { break };
+        if let Some((_, _, item)) = groups[index].pop_front() {
+            selected[index] += 1;
+            diversified.push(item);
+        }
+    }
+
+    let mut overflow: Vec<(usize, f64, T)> = groups.into_iter().flatten().collect();
+    overflow.sort_by_key(|(position, _, _)| *position);
+    diversified.extend(overflow.into_iter().map(|(_, _, item)| item));
+    diversified
+}
+
 /// 搜索聚合器
+#[derive(Clone)]
 pub struct SearchAggregator {
     /// 聚合策略
     strategy: AggregationStrategy,
@@ -53,6 +159,24 @@ pub struct SearchAggregator {
     sort_by: SortBy,
     /// 评分权重（可选）
     scoring_weights: Option<ScoringWeights>,
+    /// 引擎权重系数（引擎名 -> 乘数，未列出的引擎为 1.0）
+    engine_weights: HashMap<String, f64>,
+    /// 按相关性排序时每个域名最多排在前面的结果数（0 表示不做多样性重排）
+    max_per_domain: usize,
+    /// 请求的结果语言
+    language: Option<String>,
+    /// 丢弃（而不是降权）语言不符的结果
+    lang_strict: bool,
+    /// 结构化结果过滤
+    filter: ResultFilter,
+    /// 标准化时的 HTML 清理方式
+    sanitize: SanitizeConfig,
+    /// 结果安全标记
+    security: SecurityFlagsConfig,
+    /// 丢弃（而不是仅标记）带安全标记的结果
+    security_strict: bool,
+    /// 自定义后处理阶段（插在指定内置阶段之前，None 表示链末尾）
+    custom_stages: Vec<PositionedStage>,
 }
 
 impl SearchAggregator {
@@ -62,23 +186,129 @@ impl SearchAggregator {
             strategy, 
             sort_by,
             scoring_weights: None,
+            engine_weights: HashMap::new(),
+            max_per_domain: 0,
+            language: None,
+            lang_strict: false,
+            filter: ResultFilter::default(),
+            sanitize: SanitizeConfig::default(),
+            security: SecurityFlagsConfig::default(),
+            security_strict: false,
+            custom_stages: Vec::new(),
         }
     }
 
+    /// 排序方式
+    pub fn sort_by(&self) -> SortBy {
+        self.sort_by
+    }
+
     /// 设置评分权重
     pub fn with_scoring(mut self, weights: ScoringWeights) -> Self {
         self.scoring_weights = Some(weights);
         self
     }
 
+    /// 设置引擎权重系数
+    ///
+    /// 评分后将每个结果的分数乘以其来源引擎的系数
+    pub fn with_engine_weights(mut self, weights: HashMap<String, f64>) -> Self {
+        self.engine_weights = weights;
+        self
+    }
+
+    /// 在已有的引擎权重系数上再乘以一组系数
+    pub fn multiply_engine_weights(mut self, weights: &HashMap<String, f64>) -> Self {
+        for (engine, weight) in weights {
+            *self.engine_weights.entry(engine.clone()).or_insert(1.0) *= weight;
+        }
+        self
+    }
+
+    /// 设置每个域名的结果上限，按相关性排序时启用多样性重排（见 [`diversify_by_domain`]）
+    pub fn with_max_per_domain(mut self, max_per_domain: usize) -> Self {
+        self.max_per_domain = max_per_domain;
+        self
+    }
+
+    /// 设置请求的结果语言
+    ///
+    /// 按标题和摘要检测语言，语言不符的结果降权；`strict` 为 true 时直接丢弃。
+    /// 无法判断语言的结果不受影响
+    pub fn with_language(mut self, language: Option<String>, strict: bool) -> Self {
+        self.language = language.filter(|l| !l.trim().is_empty());
+        self.lang_strict = strict;
+        self
+    }
+
+    /// 设置结构化结果过滤
+    pub fn with_filter(mut self, filter: ResultFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// 设置标准化时的 HTML 清理方式
+    pub fn with_sanitize(mut self, sanitize: SanitizeConfig) -> Self {
+        self.sanitize = sanitize;
+        self
+    }
+
+    /// 设置结果安全标记（明文 HTTP、短链接、跟踪链接）
+    pub fn with_security_flags(mut self, security: SecurityFlagsConfig) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// 丢弃带安全标记的结果（安全标记未启用时无效）
+    pub fn with_security_strict(mut self, strict: bool) -> Self {
+        self.security_strict = strict;
+        self
+    }
+
+    /// 在后处理链末尾追加自定义阶段
+    pub fn with_result_stage(mut self, stage: Arc<dyn ResultStage>) -> Self {
+        self.custom_stages.push((None, stage));
+        self
+    }
+
+    /// 在指定内置阶段之前插入自定义阶段（找不到该阶段时追加到末尾）
+    pub fn with_result_stage_before(mut self, before: &str, stage: Arc<dyn ResultStage>) -> Self {
+        self.custom_stages.push((Some(before.to_string()), stage));
+        self
+    }
+
+    /// 构建后处理链：内置阶段依次为 dedup、security、scoring、language、sort、filter、diversity
+    pub fn result_stages(&self) -> Vec<Arc<dyn ResultStage>> {
+        let mut stages: Vec<Arc<dyn ResultStage>> = vec![
+            Arc::new(DedupStage),
+            Arc::new(SecurityStage::new(self.security.clone(), self.security_strict)),
+            Arc::new(ScoringStage::new(self.scoring_weights.clone(), self.engine_weights.clone())),
+            Arc::new(LanguageFilterStage::new(self.language.clone(), self.lang_strict)),
+            Arc::new(SortStage),
+            Arc::new(FilterStage(self.filter.clone())),
+            Arc::new(DiversityStage::new(self.max_per_domain)),
+        ];
+        for (before, stage) in &self.custom_stages {
+            let position = before
+                .as_deref()
+                .and_then(|name| stages.iter().position(|s| s.name() == name))
+                .unwrap_or(stages.len());
+            stages.insert(position, stage.clone());
+        }
+        stages
+    }
+
+    /// 后处理链中各阶段的名称（按执行顺序）
+    pub fn result_stage_names(&self) -> Vec<String> {
+        self.result_stages().iter().map(|s| s.name().to_string()).collect()
+    }
+
     /// 聚合多个搜索结果（使用智能评分）
     pub fn aggregate_with_scoring(
         &self, 
         mut results: Vec<SearchResult>,
         query: &SearchQuery,
     ) -> SearchResult {
-        use std::collections::HashMap;
-        
         if results.is_empty() {
             return SearchResult {
                 engine_name: "aggregated".to_string(),
@@ -91,9 +321,14 @@ impl SearchAggregator {
             };
         }
 
-        // 1. 标准化每个引擎的结果
+        // 1. 标准化每个引擎的结果，并记录来源引擎
         for result in &mut results {
-            standardize_results(result);
+            standardize_results(result, &self.sanitize);
+            for item in &mut result.items {
+                item.metadata
+                    .entry(SOURCE_ENGINE_KEY.to_string())
+                    .or_insert_with(|| result.engine_name.clone());
+            }
         }
 
         // 2. 合并所有结果
@@ -102,11 +337,11 @@ impl SearchAggregator {
             .flat_map(|r| r.items.into_iter())
             .collect();
 
-        // 3. 去重
-        deduplicate_by_url(&mut all_items);
-
-        // 4. 重新评分（基于查询）
-        score_and_sort_results(&mut all_items, query, "aggregated", self.scoring_weights.clone());
+        // 3. 依次执行后处理链（去重、评分、过滤、排序、多样性及自定义阶段）
+        let context = ResultContext { query, sort_by: self.sort_by };
+        for stage in self.result_stages() {
+            stage.process(&mut all_items, &context);
+        }
 
         let total_results = all_items.len();
 
@@ -123,8 +358,6 @@ impl SearchAggregator {
 
     /// 聚合多个搜索结果
     pub fn aggregate(&self, results: Vec<SearchResult>) -> SearchResult {
-        use std::collections::HashMap;
-        
         if results.is_empty() {
             return SearchResult {
                 engine_name: "aggregated".to_string(),
@@ -165,18 +398,24 @@ impl SearchAggregator {
                         }
                     }
                 }
-                self.sort_items(&mut merged_items);
+                sort_items(&mut merged_items, self.sort_by);
             }
             AggregationStrategy::RoundRobin => {
-                let max_len = results.iter().map(|r| r.items.len()).max().unwrap_or(0);
-                for i in 0..max_len {
-                    for result in &results {
-                        if let Some(item) = result.items.get(i) {
+                // 依次从每个引擎取下一项，直接移动结果项而不复制
+                let mut queues: Vec<_> = results.into_iter().map(|r| r.items.into_iter()).collect();
+                loop {
+                    let mut exhausted = true;
+                    for queue in &mut queues {
+                        if let Some(item) = queue.next() {
+                            exhausted = false;
                             if seen_urls.insert(item.url.clone()) {
-                                merged_items.push(item.clone());
+                                merged_items.push(item);
                             }
                         }
                     }
+                    if exhausted {
+                        break;
+                    }
                 }
             }
             AggregationStrategy::Ranked => {
@@ -187,7 +426,7 @@ impl SearchAggregator {
                         }
                     }
                 }
-                self.sort_items(&mut merged_items);
+                sort_items(&mut merged_items, self.sort_by);
             }
             AggregationStrategy::Custom => {
                 for result in results {
@@ -202,21 +441,6 @@ impl SearchAggregator {
 
         merged_items
     }
-
-    /// 排序结果项
-    fn sort_items(&self, items: &mut [SearchResultItem]) {
-        match self.sort_by {
-            SortBy::Relevance => {
-                // 默认顺序即为相关性顺序
-            }
-            SortBy::Time => {
-                // 按时间排序（如果有的话）
-            }
-            SortBy::Source => {
-                items.sort_by(|a, b| a.url.cmp(&b.url));
-            }
-        }
-    }
 }
 
 impl Default for SearchAggregator {
@@ -334,4 +558,165 @@ mod tests {
         assert_eq!(aggregated.items[0].title, "A1");
         assert_eq!(aggregated.items[1].title, "B1");
     }
+
+    #[test]
+    fn test_sort_by_param() {
+        assert_eq!(SortBy::from_param("date"), Some(SortBy::Time));
+        assert_eq!(SortBy::from_param("Relevance"), Some(SortBy::Relevance));
+        assert_eq!(SortBy::from_param("unknown"), None);
+    }
+
+    #[test]
+    fn test_sort_by_date_with_scoring() {
+        use std::collections::HashMap;
+
+        let agg = SearchAggregator::new(AggregationStrategy::Merged, SortBy::Time);
+        let now = chrono::Utc::now();
+
+        let mut old = create_test_item("https://example.com/old", "rust old");
+        old.published_date = Some(now - chrono::Duration::days(10));
+        let mut new = create_test_item("https://example.com/new", "rust new");
+        new.published_date = Some(now - chrono::Duration::hours(1));
+        let undated = create_test_item("https://example.com/undated", "rust rust rust");
+
+        let result = SearchResult {
+            engine_name: "engine1".to_string(),
+            total_results: Some(3),
+            elapsed_ms: 100,
+            items: vec![undated, old, new],
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+
+        let query = SearchQuery {
+            query: "rust".to_string(),
+            ..Default::default()
+        };
+        let aggregated = agg.aggregate_with_scoring(vec![result], &query);
+        assert_eq!(aggregated.items[0].url, "https://example.com/new");
+        assert_eq!(aggregated.items[1].url, "https://example.com/old");
+        assert_eq!(aggregated.items[2].url, "https://example.com/undated");
+    }
+
+    #[test]
+    fn test_engine_weights() {
+        let make_result = |engine: &str, url: &str| SearchResult {
+            engine_name: engine.to_string(),
+            total_results: Some(1),
+            elapsed_ms: 100,
+            items: vec![create_test_item(url, "rust")],
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        let query = SearchQuery {
+            query: "rust".to_string(),
+            ..Default::default()
+        };
+        let results = vec![
+            make_result("engine1", "https://a.example.com/"),
+            make_result("engine2", "https://b.example.com/"),
+        ];
+
+        let agg = SearchAggregator::default()
+            .with_engine_weights(HashMap::from([("engine2".to_string(), 1.5)]));
+        let aggregated = agg.aggregate_with_scoring(results, &query);
+
+        assert_eq!(aggregated.items[0].url, "https://b.example.com/");
+        assert_eq!(aggregated.items[0].metadata.get(SOURCE_ENGINE_KEY).unwrap(), "engine2");
+        assert_eq!(aggregated.items[1].metadata.get(SOURCE_ENGINE_KEY).unwrap(), "engine1");
+    }
+
+    #[test]
+    fn test_parse_and_multiply_engine_weights() {
+        let weights = parse_engine_weights("Bing:2.0, baidu:0.5,").unwrap();
+        assert_eq!(weights, HashMap::from([("bing".to_string(), 2.0), ("baidu".to_string(), 0.5)]));
+        assert!(parse_engine_weights("bing").is_err());
+        assert!(parse_engine_weights("bing:fast").is_err());
+        assert!(parse_engine_weights("bing:-1").is_err());
+        assert!(parse_engine_weights(":1").is_err());
+
+        let agg = SearchAggregator::default()
+            .with_engine_weights(HashMap::from([("bing".to_string(), 1.5)]))
+            .multiply_engine_weights(&weights);
+        assert_eq!(agg.engine_weights["bing"], 3.0);
+        assert_eq!(agg.engine_weights["baidu"], 0.5);
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let mut english = create_test_item("https://en.example.com/", "Rust");
+        english.content = "The Rust book: how to write safe and fast code".to_string();
+        let mut chinese = create_test_item("https://zh.example.com/", "Rust");
+        chinese.content = "Rust 编程语言入门教程：所有权与借用".to_string();
+        let result = SearchResult {
+            engine_name: "engine1".to_string(),
+            total_results: Some(2),
+            elapsed_ms: 100,
+            items: vec![english, chinese],
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+
+        let aggregated = SearchAggregator::default()
+            .with_language(Some("zh-CN".to_string()), false)
+            .aggregate_with_scoring(vec![result.clone()], &query);
+        assert_eq!(aggregated.items.len(), 2);
+        assert_eq!(aggregated.items[0].url, "https://zh.example.com/");
+
+        let aggregated = SearchAggregator::default()
+            .with_language(Some("zh".to_string()), true)
+            .aggregate_with_scoring(vec![result], &query);
+        assert_eq!(aggregated.items.len(), 1);
+        assert_eq!(aggregated.items[0].url, "https://zh.example.com/");
+    }
+
+    #[test]
+    fn test_diversify_by_domain() {
+        let items = vec![
+            ("https://a.com/1", 1.0),
+            ("https://www.a.com/2", 0.99),
+            ("https://a.com/3", 0.98),
+            ("https://a.com/4", 0.97),
+            ("https://b.com/1", 0.9),
+            ("https://c.com/1", 0.5),
+        ];
+        let urls = |items: Vec<(&'static str, f64)>| items.into_iter().map(|(url, _)| url).collect::<Vec<_>>();
+
+        // 上限 2：a.com 第三、四条排到最后；衰减让 b.com 提前到 a.com 第二条之前
+        let diversified = diversify_by_domain(items.clone(), 2, |(url, score)| (*url, *score));
+        assert_eq!(
+            urls(diversified),
+            vec!["https://a.com/1", "https://b.com/1", "https://www.a.com/2", "https://c.com/1", "https://a.com/3", "https://a.com/4"]
+        );
+
+        assert_eq!(urls(diversify_by_domain(items.clone(), 0, |(url, score)| (*url, *score))), urls(items));
+    }
+
+    #[test]
+    fn test_aggregate_with_max_per_domain() {
+        let items = (0..4)
+            .map(|i| create_test_item(&format!("https://same.example.com/{}", i), "rust"))
+            .chain(std::iter::once(create_test_item("https://other.example.org/", "rust")))
+            .collect();
+        let result = SearchResult {
+            engine_name: "engine1".to_string(),
+            total_results: Some(5),
+            elapsed_ms: 100,
+            items,
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+
+        let aggregated = SearchAggregator::default()
+            .with_max_per_domain(1)
+            .aggregate_with_scoring(vec![result], &query);
+        assert_eq!(aggregated.items.len(), 5);
+        assert_eq!(aggregated.items[1].url, "https://other.example.org/");
+    }
 }
@@ -0,0 +1,405 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 引擎响应异常检测
+//!
+//! 引擎页面结构变化后，HTTP 仍返回 200 但解析不出结果，这类静默退化不会触发熔断。
+//! 检测器为每个引擎维护“每次查询结果数”的基线（健康样本的移动平均），最近窗口内的
+//! 平均结果数跌到基线的 `collapse_ratio` 以下时产生一条 [`EngineAnomaly`]：窗口内多数
+//! 请求被拒绝（403/429/验证码）判定为引擎封锁，否则判定为解析器失效。
+//! 同一次退化只告警一次，窗口恢复后重新开始检测。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::derive::SearchResult;
+
+/// 保留的最近异常条数
+const MAX_RECENT_ANOMALIES: usize = 50;
+
+/// 基线预热结束后移动平均的平滑系数
+const BASELINE_ALPHA: f64 = 0.1;
+
+/// 异常检测配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// 是否启用（默认启用）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 最近窗口的查询数
+    #[serde(default = "default_window")]
+    pub window: usize,
+    /// 基线至少需要的健康样本数
+    #[serde(default = "default_min_baseline_samples")]
+    pub min_baseline_samples: usize,
+    /// 基线低于该结果数时不检测（结果本来就少的引擎波动太大）
+    #[serde(default = "default_min_baseline_items")]
+    pub min_baseline_items: f64,
+    /// 最近平均结果数低于“基线 × 该比例”时判定为退化
+    #[serde(default = "default_collapse_ratio")]
+    pub collapse_ratio: f64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_window() -> usize {
+    3
+}
+
+fn default_min_baseline_samples() -> usize {
+    10
+}
+
+fn default_min_baseline_items() -> f64 {
+    3.0
+}
+
+fn default_collapse_ratio() -> f64 {
+    0.2
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            window: default_window(),
+            min_baseline_samples: default_min_baseline_samples(),
+            min_baseline_items: default_min_baseline_items(),
+            collapse_ratio: default_collapse_ratio(),
+        }
+    }
+}
+
+/// 异常类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// 请求成功但解析不出结果（页面结构变化）
+    ParserBroken,
+    /// 请求被引擎拒绝（403/429/验证码）
+    EngineBlocked,
+}
+
+impl AnomalyKind {
+    /// 类型名称（用于日志和指标标签）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::ParserBroken => "parser_broken",
+            AnomalyKind::EngineBlocked => "engine_blocked",
+        }
+    }
+}
+
+/// 一次检测到的引擎异常
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineAnomaly {
+    /// 引擎名称
+    pub engine: String,
+    /// 异常类型
+    pub kind: AnomalyKind,
+    /// 基线：健康时每次查询的平均结果数
+    pub baseline_items: f64,
+    /// 最近窗口内每次查询的平均结果数
+    pub recent_items: f64,
+    /// 最近窗口内被拒绝的请求数
+    pub blocked_requests: usize,
+    /// 窗口大小
+    pub window: usize,
+    /// 检测时间
+    pub detected_at: DateTime<Utc>,
+}
+
+impl EngineAnomaly {
+    /// 严重程度（0~1，结果数相对基线下降的比例）
+    pub fn severity(&self) -> f64 {
+        if self.baseline_items <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - self.recent_items / self.baseline_items).clamp(0.0, 1.0)
+    }
+
+    /// 人类可读的描述
+    pub fn description(&self) -> String {
+        format!(
+            "Engine '{}' {}: {:.1} items/query over the last {} queries (baseline {:.1}, {} blocked)",
+            self.engine,
+            self.kind.as_str(),
+            self.recent_items,
+            self.window,
+            self.baseline_items,
+            self.blocked_requests,
+        )
+    }
+}
+
+/// 一次引擎请求的观测结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Observation {
+    /// 请求成功，解析出的结果数
+    Items(usize),
+    /// 请求被引擎拒绝
+    Blocked,
+    /// 其他失败（超时、网络错误等，由熔断器处理，不参与异常检测）
+    Failed,
+}
+
+impl Observation {
+    /// 从引擎执行结果得到观测
+    pub fn from_outcome(outcome: &Result<SearchResult, String>) -> Self {
+        match outcome {
+            Ok(result) => Observation::Items(result.items.len()),
+            Err(e) if is_blocking_error(e) => Observation::Blocked,
+            Err(_) => Observation::Failed,
+        }
+    }
+}
+
+/// 错误信息是否表明请求被引擎拒绝
+pub fn is_blocking_error(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    ["403", "429", "forbidden", "too many requests", "captcha", "验证码", "访问过于频繁"]
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+/// 检测到异常时的回调
+pub type AnomalyListener = Arc<dyn Fn(&EngineAnomaly) + Send + Sync>;
+
+/// 单个引擎的检测状态
+#[derive(Debug, Default)]
+struct EngineTrack {
+    /// 基线（健康样本的平均结果数）
+    baseline: f64,
+    /// 计入基线的样本数
+    baseline_samples: usize,
+    /// 最近窗口（被拒绝记为 None）
+    recent: VecDeque<Option<usize>>,
+    /// 当前退化是否已告警
+    alerted: bool,
+}
+
+/// 引擎响应异常检测器
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    tracks: Mutex<HashMap<String, EngineTrack>>,
+    recent: Mutex<VecDeque<EngineAnomaly>>,
+    listeners: RwLock<Vec<AnomalyListener>>,
+}
+
+impl AnomalyDetector {
+    /// 创建检测器
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            tracks: Mutex::new(HashMap::new()),
+            recent: Mutex::new(VecDeque::with_capacity(MAX_RECENT_ANOMALIES)),
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// 检测配置
+    pub fn config(&self) -> &AnomalyConfig {
+        &self.config
+    }
+
+    /// 注册检测到异常时的回调（例如投递 webhook）
+    pub fn subscribe(&self, listener: AnomalyListener) {
+        self.listeners.write().unwrap_or_else(|e| e.into_inner()).push(listener);
+    }
+
+    /// 引擎当前的基线（样本不足时为 None）
+    pub fn baseline(&self, engine: &str) -> Option<f64> {
+        let tracks = self.tracks.lock().unwrap_or_else(|e| e.into_inner());
+        tracks.get(engine)
+            .filter(|track| track.baseline_samples >= self.config.min_baseline_samples)
+            .map(|track| track.baseline)
+    }
+
+    /// 最近检测到的异常（最新的在前）
+    pub fn recent(&self) -> Vec<EngineAnomaly> {
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        recent.iter().rev().cloned().collect()
+    }
+
+    /// 记录一次观测，检测到新的异常时记录日志和指标、通知回调并返回该异常
+    pub fn observe(&self, engine: &str, observation: Observation) -> Option<EngineAnomaly> {
+        if !self.config.enabled {
+            return None;
+        }
+        let anomaly = self.detect(engine, observation)?;
+
+        tracing::warn!(
+            engine = %anomaly.engine,
+            kind = anomaly.kind.as_str(),
+            baseline_items = anomaly.baseline_items,
+            recent_items = anomaly.recent_items,
+            blocked_requests = anomaly.blocked_requests,
+            "Engine response anomaly detected"
+        );
+        metrics::counter!(
+            "seesea_engine_anomalies_total",
+            "engine" => anomaly.engine.clone(),
+            "kind" => anomaly.kind.as_str()
+        ).increment(1);
+
+        {
+            let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+            if recent.len() >= MAX_RECENT_ANOMALIES {
+                recent.pop_front();
+            }
+            recent.push_back(anomaly.clone());
+        }
+        let listeners = self.listeners.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for listener in listeners {
+            listener(&anomaly);
+        }
+        Some(anomaly)
+    }
+
+    /// 更新引擎的检测状态，退化刚发生时返回异常
+    fn detect(&self, engine: &str, observation: Observation) -> Option<EngineAnomaly> {
+        let sample = match observation {
+            Observation::Items(count) => Some(count),
+            Observation::Blocked => None,
+            Observation::Failed => return None,
+        };
+        let window = self.config.window.max(1);
+        let mut tracks = self.tracks.lock().unwrap_or_else(|e| e.into_inner());
+        let track = tracks.entry(engine.to_string()).or_default();
+        let warmed_up = track.baseline_samples >= self.config.min_baseline_samples;
+
+        // 只有健康样本计入基线，避免退化期间把基线拉低
+        if let Some(count) = sample {
+            let count = count as f64;
+            if !warmed_up {
+                track.baseline_samples += 1;
+                track.baseline += (count - track.baseline) / track.baseline_samples as f64;
+            } else if count >= track.baseline * self.config.collapse_ratio {
+                track.baseline += BASELINE_ALPHA * (count - track.baseline);
+            }
+        }
+
+        track.recent.push_back(sample);
+        while track.recent.len() > window {
+            track.recent.pop_front();
+        }
+        if !warmed_up || track.recent.len() < window || track.baseline < self.config.min_baseline_items {
+            return None;
+        }
+
+        let recent_items = track.recent.iter().map(|s| s.unwrap_or(0)).sum::<usize>() as f64 / window as f64;
+        if recent_items >= track.baseline * self.config.collapse_ratio {
+            track.alerted = false;
+            return None;
+        }
+        if track.alerted {
+            return None;
+        }
+        track.alerted = true;
+
+        let blocked_requests = track.recent.iter().filter(|s| s.is_none()).count();
+        let kind = if blocked_requests * 2 > window {
+            AnomalyKind::EngineBlocked
+        } else {
+            AnomalyKind::ParserBroken
+        };
+        Some(EngineAnomaly {
+            engine: engine.to_string(),
+            kind,
+            baseline_items: track.baseline,
+            recent_items,
+            blocked_requests,
+            window,
+            detected_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn warmed_up(detector: &AnomalyDetector, engine: &str) {
+        for _ in 0..detector.config().min_baseline_samples {
+            assert!(detector.observe(engine, Observation::Items(10)).is_none());
+        }
+        assert_eq!(detector.baseline(engine), Some(10.0));
+    }
+
+    #[test]
+    fn test_parser_broken_alerts_once() {
+        let detector = AnomalyDetector::new(AnomalyConfig::default());
+        let alerts = Arc::new(AtomicUsize::new(0));
+        let counter = alerts.clone();
+        detector.subscribe(Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        warmed_up(&detector, "bing");
+
+        assert!(detector.observe("bing", Observation::Items(0)).is_none());
+        assert!(detector.observe("bing", Observation::Items(0)).is_none());
+        let anomaly = detector.observe("bing", Observation::Items(0)).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::ParserBroken);
+        assert_eq!(anomaly.recent_items, 0.0);
+        assert_eq!(anomaly.severity(), 1.0);
+
+        // 退化期间不重复告警，基线不被拉低
+        assert!(detector.observe("bing", Observation::Items(0)).is_none());
+        assert_eq!(detector.baseline("bing"), Some(10.0));
+        assert_eq!(alerts.load(Ordering::SeqCst), 1);
+        assert_eq!(detector.recent().len(), 1);
+
+        // 恢复后再次退化会重新告警
+        for _ in 0..3 {
+            detector.observe("bing", Observation::Items(10));
+        }
+        for _ in 0..3 {
+            detector.observe("bing", Observation::Items(0));
+        }
+        assert_eq!(alerts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_engine_blocked_and_ignored_failures() {
+        let detector = AnomalyDetector::new(AnomalyConfig::default());
+        warmed_up(&detector, "baidu");
+
+        // 超时等失败不参与检测
+        for _ in 0..5 {
+            assert!(detector.observe("baidu", Observation::Failed).is_none());
+        }
+        detector.observe("baidu", Observation::Blocked);
+        detector.observe("baidu", Observation::Items(0));
+        let anomaly = detector.observe("baidu", Observation::Blocked).unwrap();
+        assert_eq!(anomaly.kind, AnomalyKind::EngineBlocked);
+        assert_eq!(anomaly.blocked_requests, 2);
+
+        // 其他引擎不受影响
+        assert!(detector.observe("bing", Observation::Items(0)).is_none());
+    }
+
+    #[test]
+    fn test_observation_from_outcome() {
+        assert_eq!(Observation::from_outcome(&Err("HTTP 错误: 429 Too Many Requests".to_string())), Observation::Blocked);
+        assert_eq!(Observation::from_outcome(&Err("Engine bing timeout".to_string())), Observation::Failed);
+        assert!(is_blocking_error("触发验证码"));
+        assert!(!is_blocking_error("connection reset"));
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 汇率换算
+//!
+//! 默认使用打包在二进制中的欧洲央行（ECB）每日参考汇率快照，离线即可换算；
+//! 启用 `live_currency_rates` 后定期从 ECB 拉取最新汇率，失败时回退到打包的快照。
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::net::client::HttpTransport;
+
+/// ECB 每日参考汇率地址
+pub const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+/// 在线汇率的刷新间隔（ECB 每个工作日更新一次）
+const LIVE_RATES_TTL: Duration = Duration::from_secs(6 * 3600);
+
+/// 打包的 ECB 汇率快照（发布前可替换为最新的 eurofxref-daily.xml）
+const BUNDLED_ECB_RATES: &str = include_str!("eurofxref-daily.xml");
+
+static BUNDLED_RATES: Lazy<Arc<ExchangeRates>> = Lazy::new(|| {
+    Arc::new(ExchangeRates::parse_ecb(BUNDLED_ECB_RATES, "ECB (bundled)").expect("bundled ECB rates are valid"))
+});
+
+static ECB_DATE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"time=['"](\d{4}-\d{2}-\d{2})['"]"#).unwrap());
+static ECB_RATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"currency=['"]([A-Z]{3})['"]\s+rate=['"]([0-9.]+)['"]"#).unwrap());
+
+/// 一组以 `base` 为基准的汇率
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRates {
+    /// 基准货币（ECB 为 EUR）
+    pub base: String,
+    /// 汇率日期（YYYY-MM-DD）
+    pub date: Option<String>,
+    /// 1 单位基准货币可兑换的各货币数量
+    pub rates: HashMap<String, f64>,
+    /// 数据来源
+    pub source: String,
+}
+
+impl ExchangeRates {
+    /// 解析 ECB eurofxref XML
+    pub fn parse_ecb(xml: &str, source: &str) -> Option<Self> {
+        let rates: HashMap<String, f64> = ECB_RATE_RE
+            .captures_iter(xml)
+            .filter_map(|c| Some((c[1].to_string(), c[2].parse::<f64>().ok().filter(|r| *r > 0.0)?)))
+            .collect();
+        if rates.is_empty() {
+            return None;
+        }
+        Some(Self {
+            base: "EUR".to_string(),
+            date: ECB_DATE_RE.captures(xml).map(|c| c[1].to_string()),
+            rates,
+            source: source.to_string(),
+        })
+    }
+
+    /// 1 单位基准货币可兑换的 `code` 数量（不支持的货币返回 None）
+    pub fn rate(&self, code: &str) -> Option<f64> {
+        if code.eq_ignore_ascii_case(&self.base) {
+            return Some(1.0);
+        }
+        self.rates.get(&code.to_ascii_uppercase()).copied()
+    }
+
+    /// 是否支持该货币
+    pub fn supports(&self, code: &str) -> bool {
+        self.rate(code).is_some()
+    }
+
+    /// 1 单位 `from` 可兑换的 `to` 数量
+    pub fn cross_rate(&self, from: &str, to: &str) -> Option<f64> {
+        Some(self.rate(to)? / self.rate(from)?)
+    }
+}
+
+/// 汇率数据提供方
+#[async_trait]
+pub trait CurrencyProvider: Send + Sync {
+    /// 获取当前汇率
+    async fn rates(&self) -> Result<Arc<ExchangeRates>, Box<dyn Error + Send + Sync>>;
+}
+
+/// ECB 汇率：打包的快照，可选在线刷新
+pub struct EcbRatesProvider {
+    /// 在线刷新使用的传输层（None 表示只用打包的快照）
+    transport: Option<Arc<dyn HttpTransport>>,
+    /// 最近一次在线获取的汇率及获取时间
+    live: RwLock<Option<(Instant, Arc<ExchangeRates>)>>,
+}
+
+impl EcbRatesProvider {
+    /// 只使用打包的快照
+    pub fn bundled() -> Self {
+        Self { transport: None, live: RwLock::new(None) }
+    }
+
+    /// 定期从 ECB 拉取最新汇率
+    pub fn live(transport: Arc<dyn HttpTransport>) -> Self {
+        Self { transport: Some(transport), live: RwLock::new(None) }
+    }
+
+    async fn fetch(&self, transport: &Arc<dyn HttpTransport>) -> Result<Arc<ExchangeRates>, Box<dyn Error + Send + Sync>> {
+        let response = transport.get(ECB_DAILY_URL, None).await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("ECB 汇率接口返回 HTTP {}", response.status()).into());
+        }
+        let xml = response.text().await?;
+        let rates = ExchangeRates::parse_ecb(&xml, "ECB").ok_or("ECB 汇率数据无法解析")?;
+        Ok(Arc::new(rates))
+    }
+}
+
+#[async_trait]
+impl CurrencyProvider for EcbRatesProvider {
+    async fn rates(&self) -> Result<Arc<ExchangeRates>, Box<dyn Error + Send + Sync>> {
+        let Some(transport) = &self.transport else {
+            return Ok(BUNDLED_RATES.clone());
+        };
+
+        if let Some((fetched_at, rates)) = self.live.read().await.as_ref()
+            && fetched_at.elapsed() < LIVE_RATES_TTL
+        {
+            return Ok(rates.clone());
+        }
+
+        match self.fetch(transport).await {
+            Ok(rates) => {
+                *self.live.write().await = Some((Instant::now(), rates.clone()));
+                Ok(rates)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to refresh ECB rates, using bundled snapshot: {}", e);
+                let live = self.live.read().await;
+                Ok(live.as_ref().map(|(_, rates)| rates.clone()).unwrap_or_else(|| BUNDLED_RATES.clone()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[test]
+    fn test_bundled_rates() {
+        let rates = BUNDLED_RATES.clone();
+        assert_eq!(rates.base, "EUR");
+        assert!(rates.date.is_some());
+        assert!(rates.supports("usd") && rates.supports("EUR") && rates.supports("CNY"));
+        assert!(!rates.supports("XYZ"));
+
+        let usd_to_eur = rates.cross_rate("USD", "EUR").unwrap();
+        assert!((usd_to_eur * rates.rate("USD").unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_live_rates_fall_back_to_bundled() {
+        let xml = "<Cube time='2030-06-01'><Cube currency='USD' rate='2.0'/></Cube>";
+        let transport = Arc::new(MockTransport::new().respond("ecb.europa.eu", MockResponse::ok(xml)));
+        let rates = EcbRatesProvider::live(transport).rates().await.unwrap();
+        assert_eq!(rates.date.as_deref(), Some("2030-06-01"));
+        assert_eq!(rates.rate("USD"), Some(2.0));
+
+        let failing = Arc::new(MockTransport::new().respond("ecb.europa.eu", MockResponse::new(503, "")));
+        let rates = EcbRatesProvider::live(failing).rates().await.unwrap();
+        assert_eq!(rates.source, "ECB (bundled)");
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 即时答案（answerers）
+//!
+//! 识别特定模式的查询并直接给出结构化答案，与网页结果一起返回：
+//! - 汇率换算：`100 usd to eur`、`100 美元 兑 人民币` 之类的查询使用 ECB 汇率换算；
+//! - 股票报价：`AAPL stock`、`TSLA 股价`，需要配置报价提供方（如 Alpha Vantage）。
+//!
+//! 数据来源通过 [`CurrencyProvider`] 和 [`StockQuoteProvider`] 接入。
+
+pub mod currency;
+pub mod stock;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::net::client::HttpTransport;
+
+pub use currency::{CurrencyProvider, EcbRatesProvider, ExchangeRates};
+pub use stock::{AlphaVantageProvider, StockQuote, StockQuoteProvider};
+
+/// 股票报价提供方
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StockProvider {
+    /// Alpha Vantage（`GLOBAL_QUOTE` 接口，免费密钥每天 25 次）
+    AlphaVantage {
+        /// API 密钥
+        api_key: String,
+    },
+}
+
+/// 即时答案配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswersConfig {
+    /// 是否启用（默认启用，汇率换算离线可用）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 是否在线获取 ECB 最新汇率（默认只用打包的快照）
+    #[serde(default)]
+    pub live_currency_rates: bool,
+    /// 股票报价提供方（未配置时不回答股票查询）
+    #[serde(default)]
+    pub stock: Option<StockProvider>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for AnswersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            live_currency_rates: false,
+            stock: None,
+        }
+    }
+}
+
+/// 汇率换算答案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyAnswer {
+    /// 换算金额
+    pub amount: f64,
+    /// 源货币代码
+    pub from: String,
+    /// 目标货币代码
+    pub to: String,
+    /// 1 单位源货币可兑换的目标货币数量
+    pub rate: f64,
+    /// 换算结果
+    pub result: f64,
+    /// 汇率日期（YYYY-MM-DD）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<String>,
+    /// 数据来源
+    pub source: String,
+}
+
+/// 结构化答案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Answer {
+    /// 汇率换算
+    Currency(CurrencyAnswer),
+    /// 股票报价
+    Stock(StockQuote),
+}
+
+/// 识别出的答案查询
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnswerQuery {
+    /// 汇率换算（货币代码为大写）
+    Currency { amount: f64, from: String, to: String },
+    /// 股票报价（代码为大写）
+    Stock { symbol: String },
+}
+
+/// 常见货币的中文名和符号
+const CURRENCY_ALIASES: &[(&str, &str)] = &[
+    ("美元", "USD"), ("$", "USD"), ("欧元", "EUR"), ("€", "EUR"), ("人民币", "CNY"), ("元", "CNY"),
+    ("¥", "CNY"), ("日元", "JPY"), ("英镑", "GBP"), ("£", "GBP"), ("港币", "HKD"), ("港元", "HKD"),
+    ("韩元", "KRW"), ("澳元", "AUD"), ("加元", "CAD"), ("瑞郎", "CHF"), ("新加坡元", "SGD"),
+];
+
+static CURRENCY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(\d+(?:[.,]\d+)?)?\s*([a-z]{3}|[^\d\s]{1,4})\s*(?:to|in|into|=|->|兑|换|兑换|换成)\s*([a-z]{3}|[^\d\s]{1,4})\s*$").unwrap()
+});
+
+static STOCK_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*\$?([a-z][a-z0-9]{0,5}(?:\.[a-z]{1,2})?)\s*(?:stock|stock price|share price|shares|quote|股价|股票)\s*$").unwrap()
+});
+
+fn currency_code(token: &str) -> Option<String> {
+    let token = token.trim();
+    if token.len() == 3 && token.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Some(token.to_ascii_uppercase());
+    }
+    CURRENCY_ALIASES.iter().find(|(alias, _)| *alias == token).map(|(_, code)| code.to_string())
+}
+
+impl AnswerQuery {
+    /// 识别查询（不检查货币代码是否受支持）
+    pub fn parse(query: &str) -> Option<Self> {
+        if let Some(c) = CURRENCY_RE.captures(query) {
+            let amount = match c.get(1) {
+                Some(m) => m.as_str().replace(',', ".").parse().ok()?,
+                None => 1.0,
+            };
+            let (from, to) = (currency_code(&c[2])?, currency_code(&c[3])?);
+            if from != to {
+                return Some(Self::Currency { amount, from, to });
+            }
+        }
+        STOCK_RE
+            .captures(query)
+            .map(|c| Self::Stock { symbol: c[1].to_ascii_uppercase() })
+    }
+}
+
+/// 即时答案服务
+pub struct AnswerService {
+    currency: Arc<dyn CurrencyProvider>,
+    stock: Option<Arc<dyn StockQuoteProvider>>,
+}
+
+impl AnswerService {
+    /// 使用指定的数据来源创建
+    pub fn new(currency: Arc<dyn CurrencyProvider>, stock: Option<Arc<dyn StockQuoteProvider>>) -> Self {
+        Self { currency, stock }
+    }
+
+    /// 为查询生成答案（不匹配任何模式或数据源出错时返回 None）
+    pub async fn answer(&self, query: &str) -> Option<Answer> {
+        match AnswerQuery::parse(query)? {
+            AnswerQuery::Currency { amount, from, to } => {
+                let rates = match self.currency.rates().await {
+                    Ok(rates) => rates,
+                    Err(e) => {
+                        tracing::warn!("Failed to load exchange rates: {}", e);
+                        return None;
+                    }
+                };
+                let rate = rates.cross_rate(&from, &to)?;
+                Some(Answer::Currency(CurrencyAnswer {
+                    amount,
+                    from,
+                    to,
+                    rate: round_to(rate, 6),
+                    result: round_to(amount * rate, 4),
+                    as_of: rates.date.clone(),
+                    source: rates.source.clone(),
+                }))
+            }
+            AnswerQuery::Stock { symbol } => {
+                let provider = self.stock.as_ref()?;
+                match provider.quote(&symbol).await {
+                    Ok(quote) => quote.map(Answer::Stock),
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch stock quote for {}: {}", symbol, e);
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// 根据配置创建即时答案服务（未启用时返回 None）
+pub fn create_answer_service(config: &AnswersConfig, transport: Arc<dyn HttpTransport>) -> Option<Arc<AnswerService>> {
+    if !config.enabled {
+        return None;
+    }
+    let currency: Arc<dyn CurrencyProvider> = if config.live_currency_rates {
+        Arc::new(EcbRatesProvider::live(transport.clone()))
+    } else {
+        Arc::new(EcbRatesProvider::bundled())
+    };
+    let stock = config.stock.as_ref().map(|provider| -> Arc<dyn StockQuoteProvider> {
+        match provider {
+            StockProvider::AlphaVantage { api_key } => Arc::new(AlphaVantageProvider::new(transport.clone(), api_key.clone())),
+        }
+    });
+    Some(Arc::new(AnswerService::new(currency, stock)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer_queries() {
+        let currency = |amount: f64, from: &str, to: &str| Some(AnswerQuery::Currency {
+            amount,
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+        assert_eq!(AnswerQuery::parse("100 usd to eur"), currency(100.0, "USD", "EUR"));
+        assert_eq!(AnswerQuery::parse("12.5GBP in JPY"), currency(12.5, "GBP", "JPY"));
+        assert_eq!(AnswerQuery::parse("usd to cny"), currency(1.0, "USD", "CNY"));
+        assert_eq!(AnswerQuery::parse("100 美元 兑 人民币"), currency(100.0, "USD", "CNY"));
+
+        let stock = |symbol: &str| Some(AnswerQuery::Stock { symbol: symbol.to_string() });
+        assert_eq!(AnswerQuery::parse("AAPL stock"), stock("AAPL"));
+        assert_eq!(AnswerQuery::parse("$tsla share price"), stock("TSLA"));
+        assert_eq!(AnswerQuery::parse("brk.b 股价"), stock("BRK.B"));
+
+        assert_eq!(AnswerQuery::parse("how to learn rust"), None);
+        assert_eq!(AnswerQuery::parse("usd to usd"), None);
+    }
+
+    #[tokio::test]
+    async fn test_currency_answer() {
+        let service = AnswerService::new(Arc::new(EcbRatesProvider::bundled()), None);
+        let Some(Answer::Currency(answer)) = service.answer("100 eur to usd").await else {
+            panic!("expected a currency answer");
+        };
+        assert_eq!(answer.from, "EUR");
+        assert!(answer.result > 0.0 && (answer.result - 100.0 * answer.rate).abs() < 0.01);
+        assert!(answer.as_of.is_some());
+
+        // 不支持的货币和未配置的股票数据源都不回答
+        assert!(service.answer("100 abc to xyz").await.is_none());
+        assert!(service.answer("AAPL stock").await.is_none());
+    }
+}
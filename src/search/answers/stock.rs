@@ -0,0 +1,152 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 股票报价
+//!
+//! 股票行情没有可打包的离线数据，需要配置 API 密钥后才会启用；
+//! 目前内置 Alpha Vantage 的 `GLOBAL_QUOTE` 接口，其他数据源通过 [`StockQuoteProvider`] 接入。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::net::client::HttpTransport;
+use crate::search::engines::utils::build_query_string_owned;
+
+/// Alpha Vantage 接口地址
+const ALPHA_VANTAGE_URL: &str = "https://www.alphavantage.co/query";
+
+/// 股票报价
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StockQuote {
+    /// 股票代码（大写）
+    pub symbol: String,
+    /// 最新价
+    pub price: f64,
+    /// 涨跌额
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<f64>,
+    /// 涨跌幅（百分比）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_percent: Option<f64>,
+    /// 计价货币
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// 最新交易日（YYYY-MM-DD）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<String>,
+    /// 数据来源
+    pub source: String,
+}
+
+/// 股票报价提供方
+#[async_trait]
+pub trait StockQuoteProvider: Send + Sync {
+    /// 查询股票报价（代码不存在时返回 None）
+    async fn quote(&self, symbol: &str) -> Result<Option<StockQuote>, Box<dyn Error + Send + Sync>>;
+}
+
+/// Alpha Vantage 报价
+pub struct AlphaVantageProvider {
+    transport: Arc<dyn HttpTransport>,
+    api_key: String,
+}
+
+impl AlphaVantageProvider {
+    /// 创建报价提供方
+    pub fn new(transport: Arc<dyn HttpTransport>, api_key: impl Into<String>) -> Self {
+        Self { transport, api_key: api_key.into() }
+    }
+}
+
+/// 解析 `GLOBAL_QUOTE` 响应
+fn parse_global_quote(json: &Value) -> Result<Option<StockQuote>, Box<dyn Error + Send + Sync>> {
+    // 超出调用频率时返回 Note/Information，密钥无效时返回 Error Message
+    for key in ["Error Message", "Note", "Information"] {
+        if let Some(message) = json.get(key).and_then(Value::as_str) {
+            return Err(format!("Alpha Vantage: {}", message).into());
+        }
+    }
+
+    let Some(quote) = json.get("Global Quote").filter(|q| q.as_object().is_some_and(|q| !q.is_empty())) else {
+        return Ok(None);
+    };
+    let field = |key: &str| quote.get(key).and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty());
+    let number = |key: &str| field(key).and_then(|s| s.trim_end_matches('%').parse::<f64>().ok());
+
+    let (Some(symbol), Some(price)) = (field("01. symbol"), number("05. price")) else {
+        return Ok(None);
+    };
+    Ok(Some(StockQuote {
+        symbol: symbol.to_ascii_uppercase(),
+        price,
+        change: number("09. change"),
+        change_percent: number("10. change percent"),
+        currency: None,
+        as_of: field("07. latest trading day").map(str::to_string),
+        source: "Alpha Vantage".to_string(),
+    }))
+}
+
+#[async_trait]
+impl StockQuoteProvider for AlphaVantageProvider {
+    async fn quote(&self, symbol: &str) -> Result<Option<StockQuote>, Box<dyn Error + Send + Sync>> {
+        let query = build_query_string_owned([
+            ("function", "GLOBAL_QUOTE".to_string()),
+            ("symbol", symbol.to_string()),
+            ("apikey", self.api_key.clone()),
+        ]);
+        let url = format!("{}?{}", ALPHA_VANTAGE_URL, query);
+        let response = self.transport.get(&url, None).await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Alpha Vantage 返回 HTTP {}", response.status()).into());
+        }
+        let json: Value = response.json().await?;
+        parse_global_quote(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[tokio::test]
+    async fn test_alpha_vantage_quote() {
+        let body = r#"{"Global Quote": {
+            "01. symbol": "AAPL", "05. price": "227.5200", "07. latest trading day": "2025-01-03",
+            "09. change": "-1.3400", "10. change percent": "-0.5855%"
+        }}"#;
+        let transport = Arc::new(MockTransport::new().respond("alphavantage.co", MockResponse::ok(body)));
+        let provider = AlphaVantageProvider::new(transport.clone(), "demo");
+
+        let quote = provider.quote("AAPL").await.unwrap().unwrap();
+        assert_eq!(quote.symbol, "AAPL");
+        assert_eq!(quote.price, 227.52);
+        assert_eq!(quote.change_percent, Some(-0.5855));
+        assert_eq!(quote.as_of.as_deref(), Some("2025-01-03"));
+        assert!(transport.requests()[0].url.contains("symbol=AAPL"));
+    }
+
+    #[test]
+    fn test_parse_global_quote_errors() {
+        let empty: Value = serde_json::from_str(r#"{"Global Quote": {}}"#).unwrap();
+        assert_eq!(parse_global_quote(&empty).unwrap(), None);
+
+        let limited: Value = serde_json::from_str(r#"{"Note": "API call frequency exceeded"}"#).unwrap();
+        assert!(parse_global_quote(&limited).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Wayback Machine 快照链接
+//!
+//! 启用后，为排名靠前的结果查询 archive.org 的最新快照，并把快照地址写入结果项
+//! metadata 的 [`ARCHIVE_URL_KEY`]。查询会产生额外的出站请求，因此默认关闭；
+//! 查询结果（包括没有快照的结果）写入缓存，同一 URL 在缓存有效期内不会重复查询。
+
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::cache::CacheManager;
+use crate::derive::SearchResultItem;
+use crate::net::client::HttpTransport;
+use crate::net::types::RequestOptions;
+
+/// 快照地址在结果项 metadata 中的键
+pub const ARCHIVE_URL_KEY: &str = "archive_url";
+
+/// 快照查询缓存键前缀（后接结果 URL）
+const ARCHIVE_CACHE_PREFIX: &str = "archive:";
+
+/// Wayback Machine 可用性接口
+const WAYBACK_AVAILABLE_API: &str = "https://archive.org/wayback/available";
+
+/// 快照链接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 每次搜索最多为前多少条结果查询快照
+    #[serde(default = "default_max_lookups")]
+    pub max_lookups: usize,
+    /// 同时进行的查询数
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// 整批查询的超时时间，超时未完成的结果不带快照链接
+    #[serde(default = "default_timeout")]
+    pub timeout: Duration,
+    /// 查询结果的缓存时间
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: Duration,
+}
+
+fn default_max_lookups() -> usize {
+    10
+}
+
+fn default_max_concurrent() -> usize {
+    5
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(3)
+}
+
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(24 * 3600)
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_lookups: default_max_lookups(),
+            max_concurrent: default_max_concurrent(),
+            timeout: default_timeout(),
+            cache_ttl: default_cache_ttl(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AvailabilityResponse {
+    #[serde(default)]
+    archived_snapshots: ArchivedSnapshots,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ArchivedSnapshots {
+    closest: Option<Snapshot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    available: bool,
+    url: String,
+}
+
+/// 解析可用性接口的响应，返回快照地址（统一为 https）
+fn parse_availability(body: &[u8]) -> Option<String> {
+    let response: AvailabilityResponse = serde_json::from_slice(body).ok()?;
+    let snapshot = response.archived_snapshots.closest.filter(|s| s.available)?;
+    Some(match snapshot.url.strip_prefix("http://") {
+        Some(rest) => format!("https://{}", rest),
+        None => snapshot.url,
+    })
+}
+
+/// 查询单个 URL 的最新快照（先查缓存）
+///
+/// 请求失败时返回 None 且不写缓存，下次搜索会重新查询
+async fn lookup(
+    config: &ArchiveConfig,
+    transport: &dyn HttpTransport,
+    cache: Option<&CacheManager>,
+    url: &str,
+) -> Option<String> {
+    let cache_key = format!("{}{}", ARCHIVE_CACHE_PREFIX, url);
+    if let Some(cached) = cache.and_then(|cache| cache.get(&cache_key).ok().flatten()) {
+        // 空值表示没有快照
+        return String::from_utf8(cached).ok().filter(|s| !s.is_empty());
+    }
+
+    let api_url = format!("{}?url={}", WAYBACK_AVAILABLE_API, urlencoding::encode(url));
+    let options = RequestOptions {
+        timeout: config.timeout,
+        ..Default::default()
+    };
+    let response = match transport.get(&api_url, Some(options)).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            tracing::debug!("Wayback lookup for {} returned {}", url, response.status());
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!("Wayback lookup for {} failed: {}", url, e);
+            return None;
+        }
+    };
+    let body = response.bytes().await.ok()?;
+    let archive_url = parse_availability(&body);
+    if let Some(cache) = cache {
+        let value = archive_url.clone().unwrap_or_default().into_bytes();
+        if let Err(e) = cache.set(cache_key, value, Some(config.cache_ttl)) {
+            tracing::warn!("Failed to cache wayback lookup: {}", e);
+        }
+    }
+    archive_url
+}
+
+/// 为前 `max_lookups` 条结果并发查询快照并写入 metadata
+pub async fn annotate_archive_urls(
+    config: &ArchiveConfig,
+    transport: &dyn HttpTransport,
+    cache: Option<&CacheManager>,
+    items: &mut [SearchResultItem],
+) {
+    let count = items.len().min(config.max_lookups);
+    let urls: Vec<(usize, String)> = items[..count]
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !item.metadata.contains_key(ARCHIVE_URL_KEY))
+        .map(|(index, item)| (index, item.url.clone()))
+        .collect();
+    if urls.is_empty() {
+        return;
+    }
+
+    let mut found = Vec::new();
+    let lookups = stream::iter(urls)
+        .map(|(index, url)| async move { (index, lookup(config, transport, cache, &url).await) })
+        .buffer_unordered(config.max_concurrent.max(1));
+    // 超时后保留已经完成的查询结果
+    let _ = tokio::time::timeout(config.timeout, lookups.for_each(|(index, archive_url)| {
+        if let Some(archive_url) = archive_url {
+            found.push((index, archive_url));
+        }
+        async {}
+    }))
+    .await;
+
+    for (index, archive_url) in found {
+        items[index].metadata.insert(ARCHIVE_URL_KEY.to_string(), archive_url);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    fn item(url: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score: 0.0,
+            result_type: Default::default(),
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_annotate_archive_urls_uses_cache() {
+        let snapshot = r#"{"url": "example.com", "archived_snapshots": {"closest": {"status": "200", "available": true,
+            "url": "http://web.archive.org/web/20240101000000/https://example.com/archived", "timestamp": "20240101000000"}}}"#;
+        let transport = MockTransport::new()
+            .respond("example.com%2Farchived", MockResponse::ok(snapshot))
+            .respond("archive.org/wayback", MockResponse::ok(r#"{"url": "x", "archived_snapshots": {}}"#));
+        let cache = CacheManager::temporary();
+        let config = ArchiveConfig {
+            enabled: true,
+            max_lookups: 2,
+            ..Default::default()
+        };
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        let mut items = vec![
+            item("https://example.com/archived"),
+            item(&format!("https://example.com/new-{}", suffix)),
+            item("https://example.com/not-looked-up"),
+        ];
+
+        annotate_archive_urls(&config, &transport, Some(&cache), &mut items).await;
+        assert_eq!(
+            items[0].metadata.get(ARCHIVE_URL_KEY).map(String::as_str),
+            Some("https://web.archive.org/web/20240101000000/https://example.com/archived"),
+        );
+        assert!(!items[1].metadata.contains_key(ARCHIVE_URL_KEY));
+        assert!(!items[2].metadata.contains_key(ARCHIVE_URL_KEY));
+        assert_eq!(transport.requests().len(), 2);
+
+        // 第二次从缓存读取（包括没有快照的结果）
+        let mut again = vec![item("https://example.com/archived"), item(&format!("https://example.com/new-{}", suffix))];
+        annotate_archive_urls(&config, &transport, Some(&cache), &mut again).await;
+        assert!(again[0].metadata.contains_key(ARCHIVE_URL_KEY));
+        assert_eq!(transport.requests().len(), 2);
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 无头浏览器引擎适配器（`browser` 特性）
+//!
+//! 部分引擎只在执行 JavaScript 后才输出结果。[`BrowserEngineAdapter`] 包装一个
+//! 返回 HTML 的 [`RequestResponseEngine`]：沿用它的 `request()` 构造 URL 和 `response()`
+//! 解析结果，只把抓取换成通过 CDP 驱动的无头 Chrome 渲染页面。
+//!
+//! [`BrowserPool`] 只启动一个浏览器进程，每个请求使用独立的浏览器上下文（互不共享
+//! Cookie 和缓存），用信号量限制并发上下文数量，并对每个页面设置超时和 HTML 大小上限。
+//! 同一个浏览器池也实现 [`PageScreenshotter`]，供结果预览截图使用。
+
+use async_trait::async_trait;
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, Viewport};
+use chromiumoxide::cdp::browser_protocol::target::{CreateBrowserContextParams, CreateTargetParams};
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use crate::derive::{
+    EngineInfo, RequestParams, RequestResponseEngine, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+
+/// 浏览器池配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserPoolConfig {
+    /// Chrome/Chromium 可执行文件，未设置时自动查找
+    pub executable: Option<PathBuf>,
+    /// 同时打开的浏览器上下文上限
+    pub max_contexts: usize,
+    /// 单个页面（导航 + 渲染 + 读取 HTML）的超时（秒）
+    pub page_timeout_secs: u64,
+    /// 启动浏览器的超时（秒）
+    pub launch_timeout_secs: u64,
+    /// 单个页面 HTML 的最大字节数，超出部分截断
+    pub max_html_bytes: usize,
+    /// V8 堆内存上限（MB）
+    pub max_heap_mb: u32,
+    /// 禁止加载图片
+    pub disable_images: bool,
+    /// 关闭 Chrome 沙箱（容器内以 root 运行时需要）
+    pub no_sandbox: bool,
+    /// 额外的 Chrome 启动参数
+    pub extra_args: Vec<String>,
+}
+
+impl Default for BrowserPoolConfig {
+    fn default() -> Self {
+        Self {
+            executable: None,
+            max_contexts: 2,
+            page_timeout_secs: 15,
+            launch_timeout_secs: 20,
+            max_html_bytes: 4 * 1024 * 1024,
+            max_heap_mb: 256,
+            disable_images: true,
+            no_sandbox: false,
+            extra_args: Vec::new(),
+        }
+    }
+}
+
+impl BrowserPoolConfig {
+    /// 资源限制相关的 Chrome 启动参数
+    fn chrome_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--disable-gpu".to_string(),
+            "--disable-dev-shm-usage".to_string(),
+            "--disable-extensions".to_string(),
+            "--disable-background-networking".to_string(),
+            "--disable-sync".to_string(),
+            "--mute-audio".to_string(),
+            "--no-first-run".to_string(),
+            format!("--js-flags=--max-old-space-size={}", self.max_heap_mb),
+        ];
+        if self.disable_images {
+            args.push("--blink-settings=imagesEnabled=false".to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+/// 把 URL 渲染为 HTML
+#[async_trait]
+pub trait PageRenderer: Send + Sync {
+    /// 打开页面并返回渲染后的 HTML
+    async fn render(&self, url: &str) -> Result<String, Box<dyn Error + Send + Sync>>;
+}
+
+/// 截图参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenshotOptions {
+    /// 页面视口宽度（CSS 像素）
+    pub viewport_width: u32,
+    /// 页面视口高度（CSS 像素）
+    pub viewport_height: u32,
+    /// 输出图片相对视口的缩放比例（0.25 表示 1280 宽的视口输出 320 宽的图片）
+    pub scale: f64,
+    /// JPEG 质量（0 ~ 100）
+    pub quality: u8,
+}
+
+/// 把 URL 截图为 JPEG
+#[async_trait]
+pub trait PageScreenshotter: Send + Sync {
+    /// 打开页面并截取首屏
+    async fn screenshot(&self, url: &str, options: &ScreenshotOptions) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+}
+
+/// 无头浏览器池
+pub struct BrowserPool {
+    browser: Browser,
+    handler: JoinHandle<()>,
+    permits: Semaphore,
+    page_timeout: Duration,
+    max_html_bytes: usize,
+}
+
+impl BrowserPool {
+    /// 启动浏览器
+    pub async fn launch(config: BrowserPoolConfig) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut builder = BrowserConfig::builder()
+            .launch_timeout(Duration::from_secs(config.launch_timeout_secs))
+            .request_timeout(Duration::from_secs(config.page_timeout_secs))
+            .args(config.chrome_args());
+        if let Some(executable) = &config.executable {
+            builder = builder.chrome_executable(executable);
+        }
+        if config.no_sandbox {
+            builder = builder.no_sandbox();
+        }
+        let browser_config = builder.build().map_err(|e| format!("Invalid browser config: {}", e))?;
+
+        let (browser, mut handler) = Browser::launch(browser_config)
+            .await
+            .map_err(|e| format!("Failed to launch browser: {}", e))?;
+        // CDP 事件必须持续轮询，否则所有命令都会挂起
+        let handler = tokio::spawn(async move { while handler.next().await.is_some() {} });
+
+        Ok(Self {
+            browser,
+            handler,
+            permits: Semaphore::new(config.max_contexts.max(1)),
+            page_timeout: Duration::from_secs(config.page_timeout_secs),
+            max_html_bytes: config.max_html_bytes,
+        })
+    }
+
+    /// 关闭浏览器进程
+    pub async fn close(mut self) {
+        if let Err(e) = self.browser.close().await {
+            tracing::warn!("Failed to close browser: {}", e);
+        }
+        let _ = self.browser.wait().await;
+        self.handler.abort();
+    }
+
+    async fn render_in_context(&self, url: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let html = self
+            .with_page(url, None, |page| async move {
+                page.content().await.map_err(|e| format!("Failed to read page content: {}", e))
+            })
+            .await?;
+        Ok(truncate_html(html, self.max_html_bytes))
+    }
+
+    /// 在独立的浏览器上下文中打开 `url`（可选先设置视口），对页面执行 `f`
+    async fn with_page<T, F, Fut>(&self, url: &str, viewport: Option<(u32, u32)>, f: F) -> Result<T, Box<dyn Error + Send + Sync>>
+    where
+        F: FnOnce(Page) -> Fut,
+        Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        let _permit = self.permits.acquire().await.map_err(|e| format!("Browser pool closed: {}", e))?;
+        let context_id = self
+            .browser
+            .create_browser_context(CreateBrowserContextParams::default())
+            .await
+            .map_err(|e| format!("Failed to create browser context: {}", e))?;
+
+        let outcome = tokio::time::timeout(self.page_timeout, async {
+            let params = CreateTargetParams::builder()
+                .url("about:blank")
+                .browser_context_id(context_id.clone())
+                .build()
+                .map_err(|e| format!("Invalid target params: {}", e))?;
+            let page = self.browser.new_page(params).await.map_err(|e| format!("Failed to open page: {}", e))?;
+            if let Some((width, height)) = viewport
+                && let Err(e) = page.execute(SetDeviceMetricsOverrideParams::new(width, height, 1.0, false)).await
+            {
+                tracing::debug!("Failed to set viewport: {}", e);
+            }
+            let result = match page.goto(url).await {
+                Ok(_) => f(page.clone()).await,
+                Err(e) => Err(format!("Navigation to {} failed: {}", url, e)),
+            };
+            let _ = page.close().await;
+            result
+        })
+        .await;
+
+        // 无论成功与否都销毁上下文，释放页面占用的内存
+        if let Err(e) = self.browser.dispose_browser_context(context_id).await {
+            tracing::warn!("Failed to dispose browser context: {}", e);
+        }
+
+        Ok(outcome.map_err(|_| format!("Loading {} timed out after {:?}", url, self.page_timeout))??)
+    }
+}
+
+impl Drop for BrowserPool {
+    fn drop(&mut self) {
+        self.handler.abort();
+    }
+}
+
+#[async_trait]
+impl PageRenderer for BrowserPool {
+    async fn render(&self, url: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.render_in_context(url).await
+    }
+}
+
+#[async_trait]
+impl PageScreenshotter for BrowserPool {
+    async fn screenshot(&self, url: &str, options: &ScreenshotOptions) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let viewport = (options.viewport_width, options.viewport_height);
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Jpeg)
+            .quality(i64::from(options.quality.min(100)))
+            .clip(Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: f64::from(options.viewport_width),
+                height: f64::from(options.viewport_height),
+                scale: options.scale,
+            })
+            .build();
+        self.with_page(url, Some(viewport), |page| async move {
+            page.screenshot(params).await.map_err(|e| format!("Failed to capture screenshot: {}", e))
+        })
+        .await
+    }
+}
+
+/// 按字节上限截断 HTML（保证不切断 UTF-8 字符）
+fn truncate_html(mut html: String, max_bytes: usize) -> String {
+    if html.len() > max_bytes {
+        let mut end = max_bytes;
+        while !html.is_char_boundary(end) {
+            end -= 1;
+        }
+        tracing::debug!("Rendered HTML truncated from {} to {} bytes", html.len(), end);
+        html.truncate(end);
+    }
+    html
+}
+
+/// 用无头浏览器抓取页面的引擎适配器
+pub struct BrowserEngineAdapter<E> {
+    inner: E,
+    renderer: Arc<dyn PageRenderer>,
+}
+
+impl<E> BrowserEngineAdapter<E>
+where
+    E: RequestResponseEngine<Response = String> + Send + Sync,
+{
+    /// 包装引擎，抓取改为由 `renderer` 渲染
+    pub fn new(inner: E, renderer: Arc<dyn PageRenderer>) -> Self {
+        Self { inner, renderer }
+    }
+
+    /// 被包装的引擎
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<E> SearchEngine for BrowserEngineAdapter<E>
+where
+    E: RequestResponseEngine<Response = String> + Send + Sync,
+{
+    fn info(&self) -> &EngineInfo {
+        self.inner.info()
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+}
+
+#[async_trait]
+impl<E> RequestResponseEngine for BrowserEngineAdapter<E>
+where
+    E: RequestResponseEngine<Response = String> + Send + Sync,
+{
+    type Response = String;
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.inner.request(query, params)?;
+        if params.method != "GET" {
+            return Err(format!("{} uses {} requests, which the browser adapter cannot render", self.info().name, params.method).into());
+        }
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        self.renderer.render(url).await
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        self.inner.response(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::engines::SoEngine;
+    use std::sync::Mutex;
+
+    /// 返回固定 HTML 并记录请求 URL 的渲染器
+    struct StaticRenderer {
+        html: String,
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl PageRenderer for StaticRenderer {
+        async fn render(&self, url: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+            self.urls.lock().unwrap().push(url.to_string());
+            Ok(self.html.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adapter_renders_with_browser() {
+        let renderer = Arc::new(StaticRenderer {
+            html: r#"<ul><li class="res-list"><h3 class="res-title"><a href="https://www.rust-lang.org/">Rust 语言</a></h3><p>安全、高效</p></li></ul>"#.to_string(),
+            urls: Mutex::new(Vec::new()),
+        });
+        let adapter = BrowserEngineAdapter::new(SoEngine::new(), renderer.clone());
+
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+        let result = SearchEngine::search(&adapter, &query).await.unwrap();
+        assert_eq!(result.engine_name, "360 Search");
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].url, "https://www.rust-lang.org/");
+
+        let urls = renderer.urls.lock().unwrap();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].starts_with("https://www.so.com/s?q=rust"));
+    }
+
+    #[test]
+    fn test_truncate_html_respects_char_boundary() {
+        assert_eq!(truncate_html("搜索结果".to_string(), 7), "搜索");
+        assert_eq!(truncate_html("abc".to_string(), 10), "abc");
+    }
+
+    #[test]
+    fn test_chrome_args_limit_resources() {
+        let args = BrowserPoolConfig::default().chrome_args();
+        assert!(args.contains(&"--blink-settings=imagesEnabled=false".to_string()));
+        assert!(args.contains(&"--js-flags=--max-old-space-size=256".to_string()));
+
+        let config: BrowserPoolConfig = toml::from_str("max_contexts = 4\ndisable_images = false").unwrap();
+        assert_eq!(config.max_contexts, 4);
+        assert!(!config.chrome_args().iter().any(|a| a.contains("imagesEnabled")));
+    }
+}
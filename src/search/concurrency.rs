@@ -0,0 +1,152 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 引擎请求并发控制
+//!
+//! 每个搜索请求会同时请求所有引擎，流量突增时出站连接数成倍增长。
+//! [`FetchLimiter`] 用一个全局信号量限制所有请求合计的并发引擎请求数，
+//! 并为每个请求分配独立的信号量：请求先获取自己的许可再排进全局队列，
+//! 单个请求在全局队列中最多占 `per_request` 个位置，不会挤占其他请求。
+//! 全局信号量按先来先得分配许可。
+
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 引擎请求并发限制（在所有请求之间共享）
+#[derive(Debug, Clone)]
+pub struct FetchLimiter {
+    /// 全局许可（None 表示不限制）
+    global: Option<Arc<Semaphore>>,
+    /// 单个请求的并发上限（0 表示不限制）
+    per_request: usize,
+}
+
+/// 单个请求的并发许可
+#[derive(Debug, Clone)]
+pub struct RequestFetchLimiter {
+    global: Option<Arc<Semaphore>>,
+    local: Option<Arc<Semaphore>>,
+}
+
+/// 引擎请求许可，释放时归还全局和请求内的名额
+#[derive(Debug)]
+pub struct FetchPermit {
+    _local: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for FetchPermit {
+    fn drop(&mut self) {
+        metrics::gauge!("seesea_engine_fetches_in_flight").decrement(1.0);
+    }
+}
+
+impl FetchLimiter {
+    /// 创建并发限制
+    ///
+    /// * `max_concurrent` - 所有请求合计的最大并发引擎请求数（0 表示不限制）
+    /// * `per_request` - 单个请求的最大并发引擎请求数（0 表示不限制）
+    pub fn new(max_concurrent: usize, per_request: usize) -> Self {
+        Self {
+            global: (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent))),
+            per_request,
+        }
+    }
+
+    /// 当前可用的全局许可数（不限制时为 None）
+    pub fn available(&self) -> Option<usize> {
+        self.global.as_ref().map(|s| s.available_permits())
+    }
+
+    /// 为一个搜索请求创建许可分配器
+    pub fn for_request(&self) -> RequestFetchLimiter {
+        RequestFetchLimiter {
+            global: self.global.clone(),
+            local: (self.per_request > 0).then(|| Arc::new(Semaphore::new(self.per_request))),
+        }
+    }
+}
+
+impl RequestFetchLimiter {
+    /// 等待一个引擎请求许可，并记录排队时间
+    pub async fn acquire(&self, engine_name: &str) -> FetchPermit {
+        let started = Instant::now();
+        // 排队中的请求被取消时也要减少排队计数
+        let queued = QueuedGuard::enter();
+
+        // 信号量不会被关闭，acquire 只会成功
+        let local = match &self.local {
+            Some(local) => local.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        let global = match &self.global {
+            Some(global) => global.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        drop(queued);
+        metrics::gauge!("seesea_engine_fetches_in_flight").increment(1.0);
+        let waited_ms = started.elapsed().as_secs_f64() * 1000.0;
+        metrics::histogram!("seesea_engine_queue_wait_ms", "engine" => engine_name.to_string()).record(waited_ms);
+        if waited_ms >= 1000.0 {
+            tracing::debug!("Engine {} waited {:.0}ms for a fetch slot", engine_name, waited_ms);
+        }
+
+        FetchPermit { _local: local, _global: global }
+    }
+}
+
+/// 排队计数，离开作用域时减少
+struct QueuedGuard;
+
+impl QueuedGuard {
+    fn enter() -> Self {
+        metrics::gauge!("seesea_engine_fetches_queued").increment(1.0);
+        Self
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        metrics::gauge!("seesea_engine_fetches_queued").decrement(1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_global_and_per_request_limits() {
+        let limiter = FetchLimiter::new(3, 2);
+        let first = limiter.for_request();
+        let second = limiter.for_request();
+
+        let a = first.acquire("a").await;
+        let _b = first.acquire("b").await;
+        // 请求内上限为 2，第三个引擎需要等待
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(20), first.acquire("c")).await.is_err());
+        // 其他请求仍能拿到全局剩余的名额
+        let _c = second.acquire("c").await;
+        assert_eq!(limiter.available(), Some(0));
+
+        drop(a);
+        assert_eq!(limiter.available(), Some(1));
+        let _d = first.acquire("d").await;
+        assert_eq!(limiter.available(), Some(0));
+
+        assert_eq!(FetchLimiter::new(0, 0).available(), None);
+    }
+}
@@ -38,6 +38,8 @@ impl Default for EngineMode {
 pub struct EngineListConfig {
     /// 全局模式引擎列表
     pub global_engines: Vec<String>,
+    /// 中国模式引擎列表（可在中国大陆访问的引擎）
+    pub china_engines: Vec<String>,
     /// 所有可用引擎列表
     pub all_available_engines: Vec<String>,
 }
@@ -55,6 +57,10 @@ impl Default for EngineListConfig {
             "unsplash".to_string(),
             "bing_images".to_string(),
             "sogou_videos".to_string(),
+            "sogou_wechat".to_string(),
+            "local_files".to_string(),
+            "podcasts".to_string(),
+            "nominatim".to_string(),
         ];
 
         #[cfg(feature = "python")]
@@ -68,6 +74,10 @@ impl Default for EngineListConfig {
             "unsplash".to_string(),
             "bing_images".to_string(),
             "sogou_videos".to_string(),
+            "sogou_wechat".to_string(),
+            "local_files".to_string(),
+            "podcasts".to_string(),
+            "nominatim".to_string(),
             "xinhua".to_string(),
             // "quark".to_string(),  // Commented out: quark engine disabled
         ];
@@ -100,8 +110,40 @@ impl Default for EngineListConfig {
             // "quark".to_string(),  // Commented out: quark engine disabled
         ];
 
+        #[cfg(not(feature = "python"))]
+        let china_engines = vec![
+            "baidu".to_string(),
+            "so".to_string(),
+            "sogou".to_string(),
+            "bing".to_string(),
+            "bilibili".to_string(),
+            "bing_images".to_string(),
+            "sogou_videos".to_string(),
+        ];
+
+        #[cfg(feature = "python")]
+        let china_engines = vec![
+            "baidu".to_string(),
+            "so".to_string(),
+            "sogou".to_string(),
+            "bing".to_string(),
+            "bilibili".to_string(),
+            "bing_images".to_string(),
+            "sogou_videos".to_string(),
+            "xinhua".to_string(),
+        ];
+
+        // 种子引擎需显式选择，不进入默认引擎列表
+        #[cfg(feature = "torrent")]
+        let all_engines = {
+            let mut all_engines = all_engines;
+            all_engines.push("torznab".to_string());
+            all_engines
+        };
+
         Self {
             global_engines,
+            china_engines,
             all_available_engines: all_engines,
         }
     }
@@ -125,6 +167,17 @@ impl EngineListConfig {
         }
     }
 
+    /// 根据客户端所在地区获取默认引擎列表
+    ///
+    /// 中国大陆客户端使用中国模式引擎，其他客户端使用全局引擎
+    pub fn default_engines_for_region(&self, china: bool) -> Vec<String> {
+        if china {
+            self.china_engines.clone()
+        } else {
+            self.global_engines.clone()
+        }
+    }
+
     /// 验证引擎是否可用
     pub fn is_engine_available(&self, engine: &str) -> bool {
         self.all_available_engines.contains(&engine.to_string())
@@ -170,6 +223,30 @@ impl EngineListConfig {
             .cloned()
             .collect()
     }
+
+    /// 确定一次搜索实际使用的引擎：未指定时使用默认全局引擎，否则只保留可用的引擎
+    /// （包括已加载的 Python 引擎脚本）
+    pub fn resolve_engines(requested: &[String]) -> Vec<String> {
+        if requested.is_empty() {
+            return Self::get_default_engines();
+        }
+        let config = Self::default();
+        requested.iter()
+            .filter(|engine| config.is_engine_available(engine) || is_script_engine(engine))
+            .cloned()
+            .collect()
+    }
+}
+
+/// 是否为已加载的 Python 引擎脚本
+#[cfg(feature = "python")]
+fn is_script_engine(engine: &str) -> bool {
+    crate::python_bindings::py_engine_loader::get_engine_script(engine).is_some()
+}
+
+#[cfg(not(feature = "python"))]
+fn is_script_engine(_engine: &str) -> bool {
+    false
 }
 
 // 全局引擎配置实例
@@ -33,6 +33,24 @@ pub enum EngineMode {
     Global,
 }
 
+/// 连续失败多少次后打开引擎熔断器
+pub const ENGINE_CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+
+/// 引擎熔断器打开后多久进入半开状态、放行探测请求
+pub const ENGINE_CIRCUIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// 单个上游引擎的熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineCircuitState {
+    /// 关闭（正常请求）
+    Closed,
+    /// 打开（跳过该引擎）
+    Open,
+    /// 半开（冷却结束，放行请求探测是否恢复）
+    HalfOpen,
+}
+
 /// 引擎状态
 #[derive(Debug, Clone)]
 pub struct EngineState {
@@ -54,6 +72,8 @@ pub struct EngineState {
     pub failed_requests: u64,
     /// 平均响应时间（毫秒）
     pub avg_response_time_ms: u64,
+    /// 熔断器打开时间（关闭时为 None）
+    pub circuit_opened_at: Option<Instant>,
 }
 
 impl EngineState {
@@ -69,12 +89,22 @@ impl EngineState {
             successful_requests: 0,
             failed_requests: 0,
             avg_response_time_ms: 0,
+            circuit_opened_at: None,
+        }
+    }
+
+    /// 当前熔断器状态
+    pub fn circuit_state(&self) -> EngineCircuitState {
+        match self.circuit_opened_at {
+            None => EngineCircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < ENGINE_CIRCUIT_COOLDOWN => EngineCircuitState::Open,
+            Some(_) => EngineCircuitState::HalfOpen,
         }
     }
 
     /// 检查引擎是否可用
     pub fn is_available(&self) -> bool {
-        if !self.enabled {
+        if !self.enabled || self.circuit_state() == EngineCircuitState::Open {
             return false;
         }
         
@@ -100,6 +130,7 @@ impl EngineState {
         self.temporarily_disabled = false;
         self.disabled_until = None;
         self.consecutive_failures = 0;
+        self.circuit_opened_at = None;
     }
 
     /// 记录成功请求
@@ -107,6 +138,9 @@ impl EngineState {
         self.total_requests += 1;
         self.successful_requests += 1;
         self.consecutive_failures = 0;
+        if self.circuit_opened_at.take().is_some() {
+            tracing::info!("Engine '{}' circuit breaker closed", self.name);
+        }
         
         // 重新启用引擎（如果之前被禁用）
         if self.temporarily_disabled {
@@ -124,10 +158,23 @@ impl EngineState {
     }
 
     /// 记录失败请求
+    ///
+    /// 连续失败达到 [`ENGINE_CIRCUIT_FAILURE_THRESHOLD`] 次或半开探测失败时打开熔断器
     pub fn record_failure(&mut self) {
         self.total_requests += 1;
         self.failed_requests += 1;
         self.consecutive_failures += 1;
+
+        let reopen = self.circuit_state() == EngineCircuitState::HalfOpen;
+        if reopen || (self.circuit_opened_at.is_none()
+            && self.consecutive_failures >= ENGINE_CIRCUIT_FAILURE_THRESHOLD)
+        {
+            self.circuit_opened_at = Some(Instant::now());
+            tracing::warn!(
+                "Engine '{}' circuit breaker opened after {} consecutive failures",
+                self.name, self.consecutive_failures
+            );
+        }
     }
     
     /// 记录零结果请求并应用指数退避禁用
@@ -157,6 +204,37 @@ impl EngineState {
     }
 }
 
+/// 引擎错误记录
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EngineErrorRecord {
+    /// 引擎名称
+    pub engine: String,
+    /// 错误信息
+    pub message: String,
+    /// 发生时间
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// 引擎脚本加载失败记录
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EngineLoadError {
+    /// 脚本文件名
+    pub file: String,
+    /// 错误信息
+    pub error: String,
+}
+
+/// 重新扫描引擎脚本目录的结果
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct EngineReloadReport {
+    /// 已加载的引擎名
+    pub loaded: Vec<String>,
+    /// 脚本被删除而移除的引擎名
+    pub removed: Vec<String>,
+    /// 加载失败的脚本
+    pub errors: Vec<EngineLoadError>,
+}
+
 /// 搜索引擎管理器
 pub struct EngineManager {
     /// 运行模式
@@ -172,7 +250,7 @@ pub struct EngineManager {
     /// 连续失败阈值
     failure_threshold: u32,
     /// 共享的 HTTP 客户端（用于优化性能）
-    shared_client: Option<Arc<crate::net::client::HttpClient>>,
+    shared_client: Option<Arc<dyn crate::net::client::HttpTransport>>,
 }
 
 impl EngineManager {
@@ -211,7 +289,7 @@ impl EngineManager {
     ///
     /// * `mode` - 运行模式
     /// * `configured_engines` - 配置的引擎列表
-    /// * `shared_client` - 共享的 HTTP 客户端
+    /// * `shared_client` - 共享的 HTTP 客户端（任意 [`HttpTransport`](crate::net::client::HttpTransport) 实现）
     ///
     /// # 返回
     ///
@@ -219,7 +297,7 @@ impl EngineManager {
     pub fn with_shared_client(
         mode: EngineMode,
         configured_engines: Vec<String>,
-        shared_client: Arc<crate::net::client::HttpClient>,
+        shared_client: Arc<dyn crate::net::client::HttpTransport>,
     ) -> Self {
         let mut manager = Self {
             mode,
@@ -238,8 +316,8 @@ impl EngineManager {
     /// 初始化所有引擎
     fn initialize_engines(&mut self) {
         // 总是使用共享客户端创建引擎（性能最优）
-        let client = Arc::clone(self.shared_client.as_ref()
-            .expect("Shared client must be initialized"));
+        let client = self.shared_client.clone()
+            .expect("Shared client must be initialized");
 
         // 基础搜索引擎 (保留 Yandex, Bing, Baidu, 360 Search)
         self.register_engine("bing", Box::new(BingEngine::with_client(Arc::clone(&client))));
@@ -253,6 +331,7 @@ impl EngineManager {
         // 搜狗变体
         self.register_engine("sogou", Box::new(SogouEngine::with_client(Arc::clone(&client))));
         self.register_engine("sogou videos", Box::new(SogouVideosEngine::with_client(Arc::clone(&client))));
+        self.register_engine("sogou wechat", Box::new(SogouWechatEngine::with_client(Arc::clone(&client))));
 
         // Bilibili 引擎
         self.register_engine("bilibili", Box::new(BilibiliEngine::with_client(Arc::clone(&client))));
@@ -514,6 +593,33 @@ mod tests {
         assert_eq!(state.consecutive_failures, 1);
     }
 
+    #[test]
+    fn test_engine_circuit_breaker() {
+        let mut state = EngineState::new("test".to_string());
+        for _ in 0..ENGINE_CIRCUIT_FAILURE_THRESHOLD - 1 {
+            state.record_failure();
+        }
+        assert_eq!(state.circuit_state(), EngineCircuitState::Closed);
+        assert!(state.is_available());
+
+        state.record_failure();
+        assert_eq!(state.circuit_state(), EngineCircuitState::Open);
+        assert!(!state.is_available());
+
+        // 冷却结束后半开放行，探测失败重新打开
+        state.circuit_opened_at = Some(Instant::now() - ENGINE_CIRCUIT_COOLDOWN);
+        assert_eq!(state.circuit_state(), EngineCircuitState::HalfOpen);
+        assert!(state.is_available());
+        state.record_failure();
+        assert_eq!(state.circuit_state(), EngineCircuitState::Open);
+
+        // 探测成功则关闭
+        state.circuit_opened_at = Some(Instant::now() - ENGINE_CIRCUIT_COOLDOWN);
+        state.record_success(10);
+        assert_eq!(state.circuit_state(), EngineCircuitState::Closed);
+        assert_eq!(state.consecutive_failures, 0);
+    }
+
     #[tokio::test]
     async fn test_engine_manager_creation() {
         let manager = EngineManager::new(
@@ -522,7 +628,7 @@ mod tests {
         );
 
         assert_eq!(manager.get_mode(), EngineMode::Global);
-        assert_eq!(manager.engines.len(), 9); // 所有9个引擎都应该注册 (Yandex, Bing*2, Baidu, Sogou*2, Bilibili, Unsplash, 360 Search)
+        assert_eq!(manager.engines.len(), 10); // 所有10个引擎都应该注册 (Yandex, Bing*2, Baidu, Sogou*3, Bilibili, Unsplash, 360 Search)
     }
 
     #[tokio::test]
@@ -547,6 +653,6 @@ mod tests {
         );
         
         let active = manager.get_active_engines().await;
-        assert_eq!(active.len(), 9); // 所有9个引擎都应该可用
+        assert_eq!(active.len(), 10); // 所有10个引擎都应该可用
     }
 }
@@ -65,9 +65,31 @@ use crate::derive::{
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, TimeRange, AboutInfo, RequestResponseEngine, RequestParams,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::is_http_url;
 use super::utils::build_query_string_owned;
+use once_cell::sync::Lazy;
+use scraper::Selector;
+
+/// 结果页解析用到的 CSS 选择器（进程内只编译一次）
+struct BaiduSelectors {
+    result: Selector,
+    title: Selector,
+    content: Selector,
+    source: Selector,
+}
+
+static SELECTORS: Lazy<BaiduSelectors> = Lazy::new(|| BaiduSelectors {
+    result: Selector::parse("#content_left div.result, #content_left div.result-op").expect("valid selector"),
+    title: Selector::parse("h3 a").expect("valid selector"),
+    content: Selector::parse(
+        ".c-abstract, [class*=\"content-right\"], [class*=\"c-span-last\"] .c-color-text, .c-span-last",
+    )
+    .expect("valid selector"),
+    source: Selector::parse(".c-showurl, [class*=\"site-name\"], .c-color-gray").expect("valid selector"),
+});
 
 /// Baidu 搜索引擎
 ///
@@ -76,7 +98,7 @@ pub struct BaiduEngine {
     /// 引擎信息
     info: EngineInfo,
     /// HTTP 客户端
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl BaiduEngine {
@@ -95,7 +117,7 @@ impl BaiduEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Baidu".to_string(),
@@ -113,6 +135,7 @@ impl BaiduEngine {
                     supports_time_range: true,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(60),
                 },
@@ -148,9 +171,9 @@ impl BaiduEngine {
     /// # 返回
     ///
     /// 时间范围的秒数
-    #[allow(dead_code)]
     fn time_range_to_seconds(time_range: TimeRange) -> u64 {
         match time_range {
+            TimeRange::Hour => 3600,      // 1 小时
             TimeRange::Day => 86400,      // 1 天
             TimeRange::Week => 604800,    // 7 天
             TimeRange::Month => 2592000,  // 30 天
@@ -181,7 +204,11 @@ impl BaiduEngine {
         }
 
         // 检查是否收到了HTML/CAPTCHA而不是JSON
+        // JSON 接口被屏蔽时常返回普通结果页，此时降级为解析 HTML
         let trimmed = json_str.trim();
+        if Self::looks_like_serp(trimmed) {
+            return Self::parse_html_results(trimmed);
+        }
         if trimmed.starts_with('<') ||
            trimmed.starts_with("Found") ||
            trimmed.contains("wappass.baidu.com") ||
@@ -195,7 +222,7 @@ impl BaiduEngine {
             Ok(json) => json,
             Err(e) => {
                 return Err(format!("Baidu JSON解析失败: {}。响应内容前100字符: {}",
-                    e, &json_str[..json_str.len().min(100)]).into());
+                    e, json_str.chars().take(100).collect::<String>()).into());
             }
         };
         let mut items = Vec::new();
@@ -221,7 +248,7 @@ impl BaiduEngine {
                         .unwrap_or("")
                         .to_string();
                     
-                    if !title.is_empty() && !url.is_empty() && url.starts_with("http") {
+                    if !title.is_empty() && is_http_url(&url) {
                         items.push(SearchResultItem {
                             title,
                             url: url.clone(),
@@ -258,7 +285,7 @@ impl BaiduEngine {
                     .unwrap_or("")
                     .to_string();
                 
-                if !title.is_empty() && !url.is_empty() && url.starts_with("http") {
+                if !title.is_empty() && is_http_url(&url) {
                     items.push(SearchResultItem {
                         title,
                         url: url.clone(),
@@ -279,6 +306,72 @@ impl BaiduEngine {
         Ok(items)
     }
 
+    /// 判断响应是否为正常的 HTML 结果页（而非验证码页）
+    fn looks_like_serp(body: &str) -> bool {
+        body.starts_with('<')
+            && body.contains("id=\"content_left\"")
+            && !body.contains("wappass.baidu.com")
+            && !body.contains("安全验证")
+    }
+
+    /// 解析 HTML 结果页
+    ///
+    /// # 参数
+    ///
+    /// * `html` - 结果页 HTML
+    ///
+    /// # 返回
+    ///
+    /// 解析出的搜索结果项列表；`div.result` 块中没有标题链接的（如相关搜索）会被跳过
+    fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        use scraper::Html;
+
+        let document = Html::parse_document(html);
+        let selectors = &*SELECTORS;
+        let mut items = Vec::with_capacity(10);
+        for result in document.select(&selectors.result) {
+            let Some(title_elem) = result.select(&selectors.title).next() else {
+                continue;
+            };
+            let title = title_elem.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ");
+
+            // `mu` 属性是真实地址，href 通常是 www.baidu.com/link?url= 跳转链接
+            let url = result.value().attr("mu")
+                .filter(|mu| is_http_url(mu))
+                .or_else(|| title_elem.value().attr("href"))
+                .unwrap_or("")
+                .to_string();
+
+            if title.is_empty() || !is_http_url(&url) {
+                continue;
+            }
+
+            let content = result.select(&selectors.content).next()
+                .map(|c| c.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default();
+
+            let site_name = result.select(&selectors.source).next()
+                .map(|s| s.text().collect::<String>().trim().to_string())
+                .filter(|s| !s.is_empty());
+
+            items.push(SearchResultItem {
+                title,
+                url: url.clone(),
+                content,
+                display_url: Some(url),
+                site_name,
+                score: 1.0,
+                result_type: ResultType::Web,
+                thumbnail: None,
+                published_date: None,
+                template: None,
+                metadata: HashMap::new(),
+            });
+        }
+
+        Ok(items)
+    }
+
     /// 检测是否遇到 Baidu CAPTCHA
     ///
     /// # 参数
@@ -325,6 +418,10 @@ impl SearchEngine for BaiduEngine {
 impl RequestResponseEngine for BaiduEngine {
     type Response = (String, Option<String>); // (JSON 字符串, Location 头)
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     /// 准备请求参数
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         let results_per_page = 10;
@@ -339,14 +436,8 @@ impl RequestResponseEngine for BaiduEngine {
         ];
         
         // 添加时间范围过滤
-        if let Some(ref time_range) = params.time_range {
-            let seconds = match time_range.as_str() {
-                "day" => 86400,
-                "week" => 604800,
-                "month" => 2592000,
-                "year" => 31536000,
-                _ => 0,
-            };
+        if let Some(time_range) = params.time_range {
+            let seconds = Self::time_range_to_seconds(time_range);
             
             if seconds > 0 {
                 let now = std::time::SystemTime::now()
@@ -372,17 +463,8 @@ impl RequestResponseEngine for BaiduEngine {
         let url = params.url.as_ref()
             .ok_or("请求 URL 未设置")?;
 
-        // 创建请求选项
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        // 添加自定义头
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
         // 发送请求
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
         // 检查状态码
@@ -404,7 +486,7 @@ impl RequestResponseEngine for BaiduEngine {
         }
 
         // 获取响应文本
-        let text = response.text().await
+        let text = response_text(response).await
             .map_err(|e| format!("Failed to read response: {}", e))?;
 
         Ok((text, location))
@@ -507,6 +589,29 @@ mod tests {
         assert_eq!(result.expect("Valid result expected").len(), 0);
     }
 
+    #[test]
+    fn test_html_fallback() {
+        let serp = r#"<!DOCTYPE html><html><body><div id="content_left">
+            <div class="result c-container" mu="https://www.rust-lang.org/" id="1">
+              <h3 class="t"><a href="http://www.baidu.com/link?url=abc">Rust 程序设计语言</a></h3>
+              <div class="c-abstract">一门赋予每个人构建可靠且高效软件能力的语言。</div>
+            </div>
+            <div class="result-op c-container" id="2"><div class="c-title">相关搜索</div></div>
+            <div class="result c-container" id="3">
+              <h3 class="t"><a href="http://www.baidu.com/link?url=def">Rust 语言圣经</a></h3>
+            </div>
+        </div></body></html>"#;
+        let items = BaiduEngine::parse_json_results(serp).expect("SERP should be parsed as HTML");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].url, "https://www.rust-lang.org/");
+        assert_eq!(items[0].content, "一门赋予每个人构建可靠且高效软件能力的语言。");
+        assert_eq!(items[1].url, "http://www.baidu.com/link?url=def");
+
+        // 验证码页仍然报错
+        let captcha = r#"<html><head><title>百度安全验证</title></head><body><div id="content_left"></div></body></html>"#;
+        assert!(BaiduEngine::parse_json_results(captcha).is_err());
+    }
+
     #[test]
     fn test_parse_invalid_json() {
         let result = BaiduEngine::parse_json_results("{}");
@@ -16,20 +16,44 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::error::Error;
+use std::time::{Duration, Instant};
 use rand::Rng;
+use tokio::sync::RwLock;
 
 use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType,
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    VideoMetadata, parse_duration, parse_view_count,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::resolve_url;
 use super::utils::build_query_string_owned;
 
+/// WBI 签名密钥的重排表
+const MIXIN_KEY_ENC_TAB: [usize; 64] = [
+    46, 47, 18, 2, 53, 8, 23, 32, 15, 50, 10, 31, 58, 3, 45, 35, 27, 43, 5, 49, 33, 9, 42, 19, 29, 28, 14, 39, 12,
+    38, 41, 13, 37, 48, 7, 16, 24, 55, 40, 61, 26, 17, 0, 1, 60, 51, 30, 4, 22, 25, 54, 21, 56, 59, 6, 63, 57, 62,
+    11, 36, 20, 34, 44, 52,
+];
+
+/// WBI 密钥每天轮换，缓存超过该时长后重新获取
+const WBI_KEY_TTL: Duration = Duration::from_secs(3600);
+
+/// 缓存的 WBI 密钥和预热 Cookie
+#[derive(Clone)]
+struct WbiState {
+    mixin_key: String,
+    cookies: HashMap<String, String>,
+    fetched_at: Instant,
+}
+
 pub struct BilibiliEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
+    wbi: RwLock<Option<WbiState>>,
 }
 
 impl BilibiliEngine {
@@ -39,7 +63,7 @@ impl BilibiliEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Bilibili".to_string(),
@@ -55,6 +79,7 @@ impl BilibiliEngine {
                     supports_time_range: false,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(30),
                 },
@@ -78,7 +103,100 @@ impl BilibiliEngine {
                 max_page: 10,
             },
             client,
+            wbi: RwLock::new(None),
+        }
+    }
+
+    /// 获取 WBI 状态，过期或 `force_refresh` 时重新获取
+    ///
+    /// 获取失败时返回 `None`，请求以未签名的方式发出
+    async fn wbi_state(&self, force_refresh: bool) -> Option<WbiState> {
+        if !force_refresh
+            && let Some(state) = self.wbi.read().await.as_ref()
+            && state.fetched_at.elapsed() < WBI_KEY_TTL
+        {
+            return Some(state.clone());
+        }
+
+        let mut guard = self.wbi.write().await;
+        // 其他请求可能已经刷新过
+        if !force_refresh
+            && let Some(state) = guard.as_ref()
+            && state.fetched_at.elapsed() < WBI_KEY_TTL
+        {
+            return Some(state.clone());
         }
+
+        match self.fetch_wbi_state().await {
+            Ok(state) => {
+                *guard = Some(state.clone());
+                Some(state)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to fetch Bilibili WBI keys: {}", e);
+                *guard = None;
+                None
+            }
+        }
+    }
+
+    async fn fetch_wbi_state(&self) -> Result<WbiState, Box<dyn Error + Send + Sync>> {
+        let nav: serde_json::Value = serde_json::from_str(
+            &self.client.get("https://api.bilibili.com/x/web-interface/nav", None).await.map_err(|e| e.to_string())?.text().await?,
+        )?;
+        // 未登录时 code 为 -101，但 wbi_img 仍然返回
+        let wbi_img = nav.pointer("/data/wbi_img").ok_or("nav response has no wbi_img")?;
+        let key_of = |field: &str| {
+            wbi_img.get(field)
+                .and_then(|v| v.as_str())
+                .and_then(|url| url.rsplit('/').next())
+                .and_then(|file| file.split('.').next())
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| format!("nav response has no {}", field))
+        };
+        let mixin_key = get_mixin_key(&key_of("img_url")?, &key_of("sub_url")?);
+
+        // 用官方下发的 buvid 预热 Cookie，失败时沿用随机生成的值
+        let mut cookies = Self::generate_bilibili_cookies();
+        match self.fetch_buvid().await {
+            Ok(buvid) => cookies.extend(buvid),
+            Err(e) => tracing::debug!("Failed to fetch Bilibili buvid: {}", e),
+        }
+
+        Ok(WbiState { mixin_key, cookies, fetched_at: Instant::now() })
+    }
+
+    async fn fetch_buvid(&self) -> Result<HashMap<String, String>, Box<dyn Error + Send + Sync>> {
+        let spi: serde_json::Value = serde_json::from_str(
+            &self.client.get("https://api.bilibili.com/x/frontend/finger/spi", None).await.map_err(|e| e.to_string())?.text().await?,
+        )?;
+        let mut cookies = HashMap::new();
+        for (field, cookie) in [("b_3", "buvid3"), ("b_4", "buvid4")] {
+            if let Some(value) = spi.pointer(&format!("/data/{}", field)).and_then(|v| v.as_str()) {
+                cookies.insert(cookie.to_string(), value.to_string());
+            }
+        }
+        if cookies.is_empty() {
+            return Err("spi response has no buvid".into());
+        }
+        Ok(cookies)
+    }
+
+    async fn send(&self, url: &str, params: &RequestParams, wbi: Option<&WbiState>) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let mut params = params.clone();
+        let url = match wbi {
+            Some(state) => {
+                params.cookies.extend(state.cookies.clone());
+                sign_wbi_url(url, &state.mixin_key, chrono::Utc::now().timestamp())
+            }
+            None => url.to_string(),
+        };
+
+        let response = self.client.get(&url, Some(params.request_options())).await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn parse_json_results(json_str: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
@@ -115,15 +233,7 @@ impl BilibiliEngine {
 
                         let thumbnail = item.get("pic")
                             .and_then(|v| v.as_str())
-                            .map(|s| {
-                                if s.starts_with("//") {
-                                    format!("https:{}", s)
-                                } else if !s.starts_with("http") {
-                                    format!("https:{}", s)
-                                } else {
-                                    s.to_string()
-                                }
-                            });
+                            .and_then(|s| resolve_url(None, s));
 
                         let content = item.get("description")
                             .and_then(|v| v.as_str())
@@ -148,17 +258,26 @@ impl BilibiliEngine {
 
                         let iframe_url = format!("https://player.bilibili.com/player.html?aid={}&high_quality=1&autoplay=false&danmaku=0", video_id);
 
+                        // play 偶尔是字符串（如 "--"），按文本兜底解析
+                        let view_count = item.get("play").and_then(|v| {
+                            v.as_u64().or_else(|| v.as_str().and_then(parse_view_count))
+                        });
+
                         let mut metadata = HashMap::new();
-                        metadata.insert("author".to_string(), author.to_string());
-                        metadata.insert("length".to_string(), duration_str.to_string());
-                        metadata.insert("iframe_src".to_string(), iframe_url);
 
                         // 添加keywords到metadata
                         if !keywords.is_empty() {
                             metadata.insert("keywords".to_string(), keywords.join(","));
                         }
 
-                        items.push(SearchResultItem {
+                        let video = VideoMetadata {
+                            duration_secs: parse_duration(duration_str),
+                            view_count,
+                            author: Some(author.to_string()).filter(|a| !a.is_empty()),
+                            embed_url: Some(iframe_url),
+                        };
+
+                        let mut result_item = SearchResultItem {
                             title,
                             url: url.clone(),
                             content,
@@ -170,7 +289,9 @@ impl BilibiliEngine {
                             published_date,
                             template: Some("videos.html".to_string()),
                             metadata,
-                        });
+                        };
+                        result_item.set_video(&video);
+                        items.push(result_item);
                     }
                 }
             }
@@ -226,9 +347,13 @@ impl SearchEngine for BilibiliEngine {
 impl RequestResponseEngine for BilibiliEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
       
-        let base_url = "https://api.bilibili.com/x/web-interface/search/type";
+        let base_url = "https://api.bilibili.com/x/web-interface/wbi/search/type";
 
         let query_params = vec![
             ("__refresh__", "true".to_string()),
@@ -254,22 +379,20 @@ impl RequestResponseEngine for BilibiliEngine {
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
 
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
+        let wbi = self.wbi_state(false).await;
+        let body = self.send(url, params, wbi.as_ref()).await?;
+        if !is_risk_control(&body) {
+            return Ok(body);
         }
 
-        // Add cookies
-        for (key, value) in &params.cookies {
-            options.headers.push(("Cookie".to_string(), format!("{}={}", key, value)));
+        // 密钥轮换或 Cookie 失效时触发风控，刷新后重试一次
+        tracing::debug!("Bilibili risk control triggered, refreshing WBI keys");
+        let wbi = self.wbi_state(true).await;
+        let body = self.send(url, params, wbi.as_ref()).await?;
+        if is_risk_control(&body) {
+            return Err("Bilibili rejected the request (risk control -412)".into());
         }
-
-        let response = self.client.get(url, Some(options)).await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        Ok(body)
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
@@ -277,6 +400,93 @@ impl RequestResponseEngine for BilibiliEngine {
     }
 }
 
+/// 响应是否为风控拦截（-412 请求被拦截，-352 校验失败）
+fn is_risk_control(body: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| json.get("code").and_then(|c| c.as_i64()))
+        .is_some_and(|code| code == -412 || code == -352)
+}
+
+/// 由 img_key 和 sub_key 生成 mixin_key
+fn get_mixin_key(img_key: &str, sub_key: &str) -> String {
+    let raw: Vec<char> = format!("{}{}", img_key, sub_key).chars().collect();
+    MIXIN_KEY_ENC_TAB
+        .iter()
+        .filter_map(|&i| raw.get(i))
+        .take(32)
+        .collect()
+}
+
+/// 为 URL 添加 WBI 签名参数 `wts` 和 `w_rid`
+///
+/// 参数按键名排序，值中去掉 `!'()*`，拼接 mixin_key 后取 MD5
+fn sign_wbi_url(url: &str, mixin_key: &str, wts: i64) -> String {
+    let (base, query) = url.split_once('?').unwrap_or((url, ""));
+    let mut pairs: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes())
+        .filter(|(key, _)| key != "wts" && key != "w_rid")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    pairs.push(("wts".to_string(), wts.to_string()));
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let query = pairs
+        .iter()
+        .map(|(key, value)| {
+            let value: String = value.chars().filter(|c| !"!'()*".contains(*c)).collect();
+            format!("{}={}", urlencoding::encode(key), urlencoding::encode(&value))
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    let w_rid = md5_hex(format!("{}{}", query, mixin_key).as_bytes());
+    format!("{}?{}&w_rid={}", base, query, w_rid)
+}
+
+/// MD5 摘要（十六进制），仅用于 WBI 签名
+fn md5_hex(input: &[u8]) -> String {
+    const S: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+        14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15,
+        21, 6, 10, 15, 21,
+    ];
+    let k: Vec<u32> = (0..64).map(|i| ((i as f64 + 1.0).sin().abs() * 4294967296.0) as u32).collect();
+
+    let mut message = input.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((input.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+    for chunk in message.chunks(64) {
+        let m: Vec<u32> = chunk
+            .chunks(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a.wrapping_add(f).wrapping_add(k[i]).wrapping_add(m[g]).rotate_left(S[i]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+    }
+
+    state.iter().flat_map(|word| word.to_le_bytes()).map(|byte| format!("{:02x}", byte)).collect()
+}
+
 // Helper function to strip HTML entities
 fn strip_html_entities(text: &str) -> String {
     // Basic HTML entity stripping - this is simplified
@@ -318,4 +528,57 @@ fn extract_keywords_and_clean_html(html: &str) -> (String, Vec<String>) {
     cleaned_html = cleaned_html.split_whitespace().collect::<Vec<_>>().join(" ");
 
     (cleaned_html, keywords)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    const NAV: &str = r#"{"code":-101,"data":{"isLogin":false,"wbi_img":{"img_url":"https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png","sub_url":"https://i0.hdslb.com/bfs/wbi/4932caff0ff746eab6f01bf08b70ac45.png"}}}"#;
+
+    #[test]
+    fn test_md5_hex() {
+        assert_eq!(md5_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(md5_hex(b"The quick brown fox jumps over the lazy dog"), "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(md5_hex("哔哩哔哩".repeat(10).as_bytes()).len(), 32);
+    }
+
+    #[test]
+    fn test_wbi_signature() {
+        let mixin_key = get_mixin_key("7cd084941338484aae1ad9425b84077c", "4932caff0ff746eab6f01bf08b70ac45");
+        assert_eq!(mixin_key, "ea1db124af3c7062474693fa704f4ff8");
+
+        let signed = sign_wbi_url("https://api.bilibili.com/x?foo=114&bar=514&zab=1919810", &mixin_key, 1702204169);
+        assert_eq!(
+            signed,
+            "https://api.bilibili.com/x?bar=514&foo=114&wts=1702204169&zab=1919810&w_rid=8f6f2b5b3d485fe1886cec6a0be8c5d4"
+        );
+
+        // 值中的 !'()* 被去掉，空格编码为 %20
+        let signed = sign_wbi_url("https://api.bilibili.com/x?keyword=rust%20(lang)!", &mixin_key, 1);
+        assert!(signed.contains("keyword=rust%20lang&wts=1&w_rid="));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_signs_and_refreshes_on_risk_control() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .respond("/x/web-interface/nav", MockResponse::ok(NAV))
+                .respond("/x/frontend/finger/spi", MockResponse::ok(r#"{"code":0,"data":{"b_3":"B3-infoc","b_4":"B4-infoc"}}"#))
+                .respond("search/type", MockResponse::ok(r#"{"code":-412,"message":"request was banned"}"#)),
+        );
+        let engine = BilibiliEngine::with_client(transport.clone());
+
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+        let err = SearchEngine::search(&engine, &query).await.unwrap_err();
+        assert!(err.to_string().contains("-412"));
+
+        let requests = transport.requests();
+        let searches: Vec<_> = requests.iter().filter(|r| r.url.contains("search/type")).collect();
+        // 首次请求被拦截后刷新密钥并重试一次
+        assert_eq!(searches.len(), 2);
+        assert_eq!(requests.iter().filter(|r| r.url.contains("/nav")).count(), 2);
+        assert!(searches[0].url.contains("wts=") && searches[0].url.contains("&w_rid="));
+        assert!(searches[0].headers.iter().any(|(name, value)| name == "Cookie" && value.split("; ").any(|c| c == "buvid3=B3-infoc")));
+    }
+}
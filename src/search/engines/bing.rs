@@ -67,9 +67,53 @@ use crate::derive::{
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, TimeRange, AboutInfo, RequestResponseEngine, RequestParams,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
-use super::utils::build_query_string_owned;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::is_http_url;
+use super::utils::{build_query_string_owned, element_text, selector};
+
+/// Bing 支持的市场（`mkt`，语言-国家）
+///
+/// 同一国家有多个市场时，排在前面的作为该国家的默认市场
+pub(crate) const BING_MARKETS: &[&str] = &[
+    "en-US", "es-US", "en-GB", "en-AU", "en-CA", "fr-CA", "en-IN", "en-ID", "en-MY",
+    "en-NZ", "en-PH", "en-ZA", "zh-CN", "zh-HK", "zh-TW", "ja-JP", "ko-KR", "de-DE",
+    "de-AT", "de-CH", "fr-FR", "fr-BE", "fr-CH", "nl-NL", "nl-BE", "it-IT", "es-ES",
+    "es-AR", "es-CL", "es-MX", "pt-BR", "da-DK", "fi-FI", "no-NO", "pl-PL", "ru-RU",
+    "sv-SE", "tr-TR",
+];
+
+/// Bing 支持地区的国家代码列表
+pub(crate) fn bing_supported_regions() -> Vec<String> {
+    let mut regions: Vec<String> = BING_MARKETS
+        .iter()
+        .filter_map(|m| m.split('-').nth(1))
+        .map(str::to_string)
+        .collect();
+    regions.sort();
+    regions.dedup();
+    regions
+}
+
+/// 根据地区和语言选择 Bing 市场
+///
+/// 地区为 `语言-国家` 且恰好是 Bing 市场时直接使用；否则优先选择与语言匹配的
+/// 该国家市场，再退回该国家的默认市场。地区无法识别时返回 None
+pub(crate) fn bing_market(region: &str, language: Option<&str>) -> Option<&'static str> {
+    let country = crate::derive::region_country(region)?;
+    let candidates = || BING_MARKETS.iter().filter(|m| m.ends_with(country.as_str()));
+    let language = region
+        .split(['-', '_'])
+        .next()
+        .filter(|l| l.len() == 2 && !l.eq_ignore_ascii_case(&country))
+        .or_else(|| language.and_then(|l| l.split(['-', '_']).next()));
+
+    language
+        .and_then(|lang| candidates().find(|m| m[..2].eq_ignore_ascii_case(lang)))
+        .or_else(|| candidates().next())
+        .copied()
+}
 
 /// Bing 搜索引擎
 ///
@@ -78,7 +122,7 @@ pub struct BingEngine {
     /// 引擎信息
     info: EngineInfo,
     /// HTTP 客户端（共享）
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl BingEngine {
@@ -117,7 +161,7 @@ impl BingEngine {
     /// let client = Arc::new(HttpClient::new(NetworkConfig::default()).unwrap());
     /// let engine = BingEngine::with_client(client);
     /// ```
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Bing".to_string(),
@@ -137,6 +181,7 @@ impl BingEngine {
                     supports_time_range: true,
                     supports_language_filter: true,
                     supports_region_filter: true,
+                    supported_regions: bing_supported_regions(),
                     supports_safe_search: true,
                     rate_limit: Some(60), // 每分钟 60 次请求
                 },
@@ -207,7 +252,6 @@ impl BingEngine {
     /// # 返回
     ///
     /// Bing API 的时间过滤字符串
-    #[allow(dead_code)]
     fn time_range_to_bing(time_range: TimeRange) -> &'static str {
         match time_range {
             TimeRange::Day => "1",
@@ -291,7 +335,7 @@ impl BingEngine {
     ///
     /// 如果 HTML 解析失败返回错误
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
+        use scraper::Html;
         
         // 检查是否有结果
         if html.contains("There are no results") || html.is_empty() {
@@ -301,47 +345,37 @@ impl BingEngine {
         let document = Html::parse_document(html);
         let mut items = Vec::new();
         
-        let results_selector = match Selector::parse("ol#b_results > li.b_algo") {
-            Ok(sel) => sel,
-            Err(_) => return Ok(Vec::new()),
-        };
-        
-        for result in document.select(&results_selector) {
+        for result in document.select(selector!("ol#b_results > li.b_algo")) {
             // 提取链接和标题 (h2/a)
-            let link_selector = Selector::parse("h2 > a").expect("valid selector");
-            let link_elem = match result.select(&link_selector).next() {
+            let link_elem = match result.select(selector!("h2 > a")).next() {
                 Some(elem) => elem,
                 None => continue,
             };
             
-            let title = link_elem.text().collect::<String>().trim().to_string();
-            let mut url = link_elem.value().attr("href").unwrap_or("").to_string();
-            
+            let title = element_text(link_elem);
             // 解码 base64 编码的 URL
-            url = Self::decode_bing_url(&url);
+            let url = Self::decode_bing_url(link_elem.value().attr("href").unwrap_or(""));
             
             // 提取内容 (p)
-            let content_selector = Selector::parse("p").expect("valid selector");
             let mut content = String::new();
             
-            for p_elem in result.select(&content_selector) {
+            for p_elem in result.select(selector!("p")) {
                 let text = p_elem.text()
                     .filter(|t| !t.trim().is_empty())
                     .collect::<Vec<_>>()
-                    .join(" ")
-                    .trim()
-                    .to_string();
+                    .join(" ");
+                let text = text.trim();
                 
                 if !text.is_empty() && text != "Web" {
-                    content = text;
+                    content = text.to_string();
                     break;
                 }
             }
             
             // 只添加有效结果
-            if !title.is_empty() && !url.is_empty() && url.starts_with("http") {
+            if !title.is_empty() && is_http_url(&url) {
                 items.push(SearchResultItem {
-                    title,
+                    title: title.into_owned(),
                     url: url.clone(),
                     content,
                     display_url: Some(url),
@@ -398,6 +432,10 @@ impl SearchEngine for BingEngine {
 impl RequestResponseEngine for BingEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     /// 准备请求参数
     ///
     /// # 参数
@@ -409,10 +447,16 @@ impl RequestResponseEngine for BingEngine {
     ///
     /// 成功返回 Ok(())，失败返回错误
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
-        // Configure language and region
-        let language = params.language.as_deref().unwrap_or("en").to_string();
-        let region = params.language.as_deref().unwrap_or("us").to_string();
-        
+        // 语言和市场分开处理：语言只影响界面语言，市场由地区决定
+        let language = params.language.as_deref()
+            .and_then(|l| l.split(['-', '_']).next())
+            .unwrap_or("en")
+            .to_lowercase();
+        let region = params.region.as_deref()
+            .and_then(|r| bing_market(r, Some(&language)))
+            .unwrap_or("en-US")
+            .to_string();
+
         Self::set_bing_cookies(params, &language, &region);
         
         // Build query parameters
@@ -436,17 +480,11 @@ impl RequestResponseEngine for BingEngine {
         
         let mut url = format!("{}?{}", base_url, query_string);
         
-        // Append time range filter if specified (not URL-encoded as it's appended directly)
-        if let Some(ref time_range) = params.time_range {
-            let tr = match time_range.as_str() {
-                "day" => "1",
-                "week" => "2",
-                "month" => "3",
-                "year" => "4",
-                _ => "",
-            };
+        // Append time range filter if specified (quotes percent-encoded as it's appended directly)
+        if let Some(time_range) = params.time_range {
+            let tr = Self::time_range_to_bing(time_range);
             if !tr.is_empty() {
-                url.push_str(&format!("&filters=ex1:\"ez{}\"", tr));
+                url.push_str(&format!("&filters=ex1:%22ez{}%22", tr));
             }
         }
         
@@ -469,22 +507,8 @@ impl RequestResponseEngine for BingEngine {
         let url = params.url.as_ref()
             .ok_or("请求 URL 未设置")?;
 
-        // 创建请求选项
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        // 添加自定义头
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
-        // 添加 cookies
-        for (key, value) in &params.cookies {
-            options.headers.push(("Cookie".to_string(), format!("{}={}", key, value)));
-        }
-
         // 发送请求
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
         // 检查状态码
@@ -498,7 +522,7 @@ impl RequestResponseEngine for BingEngine {
         }
 
         // 获取响应文本
-        let text = response.text().await
+        let text = response_text(response).await
             .map_err(|e| format!("Failed to read response: {}", e))?;
 
         Ok(text)
@@ -601,8 +625,10 @@ mod tests {
     #[test]
     fn test_request_with_time_range() {
         let engine = BingEngine::new();
-        let mut params = RequestParams::default();
-        params.time_range = Some("week".to_string());
+        let mut params = RequestParams {
+            time_range: Some(TimeRange::Week),
+            ..Default::default()
+        };
         
         let result = engine.request("test", &mut params);
         assert!(result.is_ok());
@@ -611,6 +637,39 @@ mod tests {
         assert!(url.contains("filters=ex1:%22ez2%22")); // week = 2
     }
 
+    #[test]
+    fn test_request_with_region() {
+        let engine = BingEngine::new();
+        let mut params = RequestParams {
+            language: Some("zh".to_string()),
+            region: Some("CN".to_string()),
+            ..Default::default()
+        };
+        engine.request("test", &mut params).expect("request should succeed");
+        assert_eq!(params.cookies.get("_EDGE_S"), Some(&"mkt=zh-CN&ui=zh".to_string()));
+
+        // 未指定地区时使用默认市场，语言不再被当作地区
+        let mut params = RequestParams {
+            language: Some("de".to_string()),
+            ..Default::default()
+        };
+        engine.request("test", &mut params).expect("request should succeed");
+        assert_eq!(params.cookies.get("_EDGE_S"), Some(&"mkt=en-US&ui=de".to_string()));
+    }
+
+    #[test]
+    fn test_bing_market() {
+        assert_eq!(bing_market("en-GB", None), Some("en-GB"));
+        assert_eq!(bing_market("fr_ch", None), Some("fr-CH"));
+        assert_eq!(bing_market("CH", Some("fr")), Some("fr-CH"));
+        assert_eq!(bing_market("CH", None), Some("de-CH"));
+        assert_eq!(bing_market("XX", None), None);
+
+        let engine = BingEngine::new();
+        assert!(engine.info().capabilities.supports_region("en-US"));
+        assert!(!engine.info().capabilities.supports_region("XX"));
+    }
+
     #[test]
     fn test_set_cookies() {
         let mut params = RequestParams::default();
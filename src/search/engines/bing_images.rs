@@ -16,19 +16,40 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::error::Error;
+use std::borrow::Cow;
 
 use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType,
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    ImageMetadata, parse_resolution,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
-use super::utils::build_query_string_owned;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use super::bing::{bing_market, bing_supported_regions};
+use super::utils::{build_query_string_owned, select_all_text, select_attr, selector};
+
+/// `a.iusc` 的 `m` 属性中的图片元数据
+#[derive(Debug, Default, serde::Deserialize)]
+struct BingImageMetadata<'a> {
+    /// 原图地址
+    #[serde(borrow, default)]
+    murl: Option<Cow<'a, str>>,
+    /// 缩略图地址
+    #[serde(borrow, default)]
+    turl: Option<Cow<'a, str>>,
+    /// 来源页面地址
+    #[serde(borrow, default)]
+    purl: Option<Cow<'a, str>>,
+    /// 图片描述
+    #[serde(borrow, default)]
+    desc: Option<Cow<'a, str>>,
+}
 
 pub struct BingImagesEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl BingImagesEngine {
@@ -38,7 +59,7 @@ impl BingImagesEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Bing Images".to_string(),
@@ -48,12 +69,13 @@ impl BingImagesEngine {
                 categories: vec!["images".to_string(), "web".to_string()],
                 capabilities: EngineCapabilities {
                     result_types: vec![ResultType::Image],
-                    supported_params: vec!["page".to_string(), "time_range".to_string()],
+                    supported_params: vec!["page".to_string(), "time_range".to_string(), "region".to_string()],
                     max_page_size: 35,
                     supports_pagination: true,
                     supports_time_range: true,
                     supports_language_filter: false,
-                    supports_region_filter: false,
+                    supports_region_filter: true,
+                    supported_regions: bing_supported_regions(),
                     supports_safe_search: true,
                     rate_limit: Some(30),
                 },
@@ -81,8 +103,7 @@ impl BingImagesEngine {
     }
 
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
-        use serde_json;
+        use scraper::Html;
 
         if html.is_empty() {
             return Ok(Vec::new());
@@ -91,87 +112,46 @@ impl BingImagesEngine {
         let document = Html::parse_document(html);
         let mut items = Vec::with_capacity(35);
 
-        let result_selector = Selector::parse("ul.dgControl_list li")
-            .or_else(|_| Selector::parse("ul[class*=\"dgControl_list\"] li"))
-            .expect("valid selector");
-
-        for result in document.select(&result_selector) {
-            let metadata_elem = result.select(&Selector::parse("a.iusc").expect("valid selector")).next();
-
-            if metadata_elem.is_none() {
+        for result in document.select(selector!("ul.dgControl_list li")) {
+            let metadata_str = select_attr(result, selector!("a.iusc"), "m").unwrap_or("");
+            if metadata_str.is_empty() {
                 continue;
             }
 
-            let metadata_elem = metadata_elem.unwrap();
-            let metadata_str = metadata_elem.value().attr("m").unwrap_or("");
+            // 元数据 JSON 的字段直接借用属性值，只有含转义字符时才分配
+            let metadata: BingImageMetadata<'_> = serde_json::from_str(metadata_str).unwrap_or_default();
 
-            if metadata_str.is_empty() {
-                continue;
-            }
+            let title = select_all_text(result, selector!("div.infnmpt a"));
+            let img_format = select_all_text(result, selector!("div.imgpt div span"));
+            let source = select_all_text(result, selector!("div.imgpt div.lnkw a"));
 
-            // Parse metadata JSON
-            let metadata: HashMap<String, serde_json::Value> = serde_json::from_str(metadata_str)
-                .unwrap_or_else(|_| HashMap::new());
-
-            let title = result.select(&Selector::parse("div.infnmpt a").expect("valid selector"))
-                .map(|a| a.text().collect::<String>().trim().to_string())
-                .collect::<Vec<_>>()
-                .join(" ")
-                .trim()
-                .to_string();
-
-            let img_format = result.select(&Selector::parse("div.imgpt div span").expect("valid selector"))
-                .map(|span| span.text().collect::<String>().trim().to_string())
-                .collect::<Vec<_>>()
-                .join(" ")
-                .trim()
-                .to_string();
-
-            let source = result.select(&Selector::parse("div.imgpt div.lnkw a").expect("valid selector"))
-                .map(|a| a.text().collect::<String>().trim().to_string())
-                .collect::<Vec<_>>()
-                .join(" ")
-                .trim()
-                .to_string();
-
-            let img_src = metadata.get("murl")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let thumbnail_src = metadata.get("turl")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let page_url = metadata.get("purl")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let content = metadata.get("desc")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
+            let img_src = metadata.murl.unwrap_or_default().into_owned();
+            let thumbnail_src = metadata.turl.unwrap_or_default().into_owned();
+            let page_url = metadata.purl.unwrap_or_default().into_owned();
+            let content = metadata.desc.unwrap_or_default().into_owned();
 
             if img_src.is_empty() {
                 continue;
             }
 
-            let mut meta = HashMap::new();
-            meta.insert("source".to_string(), source);
-            meta.insert("format".to_string(), img_format.clone());
-            if !img_format.is_empty() {
-                let parts: Vec<&str> = img_format.split(" · ").collect();
-                if let Some(resolution) = parts.first() {
-                    meta.insert("resolution".to_string(), resolution.to_string());
-                }
-                if let Some(format) = parts.get(1) {
-                    meta.insert("img_format".to_string(), format.to_string());
-                }
-            }
-
-            items.push(SearchResultItem {
+            // 格式信息形如 "1200 × 800 · png"
+            let mut parts = img_format.split(" · ");
+            let resolution = parts.next().and_then(parse_resolution);
+            let format = parts.next()
+                .map(|f| f.trim().to_lowercase())
+                .filter(|f| !f.is_empty());
+
+            let image = ImageMetadata {
+                image_url: Some(img_src.clone()),
+                width: resolution.map(|(w, _)| w),
+                height: resolution.map(|(_, h)| h),
+                format,
+                source_page: Some(page_url.clone()).filter(|p| !p.is_empty()),
+                source: Some(source).filter(|s| !s.is_empty()),
+                ..Default::default()
+            };
+
+            let mut item = SearchResultItem {
                 title,
                 url: page_url.clone(),
                 content,
@@ -179,15 +159,13 @@ impl BingImagesEngine {
                 site_name: None,
                 score: 1.0,
                 result_type: ResultType::Image,
-                thumbnail: if !thumbnail_src.is_empty() { Some(thumbnail_src) } else { Some(img_src.clone()) },
+                thumbnail: if !thumbnail_src.is_empty() { Some(thumbnail_src) } else { Some(img_src) },
                 published_date: None,
                 template: Some("images.html".to_string()),
-                metadata: {
-                    let mut final_meta = meta;
-                    final_meta.insert("image_url".to_string(), img_src);
-                    final_meta
-                },
-            });
+                metadata: HashMap::new(),
+            };
+            item.set_image(&image);
+            items.push(item);
         }
 
         Ok(items)
@@ -219,16 +197,13 @@ impl SearchEngine for BingImagesEngine {
 impl RequestResponseEngine for BingImagesEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         let base_url = "https://www.bing.com/images/async";
 
-        let time_map = HashMap::from([
-            ("day", 60 * 24),
-            ("week", 60 * 24 * 7),
-            ("month", 60 * 24 * 31),
-            ("year", 60 * 24 * 365),
-        ]);
-
         let mut query_params = vec![
             ("q", query.to_string()),
             ("async", "1".to_string()),
@@ -237,10 +212,14 @@ impl RequestResponseEngine for BingImagesEngine {
         ];
 
         // Add time range filter if specified
-        if let Some(ref tr) = params.time_range {
-            if let Some(minutes) = time_map.get(tr.as_str()) {
-                query_params.push(("qft", format!("filterui:age-lt{}", minutes)));
-            }
+        if let Some(minutes) = params.time_range.and_then(|tr| tr.duration()).map(|d| d.num_minutes()) {
+            query_params.push(("qft", format!("filterui:age-lt{}", minutes)));
+        }
+
+        // 与网页搜索相同，市场通过 cookie 设置
+        if let Some(market) = params.region.as_deref().and_then(|r| bing_market(r, params.language.as_deref())) {
+            params.cookies.insert("_EDGE_S".to_string(), format!("mkt={}", market));
+            params.cookies.insert("_EDGE_CD".to_string(), format!("m={}", market));
         }
 
         // Build URL with optimized query string
@@ -255,17 +234,10 @@ impl RequestResponseEngine for BingImagesEngine {
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
 
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
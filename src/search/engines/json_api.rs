@@ -0,0 +1,441 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 声明式 JSON API 引擎
+//!
+//! 完全由配置驱动：URL 和请求体模板、请求头和 Cookie 模板、结果数组及各字段的 JSONPath。
+//! 运维人员无需编写 Rust 代码即可接入简单的 JSON 搜索接口（内部服务、Meilisearch、
+//! Elasticsearch 等）。
+//!
+//! 模板占位符：`{query}`、`{page}`（从 `first_page` 开始）、`{offset}`、`{page_size}`、
+//! `{language}`。URL 中的查询词会做 URL 编码，请求体中的查询词会做 JSON 字符串转义。
+//!
+//! JSONPath 支持常用子集：`$`、`.name`、`['name']`、`[0]`、`[*]` 和 `.*`，
+//! 字段路径相对于每个结果对象（也可以省略开头的 `$.`）。
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, RequestParams, RequestResponseEngine,
+    ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+use crate::net::client::HttpTransport;
+use super::utils::{render_template, template_vars};
+
+/// 结果字段的 JSONPath（相对于每个结果对象）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonApiFields {
+    /// 标题
+    pub title: String,
+    /// 链接（缺失时丢弃该结果）
+    pub url: String,
+    /// 摘要
+    pub content: Option<String>,
+    /// 缩略图
+    pub thumbnail: Option<String>,
+    /// 发布时间（RFC 3339 字符串或 Unix 时间戳）
+    pub published_date: Option<String>,
+}
+
+impl Default for JsonApiFields {
+    fn default() -> Self {
+        Self {
+            title: "title".to_string(),
+            url: "url".to_string(),
+            content: Some("content".to_string()),
+            thumbnail: None,
+            published_date: None,
+        }
+    }
+}
+
+/// JSON API 引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonApiConfig {
+    /// 引擎名（请求中用此名称选择引擎，不能与内置引擎重名）
+    pub name: String,
+    /// 引擎类型
+    pub engine_type: EngineType,
+    /// 分类
+    pub categories: Vec<String>,
+    /// 快捷方式（可作为 bang 使用）
+    pub shortcut: Option<String>,
+    /// URL 模板，如 `https://search.internal/api?q={query}&page={page}`
+    pub url: String,
+    /// HTTP 方法（GET 或 POST）
+    pub method: String,
+    /// POST 请求体模板
+    pub body: Option<String>,
+    /// POST 请求体的 Content-Type
+    pub content_type: String,
+    /// 请求头模板
+    pub headers: HashMap<String, String>,
+    /// Cookie 模板
+    pub cookies: HashMap<String, String>,
+    /// 第一页的页码（如从 0 开始计数的接口设为 0）
+    pub first_page: usize,
+    /// 每页结果数（用于 `{page_size}` 和 `{offset}`）
+    pub page_size: usize,
+    /// 结果数组的 JSONPath，如 `$.data.items`
+    pub results: String,
+    /// 结果字段的 JSONPath
+    pub fields: JsonApiFields,
+    /// 超时时间（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for JsonApiConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            engine_type: EngineType::General,
+            categories: vec!["general".to_string()],
+            shortcut: None,
+            url: String::new(),
+            method: "GET".to_string(),
+            body: None,
+            content_type: "application/json".to_string(),
+            headers: HashMap::new(),
+            cookies: HashMap::new(),
+            first_page: 1,
+            page_size: 10,
+            results: "$".to_string(),
+            fields: JsonApiFields::default(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// 路径中的一段
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// 解析 JSONPath
+fn parse_json_path(path: &str) -> Result<Vec<Segment>, String> {
+    let path = path.trim();
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| format!("unclosed '[' in JSONPath '{}'", path))?;
+            let inner = after[..end].trim();
+            segments.push(if inner == "*" {
+                Segment::Wildcard
+            } else if let Some(key) = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+            {
+                Segment::Key(key.to_string())
+            } else {
+                Segment::Index(inner.parse().map_err(|_| format!("invalid index '{}' in JSONPath '{}'", inner, path))?)
+            });
+            rest = &after[end + 1..];
+        } else {
+            let after = rest.strip_prefix('.').unwrap_or(rest);
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let key = &after[..end];
+            if key.is_empty() {
+                return Err(format!("empty key in JSONPath '{}'", path));
+            }
+            segments.push(if key == "*" { Segment::Wildcard } else { Segment::Key(key.to_string()) });
+            rest = &after[end..];
+        }
+    }
+    Ok(segments)
+}
+
+/// 按 JSONPath 选出所有匹配的值（路径无效时返回空）
+pub fn select_json<'a>(value: &'a Value, path: &str) -> Vec<&'a Value> {
+    let Ok(segments) = parse_json_path(path) else {
+        return Vec::new();
+    };
+    let mut current = vec![value];
+    for segment in &segments {
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&Value> {
+                match (segment, value) {
+                    (Segment::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+                    (Segment::Index(index), Value::Array(items)) => items.get(*index).into_iter().collect(),
+                    (Segment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    (Segment::Wildcard, Value::Object(map)) => map.values().collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+/// 按 JSONPath 取第一个标量值并转为字符串
+pub(crate) fn select_string(value: &Value, path: &str) -> Option<String> {
+    let text = match select_json(value, path).into_iter().next()? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// 解析发布时间（RFC 3339 字符串，或秒/毫秒级 Unix 时间戳）
+pub(crate) fn parse_date(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(s) => DateTime::parse_from_rfc3339(s.trim()).ok().map(|d| d.with_timezone(&Utc)),
+        Value::Number(n) => {
+            let timestamp = n.as_i64()?;
+            if timestamp > 100_000_000_000 {
+                Utc.timestamp_millis_opt(timestamp).single()
+            } else {
+                Utc.timestamp_opt(timestamp, 0).single()
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 配置驱动的 JSON API 引擎
+pub struct JsonApiEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    config: JsonApiConfig,
+}
+
+impl JsonApiEngine {
+    /// 使用配置创建引擎
+    pub fn new(client: Arc<dyn HttpTransport>, config: JsonApiConfig) -> Self {
+        Self {
+            info: EngineInfo {
+                name: config.name.clone(),
+                engine_type: config.engine_type,
+                description: format!("JSON API engine ({})", config.url),
+                status: EngineStatus::Active,
+                categories: config.categories.clone(),
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Web],
+                    supported_params: vec!["language".to_string()],
+                    max_page_size: config.page_size,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: config.url.contains("{language}")
+                        || config.body.as_ref().is_some_and(|b| b.contains("{language}")),
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: true,
+                    require_api_key: false,
+                    results: "JSON".to_string(),
+                },
+                shortcut: config.shortcut.clone(),
+                timeout: Some(config.timeout_secs),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 0,
+            },
+            client,
+            config,
+        }
+    }
+
+    /// 模板变量（`escape` 用于处理查询词）
+    fn vars(&self, query: &str, params: &RequestParams, escape: impl Fn(&str) -> String) -> Vec<(&'static str, String)> {
+        template_vars(query, params, self.config.first_page, self.config.page_size, escape)
+    }
+
+    /// 从响应中提取结果
+    fn parse_results(&self, json: &Value) -> Vec<SearchResultItem> {
+        let fields = &self.config.fields;
+        let mut results = select_json(json, &self.config.results);
+        // 结果路径指向数组本身时展开
+        if let [Value::Array(items)] = results.as_slice() {
+            results = items.iter().collect();
+        }
+
+        results
+            .into_iter()
+            .filter_map(|result| {
+                let url = select_string(result, &fields.url)?;
+                let field = |path: &Option<String>| path.as_deref().and_then(|path| select_string(result, path));
+                Some(SearchResultItem {
+                    title: select_string(result, &fields.title).unwrap_or_else(|| url.clone()),
+                    url,
+                    content: field(&fields.content).unwrap_or_default(),
+                    display_url: None,
+                    site_name: None,
+                    score: 1.0,
+                    result_type: ResultType::Web,
+                    thumbnail: field(&fields.thumbnail),
+                    published_date: fields
+                        .published_date
+                        .as_deref()
+                        .and_then(|path| select_json(result, path).into_iter().next())
+                        .and_then(parse_date),
+                    template: None,
+                    metadata: HashMap::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SearchEngine for JsonApiEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        !self.config.url.is_empty()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for JsonApiEngine {
+    type Response = Value;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.config.url.is_empty() {
+            return Err(format!("JSON API engine '{}' has no url", self.config.name).into());
+        }
+        let url_vars = self.vars(query, params, |s| urlencoding::encode(s).into_owned());
+        let raw_vars = self.vars(query, params, str::to_string);
+
+        params.url = Some(render_template(&self.config.url, &url_vars));
+        params.method = self.config.method.to_uppercase();
+        params.headers = self.config.headers.iter().map(|(k, v)| (k.clone(), render_template(v, &raw_vars))).collect();
+        params.cookies = self.config.cookies.iter().map(|(k, v)| (k.clone(), render_template(v, &raw_vars))).collect();
+        if let Some(body) = &self.config.body {
+            let json_vars = self.vars(query, params, |s| {
+                let quoted = serde_json::to_string(s).unwrap_or_default();
+                quoted[1..quoted.len() - 1].to_string()
+            });
+            params.custom.insert("body".to_string(), render_template(body, &json_vars));
+        }
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        let mut options = params.request_options();
+
+        let response = if params.method == "POST" {
+            options.headers.push(("Content-Type".to_string(), self.config.content_type.clone()));
+            let body = params.custom.get("body").cloned().unwrap_or_default();
+            self.client.post(url, body.into_bytes(), Some(options)).await
+        } else {
+            self.client.get(url, Some(options)).await
+        }
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", self.config.name, response.status()).into());
+        }
+        response.json::<Value>().await.map_err(|e| format!("Failed to parse JSON: {}", e).into())
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        Ok(self.parse_results(&resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+    use serde_json::json;
+
+    #[test]
+    fn test_select_json() {
+        let value = json!({"data": {"items": [{"t": "a", "tags": ["x", "y"]}, {"t": "b", "meta": {"score": 2}}]}});
+        assert_eq!(select_json(&value, "$.data.items[*].t"), vec![&json!("a"), &json!("b")]);
+        assert_eq!(select_json(&value, "data.items[1]['meta'].score"), vec![&json!(2)]);
+        assert_eq!(select_json(&value, "$.data.items[0].tags.*").len(), 2);
+        assert!(select_json(&value, "$.missing[0]").is_empty());
+        assert!(parse_json_path("$.a[").is_err());
+        assert_eq!(select_string(&value, "$.data.items[1].meta.score").as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_search_renders_templates_and_maps_fields() {
+        let body = r#"{"hits": [
+            {"link": "https://wiki.internal/rust", "name": "Rust guide", "summary": "How we use Rust", "ts": 1700000000},
+            {"name": "no link"}
+        ]}"#;
+        let transport = Arc::new(MockTransport::new().respond("search.internal", MockResponse::ok(body)));
+        let config = JsonApiConfig {
+            name: "wiki".to_string(),
+            url: "https://search.internal/api?q={query}&from={offset}".to_string(),
+            method: "post".to_string(),
+            body: Some(r#"{"q": "{query}", "size": {page_size}}"#.to_string()),
+            headers: HashMap::from([("Authorization".to_string(), "Bearer token".to_string())]),
+            first_page: 0,
+            results: "$.hits".to_string(),
+            fields: JsonApiFields {
+                title: "name".to_string(),
+                url: "link".to_string(),
+                content: Some("summary".to_string()),
+                thumbnail: None,
+                published_date: Some("ts".to_string()),
+            },
+            ..Default::default()
+        };
+        let engine = JsonApiEngine::new(transport.clone(), config);
+
+        let query = SearchQuery { query: "rust \"async\"".to_string(), page: 2, ..Default::default() };
+        let result = SearchEngine::search(&engine, &query).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].title, "Rust guide");
+        assert_eq!(result.items[0].content, "How we use Rust");
+        assert_eq!(result.items[0].published_date.unwrap().timestamp(), 1_700_000_000);
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.url, "https://search.internal/api?q=rust%20%22async%22&from=10");
+        assert_eq!(request.header("Authorization"), Some("Bearer token"));
+        let sent: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent, json!({"q": "rust \"async\"", "size": 10}));
+    }
+}
@@ -0,0 +1,453 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 本地文件搜索引擎
+//!
+//! 把配置的目录树中的文件名和正文（txt/md，启用 `pdf` feature 时包括 PDF）
+//! 索引到本地缓存，作为 `files` 分类的普通引擎参与搜索。
+//! 索引在首次搜索时建立，之后按 `reindex_interval_secs` 增量更新：
+//! 只重新抽取大小或修改时间变化的文件，并删除已不存在的文件。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+use crate::cache::{FileIndexCache, IndexedFile};
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, PaginationInfo,
+    ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+
+/// 本地文件引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalFilesConfig {
+    /// 要索引的目录（为空时引擎不可用）
+    pub roots: Vec<PathBuf>,
+    /// 抽取正文的扩展名（小写，不含 `.`），其他文件只索引文件名
+    pub text_extensions: Vec<String>,
+    /// 是否索引没有正文的文件（只按文件名匹配）
+    pub index_all_files: bool,
+    /// 超过该大小的文件不抽取正文（字节）
+    pub max_file_bytes: u64,
+    /// 每个文件保留的正文上限（字节）
+    pub max_text_bytes: usize,
+    /// 单个目录树最多索引的文件数
+    pub max_files: usize,
+    /// 增量重建索引的间隔（秒）
+    pub reindex_interval_secs: u64,
+}
+
+impl Default for LocalFilesConfig {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            text_extensions: ["txt", "md", "markdown", "pdf"].iter().map(|e| e.to_string()).collect(),
+            index_all_files: false,
+            max_file_bytes: 20 * 1024 * 1024,
+            max_text_bytes: 64 * 1024,
+            max_files: 50_000,
+            reindex_interval_secs: 300,
+        }
+    }
+}
+
+/// 一次索引的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// 扫描到的文件数
+    pub scanned: usize,
+    /// 新增或更新的文件数
+    pub updated: usize,
+    /// 删除的文件数
+    pub removed: usize,
+}
+
+pub struct LocalFilesEngine {
+    info: EngineInfo,
+    config: LocalFilesConfig,
+    index: Arc<FileIndexCache>,
+    /// 上次建立索引的时间；同时保证同一时刻只有一次索引在进行
+    last_indexed: Mutex<Option<Instant>>,
+}
+
+impl LocalFilesEngine {
+    pub fn new(config: LocalFilesConfig, index: Arc<FileIndexCache>) -> Self {
+        Self {
+            info: EngineInfo {
+                name: "Local Files".to_string(),
+                engine_type: EngineType::Custom,
+                description: "Local Files - search documents in configured directories".to_string(),
+                status: EngineStatus::Active,
+                categories: vec!["files".to_string()],
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::File],
+                    supported_params: vec!["page".to_string()],
+                    max_page_size: 50,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: false,
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: false,
+                    require_api_key: false,
+                    results: "Local index".to_string(),
+                },
+                shortcut: Some("file".to_string()),
+                timeout: Some(30),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 100,
+            },
+            config,
+            index,
+            last_indexed: Mutex::new(None),
+        }
+    }
+
+    /// 配置的目录（规范化为绝对路径，无法访问的目录被忽略）
+    fn roots(&self) -> Vec<PathBuf> {
+        self.config.roots.iter().filter_map(|root| root.canonicalize().ok()).collect()
+    }
+
+    /// 立即增量重建索引
+    pub async fn reindex(&self) -> Result<IndexStats, Box<dyn Error + Send + Sync>> {
+        let mut last_indexed = self.last_indexed.lock().await;
+        let stats = self.run_index().await?;
+        *last_indexed = Some(Instant::now());
+        Ok(stats)
+    }
+
+    /// 索引过期时重建
+    async fn ensure_indexed(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut last_indexed = self.last_indexed.lock().await;
+        let interval = Duration::from_secs(self.config.reindex_interval_secs);
+        if last_indexed.is_some_and(|at| at.elapsed() < interval) {
+            return Ok(());
+        }
+        let stats = self.run_index().await?;
+        tracing::debug!(
+            "Local files indexed: {} scanned, {} updated, {} removed",
+            stats.scanned, stats.updated, stats.removed
+        );
+        *last_indexed = Some(Instant::now());
+        Ok(())
+    }
+
+    async fn run_index(&self) -> Result<IndexStats, Box<dyn Error + Send + Sync>> {
+        let config = self.config.clone();
+        let index = Arc::clone(&self.index);
+        let roots = self.roots();
+        tokio::task::spawn_blocking(move || {
+            let mut total = IndexStats::default();
+            for root in roots {
+                let stats = index_root(&root, &config, &index)?;
+                total.scanned += stats.scanned;
+                total.updated += stats.updated;
+                total.removed += stats.removed;
+            }
+            Ok(total)
+        })
+        .await?
+    }
+}
+
+/// 目录的索引键前缀（以分隔符结尾，避免 `/data` 匹配到 `/data2`）
+fn dir_prefix(root: &Path) -> String {
+    let mut prefix = root.to_string_lossy().into_owned();
+    if !prefix.ends_with(std::path::MAIN_SEPARATOR) {
+        prefix.push(std::path::MAIN_SEPARATOR);
+    }
+    prefix
+}
+
+/// 增量索引一个目录树
+fn index_root(
+    root: &Path,
+    config: &LocalFilesConfig,
+    index: &FileIndexCache,
+) -> Result<IndexStats, Box<dyn Error + Send + Sync>> {
+    let mut stats = IndexStats::default();
+    let mut seen = HashSet::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // 跳过隐藏文件和目录（.git 等）
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            // 不跟随符号链接，避免逃出配置的目录
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !file_type.is_file() || stats.scanned >= config.max_files {
+                continue;
+            }
+
+            let extension = path.extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let extract = config.text_extensions.contains(&extension);
+            if !extract && !config.index_all_files {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            stats.scanned += 1;
+
+            let path_str = path.to_string_lossy().into_owned();
+            let modified = metadata.modified().ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            seen.insert(path_str.clone());
+
+            if let Ok(Some(existing)) = index.get(&path_str)
+                && existing.size == metadata.len()
+                && existing.modified == modified
+            {
+                continue;
+            }
+
+            let text = if extract && metadata.len() <= config.max_file_bytes {
+                extract_text(&path, &extension, config.max_text_bytes).unwrap_or_default()
+            } else {
+                String::new()
+            };
+            index.upsert(&IndexedFile {
+                path: path_str,
+                name: entry.file_name().to_string_lossy().into_owned(),
+                extension,
+                size: metadata.len(),
+                modified,
+                text,
+            }).map_err(|e| e.to_string())?;
+            stats.updated += 1;
+        }
+    }
+
+    for path in index.paths_under(&dir_prefix(root)).map_err(|e| e.to_string())? {
+        if !seen.contains(&path) && index.remove(&path).map_err(|e| e.to_string())? {
+            stats.removed += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 抽取文件正文，截断到 `max_bytes`（按字符边界）
+fn extract_text(path: &Path, extension: &str, max_bytes: usize) -> Option<String> {
+    let mut text = match extension {
+        "pdf" => extract_pdf_text(path)?,
+        _ => {
+            let bytes = std::fs::read(path).ok()?;
+            // 含 NUL 的多半是二进制文件
+            if bytes.contains(&0) {
+                return None;
+            }
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+    };
+    if text.len() > max_bytes {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    Some(text)
+}
+
+#[cfg(feature = "pdf")]
+fn extract_pdf_text(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    // pdf-extract 遇到损坏的文件可能 panic
+    match std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&bytes)) {
+        Ok(Ok(text)) => Some(text),
+        Ok(Err(e)) => {
+            tracing::debug!("Failed to extract text from {}: {}", path.display(), e);
+            None
+        }
+        Err(_) => {
+            tracing::debug!("PDF text extraction panicked for {}", path.display());
+            None
+        }
+    }
+}
+
+/// 未启用 `pdf` feature 时 PDF 只按文件名索引
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf_text(_path: &Path) -> Option<String> {
+    None
+}
+
+#[async_trait]
+impl SearchEngine for LocalFilesEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        let start = Instant::now();
+        self.ensure_indexed().await?;
+
+        let keywords: Vec<String> = query.query.split_whitespace().map(str::to_string).collect();
+        let page_size = query.page_size.clamp(1, self.info.capabilities.max_page_size);
+        let page = query.page.max(1);
+        let wanted = page * page_size;
+
+        let mut matches = Vec::new();
+        for root in self.roots() {
+            let found = self.index.search(&dir_prefix(&root), &keywords, wanted).map_err(|e| e.to_string())?;
+            matches.extend(found);
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.file.modified.cmp(&a.file.modified)));
+        let total = matches.len();
+
+        let top_score = matches.first().map(|m| m.score).unwrap_or(1.0).max(f64::EPSILON);
+        let items = matches
+            .into_iter()
+            .skip((page - 1) * page_size)
+            .take(page_size)
+            .map(|m| {
+                let url = url::Url::from_file_path(&m.file.path)
+                    .map(|u| u.to_string())
+                    .unwrap_or_else(|_| format!("file://{}", m.file.path));
+                let mut metadata = HashMap::new();
+                metadata.insert("path".to_string(), m.file.path.clone());
+                metadata.insert("size".to_string(), m.file.size.to_string());
+                metadata.insert("extension".to_string(), m.file.extension.clone());
+                SearchResultItem {
+                    title: m.file.name.clone(),
+                    url,
+                    content: m.snippet,
+                    display_url: Some(m.file.path.clone()),
+                    site_name: Some("本地文件".to_string()),
+                    score: m.score / top_score,
+                    result_type: ResultType::File,
+                    thumbnail: None,
+                    published_date: chrono::DateTime::from_timestamp(m.file.modified as i64, 0),
+                    template: None,
+                    metadata,
+                }
+            })
+            .collect();
+
+        Ok(SearchResult {
+            engine_name: self.info.name.clone(),
+            total_results: Some(total),
+            elapsed_ms: start.elapsed().as_millis() as u64,
+            items,
+            pagination: Some(PaginationInfo {
+                current_page: page,
+                page_size,
+                total_pages: None,
+                next_page: None,
+                prev_page: None,
+            }),
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    async fn is_available(&self) -> bool {
+        !self.roots().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheManager;
+
+    fn engine(root: &Path) -> LocalFilesEngine {
+        let manager = CacheManager::temporary();
+        let config = LocalFilesConfig {
+            roots: vec![root.to_path_buf()],
+            reindex_interval_secs: 3600,
+            ..Default::default()
+        };
+        LocalFilesEngine::new(config, Arc::new(FileIndexCache::new(manager)))
+    }
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery { query: q.to_string(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_local_files_index_and_search() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("notes")).unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join("notes/ownership.md"), "# 所有权\n\nRust 通过借用检查器保证内存安全。").unwrap();
+        std::fs::write(dir.path().join("rust-todo.txt"), "读完 async book").unwrap();
+        std::fs::write(dir.path().join(".git/config"), "rust").unwrap();
+        std::fs::write(dir.path().join("image.png"), [0u8, 1, 2]).unwrap();
+        let engine = engine(dir.path());
+        assert!(engine.is_available().await);
+
+        let result = engine.search(&query("rust")).await.unwrap();
+        let titles: Vec<&str> = result.items.iter().map(|i| i.title.as_str()).collect();
+        // 文件名命中排在正文命中之前
+        assert_eq!(titles, ["rust-todo.txt", "ownership.md"]);
+        let item = &result.items[1];
+        assert!(item.url.starts_with("file:///") && item.url.ends_with("/notes/ownership.md"));
+        assert!(item.content.contains("借用检查器"));
+        assert_eq!(item.result_type, ResultType::File);
+
+        // 增量重建：删除和修改的文件被同步
+        std::fs::remove_file(dir.path().join("rust-todo.txt")).unwrap();
+        std::fs::write(dir.path().join("notes/ownership.md"), "生命周期").unwrap();
+        let stats = engine.reindex().await.unwrap();
+        assert_eq!(stats, IndexStats { scanned: 1, updated: 1, removed: 1 });
+        assert!(engine.search(&query("rust")).await.unwrap().items.is_empty());
+        assert_eq!(engine.search(&query("生命周期")).await.unwrap().items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_local_files_without_roots() {
+        let manager = CacheManager::temporary();
+        let engine = LocalFilesEngine::new(LocalFilesConfig::default(), Arc::new(FileIndexCache::new(manager)));
+        assert!(!engine.is_available().await);
+        assert!(engine.search(&query("rust")).await.unwrap().items.is_empty());
+        assert_eq!(extract_text(Path::new("/nonexistent.txt"), "txt", 10), None);
+    }
+}
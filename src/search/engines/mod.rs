@@ -27,8 +27,18 @@ pub mod yandex;
 pub mod unsplash;
 pub mod sogou;
 pub mod sogou_videos;
+pub mod sogou_wechat;
+pub mod sogou_link;
 pub mod bilibili;
 pub mod so;
+pub mod local_files;
+pub mod podcasts;
+pub mod nominatim;
+pub mod json_api;
+pub mod xpath;
+pub mod search_index;
+#[cfg(feature = "torrent")]
+pub mod torznab;
 
 // 统一导出引擎类型
 pub use bing::BingEngine;
@@ -38,6 +48,15 @@ pub use yandex::YandexEngine;
 pub use unsplash::UnsplashEngine;
 pub use sogou::SogouEngine;
 pub use sogou_videos::SogouVideosEngine;
+pub use sogou_wechat::SogouWechatEngine;
 pub use bilibili::BilibiliEngine;
 pub use so::SoEngine;
+pub use local_files::{LocalFilesConfig, LocalFilesEngine};
+pub use podcasts::PodcastsEngine;
+pub use nominatim::{NominatimConfig, NominatimEngine};
+pub use json_api::{JsonApiConfig, JsonApiEngine, JsonApiFields};
+pub use xpath::{XPathConfig, XPathEngine, XPathFields};
+pub use search_index::{IndexBackend, SearchIndexConfig, SearchIndexEngine, SearchIndexFields};
+#[cfg(feature = "torrent")]
+pub use torznab::{TorznabConfig, TorznabEngine};
 
@@ -0,0 +1,374 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenStreetMap Nominatim 地点搜索引擎
+//!
+//! `map` 分类的引擎，结果类型为 [`ResultType::Map`]，坐标、地点类型和结构化地址写入
+//! [`PlaceMetadata`]。按照 Nominatim 的使用政策，必须配置联系邮箱（随 `email` 参数和
+//! User-Agent 一起发送），并且引擎内置出站限速，默认每秒最多一个请求。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, PlaceMetadata,
+    RequestParams, RequestResponseEngine, ResultType, SearchEngine, SearchQuery, SearchResult,
+    SearchResultItem,
+};
+use crate::net::client::HttpTransport;
+use super::utils::build_query_string_owned;
+
+/// 公共 Nominatim 实例
+const DEFAULT_ENDPOINT: &str = "https://nominatim.openstreetmap.org";
+
+/// 每次请求返回的地点数
+const RESULT_LIMIT: usize = 10;
+
+/// Nominatim 引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NominatimConfig {
+    /// Nominatim 实例地址（自建实例可放宽限速）
+    pub endpoint: String,
+    /// 联系邮箱（必填，未配置时引擎不可用）
+    pub email: Option<String>,
+    /// 两次出站请求的最小间隔（毫秒），公共实例要求不低于 1000
+    pub min_interval_ms: u64,
+}
+
+impl Default for NominatimConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            email: None,
+            min_interval_ms: 1000,
+        }
+    }
+}
+
+impl NominatimConfig {
+    /// 已配置的联系邮箱
+    pub fn contact_email(&self) -> Option<&str> {
+        self.email.as_deref().map(str::trim).filter(|email| email.contains('@'))
+    }
+}
+
+/// 出站限速：所有请求按最小间隔排队
+#[derive(Debug)]
+struct RateGate {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateGate {
+    fn new(interval: Duration) -> Self {
+        Self { interval, next_slot: Mutex::new(None) }
+    }
+
+    /// 等待到下一个可用的发送时间
+    async fn wait(&self) {
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// 解析 jsonv2 格式的搜索结果
+fn parse_places(json: &Value) -> Vec<SearchResultItem> {
+    let Some(places) = json.as_array() else {
+        return Vec::new();
+    };
+
+    let coordinate = |value: &Value| match value {
+        Value::String(s) => s.parse::<f64>().ok(),
+        other => other.as_f64(),
+    };
+    let text = |place: &Value, key: &str| {
+        place.get(key).and_then(Value::as_str).filter(|s| !s.is_empty()).map(str::to_string)
+    };
+
+    let mut items = Vec::new();
+    for place in places {
+        let (Some(lat), Some(lon)) = (
+            place.get("lat").and_then(coordinate),
+            place.get("lon").and_then(coordinate),
+        ) else {
+            continue;
+        };
+        let display_name = text(place, "display_name").unwrap_or_default();
+        let Some(title) = text(place, "name")
+            .or_else(|| display_name.split(',').next().map(|s| s.trim().to_string()))
+            .filter(|s| !s.is_empty())
+        else {
+            continue;
+        };
+
+        let osm_id = match (text(place, "osm_type"), place.get("osm_id").and_then(Value::as_u64)) {
+            (Some(osm_type), Some(id)) => Some(format!("{}/{}", osm_type, id)),
+            _ => None,
+        };
+        let url = match &osm_id {
+            Some(osm_id) => format!("https://www.openstreetmap.org/{}", osm_id),
+            None => format!("https://www.openstreetmap.org/?mlat={}&mlon={}#map=16/{}/{}", lat, lon, lat, lon),
+        };
+
+        let address: BTreeMap<String, String> = place
+            .get("address")
+            .and_then(Value::as_object)
+            .map(|address| {
+                address
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let bounding_box = place.get("boundingbox").and_then(Value::as_array).and_then(|bbox| {
+            let values: Vec<f64> = bbox.iter().filter_map(coordinate).collect();
+            <[f64; 4]>::try_from(values).ok()
+        });
+
+        let metadata = PlaceMetadata {
+            lat,
+            lon,
+            category: text(place, "category").or_else(|| text(place, "class")),
+            place_type: text(place, "type"),
+            address,
+            bounding_box,
+            osm_id,
+        };
+
+        let mut item = SearchResultItem {
+            title,
+            url,
+            content: display_name,
+            display_url: None,
+            site_name: Some("OpenStreetMap".to_string()),
+            score: place.get("importance").and_then(Value::as_f64).unwrap_or(0.5),
+            result_type: ResultType::Map,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        };
+        item.set_place(&metadata);
+        items.push(item);
+    }
+    items
+}
+
+/// Nominatim 地点搜索引擎
+pub struct NominatimEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    config: NominatimConfig,
+    gate: RateGate,
+}
+
+impl NominatimEngine {
+    /// 使用配置创建引擎
+    pub fn new(client: Arc<dyn HttpTransport>, config: NominatimConfig) -> Self {
+        let gate = RateGate::new(Duration::from_millis(config.min_interval_ms));
+        Self {
+            info: EngineInfo {
+                name: "Nominatim".to_string(),
+                engine_type: EngineType::Custom,
+                description: "OpenStreetMap Nominatim - place and address search".to_string(),
+                status: EngineStatus::Active,
+                categories: vec!["map".to_string()],
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Map],
+                    supported_params: vec!["language".to_string(), "region".to_string()],
+                    max_page_size: RESULT_LIMIT,
+                    supports_pagination: false,
+                    supports_time_range: false,
+                    supports_language_filter: true,
+                    supports_region_filter: true,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: Some(60),
+                },
+                about: AboutInfo {
+                    website: Some("https://nominatim.org".to_string()),
+                    wikidata_id: Some("Q936".to_string()),
+                    official_api_documentation: Some("https://nominatim.org/release-docs/latest/api/Search/".to_string()),
+                    use_official_api: true,
+                    require_api_key: false,
+                    results: "JSON".to_string(),
+                },
+                shortcut: Some("osm".to_string()),
+                timeout: Some(10),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 1,
+            },
+            client,
+            config,
+            gate,
+        }
+    }
+
+    /// 标识本应用及联系方式的 User-Agent（Nominatim 使用政策要求）
+    fn user_agent(&self, email: &str) -> String {
+        format!("SeeSea/{} ({})", env!("CARGO_PKG_VERSION"), email)
+    }
+}
+
+#[async_trait]
+impl SearchEngine for NominatimEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        self.config.contact_email().is_some()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for NominatimEngine {
+    type Response = Value;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let email = self.config.contact_email().ok_or("nominatim engine requires a contact email")?;
+        let mut query_params = vec![
+            ("q", query.to_string()),
+            ("format", "jsonv2".to_string()),
+            ("addressdetails", "1".to_string()),
+            ("limit", RESULT_LIMIT.to_string()),
+            ("email", email.to_string()),
+        ];
+        if let Some(language) = params.language.as_deref().filter(|l| !l.is_empty() && *l != "all") {
+            query_params.push(("accept-language", language.to_string()));
+        }
+        if let Some(country) = params.region.as_deref().and_then(|r| r.rsplit('-').next()).filter(|c| c.len() == 2) {
+            query_params.push(("countrycodes", country.to_ascii_lowercase()));
+        }
+
+        params.url = Some(format!(
+            "{}/search?{}",
+            self.config.endpoint.trim_end_matches('/'),
+            build_query_string_owned(query_params)
+        ));
+        params.method = "GET".to_string();
+        params.headers.insert("User-Agent".to_string(), self.user_agent(email));
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        self.gate.wait().await;
+        let response = self.client.get(url, Some(params.request_options())).await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Nominatim returned {}", response.status()).into());
+        }
+        response.json::<Value>().await.map_err(|e| format!("Failed to parse JSON: {}", e).into())
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        Ok(parse_places(&resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    const RESPONSE: &str = r#"[
+        {
+            "place_id": 1, "osm_type": "way", "osm_id": 5013364,
+            "lat": "48.8582599", "lon": "2.2945006",
+            "category": "man_made", "type": "tower", "importance": 0.62,
+            "name": "Tour Eiffel",
+            "display_name": "Tour Eiffel, 5, Avenue Anatole France, Paris, France",
+            "address": {"road": "Avenue Anatole France", "city": "Paris", "country": "France", "country_code": "fr"},
+            "boundingbox": ["48.8574753", "48.8590453", "2.2933119", "2.2956897"]
+        },
+        {"place_id": 2, "display_name": "No coordinates"}
+    ]"#;
+
+    fn config(min_interval_ms: u64) -> NominatimConfig {
+        NominatimConfig {
+            endpoint: "https://nominatim.example".to_string(),
+            email: Some("ops@example.org".to_string()),
+            min_interval_ms,
+        }
+    }
+
+    #[test]
+    fn test_parse_places() {
+        let items = parse_places(&serde_json::from_str(RESPONSE).unwrap());
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].title, "Tour Eiffel");
+        assert_eq!(items[0].url, "https://www.openstreetmap.org/way/5013364");
+        assert_eq!(items[0].result_type, ResultType::Map);
+
+        let place = items[0].place().unwrap();
+        assert!((place.lat - 48.8582599).abs() < 1e-9);
+        assert_eq!(place.place_type.as_deref(), Some("tower"));
+        assert_eq!(place.address.get("city").map(String::as_str), Some("Paris"));
+        assert_eq!(place.bounding_box.map(|b| b[0]), Some(48.8574753));
+    }
+
+    #[tokio::test]
+    async fn test_requires_contact_email() {
+        let engine = NominatimEngine::new(Arc::new(MockTransport::new()), NominatimConfig::default());
+        assert!(!engine.is_available().await);
+        let mut params = RequestParams::default();
+        assert!(engine.request("paris", &mut params).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_sends_contact_and_respects_rate_limit() {
+        let transport = Arc::new(MockTransport::new().respond("nominatim.example/search", MockResponse::ok(RESPONSE)));
+        let engine = NominatimEngine::new(transport.clone(), config(200));
+        let query = SearchQuery { query: "eiffel tower".to_string(), ..Default::default() };
+
+        let start = std::time::Instant::now();
+        SearchEngine::search(&engine, &query).await.unwrap();
+        SearchEngine::search(&engine, &query).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].url.contains("email=ops%40example.org"));
+        assert!(requests[0].header("User-Agent").unwrap().contains("ops@example.org"));
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 播客引擎
+//!
+//! 在已抓取的 RSS 条目中搜索带有音频或视频附件的节目，作为 `podcasts` 分类的引擎参与搜索。
+//! 结果链接为节目页面（缺失时为媒体地址），可直接播放的媒体地址、时长和大小记录在
+//! [`PodcastMetadata`] 中。
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::cache::RssCache;
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, PaginationInfo, PodcastMetadata,
+    RssEnclosureKind, ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+use crate::rss::parse_feed_date;
+
+/// 参与排序的候选条目上限
+const MAX_CANDIDATES: usize = 500;
+
+/// 基于 RSS 缓存的播客引擎
+pub struct PodcastsEngine {
+    info: EngineInfo,
+    rss: Arc<RssCache>,
+}
+
+impl PodcastsEngine {
+    pub fn new(rss: Arc<RssCache>) -> Self {
+        Self {
+            info: EngineInfo {
+                name: "Podcasts".to_string(),
+                engine_type: EngineType::Music,
+                description: "Podcasts - search audio and video episodes in fetched RSS feeds".to_string(),
+                status: EngineStatus::Active,
+                categories: vec!["podcasts".to_string()],
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Music, ResultType::Video],
+                    supported_params: vec!["page".to_string()],
+                    max_page_size: 50,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: false,
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: false,
+                    require_api_key: false,
+                    results: "RSS cache".to_string(),
+                },
+                shortcut: Some("pod".to_string()),
+                timeout: Some(10),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 20,
+            },
+            rss,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for PodcastsEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        let start = Instant::now();
+        let keywords: Vec<String> = query.query.split_whitespace().map(str::to_lowercase).collect();
+        let page_size = query.page_size.clamp(1, self.info.capabilities.max_page_size);
+        let page = query.page.max(1);
+
+        let candidates = self.rss.search_fulltext(&keywords, false, Some(MAX_CANDIDATES)).map_err(|e| e.to_string())?;
+        let mut feed_names: HashMap<String, Option<String>> = HashMap::new();
+        let mut matches = Vec::new();
+        for (feed_url, item) in candidates {
+            let Some(enclosure) = item.playable_enclosure() else {
+                continue;
+            };
+            let show = feed_names
+                .entry(feed_url.clone())
+                .or_insert_with(|| self.rss.get_meta(&feed_url).ok().flatten().and_then(|meta| meta.name))
+                .clone();
+            let Some(podcast) = PodcastMetadata::from_enclosure(enclosure, show.clone()) else {
+                continue;
+            };
+            // 标题命中的关键词越多越靠前
+            let title = item.title.to_lowercase();
+            let title_hits = keywords.iter().filter(|keyword| title.contains(keyword.as_str())).count();
+            let score = (1.0 + title_hits as f64) / (1.0 + keywords.len() as f64);
+            let published = item.pub_date.as_deref().and_then(parse_feed_date);
+
+            let mut result = SearchResultItem {
+                url: if item.link.is_empty() { podcast.media_url.clone() } else { item.link },
+                title: item.title,
+                content: item.description.unwrap_or_default(),
+                display_url: None,
+                site_name: show,
+                score,
+                result_type: match podcast.kind {
+                    RssEnclosureKind::Video => ResultType::Video,
+                    _ => ResultType::Music,
+                },
+                thumbnail: None,
+                published_date: published,
+                template: None,
+                metadata: HashMap::new(),
+            };
+            result.set_podcast(&podcast);
+            matches.push(result);
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.published_date.cmp(&a.published_date)));
+        let total = matches.len();
+        let items = matches.into_iter().skip((page - 1) * page_size).take(page_size).collect();
+
+        Ok(SearchResult {
+            engine_name: self.info.name.clone(),
+            total_results: Some(total),
+            elapsed_ms: start.elapsed().as_millis() as u64,
+            items,
+            pagination: Some(PaginationInfo {
+                current_page: page,
+                page_size,
+                total_pages: None,
+                next_page: None,
+                prev_page: None,
+            }),
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheManager;
+    use crate::derive::{RssEnclosure, RssFeed, RssFeedItem, RssFeedMeta};
+
+    fn episode(title: &str, link: &str, enclosure: Option<(&str, &str)>) -> RssFeedItem {
+        RssFeedItem {
+            title: title.to_string(),
+            link: link.to_string(),
+            description: Some("podcastengine episode".to_string()),
+            author: None,
+            pub_date: None,
+            content: None,
+            categories: vec![],
+            guid: None,
+            enclosures: enclosure
+                .map(|(url, mime)| RssEnclosure {
+                    url: url.to_string(),
+                    mime_type: Some(mime.to_string()),
+                    length: Some(1000),
+                    kind: RssEnclosureKind::detect(Some(mime), url),
+                    duration_secs: Some(1800),
+                })
+                .into_iter()
+                .collect(),
+            tags: vec![],
+            alternate_sources: vec![],
+            custom_fields: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_podcasts_only_return_playable_episodes() {
+        let manager = CacheManager::temporary();
+        let rss = Arc::new(RssCache::new(manager));
+        let url = "https://podcastengine.example.com/feed.xml";
+        let feed = RssFeed {
+            meta: RssFeedMeta {
+                title: "PodcastEngine Show".to_string(),
+                link: "https://podcastengine.example.com".to_string(),
+                description: None,
+                language: None,
+                copyright: None,
+                last_build_date: None,
+                pub_date: None,
+                image: None,
+            },
+            items: vec![
+                episode("Audio", "https://podcastengine.example.com/1", Some(("https://cdn.podcastengine.example.com/1.mp3", "audio/mpeg"))),
+                episode("Cover", "https://podcastengine.example.com/2", Some(("https://cdn.podcastengine.example.com/2.jpg", "image/jpeg"))),
+                episode("Text", "https://podcastengine.example.com/3", None),
+            ],
+        };
+        rss.set(url, &feed, false, None, None).unwrap();
+
+        let engine = PodcastsEngine::new(rss.clone());
+        let query = SearchQuery { query: "podcastengine".to_string(), ..Default::default() };
+        let result = engine.search(&query).await.unwrap();
+        rss.delete(url).unwrap();
+
+        assert_eq!(result.items.len(), 1);
+        let item = &result.items[0];
+        assert_eq!(item.result_type, ResultType::Music);
+        let podcast = item.podcast().unwrap();
+        assert_eq!(podcast.media_url, "https://cdn.podcastengine.example.com/1.mp3");
+        assert_eq!(podcast.duration_secs, Some(1800));
+        assert_eq!(podcast.show.as_deref(), Some("PodcastEngine Show"));
+    }
+}
@@ -0,0 +1,408 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 内部搜索索引引擎（Meilisearch / Elasticsearch）
+//!
+//! 查询配置的 Meilisearch 或 Elasticsearch 索引，把命中的文档与网页结果合并。
+//! 命中结果保留索引自身的相关性分数（归一化到 0-1，记录在 `engine_score` 元数据中），
+//! 聚合评分时直接使用该分数而不是重新按 BM25 计算。
+//!
+//! - Meilisearch：`POST {endpoint}/indexes/{index}/search`，分数取 `_rankingScore`
+//! - Elasticsearch：`POST {endpoint}/{index}/_search`，分数取 `_score / max_score`
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, RequestParams, RequestResponseEngine,
+    ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+use crate::net::client::HttpTransport;
+use crate::search::aggregator::ENGINE_SCORE_KEY;
+use super::json_api::{parse_date, select_json, select_string};
+
+/// 索引服务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexBackend {
+    /// Meilisearch
+    #[default]
+    Meilisearch,
+    /// Elasticsearch（也适用于 OpenSearch）
+    Elasticsearch,
+}
+
+/// 文档字段路径（JSONPath，相对于文档本身；Elasticsearch 为 `_source`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchIndexFields {
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+    /// 摘要
+    pub content: Option<String>,
+    /// 发布时间（RFC 3339 字符串或 Unix 时间戳）
+    pub published_date: Option<String>,
+}
+
+impl Default for SearchIndexFields {
+    fn default() -> Self {
+        Self {
+            title: "title".to_string(),
+            url: "url".to_string(),
+            content: Some("content".to_string()),
+            published_date: None,
+        }
+    }
+}
+
+/// 搜索索引引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchIndexConfig {
+    /// 引擎名（请求中用此名称选择引擎，不能与内置引擎重名）
+    pub name: String,
+    /// 索引服务类型
+    pub backend: IndexBackend,
+    /// 服务地址（如 `http://127.0.0.1:7700`）
+    pub endpoint: String,
+    /// 索引名
+    pub index: String,
+    /// API 密钥（Meilisearch 以 Bearer 发送，Elasticsearch 以 ApiKey 发送）
+    pub api_key: Option<String>,
+    /// Elasticsearch 参与匹配的字段（为空时在所有字段上查询）
+    pub search_fields: Vec<String>,
+    /// 文档字段路径
+    pub fields: SearchIndexFields,
+    /// 文档没有链接字段时用于生成链接的模板，`{id}` 替换为文档 ID
+    pub url_template: Option<String>,
+    /// 引擎类型
+    pub engine_type: EngineType,
+    /// 分类
+    pub categories: Vec<String>,
+    /// 快捷方式（可作为 bang 使用）
+    pub shortcut: Option<String>,
+    /// 每页结果数
+    pub page_size: usize,
+    /// 超时时间（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for SearchIndexConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            backend: IndexBackend::default(),
+            endpoint: String::new(),
+            index: String::new(),
+            api_key: None,
+            search_fields: Vec::new(),
+            fields: SearchIndexFields::default(),
+            url_template: None,
+            engine_type: EngineType::General,
+            categories: vec!["general".to_string()],
+            shortcut: None,
+            page_size: 10,
+            timeout_secs: 5,
+        }
+    }
+}
+
+/// 配置驱动的搜索索引引擎
+pub struct SearchIndexEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    config: SearchIndexConfig,
+}
+
+impl SearchIndexEngine {
+    /// 使用配置创建引擎
+    pub fn new(client: Arc<dyn HttpTransport>, config: SearchIndexConfig) -> Self {
+        let backend = match config.backend {
+            IndexBackend::Meilisearch => "Meilisearch",
+            IndexBackend::Elasticsearch => "Elasticsearch",
+        };
+        Self {
+            info: EngineInfo {
+                name: config.name.clone(),
+                engine_type: config.engine_type,
+                description: format!("{} index '{}'", backend, config.index),
+                status: EngineStatus::Active,
+                categories: config.categories.clone(),
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Web],
+                    supported_params: Vec::new(),
+                    max_page_size: config.page_size,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: false,
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: true,
+                    require_api_key: false,
+                    results: "JSON".to_string(),
+                },
+                shortcut: config.shortcut.clone(),
+                timeout: Some(config.timeout_secs),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 0,
+            },
+            client,
+            config,
+        }
+    }
+
+    /// 请求体
+    fn request_body(&self, query: &str, offset: usize) -> Value {
+        let size = self.config.page_size;
+        match self.config.backend {
+            IndexBackend::Meilisearch => json!({
+                "q": query,
+                "offset": offset,
+                "limit": size,
+                "showRankingScore": true,
+            }),
+            IndexBackend::Elasticsearch => {
+                let matcher = if self.config.search_fields.is_empty() {
+                    json!({ "simple_query_string": { "query": query } })
+                } else {
+                    json!({ "multi_match": { "query": query, "fields": self.config.search_fields } })
+                };
+                json!({ "query": matcher, "from": offset, "size": size })
+            }
+        }
+    }
+
+    /// 从响应中提取 (文档 ID, 文档, 归一化分数)
+    fn hits<'a>(&self, json: &'a Value) -> Vec<(Option<String>, &'a Value, f64)> {
+        match self.config.backend {
+            IndexBackend::Meilisearch => select_json(json, "$.hits[*]")
+                .into_iter()
+                .map(|hit| {
+                    let score = hit.get("_rankingScore").and_then(Value::as_f64).unwrap_or(0.0);
+                    (select_string(hit, "id"), hit, score)
+                })
+                .collect(),
+            IndexBackend::Elasticsearch => {
+                let hits = select_json(json, "$.hits.hits[*]");
+                let max_score = json
+                    .pointer("/hits/max_score")
+                    .and_then(Value::as_f64)
+                    .or_else(|| hits.iter().filter_map(|hit| hit.get("_score")?.as_f64()).reduce(f64::max))
+                    .filter(|max| *max > 0.0);
+                hits.into_iter()
+                    .filter_map(|hit| {
+                        let score = match (hit.get("_score").and_then(Value::as_f64), max_score) {
+                            (Some(score), Some(max)) => score / max,
+                            _ => 0.0,
+                        };
+                        Some((select_string(hit, "_id"), hit.get("_source")?, score))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// 把命中转换为结果项
+    fn parse_results(&self, json: &Value) -> Vec<SearchResultItem> {
+        let fields = &self.config.fields;
+        self.hits(json)
+            .into_iter()
+            .filter_map(|(id, document, score)| {
+                let url = select_string(document, &fields.url).or_else(|| {
+                    let template = self.config.url_template.as_ref()?;
+                    Some(template.replace("{id}", &urlencoding::encode(&id?)))
+                })?;
+                let score = score.clamp(0.0, 1.0);
+                Some(SearchResultItem {
+                    title: select_string(document, &fields.title).unwrap_or_else(|| url.clone()),
+                    url,
+                    content: fields.content.as_deref().and_then(|path| select_string(document, path)).unwrap_or_default(),
+                    display_url: None,
+                    site_name: None,
+                    score,
+                    result_type: ResultType::Web,
+                    thumbnail: None,
+                    published_date: fields
+                        .published_date
+                        .as_deref()
+                        .and_then(|path| select_json(document, path).into_iter().next())
+                        .and_then(parse_date),
+                    template: None,
+                    metadata: HashMap::from([(ENGINE_SCORE_KEY.to_string(), format!("{:.4}", score))]),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SearchEngine for SearchIndexEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        !self.config.endpoint.is_empty() && !self.config.index.is_empty()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for SearchIndexEngine {
+    type Response = Value;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.config.endpoint.is_empty() || self.config.index.is_empty() {
+            return Err(format!("index engine '{}' has no endpoint or index", self.config.name).into());
+        }
+        let endpoint = self.config.endpoint.trim_end_matches('/');
+        let index = urlencoding::encode(&self.config.index);
+        params.url = Some(match self.config.backend {
+            IndexBackend::Meilisearch => format!("{}/indexes/{}/search", endpoint, index),
+            IndexBackend::Elasticsearch => format!("{}/{}/_search", endpoint, index),
+        });
+        params.method = "POST".to_string();
+        if let Some(key) = &self.config.api_key {
+            let scheme = match self.config.backend {
+                IndexBackend::Meilisearch => "Bearer",
+                IndexBackend::Elasticsearch => "ApiKey",
+            };
+            params.headers.insert("Authorization".to_string(), format!("{} {}", scheme, key));
+        }
+        let offset = (params.pageno - 1) * self.config.page_size;
+        params.custom.insert("body".to_string(), self.request_body(query, offset).to_string());
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        let mut options = params.request_options();
+        options.headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        let body = params.custom.get("body").cloned().unwrap_or_default();
+        let response = self
+            .client
+            .post(url, body.into_bytes(), Some(options))
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", self.config.name, response.status()).into());
+        }
+        response.json::<Value>().await.map_err(|e| format!("Failed to parse JSON: {}", e).into())
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        Ok(self.parse_results(&resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[tokio::test]
+    async fn test_meilisearch_hits_keep_ranking_score() {
+        let body = r#"{"hits": [
+            {"id": 7, "title": "Onboarding", "url": "https://wiki.internal/onboarding", "content": "Welcome", "_rankingScore": 0.93},
+            {"id": 8, "title": "No url", "_rankingScore": 0.5}
+        ]}"#;
+        let transport = Arc::new(MockTransport::new().respond("127.0.0.1:7700", MockResponse::ok(body)));
+        let config = SearchIndexConfig {
+            name: "wiki".to_string(),
+            endpoint: "http://127.0.0.1:7700/".to_string(),
+            index: "docs".to_string(),
+            api_key: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let engine = SearchIndexEngine::new(transport.clone(), config);
+
+        let query = SearchQuery { query: "onboarding".to_string(), page: 2, ..Default::default() };
+        let result = SearchEngine::search(&engine, &query).await.unwrap();
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].score, 0.93);
+        assert_eq!(result.items[0].metadata[ENGINE_SCORE_KEY], "0.9300");
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.url, "http://127.0.0.1:7700/indexes/docs/search");
+        assert_eq!(request.header("Authorization"), Some("Bearer secret"));
+        let sent: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent["offset"], 10);
+        assert_eq!(sent["showRankingScore"], true);
+    }
+
+    #[tokio::test]
+    async fn test_elasticsearch_scores_are_normalized() {
+        let body = r#"{"hits": {"max_score": 8.0, "hits": [
+            {"_id": "a1", "_score": 8.0, "_source": {"doc": {"name": "Runbook"}, "body": "Restart the service"}},
+            {"_id": "b/2", "_score": 2.0, "_source": {"doc": {"name": "Postmortem"}}}
+        ]}}"#;
+        let transport = Arc::new(MockTransport::new().respond("es.internal", MockResponse::ok(body)));
+        let config = SearchIndexConfig {
+            name: "kb".to_string(),
+            backend: IndexBackend::Elasticsearch,
+            endpoint: "https://es.internal:9200".to_string(),
+            index: "kb".to_string(),
+            search_fields: vec!["doc.name^2".to_string(), "body".to_string()],
+            fields: SearchIndexFields {
+                title: "doc.name".to_string(),
+                content: Some("body".to_string()),
+                ..Default::default()
+            },
+            url_template: Some("https://kb.internal/{id}".to_string()),
+            ..Default::default()
+        };
+        let engine = SearchIndexEngine::new(transport.clone(), config);
+
+        let query = SearchQuery { query: "restart".to_string(), ..Default::default() };
+        let result = SearchEngine::search(&engine, &query).await.unwrap();
+        let scores: Vec<f64> = result.items.iter().map(|item| item.score).collect();
+        assert_eq!(scores, vec![1.0, 0.25]);
+        assert_eq!(result.items[0].url, "https://kb.internal/a1");
+        assert_eq!(result.items[1].url, "https://kb.internal/b%2F2");
+        assert_eq!(result.items[0].content, "Restart the service");
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.url, "https://es.internal:9200/kb/_search");
+        let sent: Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent["query"]["multi_match"]["fields"][0], "doc.name^2");
+        assert_eq!(sent["from"], 0);
+    }
+}
@@ -2,19 +2,21 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::error::Error;
+use std::borrow::Cow;
 
 use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType,
     ResultType, SearchEngine, SearchQuery, SearchResult,
-    SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    SearchResultItem, TimeRange, AboutInfo, RequestResponseEngine, RequestParams,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
-use super::utils::build_query_string_owned;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use super::utils::{build_query_string_owned, element_text, select_text, selector};
 
 pub struct SoEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl SoEngine {
@@ -24,7 +26,7 @@ impl SoEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "360 Search".to_string(),
@@ -40,6 +42,7 @@ impl SoEngine {
                     supports_time_range: true,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(60),
                 },
@@ -67,7 +70,7 @@ impl SoEngine {
     }
 
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
+        use scraper::Html;
 
         if html.is_empty() {
             return Ok(Vec::new());
@@ -76,62 +79,39 @@ impl SoEngine {
         let document = Html::parse_document(html);
         let mut items = Vec::with_capacity(10);
 
-        let res_list_selector = Selector::parse("li.res-list")
-            .or_else(|_| Selector::parse("li[class*=\"res-list\"]"))
-            .expect("valid selector");
-
-        for result in document.select(&res_list_selector) {
-            let title_selector = Selector::parse("h3.res-title a")
-                .or_else(|_| Selector::parse("h3[class*=\"res-title\"] a"))
-                .expect("valid selector");
-            let title_elem = result.select(&title_selector).next();
-
-            if title_elem.is_none() {
+        for result in document.select(selector!("li.res-list")) {
+            let Some(title_elem) = result.select(selector!("h3.res-title a")).next() else {
                 continue;
-            }
-
-            let title_elem = title_elem.unwrap();
-            let title = title_elem.text().collect::<String>().trim().to_string();
+            };
 
+            let title = element_text(title_elem);
             if title.is_empty() {
                 continue;
             }
 
-            let url = title_elem.value().attr("href")
-                .unwrap_or("")
-                .to_string();
-
-            let real_url = title_elem.value().attr("data-mdurl")
-                .unwrap_or("")
-                .to_string();
-
-            let final_url = if !real_url.is_empty() { real_url } else { url.clone() };
+            // data-mdurl 是真实地址，href 可能是跳转链接
+            let final_url = title_elem.value().attr("data-mdurl")
+                .filter(|u| !u.is_empty())
+                .or_else(|| title_elem.value().attr("href"))
+                .unwrap_or("");
 
             if final_url.is_empty() {
                 continue;
             }
 
-            let content = result.select(&Selector::parse("p").expect("valid selector")).next()
-                .map(|c| c.text().collect::<String>().trim().to_string())
-                .or_else(|| {
-                    result.select(&Selector::parse("div[class*=\"desc\"]").expect("valid selector")).next()
-                        .map(|c| c.text().collect::<String>().trim().to_string())
-                })
-                .or_else(|| {
-                    result.select(&Selector::parse("div[class*=\"content\"]").expect("valid selector")).next()
-                        .map(|c| c.text().collect::<String>().trim().to_string())
-                })
+            let content = select_text(result, selector!("p"))
+                .or_else(|| select_text(result, selector!("div[class*=\"desc\"]")))
+                .or_else(|| select_text(result, selector!("div[class*=\"content\"]")))
                 .unwrap_or_default();
 
-            let display_url = result.select(&Selector::parse("cite").expect("valid selector")).next()
-                .map(|c| c.text().collect::<String>().trim().to_string())
-                .or_else(|| Some(final_url.clone()));
+            let display_url = select_text(result, selector!("cite"))
+                .unwrap_or(Cow::Borrowed(final_url));
 
             items.push(SearchResultItem {
-                title,
-                url: final_url.clone(),
-                content,
-                display_url,
+                title: title.into_owned(),
+                url: final_url.to_string(),
+                content: content.into_owned(),
+                display_url: Some(display_url.into_owned()),
                 site_name: None,
                 score: 1.0,
                 result_type: ResultType::Web,
@@ -143,15 +123,8 @@ impl SoEngine {
         }
 
         if items.is_empty() {
-            let res_rich_selector = Selector::parse("div.res-rich")
-                .or_else(|_| Selector::parse("div[class*=\"res-rich\"]"))
-                .expect("valid selector");
-
-            for result in document.select(&res_rich_selector) {
-                let link_selector = Selector::parse("a").expect("valid selector");
-                let links = result.select(&link_selector);
-
-                for link in links {
+            for result in document.select(selector!("div.res-rich")) {
+                for link in result.select(selector!("a")) {
                     let text = link.text().collect::<String>().trim().to_string();
 
                     if text.len() < 5 || text == "360软件宝库" {
@@ -230,6 +203,10 @@ impl SearchEngine for SoEngine {
 impl RequestResponseEngine for SoEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         let mut query_params = vec![
             ("q", query.to_string()),
@@ -241,13 +218,13 @@ impl RequestResponseEngine for SoEngine {
             query_params.push(("pn", ((params.pageno - 1) * 10).to_string()));
         }
 
-        if let Some(ref tr) = params.time_range {
-            let time_filter = match tr.as_str() {
-                "day" => "d",
-                "week" => "w",
-                "month" => "m",
-                "year" => "y",
-                _ => "",
+        if let Some(tr) = params.time_range {
+            let time_filter = match tr {
+                TimeRange::Day => "d",
+                TimeRange::Week => "w",
+                TimeRange::Month => "m",
+                TimeRange::Year => "y",
+                TimeRange::Any | TimeRange::Hour => "",
             };
             if !time_filter.is_empty() {
                 query_params.push(("adv", time_filter.to_string()));
@@ -265,16 +242,10 @@ impl RequestResponseEngine for SoEngine {
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
 
-        let mut options = RequestOptions::default();
-
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
@@ -20,15 +20,18 @@ use std::error::Error;
 use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType,
     ResultType, SearchEngine, SearchQuery, SearchResult,
-    SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    SearchResultItem, TimeRange, AboutInfo, RequestResponseEngine, RequestParams,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
-use super::utils::build_query_string_owned;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use super::sogou_link::{absolutize_link, SogouLinkResolver, LINK_PREFIX};
+use super::utils::{build_query_string_owned, element_text, select_text, selector};
 
 pub struct SogouEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
+    resolver: SogouLinkResolver,
 }
 
 impl SogouEngine {
@@ -38,7 +41,7 @@ impl SogouEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Sogou".to_string(),
@@ -54,6 +57,7 @@ impl SogouEngine {
                     supports_time_range: true,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(60),
                 },
@@ -76,12 +80,13 @@ impl SogouEngine {
                 tokens: Vec::new(),
                 max_page: 10,
             },
+            resolver: SogouLinkResolver::new(Arc::clone(&client)),
             client,
         }
     }
 
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
+        use scraper::Html;
 
         if html.is_empty() {
             return Ok(Vec::new());
@@ -90,56 +95,37 @@ impl SogouEngine {
         let document = Html::parse_document(html);
         let mut items = Vec::with_capacity(10);
 
-        let result_selector = Selector::parse("div.vrwrap")
-            .or_else(|_| Selector::parse("div[class*=\"vrwrap\"]"))
-            .expect("valid selector");
-
-        for result in document.select(&result_selector) {
-             let title_selector = Selector::parse("h3.vr-title a")
-                .or_else(|_| Selector::parse("h3[class*=\"vr-title\"] a"))
-                .expect("valid selector");
-            let title_elem = result.select(&title_selector).next();
-
-            if title_elem.is_none() {
+        for result in document.select(selector!("div.vrwrap")) {
+            let Some(title_elem) = result.select(selector!("h3.vr-title a")).next() else {
                 continue;
-            }
-
-            let title_elem = title_elem.unwrap();
-            let title = title_elem.text().collect::<String>().trim().to_string();
+            };
 
+            let title = element_text(title_elem);
             if title.is_empty() {
                 continue;
             }
 
-             let url_elem = title_elem;
-            let url = url_elem.value().attr("href")
-                .unwrap_or("")
-                .to_string();
-
-            // Handle redirect URLs: if url.startswith("/link?url="):
-            if url.starts_with("/link?url=") {
-                // In real implementation, we might need to resolve this redirect
-                // For now, construct the full URL
-                continue; // Skip redirects for now as they require special handling
-            }
+            let href = title_elem.value().attr("href").unwrap_or("");
+            // 跳转链接先补全为绝对地址，search() 中再解析为真实地址
+            let url = if href.starts_with(LINK_PREFIX) {
+                absolutize_link(href, "https://www.sogou.com")
+            } else {
+                href.to_string()
+            };
 
             if url.is_empty() {
                 continue;
             }
 
-             // if not content: content = extract_text(item.xpath('.//div[contains(@class, "fz-mid space-txt")]'))
-            let content = result.select(&Selector::parse("div.text-layout p.star-wiki").expect("valid selector")).next()
-                .map(|c| c.text().collect::<String>().trim().to_string())
-                .or_else(|| {
-                    result.select(&Selector::parse("div.fz-mid.space-txt").expect("valid selector")).next()
-                        .map(|c| c.text().collect::<String>().trim().to_string())
-                })
+            // if not content: content = extract_text(item.xpath('.//div[contains(@class, "fz-mid space-txt")]'))
+            let content = select_text(result, selector!("div.text-layout p.star-wiki"))
+                .or_else(|| select_text(result, selector!("div.fz-mid.space-txt")))
                 .unwrap_or_default();
 
             items.push(SearchResultItem {
-                title,
+                title: title.into_owned(),
                 url: url.clone(),
-                content,
+                content: content.into_owned(),
                 display_url: Some(url),
                 site_name: None,
                 score: 1.0,
@@ -168,7 +154,9 @@ impl SearchEngine for SogouEngine {
     }
 
     async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
-        <Self as RequestResponseEngine>::search(self, query).await
+        let mut result = <Self as RequestResponseEngine>::search(self, query).await?;
+        self.resolver.resolve_items(&mut result.items).await;
+        Ok(result)
     }
 
     async fn is_available(&self) -> bool {
@@ -180,6 +168,10 @@ impl SearchEngine for SogouEngine {
 impl RequestResponseEngine for SogouEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
          // query_params = {"query": query, "page": params["pageno"]}
         let mut query_params = vec![
@@ -188,13 +180,13 @@ impl RequestResponseEngine for SogouEngine {
         ];
 
         // Add time range filter if specified
-         if let Some(ref tr) = params.time_range {
-            let s_from = match tr.as_str() {
-                "day" => "inttime_day",
-                "week" => "inttime_week",
-                "month" => "inttime_month",
-                "year" => "inttime_year",
-                _ => "",
+        if let Some(tr) = params.time_range {
+            let s_from = match tr {
+                TimeRange::Day => "inttime_day",
+                TimeRange::Week => "inttime_week",
+                TimeRange::Month => "inttime_month",
+                TimeRange::Year => "inttime_year",
+                TimeRange::Any | TimeRange::Hour => "",
             };
             if !s_from.is_empty() {
                 query_params.push(("s_from", s_from.to_string()));
@@ -214,17 +206,10 @@ impl RequestResponseEngine for SogouEngine {
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
 
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
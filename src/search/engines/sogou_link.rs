@@ -0,0 +1,255 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 搜狗跳转链接解析
+//!
+//! 搜狗网页和微信搜索的大部分结果链接形如 `/link?url=...`，需要请求一次跳转页才能
+//! 拿到真实地址。跳转页通常返回 200，真实地址写在 `window.location.replace(...)`、
+//! 逐段拼接的 `url += '...'`（微信）或 `<meta http-equiv="refresh">` 中。
+
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::derive::SearchResultItem;
+use crate::net::client::HttpTransport;
+use crate::net::charset::response_text;
+use crate::net::types::RequestOptions;
+use crate::url_utils::is_http_url;
+
+/// 同时解析的跳转链接上限
+const MAX_CONCURRENT_RESOLVES: usize = 4;
+
+/// 缓存的已解析链接上限，超出后清空
+const MAX_CACHED_LINKS: usize = 2048;
+
+/// 跳转链接的路径前缀
+pub(crate) const LINK_PREFIX: &str = "/link?url=";
+
+/// 是否为搜狗跳转链接
+pub fn is_sogou_link(url: &str) -> bool {
+    url.starts_with(LINK_PREFIX)
+        || (url.contains(".sogou.com/link?") && url::Url::parse(url).is_ok_and(|u| u.path() == "/link"))
+}
+
+/// 把相对跳转链接补全为 `origin` 下的绝对地址，能直接解码时返回真实地址
+pub fn absolutize_link(href: &str, origin: &str) -> String {
+    decode_link(href).unwrap_or_else(|| format!("{}{}", origin, href))
+}
+
+/// 直接解码跳转链接
+///
+/// 部分链接的 `url` 参数是百分号编码的明文地址；加密的参数（常见于微信）返回 `None`
+pub fn decode_link(href: &str) -> Option<String> {
+    let (_, query) = href.split_once('?')?;
+    let target = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "url")
+        .map(|(_, value)| value.into_owned())?;
+    is_http_url(&target).then_some(target)
+}
+
+/// 从跳转页中提取真实地址
+fn extract_target(body: &str) -> Option<String> {
+    // 微信：var url = ''; url += 'https://mp.'; url += 'weixin.qq.com/s?...'; url.replace("@", "")
+    let pieces: String = regex::Regex::new(r"url\s*\+=\s*'([^']*)'")
+        .ok()?
+        .captures_iter(body)
+        .filter_map(|caps| caps.get(1).map(|m| m.as_str().to_string()))
+        .collect();
+    if is_http_url(&pieces) {
+        return Some(pieces.replace('@', ""));
+    }
+
+    let patterns = [
+        r#"location\.replace\(\s*["']([^"']+)["']"#,
+        r#"location\.href\s*=\s*["']([^"']+)["']"#,
+        r#"(?i)http-equiv=["']?refresh["']?[^>]*url=['"]?([^'">]+)"#,
+    ];
+    patterns.iter().find_map(|pattern| {
+        regex::Regex::new(pattern)
+            .ok()?
+            .captures(body)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|target| is_http_url(target))
+    })
+}
+
+/// 跳转链接解析器，带缓存和并发限制
+pub struct SogouLinkResolver {
+    client: Arc<dyn HttpTransport>,
+    cache: Mutex<HashMap<String, String>>,
+}
+
+impl SogouLinkResolver {
+    /// 使用引擎的传输层创建解析器
+    pub fn new(client: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            client,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 解析单个跳转链接
+    pub async fn resolve(&self, link: &str) -> Option<String> {
+        if let Some(target) = self.cache.lock().ok()?.get(link) {
+            return Some(target.clone());
+        }
+
+        let target = self.fetch_target(link).await?;
+        if let Ok(mut cache) = self.cache.lock() {
+            if cache.len() >= MAX_CACHED_LINKS {
+                cache.clear();
+            }
+            cache.insert(link.to_string(), target.clone());
+        }
+        Some(target)
+    }
+
+    async fn fetch_target(&self, link: &str) -> Option<String> {
+        let options = RequestOptions {
+            headers: vec![("Referer".to_string(), link_origin(link).to_string())],
+            ..Default::default()
+        };
+        let response = match self.client.get(link, Some(options)).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::debug!("Failed to resolve Sogou link {}: {}", link, e);
+                return None;
+            }
+        };
+
+        if response.status().is_redirection() {
+            return response
+                .headers()
+                .get("location")
+                .and_then(|v| v.to_str().ok())
+                .filter(|target| is_http_url(target))
+                .map(str::to_string);
+        }
+        // 客户端已跟随 HTTP 重定向到了目标站点（回放和模拟的响应没有真实 URL，主机为 *.local）
+        let final_url = response.url();
+        if final_url.host_str().is_some_and(|host| !host.ends_with("sogou.com") && !host.ends_with(".local")) {
+            return Some(final_url.to_string());
+        }
+
+        let body = response_text(response).await.ok()?;
+        extract_target(&body)
+    }
+
+    /// 把结果中的跳转链接替换为真实地址
+    ///
+    /// 解析失败的结果保留跳转链接（在浏览器中仍可打开）
+    pub async fn resolve_items(&self, items: &mut [SearchResultItem]) {
+        let links: Vec<(usize, String)> = items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| is_sogou_link(&item.url))
+            .map(|(i, item)| (i, item.url.clone()))
+            .collect();
+        if links.is_empty() {
+            return;
+        }
+
+        let resolved: Vec<(usize, Option<String>)> = stream::iter(links)
+            .map(|(i, link)| async move { (i, self.resolve(&link).await) })
+            .buffer_unordered(MAX_CONCURRENT_RESOLVES)
+            .collect()
+            .await;
+
+        for (i, target) in resolved {
+            if let Some(target) = target {
+                let item = &mut items[i];
+                if item.display_url.as_deref() == Some(item.url.as_str()) {
+                    item.display_url = Some(target.clone());
+                }
+                item.url = target;
+            }
+        }
+    }
+}
+
+/// 跳转链接所在站点（用作 Referer）
+fn link_origin(link: &str) -> &str {
+    link.find(LINK_PREFIX).map(|i| &link[..i]).unwrap_or(link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    fn item(url: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: "Rust".to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            display_url: Some(url.to_string()),
+            site_name: None,
+            score: 1.0,
+            result_type: crate::derive::ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_decode_and_extract() {
+        assert_eq!(
+            decode_link("/link?url=https%3A%2F%2Fcourse.rs%2F&query=rust").as_deref(),
+            Some("https://course.rs/")
+        );
+        assert_eq!(decode_link("/link?url=hedJjaC291MLsVKc4hzYcqe0GdXLN2QOSuGlQYu8mhCVK0FiNSpZ1g.."), None);
+        assert_eq!(absolutize_link("/link?url=abc", "https://www.sogou.com"), "https://www.sogou.com/link?url=abc");
+        assert!(is_sogou_link("https://weixin.sogou.com/link?url=abc&type=2"));
+        assert!(!is_sogou_link("https://www.rust-lang.org/link?url=abc"));
+
+        let wechat = "<script>var url = '';\nurl += 'https://mp.';\nurl += 'weixin.qq.com/s?src=11&timestamp=1@7';\nwindow.location.replace(url)</script>";
+        assert_eq!(extract_target(wechat).as_deref(), Some("https://mp.weixin.qq.com/s?src=11&timestamp=17"));
+        let web = r#"<meta content="always" name="referrer"><script>window.location.replace("https://course.rs/about-book.html")</script>"#;
+        assert_eq!(extract_target(web).as_deref(), Some("https://course.rs/about-book.html"));
+        let meta = r#"<noscript><META http-equiv="refresh" content="0;URL='https://course.rs/'"></noscript>"#;
+        assert_eq!(extract_target(meta).as_deref(), Some("https://course.rs/"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_items_caches_targets() {
+        let transport = Arc::new(
+            MockTransport::new()
+                .respond("url=ok", MockResponse::ok(r#"<script>window.location.replace("https://course.rs/")</script>"#))
+                .respond("url=bad", MockResponse::ok("<html>antispider</html>")),
+        );
+        let resolver = SogouLinkResolver::new(transport.clone());
+
+        let mut items = vec![
+            item("https://www.rust-lang.org/"),
+            item("https://www.sogou.com/link?url=ok"),
+            item("https://www.sogou.com/link?url=bad"),
+        ];
+        resolver.resolve_items(&mut items).await;
+        assert_eq!(items[0].url, "https://www.rust-lang.org/");
+        assert_eq!(items[1].url, "https://course.rs/");
+        assert_eq!(items[1].display_url.as_deref(), Some("https://course.rs/"));
+        assert_eq!(items[2].url, "https://www.sogou.com/link?url=bad");
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(transport.requests()[0].header("referer"), Some("https://www.sogou.com"));
+
+        let mut again = vec![item("https://www.sogou.com/link?url=ok")];
+        resolver.resolve_items(&mut again).await;
+        assert_eq!(again[0].url, "https://course.rs/");
+        assert_eq!(transport.requests().len(), 2);
+    }
+}
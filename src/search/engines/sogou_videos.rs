@@ -21,14 +21,17 @@ use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType,
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    VideoMetadata, embed_url_for, parse_duration, parse_view_count,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
-use super::utils::build_query_string_owned;
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::resolve_url;
+use super::utils::{build_query_string_owned, element_text, select_text, selector};
 
 pub struct SogouVideosEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl SogouVideosEngine {
@@ -38,7 +41,7 @@ impl SogouVideosEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Sogou Videos".to_string(),
@@ -54,6 +57,7 @@ impl SogouVideosEngine {
                     supports_time_range: false,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(30),
                 },
@@ -81,104 +85,76 @@ impl SogouVideosEngine {
     }
 
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
+        use scraper::Html;
 
         if html.is_empty() {
             return Ok(Vec::new());
         }
 
         let document = Html::parse_document(html);
+        let base = url::Url::parse("https://v.sogou.com/").ok();
         let mut items = Vec::with_capacity(10);
 
         // Sogou video results - typical pattern for video listings
-        let result_selector = Selector::parse("div.video-box")
-            .or_else(|_| Selector::parse("div[class*=\"video\"]"))
-            .or_else(|_| Selector::parse("li.vr-item"))
-            .or_else(|_| Selector::parse("div.video-item"))
-            .expect("valid selector");
-
-        for result in document.select(&result_selector) {
+        for result in document.select(selector!("div.video-box")) {
             // Extract title from video title element
-            let title_selector = Selector::parse("h3 a")
-                .or_else(|_| Selector::parse("h4 a"))
-                .or_else(|_| Selector::parse("a.video-title"))
-                .expect("valid selector");
-            let title_elem = result.select(&title_selector).next();
-
-            if title_elem.is_none() {
+            let Some(title_elem) = result.select(selector!("h3 a")).next() else {
                 continue;
-            }
-
-            let title_elem = title_elem.unwrap();
-            let title = title_elem.text().collect::<String>().trim().to_string();
+            };
 
+            let title = element_text(title_elem);
             if title.is_empty() {
                 continue;
             }
 
             // Extract video URL
-            let video_url = title_elem.value().attr("href")
-                .unwrap_or("")
-                .to_string();
-
+            let video_url = title_elem.value().attr("href").unwrap_or("");
             if video_url.is_empty() {
                 continue;
             }
 
             // Extract thumbnail image
-            let img_selector = Selector::parse("img")
-                .expect("valid selector");
-            let thumbnail_url = result.select(&img_selector).next()
+            let thumbnail_url = result.select(selector!("img")).next()
                 .and_then(|img| {
                     img.value().attr("src")
                         .or_else(|| img.value().attr("data-src"))
                         .or_else(|| img.value().attr("data-original"))
                 })
-                .map(|s| {
-                    if s.starts_with("//") {
-                        format!("https:{}", s)
-                    } else if !s.starts_with("http") {
-                        format!("https://v.sogou.com{}", s)
-                    } else {
-                        s.to_string()
-                    }
-                });
+                .and_then(|s| resolve_url(base.as_ref(), s));
 
             // Extract description/content
-            let content_selector = Selector::parse("p.desc")
-                .or_else(|_| Selector::parse("p.video-desc"))
-                .or_else(|_| Selector::parse("span.txt"))
-                .expect("valid selector");
-            let content = result.select(&content_selector).next()
-                .map(|c| c.text().collect::<String>().trim().to_string())
-                .unwrap_or_default();
+            let content = select_text(result, selector!("p.desc")).unwrap_or_default();
 
             // Extract duration if available
-            let duration_selector = Selector::parse("span.duration")
-                .or_else(|_| Selector::parse("span.time"))
-                .expect("valid selector");
-            let duration = result.select(&duration_selector).next()
-                .map(|d| d.text().collect::<String>().trim().to_string())
-                .filter(|d| !d.is_empty());
-
-            let mut metadata = HashMap::new();
-            if let Some(dur) = duration {
-                metadata.insert("duration".to_string(), dur);
-            }
-
-            items.push(SearchResultItem {
-                title,
-                url: video_url.clone(),
-                content,
-                display_url: Some(video_url),
+            let duration_secs = select_text(result, selector!("span.duration"))
+                .and_then(|d| parse_duration(&d));
+
+            // Extract play count if available (e.g. "152.3万次播放")
+            let view_count = select_text(result, selector!("span.play-count, span.num"))
+                .and_then(|v| parse_view_count(&v));
+
+            let video = VideoMetadata {
+                duration_secs,
+                view_count,
+                author: None,
+                embed_url: embed_url_for(video_url),
+            };
+
+            let mut item = SearchResultItem {
+                title: title.into_owned(),
+                url: video_url.to_string(),
+                content: content.into_owned(),
+                display_url: Some(video_url.to_string()),
                 site_name: None,
                 score: 1.0,
                 result_type: ResultType::Video,
                 thumbnail: thumbnail_url,
                 published_date: None,
                 template: None,
-                metadata,
-            });
+                metadata: HashMap::new(),
+            };
+            item.set_video(&video);
+            items.push(item);
         }
 
         Ok(items)
@@ -210,6 +186,10 @@ impl SearchEngine for SogouVideosEngine {
 impl RequestResponseEngine for SogouVideosEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         // Sogou video search URL
         let query_params = vec![
@@ -229,17 +209,10 @@ impl RequestResponseEngine for SogouVideosEngine {
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
 
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
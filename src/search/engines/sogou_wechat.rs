@@ -0,0 +1,252 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::error::Error;
+
+use crate::derive::{
+    EngineCapabilities, EngineInfo, EngineStatus, EngineType,
+    ResultType, SearchEngine, SearchQuery, SearchResult,
+    SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+};
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::resolve_url;
+use super::sogou_link::{absolutize_link, SogouLinkResolver};
+use super::utils::build_query_string_owned;
+use once_cell::sync::Lazy;
+use scraper::Selector;
+
+/// 结果页解析用到的 CSS 选择器（进程内只编译一次）
+struct WechatSelectors {
+    result: Selector,
+    title: Selector,
+    content: Selector,
+    thumbnail: Selector,
+    account: Selector,
+    time: Selector,
+}
+
+static SELECTORS: Lazy<WechatSelectors> = Lazy::new(|| WechatSelectors {
+    result: Selector::parse("li[id^=\"sogou_vr_\"]").expect("valid selector"),
+    title: Selector::parse("h3 a").expect("valid selector"),
+    content: Selector::parse("p.txt-info").expect("valid selector"),
+    thumbnail: Selector::parse("div.img-box img").expect("valid selector"),
+    account: Selector::parse("div.s-p .all-time-y2, div.s-p a.account").expect("valid selector"),
+    time: Selector::parse("div.s-p .s2").expect("valid selector"),
+});
+
+const WECHAT_ORIGIN: &str = "https://weixin.sogou.com";
+
+pub struct SogouWechatEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    resolver: SogouLinkResolver,
+}
+
+impl SogouWechatEngine {
+    pub fn new() -> Self {
+        let client = HttpClient::new(NetworkConfig::default())
+            .unwrap_or_else(|_| panic!("Failed to create HTTP client"));
+        Self::with_client(Arc::new(client))
+    }
+
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            info: EngineInfo {
+                name: "Sogou WeChat".to_string(),
+                engine_type: EngineType::News,
+                description: "Sogou WeChat - WeChat official account article search".to_string(),
+                status: EngineStatus::Active,
+                categories: vec!["news".to_string()],
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::News],
+                    supported_params: vec!["page".to_string()],
+                    max_page_size: 10,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: false,
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: Some(30),
+                },
+                about: AboutInfo {
+                    website: Some("https://weixin.sogou.com/".to_string()),
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: false,
+                    require_api_key: false,
+                    results: "HTML".to_string(),
+                },
+                shortcut: Some("sgw".to_string()),
+                timeout: Some(10),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 10,
+            },
+            resolver: SogouLinkResolver::new(Arc::clone(&client)),
+            client,
+        }
+    }
+
+    fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        use scraper::Html;
+
+        if html.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let document = Html::parse_document(html);
+        let mut items = Vec::with_capacity(10);
+
+        let selectors = &*SELECTORS;
+
+        for result in document.select(&selectors.result) {
+            let Some(title_elem) = result.select(&selectors.title).next() else {
+                continue;
+            };
+
+            let title = title_elem.text().collect::<String>().trim().to_string();
+            let href = title_elem.value().attr("href").unwrap_or("");
+            if title.is_empty() || href.is_empty() {
+                continue;
+            }
+
+            // 文章链接都是跳转链接，search() 中再解析为 mp.weixin.qq.com 地址
+            let url = if href.starts_with('/') {
+                absolutize_link(href, WECHAT_ORIGIN)
+            } else {
+                href.to_string()
+            };
+
+            let content = result.select(&selectors.content).next()
+                .map(|c| c.text().collect::<String>().trim().to_string())
+                .unwrap_or_default();
+
+            let thumbnail = result.select(&selectors.thumbnail).next()
+                .and_then(|img| img.value().attr("src"))
+                .and_then(|src| resolve_url(None, src));
+
+            // 发布时间以脚本形式给出：document.write(timeConvert('1700000000'))
+            let published_date = result.select(&selectors.time).next()
+                .and_then(|span| {
+                    let script = span.inner_html();
+                    let digits: String = script.chars().filter(|c| c.is_ascii_digit()).collect();
+                    digits.parse::<i64>().ok()
+                })
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0));
+
+            let mut metadata = HashMap::new();
+            if let Some(account) = result.select(&selectors.account).next() {
+                let account = account.text().collect::<String>().trim().to_string();
+                if !account.is_empty() {
+                    metadata.insert("author".to_string(), account);
+                }
+            }
+
+            items.push(SearchResultItem {
+                title,
+                url: url.clone(),
+                content,
+                display_url: Some(url),
+                site_name: Some("微信公众号".to_string()),
+                score: 1.0,
+                result_type: ResultType::News,
+                thumbnail,
+                published_date,
+                template: None,
+                metadata,
+            });
+        }
+
+        Ok(items)
+    }
+}
+
+impl Default for SogouWechatEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SearchEngine for SogouWechatEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        let mut result = <Self as RequestResponseEngine>::search(self, query).await?;
+        self.resolver.resolve_items(&mut result.items).await;
+        Ok(result)
+    }
+
+    async fn is_available(&self) -> bool {
+        self.client.get(WECHAT_ORIGIN, None).await.is_ok()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for SogouWechatEngine {
+    type Response = String;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // type=2 为文章搜索，type=1 为公众号搜索
+        let query_params = vec![
+            ("type", "2".to_string()),
+            ("query", query.to_string()),
+            ("page", params.pageno.to_string()),
+            ("ie", "utf8".to_string()),
+        ];
+
+        let query_string = build_query_string_owned(query_params);
+
+        params.url = Some(format!("{}/weixin?{}", WECHAT_ORIGIN, query_string));
+        params.method = "GET".to_string();
+        params.headers.insert("Referer".to_string(), format!("{}/", WECHAT_ORIGIN));
+
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+
+        let response = self.client.get(url, Some(params.request_options())).await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        // 触发反爬时跳转到 /antispider/
+        if response.url().path().starts_with("/antispider") {
+            return Err("Sogou WeChat returned an antispider page".into());
+        }
+
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        Self::parse_html_results(&resp)
+    }
+}
@@ -0,0 +1,343 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Torznab 搜索引擎（需启用 `torrent` feature）
+//!
+//! 通用的 Torznab 客户端，可对接 Jackett、Prowlarr 等索引聚合器或直接支持 Torznab 的站点，
+//! 作为 `files_p2p` 分类的引擎返回种子结果，做种数、下载数、大小和磁力链接写入
+//! [`TorrentMetadata`]。未配置 `endpoint` 时引擎不可用，也不在默认引擎列表中。
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, RequestParams,
+    RequestResponseEngine, ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+    TorrentMetadata,
+};
+use crate::net::client::HttpTransport;
+use crate::net::charset::response_text;
+use crate::rss::parse_feed_date;
+use crate::url_utils::is_http_url;
+use super::utils::build_query_string_owned;
+
+/// 每页结果数
+const PAGE_SIZE: usize = 50;
+
+/// Torznab 引擎配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TorznabConfig {
+    /// Torznab 接口地址，如 `http://127.0.0.1:9117/api/v2.0/indexers/all/results/torznab/api`
+    pub endpoint: Option<String>,
+    /// API 密钥
+    pub api_key: Option<String>,
+    /// 限定的 Torznab 分类编号（为空表示全部）
+    pub categories: Vec<u32>,
+}
+
+static ITEM_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<item\b[^>]*>(.*?)</item>").unwrap());
+static ATTR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<(?:torznab|newznab):attr\s+name="([^"]+)"\s+value="([^"]*)"\s*/?>"#).unwrap()
+});
+static ENCLOSURE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"<enclosure\b[^>]*\burl="([^"]+)""#).unwrap());
+
+/// 提取 `<tag ...>...</tag>` 的文本（去掉 CDATA 并解码实体）
+fn tag_text(block: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}", tag);
+    let mut offset = 0;
+    let start = loop {
+        let pos = offset + block[offset..].find(&open)?;
+        let rest = &block[pos + open.len()..];
+        // 跳过前缀相同的其他标签（如 <link> 与 <linkage>）
+        if rest.starts_with('>') || rest.starts_with(char::is_whitespace) {
+            break pos + open.len() + rest.find('>')? + 1;
+        }
+        offset = pos + open.len();
+    };
+    let end = start + block[start..].find(&format!("</{}>", tag))?;
+    let raw = block[start..end].trim();
+    let raw = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|r| r.strip_suffix("]]>"))
+        .unwrap_or(raw);
+    Some(html_escape::decode_html_entities(raw.trim()).into_owned()).filter(|s| !s.is_empty())
+}
+
+/// 解析 Torznab 响应（RSS 2.0 加 `torznab:attr` 扩展属性）
+fn parse_torznab(xml: &str) -> Vec<SearchResultItem> {
+    let mut items = Vec::new();
+    for captures in ITEM_RE.captures_iter(xml) {
+        let block = &captures[1];
+        let Some(title) = tag_text(block, "title") else {
+            continue;
+        };
+
+        let attrs: HashMap<String, String> = ATTR_RE
+            .captures_iter(block)
+            .map(|c| (c[1].to_ascii_lowercase(), html_escape::decode_html_entities(&c[2]).into_owned()))
+            .collect();
+        let number = |name: &str| attrs.get(name).and_then(|v| v.trim().parse::<u64>().ok());
+
+        let seeders = number("seeders");
+        // Torznab 的 peers 包含做种者
+        let leechers = number("leechers").or_else(|| Some(number("peers")?.saturating_sub(seeders.unwrap_or(0))));
+        let torrent_url = ENCLOSURE_RE
+            .captures(block)
+            .map(|c| html_escape::decode_html_entities(&c[1]).into_owned())
+            .or_else(|| tag_text(block, "link"))
+            .filter(|url| !url.starts_with("magnet:"));
+        let magnet_url = attrs.get("magneturl").cloned()
+            .or_else(|| tag_text(block, "link").filter(|url| url.starts_with("magnet:")));
+        let torrent = TorrentMetadata {
+            seeders,
+            leechers,
+            size_bytes: number("size").or_else(|| tag_text(block, "size")?.parse().ok()),
+            magnet_url,
+            torrent_url,
+            info_hash: attrs.get("infohash").map(|h| h.to_ascii_lowercase()),
+            files: number("files"),
+        };
+
+        // 优先链接到详情页，其次是磁力链接或种子文件
+        let Some(url) = tag_text(block, "comments")
+            .filter(|url| is_http_url(url))
+            .or_else(|| torrent.magnet_url.clone())
+            .or_else(|| torrent.torrent_url.clone())
+        else {
+            continue;
+        };
+
+        let mut item = SearchResultItem {
+            title,
+            url,
+            content: tag_text(block, "description").unwrap_or_default(),
+            display_url: None,
+            site_name: attrs.get("indexer").cloned().or_else(|| tag_text(block, "jackettindexer")),
+            score: 1.0,
+            result_type: ResultType::Torrent,
+            thumbnail: None,
+            published_date: tag_text(block, "pubDate").as_deref().and_then(parse_feed_date),
+            template: None,
+            metadata: HashMap::new(),
+        };
+        item.set_torrent(&torrent);
+        items.push(item);
+    }
+    items
+}
+
+/// Torznab 搜索引擎
+pub struct TorznabEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    config: TorznabConfig,
+}
+
+impl TorznabEngine {
+    /// 使用配置创建引擎
+    pub fn new(client: Arc<dyn HttpTransport>, config: TorznabConfig) -> Self {
+        Self {
+            info: EngineInfo {
+                name: "Torznab".to_string(),
+                engine_type: EngineType::Custom,
+                description: "Torznab - generic client for torrent indexers (Jackett, Prowlarr)".to_string(),
+                status: EngineStatus::Active,
+                categories: vec!["files_p2p".to_string()],
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Torrent],
+                    supported_params: vec!["page".to_string()],
+                    max_page_size: PAGE_SIZE,
+                    supports_pagination: true,
+                    supports_time_range: false,
+                    supports_language_filter: false,
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: Some("https://torznab.github.io/spec-1.3-draft/".to_string()),
+                    use_official_api: true,
+                    require_api_key: true,
+                    results: "XML".to_string(),
+                },
+                shortcut: Some("tz".to_string()),
+                timeout: Some(20),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: 10,
+            },
+            client,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for TorznabEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        let Some(endpoint) = &self.config.endpoint else {
+            return false;
+        };
+        let url = format!("{}?t=caps", endpoint);
+        self.client.get(&url, None).await.is_ok()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for TorznabEngine {
+    type Response = String;
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let endpoint = self.config.endpoint.as_deref().ok_or("torznab engine has no configured endpoint")?;
+        let mut query_params = vec![
+            ("t", "search".to_string()),
+            ("q", query.to_string()),
+            ("offset", ((params.pageno - 1) * PAGE_SIZE).to_string()),
+            ("limit", PAGE_SIZE.to_string()),
+        ];
+        if let Some(api_key) = &self.config.api_key {
+            query_params.push(("apikey", api_key.clone()));
+        }
+        if !self.config.categories.is_empty() {
+            let categories: Vec<String> = self.config.categories.iter().map(u32::to_string).collect();
+            query_params.push(("cat", categories.join(",")));
+        }
+
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        params.url = Some(format!("{}{}{}", endpoint, separator, build_query_string_owned(query_params)));
+        params.method = "GET".to_string();
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        let response = self.client.get(url, Some(params.request_options())).await
+            .map_err(|e| format!("Request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Torznab endpoint returned {}", response.status()).into());
+        }
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
+    }
+
+    fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        // Torznab 用 <error code="..." description="..."/> 报告错误（如 API 密钥无效）
+        if let Some(start) = resp.find("<error") {
+            let end = resp[start..].find('>').map(|e| start + e + 1).unwrap_or(resp.len());
+            return Err(format!("Torznab error: {}", &resp[start..end]).into());
+        }
+        Ok(parse_torznab(&resp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    const RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:torznab="http://torznab.com/schemas/2015/feed">
+  <channel>
+    <title>Jackett</title>
+    <item>
+      <title><![CDATA[Ubuntu 24.04 LTS Desktop amd64]]></title>
+      <guid>https://indexer.example/details/42</guid>
+      <jackettindexer id="example">Example Indexer</jackettindexer>
+      <comments>https://indexer.example/details/42</comments>
+      <pubDate>Thu, 25 Apr 2024 12:00:00 +0000</pubDate>
+      <size>6114656256</size>
+      <link>https://indexer.example/download/42?key=a&amp;b=1</link>
+      <enclosure url="https://indexer.example/download/42?key=a&amp;b=1" length="6114656256" type="application/x-bittorrent" />
+      <torznab:attr name="seeders" value="1200" />
+      <torznab:attr name="peers" value="1350" />
+      <torznab:attr name="infohash" value="ABCDEF0123456789ABCDEF0123456789ABCDEF01" />
+      <torznab:attr name="magneturl" value="magnet:?xt=urn:btih:abcdef0123456789abcdef0123456789abcdef01&amp;dn=ubuntu" />
+      <torznab:attr name="files" value="1" />
+    </item>
+    <item>
+      <title>Magnet only</title>
+      <link>magnet:?xt=urn:btih:0000</link>
+      <torznab:attr name="seeders" value="3" />
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_torznab_items() {
+        let items = parse_torznab(RESPONSE);
+        assert_eq!(items.len(), 2);
+
+        let torrent = items[0].torrent().unwrap();
+        assert_eq!(items[0].title, "Ubuntu 24.04 LTS Desktop amd64");
+        assert_eq!(items[0].url, "https://indexer.example/details/42");
+        assert_eq!(items[0].result_type, ResultType::Torrent);
+        assert_eq!(items[0].site_name.as_deref(), Some("Example Indexer"));
+        assert!(items[0].published_date.is_some());
+        assert_eq!(torrent.seeders, Some(1200));
+        assert_eq!(torrent.leechers, Some(150));
+        assert_eq!(torrent.size_bytes, Some(6114656256));
+        assert_eq!(torrent.torrent_url.as_deref(), Some("https://indexer.example/download/42?key=a&b=1"));
+        assert_eq!(torrent.magnet_url.as_deref(), Some("magnet:?xt=urn:btih:abcdef0123456789abcdef0123456789abcdef01&dn=ubuntu"));
+        assert_eq!(torrent.info_hash.as_deref(), Some("abcdef0123456789abcdef0123456789abcdef01"));
+
+        assert_eq!(items[1].url, "magnet:?xt=urn:btih:0000");
+        assert_eq!(items[1].torrent().unwrap().torrent_url, None);
+    }
+
+    #[tokio::test]
+    async fn test_torznab_search_request() {
+        let transport = Arc::new(MockTransport::new().respond("indexer.example/api", MockResponse::ok(RESPONSE)));
+        let engine = TorznabEngine::new(transport.clone(), TorznabConfig {
+            endpoint: Some("https://indexer.example/api".to_string()),
+            api_key: Some("secret".to_string()),
+            categories: vec![2000, 5000],
+        });
+        let result = SearchEngine::search(&engine, &SearchQuery { query: "ubuntu".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(result.items.len(), 2);
+
+        let url = &transport.requests()[0].url;
+        assert!(url.contains("t=search") && url.contains("q=ubuntu") && url.contains("apikey=secret"));
+        assert!(url.contains("cat=2000%2C5000"));
+
+        let error = engine.response(r#"<error code="100" description="Invalid API Key" />"#.to_string()).unwrap_err();
+        assert!(error.to_string().contains("Invalid API Key"));
+    }
+}
@@ -22,14 +22,27 @@ use crate::derive::{
     EngineCapabilities, EngineInfo, EngineStatus, EngineType, 
     ResultType, SearchEngine, SearchQuery, SearchResult, 
     SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
+    ImageMetadata,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
 use super::utils::build_query_string_owned;
 
+/// Unsplash License 说明页
+const UNSPLASH_LICENSE_URL: &str = "https://unsplash.com/license";
+
+/// 官方 API 地址
+const UNSPLASH_API_URL: &str = "https://api.unsplash.com";
+
+/// 官方 API 要求指向 Unsplash 的链接带上的来源参数
+const UNSPLASH_REFERRAL: &str = "utm_source=seesea&utm_medium=referral";
+
 pub struct UnsplashEngine {
     info: EngineInfo,
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
+    /// 官方 API 的 Access Key，配置后改用官方 JSON API
+    access_key: Option<String>,
 }
 
 impl UnsplashEngine {
@@ -39,7 +52,7 @@ impl UnsplashEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Unsplash".to_string(),
@@ -55,6 +68,7 @@ impl UnsplashEngine {
                     supports_time_range: false,
                     supports_language_filter: false,
                     supports_region_filter: false,
+                    supported_regions: Vec::new(),
                     supports_safe_search: false,
                     rate_limit: Some(50),
                 },
@@ -78,7 +92,30 @@ impl UnsplashEngine {
                 max_page: 10,
             },
             client,
+            access_key: None,
+        }
+    }
+
+    /// 使用官方 API 的 Access Key 创建引擎
+    pub fn with_access_key(client: Arc<dyn HttpTransport>, access_key: impl Into<String>) -> Self {
+        let mut engine = Self::with_client(client);
+        engine.access_key = Some(access_key.into()).filter(|key: &String| !key.trim().is_empty());
+        if engine.access_key.is_some() {
+            engine.info.about.use_official_api = true;
+            engine.info.about.require_api_key = true;
         }
+        engine
+    }
+
+    /// 是否使用官方 API
+    pub fn uses_official_api(&self) -> bool {
+        self.access_key.is_some()
+    }
+
+    /// 按 API 使用规范为指向 Unsplash 的链接加上来源参数
+    fn with_referral(url: &str) -> String {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", url, separator, UNSPLASH_REFERRAL)
     }
 
     /// Clean URL by removing ixid parameter
@@ -115,7 +152,11 @@ impl UnsplashEngine {
         }
     }
 
-    fn parse_json_result(json_str: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+    /// 解析搜索结果
+    ///
+    /// 网页接口（napi）与官方 API 返回的结构相同；`official` 时按 API 规范为
+    /// 图片页和摄影师主页链接加上来源参数
+    fn parse_json_result(json_str: &str, official: bool) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
         let api_result: Value = serde_json::from_str(json_str)?;
         let mut items = Vec::new();
 
@@ -132,7 +173,10 @@ impl UnsplashEngine {
                     continue;
                 }
                 
-                let url = Self::clean_url(url_raw);
+                let mut url = Self::clean_url(url_raw);
+                if official {
+                    url = Self::with_referral(&url);
+                }
 
                 // Python: 'title': result.get('alt_description') or 'unknown'
                 let title = result.get("alt_description")
@@ -153,34 +197,37 @@ impl UnsplashEngine {
                     .and_then(|t| t.as_str())
                     .map(|s| Self::clean_url(s));
 
+                let published_date = result.get("created_at")
+                    .and_then(|c| c.as_str())
+                    .and_then(|c| chrono::DateTime::parse_from_rfc3339(c).ok())
+                    .map(|c| c.with_timezone(&chrono::Utc));
+
                 // Python: 'img_src': clean_url(result['urls']['regular'])
                 // 'template': 'images.html'
-                let mut metadata = HashMap::new();
-                if let Some(img_src) = result.get("urls").and_then(|u| u.get("regular")).and_then(|r| r.as_str()) {
-                    metadata.insert("img_src".to_string(), Self::clean_url(img_src));
-                }
-                
-                // Additional metadata from Unsplash
-                if let Some(user) = result.get("user") {
-                    if let Some(username) = user.get("name").and_then(|n| n.as_str()) {
-                        metadata.insert("photographer".to_string(), format!("by {}", username));
-                    }
-                    if let Some(profile_url) = user.get("links").and_then(|l| l.get("html")).and_then(|h| h.as_str()) {
-                        metadata.insert("photographer_url".to_string(), profile_url.to_string());
-                    }
-                }
-                
-                if let Some(width) = result.get("width").and_then(|w| w.as_i64()) {
-                    metadata.insert("width".to_string(), width.to_string());
-                }
-                if let Some(height) = result.get("height").and_then(|h| h.as_i64()) {
-                    metadata.insert("height".to_string(), height.to_string());
-                }
-                if let Some(color) = result.get("color").and_then(|c| c.as_str()) {
-                    metadata.insert("color".to_string(), color.to_string());
-                }
+                let user = result.get("user");
+                let image = ImageMetadata {
+                    image_url: result.get("urls")
+                        .and_then(|u| u.get("regular"))
+                        .and_then(|r| r.as_str())
+                        .map(Self::clean_url),
+                    width: result.get("width").and_then(|w| w.as_u64()).and_then(|w| u32::try_from(w).ok()),
+                    height: result.get("height").and_then(|h| h.as_u64()).and_then(|h| u32::try_from(h).ok()),
+                    format: None,
+                    source_page: Some(url.clone()),
+                    source: Some("Unsplash".to_string()),
+                    author: user.and_then(|u| u.get("name")).and_then(|n| n.as_str()).map(str::to_string),
+                    author_url: user
+                        .and_then(|u| u.get("links"))
+                        .and_then(|l| l.get("html"))
+                        .and_then(|h| h.as_str())
+                        .map(|h| if official { Self::with_referral(h) } else { h.to_string() }),
+                    // Unsplash 上的图片统一使用 Unsplash License
+                    license: Some("Unsplash License".to_string()),
+                    license_url: Some(UNSPLASH_LICENSE_URL.to_string()),
+                    color: result.get("color").and_then(|c| c.as_str()).map(str::to_string),
+                };
 
-                items.push(SearchResultItem {
+                let mut item = SearchResultItem {
                     title,
                     url: url.clone(),
                     content,
@@ -189,10 +236,12 @@ impl UnsplashEngine {
                     score: 1.0,
                     result_type: ResultType::Image,
                     thumbnail,
-                    published_date: None,
+                    published_date,
                     template: Some("images.html".to_string()), // Python: 'template': 'images.html'
-                    metadata,
-                });
+                    metadata: HashMap::new(),
+                };
+                item.set_image(&image);
+                items.push(item);
             }
         }
 
@@ -225,6 +274,10 @@ impl SearchEngine for UnsplashEngine {
 impl RequestResponseEngine for UnsplashEngine {
     type Response = String;
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         // Python: params['url'] = search_url + urlencode({'query': query, 'page': params['pageno'], 'per_page': page_size})
         // search_url = base_url + 'napi/search/photos?'
@@ -236,9 +289,19 @@ impl RequestResponseEngine for UnsplashEngine {
             ("per_page", "20".to_string()),
         ];
 
-        let query_string = build_query_string_owned(query_params.into_iter());
-        
-        params.url = Some(format!("https://unsplash.com/napi/search/photos?{}", query_string));
+        let query_string = build_query_string_owned(query_params);
+
+        match &self.access_key {
+            Some(access_key) => {
+                // 官方 API：https://unsplash.com/documentation#search-photos
+                params.url = Some(format!("{}/search/photos?{}", UNSPLASH_API_URL, query_string));
+                params.headers.insert("Authorization".to_string(), format!("Client-ID {}", access_key));
+                params.headers.insert("Accept-Version".to_string(), "v1".to_string());
+            }
+            None => {
+                params.url = Some(format!("https://unsplash.com/napi/search/photos?{}", query_string));
+            }
+        }
         params.method = "GET".to_string();
 
         Ok(())
@@ -246,21 +309,23 @@ impl RequestResponseEngine for UnsplashEngine {
 
     async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
         let url = params.url.as_ref().ok_or("URL not set")?;
-        
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-        
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
 
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
-        response.text().await.map_err(|e| format!("Failed to read response: {}", e).into())
+        // 官方 API 在密钥无效时返回 401，超出每小时配额时返回 403
+        if self.uses_official_api() {
+            match response.status().as_u16() {
+                401 => return Err("Unsplash API rejected the access key".into()),
+                403 => return Err("Unsplash API rate limit exceeded".into()),
+                _ => {}
+            }
+        }
+
+        response_text(response).await.map_err(|e| format!("Failed to read response: {}", e).into())
     }
 
     fn response(&self, resp: Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        Self::parse_json_result(&resp)
+        Self::parse_json_result(&resp, self.uses_official_api())
     }
 }
@@ -19,6 +19,28 @@
 
 use std::borrow::Cow;
 
+use scraper::{ElementRef, Selector};
+
+/// Compile a CSS selector once and reuse it for every call
+///
+/// Expands to a `&'static Selector` backed by a lazily initialised static at
+/// the call site, so parsing loops no longer re-parse the same selector for
+/// every result row. Panics on first use if the selector is invalid.
+///
+/// ```ignore
+/// for row in document.select(selector!("li.res-list")) {
+///     let title = select_text(row, selector!("h3 a"));
+/// }
+/// ```
+macro_rules! selector {
+    ($css:expr) => {{
+        static SELECTOR: once_cell::sync::Lazy<scraper::Selector> =
+            once_cell::sync::Lazy::new(|| scraper::Selector::parse($css).expect("valid selector"));
+        &*SELECTOR
+    }};
+}
+pub(crate) use selector;
+
 /// Build a URL query string efficiently with pre-allocated capacity
 ///
 /// This function builds query strings more efficiently than the iterator-collect-join pattern
@@ -122,6 +144,99 @@ where
     result
 }
 
+/// Trimmed text content of an HTML element
+///
+/// Equivalent to `element.text().collect::<String>().trim()`, but borrows
+/// from the document when the element has a single non-blank text node
+/// (the common case for titles and links) instead of allocating.
+pub fn element_text(element: ElementRef<'_>) -> Cow<'_, str> {
+    let mut fragments = element.text().filter(|t| !t.trim().is_empty());
+    let Some(first) = fragments.next() else {
+        return Cow::Borrowed("");
+    };
+    if fragments.next().is_none() {
+        return Cow::Borrowed(first.trim());
+    }
+    // Several fragments: concatenate everything, keeping inner whitespace
+    Cow::Owned(element.text().collect::<String>().trim().to_string())
+}
+
+/// Trimmed text of the first element matching `selector`
+///
+/// Returns `None` when nothing matches; a matching but empty element
+/// yields `Some("")`.
+pub fn select_text<'a>(element: ElementRef<'a>, selector: &Selector) -> Option<Cow<'a, str>> {
+    element.select(selector).next().map(element_text)
+}
+
+/// Attribute value of the first element matching `selector`
+///
+/// The value is borrowed from the document.
+pub fn select_attr<'a>(element: ElementRef<'a>, selector: &Selector, attr: &str) -> Option<&'a str> {
+    element.select(selector).next().and_then(|e| e.value().attr(attr))
+}
+
+/// Trimmed texts of all elements matching `selector`, joined with spaces
+pub fn select_all_text(element: ElementRef<'_>, selector: &Selector) -> String {
+    let mut joined = String::new();
+    for text in element.select(selector).map(element_text) {
+        if text.is_empty() {
+            continue;
+        }
+        if !joined.is_empty() {
+            joined.push(' ');
+        }
+        joined.push_str(&text);
+    }
+    joined
+}
+
+/// Build a single `Cookie` header value from a cookie map
+///
+/// Cookies are sorted by name so the header is deterministic.
+/// Returns `None` when there are no cookies.
+pub fn cookie_header(cookies: &std::collections::HashMap<String, String>) -> Option<String> {
+    if cookies.is_empty() {
+        return None;
+    }
+    let mut pairs: Vec<_> = cookies.iter().collect();
+    pairs.sort_by_key(|(name, _)| name.as_str());
+    Some(
+        pairs
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+/// Placeholder values for declaratively configured engines
+///
+/// Provides `{query}`, `{page}` (counted from `first_page`), `{offset}`,
+/// `{page_size}` and `{language}`. `escape` is applied to the query and
+/// language, e.g. URL encoding for URL templates.
+pub fn template_vars(
+    query: &str,
+    params: &crate::derive::RequestParams,
+    first_page: usize,
+    page_size: usize,
+    escape: impl Fn(&str) -> String,
+) -> Vec<(&'static str, String)> {
+    let page = params.pageno - 1;
+    vec![
+        ("query", escape(query)),
+        ("page", (page + first_page).to_string()),
+        ("offset", (page * page_size).to_string()),
+        ("page_size", page_size.to_string()),
+        ("language", escape(params.language.as_deref().unwrap_or(""))),
+    ]
+}
+
+/// Replace `{name}` placeholders in a template
+pub fn render_template(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter().fold(template.to_string(), |text, (key, value)| text.replace(&format!("{{{}}}", key), value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +275,23 @@ mod tests {
         assert_eq!(result, "Hello world !");
     }
 
+    #[test]
+    fn test_element_text_borrows_single_fragment() {
+        let html = scraper::Html::parse_fragment(
+            r#"<div><h3><a href="/a">  Title  </a></h3><p>Hello <em>big</em> world</p><span> </span></div>"#,
+        );
+        let root = html.root_element();
+
+        let title = select_text(root, selector!("h3 a")).unwrap();
+        assert!(matches!(title, Cow::Borrowed("Title")));
+        assert_eq!(select_text(root, selector!("p")).unwrap(), "Hello big world");
+        assert_eq!(select_text(root, selector!("span")).unwrap(), "");
+        assert!(select_text(root, selector!("cite")).is_none());
+        assert_eq!(select_attr(root, selector!("h3 a"), "href"), Some("/a"));
+        assert_eq!(select_attr(root, selector!("h3 a"), "title"), None);
+        assert_eq!(select_all_text(root, selector!("h3 a, span, em")), "Title big");
+    }
+
     #[test]
     fn test_collect_text_empty() {
         let fragments: Vec<&str> = vec![];
@@ -0,0 +1,563 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 声明式 HTML 抓取引擎
+//!
+//! 与 SearXNG 的 xpath 引擎类似：URL 模板、结果容器和各字段的选择器、分页规则都写在
+//! 配置里，无需改代码即可原型化新的 HTML 引擎。
+//!
+//! 选择器可以写 CSS（如 `div.result`、`a.title@href`，`@属性` 后缀表示取属性），
+//! 也可以写常用的 XPath 子集（以 `/` 或 `.` 开头）：
+//!
+//! - 轴：`//`、`/`、`.//`、`./`，步骤为标签名或 `*`
+//! - 谓词：`[@a]`、`[@a="v"]`、`[contains(@a, "v")]`、`[starts-with(@a, "v")]`、
+//!   `[contains(concat(" ", normalize-space(@class), " "), " v ")]`、`[n]`，可用 `and` 连接
+//! - 结尾：`/@href` 取属性，`/text()` 取文本
+//!
+//! XPath 在加载配置时被转换为 CSS 选择器，不支持的写法会让引擎不可用并给出原因。
+
+use async_trait::async_trait;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::derive::{
+    AboutInfo, EngineCapabilities, EngineInfo, EngineStatus, EngineType, RequestParams, RequestResponseEngine,
+    ResultType, SearchEngine, SearchQuery, SearchResult, SearchResultItem,
+};
+use crate::net::client::HttpTransport;
+use crate::net::charset::response_text;
+use crate::url_utils::resolve_url;
+use super::utils::{element_text, render_template, template_vars};
+
+/// 结果字段的选择器（相对于结果容器）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XPathFields {
+    /// 标题
+    pub title: String,
+    /// 链接（相对链接按页面地址补全，缺失时丢弃该结果）
+    pub url: String,
+    /// 摘要
+    pub content: Option<String>,
+    /// 缩略图
+    pub thumbnail: Option<String>,
+}
+
+impl Default for XPathFields {
+    fn default() -> Self {
+        Self {
+            title: "a".to_string(),
+            url: "a@href".to_string(),
+            content: None,
+            thumbnail: None,
+        }
+    }
+}
+
+/// HTML 抓取引擎配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XPathConfig {
+    /// 引擎名（请求中用此名称选择引擎，不能与内置引擎重名）
+    pub name: String,
+    /// 引擎类型
+    pub engine_type: EngineType,
+    /// 分类
+    pub categories: Vec<String>,
+    /// 快捷方式（可作为 bang 使用）
+    pub shortcut: Option<String>,
+    /// URL 模板，占位符同 JSON API 引擎
+    pub url: String,
+    /// 请求头模板
+    pub headers: HashMap<String, String>,
+    /// Cookie 模板
+    pub cookies: HashMap<String, String>,
+    /// 是否支持翻页（为 false 时第一页之后不再请求）
+    pub paging: bool,
+    /// 第一页的页码
+    pub first_page: usize,
+    /// 每页结果数（用于 `{page_size}` 和 `{offset}`）
+    pub page_size: usize,
+    /// 结果容器的选择器
+    pub results: String,
+    /// 结果字段的选择器
+    pub fields: XPathFields,
+    /// 超时时间（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for XPathConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            engine_type: EngineType::General,
+            categories: vec!["general".to_string()],
+            shortcut: None,
+            url: String::new(),
+            headers: HashMap::new(),
+            cookies: HashMap::new(),
+            paging: true,
+            first_page: 1,
+            page_size: 10,
+            results: String::new(),
+            fields: XPathFields::default(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// 字段取值方式
+#[derive(Debug, Clone, PartialEq)]
+enum Extract {
+    Text,
+    Attr(String),
+}
+
+/// 编译后的字段选择器（selector 为 None 时取结果容器本身）
+#[derive(Debug)]
+struct FieldSelector {
+    selector: Option<Selector>,
+    extract: Extract,
+}
+
+impl FieldSelector {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let (css, extract) = to_css(spec)?;
+        let selector = match css.as_str() {
+            "" | ":scope" => None,
+            css => Some(Selector::parse(css).map_err(|e| format!("invalid selector '{}': {}", spec, e))?),
+        };
+        Ok(Self { selector, extract })
+    }
+
+    /// 取第一个匹配元素的文本或属性
+    fn value(&self, container: ElementRef<'_>) -> Option<String> {
+        let element = match &self.selector {
+            Some(selector) => container.select(selector).next()?,
+            None => container,
+        };
+        let value = match &self.extract {
+            Extract::Text => element_text(element).into_owned(),
+            Extract::Attr(attr) => element.value().attr(attr)?.trim().to_string(),
+        };
+        (!value.is_empty()).then_some(value)
+    }
+}
+
+/// 把选择器（CSS 或 XPath 子集）转换为 CSS 选择器和取值方式
+fn to_css(spec: &str) -> Result<(String, Extract), String> {
+    let spec = spec.trim();
+    if spec.starts_with('/') || spec.starts_with('.') {
+        return xpath_to_css(spec);
+    }
+    // CSS 中不会出现 `@`，`@` 之后为要取的属性
+    match spec.rsplit_once('@') {
+        Some((css, attr)) if !attr.is_empty() => Ok((css.trim().to_string(), Extract::Attr(attr.trim().to_string()))),
+        _ => Ok((spec.to_string(), Extract::Text)),
+    }
+}
+
+/// 按 `/` 切分 XPath 步骤（忽略谓词和引号中的 `/`），`//` 产生空步骤
+fn split_steps(path: &str) -> Vec<&str> {
+    let mut steps = Vec::new();
+    let (mut depth, mut quote, mut start) = (0usize, None, 0);
+    for (i, c) in path.char_indices() {
+        match c {
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            '[' if quote.is_none() => depth += 1,
+            ']' if quote.is_none() => depth = depth.saturating_sub(1),
+            '/' if quote.is_none() && depth == 0 => {
+                steps.push(&path[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    steps.push(&path[start..]);
+    steps
+}
+
+/// 转换单个谓词（不含方括号）
+fn predicate_to_css(predicate: &str) -> Result<String, String> {
+    static CLASS_TOKEN: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+        Regex::new(r#"^contains\(\s*concat\(\s*["'] ["']\s*,\s*normalize-space\(\s*@class\s*\)\s*,\s*["'] ["']\s*\)\s*,\s*["'] ([^ "']+) ["']\s*\)$"#)
+            .expect("valid regex")
+    });
+    static FUNCTION: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+        Regex::new(r#"^(contains|starts-with)\(\s*@([\w:-]+)\s*,\s*["']([^"']*)["']\s*\)$"#).expect("valid regex")
+    });
+    static ATTRIBUTE: once_cell::sync::Lazy<Regex> = once_cell::sync::Lazy::new(|| {
+        Regex::new(r#"^@([\w:-]+)(?:\s*=\s*["']([^"']*)["'])?$"#).expect("valid regex")
+    });
+
+    let mut css = String::new();
+    for part in predicate.split(" and ").map(str::trim) {
+        if let Some(caps) = CLASS_TOKEN.captures(part) {
+            css.push_str(&format!("[class~=\"{}\"]", &caps[1]));
+        } else if let Some(caps) = FUNCTION.captures(part) {
+            let operator = if &caps[1] == "contains" { "*=" } else { "^=" };
+            css.push_str(&format!("[{}{}\"{}\"]", &caps[2], operator, &caps[3]));
+        } else if let Some(caps) = ATTRIBUTE.captures(part) {
+            match caps.get(2) {
+                Some(value) => css.push_str(&format!("[{}=\"{}\"]", &caps[1], value.as_str())),
+                None => css.push_str(&format!("[{}]", &caps[1])),
+            }
+        } else if let Ok(position) = part.parse::<usize>() {
+            css.push_str(&format!(":nth-of-type({})", position));
+        } else {
+            return Err(format!("unsupported XPath predicate '{}'", part));
+        }
+    }
+    Ok(css)
+}
+
+/// 把 XPath 子集转换为 CSS 选择器
+fn xpath_to_css(xpath: &str) -> Result<(String, Extract), String> {
+    let unsupported = |reason: &str| format!("unsupported XPath '{}': {}", xpath, reason);
+    // 起始位置：相对路径以 `:scope` 为起点，绝对路径 `/x` 从文档根开始
+    let (mut css, rest, mut root) = if xpath == "." {
+        (":scope".to_string(), "", false)
+    } else if let Some(rest) = xpath.strip_prefix(".//") {
+        (String::new(), rest, false)
+    } else if let Some(rest) = xpath.strip_prefix("./") {
+        (":scope >".to_string(), rest, false)
+    } else if let Some(rest) = xpath.strip_prefix("//") {
+        (String::new(), rest, false)
+    } else if let Some(rest) = xpath.strip_prefix('/') {
+        (String::new(), rest, true)
+    } else {
+        return Err(unsupported("expected a path starting with '/' or '.'"));
+    };
+
+    let steps = if rest.is_empty() { Vec::new() } else { split_steps(rest) };
+    let mut extract = Extract::Text;
+    let mut descendant = false;
+    for (index, step) in steps.iter().enumerate() {
+        let step = step.trim();
+        let last = index + 1 == steps.len();
+        if step.is_empty() {
+            descendant = true;
+            continue;
+        }
+        if let Some(attr) = step.strip_prefix('@') {
+            if !last {
+                return Err(unsupported("attribute step must be last"));
+            }
+            extract = Extract::Attr(attr.to_string());
+            break;
+        }
+        if step == "text()" {
+            if !last {
+                return Err(unsupported("text() must be last"));
+            }
+            break;
+        }
+
+        let (name, predicates) = match step.find('[') {
+            Some(bracket) => (&step[..bracket], &step[bracket..]),
+            None => (step, ""),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '*' || c == '-') {
+            return Err(unsupported(&format!("invalid step '{}'", step)));
+        }
+        let mut compound = name.to_string();
+        if root {
+            compound.push_str(":root");
+            root = false;
+        }
+        let mut remaining = predicates;
+        while let Some(inner) = remaining.strip_prefix('[') {
+            let end = split_steps_end(inner).ok_or_else(|| unsupported("unclosed predicate"))?;
+            compound.push_str(&predicate_to_css(&inner[..end])?);
+            remaining = &inner[end + 1..];
+        }
+        if !remaining.is_empty() {
+            return Err(unsupported(&format!("invalid step '{}'", step)));
+        }
+
+        if !css.is_empty() && !css.ends_with('>') {
+            css.push_str(if descendant { " " } else { " > " });
+        } else if css.ends_with('>') {
+            css.push(' ');
+        }
+        css.push_str(&compound);
+        descendant = false;
+    }
+    // `./@href` 没有元素步骤，取结果容器本身
+    Ok((css.trim_end_matches('>').trim().to_string(), extract))
+}
+
+/// 谓词结束的 `]` 位置（忽略引号中的内容和嵌套的方括号）
+fn split_steps_end(predicate: &str) -> Option<usize> {
+    let (mut depth, mut quote) = (0usize, None);
+    for (i, c) in predicate.char_indices() {
+        match c {
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() => quote = Some(c),
+            '[' if quote.is_none() => depth += 1,
+            ']' if quote.is_none() && depth == 0 => return Some(i),
+            ']' if quote.is_none() => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 编译后的选择器
+#[derive(Debug)]
+struct CompiledSelectors {
+    results: Selector,
+    title: FieldSelector,
+    url: FieldSelector,
+    content: Option<FieldSelector>,
+    thumbnail: Option<FieldSelector>,
+}
+
+impl CompiledSelectors {
+    fn compile(config: &XPathConfig) -> Result<Self, String> {
+        let (results, extract) = to_css(&config.results)?;
+        if extract != Extract::Text || results.is_empty() {
+            return Err(format!("results selector '{}' must select elements", config.results));
+        }
+        let fields = &config.fields;
+        Ok(Self {
+            results: Selector::parse(&results).map_err(|e| format!("invalid selector '{}': {}", config.results, e))?,
+            title: FieldSelector::parse(&fields.title)?,
+            url: FieldSelector::parse(&fields.url)?,
+            content: fields.content.as_deref().map(FieldSelector::parse).transpose()?,
+            thumbnail: fields.thumbnail.as_deref().map(FieldSelector::parse).transpose()?,
+        })
+    }
+}
+
+/// 解析结果页面，相对链接按 `base` 补全
+fn parse_page(selectors: &CompiledSelectors, html: &str, base: Option<&url::Url>) -> Vec<SearchResultItem> {
+    let document = Html::parse_document(html);
+    document
+        .select(&selectors.results)
+        .filter_map(|container| {
+            let url = resolve_url(base, &selectors.url.value(container)?)?;
+            Some(SearchResultItem {
+                title: selectors.title.value(container).unwrap_or_else(|| url.clone()),
+                url,
+                content: selectors.content.as_ref().and_then(|s| s.value(container)).unwrap_or_default(),
+                display_url: None,
+                site_name: None,
+                score: 1.0,
+                result_type: ResultType::Web,
+                thumbnail: selectors.thumbnail.as_ref().and_then(|s| s.value(container)).and_then(|link| resolve_url(base, &link)),
+                published_date: None,
+                template: None,
+                metadata: HashMap::new(),
+            })
+        })
+        .collect()
+}
+
+/// 配置驱动的 HTML 抓取引擎
+pub struct XPathEngine {
+    info: EngineInfo,
+    client: Arc<dyn HttpTransport>,
+    config: XPathConfig,
+    selectors: Result<CompiledSelectors, String>,
+}
+
+impl XPathEngine {
+    /// 使用配置创建引擎（选择器无效时引擎不可用，搜索时返回原因）
+    pub fn new(client: Arc<dyn HttpTransport>, config: XPathConfig) -> Self {
+        let selectors = CompiledSelectors::compile(&config);
+        if let Err(e) = &selectors {
+            tracing::warn!("XPath engine '{}' is misconfigured: {}", config.name, e);
+        }
+        Self {
+            info: EngineInfo {
+                name: config.name.clone(),
+                engine_type: config.engine_type,
+                description: format!("HTML scraping engine ({})", config.url),
+                status: EngineStatus::Active,
+                categories: config.categories.clone(),
+                capabilities: EngineCapabilities {
+                    result_types: vec![ResultType::Web],
+                    supported_params: vec!["language".to_string()],
+                    max_page_size: config.page_size,
+                    supports_pagination: config.paging,
+                    supports_time_range: false,
+                    supports_language_filter: config.url.contains("{language}"),
+                    supports_region_filter: false,
+                    supported_regions: Vec::new(),
+                    supports_safe_search: false,
+                    rate_limit: None,
+                },
+                about: AboutInfo {
+                    website: None,
+                    wikidata_id: None,
+                    official_api_documentation: None,
+                    use_official_api: false,
+                    require_api_key: false,
+                    results: "HTML".to_string(),
+                },
+                shortcut: config.shortcut.clone(),
+                timeout: Some(config.timeout_secs),
+                disabled: false,
+                inactive: false,
+                version: Some("1.0.0".to_string()),
+                last_checked: None,
+                using_tor_proxy: false,
+                display_error_messages: true,
+                tokens: Vec::new(),
+                max_page: if config.paging { 0 } else { 1 },
+            },
+            client,
+            config,
+            selectors,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for XPathEngine {
+    fn info(&self) -> &EngineInfo {
+        &self.info
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchResult, Box<dyn Error + Send + Sync>> {
+        if !self.config.paging && query.page > 1 {
+            return Ok(SearchResult {
+                engine_name: self.info.name.clone(),
+                total_results: Some(0),
+                elapsed_ms: 0,
+                items: Vec::new(),
+                pagination: None,
+                suggestions: Vec::new(),
+                metadata: HashMap::new(),
+            });
+        }
+        <Self as RequestResponseEngine>::search(self, query).await
+    }
+
+    async fn is_available(&self) -> bool {
+        !self.config.url.is_empty() && self.selectors.is_ok()
+    }
+}
+
+#[async_trait]
+impl RequestResponseEngine for XPathEngine {
+    type Response = (String, String);
+
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
+    fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Err(e) = &self.selectors {
+            return Err(format!("XPath engine '{}' is misconfigured: {}", self.config.name, e).into());
+        }
+        if self.config.url.is_empty() {
+            return Err(format!("XPath engine '{}' has no url", self.config.name).into());
+        }
+        let (first_page, page_size) = (self.config.first_page, self.config.page_size);
+        let url_vars = template_vars(query, params, first_page, page_size, |s| urlencoding::encode(s).into_owned());
+        let raw_vars = template_vars(query, params, first_page, page_size, str::to_string);
+
+        params.url = Some(render_template(&self.config.url, &url_vars));
+        params.headers = self.config.headers.iter().map(|(k, v)| (k.clone(), render_template(v, &raw_vars))).collect();
+        params.cookies = self.config.cookies.iter().map(|(k, v)| (k.clone(), render_template(v, &raw_vars))).collect();
+        Ok(())
+    }
+
+    async fn fetch(&self, params: &RequestParams) -> Result<Self::Response, Box<dyn Error + Send + Sync>> {
+        let url = params.url.as_ref().ok_or("URL not set")?;
+        let response = self.client.get(url, Some(params.request_options())).await.map_err(|e| format!("Request failed: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", self.config.name, response.status()).into());
+        }
+        let final_url = response.url().to_string();
+        let html = response_text(response).await.map_err(|e| format!("Failed to read response: {}", e))?;
+        Ok((final_url, html))
+    }
+
+    fn response(&self, (url, html): Self::Response) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
+        let selectors = self.selectors.as_ref().map_err(|e| e.clone())?;
+        Ok(parse_page(selectors, &html, url::Url::parse(&url).ok().as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[test]
+    fn test_xpath_to_css() {
+        let css = |xpath: &str| xpath_to_css(xpath).unwrap();
+        assert_eq!(css("//div[@class=\"result\"]"), ("div[class=\"result\"]".to_string(), Extract::Text));
+        assert_eq!(
+            css("//ol[@id='b_results']/li[contains(@class, 'algo')]"),
+            ("ol[id=\"b_results\"] > li[class*=\"algo\"]".to_string(), Extract::Text)
+        );
+        assert_eq!(css(".//h3/a/@href"), ("h3 > a".to_string(), Extract::Attr("href".to_string())));
+        assert_eq!(css("./a//span/text()"), (":scope > a span".to_string(), Extract::Text));
+        assert_eq!(css("/html/body//p[2]"), ("html:root > body p:nth-of-type(2)".to_string(), Extract::Text));
+        assert_eq!(
+            css("//div[contains(concat(' ', normalize-space(@class), ' '), ' res ') and @data-id]"),
+            ("div[class~=\"res\"][data-id]".to_string(), Extract::Text)
+        );
+        assert_eq!(css("./@href"), (":scope".to_string(), Extract::Attr("href".to_string())));
+        assert!(xpath_to_css("//a[last()]").is_err());
+        assert!(xpath_to_css("//a/@href/b").is_err());
+
+        assert_eq!(to_css("a.title@href").unwrap(), ("a.title".to_string(), Extract::Attr("href".to_string())));
+        assert_eq!(to_css("div.result").unwrap(), ("div.result".to_string(), Extract::Text));
+    }
+
+    #[tokio::test]
+    async fn test_search_with_xpath_and_css() {
+        let html = r#"<html><body><div id="main">
+            <div class="hit big"><h3><a href="/doc/1">First <b>doc</b></a></h3><p class="snippet">one</p><img src="/t/1.png"></div>
+            <div class="hit"><h3><a href="https://other.example/2">Second</a></h3></div>
+            <div class="hit"><h3>No link</h3></div>
+        </div></body></html>"#;
+        let transport = Arc::new(MockTransport::new().respond("docs.example/search", MockResponse::ok(html)));
+        let config = XPathConfig {
+            name: "docs".to_string(),
+            url: "https://docs.example/search?q={query}&start={offset}".to_string(),
+            results: "//div[@id='main']/div[contains(concat(' ', normalize-space(@class), ' '), ' hit ')]".to_string(),
+            fields: XPathFields {
+                title: ".//h3/a".to_string(),
+                url: ".//h3/a/@href".to_string(),
+                content: Some("p.snippet".to_string()),
+                thumbnail: Some("img@src".to_string()),
+            },
+            ..Default::default()
+        };
+        let engine = XPathEngine::new(transport.clone(), config);
+        assert!(engine.is_available().await);
+
+        let query = SearchQuery { query: "rust book".to_string(), page: 3, ..Default::default() };
+        let result = SearchEngine::search(&engine, &query).await.unwrap();
+        assert_eq!(transport.requests()[0].url, "https://docs.example/search?q=rust%20book&start=20");
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(result.items[0].title, "First doc");
+        assert_eq!(result.items[0].content, "one");
+        assert_eq!(result.items[1].url, "https://other.example/2");
+
+        let misconfigured = XPathEngine::new(transport, XPathConfig { results: "//a[last()]".to_string(), ..Default::default() });
+        assert!(!misconfigured.is_available().await);
+    }
+}
@@ -63,9 +63,53 @@ use crate::derive::{
     ResultType, SearchEngine, SearchQuery, SearchResult,
     SearchResultItem, AboutInfo, RequestResponseEngine, RequestParams,
 };
-use crate::net::client::HttpClient;
-use crate::net::types::{NetworkConfig, RequestOptions};
+use crate::net::client::{HttpClient, HttpTransport};
+use crate::net::charset::response_text;
+use crate::net::types::NetworkConfig;
+use crate::url_utils::is_http_url;
 use super::utils::build_query_string_owned;
+use once_cell::sync::Lazy;
+use scraper::Selector;
+
+/// 结果页解析用到的 CSS 选择器（进程内只编译一次），数组按优先级排列
+struct YandexSelectors {
+    results: Vec<Selector>,
+    titles: Vec<Selector>,
+    link: Selector,
+    snippets: Vec<Selector>,
+}
+
+static SELECTORS: Lazy<YandexSelectors> = Lazy::new(|| {
+    let parse_all = |list: &[&str]| -> Vec<Selector> {
+        list.iter().map(|s| Selector::parse(s).expect("valid selector")).collect()
+    };
+    YandexSelectors {
+        // Yandex 的搜索结果通常在特定的 li 或 div 元素中
+        results: parse_all(&["li.serp-item", "div.serp-item", "div[class*='serp-item']"]),
+        titles: parse_all(&["h2", "h3", "a.link"]),
+        link: Selector::parse("a").expect("valid selector"),
+        snippets: parse_all(&[
+            "div.text-container",
+            "div.OrganicTextContentSpan",
+            "div.text",
+            "div[class*='snippet']",
+        ]),
+    }
+});
+
+/// 国家代码到 Yandex 地区 ID（`lr` 参数）的映射
+const YANDEX_REGIONS: &[(&str, u32)] = &[
+    ("RU", 225),
+    ("UA", 187),
+    ("BY", 149),
+    ("KZ", 159),
+    ("TR", 983),
+    ("US", 84),
+    ("GB", 102),
+    ("DE", 96),
+    ("FR", 124),
+    ("CN", 134),
+];
 
 /// Yandex 搜索引擎
 ///
@@ -74,7 +118,7 @@ pub struct YandexEngine {
     /// 引擎信息
     info: EngineInfo,
     /// HTTP 客户端
-    client: Arc<HttpClient>,
+    client: Arc<dyn HttpTransport>,
 }
 
 impl YandexEngine {
@@ -93,7 +137,7 @@ impl YandexEngine {
         Self::with_client(Arc::new(client))
     }
 
-    pub fn with_client(client: Arc<HttpClient>) -> Self {
+    pub fn with_client(client: Arc<dyn HttpTransport>) -> Self {
         Self {
             info: EngineInfo {
                 name: "Yandex".to_string(),
@@ -103,12 +147,13 @@ impl YandexEngine {
                 categories: vec!["general".to_string(), "web".to_string()],
                 capabilities: EngineCapabilities {
                     result_types: vec![ResultType::Web],
-                    supported_params: vec![],
+                    supported_params: vec!["region".to_string()],
                     max_page_size: 10,
                     supports_pagination: true,
                     supports_time_range: false,
                     supports_language_filter: false,
-                    supports_region_filter: false,
+                    supports_region_filter: true,
+                    supported_regions: YANDEX_REGIONS.iter().map(|(code, _)| code.to_string()).collect(),
                     supports_safe_search: false,
                     rate_limit: Some(60),
                 },
@@ -135,6 +180,15 @@ impl YandexEngine {
         }
     }
 
+    /// 将地区转换为 Yandex 地区 ID
+    fn region_id(region: &str) -> Option<u32> {
+        let country = crate::derive::region_country(region)?;
+        YANDEX_REGIONS
+            .iter()
+            .find(|(code, _)| *code == country)
+            .map(|(_, id)| *id)
+    }
+
     /// 检测是否遇到 Yandex CAPTCHA
     ///
     /// # 参数
@@ -162,7 +216,7 @@ impl YandexEngine {
     ///
     /// 如果 HTML 解析失败返回错误
     fn parse_html_results(html: &str) -> Result<Vec<SearchResultItem>, Box<dyn Error + Send + Sync>> {
-        use scraper::{Html, Selector};
+        use scraper::Html;
         
         // 检查是否有结果
         if html.is_empty() {
@@ -172,39 +226,14 @@ impl YandexEngine {
         let document = Html::parse_document(html);
         let mut items = Vec::new();
         
-        // Yandex 的搜索结果通常在特定的 li 或 div 元素中
-        let result_selectors = vec![
-            "li.serp-item",
-            "div.serp-item",
-            "div[class*='serp-item']",
-        ];
-        
+        let selectors = &*SELECTORS;
         let mut results_found = false;
-        for selector_str in result_selectors {
-            let selector = match Selector::parse(selector_str) {
-                Ok(sel) => sel,
-                Err(_) => continue,
-            };
-            
-            for result in document.select(&selector) {
+        for selector in &selectors.results {
+            for result in document.select(selector) {
                 results_found = true;
                 
-                // 提取标题和 URL
-                let title_selectors = vec![
-                    Selector::parse("h2").ok(),
-                    Selector::parse("h3").ok(),
-                    Selector::parse("a.link").ok(),
-                ];
-                let link_selector = Selector::parse("a").expect("Expected valid value");
-                let snippet_selectors = vec![
-                    Selector::parse("div.text-container").ok(),
-                    Selector::parse("div.OrganicTextContentSpan").ok(),
-                    Selector::parse("div.text").ok(),
-                    Selector::parse("div[class*='snippet']").ok(),
-                ];
-                
                 let mut title = String::new();
-                for selector in title_selectors.iter().flatten() {
+                for selector in &selectors.titles {
                     if let Some(t) = result.select(selector).next() {
                         title = t.text().collect::<String>().trim().to_string();
                         if !title.is_empty() {
@@ -213,12 +242,12 @@ impl YandexEngine {
                     }
                 }
                 
-                let url = result.select(&link_selector).next()
+                let url = result.select(&selectors.link).next()
                     .and_then(|a| a.value().attr("href"))
                     .unwrap_or_default();
                 
                 let mut content = String::new();
-                for selector in snippet_selectors.iter().flatten() {
+                for selector in &selectors.snippets {
                     if let Some(snippet) = result.select(selector).next() {
                         content = snippet.text().collect::<String>().trim().to_string();
                         if !content.is_empty() {
@@ -228,7 +257,7 @@ impl YandexEngine {
                 }
                 
                 // 过滤有效结果
-                if !title.is_empty() && !url.is_empty() && url.starts_with("http") {
+                if !title.is_empty() && is_http_url(url) {
                     items.push(SearchResultItem {
                         title,
                         url: url.to_string(),
@@ -282,6 +311,10 @@ impl SearchEngine for YandexEngine {
 impl RequestResponseEngine for YandexEngine {
     type Response = (String, Option<String>); // (HTML 字符串, captcha 头)
 
+    fn transport(&self) -> Option<&dyn HttpTransport> {
+        Some(self.client.as_ref())
+    }
+
     /// 准备请求参数
     fn request(&self, query: &str, params: &mut RequestParams) -> Result<(), Box<dyn Error + Send + Sync>> {
         // 构建查询参数
@@ -297,6 +330,11 @@ impl RequestResponseEngine for YandexEngine {
         if params.pageno > 1 {
             query_params.push(("p", (params.pageno - 1).to_string()));
         }
+
+        // 添加地区参数
+        if let Some(lr) = params.region.as_deref().and_then(Self::region_id) {
+            query_params.push(("lr", lr.to_string()));
+        }
         
         // Build URL with optimized query string
         let query_string = build_query_string_owned(query_params.into_iter());
@@ -318,17 +356,8 @@ impl RequestResponseEngine for YandexEngine {
         let url = params.url.as_ref()
             .ok_or("请求 URL 未设置")?;
 
-        // 创建请求选项
-        let mut options = RequestOptions::default();
-        // 使用配置的默认超时时间
-
-        // 添加自定义头
-        for (key, value) in &params.headers {
-            options.headers.push((key.clone(), value.clone()));
-        }
-
         // 发送请求
-        let response = self.client.get(url, Some(options)).await
+        let response = self.client.get(url, Some(params.request_options())).await
             .map_err(|e| format!("Request failed: {}", e))?;
 
         // 检查状态码
@@ -342,7 +371,7 @@ impl RequestResponseEngine for YandexEngine {
         }
 
         // 获取响应文本
-        let text = response.text().await
+        let text = response_text(response).await
             .map_err(|e| format!("Failed to read response: {}", e))?;
 
         Ok((text, None))
@@ -417,6 +446,25 @@ mod tests {
         assert!(url.contains("p=2")); // page 3 -> p=2 (0-indexed)
     }
 
+    #[test]
+    fn test_request_with_region() {
+        let engine = YandexEngine::new();
+        let mut params = RequestParams {
+            region: Some("ru-RU".to_string()),
+            ..Default::default()
+        };
+        engine.request("test", &mut params).expect("request should succeed");
+        assert!(params.url.expect("Expected valid value").contains("lr=225"));
+
+        let mut params = RequestParams {
+            region: Some("JP".to_string()),
+            ..Default::default()
+        };
+        engine.request("test", &mut params).expect("request should succeed");
+        assert!(!params.url.expect("Expected valid value").contains("lr="));
+        assert!(!engine.info().capabilities.supports_region("JP"));
+    }
+
     #[test]
     fn test_default() {
         let engine = YandexEngine::default();
@@ -0,0 +1,159 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 网页正文提取
+//!
+//! 答案生成和结果摘要需要比引擎摘要更完整的页面内容。提取只做轻量的启发式处理：
+//! 页面有 `<article>` 或 `<main>` 时只取其中的段落，否则取全页段落；跳过导航、页眉、
+//! 页脚、侧栏和表单中的内容，丢弃过短的段落。
+
+use std::error::Error;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::net::client::HttpTransport;
+use crate::net::types::RequestOptions;
+
+/// 正文容器（按优先级）
+static CONTAINER_SELECTORS: Lazy<Vec<Selector>> = Lazy::new(|| {
+    ["article", "main", "[role=main]", "body"]
+        .iter()
+        .map(|s| Selector::parse(s).expect("valid selector"))
+        .collect()
+});
+
+/// 段落元素
+static BLOCK_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse("p, li, h1, h2, h3, blockquote, pre").expect("valid selector")
+});
+
+/// 祖先是这些元素时跳过（导航、页眉页脚等页面框架）
+const SKIPPED_ANCESTORS: &[&str] = &["nav", "header", "footer", "aside", "form", "script", "style", "noscript"];
+
+/// 段落至少包含的字符数（标题不受限制）
+const MIN_BLOCK_CHARS: usize = 20;
+
+/// 提取页面正文段落（按文档顺序，已合并空白）
+pub fn extract_paragraphs(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let Some(container) = CONTAINER_SELECTORS
+        .iter()
+        .find_map(|selector| document.select(selector).next())
+    else {
+        return Vec::new();
+    };
+
+    container
+        .select(&BLOCK_SELECTOR)
+        .filter(|block| !in_page_chrome(*block))
+        // 嵌套的段落（如 li 中的 p）只取最内层，避免重复
+        .filter(|block| block.select(&BLOCK_SELECTOR).next().is_none())
+        .filter_map(|block| {
+            let text = block.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ");
+            let is_heading = matches!(block.value().name(), "h1" | "h2" | "h3");
+            let long_enough = text.chars().count() >= MIN_BLOCK_CHARS;
+            (!text.is_empty() && (is_heading || long_enough)).then_some(text)
+        })
+        .collect()
+}
+
+/// 提取页面正文，段落之间换行，最多 `max_chars` 个字符
+pub fn extract_main_text(html: &str, max_chars: usize) -> String {
+    truncate_chars(&extract_paragraphs(html).join("\n"), max_chars)
+}
+
+/// 抓取页面并提取正文（非 HTML 或非 2xx 响应返回错误）
+pub async fn fetch_page_text(
+    transport: &dyn HttpTransport,
+    url: &str,
+    max_chars: usize,
+    timeout: Duration,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let options = RequestOptions { timeout, ..Default::default() };
+    let response = transport.get(url, Some(options)).await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("页面返回 HTTP {}", response.status()).into());
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|v| v.contains("html"));
+    if !is_html {
+        return Err("页面不是 HTML".into());
+    }
+    let html = response.text().await?;
+    Ok(extract_main_text(&html, max_chars))
+}
+
+/// 按字符截断（不切断多字节字符）
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn in_page_chrome(element: ElementRef<'_>) -> bool {
+    element.ancestors().any(|node| {
+        node.value()
+            .as_element()
+            .is_some_and(|ancestor| SKIPPED_ANCESTORS.contains(&ancestor.name()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    const PAGE: &str = r#"<html><body>
+        <nav><p>Home | About | A very long navigation paragraph here</p></nav>
+        <article>
+            <h1>Rust</h1>
+            <p>Rust is a multi-paradigm, general-purpose programming language.</p>
+            <p>Short.</p>
+            <ul><li><p>Rust emphasizes performance, type safety, and concurrency.</p></li></ul>
+            <footer><p>Copyright notice that is long enough to pass the filter</p></footer>
+        </article>
+    </body></html>"#;
+
+    #[test]
+    fn test_extract_paragraphs_from_article() {
+        let paragraphs = extract_paragraphs(PAGE);
+        assert_eq!(paragraphs, vec![
+            "Rust".to_string(),
+            "Rust is a multi-paradigm, general-purpose programming language.".to_string(),
+            "Rust emphasizes performance, type safety, and concurrency.".to_string(),
+        ]);
+        assert_eq!(extract_main_text(PAGE, 4), "Rust");
+        assert_eq!(truncate_chars("中文内容", 2), "中文");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_text() {
+        let transport = MockTransport::new()
+            .respond("example.com/page", MockResponse::ok(PAGE).header("content-type", "text/html; charset=utf-8"))
+            .respond("example.com/file.pdf", MockResponse::ok("%PDF").header("content-type", "application/pdf"))
+            .respond("example.com/missing", MockResponse::new(404, ""));
+        let timeout = Duration::from_secs(1);
+        let text = fetch_page_text(&transport, "https://example.com/page", 1000, timeout).await.unwrap();
+        assert!(text.starts_with("Rust\nRust is"));
+        assert!(fetch_page_text(&transport, "https://example.com/file.pdf", 1000, timeout).await.is_err());
+        assert!(fetch_page_text(&transport, "https://example.com/missing", 1000, timeout).await.is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果过滤
+//!
+//! [`ResultFilter`] 在聚合、评分之后按站点、结果类型、最低分数和发布时间筛选结果，
+//! 由 API 的结构化 `filters` 参数生成，也可以直接在 [`SearchRequest`](super::SearchRequest) 中设置。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::derive::{ResultType, SearchResultItem, TimeRange};
+
+/// 结果过滤条件（所有条件同时满足才保留，空条件不过滤）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultFilter {
+    /// 只保留这些站点（含子域名）的结果
+    pub include_sites: Vec<String>,
+    /// 排除这些站点（含子域名）的结果
+    pub exclude_sites: Vec<String>,
+    /// 只保留这些类型的结果
+    pub result_types: Vec<ResultType>,
+    /// 最低分数（按聚合后的分数）
+    pub min_score: Option<f64>,
+    /// 只保留此时间之后发布的结果（没有发布时间的结果保留）
+    pub published_after: Option<DateTime<Utc>>,
+}
+
+/// 规范化站点：小写、去掉协议、路径和 `www.` 前缀
+pub fn normalize_site(site: &str) -> String {
+    let site = site.trim().to_lowercase();
+    let site = site.split_once("://").map_or(site.as_str(), |(_, rest)| rest);
+    let site = site.split(['/', '?', '#']).next().unwrap_or_default();
+    site.strip_prefix("www.").unwrap_or(site).trim_end_matches('.').to_string()
+}
+
+/// URL 的主机是否属于站点（相同或为其子域名）
+fn host_matches(host: &str, site: &str) -> bool {
+    host == site || host.strip_suffix(site).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+impl ResultFilter {
+    /// 按时间范围设置发布时间下限
+    pub fn with_time_range(mut self, time_range: TimeRange) -> Self {
+        self.published_after = time_range.duration().map(|d| Utc::now() - d);
+        self
+    }
+
+    /// 是否没有任何条件
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// 结果是否满足全部条件
+    pub fn matches(&self, item: &SearchResultItem) -> bool {
+        if !self.result_types.is_empty() && !self.result_types.contains(&item.result_type) {
+            return false;
+        }
+        if self.min_score.is_some_and(|min| item.score < min) {
+            return false;
+        }
+        if let (Some(after), Some(published)) = (self.published_after, item.published_date)
+            && published < after
+        {
+            return false;
+        }
+        if self.include_sites.is_empty() && self.exclude_sites.is_empty() {
+            return true;
+        }
+
+        let host = url::Url::parse(&item.url)
+            .ok()
+            .and_then(|url| url.host_str().map(normalize_site))
+            .unwrap_or_default();
+        let in_sites = |sites: &[String]| sites.iter().any(|site| host_matches(&host, &normalize_site(site)));
+        (self.include_sites.is_empty() || in_sites(&self.include_sites)) && !in_sites(&self.exclude_sites)
+    }
+
+    /// 移除不满足条件的结果
+    pub fn apply(&self, items: &mut Vec<SearchResultItem>) {
+        if !self.is_empty() {
+            items.retain(|item| self.matches(item));
+        }
+    }
+}
+
+/// 在已有结果中细化：只保留标题、摘要或链接包含全部关键词的结果（不区分大小写）
+pub fn refine_items(items: &mut Vec<SearchResultItem>, terms: &str) {
+    let terms: Vec<String> = terms.split_whitespace().map(str::to_lowercase).collect();
+    items.retain(|item| {
+        let text = format!("{} {} {}", item.title, item.content, item.url).to_lowercase();
+        terms.iter().all(|term| text.contains(term))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn item(url: &str, score: f64, result_type: ResultType) -> SearchResultItem {
+        SearchResultItem {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score,
+            result_type,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_site() {
+        assert_eq!(normalize_site("https://WWW.Example.com/path"), "example.com");
+        assert_eq!(normalize_site(" docs.rs "), "docs.rs");
+    }
+
+    #[test]
+    fn test_site_filters() {
+        let filter = ResultFilter {
+            include_sites: vec!["rust-lang.org".to_string()],
+            exclude_sites: vec!["forum.rust-lang.org".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches(&item("https://www.rust-lang.org/learn", 1.0, ResultType::Web)));
+        assert!(filter.matches(&item("https://doc.rust-lang.org/std", 1.0, ResultType::Web)));
+        assert!(!filter.matches(&item("https://forum.rust-lang.org/t/1", 1.0, ResultType::Web)));
+        assert!(!filter.matches(&item("https://notrust-lang.org/", 1.0, ResultType::Web)));
+    }
+
+    #[test]
+    fn test_type_score_and_time_filters() {
+        let filter = ResultFilter {
+            result_types: vec![ResultType::News],
+            min_score: Some(0.5),
+            ..Default::default()
+        }
+        .with_time_range(TimeRange::Week);
+
+        let mut items = vec![
+            item("https://a.example/", 0.9, ResultType::News),
+            item("https://b.example/", 0.1, ResultType::News),
+            item("https://c.example/", 0.9, ResultType::Web),
+            item("https://d.example/", 0.9, ResultType::News),
+        ];
+        items[3].published_date = Some(Utc::now() - chrono::Duration::days(30));
+        filter.apply(&mut items);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://a.example/");
+    }
+
+    #[test]
+    fn test_refine_items() {
+        let mut items = vec![
+            item("https://tokio.rs/", 1.0, ResultType::Web),
+            item("https://async-std.example/", 1.0, ResultType::Web),
+            item("https://docs.rs/tokio-util", 1.0, ResultType::Web),
+        ];
+        items[2].content = "Utilities for Tokio".to_string();
+        refine_items(&mut items, "TOKIO util");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://docs.rs/tokio-util");
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 查询词高亮
+//!
+//! 计算查询词在标题和摘要中的位置，返回 `[start, end)` 字节区间，客户端不必自己实现匹配。
+//! 匹配忽略大小写，但区间始终落在原文的字符边界上（大小写转换改变字节长度时也不会错位）。
+//! 拉丁文字的词要求整词匹配；中日韩文字没有空格分词，按连续文字直接匹配。
+
+/// 高亮区间（原文中的 `[start, end)` 字节偏移）
+pub type HighlightRange = [usize; 2];
+
+/// 中日韩文字（假名、汉字、谚文）
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// 需要整词匹配的字符（拉丁等有空格分词的文字）
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_cjk(c)
+}
+
+/// 逐字符的大小写折叠（一对一映射，保证与原文字符一一对应）
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// 从查询中提取高亮词
+///
+/// 跳过 `!bang`、`-排除词` 和 `site:` 之类的操作符；词内的中日韩文字与其他文字分开，
+/// 例如 `rust所有权` 得到 `rust` 和 `所有权`
+pub fn highlight_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = Vec::new();
+    for token in query.split_whitespace() {
+        if token.starts_with('!') || token.starts_with('-') || token.contains(':') {
+            continue;
+        }
+        let mut current: Vec<char> = Vec::new();
+        let mut current_cjk = false;
+        for c in token.chars() {
+            let cjk = is_cjk(c);
+            if !(cjk || c.is_alphanumeric()) || (!current.is_empty() && cjk != current_cjk) {
+                push_term(&mut terms, std::mem::take(&mut current));
+            }
+            if cjk || c.is_alphanumeric() {
+                current.push(fold(c));
+                current_cjk = cjk;
+            }
+        }
+        push_term(&mut terms, current);
+    }
+    terms
+}
+
+fn push_term(terms: &mut Vec<Vec<char>>, term: Vec<char>) {
+    if !term.is_empty() && !terms.contains(&term) {
+        terms.push(term);
+    }
+}
+
+/// 计算 `terms` 在 `text` 中的高亮区间（按起点排序，重叠或相邻的区间合并）
+pub fn find_highlights(text: &str, terms: &[Vec<char>]) -> Vec<HighlightRange> {
+    if terms.is_empty() || text.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().map(|(offset, c)| (offset, fold(c))).collect();
+    let byte_at = |index: usize| chars.get(index).map_or(text.len(), |(offset, _)| *offset);
+
+    let mut ranges: Vec<HighlightRange> = Vec::new();
+    for start in 0..chars.len() {
+        for term in terms {
+            let end = start + term.len();
+            if end > chars.len() || !chars[start..end].iter().map(|(_, c)| c).eq(term.iter()) {
+                continue;
+            }
+            // 拉丁词的首尾必须在词边界上
+            let starts_word = !is_word_char(term[0]) || start == 0 || !is_word_char(chars[start - 1].1);
+            let ends_word = !is_word_char(term[term.len() - 1]) || end == chars.len() || !is_word_char(chars[end].1);
+            if starts_word && ends_word {
+                ranges.push([byte_at(start), byte_at(end)]);
+            }
+        }
+    }
+
+    ranges.sort_unstable();
+    let mut merged: Vec<HighlightRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slices<'a>(text: &'a str, ranges: &[HighlightRange]) -> Vec<&'a str> {
+        ranges.iter().map(|[start, end]| &text[*start..*end]).collect()
+    }
+
+    #[test]
+    fn test_highlight_terms() {
+        let terms = highlight_terms("Rust所有权 !w -java site:rust-lang.org \"async\"");
+        let terms: Vec<String> = terms.iter().map(|t| t.iter().collect()).collect();
+        assert_eq!(terms, vec!["rust", "所有权", "async"]);
+    }
+
+    #[test]
+    fn test_find_highlights_latin_whole_words() {
+        let text = "Rust and rusty RUST: trust rust.";
+        let ranges = find_highlights(text, &highlight_terms("rust"));
+        assert_eq!(slices(text, &ranges), vec!["Rust", "RUST", "rust"]);
+    }
+
+    #[test]
+    fn test_find_highlights_cjk_byte_offsets() {
+        let text = "Rust 的所有权系统：所有权规则";
+        let ranges = find_highlights(text, &highlight_terms("所有权 rust"));
+        assert_eq!(ranges[0], [0, 4]);
+        assert_eq!(slices(text, &ranges), vec!["Rust", "所有权", "所有权"]);
+
+        // 大小写转换改变字节长度的字符也保持原文偏移
+        let text = "İstanbul RUST";
+        let ranges = find_highlights(text, &highlight_terms("rust"));
+        assert_eq!(slices(text, &ranges), vec!["RUST"]);
+
+        // 重叠的匹配合并
+        let text = "所有权规则";
+        assert_eq!(find_highlights(text, &highlight_terms("所有 有权")), vec![[0, 9]]);
+    }
+}
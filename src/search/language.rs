@@ -0,0 +1,216 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果语言检测
+//!
+//! 按文字系统和常用虚词粗略判断标题 + 摘要的语言，供聚合器在请求指定语言时
+//! 降权或丢弃语言不符的结果。只在有把握时给出判断，文本过短或无法区分时视为未知。
+
+/// 文字系统
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    /// 拉丁字母
+    Latin,
+    /// 汉字（不含假名）
+    Han,
+    /// 日文（含假名）
+    Japanese,
+    /// 韩文
+    Hangul,
+    /// 西里尔字母
+    Cyrillic,
+    /// 阿拉伯字母
+    Arabic,
+    /// 希腊字母
+    Greek,
+    /// 希伯来字母
+    Hebrew,
+    /// 天城文
+    Devanagari,
+    /// 泰文
+    Thai,
+}
+
+impl Script {
+    /// 语言代码使用的文字系统（未列出的语言按拉丁字母处理）
+    pub fn of_language(language: &str) -> Self {
+        match language {
+            "zh" => Self::Han,
+            "ja" => Self::Japanese,
+            "ko" => Self::Hangul,
+            "ru" | "uk" | "bg" | "sr" | "be" | "kk" | "mk" | "mn" => Self::Cyrillic,
+            "ar" | "fa" | "ur" => Self::Arabic,
+            "el" => Self::Greek,
+            "he" | "yi" => Self::Hebrew,
+            "hi" | "mr" | "ne" => Self::Devanagari,
+            "th" => Self::Thai,
+            _ => Self::Latin,
+        }
+    }
+
+    fn of_char(c: char) -> Option<Self> {
+        match c {
+            '\u{3040}'..='\u{30ff}' => Some(Self::Japanese),
+            '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{f900}'..='\u{faff}' => Some(Self::Han),
+            '\u{1100}'..='\u{11ff}' | '\u{3130}'..='\u{318f}' | '\u{ac00}'..='\u{d7af}' => Some(Self::Hangul),
+            '\u{0400}'..='\u{04ff}' => Some(Self::Cyrillic),
+            '\u{0600}'..='\u{06ff}' => Some(Self::Arabic),
+            '\u{0370}'..='\u{03ff}' => Some(Self::Greek),
+            '\u{0590}'..='\u{05ff}' => Some(Self::Hebrew),
+            '\u{0900}'..='\u{097f}' => Some(Self::Devanagari),
+            '\u{0e00}'..='\u{0e7f}' => Some(Self::Thai),
+            c if c.is_alphabetic() && (c.is_ascii() || ('\u{00c0}'..='\u{024f}').contains(&c)) => Some(Self::Latin),
+            _ => None,
+        }
+    }
+
+    /// 一个字符大致相当于一个词的文字系统
+    fn is_logographic(self) -> bool {
+        matches!(self, Self::Han | Self::Japanese | Self::Hangul)
+    }
+}
+
+/// 拉丁字母语言的常用虚词
+const LATIN_STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "of", "to", "is", "in", "for", "with", "that", "this", "are", "on", "how", "what"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "mit", "ein", "eine", "für", "auf", "den", "von", "wie"]),
+    ("fr", &["le", "la", "les", "et", "est", "des", "une", "dans", "pour", "avec", "pas", "sur", "du", "qui"]),
+    ("es", &["el", "los", "las", "y", "es", "una", "del", "por", "para", "con", "que", "como", "se", "más"]),
+    ("it", &["il", "gli", "e", "è", "della", "che", "per", "con", "non", "una", "sono", "come", "nel", "di"]),
+    ("pt", &["os", "as", "e", "é", "uma", "do", "da", "para", "com", "não", "que", "como", "em", "no"]),
+    ("nl", &["de", "het", "een", "en", "is", "van", "niet", "met", "voor", "op", "dat", "zijn", "hoe", "wat"]),
+];
+
+/// 至少需要的字母数（逻辑文字按 3 倍计）
+const MIN_LETTERS: usize = 6;
+
+/// 检测到的语言
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedLanguage {
+    /// 主要文字系统
+    pub script: Script,
+    /// 语言代码（同一文字系统内无法区分时为 None）
+    pub language: Option<&'static str>,
+}
+
+/// 取语言代码的主标签并转为小写（`zh-CN` -> `zh`）
+pub fn primary_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// 检测文本语言（文本过短时为 None）
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(Script::of_char) {
+        let weight = if script.is_logographic() { 3 } else { 1 };
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += weight,
+            None => counts.push((script, weight)),
+        }
+    }
+    // 日文通常夹杂汉字，出现假名即按日文计
+    if let Some(kana) = counts.iter().position(|(s, _)| *s == Script::Japanese)
+        && let Some(han) = counts.iter().position(|(s, _)| *s == Script::Han)
+    {
+        let han_count = counts[han].1;
+        counts[kana].1 += han_count;
+        counts.remove(han);
+    }
+
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    if total < MIN_LETTERS {
+        return None;
+    }
+    let (script, _) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    let language = match script {
+        Script::Latin => detect_latin_language(text),
+        Script::Han => Some("zh"),
+        Script::Japanese => Some("ja"),
+        Script::Hangul => Some("ko"),
+        Script::Greek => Some("el"),
+        Script::Thai => Some("th"),
+        _ => None,
+    };
+    Some(DetectedLanguage { script, language })
+}
+
+/// 按虚词命中数区分拉丁字母语言（命中少于 2 个或并列时为 None）
+fn detect_latin_language(text: &str) -> Option<&'static str> {
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = LATIN_STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| (*language, words.iter().filter(|w| stopwords.contains(w)).count()))
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    match scores.as_slice() {
+        [(language, best), (_, second), ..] if *best >= 2 && best > second => Some(language),
+        _ => None,
+    }
+}
+
+/// 文本是否符合请求的语言（无法判断时为 None）
+pub fn matches_language(text: &str, requested: &str) -> Option<bool> {
+    let requested = primary_language(requested);
+    let detected = detect_language(text)?;
+    if detected.script != Script::of_language(&requested) {
+        return Some(false);
+    }
+    match detected.language {
+        Some(language) if LATIN_STOPWORDS.iter().any(|(l, _)| *l == requested) || detected.script != Script::Latin => {
+            Some(language == requested)
+        }
+        // 拉丁字母语言中无法区分请求的语言时不下结论
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let zh = detect_language("Rust 编程语言入门教程：所有权与借用").unwrap();
+        assert_eq!(zh.language, Some("zh"));
+        let ja = detect_language("Rustプログラミング言語の入門").unwrap();
+        assert_eq!(ja.language, Some("ja"));
+        let en = detect_language("The Rust book: how to write safe and fast code").unwrap();
+        assert_eq!(en.language, Some("en"));
+        let de = detect_language("Die Programmiersprache Rust ist nicht nur schnell, sondern auch sicher und für alle").unwrap();
+        assert_eq!(de.language, Some("de"));
+        assert_eq!(detect_language("Русский язык программирования").unwrap().script, Script::Cyrillic);
+        assert_eq!(detect_language("Rust"), None);
+    }
+
+    #[test]
+    fn test_matches_language() {
+        assert_eq!(matches_language("The Rust book: how to write safe code", "zh-CN"), Some(false));
+        assert_eq!(matches_language("Rust 编程语言入门教程", "zh"), Some(true));
+        assert_eq!(matches_language("The Rust book: how to write safe code", "en-US"), Some(true));
+        assert_eq!(matches_language("Le langage Rust est rapide et sûr pour les systèmes", "en"), Some(false));
+        // 无法区分的拉丁字母语言不下结论
+        assert_eq!(matches_language("The Rust book: how to write safe code", "sv"), None);
+        assert_eq!(matches_language("Rust", "en"), None);
+    }
+}
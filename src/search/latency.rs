@@ -0,0 +1,251 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 引擎延迟统计与自适应超时
+//!
+//! 每个引擎用 HDR 直方图记录成功和超时请求的耗时（失败请求通常很快返回，不计入），
+//! 按 `window` 个样本轮换：保留当前和上一个窗口，百分位由两者合并计算，旧数据随之淘汰。
+//! 启用自适应超时后，每个引擎的超时为其 p95 × `timeout_factor`，限制在
+//! `[min_timeout_ms, max_timeout_ms]` 内；样本不足时使用配置的默认超时（不超过上限）。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+
+/// 直方图记录的最大耗时（毫秒），超出按该值记录
+const MAX_TRACKABLE_MS: u64 = 10 * 60 * 1000;
+
+/// 直方图精度（有效数字位数）
+const SIGNIFICANT_FIGURES: u8 = 2;
+
+/// 延迟统计与自适应超时配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// 每个直方图窗口的样本数
+    #[serde(default = "default_window")]
+    pub window: u64,
+    /// 是否按引擎的 p95 设置超时（默认关闭，所有引擎使用 `default_timeout`）
+    #[serde(default)]
+    pub adaptive_timeout: bool,
+    /// 超时 = p95 × 该系数
+    #[serde(default = "default_timeout_factor")]
+    pub timeout_factor: f64,
+    /// 自适应超时下限（毫秒）
+    #[serde(default = "default_min_timeout_ms")]
+    pub min_timeout_ms: u64,
+    /// 自适应超时上限（毫秒）
+    #[serde(default = "default_max_timeout_ms")]
+    pub max_timeout_ms: u64,
+    /// 使用自适应超时至少需要的样本数
+    #[serde(default = "default_min_samples")]
+    pub min_samples: u64,
+}
+
+fn default_window() -> u64 {
+    1000
+}
+
+fn default_timeout_factor() -> f64 {
+    2.0
+}
+
+fn default_min_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_max_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_min_samples() -> u64 {
+    20
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        Self {
+            window: default_window(),
+            adaptive_timeout: false,
+            timeout_factor: default_timeout_factor(),
+            min_timeout_ms: default_min_timeout_ms(),
+            max_timeout_ms: default_max_timeout_ms(),
+            min_samples: default_min_samples(),
+        }
+    }
+}
+
+impl LatencyConfig {
+    /// 计算引擎的超时：未启用自适应超时时为 `default`，样本不足时为 `default` 与上限中较小的一个
+    pub fn timeout_for(&self, latency: Option<&LatencyPercentiles>, default: Duration) -> Duration {
+        if !self.adaptive_timeout {
+            return default;
+        }
+        let max = Duration::from_millis(self.max_timeout_ms.max(self.min_timeout_ms));
+        match latency.filter(|latency| latency.samples >= self.min_samples) {
+            Some(latency) => {
+                let adaptive = Duration::from_millis((latency.p95_ms as f64 * self.timeout_factor).ceil() as u64);
+                adaptive.clamp(Duration::from_millis(self.min_timeout_ms), max)
+            }
+            None => default.min(max),
+        }
+    }
+}
+
+/// 引擎延迟百分位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    /// 统计的样本数
+    pub samples: u64,
+    /// 中位数（毫秒）
+    pub p50_ms: u64,
+    /// 95 分位（毫秒）
+    pub p95_ms: u64,
+    /// 99 分位（毫秒）
+    pub p99_ms: u64,
+    /// 最大值（毫秒）
+    pub max_ms: u64,
+}
+
+impl LatencyPercentiles {
+    fn from_histogram(histogram: &Histogram<u64>) -> Option<Self> {
+        if histogram.is_empty() {
+            return None;
+        }
+        Some(Self {
+            samples: histogram.len(),
+            p50_ms: histogram.value_at_quantile(0.50),
+            p95_ms: histogram.value_at_quantile(0.95),
+            p99_ms: histogram.value_at_quantile(0.99),
+            max_ms: histogram.max(),
+        })
+    }
+}
+
+/// 单个引擎的当前和上一个窗口
+struct EngineHistogram {
+    current: Histogram<u64>,
+    previous: Option<Histogram<u64>>,
+}
+
+impl EngineHistogram {
+    fn new() -> Self {
+        Self { current: new_histogram(), previous: None }
+    }
+
+    fn merged(&self) -> Histogram<u64> {
+        let mut merged = self.current.clone();
+        if let Some(previous) = &self.previous
+            && let Err(e) = merged.add(previous)
+        {
+            tracing::debug!("Failed to merge latency histograms: {}", e);
+        }
+        merged
+    }
+}
+
+fn new_histogram() -> Histogram<u64> {
+    Histogram::new_with_bounds(1, MAX_TRACKABLE_MS, SIGNIFICANT_FIGURES)
+        .expect("valid histogram bounds")
+}
+
+/// 所有引擎的延迟直方图
+pub struct EngineLatencies {
+    window: u64,
+    engines: Mutex<HashMap<String, EngineHistogram>>,
+}
+
+impl EngineLatencies {
+    /// 创建延迟统计，每个窗口 `window` 个样本
+    pub fn new(window: u64) -> Self {
+        Self {
+            window: window.max(1),
+            engines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录一次请求耗时
+    pub fn record(&self, engine: &str, elapsed_ms: u64) {
+        let mut engines = self.engines.lock().unwrap_or_else(|e| e.into_inner());
+        let histogram = engines.entry(engine.to_string()).or_insert_with(EngineHistogram::new);
+        if histogram.current.len() >= self.window {
+            histogram.previous = Some(std::mem::replace(&mut histogram.current, new_histogram()));
+        }
+        histogram.current.saturating_record(elapsed_ms.max(1));
+    }
+
+    /// 引擎的延迟百分位（没有样本时为 None）
+    pub fn percentiles(&self, engine: &str) -> Option<LatencyPercentiles> {
+        let engines = self.engines.lock().unwrap_or_else(|e| e.into_inner());
+        LatencyPercentiles::from_histogram(&engines.get(engine)?.merged())
+    }
+
+    /// 所有引擎的延迟百分位（按引擎名排序）
+    pub fn snapshot(&self) -> BTreeMap<String, LatencyPercentiles> {
+        let engines = self.engines.lock().unwrap_or_else(|e| e.into_inner());
+        engines.iter()
+            .filter_map(|(engine, histogram)| {
+                Some((engine.clone(), LatencyPercentiles::from_histogram(&histogram.merged())?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_window_rotation() {
+        let latencies = EngineLatencies::new(100);
+        assert!(latencies.percentiles("bing").is_none());
+        for ms in 1..=100 {
+            latencies.record("bing", ms * 10);
+        }
+        let p = latencies.percentiles("bing").unwrap();
+        assert_eq!(p.samples, 100);
+        assert!((495..=505).contains(&p.p50_ms), "p50 = {}", p.p50_ms);
+        assert!((945..=955).contains(&p.p95_ms), "p95 = {}", p.p95_ms);
+        assert!(p.max_ms >= 995);
+
+        // 两个窗口之后，最早的样本被淘汰
+        for _ in 0..200 {
+            latencies.record("bing", 20);
+        }
+        let p = latencies.percentiles("bing").unwrap();
+        assert_eq!(p.samples, 200);
+        assert_eq!(p.p99_ms, 20);
+        assert_eq!(latencies.snapshot().len(), 1);
+    }
+
+    #[test]
+    fn test_adaptive_timeout_bounds() {
+        let default = Duration::from_secs(30);
+        let latency = |samples, p95_ms| LatencyPercentiles { samples, p50_ms: 0, p95_ms, p99_ms: 0, max_ms: 0 };
+
+        let config = LatencyConfig::default();
+        assert_eq!(config.timeout_for(Some(&latency(100, 800)), default), default);
+
+        let config = LatencyConfig { adaptive_timeout: true, ..LatencyConfig::default() };
+        assert_eq!(config.timeout_for(Some(&latency(100, 800)), default), Duration::from_millis(1600));
+        assert_eq!(config.timeout_for(Some(&latency(100, 100)), default), Duration::from_millis(1000));
+        assert_eq!(config.timeout_for(Some(&latency(100, 9000)), default), Duration::from_secs(10));
+        // 样本不足时使用默认超时，但不超过上限
+        assert_eq!(config.timeout_for(Some(&latency(5, 800)), default), Duration::from_secs(10));
+        assert_eq!(config.timeout_for(None, Duration::from_secs(3)), Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! OpenAI 兼容的对话接口客户端
+//!
+//! 答案生成和结果摘要共用：`endpoint` 指向 `/v1/chat/completions`，可以是 OpenAI，
+//! 也可以是 vLLM、Ollama、llama.cpp server 等本地服务。
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::net::client::HttpTransport;
+use crate::net::types::RequestOptions;
+
+/// 对话接口配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// `/v1/chat/completions` 接口地址
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// 模型名
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// API 密钥（以 `Authorization: Bearer` 发送）
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// 请求超时（毫秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 采样温度
+    #[serde(default = "default_temperature")]
+    pub temperature: f64,
+}
+
+fn default_endpoint() -> String {
+    "http://127.0.0.1:11434/v1/chat/completions".to_string()
+}
+
+fn default_model() -> String {
+    "qwen2.5:7b-instruct".to_string()
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_temperature() -> f64 {
+    0.2
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            model: default_model(),
+            api_key: None,
+            timeout_ms: default_timeout_ms(),
+            temperature: default_temperature(),
+        }
+    }
+}
+
+/// 对话消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// 角色（`system`、`user`、`assistant`）
+    pub role: String,
+    /// 内容
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// 系统消息
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: "system".to_string(), content: content.into() }
+    }
+
+    /// 用户消息
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: "user".to_string(), content: content.into() }
+    }
+}
+
+/// token 用量（接口返回时才有）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// 提示词 token 数
+    #[serde(default)]
+    pub prompt_tokens: u64,
+    /// 生成的 token 数
+    #[serde(default)]
+    pub completion_tokens: u64,
+    /// 合计
+    #[serde(default)]
+    pub total_tokens: u64,
+}
+
+/// 一次对话补全的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatCompletion {
+    /// 生成的内容
+    pub content: String,
+    /// 接口报告的模型名
+    pub model: Option<String>,
+    /// token 用量
+    pub usage: Option<TokenUsage>,
+}
+
+#[derive(Serialize)]
+struct CompletionRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    max_tokens: u32,
+    temperature: f64,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    #[serde(default)]
+    model: Option<String>,
+    choices: Vec<CompletionChoice>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Deserialize)]
+struct CompletionChoice {
+    message: ChatMessage,
+}
+
+/// 对话接口客户端
+pub struct LlmClient {
+    transport: Arc<dyn HttpTransport>,
+    config: LlmConfig,
+}
+
+impl LlmClient {
+    /// 创建客户端
+    pub fn new(transport: Arc<dyn HttpTransport>, config: LlmConfig) -> Self {
+        Self { transport, config }
+    }
+
+    /// 接口配置
+    pub fn config(&self) -> &LlmConfig {
+        &self.config
+    }
+
+    /// 请求一次对话补全，最多生成 `max_tokens` 个 token
+    pub async fn complete(&self, messages: &[ChatMessage], max_tokens: u32) -> Result<ChatCompletion, Box<dyn Error + Send + Sync>> {
+        let request = CompletionRequest {
+            model: &self.config.model,
+            messages,
+            max_tokens,
+            temperature: self.config.temperature,
+            stream: false,
+        };
+        let body = serde_json::to_vec(&request)?;
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        if let Some(api_key) = &self.config.api_key {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", api_key)));
+        }
+        let options = RequestOptions {
+            timeout: Duration::from_millis(self.config.timeout_ms),
+            headers,
+            ..Default::default()
+        };
+        let response = self.transport
+            .post(&self.config.endpoint, body, Some(options))
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        metrics::counter!("seesea_llm_requests_total", "status" => status.as_u16().to_string()).increment(1);
+        if !status.is_success() {
+            return Err(format!("对话接口返回 HTTP {}", status).into());
+        }
+        let response: CompletionResponse = response.json().await?;
+        if let Some(usage) = &response.usage {
+            metrics::counter!("seesea_llm_tokens_total", "kind" => "prompt").increment(usage.prompt_tokens);
+            metrics::counter!("seesea_llm_tokens_total", "kind" => "completion").increment(usage.completion_tokens);
+        }
+        let content = response.choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content.trim().to_string())
+            .ok_or("对话接口没有返回内容")?;
+        Ok(ChatCompletion { content, model: response.model, usage: response.usage })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[tokio::test]
+    async fn test_complete() {
+        let body = r#"{"model": "test-model", "choices": [{"message": {"role": "assistant", "content": " Hello "}}],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 2, "total_tokens": 12}}"#;
+        let transport = Arc::new(MockTransport::new()
+            .respond("/v1/chat/completions", MockResponse::ok(body))
+            .respond("broken", MockResponse::new(500, "")));
+        let config = LlmConfig { api_key: Some("key".to_string()), ..LlmConfig::default() };
+        let client = LlmClient::new(transport.clone(), config);
+
+        let completion = client.complete(&[ChatMessage::user("Hi")], 16).await.unwrap();
+        assert_eq!(completion.content, "Hello");
+        assert_eq!(completion.model.as_deref(), Some("test-model"));
+        assert_eq!(completion.usage.unwrap().total_tokens, 12);
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.header("authorization"), Some("Bearer key"));
+        let sent: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent["max_tokens"], 16);
+        assert_eq!(sent["messages"][0]["role"], "user");
+
+        let broken = LlmClient::new(transport, LlmConfig { endpoint: "http://broken/".to_string(), ..LlmConfig::default() });
+        assert!(broken.complete(&[ChatMessage::user("Hi")], 16).await.is_err());
+    }
+}
@@ -22,30 +22,115 @@
 //! - 生命周期管理，避免重复创建资源
 //! - 清晰的职责划分，每个组件只负责一个功能
 
+// 查询解析、结果聚合、去重和排序不依赖运行时，wasm 构建也会编译
 pub mod aggregator;
-pub mod engines;
 pub mod query;
-pub mod types;
 pub mod scoring;
 pub mod standardization;
+pub mod sanitize;
+pub mod security;
+pub mod news;
+pub mod engine_config;
+pub mod filter;
+pub mod highlight;
+pub mod language;
+pub mod stages;
+
+// 引擎、并发调度和搜索接口需要 native 特性
+#[cfg(feature = "native")]
+pub mod engines;
+#[cfg(feature = "native")]
+pub mod types;
+#[cfg(feature = "native")]
 pub mod engine_manager;
+#[cfg(feature = "native")]
+pub mod translate;
+#[cfg(feature = "native")]
+pub mod concurrency;
+#[cfg(feature = "native")]
+pub mod archive;
+#[cfg(feature = "native")]
+pub mod answers;
+#[cfg(feature = "native")]
+pub mod pipeline;
+#[cfg(feature = "native")]
+pub mod anomaly;
+#[cfg(feature = "native")]
+pub mod slowlog;
+#[cfg(feature = "native")]
+pub mod latency;
+#[cfg(feature = "semantic")]
+pub mod semantic;
+#[cfg(feature = "native")]
+pub mod extract;
+#[cfg(feature = "native")]
+pub mod llm;
+#[cfg(feature = "native")]
+pub mod synthesis;
+#[cfg(feature = "native")]
+pub mod summarize;
+#[cfg(feature = "native")]
+pub mod selftest;
+#[cfg(feature = "browser")]
+pub mod browser;
+#[cfg(feature = "preview")]
+pub mod preview;
 
 // 核心组件
-pub mod engine_config;
+#[cfg(feature = "native")]
 pub mod on;
 
 // 统一导出 - 明确导出以避免歧义
-pub use aggregator::{SearchAggregator, AggregationStrategy, SortBy};
+pub use aggregator::{SearchAggregator, AggregationStrategy, SortBy, SOURCE_ENGINE_KEY, ENGINE_SCORE_KEY, diversify_by_domain};
 pub use query::{QueryParser, ParsedQuery};
-pub use types::{SearchRequest, SearchResponse, SearchConfig};
+#[cfg(feature = "native")]
+pub use types::{SearchRequest, SearchResponse, SearchConfig, SearxngImportConfig, EarlyResponse, EngineCounts, EngineTiming, EngineTimingStatus, SearchStatus};
+#[cfg(feature = "native")]
+pub use translate::{TranslationConfig, TranslationProvider, Translator, TRANSLATED_QUERY_KEY};
+pub use filter::{refine_items, ResultFilter};
+pub use highlight::{find_highlights, highlight_terms, HighlightRange};
+pub use stages::{ResultContext, ResultStage};
+pub use news::{cluster_news, NewsArticle, NewsCluster};
+#[cfg(feature = "native")]
+pub use concurrency::FetchLimiter;
+#[cfg(feature = "native")]
+pub use archive::{ArchiveConfig, ARCHIVE_URL_KEY};
+#[cfg(feature = "native")]
+pub use answers::{Answer, AnswerService, AnswersConfig};
+#[cfg(feature = "native")]
+pub use pipeline::{PipelineConfig, QueryContext, QueryPipeline, QueryStage};
+#[cfg(feature = "native")]
+pub use anomaly::{AnomalyConfig, AnomalyDetector, AnomalyKind, EngineAnomaly, Observation};
+#[cfg(feature = "semantic")]
+pub use semantic::{create_reranker, Embedder, HttpEmbedder, SemanticConfig, SemanticReranker, SEMANTIC_SCORE_KEY};
+#[cfg(feature = "native")]
+pub use llm::{ChatCompletion, ChatMessage, LlmClient, LlmConfig, TokenUsage};
+#[cfg(feature = "native")]
+pub use summarize::{textrank_summary, Summarizer, SummaryConfig, SummaryMode, SUMMARY_KEY};
+#[cfg(feature = "native")]
+pub use synthesis::{AnswerSource, AnswerSynthesizer, SynthesisConfig, SynthesizedAnswer};
+#[cfg(feature = "native")]
+pub use latency::{EngineLatencies, LatencyConfig, LatencyPercentiles};
+#[cfg(feature = "native")]
+pub use slowlog::{SlowQuery, SlowQueryConfig, SlowQueryLog, TopQuery};
+#[cfg(feature = "native")]
+pub use selftest::{EngineSelfTest, SelfTestQuery, SelfTestReport, DEFAULT_SELFTEST_QUERIES};
+#[cfg(feature = "browser")]
+pub use browser::{BrowserEngineAdapter, BrowserPool, BrowserPoolConfig, PageRenderer, PageScreenshotter, ScreenshotOptions};
+#[cfg(feature = "preview")]
+pub use preview::{PreviewConfig, PreviewService};
 pub use scoring::{BM25Params, ScoringWeights, get_engine_authority, score_results, score_and_sort_results};
-pub use standardization::{clean_text, standardize_item, deduplicate_by_url, standardize_results};
+pub use standardization::{clean_text, standardize_item, standardize_item_with, deduplicate_by_url, standardize_results};
+pub use sanitize::{sanitize_fragment, sanitize_text, SanitizeConfig};
+pub use security::SecurityFlagsConfig;
 
 // 引擎配置导出
 pub use engine_config::{EngineListConfig, EngineMode};
 
 // 引擎管理器导出（避免全局导出避免冲突）
-pub use engine_manager::{EngineManager, EngineState};
+#[cfg(feature = "native")]
+pub use engine_manager::{EngineManager, EngineState, EngineCircuitState, EngineErrorRecord, EngineLoadError, EngineReloadReport};
 
 // 主要接口导出
+#[cfg(feature = "native")]
 pub use on::{SearchInterface, SearchStats, SearchStatsResult};
@@ -0,0 +1,205 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 新闻聚类
+//!
+//! 把不同媒体对同一事件的报道归为一簇。标题先归一化（小写、去标点），
+//! 再按字符二元组的 Jaccard 相似度做单链接聚类，对中英文标题都适用。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 默认的聚类相似度阈值
+pub const DEFAULT_CLUSTER_THRESHOLD: f64 = 0.5;
+
+/// 待聚类的新闻
+#[derive(Debug, Clone, Serialize)]
+pub struct NewsArticle {
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+    /// 摘要
+    pub summary: String,
+    /// 来源（引擎名、公众号或 RSS feed）
+    pub source: String,
+    /// 发布时间
+    pub published: Option<DateTime<Utc>>,
+}
+
+/// 同一事件的新闻簇
+#[derive(Debug, Clone, Serialize)]
+pub struct NewsCluster {
+    /// 代表标题（与簇内其他标题最相似的一篇）
+    pub title: String,
+    /// 代表链接
+    pub url: String,
+    /// 代表摘要
+    pub summary: String,
+    /// 簇内最早的发布时间
+    pub earliest: Option<DateTime<Utc>>,
+    /// 报道来源（去重，按出现顺序）
+    pub sources: Vec<String>,
+    /// 簇内全部报道
+    pub articles: Vec<NewsArticle>,
+}
+
+/// 标题的字符二元组集合
+fn title_shingles(title: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if chars.len() == 1 {
+        return HashSet::from([(chars[0], ' ')]);
+    }
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+fn jaccard(a: &HashSet<(char, char)>, b: &HashSet<(char, char)>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(b).count();
+    intersection as f64 / (a.len() + b.len() - intersection) as f64
+}
+
+/// 聚类新闻
+///
+/// 标题相似度不低于 `threshold` 的报道（直接或经由簇内其他报道）归为一簇；
+/// 链接相同的报道只保留第一篇。返回的簇按报道数降序、最早时间降序排列。
+pub fn cluster_news(articles: Vec<NewsArticle>, threshold: f64) -> Vec<NewsCluster> {
+    let mut seen_urls = HashSet::new();
+    let articles: Vec<NewsArticle> = articles
+        .into_iter()
+        .filter(|a| seen_urls.insert(a.url.clone()))
+        .collect();
+    let shingles: Vec<_> = articles.iter().map(|a| title_shingles(&a.title)).collect();
+
+    // 并查集
+    let mut parent: Vec<usize> = (0..articles.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut node = i;
+        while parent[node] != root {
+            let next = parent[node];
+            parent[node] = root;
+            node = next;
+        }
+        root
+    }
+    for i in 0..articles.len() {
+        for j in (i + 1)..articles.len() {
+            if jaccard(&shingles[i], &shingles[j]) >= threshold {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                if a != b {
+                    parent[b] = a;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root = std::collections::HashMap::new();
+    for i in 0..articles.len() {
+        let root = find(&mut parent, i);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(i);
+    }
+
+    let mut clusters: Vec<NewsCluster> = groups
+        .into_iter()
+        .map(|members| {
+            // 代表报道：与其他报道相似度之和最大，相同时取较早出现的
+            let representative = *members
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let score = |i: usize| members.iter().map(|&j| jaccard(&shingles[i], &shingles[j])).sum::<f64>();
+                    score(a).total_cmp(&score(b)).then(b.cmp(&a))
+                })
+                .expect("cluster is never empty");
+            let mut sources = Vec::new();
+            for &i in &members {
+                if !sources.contains(&articles[i].source) {
+                    sources.push(articles[i].source.clone());
+                }
+            }
+            let rep = &articles[representative];
+            NewsCluster {
+                title: rep.title.clone(),
+                url: rep.url.clone(),
+                summary: rep.summary.clone(),
+                earliest: members.iter().filter_map(|&i| articles[i].published).min(),
+                sources,
+                articles: members.iter().map(|&i| articles[i].clone()).collect(),
+            }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| b.articles.len().cmp(&a.articles.len()).then(b.earliest.cmp(&a.earliest)));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, url: &str, source: &str, ts: Option<i64>) -> NewsArticle {
+        NewsArticle {
+            title: title.to_string(),
+            url: url.to_string(),
+            summary: String::new(),
+            source: source.to_string(),
+            published: ts.and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        }
+    }
+
+    #[test]
+    fn test_cluster_same_event_from_different_outlets() {
+        let clusters = cluster_news(
+            vec![
+                article("Rust 1.80 发布：LazyCell 与 LazyLock 稳定", "https://a.com/1", "Sogou WeChat", Some(200)),
+                article("Rust 1.80 正式发布，LazyCell 和 LazyLock 稳定", "https://b.com/1", "新华网", Some(100)),
+                article("Rust 1.80 发布：LazyCell 与 LazyLock 稳定", "https://a.com/1", "Bing", Some(300)),
+                article("Python 3.13 引入实验性 JIT", "https://c.com/1", "Bing", None),
+            ],
+            DEFAULT_CLUSTER_THRESHOLD,
+        );
+
+        assert_eq!(clusters.len(), 2);
+        let rust = &clusters[0];
+        assert_eq!(rust.articles.len(), 2);
+        assert_eq!(rust.sources, ["Sogou WeChat", "新华网"]);
+        assert_eq!(rust.earliest, DateTime::from_timestamp(100, 0));
+        assert_eq!(clusters[1].title, "Python 3.13 引入实验性 JIT");
+        assert_eq!(clusters[1].earliest, None);
+    }
+
+    #[test]
+    fn test_similarity_ignores_case_and_punctuation() {
+        let a = title_shingles("Apple Unveils New iPhone!");
+        let b = title_shingles("apple unveils new iphone");
+        assert_eq!(jaccard(&a, &b), 1.0);
+        assert_eq!(jaccard(&title_shingles(""), &b), 0.0);
+        assert!(cluster_news(Vec::new(), DEFAULT_CLUSTER_THRESHOLD).is_empty());
+    }
+}
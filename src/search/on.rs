@@ -16,17 +16,53 @@
 //!
 //! 提供统一的搜索接口供外部使用
 
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time::timeout;
 use futures::stream::{FuturesUnordered, StreamExt};
+use tracing::Instrument;
 
 use super::aggregator::{SearchAggregator, AggregationStrategy, SortBy};
 use super::query::QueryParser;
-use super::types::{SearchConfig, SearchRequest, SearchResponse};
+use super::types::{EarlyResponse, EngineCounts, EngineTiming, EngineTimingStatus, SearchConfig, SearchRequest, SearchResponse};
 use super::engine_config::{EngineListConfig, EngineMode};
-use crate::derive::SearchResult;
+use super::answers::{create_answer_service, Answer, AnswerService};
+use super::archive::annotate_archive_urls;
+use super::translate::{create_translator, Translator, TRANSLATED_QUERY_KEY};
+use super::pipeline::{PreparedTranslation, QueryContext, QueryPipeline, QueryStage, TranslationStage};
+use super::stages::{PositionedStage, ResultStage};
+use super::concurrency::{FetchLimiter, RequestFetchLimiter};
+use super::anomaly::{AnomalyDetector, Observation};
+use super::slowlog::SlowQueryLog;
+use super::latency::{EngineLatencies, LatencyPercentiles};
+use super::summarize::{create_summarizer, Summarizer};
+use super::synthesis::{create_synthesizer, AnswerSynthesizer};
+use super::selftest::{parse_warnings, EngineSelfTest, SelfTestQuery, SelfTestReport};
+use crate::cache::CacheInterface;
+use crate::net::client::{create_backend_transport, create_transport, HttpTransport};
+use crate::net::types::{HttpBackend, NetworkMode};
+use crate::derive::{SearchQuery, SearchResult};
+
+/// 加载引擎脚本目录并导入 SearXNG 引擎，合并两者的结果
+#[cfg(feature = "python")]
+fn load_python_engines(config: &SearchConfig) -> Result<super::engine_manager::EngineReloadReport, String> {
+    use crate::python_bindings::py_engine_loader::{import_searxng_settings, load_engines_dir};
+
+    let mut report = super::engine_manager::EngineReloadReport::default();
+    if let Some(dir) = &config.engines_dir {
+        report = load_engines_dir(dir)?;
+    }
+    if let Some(searxng) = &config.searxng {
+        let imported = import_searxng_settings(&searxng.settings, &searxng.modules_dir)?;
+        report.loaded.extend(imported.loaded);
+        report.removed.extend(imported.removed);
+        report.errors.extend(imported.errors);
+    }
+    Ok(report)
+}
 
 /// 搜索接口
 ///
@@ -40,12 +76,132 @@ pub struct SearchInterface {
     parser: QueryParser,
     /// HTTP客户端（复用）
     http_client: Arc<crate::net::client::HttpClient>,
+    /// 引擎使用的传输层
+    transport: Arc<dyn HttpTransport>,
+    /// 按引擎覆盖的传输层
+    engine_transports: HashMap<String, Arc<dyn HttpTransport>>,
     /// 引擎实例缓存
     engine_cache: Arc<RwLock<std::collections::HashMap<String, Arc<dyn crate::derive::SearchEngine + Send + Sync>>>>,
     /// 引擎状态（用于零结果指数禁用）
-    engine_states: Arc<RwLock<std::collections::HashMap<String, super::engine_manager::EngineState>>>,
+    engine_states: Arc<EngineStates>,
     /// 统计信息
     stats: Arc<SearchStats>,
+    /// 最近的引擎错误（环形缓冲）
+    recent_errors: Arc<std::sync::Mutex<VecDeque<super::engine_manager::EngineErrorRecord>>>,
+    /// 查询预处理流水线
+    pipeline: QueryPipeline,
+    /// 自定义结果后处理阶段（插入位置，阶段），运行时可追加
+    result_stages: Arc<std::sync::RwLock<Vec<PositionedStage>>>,
+    /// 共享的缓存接口（缓存数据库不可用时为 None）
+    cache: Option<Arc<CacheInterface>>,
+    /// 所有请求共享的引擎请求并发限制
+    fetch_limiter: FetchLimiter,
+    /// 即时答案服务（未启用时为 None）
+    answers: Option<Arc<AnswerService>>,
+    /// 引擎响应异常检测
+    anomalies: Arc<AnomalyDetector>,
+    /// 慢查询日志
+    slow_queries: Arc<SlowQueryLog>,
+    /// 各引擎的延迟直方图
+    latencies: Arc<EngineLatencies>,
+    /// 结果摘要生成器（未启用时为 None）
+    summarizer: Option<Arc<Summarizer>>,
+    /// 答案生成器（未启用时为 None）
+    synthesizer: Option<Arc<AnswerSynthesizer>>,
+    /// 语义重排器（未启用时为 None）
+    #[cfg(feature = "semantic")]
+    reranker: Option<Arc<super::semantic::SemanticReranker>>,
+}
+
+/// 保留的最近引擎错误条数
+const MAX_RECENT_ERRORS: usize = 50;
+
+/// 单个引擎的执行结果：搜索结果或错误信息、引擎名，以及耗时
+type EngineOutcome = (Result<SearchResult, String>, String, EngineTiming);
+
+/// 引擎状态表
+type EngineStates = RwLock<HashMap<String, super::engine_manager::EngineState>>;
+
+/// 记录引擎执行结果（零结果退避、成功和失败计数、最近错误）
+struct OutcomeRecorder {
+    engine_states: Arc<EngineStates>,
+    recent_errors: Arc<std::sync::Mutex<VecDeque<super::engine_manager::EngineErrorRecord>>>,
+    anomalies: Arc<AnomalyDetector>,
+}
+
+impl OutcomeRecorder {
+    async fn record(&self, engine_name: &str, outcome: &Result<SearchResult, String>) {
+        self.anomalies.observe(engine_name, Observation::from_outcome(outcome));
+        match outcome {
+            Ok(result) if result.items.is_empty() => {
+                // 零结果，更新引擎状态并应用指数退避
+                metrics::counter!("seesea_engine_zero_results_total", "engine" => engine_name.to_string()).increment(1);
+                let mut states = self.engine_states.write().await;
+                if let Some(state) = states.get_mut(engine_name) {
+                    state.record_zero_results();
+                    set_engine_disabled_gauge(engine_name, !state.is_available());
+                }
+            }
+            Ok(result) => {
+                // 有结果，记录成功
+                let mut states = self.engine_states.write().await;
+                if let Some(state) = states.get_mut(engine_name) {
+                    state.record_success(result.elapsed_ms);
+                }
+            }
+            Err(e) => {
+                // 失败，记录失败
+                push_recent_error(&self.recent_errors, engine_name, e.clone());
+                let mut states = self.engine_states.write().await;
+                let state = states.entry(engine_name.to_string())
+                    .or_insert_with(|| super::engine_manager::EngineState::new(engine_name.to_string()));
+                state.record_failure();
+                set_engine_disabled_gauge(engine_name, !state.is_available());
+            }
+        }
+    }
+}
+
+/// 搜索被取消时返回的错误，同时记录取消次数
+fn search_cancelled() -> Box<dyn std::error::Error + Send + Sync> {
+    metrics::counter!("seesea_search_cancelled_total").increment(1);
+    tracing::debug!("Search cancelled, in-flight engine requests aborted");
+    "Search cancelled".into()
+}
+
+/// 追加一条最近引擎错误（超出上限时丢弃最旧的）
+fn push_recent_error(
+    errors: &std::sync::Mutex<VecDeque<super::engine_manager::EngineErrorRecord>>,
+    engine: &str,
+    message: String,
+) {
+    let mut errors = errors.lock().unwrap_or_else(|e| e.into_inner());
+    if errors.len() >= MAX_RECENT_ERRORS {
+        errors.pop_front();
+    }
+    errors.push_back(super::engine_manager::EngineErrorRecord {
+        engine: engine.to_string(),
+        message,
+        timestamp: chrono::Utc::now(),
+    });
+}
+
+/// 打开默认缓存
+///
+/// 缓存只打开一次，全文搜索、本地文件引擎和 API 处理器共用；
+/// 测试中每个搜索接口使用独立的临时数据库，测试之间不会共用结果缓存
+fn default_cache() -> Option<Arc<CacheInterface>> {
+    #[cfg(test)]
+    return Some(Arc::new(CacheInterface::with_manager(crate::cache::CacheManager::temporary())));
+
+    #[cfg(not(test))]
+    match CacheInterface::new(crate::cache::CacheImplConfig::default()) {
+        Ok(cache) => Some(Arc::new(cache)),
+        Err(e) => {
+            tracing::warn!("Cache unavailable, cache-backed features are disabled: {}", e);
+            None
+        }
+    }
 }
 
 impl SearchInterface {
@@ -61,27 +217,190 @@ impl SearchInterface {
     pub fn new(
         config: SearchConfig,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        let aggregator = SearchAggregator::default();
+        Self::new_with_cache(config, default_cache())
+    }
+
+    /// 使用指定的缓存接口创建搜索接口（为 None 时不使用缓存，不会打开默认缓存数据库）
+    pub fn new_with_cache(
+        mut config: SearchConfig,
+        cache: Option<Arc<CacheInterface>>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let aggregator = SearchAggregator::default()
+            .with_scoring(config.scoring_weights())
+            .with_sanitize(config.sanitize.clone())
+            .with_security_flags(config.security_flags.clone());
         let parser = QueryParser::default();
 
         // 创建共享HTTP客户端以提高性能
-        let network_config = crate::net::types::NetworkConfig::default();
         let http_client = Arc::new(
-            crate::net::client::HttpClient::new(network_config)
+            crate::net::client::HttpClient::new(config.network.clone())
                 .map_err(|e| format!("Failed to create HTTP client: {}", e))?
         );
+        // 引擎使用的传输层由 network.backend 和 network.mode 决定（默认即共享客户端），
+        // engine_backends 中的引擎各自使用单独的传输层
+        let transport: Arc<dyn HttpTransport> = match (config.network.mode, &config.network.backend) {
+            (NetworkMode::Live, HttpBackend::Reqwest) => http_client.clone(),
+            _ => create_transport(&config.network)
+                .map_err(|e| format!("Failed to create HTTP transport: {}", e))?,
+        };
+        let mut engine_transports = HashMap::new();
+        for (engine, backend) in &config.network.engine_backends {
+            let engine_transport = create_backend_transport(&config.network, backend)
+                .map_err(|e| format!("Failed to create HTTP transport for {}: {}", engine, e))?;
+            engine_transports.insert(engine.clone(), engine_transport);
+        }
+
+        // 声明式引擎的快捷方式作为 bang 使用（不覆盖已配置的 bang）
+        for (shortcut, engine) in config.declared_shortcuts() {
+            config.pipeline.bangs.entry(shortcut).or_insert_with(|| vec![engine]);
+        }
+
+        // 加载引擎脚本目录和 SearXNG 引擎（失败只记录日志，不影响启动），
+        // 引擎的快捷方式作为 bang 使用（不覆盖已配置的 bang）
+        #[cfg(feature = "python")]
+        if config.engines_dir.is_some() || config.searxng.is_some() {
+            match load_python_engines(&config) {
+                Ok(report) => {
+                    for error in &report.errors {
+                        tracing::warn!("Failed to load engine script {}: {}", error.file, error.error);
+                    }
+                    tracing::info!("Loaded {} Python engines", report.loaded.len());
+                }
+                Err(e) => tracing::warn!("Python engines not loaded: {}", e),
+            }
+            for (shortcut, engine) in crate::python_bindings::py_engine_loader::engine_shortcuts() {
+                config.pipeline.bangs.entry(shortcut).or_insert_with(|| vec![engine]);
+            }
+        }
+
+        let translator = create_translator(&config.translation, http_client.clone());
+        let pipeline = QueryPipeline::from_config(&config.pipeline, translator, &config.translation)?;
+
+        let fetch_limiter = FetchLimiter::new(config.max_concurrent_fetches, config.max_concurrent_engines);
+        let answers = create_answer_service(&config.answers, transport.clone());
+        let anomalies = Arc::new(AnomalyDetector::new(config.anomaly.clone()));
+        let slow_queries = Arc::new(SlowQueryLog::new(config.slow_queries.clone()));
+        let latencies = Arc::new(EngineLatencies::new(config.latency.window));
+        let summarizer = create_summarizer(&config.summary, transport.clone());
+        let synthesizer = create_synthesizer(&config.synthesis, transport.clone());
+        #[cfg(feature = "semantic")]
+        let reranker = super::semantic::create_reranker(&config.semantic, transport.clone());
 
         Ok(Self {
             config,
             aggregator,
             parser,
             http_client,
+            transport,
+            engine_transports,
             engine_cache: Arc::new(RwLock::new(std::collections::HashMap::new())),
             engine_states: Arc::new(RwLock::new(std::collections::HashMap::new())),
             stats: Arc::new(SearchStats::default()),
+            recent_errors: Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(MAX_RECENT_ERRORS))),
+            pipeline,
+            result_stages: Arc::new(std::sync::RwLock::new(Vec::new())),
+            cache,
+            fetch_limiter,
+            answers,
+            anomalies,
+            slow_queries,
+            latencies,
+            summarizer,
+            synthesizer,
+            #[cfg(feature = "semantic")]
+            reranker,
         })
     }
 
+    /// 使用指定的缓存接口（替换构造时打开的默认缓存）
+    pub fn with_cache(mut self, cache: Arc<CacheInterface>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// 搜索配置
+    pub fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    /// 共享的缓存接口
+    pub fn cache(&self) -> Option<&Arc<CacheInterface>> {
+        self.cache.as_ref()
+    }
+
+    /// 引擎默认使用的传输层（可能按 network.mode 录制/回放，或使用浏览器伪装后端）
+    pub fn transport(&self) -> &Arc<dyn HttpTransport> {
+        &self.transport
+    }
+
+    /// 共享的 HTTP 客户端，总是直接请求目标地址，用于 webhook 等非引擎的出站请求
+    pub fn http_client(&self) -> &Arc<crate::net::client::HttpClient> {
+        &self.http_client
+    }
+
+    /// 所有引擎改用自定义传输层（例如测试替身），已创建的引擎实例会被丢弃
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.answers = create_answer_service(&self.config.answers, transport.clone());
+        self.summarizer = create_summarizer(&self.config.summary, transport.clone());
+        self.synthesizer = create_synthesizer(&self.config.synthesis, transport.clone());
+        self.transport = transport;
+        self.engine_transports.clear();
+        self.engine_cache = Arc::new(RwLock::new(std::collections::HashMap::new()));
+        self
+    }
+
+    /// 使用自定义翻译服务（同时启用翻译后搜索）
+    pub fn with_translator(mut self, translator: Arc<dyn Translator>) -> Self {
+        self.config.translation.enabled = true;
+        self.pipeline.replace(Arc::new(TranslationStage::new(Some(translator), self.config.translation.clone())));
+        self
+    }
+
+    /// 在查询预处理流水线末尾追加自定义阶段
+    pub fn with_query_stage(mut self, stage: Arc<dyn QueryStage>) -> Self {
+        self.pipeline.push(stage);
+        self
+    }
+
+    /// 追加自定义结果后处理阶段
+    ///
+    /// `before` 为内置阶段名（如 `sort`）时插在该阶段之前，否则追加到后处理链末尾
+    pub fn with_result_stage(self, before: Option<&str>, stage: Arc<dyn ResultStage>) -> Self {
+        self.add_result_stage(before, stage);
+        self
+    }
+
+    /// 在运行时追加自定义结果后处理阶段（对之后的搜索生效），参数同 [`Self::with_result_stage`]
+    pub fn add_result_stage(&self, before: Option<&str>, stage: Arc<dyn ResultStage>) {
+        self.result_stages
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((before.map(str::to_string), stage));
+    }
+
+    /// 在聚合器上挂载自定义结果后处理阶段
+    fn with_custom_stages(&self, mut aggregator: SearchAggregator) -> SearchAggregator {
+        let stages = self.result_stages.read().unwrap_or_else(|e| e.into_inner());
+        for (before, stage) in stages.iter() {
+            aggregator = match before {
+                Some(before) => aggregator.with_result_stage_before(before, stage.clone()),
+                None => aggregator.with_result_stage(stage.clone()),
+            };
+        }
+        aggregator
+    }
+
+    /// 使用自定义即时答案服务（例如接入其他汇率或股票数据源）
+    pub fn with_answer_service(mut self, answers: Arc<AnswerService>) -> Self {
+        self.answers = Some(answers);
+        self
+    }
+
+    /// 为查询生成即时答案（汇率换算、股票报价），未启用或不匹配时返回 None
+    pub async fn answer(&self, query: &str) -> Option<Answer> {
+        self.answers.as_ref()?.answer(query).await
+    }
+
     /// 执行搜索
     ///
     /// # Arguments
@@ -95,39 +414,155 @@ impl SearchInterface {
         &self,
         request: &SearchRequest,
     ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
-        // 解析查询
-        let _parsed = self.parser.parse(&request.query.query);
+        self.search_with_aggregator(request, &self.aggregator).await
+    }
 
-        // 确定要使用的引擎列表
-        let engines_to_use = if request.engines.is_empty() {
-            // 如果没有指定引擎，使用默认全局引擎
-            let all_engines = EngineListConfig::get_default_engines();
-            all_engines
-        } else {
-            // 使用请求中指定的引擎列表（验证可用性）
-            let config = EngineListConfig::default();
-            config.filter_available_engines(&request.engines)
-        };
+    /// 确定一次搜索实际使用的引擎（请求中的声明式引擎同样可用）
+    fn resolve_engines(&self, requested: &[String]) -> Vec<String> {
+        let resolved = EngineListConfig::resolve_engines(requested);
+        if requested.is_empty() {
+            return resolved;
+        }
+        requested
+            .iter()
+            .filter(|engine| resolved.contains(engine) || self.config.is_declared_engine(engine))
+            .cloned()
+            .collect()
+    }
+
+    /// 使用指定聚合器执行搜索
+    async fn search_with_aggregator(
+        &self,
+        request: &SearchRequest,
+        aggregator: &SearchAggregator,
+    ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let start_time = std::time::Instant::now();
+        // 查询预处理（规范化、bang、拼写提示、翻译等），之后使用处理后的请求
+        let context = self.pipeline.run(request.clone()).await?;
+        let request = &context.request;
 
+        // 确定要使用的引擎列表（未指定时使用默认全局引擎）
+        let engines_to_use = self.resolve_engines(&request.engines);
         if engines_to_use.is_empty() {
             return Err("No available engines".into());
         }
 
         // 执行并发搜索
         let mut response = self.execute_concurrent_search(request, &engines_to_use).await?;
+        response.spell_hints = context.spell_hints.clone();
 
-        // 对结果进行聚合、评分和排序（无论有几个结果）
-        let aggregated = self.aggregator.aggregate_with_scoring(
-            response.results.clone(),
-            &request.query
+        // 翻译后搜索：翻译结果并入原结果，评分时同时使用原查询和翻译后的查询
+        let scoring_query = self.merge_translated(&context, &mut response).await;
+
+        // 对结果进行聚合、评分和排序（无论有几个结果），配置和请求的引擎权重乘在聚合器已有的权重上，
+        // 域名结果上限取请求或配置的值；请求指定语言时按语言过滤（并入了翻译后搜索的结果时除外），
+        // 结构化过滤和自定义阶段都在后处理链中执行
+        let language = match scoring_query {
+            std::borrow::Cow::Borrowed(_) => request.query.language.clone(),
+            std::borrow::Cow::Owned(_) => None,
+        };
+        let aggregator = self.with_request_options(aggregator.clone(), request, language);
+        let aggregated = aggregator.aggregate_with_scoring(
+            std::mem::take(&mut response.results),
+            &scoring_query
         );
         response.total_count = aggregated.items.len();
         // 用聚合后的结果替换原始结果
         response.results = vec![aggregated];
 
+        // 按相关性排序时用语义相似度重排靠前的结果，之后重新做域名多样性重排
+        #[cfg(feature = "semantic")]
+        if let Some(reranker) = &self.reranker
+            && aggregator.sort_by() == SortBy::Relevance
+        {
+            let max_per_domain = self.config.max_per_domain_for(request);
+            for result in &mut response.results {
+                if reranker.rerank(&request.query.query, &mut result.items).await {
+                    result.items = super::aggregator::diversify_by_domain(std::mem::take(&mut result.items), max_per_domain, |item| {
+                        (item.url.as_str(), item.score)
+                    });
+                }
+            }
+        }
+
+        // 为排名靠前的结果附加 Wayback Machine 快照链接
+        if self.config.archive.enabled {
+            let cache = self.cache.as_ref().map(|cache| cache.manager());
+            for result in &mut response.results {
+                annotate_archive_urls(&self.config.archive, self.transport.as_ref(), cache, &mut result.items).await;
+            }
+        }
+
+        // 为排名靠前的结果生成页面摘要
+        if let Some(summarizer) = &self.summarizer {
+            let cache = self.cache.as_ref().map(|cache| cache.manager());
+            for result in &mut response.results {
+                summarizer.annotate(cache, &mut result.items).await;
+            }
+        }
+
+        self.slow_queries.record(&request.query.query, start_time.elapsed().as_millis() as u64, &response.engine_timings);
         Ok(response)
     }
 
+    /// 预处理阶段准备了译文时执行翻译后搜索，返回评分使用的查询
+    ///
+    /// 并入了译文结果时为原查询和译文拼接后的查询，否则为原查询
+    async fn merge_translated<'a>(
+        &self,
+        context: &'a QueryContext,
+        response: &mut SearchResponse,
+    ) -> std::borrow::Cow<'a, SearchQuery> {
+        let request = &context.request;
+        if let Some(translation) = &context.translation
+            && let Some(translated) = self.search_translated(request, translation, response).await
+        {
+            let mut query = request.query.clone();
+            query.query = format!("{} {}", request.query.query, translated);
+            return std::borrow::Cow::Owned(query);
+        }
+        std::borrow::Cow::Borrowed(&request.query)
+    }
+
+    /// 用预处理阶段准备的译文查询搜索目标语言的引擎，并把结果并入 `response`
+    ///
+    /// 搜索失败时不影响原搜索，返回 None
+    async fn search_translated(
+        &self,
+        request: &SearchRequest,
+        translation: &PreparedTranslation,
+        response: &mut SearchResponse,
+    ) -> Option<String> {
+        let translated = translation.text.clone();
+        let mut translated_request = request.clone();
+        translated_request.query.query = translated.clone();
+        translated_request.query.language = Some(translation.language.clone());
+
+        match self.execute_concurrent_search(&translated_request, &translation.engines).await {
+            Ok(extra) => {
+                for mut result in extra.results {
+                    for item in &mut result.items {
+                        item.metadata.insert(TRANSLATED_QUERY_KEY.to_string(), translated.clone());
+                    }
+                    response.results.push(result);
+                }
+                for engine in extra.engines_used {
+                    if !response.engines_used.contains(&engine) {
+                        response.engines_used.push(engine);
+                    }
+                }
+                response.engine_timings.extend(extra.engine_timings);
+                response.engine_counts.merge(&extra.engine_counts);
+                response.translated_query = Some(translated.clone());
+                Some(translated)
+            }
+            Err(e) => {
+                tracing::warn!("Translated search failed: {}", e);
+                None
+            }
+        }
+    }
+
     /// 带模式执行搜索
     ///
     /// # Arguments
@@ -158,8 +593,8 @@ impl SearchInterface {
         let mut response = self.execute_concurrent_search(request, &engines_to_use).await?;
 
         // 对结果进行聚合、评分和排序（无论有几个结果）
-        let aggregated = self.aggregator.aggregate_with_scoring(
-            response.results.clone(),
+        let aggregated = self.with_custom_stages(self.aggregator.clone()).aggregate_with_scoring(
+            std::mem::take(&mut response.results),
             &request.query
         );
         response.total_count = aggregated.items.len();
@@ -182,10 +617,66 @@ impl SearchInterface {
     pub async fn search_with_options(
         &self,
         request: &SearchRequest,
-        _strategy: AggregationStrategy,
-        _sort_by: SortBy,
+        strategy: AggregationStrategy,
+        sort_by: SortBy,
     ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
-        self.search(request).await
+        self.search_with_engine_weights(request, strategy, sort_by, HashMap::new()).await
+    }
+
+    /// 带引擎权重执行搜索
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - 搜索请求
+    /// * `strategy` - 聚合策略
+    /// * `sort_by` - 排序方式
+    /// * `engine_weights` - 引擎权重系数（引擎名 -> 分数乘数）
+    ///
+    /// # Returns
+    ///
+    /// 返回搜索响应或错误
+    pub async fn search_with_engine_weights(
+        &self,
+        request: &SearchRequest,
+        strategy: AggregationStrategy,
+        sort_by: SortBy,
+        engine_weights: HashMap<String, f64>,
+    ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let aggregator = self.base_aggregator(strategy, sort_by, engine_weights);
+        self.search_with_aggregator(request, &aggregator).await
+    }
+
+    /// 按请求构建聚合器（与 [`search_with_engine_weights`](Self::search_with_engine_weights) 使用的相同），
+    /// 供调用方自行聚合 [`search_streaming`](Self::search_streaming) 逐个返回的引擎结果
+    pub fn aggregator_for(
+        &self,
+        request: &SearchRequest,
+        strategy: AggregationStrategy,
+        sort_by: SortBy,
+        engine_weights: HashMap<String, f64>,
+    ) -> SearchAggregator {
+        let aggregator = self.base_aggregator(strategy, sort_by, engine_weights);
+        self.with_request_options(aggregator, request, request.query.language.clone())
+    }
+
+    /// 使用配置的评分权重、HTML 清理和安全标记创建聚合器
+    fn base_aggregator(&self, strategy: AggregationStrategy, sort_by: SortBy, engine_weights: HashMap<String, f64>) -> SearchAggregator {
+        SearchAggregator::new(strategy, sort_by)
+            .with_scoring(self.config.scoring_weights())
+            .with_sanitize(self.config.sanitize.clone())
+            .with_security_flags(self.config.security_flags.clone())
+            .with_engine_weights(engine_weights)
+    }
+
+    /// 叠加请求级的聚合选项：配置和请求的引擎权重、域名结果上限、语言过滤、安全模式、
+    /// 结构化过滤和自定义阶段
+    fn with_request_options(&self, aggregator: SearchAggregator, request: &SearchRequest, language: Option<String>) -> SearchAggregator {
+        self.with_custom_stages(aggregator)
+            .multiply_engine_weights(&self.config.engine_weights_for(request))
+            .with_max_per_domain(self.config.max_per_domain_for(request))
+            .with_language(language, request.lang_strict)
+            .with_security_strict(request.security_strict)
+            .with_filter(request.filter.clone())
     }
 
     /// 流式搜索 - 哪个搜索引擎先完成就先返回哪个的结果
@@ -213,17 +704,10 @@ impl SearchInterface {
         
         let start_time = std::time::Instant::now();
 
-        // 解析查询
-        let _parsed = self.parser.parse(&request.query.query);
-
-        // 确定要使用的引擎列表
-        let engines_to_use = if request.engines.is_empty() {
-            let all_engines = EngineListConfig::get_default_engines();
-            all_engines
-        } else {
-            let config = EngineListConfig::default();
-            config.filter_available_engines(&request.engines)
-        };
+        // 与 search 相同：先做查询预处理（规范化、bang、翻译等），再确定引擎（声明式引擎同样可用）
+        let context = self.pipeline.run(request.clone()).await?;
+        let request = &context.request;
+        let engines_to_use = self.resolve_engines(&request.engines);
 
         if engines_to_use.is_empty() {
             return Err("No available engines".into());
@@ -241,6 +725,7 @@ impl SearchInterface {
         // 创建 FuturesUnordered 用于流式处理
         let mut futures_unordered = FuturesUnordered::new();
         let mut engines_to_execute = Vec::new();
+        let (mut disabled, mut skipped) = (0, 0);
 
         // 获取所有要执行的引擎实例
         for engine_name in &engines_to_use {
@@ -248,7 +733,10 @@ impl SearchInterface {
             {
                 let states = self.engine_states.read().await;
                 if let Some(state) = states.get(engine_name) {
-                    if !state.is_available() {
+                    let available = state.is_available();
+                    set_engine_disabled_gauge(engine_name, !available);
+                    if !available {
+                        disabled += 1;
                         continue;
                     }
                 }
@@ -259,72 +747,43 @@ impl SearchInterface {
                 }
                 Err(_e) => {
                     self.stats.engine_failures.fetch_add(1, Ordering::Relaxed);
+                    skipped += 1;
                 }
             }
         }
 
-        // 创建并发任务
+        // 创建并发任务（同一请求的引擎共享请求内的并发名额）
+        let fetch_slots = self.fetch_limiter.for_request();
         for (engine_name, engine) in engines_to_execute {
-            let query = request.query.clone();
-            let timeout_duration = Duration::from_secs(self.config.default_timeout.as_secs());
-            let stats = Arc::clone(&self.stats);
-            
-            let future = async move {
-                let search_start = std::time::Instant::now();
-                match timeout(timeout_duration, engine.search(&query)).await {
-                    Ok(Ok(mut result)) => {
-                        result.elapsed_ms = search_start.elapsed().as_millis() as u64;
-                        Some((Ok(result), engine_name))
-                    }
-                    Ok(Err(e)) => {
-                        stats.engine_failures.fetch_add(1, Ordering::Relaxed);
-                        Some((Err(format!("Engine {} error: {}", engine_name, e)), engine_name))
-                    }
-                    Err(_) => {
-                        stats.timeouts.fetch_add(1, Ordering::Relaxed);
-                        Some((Err(format!("Engine {} timeout", engine_name)), engine_name))
-                    }
-                }
-            };
-            
-            futures_unordered.push(future);
+            futures_unordered.push(self.engine_task(request, engine_name, engine, fetch_slots.clone()));
         }
 
         // 流式处理结果
         let mut successful_results = Vec::new();
         let mut engines_used = Vec::new();
-
-        while let Some(result) = futures_unordered.next().await {
-            if let Some((search_result, engine_name)) = result {
-                match search_result {
-                    Ok(result) => {
-                        // 检查是否为零结果
-                        let is_zero_results = result.items.is_empty();
-
-                        if is_zero_results {
-                            // 零结果，更新引擎状态
-                            let mut states = self.engine_states.write().await;
-                            if let Some(state) = states.get_mut(&engine_name) {
-                                state.record_zero_results();
-                            }
-                        } else {
-                            // 有结果，记录成功
-                            let mut states = self.engine_states.write().await;
-                            if let Some(state) = states.get_mut(&engine_name) {
-                                state.record_success(result.elapsed_ms);
-                            }
-                            
-                            // 立即回调返回结果
-                            callback(result.clone(), engine_name.clone());
-                            
-                            successful_results.push(result);
-                            engines_used.push(engine_name);
-                        }
-                    }
-                    Err(_e) => {
-                        // 错误处理
-                        self.stats.engine_failures.fetch_add(1, Ordering::Relaxed);
-                    }
+        let mut engine_timings = Vec::new();
+        let cancellation = request.cancellation.clone().unwrap_or_default();
+        let recorder = self.outcome_recorder();
+
+        loop {
+            let result = tokio::select! {
+                result = futures_unordered.next() => result,
+                _ = cancellation.cancelled() => return Err(search_cancelled()),
+            };
+            let Some(result) = result else {
+                break;
+            };
+            if let Some((search_result, engine_name, timing)) = result {
+                engine_timings.push(timing);
+                // 失败次数已由 engine_task 计入，这里只记录引擎状态和最近错误
+                recorder.record(&engine_name, &search_result).await;
+                // 非空结果立即回调返回
+                if let Ok(result) = search_result
+                    && !result.items.is_empty()
+                {
+                    callback(result.clone(), engine_name.clone());
+                    successful_results.push(result);
+                    engines_used.push(engine_name);
                 }
             }
         }
@@ -340,15 +799,31 @@ impl SearchInterface {
             engines_used,
             query_time_ms,
             cached: false,
+            translated_query: None,
+            spell_hints: context.spell_hints.clone(),
+            engine_counts: engine_counts(engines_to_use.len(), disabled, skipped, &engine_timings),
+            engine_timings,
         };
 
-        // 对结果进行聚合、评分和排序
-        let aggregated = self.aggregator.aggregate_with_scoring(
-            response.results.clone(),
-            &request.query
+        // 与 search 相同：并入翻译后搜索的结果（逐个回调），再叠加请求级的权重、域名上限、
+        // 语言和结构化过滤进行聚合、评分和排序
+        let streamed = response.results.len();
+        let scoring_query = self.merge_translated(&context, &mut response).await;
+        for result in &response.results[streamed..] {
+            callback(result.clone(), result.engine_name.clone());
+        }
+        let language = match scoring_query {
+            std::borrow::Cow::Borrowed(_) => request.query.language.clone(),
+            std::borrow::Cow::Owned(_) => None,
+        };
+        let aggregator = self.with_request_options(self.aggregator.clone(), request, language);
+        let aggregated = aggregator.aggregate_with_scoring(
+            std::mem::take(&mut response.results),
+            &scoring_query
         );
         response.total_count = aggregated.items.len();
         response.results = vec![aggregated];
+        self.slow_queries.record(&request.query.query, start_time.elapsed().as_millis() as u64, &response.engine_timings);
 
         Ok(response)
     }
@@ -367,20 +842,13 @@ impl SearchInterface {
         request: &SearchRequest,
     ) -> Result<SearchResponse, Box<dyn std::error::Error + Send + Sync>> {
         use std::sync::atomic::Ordering;
-        use crate::cache::on::CacheInterface;
-        use crate::cache::types::CacheImplConfig;
         
         let start_time = std::time::Instant::now();
         
         // 1. 执行网络搜索
         let network_response = self.search(request).await?;
         
-        // 2. 从数据库获取所有相关结果（包括过期的）
-        // 创建缓存接口
-        let cache_config = CacheImplConfig::default();
-        let cache_interface = CacheInterface::new(cache_config)
-            .map_err(|e| format!("Failed to create cache interface: {}", e))?;
-        
+        // 2. 从数据库获取所有相关结果（包括过期的），缓存不可用时只返回网络结果
         // 从查询中提取关键词
         let query_keywords: Vec<String> = request.query.query
             .split_whitespace()
@@ -388,25 +856,25 @@ impl SearchInterface {
             .collect();
         
         // 从结果缓存搜索历史结果
-        let result_cache = cache_interface.results();
-        let cached_items = match result_cache.search_fulltext(&query_keywords, true, Some(50)) {
-            Ok(items) => items,
-            Err(e) => {
+        let cached_items = match self.cache.as_ref().map(|cache| cache.results().search_fulltext(&query_keywords, true, Some(50))) {
+            Some(Ok(items)) => items,
+            Some(Err(e)) => {
                 // 记录错误但不中断搜索流程
                 tracing::warn!("Failed to search result cache: {}", e);
                 Vec::new()
             }
+            None => Vec::new(),
         };
         
         // 从 RSS 缓存搜索相关内容
-        let rss_cache = cache_interface.rss();
-        let rss_items = match rss_cache.search_fulltext(&query_keywords, true, Some(30)) {
-            Ok(items) => items,
-            Err(e) => {
+        let rss_items = match self.cache.as_ref().map(|cache| cache.rss().search_fulltext(&query_keywords, true, Some(30))) {
+            Some(Ok(items)) => items,
+            Some(Err(e)) => {
                 // 记录错误但不中断搜索流程
                 tracing::warn!("Failed to search RSS cache: {}", e);
                 Vec::new()
             }
+            None => Vec::new(),
         };
         
         // 3. 将 RSS items 转换为 SearchResultItem
@@ -414,6 +882,7 @@ impl SearchInterface {
             use crate::derive::types::{SearchResultItem, ResultType};
             use std::collections::HashMap;
             
+            let published_date = crate::rss::date::item_published_date(&item);
             SearchResultItem {
                 title: item.title,
                 url: item.link,
@@ -423,8 +892,7 @@ impl SearchInterface {
                 score: 0.7, // RSS 结果的默认得分
                 result_type: ResultType::Web,
                 thumbnail: None,
-                // TODO: Implement date parsing for RSS pub_date string to DateTime
-                published_date: None,
+                published_date,
                 template: None,
                 metadata: HashMap::new(),
             }
@@ -434,8 +902,8 @@ impl SearchInterface {
         let mut all_items: Vec<crate::derive::types::SearchResultItem> = Vec::new();
         
         // 添加网络搜索结果（优先级最高）
-        for result in &network_response.results {
-            all_items.extend(result.items.clone());
+        for result in network_response.results {
+            all_items.extend(result.items);
         }
         
         // 添加缓存的历史结果
@@ -500,7 +968,7 @@ impl SearchInterface {
         let query_time_ms = start_time.elapsed().as_millis() as u64;
         
         // 8. 构建响应
-        let mut engines_used = network_response.engines_used.clone();
+        let mut engines_used = network_response.engines_used;
         engines_used.push("DatabaseCache".to_string());
         engines_used.push("RSSCache".to_string());
         
@@ -513,6 +981,10 @@ impl SearchInterface {
             engines_used,
             query_time_ms,
             cached: false, // 混合了网络和缓存结果
+            translated_query: None,
+            spell_hints: Vec::new(),
+            engine_timings: network_response.engine_timings,
+            engine_counts: network_response.engine_counts,
         })
     }
 
@@ -541,6 +1013,11 @@ impl SearchInterface {
         Ok(engine)
     }
 
+    /// 检查引擎名能否创建出引擎实例（内置引擎、本地文件引擎或已注册的 Python 引擎）
+    pub fn check_engine(&self, engine_name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.create_engine_instance(engine_name).map(|_| ())
+    }
+
     /// 创建引擎实例（Arc版本，用于缓存）
     fn create_engine_instance(
         &self,
@@ -548,21 +1025,66 @@ impl SearchInterface {
     ) -> Result<Arc<dyn crate::derive::SearchEngine + Send + Sync>, Box<dyn std::error::Error + Send + Sync>> {
         use crate::search::engines::*;
 
+        let transport = self.engine_transports.get(engine_name).unwrap_or(&self.transport);
         let engine: Arc<dyn crate::derive::SearchEngine + Send + Sync> = match engine_name {
-            "bing" => Arc::new(BingEngine::with_client(Arc::clone(&self.http_client))),
-            "baidu" => Arc::new(BaiduEngine::with_client(Arc::clone(&self.http_client))),
-            "yandex" => Arc::new(YandexEngine::with_client(Arc::clone(&self.http_client))),
-            "so" => Arc::new(SoEngine::with_client(Arc::clone(&self.http_client))),
-            "unsplash" => Arc::new(UnsplashEngine::with_client(Arc::clone(&self.http_client))),
-            "bing_images" => Arc::new(BingImagesEngine::with_client(Arc::clone(&self.http_client))),
-            "bilibili" => Arc::new(BilibiliEngine::with_client(Arc::clone(&self.http_client))),
-            "sogou" => Arc::new(SogouEngine::with_client(Arc::clone(&self.http_client))),
-            "sogou_videos" => Arc::new(SogouVideosEngine::with_client(Arc::clone(&self.http_client))),
+            "bing" => Arc::new(BingEngine::with_client(Arc::clone(transport))),
+            "baidu" => Arc::new(BaiduEngine::with_client(Arc::clone(transport))),
+            "yandex" => Arc::new(YandexEngine::with_client(Arc::clone(transport))),
+            "so" => Arc::new(SoEngine::with_client(Arc::clone(transport))),
+            "unsplash" => match self.config.engine_api_keys.get("unsplash") {
+                Some(access_key) => Arc::new(UnsplashEngine::with_access_key(Arc::clone(transport), access_key.as_str())),
+                None => Arc::new(UnsplashEngine::with_client(Arc::clone(transport))),
+            },
+            "bing_images" => Arc::new(BingImagesEngine::with_client(Arc::clone(transport))),
+            "bilibili" => Arc::new(BilibiliEngine::with_client(Arc::clone(transport))),
+            "sogou" => Arc::new(SogouEngine::with_client(Arc::clone(transport))),
+            "sogou_videos" => Arc::new(SogouVideosEngine::with_client(Arc::clone(transport))),
+            "sogou_wechat" => Arc::new(SogouWechatEngine::with_client(Arc::clone(transport))),
+            "local_files" => {
+                if self.config.local_files.roots.is_empty() {
+                    return Err("local_files engine has no configured roots".into());
+                }
+                let cache = self.cache.as_ref().ok_or("local_files engine requires the cache database")?;
+                Arc::new(LocalFilesEngine::new(self.config.local_files.clone(), Arc::new(cache.files())))
+            }
+            "podcasts" => {
+                let cache = self.cache.as_ref().ok_or("podcasts engine requires the cache database")?;
+                Arc::new(PodcastsEngine::new(Arc::new(cache.rss())))
+            }
+            "nominatim" => {
+                if self.config.nominatim.contact_email().is_none() {
+                    return Err("nominatim engine requires a contact email".into());
+                }
+                Arc::new(NominatimEngine::new(Arc::clone(transport), self.config.nominatim.clone()))
+            }
+            #[cfg(feature = "torrent")]
+            "torznab" => {
+                if self.config.torznab.endpoint.is_none() {
+                    return Err("torznab engine has no configured endpoint".into());
+                }
+                Arc::new(TorznabEngine::new(Arc::clone(transport), self.config.torznab.clone()))
+            }
             _ => {
+                // 配置中声明的 JSON API 引擎
+                if let Some(config) = self.config.json_engines.iter().find(|engine| engine.name == engine_name) {
+                    return Ok(Arc::new(JsonApiEngine::new(Arc::clone(transport), config.clone())));
+                }
+                // 配置中声明的 HTML 抓取引擎
+                if let Some(config) = self.config.xpath_engines.iter().find(|engine| engine.name == engine_name) {
+                    return Ok(Arc::new(XPathEngine::new(Arc::clone(transport), config.clone())));
+                }
+                // 配置中声明的 Meilisearch / Elasticsearch 索引
+                if let Some(config) = self.config.index_engines.iter().find(|engine| engine.name == engine_name) {
+                    return Ok(Arc::new(SearchIndexEngine::new(Arc::clone(transport), config.clone())));
+                }
                 // 尝试从Python注册表获取引擎
                 #[cfg(feature = "python")]
                 {
+                    use crate::python_bindings::py_engine_loader::{get_engine_script, ScriptEngine};
                     use crate::python_bindings::py_engine_registry::try_get_python_engine_sync;
+                    if let Some(script) = get_engine_script(engine_name) {
+                        return Ok(Arc::new(ScriptEngine::new(script, Arc::clone(transport))));
+                    }
                     if let Some(py_engine) = try_get_python_engine_sync(engine_name) {
                         return Ok(py_engine as Arc<dyn crate::derive::SearchEngine + Send + Sync>);
                     }
@@ -593,6 +1115,7 @@ impl SearchInterface {
         let start_time = std::time::Instant::now();
         let mut futures_list = Vec::new();
         let mut engines_to_execute = Vec::new();
+        let (mut disabled, mut skipped) = (0, 0);
 
         // 预先确保所有引擎都有状态记录
         {
@@ -603,94 +1126,80 @@ impl SearchInterface {
             }
         }
 
-        // 获取所有要执行的引擎实例，并过滤掉被禁用的引擎
+        // 获取所有要执行的引擎实例，并过滤掉被禁用的引擎；结果缓存中未过期的引擎结果直接使用
+        let mut cached_results = Vec::new();
         for engine_name in engine_names {
             // 检查引擎是否被临时禁用
             {
                 let states = self.engine_states.read().await;
                 if let Some(state) = states.get(engine_name) {
-                    if !state.is_available() {
+                    let available = state.is_available();
+                    set_engine_disabled_gauge(engine_name, !available);
+                    if !available {
+                        disabled += 1;
                         continue;
                     }
                 }
             }
+            if let Some(result) = self.cached_result(request, engine_name) {
+                cached_results.push((engine_name.clone(), result));
+                continue;
+            }
             match self.get_or_create_engine(engine_name).await {
                 Ok(engine) => {
                     engines_to_execute.push((engine_name.clone(), engine));
                 }
                 Err(_e) => {
                     self.stats.engine_failures.fetch_add(1, Ordering::Relaxed);
+                    skipped += 1;
                 }
             }
         }
+        let cached = !cached_results.is_empty() && engines_to_execute.is_empty();
 
-        // 创建并发任务
+        // 创建并发任务（同一请求的引擎共享请求内的并发名额）
+        let fetch_slots = self.fetch_limiter.for_request();
         for (engine_name, engine) in engines_to_execute {
-            let query = request.query.clone();
-            let timeout_duration = Duration::from_secs(self.config.default_timeout.as_secs());
-            let stats = Arc::clone(&self.stats);
-            
-            let future = async move {
-                let search_start = std::time::Instant::now();
-                match timeout(timeout_duration, engine.search(&query)).await {
-                    Ok(Ok(mut result)) => {
-                        result.elapsed_ms = search_start.elapsed().as_millis() as u64;
-                        Some((Ok(result), engine_name))
-                    }
-                    Ok(Err(e)) => {
-                        stats.engine_failures.fetch_add(1, Ordering::Relaxed);
-                        Some((Err(format!("Engine {} error: {}", engine_name, e)), engine_name))
-                    }
-                    Err(_) => {
-                        stats.timeouts.fetch_add(1, Ordering::Relaxed);
-                        Some((Err(format!("Engine {} timeout", engine_name)), engine_name))
-                    }
-                }
-            };
-            
-            futures_list.push(future);
+            futures_list.push(self.engine_task(request, engine_name, engine, fetch_slots.clone()));
         }
         
-        // 并发执行所有搜索
-        let results = futures::future::join_all(futures_list).await;
+        // 并发执行所有搜索；设置了提前返回条件时，满足条件即停止等待。
+        // 请求被取消时丢弃所有引擎 future，进行中的 HTTP 请求随之中止
+        let cancellation = request.cancellation.clone().unwrap_or_default();
+        let collect = async {
+            match request.early_response.filter(EarlyResponse::is_active) {
+                Some(early) => self.collect_early(request, futures_list, early).await,
+                None => futures::future::join_all(futures_list).await.into_iter().flatten().collect(),
+            }
+        };
+        let results = tokio::select! {
+            results = collect => results,
+            _ = cancellation.cancelled() => return Err(search_cancelled()),
+        };
 
         // 收集成功的结果，并检测零结果情况
         let mut successful_results = Vec::new();
         let mut engines_used = Vec::new();
-
-        for result in results.iter() {
-            if let Some((search_result, engine_name)) = result {
-                match search_result {
-                    Ok(result) => {
-                        // 检查是否为零结果
-                        let is_zero_results = result.items.is_empty();
-
-                        if is_zero_results {
-                            // 零结果，更新引擎状态并应用指数退避
-                            let mut states = self.engine_states.write().await;
-                            if let Some(state) = states.get_mut(engine_name) {
-                                state.record_zero_results();
-                            }
-                        } else {
-                            // 有结果，记录成功
-                            let mut states = self.engine_states.write().await;
-                            if let Some(state) = states.get_mut(engine_name) {
-                                state.record_success(result.elapsed_ms);
-                            }
-                        }
-
-                        
-                        successful_results.push(result.clone());
-                        engines_used.push(engine_name.clone());
-                    }
-                    Err(_) => {
-                        // 失败，记录失败
-                        let mut states = self.engine_states.write().await;
-                        let state = states.entry(engine_name.clone())
-                            .or_insert_with(|| super::engine_manager::EngineState::new(engine_name.clone()));
-                        state.record_failure();
-                    }
-                }
+        let mut engine_timings = Vec::new();
+        let recorder = self.outcome_recorder();
+
+        for (engine_name, result) in cached_results {
+            engine_timings.push(EngineTiming {
+                engine: engine_name.clone(),
+                status: EngineTimingStatus::Success,
+                elapsed_ms: 0,
+                items: result.items.len(),
+            });
+            successful_results.push(result);
+            engines_used.push(engine_name);
+        }
+        for (search_result, engine_name, timing) in results {
+            recorder.record(&engine_name, &search_result).await;
+            engine_timings.push(timing);
+            if let Ok(result) = search_result {
+                self.store_result(request, &engine_name, &result);
+                successful_results.push(result);
+                engines_used.push(engine_name);
             }
         }
         
@@ -702,10 +1211,168 @@ impl SearchInterface {
             total_count,
             engines_used,
             query_time_ms,
-            cached: false,
+            cached,
+            translated_query: None,
+            spell_hints: Vec::new(),
+            engine_counts: engine_counts(engine_names.len(), disabled, skipped, &engine_timings),
+            engine_timings,
+        })
+    }
+
+    /// 读取结果缓存中未超过刷新时间线的引擎结果（强制搜索或未启用缓存时不读取）
+    fn cached_result(&self, request: &SearchRequest, engine_name: &str) -> Option<SearchResult> {
+        let cache = self.cache.as_ref().filter(|_| !request.force)?;
+        let results = cache.results();
+        if let Some(timeline) = request.cache_timeline
+            && results.is_stale(&request.query, engine_name, timeline).ok()? != Some(false)
+        {
+            return None;
+        }
+        results.get(&request.query, engine_name).unwrap_or_else(|e| {
+            tracing::warn!("Failed to read cached result of {}: {}", engine_name, e);
+            None
         })
     }
 
+    /// 把引擎的非空结果写入结果缓存，生存时间取请求的刷新时间线
+    fn store_result(&self, request: &SearchRequest, engine_name: &str, result: &SearchResult) {
+        if let Some(cache) = &self.cache
+            && !result.items.is_empty()
+            && let Err(e) = cache.results().set(&request.query, engine_name, result, request.cache_timeline.map(Duration::from_secs))
+        {
+            tracing::warn!("Failed to cache result of {}: {}", engine_name, e);
+        }
+    }
+
+    /// 创建单个引擎的搜索任务
+    ///
+    /// 先等待并发名额，拿到名额后才开始计时和发送请求
+    fn engine_task(
+        &self,
+        request: &SearchRequest,
+        engine_name: String,
+        engine: Arc<dyn crate::derive::SearchEngine + Send + Sync>,
+        fetch_slots: RequestFetchLimiter,
+    ) -> impl Future<Output = Option<EngineOutcome>> + Send + 'static {
+        use std::sync::atomic::Ordering;
+
+        let query = request.query.clone();
+        let timeout_duration = self.engine_timeout(&engine_name);
+        let stats = Arc::clone(&self.stats);
+        let latencies = Arc::clone(&self.latencies);
+        let span = tracing::info_span!("engine", engine = %engine_name);
+
+        async move {
+            let _permit = fetch_slots.acquire(&engine_name).await;
+            let search_start = std::time::Instant::now();
+            let outcome = timeout(timeout_duration, engine.search(&query)).await;
+            let elapsed_ms = search_start.elapsed().as_millis() as u64;
+            let timing = |status, items| EngineTiming { engine: engine_name.clone(), status, elapsed_ms, items };
+            match outcome {
+                Ok(Ok(mut result)) => {
+                    result.elapsed_ms = elapsed_ms;
+                    record_engine_request(&engine_name, "success", elapsed_ms);
+                    latencies.record(&engine_name, elapsed_ms);
+                    let timing = timing(EngineTimingStatus::Success, result.items.len());
+                    Some((Ok(result), engine_name, timing))
+                }
+                Ok(Err(e)) => {
+                    stats.engine_failures.fetch_add(1, Ordering::Relaxed);
+                    record_engine_request(&engine_name, "error", elapsed_ms);
+                    let timing = timing(EngineTimingStatus::Error, 0);
+                    Some((Err(format!("Engine {} error: {}", engine_name, e)), engine_name, timing))
+                }
+                Err(_) => {
+                    stats.timeouts.fetch_add(1, Ordering::Relaxed);
+                    record_engine_request(&engine_name, "timeout", elapsed_ms);
+                    latencies.record(&engine_name, elapsed_ms);
+                    let timing = timing(EngineTimingStatus::Timeout, 0);
+                    Some((Err(format!("Engine {} timeout", engine_name)), engine_name, timing))
+                }
+            }
+        }
+        .instrument(span)
+    }
+
+    /// 等待引擎直到满足提前返回条件，其余引擎移交后台任务
+    ///
+    /// 后台任务照常记录引擎状态，并把非空结果写入结果缓存
+    async fn collect_early<F>(
+        &self,
+        request: &SearchRequest,
+        futures_list: Vec<F>,
+        early: EarlyResponse,
+    ) -> Vec<EngineOutcome>
+    where
+        F: Future<Output = Option<EngineOutcome>> + Send + 'static,
+    {
+        let mut pending: FuturesUnordered<F> = futures_list.into_iter().collect();
+        let deadline = early.max_wait.map(|wait| tokio::time::Instant::now() + wait);
+        let mut results = Vec::new();
+        let mut answered = 0;
+
+        while early.min_engines.is_none_or(|min| answered < min) {
+            let next = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, pending.next()).await {
+                    Ok(next) => next,
+                    Err(_) => break,
+                },
+                None => pending.next().await,
+            };
+            let Some(next) = next else {
+                break;
+            };
+            if let Some(outcome) = next {
+                if outcome.0.as_ref().is_ok_and(|result| !result.items.is_empty()) {
+                    answered += 1;
+                }
+                results.push(outcome);
+            }
+        }
+
+        if !pending.is_empty() {
+            tracing::debug!("Responding early, {} engines continue in background", pending.len());
+            let recorder = self.outcome_recorder();
+            let cache = self.cache.clone();
+            let query = request.query.clone();
+            let ttl = request.cache_timeline.map(Duration::from_secs);
+            let cancellation = request.cancellation.clone().unwrap_or_default();
+            tokio::spawn(async move {
+                loop {
+                    let next = tokio::select! {
+                        next = pending.next() => next,
+                        // 客户端在响应前断开时，后台引擎一并中止
+                        _ = cancellation.cancelled() => break,
+                    };
+                    let Some(next) = next else {
+                        break;
+                    };
+                    let Some((outcome, engine_name, _)) = next else {
+                        continue;
+                    };
+                    recorder.record(&engine_name, &outcome).await;
+                    if let (Ok(result), Some(cache)) = (&outcome, &cache)
+                        && !result.items.is_empty()
+                        && let Err(e) = cache.results().set(&query, &engine_name, result, ttl)
+                    {
+                        tracing::warn!("Failed to cache background result of {}: {}", engine_name, e);
+                    }
+                }
+            }.in_current_span());
+        }
+
+        results
+    }
+
+    /// 引擎执行结果的记录器，可移入后台任务
+    fn outcome_recorder(&self) -> OutcomeRecorder {
+        OutcomeRecorder {
+            engine_states: Arc::clone(&self.engine_states),
+            recent_errors: Arc::clone(&self.recent_errors),
+            anomalies: Arc::clone(&self.anomalies),
+        }
+    }
+
     /// 获取统计信息
     pub async fn get_stats(&self) -> SearchStatsResult {
         use std::sync::atomic::Ordering;
@@ -743,6 +1410,35 @@ impl SearchInterface {
         EngineListConfig::default().all_available_engines.clone()
     }
 
+    /// 列出属于指定分类（如 `news`）的可用引擎
+    pub async fn engines_in_category(&self, category: &str) -> Vec<String> {
+        let mut engines = Vec::new();
+        for name in self.list_engines() {
+            match self.get_or_create_engine(&name).await {
+                Ok(engine) if engine.info().categories.iter().any(|c| c == category) => engines.push(name),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("Skipping engine {}: {}", name, e),
+            }
+        }
+        engines
+    }
+
+    /// 按分类统计可用引擎数（分类按名称排序）
+    pub async fn engine_categories(&self) -> BTreeMap<String, usize> {
+        let mut categories = BTreeMap::new();
+        for name in self.list_engines() {
+            match self.get_or_create_engine(&name).await {
+                Ok(engine) => {
+                    for category in &engine.info().categories {
+                        *categories.entry(category.clone()).or_insert(0) += 1;
+                    }
+                }
+                Err(e) => tracing::debug!("Skipping engine {}: {}", name, e),
+            }
+        }
+        categories
+    }
+
     /// 列出全局模式引擎
     pub fn list_global_engines(&self) -> Vec<String> {
         EngineListConfig::default().global_engines.clone()
@@ -756,6 +1452,135 @@ impl SearchInterface {
         Ok(engines.into_iter().map(|e| (e, true)).collect())
     }
 
+    /// 用探测查询直接调用引擎并报告结果数、耗时和解析警告
+    ///
+    /// `engines` 为空时测试所有未被手动禁用的引擎。各引擎并发执行，同一引擎的查询依次执行；
+    /// 绕过结果缓存，也不更新引擎状态
+    pub async fn self_test(&self, queries: &[String], engines: &[String]) -> SelfTestReport {
+        let start_time = std::time::Instant::now();
+        let engines = if engines.is_empty() {
+            let states = self.engine_states.read().await;
+            self.list_engines()
+                .into_iter()
+                .filter(|name| states.get(name).is_none_or(|state| state.enabled))
+                .collect()
+        } else {
+            engines.to_vec()
+        };
+
+        let timeout_duration = self.config.default_timeout;
+        let tests = engines.into_iter().map(|name| async move {
+            let engine = match self.get_or_create_engine(&name).await {
+                Ok(engine) => engine,
+                Err(e) => {
+                    let results = queries.iter().map(|query| SelfTestQuery {
+                        query: query.clone(),
+                        items: 0,
+                        latency_ms: 0,
+                        error: Some(e.to_string()),
+                        warnings: Vec::new(),
+                    }).collect();
+                    return EngineSelfTest::new(name, results);
+                }
+            };
+            let mut results = Vec::with_capacity(queries.len());
+            for query in queries {
+                let search_query = match crate::derive::SearchQuery::builder().query(query.as_str()).build() {
+                    Ok(search_query) => search_query,
+                    Err(e) => {
+                        results.push(SelfTestQuery {
+                            query: query.clone(),
+                            items: 0,
+                            latency_ms: 0,
+                            error: Some(e.to_string()),
+                            warnings: Vec::new(),
+                        });
+                        continue;
+                    }
+                };
+                let search_start = std::time::Instant::now();
+                let outcome = timeout(timeout_duration, engine.search(&search_query)).await;
+                let latency_ms = search_start.elapsed().as_millis() as u64;
+                results.push(match outcome {
+                    Ok(Ok(result)) => SelfTestQuery {
+                        query: query.clone(),
+                        items: result.items.len(),
+                        latency_ms,
+                        error: None,
+                        warnings: parse_warnings(&result.items),
+                    },
+                    Ok(Err(e)) => SelfTestQuery {
+                        query: query.clone(),
+                        items: 0,
+                        latency_ms,
+                        error: Some(e.to_string()),
+                        warnings: Vec::new(),
+                    },
+                    Err(_) => SelfTestQuery {
+                        query: query.clone(),
+                        items: 0,
+                        latency_ms,
+                        error: Some(format!("timeout after {}s", timeout_duration.as_secs())),
+                        warnings: Vec::new(),
+                    },
+                });
+            }
+            EngineSelfTest::new(name, results)
+        });
+        let results = futures::future::join_all(tests).await;
+
+        let report = SelfTestReport::new(queries.to_vec(), results, start_time.elapsed().as_millis() as u64);
+        tracing::info!(passed = report.passed, failed = report.failed, "Engine self-test finished");
+        report
+    }
+
+    /// 引擎响应异常检测器（可注册告警回调，查看最近的异常）
+    pub fn anomaly_detector(&self) -> &Arc<AnomalyDetector> {
+        &self.anomalies
+    }
+
+    /// 引擎的延迟百分位（还没有样本时为 None）
+    pub fn engine_latency(&self, engine: &str) -> Option<LatencyPercentiles> {
+        self.latencies.percentiles(engine)
+    }
+
+    /// 引擎当前使用的超时（启用自适应超时时按其 p95 计算）
+    pub fn engine_timeout(&self, engine: &str) -> Duration {
+        let default = Duration::from_secs(self.config.default_timeout.as_secs());
+        if !self.config.latency.adaptive_timeout {
+            return default;
+        }
+        let latency = self.latencies.percentiles(engine);
+        let timeout = self.config.latency.timeout_for(latency.as_ref(), default);
+        metrics::gauge!("seesea_engine_timeout_ms", "engine" => engine.to_string())
+            .set(timeout.as_millis() as f64);
+        timeout
+    }
+
+    /// 答案生成器（未启用时为 None）
+    pub fn synthesizer(&self) -> Option<&Arc<AnswerSynthesizer>> {
+        self.synthesizer.as_ref()
+    }
+
+    /// 慢查询日志
+    pub fn slow_queries(&self) -> &Arc<SlowQueryLog> {
+        &self.slow_queries
+    }
+
+    /// 获取最近的引擎错误（最新的在前）
+    pub fn recent_errors(&self) -> Vec<super::engine_manager::EngineErrorRecord> {
+        let errors = self.recent_errors.lock().unwrap_or_else(|e| e.into_inner());
+        errors.iter().rev().cloned().collect()
+    }
+
+    /// 获取完整的引擎状态快照
+    pub async fn engine_state_snapshots(&self) -> Vec<super::engine_manager::EngineState> {
+        let states = self.engine_states.read().await;
+        let mut snapshots: Vec<_> = states.values().cloned().collect();
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+    }
+
     /// 获取引擎状态
     pub async fn get_engine_states(&self) -> Vec<(String, (bool, bool, u32))> {
         let states = self.engine_states.read().await;
@@ -778,6 +1603,29 @@ impl SearchInterface {
         Ok(())
     }
 
+    /// 重新扫描引擎脚本目录并重新导入 SearXNG 引擎（需启用 `python` feature 并配置
+    /// `engines_dir` 或 `searxng`）
+    ///
+    /// 新增、修改和删除的脚本对之后的搜索生效；新的快捷方式需重启后才能作为 bang 使用
+    pub async fn reload_engines(&self) -> Result<super::engine_manager::EngineReloadReport, Box<dyn std::error::Error + Send + Sync>> {
+        if self.config.engines_dir.is_none() && self.config.searxng.is_none() {
+            return Err("engines_dir and searxng are not configured".into());
+        }
+        #[cfg(feature = "python")]
+        {
+            let report = load_python_engines(&self.config)?;
+            let mut cache = self.engine_cache.write().await;
+            for name in report.loaded.iter().chain(&report.removed) {
+                cache.remove(name);
+            }
+            Ok(report)
+        }
+        #[cfg(not(feature = "python"))]
+        {
+            Err("Cannot load Python engines: the `python` feature is disabled".into())
+        }
+    }
+
     /// 获取隐私保护统计信息
     pub async fn get_privacy_stats(&self) -> Option<crate::net::privacy::PrivacyStats> {
         // 从 HTTP 客户端获取隐私管理器
@@ -831,6 +1679,36 @@ pub struct SearchStatsResult {
     pub timeouts: u64,
 }
 
+/// 记录引擎请求指标（请求计数与耗时直方图）
+fn record_engine_request(engine_name: &str, outcome: &'static str, elapsed_ms: u64) {
+    metrics::counter!(
+        "seesea_engine_requests_total",
+        "engine" => engine_name.to_string(),
+        "outcome" => outcome
+    ).increment(1);
+    metrics::histogram!("seesea_engine_latency_ms", "engine" => engine_name.to_string())
+        .record(elapsed_ms as f64);
+}
+
+/// 根据选中、禁用、跳过的引擎数和已返回引擎的耗时汇总执行概况，其余引擎仍在后台执行
+fn engine_counts(selected: usize, disabled: usize, skipped: usize, timings: &[EngineTiming]) -> EngineCounts {
+    let succeeded = timings.iter().filter(|t| t.status == EngineTimingStatus::Success).count();
+    EngineCounts {
+        selected,
+        succeeded,
+        failed: timings.len() - succeeded,
+        disabled,
+        skipped,
+        pending: selected.saturating_sub(disabled + skipped + timings.len()),
+    }
+}
+
+/// 更新引擎禁用状态指标（1 表示被禁用）
+fn set_engine_disabled_gauge(engine_name: &str, disabled: bool) {
+    metrics::gauge!("seesea_engine_disabled", "engine" => engine_name.to_string())
+        .set(if disabled { 1.0 } else { 0.0 });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -842,6 +1720,251 @@ mod tests {
         assert!(interface.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_reload_engines_requires_dir() {
+        let interface = SearchInterface::new(SearchConfig::default()).unwrap();
+        let err = interface.reload_engines().await.unwrap_err();
+        assert!(err.to_string().contains("not configured"));
+    }
+
+    #[tokio::test]
+    async fn test_declared_json_engine() {
+        use crate::net::client::{MockResponse, MockTransport};
+        use crate::search::engines::JsonApiConfig;
+
+        let body = r#"[{"title": "Internal Rust guide", "url": "https://wiki.internal/rust", "content": "rust"}]"#;
+        let transport = Arc::new(MockTransport::new().respond("wiki.internal/api", MockResponse::ok(body)));
+        let config = SearchConfig {
+            json_engines: vec![JsonApiConfig {
+                name: "wiki".to_string(),
+                shortcut: Some("w".to_string()),
+                url: "https://wiki.internal/api?q={query}".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let interface = SearchInterface::new(config).unwrap().with_transport(transport.clone());
+
+        let request = SearchRequest {
+            query: crate::derive::SearchQuery { query: "!w rust".to_string(), ..Default::default() },
+            ..Default::default()
+        };
+        let response = interface.search(&request).await.unwrap();
+        assert_eq!(response.engines_used, vec!["wiki"]);
+        assert_eq!(response.results[0].items[0].url, "https://wiki.internal/rust");
+        assert_eq!(transport.requests()[0].url, "https://wiki.internal/api?q=rust");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_uses_search_pipeline_and_filters() {
+        use crate::net::client::{MockResponse, MockTransport};
+        use crate::search::engines::JsonApiConfig;
+
+        let body = r#"[{"title": "Internal Rust guide", "url": "https://wiki.internal/rust", "content": "rust"},
+            {"title": "Rust mirror", "url": "https://mirror.example.com/rust", "content": "rust"}]"#;
+        let transport = Arc::new(MockTransport::new().respond("wiki.internal/api", MockResponse::ok(body)));
+        let engine = |name: &str, shortcut: &str, url: &str| JsonApiConfig {
+            name: name.to_string(),
+            shortcut: Some(shortcut.to_string()),
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let config = SearchConfig {
+            json_engines: vec![
+                engine("wiki", "w", "https://wiki.internal/api?q={query}"),
+                engine("broken", "b", "https://broken.internal/api?q={query}"),
+            ],
+            ..Default::default()
+        };
+        let interface = SearchInterface::new(config).unwrap().with_transport(transport.clone());
+
+        // bang 选中声明式引擎，结构化过滤作用于最终结果
+        let request = SearchRequest {
+            query: crate::derive::SearchQuery { query: "!w rust".to_string(), ..Default::default() },
+            filter: crate::search::ResultFilter { exclude_sites: vec!["mirror.example.com".to_string()], ..Default::default() },
+            ..Default::default()
+        };
+        let mut streamed = Vec::new();
+        let response = interface.search_streaming(&request, |_, engine| streamed.push(engine)).await.unwrap();
+        assert_eq!(streamed, vec!["wiki"]);
+        assert_eq!(transport.requests()[0].url, "https://wiki.internal/api?q=rust");
+        assert_eq!(response.results[0].items.len(), 1);
+        assert_eq!(response.results[0].items[0].url, "https://wiki.internal/rust");
+
+        // 失败的引擎只计一次
+        let request = SearchRequest {
+            query: crate::derive::SearchQuery { query: "rust".to_string(), ..Default::default() },
+            engines: vec!["broken".to_string()],
+            ..Default::default()
+        };
+        interface.search_streaming(&request, |_, _| {}).await.unwrap();
+        assert_eq!(interface.get_stats().await.engine_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_mock_transport() {
+        use crate::net::client::{MockResponse, MockTransport};
+
+        let html = r#"<ol id="b_results"><li class="b_algo"><h2><a href="https://www.rust-lang.org/">Rust</a></h2><p>Rust language</p></li></ol>"#;
+        let transport = Arc::new(MockTransport::new().respond("www.bing.com/search", MockResponse::ok(html)));
+        let interface = SearchInterface::new(SearchConfig::default())
+            .unwrap()
+            .with_transport(transport.clone());
+
+        let request = SearchRequest {
+            query: crate::derive::SearchQuery {
+                query: "rust".to_string(),
+                ..Default::default()
+            },
+            engines: vec!["bing".to_string()],
+            ..Default::default()
+        };
+        let response = interface.search(&request).await.unwrap();
+        assert_eq!(response.engines_used, vec!["bing"]);
+        assert_eq!(response.total_count, 1);
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(response.engine_timings[0].status, EngineTimingStatus::Success);
+        assert_eq!(response.engine_counts.status(), crate::search::SearchStatus::Complete);
+        assert!(!response.cached);
+
+        // 再次搜索直接使用结果缓存，不再请求引擎；强制搜索绕过缓存
+        let response = interface.search(&request).await.unwrap();
+        assert!(response.cached);
+        assert_eq!(response.total_count, 1);
+        assert_eq!(response.engine_counts.succeeded, 1);
+        assert_eq!(transport.requests().len(), 1);
+        let forced = SearchRequest { force: true, ..request.clone() };
+        assert!(!interface.search(&forced).await.unwrap().cached);
+        assert_eq!(transport.requests().len(), 2);
+
+        // 时间范围不同的搜索不能使用其他时间范围的缓存结果
+        let mut past_week = request.clone();
+        past_week.query.time_range = Some(crate::derive::TimeRange::Week);
+        assert!(!interface.search(&past_week).await.unwrap().cached);
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_self_test_reports_per_engine() {
+        use crate::net::client::{MockResponse, MockTransport};
+
+        let html = r#"<ol id="b_results"><li class="b_algo"><h2><a href="https://www.rust-lang.org/">Rust</a></h2><p>Rust language</p></li></ol>"#;
+        let transport = Arc::new(MockTransport::new().respond("www.bing.com/search", MockResponse::ok(html)));
+        let interface = SearchInterface::new(SearchConfig::default())
+            .unwrap()
+            .with_transport(transport);
+
+        let queries = vec!["rust".to_string()];
+        let report = interface.self_test(&queries, &["bing".to_string(), "no_such_engine".to_string()]).await;
+        assert_eq!((report.passed, report.failed), (1, 1));
+        let bing = &report.engines[0];
+        assert_eq!(bing.engine, "bing");
+        assert_eq!(bing.queries[0].items, 1);
+        assert!(bing.queries[0].warnings.is_empty());
+        assert!(report.engines[1].queries[0].error.is_some());
+
+        // 自检不计入引擎状态
+        assert!(interface.engine_state_snapshots().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_early_response_continues_in_background() {
+        use crate::net::client::{MockResponse, MockTransport};
+
+        let html = r#"<ol id="b_results"><li class="b_algo"><h2><a href="https://www.rust-lang.org/">Rust</a></h2><p>Rust language</p></li></ol>"#;
+        let query = crate::derive::SearchQuery {
+            query: format!("rust {}", uuid::Uuid::new_v4().simple()),
+            ..Default::default()
+        };
+
+        // 第一个引擎返回后立即响应，不等待慢引擎
+        let transport = Arc::new(MockTransport::new()
+            .respond("www.bing.com/search", MockResponse::ok(html))
+            .respond("yandex.com", MockResponse::ok("").delayed(Duration::from_secs(5))));
+        let interface = SearchInterface::new(SearchConfig::default()).unwrap().with_transport(transport);
+        let request = SearchRequest {
+            query: query.clone(),
+            engines: vec!["bing".to_string(), "yandex".to_string()],
+            early_response: Some(EarlyResponse { min_engines: Some(1), max_wait: None }),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let response = interface.search(&request).await.unwrap();
+        assert_eq!(response.engines_used, vec!["bing"]);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!((response.engine_counts.succeeded, response.engine_counts.pending), (1, 1));
+        assert_eq!(response.engine_counts.status(), crate::search::SearchStatus::Complete);
+
+        // 软截止时间到达时返回，慢引擎的结果在后台写入结果缓存
+        let transport = Arc::new(MockTransport::new()
+            .respond("www.bing.com/search", MockResponse::ok(html).delayed(Duration::from_millis(200))));
+        let interface = SearchInterface::new(SearchConfig::default()).unwrap().with_transport(transport);
+        let request = SearchRequest {
+            query: query.clone(),
+            engines: vec!["bing".to_string()],
+            early_response: Some(EarlyResponse { min_engines: None, max_wait: Some(Duration::from_millis(20)) }),
+            ..Default::default()
+        };
+        let response = interface.search(&request).await.unwrap();
+        assert!(response.engines_used.is_empty());
+
+        let results = interface.cache().unwrap().results();
+        let mut cached = None;
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            cached = results.get(&query, "bing").unwrap();
+            if cached.is_some() {
+                break;
+            }
+        }
+        assert_eq!(cached.unwrap().items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_aborts_engines() {
+        use crate::net::client::{MockResponse, MockTransport};
+        use tokio_util::sync::CancellationToken;
+
+        let transport = Arc::new(MockTransport::new()
+            .respond("www.bing.com/search", MockResponse::ok("").delayed(Duration::from_secs(5))));
+        let interface = SearchInterface::new(SearchConfig::default()).unwrap().with_transport(transport);
+        let cancellation = CancellationToken::new();
+        let request = SearchRequest {
+            query: crate::derive::SearchQuery {
+                query: "rust".to_string(),
+                ..Default::default()
+            },
+            engines: vec!["bing".to_string()],
+            cancellation: Some(cancellation.clone()),
+            ..Default::default()
+        };
+
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            cancellation.cancel();
+        });
+        let started = std::time::Instant::now();
+        let error = interface.search(&request).await.unwrap_err();
+        canceller.await.unwrap();
+        assert_eq!(error.to_string(), "Search cancelled");
+        assert!(started.elapsed() < Duration::from_secs(2));
+        // 被取消的引擎不计为失败
+        assert!(interface.recent_errors().is_empty());
+    }
+
+    #[test]
+    fn test_recent_errors_ring_buffer() {
+        let interface = SearchInterface::new(SearchConfig::default()).unwrap();
+        for i in 0..MAX_RECENT_ERRORS + 5 {
+            push_recent_error(&interface.recent_errors, "bing", format!("error {}", i));
+        }
+
+        let errors = interface.recent_errors();
+        assert_eq!(errors.len(), MAX_RECENT_ERRORS);
+        assert_eq!(errors[0].message, format!("error {}", MAX_RECENT_ERRORS + 4));
+        assert_eq!(errors.last().unwrap().message, "error 5");
+    }
+
     #[test]
     fn test_stats_structure() {
         use std::sync::atomic::AtomicU64;
@@ -0,0 +1,487 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 查询预处理流水线
+//!
+//! 搜索前依次执行一组 [`QueryStage`]：规范化、bang 解析、语言检测、拼写提示、
+//! 安全搜索级别约束和翻译。阶段的启用与顺序由 [`PipelineConfig::stages`] 决定，
+//! 也可以通过 [`QueryPipeline::push`] 接入自定义阶段。
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::common::SafeSearchLevel;
+use super::engine_config::EngineListConfig;
+use super::language::detect_language;
+use super::translate::{TranslationConfig, Translator};
+use super::types::SearchRequest;
+
+/// 规范化阶段
+pub const STAGE_NORMALIZE: &str = "normalize";
+/// bang 解析阶段
+pub const STAGE_BANG: &str = "bang";
+/// 语言检测阶段
+pub const STAGE_LANGUAGE: &str = "language";
+/// 拼写提示阶段
+pub const STAGE_SPELL_HINTS: &str = "spell_hints";
+/// 安全搜索级别约束阶段
+pub const STAGE_SAFE_SEARCH: &str = "safe_search";
+/// 翻译阶段
+pub const STAGE_TRANSLATION: &str = "translation";
+
+/// 内置 bang 别名（引擎名本身也可以直接作为 bang，如 `!bing`）
+const BUILTIN_BANGS: &[(&str, &str)] = &[
+    ("b", "bing"),
+    ("bd", "baidu"),
+    ("yx", "yandex"),
+    ("sg", "sogou"),
+    ("bili", "bilibili"),
+    ("img", "bing_images"),
+    ("v", "sogou_videos"),
+    ("wx", "sogou_wechat"),
+    ("map", "nominatim"),
+];
+
+/// 内置的常见拼写错误
+const BUILTIN_SPELLING: &[(&str, &str)] = &[
+    ("teh", "the"),
+    ("recieve", "receive"),
+    ("seperate", "separate"),
+    ("definately", "definitely"),
+    ("occured", "occurred"),
+    ("pyhton", "python"),
+    ("javascirpt", "javascript"),
+    ("javscript", "javascript"),
+    ("tutoral", "tutorial"),
+    ("databse", "database"),
+    ("fucntion", "function"),
+    ("programing", "programming"),
+];
+
+/// 查询预处理配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// 按顺序执行的阶段（未列出的阶段不执行）
+    pub stages: Vec<String>,
+    /// 额外的 bang 别名（不含 `!`，如 `"n": ["bing", "sogou_wechat"]`）
+    pub bangs: HashMap<String, Vec<String>>,
+    /// 安全搜索级别下限（请求的级别更低时提升到该级别）
+    pub min_safe_search: Option<SafeSearchLevel>,
+    /// 额外的拼写纠正（小写错词 -> 正确写法）
+    pub spelling: HashMap<String, String>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            // 语言检测会让聚合器按语言过滤结果，默认不启用
+            stages: [STAGE_NORMALIZE, STAGE_BANG, STAGE_SPELL_HINTS, STAGE_SAFE_SEARCH, STAGE_TRANSLATION]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            bangs: HashMap::new(),
+            min_safe_search: None,
+            spelling: HashMap::new(),
+        }
+    }
+}
+
+/// 翻译阶段准备好的译文查询
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedTranslation {
+    /// 译文查询
+    pub text: String,
+    /// 译文语言
+    pub language: String,
+    /// 接收译文查询的引擎
+    pub engines: Vec<String>,
+}
+
+/// 预处理上下文
+#[derive(Debug, Clone)]
+pub struct QueryContext {
+    /// 正在处理的搜索请求（阶段可以改写查询、引擎等）
+    pub request: SearchRequest,
+    /// 命中的 bang（不含 `!`）
+    pub bang: Option<String>,
+    /// 检测到的查询语言
+    pub detected_language: Option<String>,
+    /// 拼写提示（纠正后的完整查询）
+    pub spell_hints: Vec<String>,
+    /// 翻译后搜索使用的译文查询
+    pub translation: Option<PreparedTranslation>,
+}
+
+impl QueryContext {
+    /// 从搜索请求创建上下文
+    pub fn new(request: SearchRequest) -> Self {
+        Self {
+            request,
+            bang: None,
+            detected_language: None,
+            spell_hints: Vec::new(),
+            translation: None,
+        }
+    }
+}
+
+/// 查询预处理阶段
+#[async_trait]
+pub trait QueryStage: Send + Sync {
+    /// 阶段名（用于配置和日志）
+    fn name(&self) -> &str;
+
+    /// 处理查询，返回错误时中止搜索
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// 规范化：去掉控制字符，全角空格转半角，合并连续空白
+pub struct NormalizeStage;
+
+#[async_trait]
+impl QueryStage for NormalizeStage {
+    fn name(&self) -> &str {
+        STAGE_NORMALIZE
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let query = &mut context.request.query.query;
+        let normalized = query
+            .chars()
+            .map(|c| if c == '\u{3000}' { ' ' } else { c })
+            .filter(|c| !c.is_control() || c.is_whitespace())
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if normalized.is_empty() {
+            return Err("查询为空".into());
+        }
+        *query = normalized;
+        Ok(())
+    }
+}
+
+/// bang 解析：`!bd rust` 或 `rust !bd` 只查询对应的引擎，并从查询中去掉 bang
+pub struct BangStage {
+    bangs: HashMap<String, Vec<String>>,
+}
+
+impl BangStage {
+    /// 创建 bang 解析阶段（内置别名、引擎名和配置的别名，配置优先）
+    pub fn new(extra: &HashMap<String, Vec<String>>) -> Self {
+        let mut bangs: HashMap<String, Vec<String>> = EngineListConfig::default()
+            .all_available_engines
+            .into_iter()
+            .map(|engine| (engine.clone(), vec![engine]))
+            .collect();
+        for (bang, engine) in BUILTIN_BANGS {
+            bangs.insert(bang.to_string(), vec![engine.to_string()]);
+        }
+        for (bang, engines) in extra {
+            bangs.insert(bang.trim_start_matches('!').to_lowercase(), engines.clone());
+        }
+        Self { bangs }
+    }
+}
+
+#[async_trait]
+impl QueryStage for BangStage {
+    fn name(&self) -> &str {
+        STAGE_BANG
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let words: Vec<&str> = context.request.query.query.split_whitespace().collect();
+        let found = words.iter().enumerate().find_map(|(index, word)| {
+            let bang = word.strip_prefix('!')?.to_lowercase();
+            self.bangs.get(&bang).map(|engines| (index, bang, engines.clone()))
+        });
+        let Some((index, bang, engines)) = found else { return Ok(()) };
+
+        let rest: Vec<&str> = words.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, w)| *w).collect();
+        // 只有 bang 时保留原查询，交给引擎处理
+        if rest.is_empty() {
+            return Ok(());
+        }
+        context.request.query.query = rest.join(" ");
+        context.request.engines = engines;
+        context.bang = Some(bang);
+        Ok(())
+    }
+}
+
+/// 语言检测：请求未指定语言时填入检测到的查询语言
+///
+/// 填入的语言会传给引擎，聚合器也会据此降权其他语言的结果
+pub struct LanguageStage;
+
+#[async_trait]
+impl QueryStage for LanguageStage {
+    fn name(&self) -> &str {
+        STAGE_LANGUAGE
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let detected = detect_language(&context.request.query.query).and_then(|d| d.language);
+        if let Some(language) = detected {
+            context.detected_language = Some(language.to_string());
+            context.request.query.language.get_or_insert_with(|| language.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 拼写提示：按词典纠正常见拼写错误，生成“你是不是要找”提示（不改写查询）
+pub struct SpellHintStage {
+    corrections: HashMap<String, String>,
+}
+
+impl SpellHintStage {
+    /// 创建拼写提示阶段（内置词典加配置的纠正，配置优先）
+    pub fn new(extra: &HashMap<String, String>) -> Self {
+        let mut corrections: HashMap<String, String> = BUILTIN_SPELLING
+            .iter()
+            .map(|(wrong, right)| (wrong.to_string(), right.to_string()))
+            .collect();
+        corrections.extend(extra.iter().map(|(wrong, right)| (wrong.to_lowercase(), right.clone())));
+        Self { corrections }
+    }
+}
+
+#[async_trait]
+impl QueryStage for SpellHintStage {
+    fn name(&self) -> &str {
+        STAGE_SPELL_HINTS
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut changed = false;
+        let corrected: Vec<String> = context.request.query.query
+            .split_whitespace()
+            .map(|word| match self.corrections.get(&word.to_lowercase()) {
+                Some(right) => {
+                    changed = true;
+                    right.clone()
+                }
+                None => word.to_string(),
+            })
+            .collect();
+        if changed {
+            context.spell_hints.push(corrected.join(" "));
+        }
+        Ok(())
+    }
+}
+
+/// 安全搜索级别约束：请求的级别低于下限时提升到下限
+pub struct SafeSearchStage {
+    minimum: Option<SafeSearchLevel>,
+}
+
+impl SafeSearchStage {
+    /// 创建安全搜索约束阶段（下限为 None 时不做任何事）
+    pub fn new(minimum: Option<SafeSearchLevel>) -> Self {
+        Self { minimum }
+    }
+}
+
+#[async_trait]
+impl QueryStage for SafeSearchStage {
+    fn name(&self) -> &str {
+        STAGE_SAFE_SEARCH
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(minimum) = &self.minimum
+            && (context.request.query.safe_search.clone() as u8) < (minimum.clone() as u8)
+        {
+            context.request.query.safe_search = minimum.clone();
+        }
+        Ok(())
+    }
+}
+
+/// 翻译：为翻译后搜索准备译文查询（未配置翻译服务时不做任何事）
+pub struct TranslationStage {
+    translator: Option<Arc<dyn Translator>>,
+    config: TranslationConfig,
+}
+
+impl TranslationStage {
+    /// 创建翻译阶段
+    pub fn new(translator: Option<Arc<dyn Translator>>, config: TranslationConfig) -> Self {
+        Self { translator, config }
+    }
+}
+
+#[async_trait]
+impl QueryStage for TranslationStage {
+    fn name(&self) -> &str {
+        STAGE_TRANSLATION
+    }
+
+    async fn process(&self, context: &mut QueryContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let Some(translator) = &self.translator else { return Ok(()) };
+        let original = context.request.query.query.trim();
+        let engines = EngineListConfig::resolve_engines(&context.request.engines);
+        let Some(plan) = self.config.plan(original, &engines) else { return Ok(()) };
+
+        // 翻译失败不影响原搜索
+        let translated = match translator.translate(original, plan.source.code(), plan.target.code()).await {
+            Ok(text) => text.trim().to_string(),
+            Err(e) => {
+                tracing::warn!("Query translation failed: {}", e);
+                return Ok(());
+            }
+        };
+        if translated.is_empty() || translated == original {
+            return Ok(());
+        }
+        context.translation = Some(PreparedTranslation {
+            text: translated,
+            language: plan.target.code().to_string(),
+            engines: plan.engines,
+        });
+        Ok(())
+    }
+}
+
+/// 查询预处理流水线
+#[derive(Clone, Default)]
+pub struct QueryPipeline {
+    stages: Vec<Arc<dyn QueryStage>>,
+}
+
+impl QueryPipeline {
+    /// 按配置创建内置阶段（阶段名未知时返回错误）
+    pub fn from_config(
+        config: &PipelineConfig,
+        translator: Option<Arc<dyn Translator>>,
+        translation: &TranslationConfig,
+    ) -> Result<Self, String> {
+        let mut pipeline = Self::default();
+        for name in &config.stages {
+            let stage: Arc<dyn QueryStage> = match name.as_str() {
+                STAGE_NORMALIZE => Arc::new(NormalizeStage),
+                STAGE_BANG => Arc::new(BangStage::new(&config.bangs)),
+                STAGE_LANGUAGE => Arc::new(LanguageStage),
+                STAGE_SPELL_HINTS => Arc::new(SpellHintStage::new(&config.spelling)),
+                STAGE_SAFE_SEARCH => Arc::new(SafeSearchStage::new(config.min_safe_search.clone())),
+                STAGE_TRANSLATION => Arc::new(TranslationStage::new(translator.clone(), translation.clone())),
+                other => return Err(format!("未知的查询预处理阶段: {}", other)),
+            };
+            pipeline.stages.push(stage);
+        }
+        Ok(pipeline)
+    }
+
+    /// 在末尾追加一个阶段
+    pub fn push(&mut self, stage: Arc<dyn QueryStage>) {
+        self.stages.push(stage);
+    }
+
+    /// 替换同名阶段（不存在时不做任何事），返回是否替换
+    pub fn replace(&mut self, stage: Arc<dyn QueryStage>) -> bool {
+        match self.stages.iter_mut().find(|s| s.name() == stage.name()) {
+            Some(slot) => {
+                *slot = stage;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 按顺序排列的阶段名
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// 依次执行所有阶段
+    pub async fn run(&self, request: SearchRequest) -> Result<QueryContext, Box<dyn Error + Send + Sync>> {
+        let mut context = QueryContext::new(request);
+        for stage in &self.stages {
+            stage
+                .process(&mut context)
+                .await
+                .map_err(|e| format!("查询预处理阶段 {} 失败: {}", stage.name(), e))?;
+        }
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive::SearchQuery;
+
+    fn request(query: &str) -> SearchRequest {
+        SearchRequest {
+            query: SearchQuery { query: query.to_string(), ..Default::default() },
+            engines: vec!["bing".to_string(), "baidu".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn pipeline(config: &PipelineConfig) -> QueryPipeline {
+        QueryPipeline::from_config(config, None, &TranslationConfig::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_default_pipeline() {
+        let config = PipelineConfig {
+            min_safe_search: Some(SafeSearchLevel::Strict),
+            ..Default::default()
+        };
+        let context = pipeline(&config).run(request("  !bd\u{3000}pyhton   教程 ")).await.unwrap();
+        assert_eq!(context.request.query.query, "pyhton 教程");
+        assert_eq!(context.request.engines, vec!["baidu"]);
+        assert_eq!(context.bang.as_deref(), Some("bd"));
+        assert_eq!(context.spell_hints, vec!["python 教程"]);
+        assert!(matches!(context.request.query.safe_search, SafeSearchLevel::Strict));
+        // 默认不启用语言检测
+        assert_eq!(context.request.query.language, None);
+
+        assert!(pipeline(&config).run(request(" \t ")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stage_order_and_selection() {
+        let config = PipelineConfig {
+            stages: vec![STAGE_LANGUAGE.to_string(), STAGE_BANG.to_string()],
+            bangs: HashMap::from([("!docs".to_string(), vec!["bing".to_string()])]),
+            ..Default::default()
+        };
+        let pipeline = pipeline(&config);
+        assert_eq!(pipeline.stage_names(), vec![STAGE_LANGUAGE, STAGE_BANG]);
+
+        let context = pipeline.run(request("Rust 编程语言入门教程 !docs")).await.unwrap();
+        assert_eq!(context.request.query.query, "Rust 编程语言入门教程");
+        assert_eq!(context.request.engines, vec!["bing"]);
+        assert_eq!(context.request.query.language.as_deref(), Some("zh"));
+
+        // 未知的 bang 和只有 bang 的查询保持原样
+        let context = pipeline.run(request("!nope rust")).await.unwrap();
+        assert_eq!(context.request.query.query, "!nope rust");
+        assert_eq!(context.bang, None);
+
+        let unknown = PipelineConfig { stages: vec!["stemming".to_string()], ..Default::default() };
+        assert!(QueryPipeline::from_config(&unknown, None, &TranslationConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果页面预览（`preview` 特性）
+//!
+//! 按需用无头浏览器截取结果页面首屏的 JPEG 缩略图，供结果页悬停预览使用。
+//! 截图按 URL 写入缓存并长期保留，同一 URL 的并发请求只截图一次；浏览器在
+//! 第一次请求预览时才启动。
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+use uuid::Uuid;
+
+use crate::cache::CacheManager;
+use crate::search::browser::{BrowserPool, BrowserPoolConfig, PageScreenshotter, ScreenshotOptions};
+
+/// 截图在缓存中的键前缀
+const PREVIEW_CACHE_PREFIX: &str = "preview:";
+
+/// 预览配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    /// 是否启用（默认关闭）
+    pub enabled: bool,
+    /// 预览链接签名密钥
+    pub secret: String,
+    /// 页面视口宽度
+    pub viewport_width: u32,
+    /// 页面视口高度
+    pub viewport_height: u32,
+    /// 缩略图宽度（高度按视口比例缩放）
+    pub thumbnail_width: u32,
+    /// JPEG 质量（0 ~ 100）
+    pub quality: u8,
+    /// 截图的缓存时间（秒），同时用作响应的 `Cache-Control: max-age`
+    pub cache_ttl_secs: u64,
+    /// 截图使用的浏览器池（默认加载图片）
+    pub browser: BrowserPoolConfig,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secret: format!("preview_secret_{}", Uuid::new_v4()),
+            viewport_width: 1280,
+            viewport_height: 800,
+            thumbnail_width: 320,
+            quality: 60,
+            cache_ttl_secs: 7 * 24 * 3600, // 7 天
+            browser: BrowserPoolConfig {
+                disable_images: false,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl PreviewConfig {
+    /// 截图参数
+    pub fn screenshot_options(&self) -> ScreenshotOptions {
+        let viewport_width = self.viewport_width.max(1);
+        ScreenshotOptions {
+            viewport_width,
+            viewport_height: self.viewport_height.max(1),
+            scale: (f64::from(self.thumbnail_width) / f64::from(viewport_width)).clamp(0.05, 1.0),
+            quality: self.quality.min(100),
+        }
+    }
+
+    /// 截图的缓存时间
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.cache_ttl_secs)
+    }
+}
+
+/// 预览服务
+pub struct PreviewService {
+    config: PreviewConfig,
+    screenshotter: OnceCell<Arc<dyn PageScreenshotter>>,
+    cache: Option<Arc<CacheManager>>,
+    /// 正在截图的 URL，同一 URL 的请求排队等待第一次截图写入缓存
+    in_flight: DashMap<String, Arc<Mutex<()>>>,
+}
+
+impl PreviewService {
+    /// 创建预览服务（`cache` 为 None 时每次都重新截图）
+    pub fn new(config: PreviewConfig, cache: Option<Arc<CacheManager>>) -> Self {
+        Self {
+            config,
+            screenshotter: OnceCell::new(),
+            cache,
+            in_flight: DashMap::new(),
+        }
+    }
+
+    /// 使用指定的截图实现，不再自行启动浏览器
+    pub fn with_screenshotter(self, screenshotter: Arc<dyn PageScreenshotter>) -> Self {
+        Self {
+            screenshotter: OnceCell::new_with(Some(screenshotter)),
+            ..self
+        }
+    }
+
+    /// 预览配置
+    pub fn config(&self) -> &PreviewConfig {
+        &self.config
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 取得页面截图（优先读缓存）
+    pub async fn capture(&self, url: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let cache_key = format!("{}{}", PREVIEW_CACHE_PREFIX, url);
+        if let Some(image) = self.cached(&cache_key) {
+            return Ok(image);
+        }
+
+        let lock = self.in_flight.entry(url.to_string()).or_default().clone();
+        let result = async {
+            let _guard = lock.lock().await;
+            // 排队期间其他请求可能已经截好图
+            if let Some(image) = self.cached(&cache_key) {
+                return Ok(image);
+            }
+            let screenshotter = self
+                .screenshotter
+                .get_or_try_init(|| async {
+                    let pool = BrowserPool::launch(self.config.browser.clone()).await?;
+                    Ok::<_, Box<dyn Error + Send + Sync>>(Arc::new(pool) as Arc<dyn PageScreenshotter>)
+                })
+                .await?;
+            let image = screenshotter.screenshot(url, &self.config.screenshot_options()).await?;
+            if let Some(cache) = &self.cache
+                && let Err(e) = cache.set(cache_key.clone(), image.clone(), Some(self.config.cache_ttl()))
+            {
+                tracing::warn!("Failed to cache preview for {}: {}", url, e);
+            }
+            Ok(image)
+        }
+        .await;
+        self.in_flight.remove_if(url, |_, lock| Arc::strong_count(lock) <= 2);
+        result
+    }
+
+    fn cached(&self, cache_key: &str) -> Option<Vec<u8>> {
+        self.cache.as_ref()?.get(cache_key).ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 返回固定字节并统计调用次数的截图实现
+    #[derive(Default)]
+    struct CountingScreenshotter {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PageScreenshotter for CountingScreenshotter {
+        async fn screenshot(&self, url: &str, options: &ScreenshotOptions) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(format!("{}@{}", url, options.quality).into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_screenshot_options_scale() {
+        let options = PreviewConfig::default().screenshot_options();
+        assert_eq!((options.viewport_width, options.viewport_height), (1280, 800));
+        assert_eq!(options.scale, 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_capture_is_cached_and_deduplicated() {
+        let screenshotter = Arc::new(CountingScreenshotter::default());
+        let cache = CacheManager::temporary();
+        let service = PreviewService::new(PreviewConfig::default(), Some(cache))
+            .with_screenshotter(screenshotter.clone());
+        let url = format!("https://example.com/{}", Uuid::new_v4().simple());
+
+        let (a, b) = tokio::join!(service.capture(&url), service.capture(&url));
+        assert_eq!(a.unwrap(), format!("{}@60", url).into_bytes());
+        assert_eq!(b.unwrap(), format!("{}@60", url).into_bytes());
+        assert!(service.capture(&url).await.is_ok());
+        assert_eq!(screenshotter.calls.load(Ordering::SeqCst), 1);
+        assert!(service.in_flight.is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTML 清理
+//!
+//! feed 的摘要和部分引擎的片段带有原始 HTML（`<script>`、内联样式、关键词高亮等）。
+//! 默认把它们转为纯文本；开启 [`SanitizeConfig::keep_ui_tags`] 时保留一小组排版标签
+//! （[`UI_TAGS`]），输出是已清理的 HTML 片段，可以直接插入页面。
+
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 保留标签模式下允许的标签（均不允许属性）
+pub const UI_TAGS: &[&str] = &["b", "strong", "em", "i", "mark", "code", "sub", "sup", "br"];
+
+/// 清理配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanitizeConfig {
+    /// 结果摘要保留 [`UI_TAGS`] 中的标签（供网页 UI 展示高亮），否则转为纯文本
+    pub keep_ui_tags: bool,
+}
+
+static TEXT_CLEANER: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let mut builder = ammonia::Builder::empty();
+    builder.clean_content_tags(HashSet::from(["script", "style"]));
+    builder
+});
+
+static UI_CLEANER: Lazy<ammonia::Builder<'static>> = Lazy::new(|| {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .tags(UI_TAGS.iter().copied().collect())
+        .clean_content_tags(HashSet::from(["script", "style"]));
+    builder
+});
+
+/// 常见 HTML 元素的标签或注释（`Vec<T>`、`a <runtime>` 这类纯文本不算）
+static MARKUP_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)<!--|</?(a|abbr|b|blockquote|br|code|dd|del|div|dl|dt|em|figcaption|figure|font|h[1-6]|hr|i|iframe|img|ins|li|mark|noscript|object|ol|p|pre|q|s|script|small|span|strike|strong|style|sub|sup|svg|table|tbody|td|th|thead|tr|u|ul|video|audio|source|embed|form|input|button|link|meta)(\s[^>]*)?/?>",
+    )
+    .unwrap()
+});
+
+/// 是否含有 HTML 标签（纯文本只解码实体，保留字面的 `<`）
+fn has_markup(text: &str) -> bool {
+    text.contains('<') && MARKUP_RE.is_match(text)
+}
+
+/// 转为纯文本：去掉所有标签（`<script>` / `<style>` 连同内容），解码实体并合并空白
+pub fn sanitize_text(html: &str) -> String {
+    let text = if has_markup(html) {
+        let cleaned = TEXT_CLEANER.clean(html).to_string();
+        html_escape::decode_html_entities(&cleaned).into_owned()
+    } else {
+        html_escape::decode_html_entities(html).into_owned()
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 清理为只含 [`UI_TAGS`] 的 HTML 片段，并合并空白
+///
+/// 不含 HTML 标签的纯文本只做转义
+pub fn sanitize_fragment(html: &str) -> String {
+    let collapsed = html.split_whitespace().collect::<Vec<_>>().join(" ");
+    if has_markup(&collapsed) {
+        UI_CLEANER.clean(&collapsed).to_string()
+    } else {
+        let text = html_escape::decode_html_entities(&collapsed);
+        html_escape::encode_text(&text).into_owned()
+    }
+}
+
+impl SanitizeConfig {
+    /// 按配置清理摘要类字段
+    pub fn sanitize(&self, html: &str) -> String {
+        if self.keep_ui_tags {
+            sanitize_fragment(html)
+        } else {
+            sanitize_text(html)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_text() {
+        let html = r#"<p onclick="x()">Rust &amp; <em>async</em></p><script>alert(1)</script>
+            <style>p { color: red }</style><img src=x onerror=alert(1)>done"#;
+        assert_eq!(sanitize_text(html), "Rust & async done");
+        assert_eq!(sanitize_text("  plain   text "), "plain text");
+        // 纯文本中的尖括号不是标签
+        assert_eq!(sanitize_text("Vec<String> &amp; Tokio <runtime>"), "Vec<String> & Tokio <runtime>");
+    }
+
+    #[test]
+    fn test_sanitize_fragment_keeps_ui_tags() {
+        let html = r#"<div><em class="keyword">Rust</em> <a href="javascript:alert(1)">link</a><script>x</script> a &lt; b</div>"#;
+        assert_eq!(sanitize_fragment(html), "<em>Rust</em> link a &lt; b");
+        assert_eq!(SanitizeConfig { keep_ui_tags: false }.sanitize(html), "Rust link a < b");
+        assert_eq!(sanitize_fragment("Vec<String>"), "Vec&lt;String&gt;");
+    }
+}
@@ -50,16 +50,22 @@ pub struct ScoringWeights {
     pub engine_authority: f64,
     /// 位置权重
     pub position_weight: f64,
+    /// 时效性权重（仅对带发布时间的结果生效）
+    pub recency: f64,
+    /// 时效性衰减半衰期（小时）
+    pub recency_half_life_hours: f64,
 }
 
 impl Default for ScoringWeights {
     fn default() -> Self {
         Self {
-            title_bm25: 0.40,       // 标题最重要
-            content_bm25: 0.30,     // 内容次之
+            title_bm25: 0.35,       // 标题最重要
+            content_bm25: 0.25,     // 内容次之
             url_match: 0.10,        // URL 匹配
             engine_authority: 0.15, // 引擎权威度
             position_weight: 0.05,  // 原始排名位置
+            recency: 0.10,          // 时效性加分
+            recency_half_life_hours: 72.0,
         }
     }
 }
@@ -215,6 +221,24 @@ pub(crate) fn position_score(position: usize) -> f64 {
     1.0 / (1.0 + ((position + 1) as f64).ln())
 }
 
+/// 时效性评分（指数衰减）
+///
+/// 发布时间每经过一个半衰期，分数减半；无发布时间返回 0，未来时间按 1 计。
+pub(crate) fn recency_score(
+    published_date: Option<chrono::DateTime<chrono::Utc>>,
+    half_life_hours: f64,
+) -> f64 {
+    let Some(date) = published_date else {
+        return 0.0;
+    };
+    if half_life_hours <= 0.0 {
+        return 0.0;
+    }
+
+    let age_hours = (chrono::Utc::now() - date).num_seconds().max(0) as f64 / 3600.0;
+    0.5_f64.powf(age_hours / half_life_hours)
+}
+
 /// 计算综合评分
 pub(crate) fn calculate_score(
     item: &SearchResultItem,
@@ -245,13 +269,17 @@ pub(crate) fn calculate_score(
     // 5. 位置评分
     let pos_score = position_score(position);
     
+    // 6. 时效性评分
+    let fresh_score = recency_score(item.published_date, weights.recency_half_life_hours);
+    
     // 加权求和
     let final_score = 
         title_score * weights.title_bm25 +
         content_score * weights.content_bm25 +
         url_score * weights.url_match +
         authority_score * weights.engine_authority +
-        pos_score * weights.position_weight;
+        pos_score * weights.position_weight +
+        fresh_score * weights.recency;
     
     // 确保在 [0, 1] 范围内
     final_score.max(0.0).min(1.0)
@@ -361,6 +389,19 @@ mod tests {
         assert!(position_score(5) > position_score(10));
     }
 
+    #[test]
+    fn test_recency_score() {
+        let now = chrono::Utc::now();
+        assert_eq!(recency_score(None, 72.0), 0.0);
+        assert!((recency_score(Some(now), 72.0) - 1.0).abs() < 1e-3);
+
+        let one_half_life = recency_score(Some(now - chrono::Duration::hours(72)), 72.0);
+        assert!((one_half_life - 0.5).abs() < 1e-3);
+
+        let older = recency_score(Some(now - chrono::Duration::days(30)), 72.0);
+        assert!(older < one_half_life);
+    }
+
     #[test]
     fn test_engine_authority() {
         assert_eq!(get_engine_authority("google"), 1.0);
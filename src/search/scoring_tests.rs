@@ -260,7 +260,7 @@ fn test_tokenize_empty_string() {
         
         // Verify weights sum to approximately 1.0
         let sum = weights.title_bm25 + weights.content_bm25 + weights.url_match 
-                + weights.engine_authority + weights.position_weight;
+                + weights.engine_authority + weights.position_weight + weights.recency;
         assert!((sum - 1.0).abs() < 0.01, "Weights should sum to ~1.0");
     }
 
@@ -0,0 +1,110 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果安全标记
+//!
+//! 按结果 URL 标记明文 HTTP（`insecure`）、短链接（`shortener`）和广告/点击跟踪跳转（`tracker`）。
+//! 短链接和跟踪域名使用内置列表，可以在配置中追加；严格模式下带标记的结果直接丢弃。
+
+use serde::{Deserialize, Serialize};
+
+use crate::derive::SecurityFlag;
+use crate::url_utils::{domain, parse_http_url};
+
+/// 内置的短链接域名
+pub const SHORTENER_DOMAINS: &[&str] = &[
+    "bit.ly", "bitly.com", "t.co", "tinyurl.com", "goo.gl", "ow.ly", "is.gd", "buff.ly", "rebrand.ly",
+    "cutt.ly", "shorturl.at", "rb.gy", "tiny.cc", "lnkd.in", "s.id", "v.gd", "t.ly", "t.cn", "url.cn",
+    "dwz.cn", "suo.im", "b23.tv",
+];
+
+/// 内置的广告和点击跟踪域名
+pub const TRACKER_DOMAINS: &[&str] = &[
+    "doubleclick.net", "googleadservices.com", "googlesyndication.com", "adservice.google.com",
+    "click.linksynergy.com", "awin1.com", "anrdoezrs.net", "dpbolvw.net", "jdoqocy.com", "kqzyfj.com",
+    "tkqlhce.com", "shareasale.com", "clickbank.net", "awstrack.me", "list-manage.com", "hubspotlinks.com",
+    "mclick.mail.qq.com", "e.cn.miaozhen.com", "click.tanx.com",
+];
+
+/// 安全标记配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityFlagsConfig {
+    /// 是否标记结果
+    pub enabled: bool,
+    /// 追加的短链接域名（子域名同样匹配）
+    pub shorteners: Vec<String>,
+    /// 追加的跟踪域名（子域名同样匹配）
+    pub trackers: Vec<String>,
+}
+
+impl Default for SecurityFlagsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shorteners: Vec::new(),
+            trackers: Vec::new(),
+        }
+    }
+}
+
+impl SecurityFlagsConfig {
+    /// 计算 URL 的安全标记（无法解析的地址没有标记）
+    pub fn flags_for(&self, url: &str) -> Vec<SecurityFlag> {
+        let mut flags = Vec::new();
+        let (Some(parsed), Some(host)) = (parse_http_url(url), domain(url)) else {
+            return flags;
+        };
+        if parsed.scheme() == "http" {
+            flags.push(SecurityFlag::Insecure);
+        }
+        if matches_domain(&host, SHORTENER_DOMAINS.iter().copied().chain(self.shorteners.iter().map(String::as_str))) {
+            flags.push(SecurityFlag::Shortener);
+        }
+        if matches_domain(&host, TRACKER_DOMAINS.iter().copied().chain(self.trackers.iter().map(String::as_str))) {
+            flags.push(SecurityFlag::Tracker);
+        }
+        flags
+    }
+}
+
+/// 主机名是否为列表中的域名或其子域名
+fn matches_domain<'a>(host: &str, domains: impl IntoIterator<Item = &'a str>) -> bool {
+    domains.into_iter().any(|listed| {
+        let listed = listed.trim().trim_start_matches("www.").to_lowercase();
+        !listed.is_empty()
+            && (host == listed || host.strip_suffix(listed.as_str()).is_some_and(|prefix| prefix.ends_with('.')))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_for() {
+        let config = SecurityFlagsConfig {
+            shorteners: vec!["go.corp".to_string()],
+            ..Default::default()
+        };
+        assert!(config.flags_for("https://www.rust-lang.org/").is_empty());
+        assert_eq!(config.flags_for("http://example.com/"), vec![SecurityFlag::Insecure]);
+        assert_eq!(config.flags_for("http://bit.ly/abc"), vec![SecurityFlag::Insecure, SecurityFlag::Shortener]);
+        assert_eq!(config.flags_for("https://go.corp/wiki"), vec![SecurityFlag::Shortener]);
+        assert_eq!(config.flags_for("https://ad.doubleclick.net/ddm/clk/1"), vec![SecurityFlag::Tracker]);
+        // 只匹配完整的域名标签
+        assert!(config.flags_for("https://notbit.ly/").is_empty());
+        assert!(config.flags_for("not a url").is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 引擎自检
+//!
+//! 用一组固定的探测查询（默认 `rust` 和 `新闻`）直接调用每个启用的引擎，报告每次查询的
+//! 结果数、耗时和解析警告（空标题、无效链接、重复链接等），用于部署或修改解析器后验证实例。
+//! 自检绕过结果缓存，也不计入引擎状态、熔断和异常检测。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::derive::SearchResultItem;
+
+/// 默认的探测查询
+pub const DEFAULT_SELFTEST_QUERIES: &[&str] = &["rust", "新闻"];
+
+/// 单次探测查询的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestQuery {
+    /// 查询词
+    pub query: String,
+    /// 解析出的结果数
+    pub items: usize,
+    /// 耗时（毫秒）
+    pub latency_ms: u64,
+    /// 请求或解析错误
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 解析警告
+    pub warnings: Vec<String>,
+}
+
+impl SelfTestQuery {
+    /// 查询是否通过（无错误且有结果）
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.items > 0
+    }
+}
+
+/// 单个引擎的自检结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSelfTest {
+    /// 引擎名称
+    pub engine: String,
+    /// 是否至少有一个探测查询通过
+    pub passed: bool,
+    /// 各探测查询的结果
+    pub queries: Vec<SelfTestQuery>,
+}
+
+impl EngineSelfTest {
+    /// 汇总引擎的探测结果
+    pub fn new(engine: String, queries: Vec<SelfTestQuery>) -> Self {
+        Self {
+            engine,
+            passed: queries.iter().any(SelfTestQuery::passed),
+            queries,
+        }
+    }
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    /// 使用的探测查询
+    pub queries: Vec<String>,
+    /// 通过的引擎数
+    pub passed: usize,
+    /// 未通过的引擎数
+    pub failed: usize,
+    /// 总耗时（毫秒）
+    pub elapsed_ms: u64,
+    /// 各引擎的结果（按引擎名排序）
+    pub engines: Vec<EngineSelfTest>,
+}
+
+impl SelfTestReport {
+    /// 汇总各引擎的结果
+    pub fn new(queries: Vec<String>, mut engines: Vec<EngineSelfTest>, elapsed_ms: u64) -> Self {
+        engines.sort_by(|a, b| a.engine.cmp(&b.engine));
+        let passed = engines.iter().filter(|engine| engine.passed).count();
+        Self {
+            queries,
+            passed,
+            failed: engines.len() - passed,
+            elapsed_ms,
+            engines,
+        }
+    }
+}
+
+/// 检查解析出的结果，返回解析警告
+pub fn parse_warnings(items: &[SearchResultItem]) -> Vec<String> {
+    if items.is_empty() {
+        return vec!["no items parsed".to_string()];
+    }
+    let empty_titles = items.iter().filter(|item| item.title.trim().is_empty()).count();
+    let invalid_urls = items.iter()
+        .filter(|item| !matches!(url::Url::parse(&item.url), Ok(url) if url.has_host()))
+        .count();
+    let mut seen = HashSet::new();
+    let duplicates = items.iter().filter(|item| !seen.insert(item.url.as_str())).count();
+    let empty_content = items.iter().filter(|item| item.content.trim().is_empty()).count();
+
+    let mut warnings = Vec::new();
+    if empty_titles > 0 {
+        warnings.push(format!("{} items with empty title", empty_titles));
+    }
+    if invalid_urls > 0 {
+        warnings.push(format!("{} items with invalid URL", invalid_urls));
+    }
+    if duplicates > 0 {
+        warnings.push(format!("{} duplicate URLs", duplicates));
+    }
+    // 少数结果没有摘要很常见，全部没有通常说明摘要选择器失效
+    if empty_content == items.len() {
+        warnings.push("no items have a snippet".to_string());
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive::ResultType;
+    use std::collections::HashMap;
+
+    fn item(title: &str, url: &str, content: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: title.to_string(),
+            url: url.to_string(),
+            content: content.to_string(),
+            display_url: None,
+            site_name: None,
+            score: 1.0,
+            result_type: ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_warnings() {
+        assert_eq!(parse_warnings(&[]), vec!["no items parsed"]);
+        assert!(parse_warnings(&[item("Rust", "https://www.rust-lang.org/", "A language")]).is_empty());
+
+        let items = vec![
+            item("", "https://a.com/1", ""),
+            item("B", "/relative", ""),
+            item("C", "https://a.com/1", ""),
+        ];
+        assert_eq!(parse_warnings(&items), vec![
+            "1 items with empty title",
+            "1 items with invalid URL",
+            "1 duplicate URLs",
+            "no items have a snippet",
+        ]);
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let ok = SelfTestQuery { query: "rust".to_string(), items: 10, latency_ms: 120, error: None, warnings: vec![] };
+        let broken = SelfTestQuery { query: "rust".to_string(), items: 0, latency_ms: 90, error: None, warnings: parse_warnings(&[]) };
+        let report = SelfTestReport::new(
+            vec!["rust".to_string()],
+            vec![EngineSelfTest::new("so".to_string(), vec![broken]), EngineSelfTest::new("bing".to_string(), vec![ok])],
+            130,
+        );
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.engines[0].engine, "bing");
+        assert!(!report.engines[1].passed);
+    }
+}
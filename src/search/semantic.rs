@@ -0,0 +1,339 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 语义重排（`semantic` 特性）
+//!
+//! 为查询和排名前 `top_k` 的结果（标题 + 摘要）计算向量，把余弦相似度按 `weight` 混入
+//! 最终分数：`分数 = (1 - weight) × 原分数 + weight × max(相似度, 0)`，然后在这 `top_k`
+//! 条内重新排序。对较长的自然语言查询效果明显，关键词查询通常无需重排，可用
+//! `min_query_terms` 跳过。
+//!
+//! 向量来自 [`Embedder`]。内置的 [`HttpEmbedder`] 调用 OpenAI 兼容的 `/v1/embeddings`
+//! 接口，可以指向 text-embeddings-inference、Ollama 等本地服务运行的小型多语言模型
+//! （如 `multilingual-e5-small`）；其他实现（例如进程内的 ONNX 模型）实现该 trait 后
+//! 通过 [`SemanticReranker::new`] 接入。计算失败时保持原顺序。
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::derive::SearchResultItem;
+use crate::net::client::HttpTransport;
+use crate::net::types::RequestOptions;
+
+/// 结果元数据中记录语义相似度的键
+pub const SEMANTIC_SCORE_KEY: &str = "semantic_score";
+
+/// 每条结果参与计算的最大字符数
+const MAX_DOCUMENT_CHARS: usize = 512;
+
+/// 语义重排配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// OpenAI 兼容的向量接口地址
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// 模型名
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// API 密钥（以 `Authorization: Bearer` 发送）
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// 查询前缀（E5 系列模型为 `"query: "`）
+    #[serde(default)]
+    pub query_prefix: String,
+    /// 结果文本前缀（E5 系列模型为 `"passage: "`）
+    #[serde(default)]
+    pub document_prefix: String,
+    /// 参与重排的结果数
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    /// 相似度在最终分数中的比重（0~1）
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// 查询至少包含的词数（更短的查询不重排）
+    #[serde(default = "default_min_query_terms")]
+    pub min_query_terms: usize,
+    /// 向量接口超时（毫秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_endpoint() -> String {
+    "http://127.0.0.1:8090/v1/embeddings".to_string()
+}
+
+fn default_model() -> String {
+    "intfloat/multilingual-e5-small".to_string()
+}
+
+fn default_top_k() -> usize {
+    20
+}
+
+fn default_weight() -> f64 {
+    0.3
+}
+
+fn default_min_query_terms() -> usize {
+    3
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+impl Default for SemanticConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_endpoint(),
+            model: default_model(),
+            api_key: None,
+            query_prefix: String::new(),
+            document_prefix: String::new(),
+            top_k: default_top_k(),
+            weight: default_weight(),
+            min_query_terms: default_min_query_terms(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+/// 文本向量计算
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// 按顺序返回每段文本的向量
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, Box<dyn Error + Send + Sync>>;
+}
+
+/// 调用 OpenAI 兼容 `/v1/embeddings` 接口的向量计算
+pub struct HttpEmbedder {
+    transport: Arc<dyn HttpTransport>,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    timeout: Duration,
+}
+
+impl HttpEmbedder {
+    /// 按配置创建
+    pub fn new(transport: Arc<dyn HttpTransport>, config: &SemanticConfig) -> Self {
+        Self {
+            transport,
+            endpoint: config.endpoint.clone(),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+            timeout: Duration::from_millis(config.timeout_ms),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+#[async_trait]
+impl Embedder for HttpEmbedder {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, Box<dyn Error + Send + Sync>> {
+        let body = serde_json::to_vec(&EmbeddingRequest { model: &self.model, input: texts })?;
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        if let Some(api_key) = &self.api_key {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", api_key)));
+        }
+        let options = RequestOptions { timeout: self.timeout, headers, ..Default::default() };
+        let response = self.transport
+            .post(&self.endpoint, body, Some(options))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("向量接口返回 HTTP {}", response.status()).into());
+        }
+        let mut body: EmbeddingResponse = response.json().await?;
+        if body.data.len() != texts.len() {
+            return Err(format!("向量接口返回 {} 个向量，请求了 {} 段文本", body.data.len(), texts.len()).into());
+        }
+        body.data.sort_by_key(|data| data.index);
+        Ok(body.data.into_iter().map(|data| data.embedding).collect())
+    }
+}
+
+/// 余弦相似度（任一向量为零或维度不同时为 0）
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// 语义重排器
+pub struct SemanticReranker {
+    config: SemanticConfig,
+    embedder: Arc<dyn Embedder>,
+}
+
+impl SemanticReranker {
+    /// 使用指定的向量计算创建重排器
+    pub fn new(config: SemanticConfig, embedder: Arc<dyn Embedder>) -> Self {
+        Self { config, embedder }
+    }
+
+    /// 重排配置
+    pub fn config(&self) -> &SemanticConfig {
+        &self.config
+    }
+
+    /// 把语义相似度混入前 `top_k` 条结果的分数并在其中重新排序，返回是否做了重排
+    pub async fn rerank(&self, query: &str, items: &mut [SearchResultItem]) -> bool {
+        if query.split_whitespace().count() < self.config.min_query_terms || items.len() < 2 {
+            return false;
+        }
+        let head = items.len().min(self.config.top_k);
+        let mut texts = Vec::with_capacity(head + 1);
+        texts.push(format!("{}{}", self.config.query_prefix, query));
+        texts.extend(items[..head].iter().map(|item| self.document_text(item)));
+
+        let embeddings = match self.embedder.embed(&texts).await {
+            Ok(embeddings) if embeddings.len() == texts.len() => embeddings,
+            Ok(_) => {
+                tracing::warn!("Semantic rerank skipped: embedding count mismatch");
+                metrics::counter!("seesea_semantic_rerank_total", "outcome" => "error").increment(1);
+                return false;
+            }
+            Err(e) => {
+                tracing::warn!("Semantic rerank skipped: {}", e);
+                metrics::counter!("seesea_semantic_rerank_total", "outcome" => "error").increment(1);
+                return false;
+            }
+        };
+
+        let weight = self.config.weight.clamp(0.0, 1.0);
+        for (item, embedding) in items[..head].iter_mut().zip(&embeddings[1..]) {
+            let similarity = cosine_similarity(&embeddings[0], embedding);
+            item.score = (1.0 - weight) * item.score + weight * similarity.max(0.0);
+            item.metadata.insert(SEMANTIC_SCORE_KEY.to_string(), format!("{:.4}", similarity));
+        }
+        items[..head].sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        metrics::counter!("seesea_semantic_rerank_total", "outcome" => "success").increment(1);
+        true
+    }
+
+    fn document_text(&self, item: &SearchResultItem) -> String {
+        let text: String = format!("{} {}", item.title, item.content)
+            .chars()
+            .take(MAX_DOCUMENT_CHARS)
+            .collect();
+        format!("{}{}", self.config.document_prefix, text.trim())
+    }
+}
+
+/// 根据配置创建重排器（未启用时返回 None）
+pub fn create_reranker(config: &SemanticConfig, transport: Arc<dyn HttpTransport>) -> Option<Arc<SemanticReranker>> {
+    if !config.enabled {
+        return None;
+    }
+    let embedder = Arc::new(HttpEmbedder::new(transport, config));
+    Some(Arc::new(SemanticReranker::new(config.clone(), embedder)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    fn item(title: &str, score: f64) -> SearchResultItem {
+        SearchResultItem {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", title),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score,
+            result_type: crate::derive::ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_blends_similarity() {
+        let body = r#"{"data": [
+            {"embedding": [0.0, 1.0], "index": 1},
+            {"embedding": [1.0, 0.0], "index": 0},
+            {"embedding": [1.0, 0.0], "index": 2}
+        ]}"#;
+        let transport = Arc::new(MockTransport::new().respond("/v1/embeddings", MockResponse::ok(body)));
+        let config = SemanticConfig { enabled: true, weight: 0.5, api_key: Some("key".to_string()), ..Default::default() };
+        let reranker = create_reranker(&config, transport.clone()).unwrap();
+
+        let mut items = vec![item("keyword", 0.9), item("meaning", 0.8)];
+        assert!(reranker.rerank("how do i learn rust", &mut items).await);
+        assert_eq!(items[0].title, "meaning");
+        assert!((items[0].score - 0.9).abs() < 1e-9);
+        assert_eq!(items[1].metadata[SEMANTIC_SCORE_KEY], "0.0000");
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.header("authorization"), Some("Bearer key"));
+        let sent: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent["input"].as_array().unwrap().len(), 3);
+
+        // 短查询不重排，接口失败时保持原顺序
+        assert!(!reranker.rerank("rust", &mut items).await);
+        let failing = create_reranker(&config, Arc::new(MockTransport::new())).unwrap();
+        let mut items = vec![item("keyword", 0.9), item("meaning", 0.8)];
+        assert!(!failing.rerank("how do i learn rust", &mut items).await);
+        assert_eq!(items[0].title, "keyword");
+    }
+}
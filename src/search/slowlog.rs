@@ -0,0 +1,274 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 慢查询日志
+//!
+//! 总耗时超过阈值的搜索连同各引擎的耗时记入环形缓冲，管理 API 提供最近的慢查询和按查询
+//! 聚合的排行，帮助运维调整超时和引擎组合。启用 `hash_queries` 后只保存规范化查询的
+//! SHA-256 摘要，原始查询不进入内存。
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::types::EngineTiming;
+
+/// 查询摘要保留的十六进制字符数
+const QUERY_HASH_LEN: usize = 16;
+
+/// 慢查询日志配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQueryConfig {
+    /// 是否启用（默认启用）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 总耗时达到该值（毫秒）的搜索记为慢查询
+    #[serde(default = "default_threshold_ms")]
+    pub threshold_ms: u64,
+    /// 保留的最近慢查询条数
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    /// 只保存查询的摘要（隐私保护，排行中也只显示摘要）
+    #[serde(default)]
+    pub hash_queries: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_threshold_ms() -> u64 {
+    3000
+}
+
+fn default_capacity() -> usize {
+    100
+}
+
+impl Default for SlowQueryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            threshold_ms: default_threshold_ms(),
+            capacity: default_capacity(),
+            hash_queries: false,
+        }
+    }
+}
+
+/// 一条慢查询记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowQuery {
+    /// 规范化后的查询（`hashed` 时为摘要）
+    pub query: String,
+    /// `query` 是否为摘要
+    pub hashed: bool,
+    /// 搜索总耗时（毫秒）
+    pub total_ms: u64,
+    /// 各引擎耗时（最慢的在前）
+    pub engines: Vec<EngineTiming>,
+    /// 记录时间
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// 按查询聚合的慢查询排行项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopQuery {
+    /// 规范化后的查询（`hashed` 时为摘要）
+    pub query: String,
+    /// `query` 是否为摘要
+    pub hashed: bool,
+    /// 慢查询次数
+    pub count: usize,
+    /// 平均总耗时（毫秒）
+    pub avg_ms: u64,
+    /// 最大总耗时（毫秒）
+    pub max_ms: u64,
+    /// 各引擎的平均耗时（毫秒）
+    pub engine_avg_ms: BTreeMap<String, u64>,
+    /// 最近一次出现的时间
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// 慢查询日志
+pub struct SlowQueryLog {
+    config: SlowQueryConfig,
+    entries: Mutex<VecDeque<SlowQuery>>,
+}
+
+impl SlowQueryLog {
+    /// 创建慢查询日志
+    pub fn new(config: SlowQueryConfig) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(config.capacity)),
+            config,
+        }
+    }
+
+    /// 日志配置
+    pub fn config(&self) -> &SlowQueryConfig {
+        &self.config
+    }
+
+    /// 记录一次搜索，超过阈值时写入日志和指标并返回 true
+    pub fn record(&self, query: &str, total_ms: u64, engines: &[EngineTiming]) -> bool {
+        if !self.config.enabled || self.config.capacity == 0 || total_ms < self.config.threshold_ms {
+            return false;
+        }
+        let mut engines = engines.to_vec();
+        engines.sort_by_key(|timing| std::cmp::Reverse(timing.elapsed_ms));
+        let entry = SlowQuery {
+            query: self.query_key(query),
+            hashed: self.config.hash_queries,
+            total_ms,
+            engines,
+            recorded_at: Utc::now(),
+        };
+
+        tracing::info!(
+            query = %entry.query,
+            total_ms,
+            slowest_engine = entry.engines.first().map(|e| e.engine.as_str()).unwrap_or("-"),
+            "Slow query"
+        );
+        metrics::counter!("seesea_slow_queries_total").increment(1);
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        while entries.len() >= self.config.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+        true
+    }
+
+    /// 最近的慢查询（最新的在前）
+    pub fn recent(&self) -> Vec<SlowQuery> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().rev().cloned().collect()
+    }
+
+    /// 按查询聚合的排行（次数多的在前，次数相同时平均耗时长的在前），最多 `limit` 项
+    pub fn top(&self, limit: usize) -> Vec<TopQuery> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut groups: HashMap<&str, Vec<&SlowQuery>> = HashMap::new();
+        for entry in entries.iter() {
+            groups.entry(entry.query.as_str()).or_default().push(entry);
+        }
+
+        let mut top: Vec<TopQuery> = groups.into_iter()
+            .map(|(query, group)| {
+                let count = group.len();
+                let mut engine_totals: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+                for timing in group.iter().flat_map(|entry| &entry.engines) {
+                    let total = engine_totals.entry(timing.engine.clone()).or_default();
+                    total.0 += timing.elapsed_ms;
+                    total.1 += 1;
+                }
+                TopQuery {
+                    query: query.to_string(),
+                    hashed: group[0].hashed,
+                    count,
+                    avg_ms: group.iter().map(|entry| entry.total_ms).sum::<u64>() / count as u64,
+                    max_ms: group.iter().map(|entry| entry.total_ms).max().unwrap_or(0),
+                    engine_avg_ms: engine_totals.into_iter()
+                        .map(|(engine, (sum, n))| (engine, sum / n))
+                        .collect(),
+                    last_seen_at: group.iter().map(|entry| entry.recorded_at).max().unwrap_or_default(),
+                }
+            })
+            .collect();
+        top.sort_by(|a, b| b.count.cmp(&a.count).then(b.avg_ms.cmp(&a.avg_ms)));
+        top.truncate(limit);
+        top
+    }
+
+    /// 规范化查询（去除首尾空白、合并空白、小写），按配置转为摘要
+    fn query_key(&self, query: &str) -> String {
+        let normalized = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        if !self.config.hash_queries {
+            return normalized;
+        }
+        let digest = format!("{:x}", Sha256::digest(normalized.as_bytes()));
+        digest[..QUERY_HASH_LEN].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::EngineTimingStatus;
+
+    fn timing(engine: &str, elapsed_ms: u64) -> EngineTiming {
+        EngineTiming {
+            engine: engine.to_string(),
+            status: EngineTimingStatus::Success,
+            elapsed_ms,
+            items: 10,
+        }
+    }
+
+    fn config(threshold_ms: u64, capacity: usize) -> SlowQueryConfig {
+        SlowQueryConfig { threshold_ms, capacity, ..SlowQueryConfig::default() }
+    }
+
+    #[test]
+    fn test_records_only_slow_queries_in_ring_buffer() {
+        let log = SlowQueryLog::new(config(1000, 2));
+        assert!(!log.record("fast", 999, &[]));
+        assert!(log.record("rust  Async", 1500, &[timing("bing", 200), timing("google", 1400)]));
+        assert!(log.record("second", 1000, &[]));
+        assert!(log.record("third", 2000, &[]));
+
+        let recent = log.recent();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].query, "third");
+        assert_eq!(recent[1].query, "second");
+
+        let log = SlowQueryLog::new(config(1000, 10));
+        log.record("rust  Async", 1500, &[timing("bing", 200), timing("google", 1400)]);
+        let entry = &log.recent()[0];
+        assert_eq!(entry.query, "rust async");
+        assert_eq!(entry.engines[0].engine, "google");
+    }
+
+    #[test]
+    fn test_top_queries_aggregate_and_hash() {
+        let log = SlowQueryLog::new(config(100, 10));
+        log.record("rust", 200, &[timing("bing", 100)]);
+        log.record("Rust", 400, &[timing("bing", 300)]);
+        log.record("go", 1000, &[]);
+
+        let top = log.top(10);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].query, "rust");
+        assert_eq!(top[0].count, 2);
+        assert_eq!(top[0].avg_ms, 300);
+        assert_eq!(top[0].max_ms, 400);
+        assert_eq!(top[0].engine_avg_ms["bing"], 200);
+        assert_eq!(log.top(1).len(), 1);
+
+        let hashed = SlowQueryLog::new(SlowQueryConfig { hash_queries: true, ..config(100, 10) });
+        hashed.record("secret query", 200, &[]);
+        hashed.record("Secret  Query", 200, &[]);
+        let top = hashed.top(10);
+        assert_eq!(top.len(), 1);
+        assert!(top[0].hashed);
+        assert_eq!(top[0].query.len(), QUERY_HASH_LEN);
+        assert!(!top[0].query.contains("secret"));
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 结果后处理阶段
+//!
+//! 聚合器把合并后的结果依次交给一串 [`ResultStage`]：去重、安全标记、评分、语言过滤、排序、
+//! 结构化过滤和域名多样性重排。下游 crate 或 Python 绑定可以在任意内置阶段之前
+//! 或整条链末尾插入自定义阶段（例如内部的重排模型），无需改动聚合器。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::derive::{SearchQuery, SearchResultItem};
+use super::aggregator::{diversify_by_domain, SortBy, ENGINE_SCORE_KEY, SOURCE_ENGINE_KEY};
+use super::filter::ResultFilter;
+use super::language::matches_language;
+use super::scoring::{score_results, ScoringWeights};
+use super::security::SecurityFlagsConfig;
+use super::standardization::deduplicate_by_url;
+
+/// 去重阶段
+pub const STAGE_DEDUP: &str = "dedup";
+/// 安全标记阶段
+pub const STAGE_SECURITY: &str = "security";
+/// 评分阶段
+pub const STAGE_SCORING: &str = "scoring";
+/// 语言过滤阶段
+pub const STAGE_LANGUAGE: &str = "language";
+/// 排序阶段
+pub const STAGE_SORT: &str = "sort";
+/// 结构化过滤阶段
+pub const STAGE_FILTER: &str = "filter";
+/// 域名多样性阶段
+pub const STAGE_DIVERSITY: &str = "diversity";
+
+/// 非严格语言过滤时，语言不符的结果分数乘以该系数
+const LANGUAGE_MISMATCH_PENALTY: f64 = 0.3;
+
+/// 自定义阶段及其插入位置（插在该内置阶段之前，None 表示链末尾）
+pub type PositionedStage = (Option<String>, Arc<dyn ResultStage>);
+
+/// 后处理上下文
+#[derive(Debug, Clone, Copy)]
+pub struct ResultContext<'a> {
+    /// 评分使用的查询（启用翻译后搜索时包含译文）
+    pub query: &'a SearchQuery,
+    /// 排序方式
+    pub sort_by: SortBy,
+}
+
+/// 结果后处理阶段
+///
+/// 阶段可以改写、删除或重排结果；只调整分数的重排器应插在 `sort` 之前，
+/// 由排序阶段统一排序
+pub trait ResultStage: Send + Sync {
+    /// 阶段名（用于定位插入位置和日志）
+    fn name(&self) -> &str;
+
+    /// 处理结果
+    fn process(&self, items: &mut Vec<SearchResultItem>, context: &ResultContext<'_>);
+}
+
+/// 按 URL 去重
+pub struct DedupStage;
+
+impl ResultStage for DedupStage {
+    fn name(&self) -> &str {
+        STAGE_DEDUP
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+        deduplicate_by_url(items);
+    }
+}
+
+/// 标记明文 HTTP、短链接和跟踪链接；`strict` 为 true 时丢弃带标记的结果
+pub struct SecurityStage {
+    config: SecurityFlagsConfig,
+    strict: bool,
+}
+
+impl SecurityStage {
+    /// 创建安全标记阶段（配置未启用时不做任何事）
+    pub fn new(config: SecurityFlagsConfig, strict: bool) -> Self {
+        Self { config, strict }
+    }
+}
+
+impl ResultStage for SecurityStage {
+    fn name(&self) -> &str {
+        STAGE_SECURITY
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+        if !self.config.enabled {
+            return;
+        }
+        for item in items.iter_mut() {
+            let flags = self.config.flags_for(&item.url);
+            item.set_security_flags(&flags);
+        }
+        if self.strict {
+            items.retain(|item| item.security_flags().is_empty());
+        }
+    }
+}
+
+/// 基于查询重新评分，并乘以来源引擎的权重系数
+pub struct ScoringStage {
+    weights: Option<ScoringWeights>,
+    engine_weights: HashMap<String, f64>,
+}
+
+impl ScoringStage {
+    /// 创建评分阶段
+    pub fn new(weights: Option<ScoringWeights>, engine_weights: HashMap<String, f64>) -> Self {
+        Self { weights, engine_weights }
+    }
+}
+
+impl ResultStage for ScoringStage {
+    fn name(&self) -> &str {
+        STAGE_SCORING
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, context: &ResultContext<'_>) {
+        score_results(items, context.query, "aggregated", self.weights.clone(), None);
+        for item in items.iter_mut() {
+            // 自带相关性分数的引擎（如内部搜索索引）保留自己的分数
+            if let Some(score) = item.metadata.get(ENGINE_SCORE_KEY).and_then(|s| s.parse::<f64>().ok()) {
+                item.score = score;
+            }
+            if let Some(weight) = item.metadata.get(SOURCE_ENGINE_KEY).and_then(|e| self.engine_weights.get(e)) {
+                item.score *= weight;
+            }
+        }
+    }
+}
+
+/// 降权或丢弃与请求语言不符的结果（无法判断语言的结果不受影响）
+pub struct LanguageFilterStage {
+    language: Option<String>,
+    strict: bool,
+}
+
+impl LanguageFilterStage {
+    /// 创建语言过滤阶段（语言为 None 时不做任何事）
+    pub fn new(language: Option<String>, strict: bool) -> Self {
+        Self { language, strict }
+    }
+}
+
+impl ResultStage for LanguageFilterStage {
+    fn name(&self) -> &str {
+        STAGE_LANGUAGE
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+        let Some(language) = &self.language else { return };
+        let mismatched = |item: &SearchResultItem| {
+            matches_language(&format!("{} {}", item.title, item.content), language) == Some(false)
+        };
+        if self.strict {
+            items.retain(|item| !mismatched(item));
+        } else {
+            for item in items.iter_mut().filter(|item| mismatched(item)) {
+                item.score *= LANGUAGE_MISMATCH_PENALTY;
+            }
+        }
+    }
+}
+
+/// 按分数降序排列，再按请求的排序方式重排
+pub struct SortStage;
+
+impl ResultStage for SortStage {
+    fn name(&self) -> &str {
+        STAGE_SORT
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, context: &ResultContext<'_>) {
+        items.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        sort_items(items, context.sort_by);
+    }
+}
+
+/// 按请求的排序方式重排结果（相关性顺序保持不变）
+pub(crate) fn sort_items(items: &mut [SearchResultItem], sort_by: SortBy) {
+    match sort_by {
+        SortBy::Relevance => {
+            // 默认顺序即为相关性顺序
+        }
+        SortBy::Time => {
+            // 严格按发布时间降序，无发布时间的结果排在最后（稳定排序保持原相对顺序）
+            items.sort_by_key(|item| std::cmp::Reverse(item.published_date));
+        }
+        SortBy::Source => {
+            items.sort_by(|a, b| a.url.cmp(&b.url));
+        }
+    }
+}
+
+/// 按结构化过滤条件筛选结果
+pub struct FilterStage(pub ResultFilter);
+
+impl ResultStage for FilterStage {
+    fn name(&self) -> &str {
+        STAGE_FILTER
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+        if !self.0.is_empty() {
+            self.0.apply(items);
+        }
+    }
+}
+
+/// 相关性排序时按域名多样性重排（见 [`diversify_by_domain`]）
+pub struct DiversityStage {
+    max_per_domain: usize,
+}
+
+impl DiversityStage {
+    /// 创建多样性阶段（上限为 0 时不做任何事）
+    pub fn new(max_per_domain: usize) -> Self {
+        Self { max_per_domain }
+    }
+}
+
+impl ResultStage for DiversityStage {
+    fn name(&self) -> &str {
+        STAGE_DIVERSITY
+    }
+
+    fn process(&self, items: &mut Vec<SearchResultItem>, context: &ResultContext<'_>) {
+        if context.sort_by == SortBy::Relevance {
+            let diversified = diversify_by_domain(std::mem::take(items), self.max_per_domain, |item| {
+                (item.url.as_str(), item.score)
+            });
+            *items = diversified;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive::ResultType;
+    use crate::search::SearchAggregator;
+    use crate::derive::SearchResult;
+
+    fn item(url: &str, engine: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: "rust".to_string(),
+            url: url.to_string(),
+            content: "rust".to_string(),
+            display_url: None,
+            site_name: None,
+            result_type: ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::from([(SOURCE_ENGINE_KEY.to_string(), engine.to_string())]),
+            score: 1.0,
+        }
+    }
+
+    /// 把内部站点的结果提到最前
+    struct BoostInternal;
+
+    impl ResultStage for BoostInternal {
+        fn name(&self) -> &str {
+            "boost_internal"
+        }
+
+        fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+            for item in items.iter_mut().filter(|item| item.url.contains("intranet")) {
+                item.score += 10.0;
+            }
+        }
+    }
+
+    /// 记录处理时看到的结果数
+    struct Tag;
+
+    impl ResultStage for Tag {
+        fn name(&self) -> &str {
+            "tag"
+        }
+
+        fn process(&self, items: &mut Vec<SearchResultItem>, _context: &ResultContext<'_>) {
+            for (position, item) in items.iter_mut().enumerate() {
+                item.metadata.insert("position".to_string(), position.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_result_stages() {
+        let result = SearchResult {
+            engine_name: "bing".to_string(),
+            total_results: Some(3),
+            elapsed_ms: 0,
+            items: vec![item("https://a.example.com/", "bing"), item("https://intranet.corp/", "bing"), item("https://a.example.com/", "bing")],
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        };
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+
+        let aggregator = SearchAggregator::default()
+            .with_result_stage(Arc::new(Tag))
+            .with_result_stage_before(STAGE_SORT, Arc::new(BoostInternal));
+        assert_eq!(
+            aggregator.result_stage_names(),
+            vec![STAGE_DEDUP, STAGE_SECURITY, STAGE_SCORING, STAGE_LANGUAGE, "boost_internal", STAGE_SORT, STAGE_FILTER, STAGE_DIVERSITY, "tag"]
+        );
+
+        let aggregated = aggregator.aggregate_with_scoring(vec![result], &query);
+        assert_eq!(aggregated.items.len(), 2);
+        assert_eq!(aggregated.items[0].url, "https://intranet.corp/");
+        assert_eq!(aggregated.items[1].metadata["position"], "1");
+    }
+
+    #[test]
+    fn test_scoring_keeps_engine_scores() {
+        let mut indexed = item("https://wiki.internal/rust", "wiki");
+        indexed.metadata.insert(ENGINE_SCORE_KEY.to_string(), "0.8".to_string());
+        let mut items = vec![item("https://a.example.com/", "bing"), indexed];
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+        let context = ResultContext { query: &query, sort_by: SortBy::Relevance };
+
+        ScoringStage::new(None, HashMap::from([("wiki".to_string(), 0.5)])).process(&mut items, &context);
+        assert_eq!(items[1].score, 0.4);
+        assert!(items[0].score < 1.0);
+    }
+
+    #[test]
+    fn test_security_stage() {
+        let mut items = vec![item("https://a.example.com/", "bing"), item("http://bit.ly/x", "bing")];
+        let query = SearchQuery { query: "rust".to_string(), ..Default::default() };
+        let context = ResultContext { query: &query, sort_by: SortBy::Relevance };
+
+        SecurityStage::new(SecurityFlagsConfig::default(), false).process(&mut items, &context);
+        assert!(items[0].security_flags().is_empty());
+        assert_eq!(items[1].security_flags(), vec![crate::derive::SecurityFlag::Insecure, crate::derive::SecurityFlag::Shortener]);
+
+        SecurityStage::new(SecurityFlagsConfig::default(), true).process(&mut items, &context);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].url, "https://a.example.com/");
+    }
+}
@@ -17,6 +17,8 @@
 //! 对搜索结果进行基本的清理和标准化
 
 use crate::derive::{SearchResultItem, SearchResult};
+use super::sanitize::{sanitize_text, SanitizeConfig};
+use crate::url_utils::{dedup_key, display_url, is_http_url};
 use std::collections::HashSet;
 
 /// 清理文本
@@ -39,34 +41,51 @@ pub fn clean_text(text: &str, max_length: usize) -> String {
     }
 }
 
-/// 标准化单个结果项
+/// 标准化单个结果项（标题和内容均转为纯文本）
 pub fn standardize_item(item: &mut SearchResultItem) {
+    standardize_item_with(item, &SanitizeConfig::default());
+}
+
+/// 按清理配置标准化单个结果项
+///
+/// 标题总是纯文本；内容在保留标签模式下为已清理的 HTML 片段，截断后再清理以补全被截断的标签
+pub fn standardize_item_with(item: &mut SearchResultItem, sanitize: &SanitizeConfig) {
     // 清理标题（最多200字符）
-    item.title = clean_text(&item.title, 200);
+    item.title = clean_text(&sanitize_text(&item.title), 200);
     
     // 清理内容（最多500字符）
-    item.content = clean_text(&item.content, 500);
+    item.content = if sanitize.keep_ui_tags {
+        let collapsed = item.content.split_whitespace().collect::<Vec<_>>().join(" ");
+        let truncated: String = collapsed.chars().take(500).collect();
+        sanitize.sanitize(&truncated)
+    } else {
+        clean_text(&sanitize_text(&item.content), 500)
+    };
     
     // 确保 URL 不为空
     if item.url.trim().is_empty() {
         item.url = "#".to_string();
     }
+
+    // 展示地址：国际化域名还原为 Unicode，路径百分号解码
+    let shown = match item.display_url.as_deref() {
+        Some(shown) => Some(display_url(shown)),
+        None => is_http_url(&item.url).then(|| display_url(&item.url)),
+    };
+    item.display_url = shown;
 }
 
-/// 简单去重（基于 URL）
+/// 简单去重（基于规范化 URL，忽略 scheme、`www.` 和末尾的 `/`）
 pub fn deduplicate_by_url(items: &mut Vec<SearchResultItem>) {
     let mut seen = HashSet::new();
-    items.retain(|item| {
-        let url_lower = item.url.to_lowercase().trim().to_string();
-        seen.insert(url_lower)
-    });
+    items.retain(|item| seen.insert(dedup_key(&item.url)));
 }
 
 /// 标准化搜索结果
-pub fn standardize_results(result: &mut SearchResult) {
+pub fn standardize_results(result: &mut SearchResult, sanitize: &SanitizeConfig) {
     // 1. 标准化每个项
     for item in &mut result.items {
-        standardize_item(item);
+        standardize_item_with(item, sanitize);
     }
     
     // 2. 去重
@@ -85,4 +104,37 @@ mod tests {
         let cleaned = clean_text(&long, 100);
         assert!(cleaned.len() <= 103); // 100 + "..."
     }
+
+    #[test]
+    fn test_standardize_item_sanitizes_html() {
+        let mut item = SearchResultItem {
+            title: "<b>Tokio</b> &amp; Rust".to_string(),
+            url: "https://tokio.rs/".to_string(),
+            content: r#"<em class="hl">async</em> runtime<script>alert(1)</script>"#.to_string(),
+            display_url: None,
+            site_name: None,
+            score: 0.0,
+            result_type: crate::derive::ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: Default::default(),
+        };
+        let mut rich = item.clone();
+
+        standardize_item(&mut item);
+        assert_eq!(item.title, "Tokio & Rust");
+        assert_eq!(item.content, "async runtime");
+
+        standardize_item_with(&mut rich, &SanitizeConfig { keep_ui_tags: true });
+        assert_eq!(rich.title, "Tokio & Rust");
+        assert_eq!(rich.content, "<em>async</em> runtime");
+        assert_eq!(item.display_url.as_deref(), Some("tokio.rs"));
+
+        let mut duplicate = item.clone();
+        duplicate.url = "http://www.TOKIO.rs".to_string();
+        let mut items = vec![item, duplicate];
+        deduplicate_by_url(&mut items);
+        assert_eq!(items.len(), 1);
+    }
 }
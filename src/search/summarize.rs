@@ -0,0 +1,461 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 结果摘要
+//!
+//! 启用后，为排名靠前的结果抓取页面正文并生成摘要，写入结果项 metadata 的 [`SUMMARY_KEY`]，
+//! 供需要比引擎摘要更完整的内容的界面使用。默认用 TextRank 抽取关键句，不调用任何外部服务；
+//! 配置为 `llm` 时交给 OpenAI 兼容接口生成，接口失败时退回抽取式摘要。
+//! 摘要按 URL 的哈希写入缓存，同一页面在缓存有效期内不会重复抓取。
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cache::CacheManager;
+use crate::derive::SearchResultItem;
+use crate::net::client::HttpTransport;
+use super::extract::{fetch_page_text, truncate_chars};
+use super::highlight::is_cjk;
+use super::llm::{ChatMessage, LlmClient, LlmConfig};
+
+/// 摘要在结果项 metadata 中的键
+pub const SUMMARY_KEY: &str = "summary";
+
+/// 摘要缓存键前缀（后接摘要方式和 URL 的 SHA-256）
+const SUMMARY_CACHE_PREFIX: &str = "summary:";
+
+/// PageRank 阻尼系数
+const DAMPING: f64 = 0.85;
+
+/// PageRank 最多迭代次数
+const MAX_ITERATIONS: usize = 50;
+
+/// 参与排序的句子最少字符数
+const MIN_SENTENCE_CHARS: usize = 8;
+
+/// 摘要方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryMode {
+    /// TextRank 抽取关键句（默认，不调用外部服务）
+    #[default]
+    Extractive,
+    /// OpenAI 兼容接口生成
+    Llm,
+}
+
+impl SummaryMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Extractive => "extractive",
+            Self::Llm => "llm",
+        }
+    }
+}
+
+/// 结果摘要配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 摘要方式
+    #[serde(default)]
+    pub mode: SummaryMode,
+    /// 每次搜索最多为前多少条结果生成摘要
+    #[serde(default = "default_max_items")]
+    pub max_items: usize,
+    /// 同时抓取的页面数
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// 整批摘要的超时时间，超时未完成的结果不带摘要
+    #[serde(default = "default_timeout")]
+    pub timeout: Duration,
+    /// 参与摘要的页面正文最多字符数
+    #[serde(default = "default_max_page_chars")]
+    pub max_page_chars: usize,
+    /// 抽取式摘要的句子数
+    #[serde(default = "default_sentences")]
+    pub sentences: usize,
+    /// 摘要最多字符数
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    /// 摘要的缓存时间
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: Duration,
+    /// 生成式摘要使用的对话接口（`mode = "llm"` 时有效）
+    #[serde(default)]
+    pub llm: LlmConfig,
+    /// 生成式摘要最多生成的 token 数
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+}
+
+fn default_max_items() -> usize {
+    5
+}
+
+fn default_max_concurrent() -> usize {
+    3
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(8)
+}
+
+fn default_max_page_chars() -> usize {
+    20_000
+}
+
+fn default_sentences() -> usize {
+    3
+}
+
+fn default_max_chars() -> usize {
+    400
+}
+
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(7 * 24 * 3600)
+}
+
+fn default_max_tokens() -> u32 {
+    200
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: SummaryMode::default(),
+            max_items: default_max_items(),
+            max_concurrent: default_max_concurrent(),
+            timeout: default_timeout(),
+            max_page_chars: default_max_page_chars(),
+            sentences: default_sentences(),
+            max_chars: default_max_chars(),
+            cache_ttl: default_cache_ttl(),
+            llm: LlmConfig::default(),
+            max_tokens: default_max_tokens(),
+        }
+    }
+}
+
+/// 结果摘要生成器
+pub struct Summarizer {
+    config: SummaryConfig,
+    transport: Arc<dyn HttpTransport>,
+    llm: Option<LlmClient>,
+}
+
+impl Summarizer {
+    /// 创建摘要生成器（页面抓取和对话接口共用传输层）
+    pub fn new(config: SummaryConfig, transport: Arc<dyn HttpTransport>) -> Self {
+        let llm = (config.mode == SummaryMode::Llm).then(|| LlmClient::new(transport.clone(), config.llm.clone()));
+        Self { config, transport, llm }
+    }
+
+    /// 摘要配置
+    pub fn config(&self) -> &SummaryConfig {
+        &self.config
+    }
+
+    /// 为前 `max_items` 条结果并发生成摘要并写入 metadata
+    pub async fn annotate(&self, cache: Option<&CacheManager>, items: &mut [SearchResultItem]) {
+        let count = items.len().min(self.config.max_items);
+        let urls: Vec<(usize, String)> = items[..count]
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.metadata.contains_key(SUMMARY_KEY))
+            .map(|(index, item)| (index, item.url.clone()))
+            .collect();
+        if urls.is_empty() {
+            return;
+        }
+
+        let mut found = Vec::new();
+        let summaries = stream::iter(urls)
+            .map(|(index, url)| async move { (index, self.summary(cache, &url).await) })
+            .buffer_unordered(self.config.max_concurrent.max(1));
+        // 超时后保留已经完成的摘要
+        let _ = tokio::time::timeout(self.config.timeout, summaries.for_each(|(index, summary)| {
+            if let Some(summary) = summary {
+                found.push((index, summary));
+            }
+            async {}
+        }))
+        .await;
+
+        for (index, summary) in found {
+            items[index].metadata.insert(SUMMARY_KEY.to_string(), summary);
+        }
+    }
+
+    /// 生成单个页面的摘要（先查缓存）
+    ///
+    /// 抓取失败时返回 None 且不写缓存；页面没有正文时缓存空值
+    async fn summary(&self, cache: Option<&CacheManager>, url: &str) -> Option<String> {
+        let cache_key = summary_cache_key(self.config.mode, url);
+        if let Some(cached) = cache.and_then(|cache| cache.get(&cache_key).ok().flatten()) {
+            return String::from_utf8(cached).ok().filter(|s| !s.is_empty());
+        }
+
+        let text = match fetch_page_text(self.transport.as_ref(), url, self.config.max_page_chars, self.config.timeout).await {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::debug!("Failed to fetch {} for summary: {}", url, e);
+                return None;
+            }
+        };
+        let summary = match &self.llm {
+            Some(llm) if !text.trim().is_empty() => match self.llm_summary(llm, &text).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::debug!("LLM summary for {} failed, using extractive summary: {}", url, e);
+                    textrank_summary(&text, self.config.sentences, self.config.max_chars)
+                }
+            },
+            _ => textrank_summary(&text, self.config.sentences, self.config.max_chars),
+        };
+        if let Some(cache) = cache
+            && let Err(e) = cache.set(cache_key, summary.clone().into_bytes(), Some(self.config.cache_ttl))
+        {
+            tracing::warn!("Failed to cache summary: {}", e);
+        }
+        Some(summary).filter(|s| !s.is_empty())
+    }
+
+    async fn llm_summary(&self, llm: &LlmClient, text: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let messages = [
+            ChatMessage::system(format!(
+                "Summarize the web page text in at most {} sentences. Reply with the summary only, in the language of the page.",
+                self.config.sentences.max(1)
+            )),
+            ChatMessage::user(text),
+        ];
+        let completion = llm.complete(&messages, self.config.max_tokens).await?;
+        Ok(truncate_chars(completion.content.trim(), self.config.max_chars))
+    }
+}
+
+/// 根据配置创建摘要生成器（未启用时返回 None）
+pub fn create_summarizer(config: &SummaryConfig, transport: Arc<dyn HttpTransport>) -> Option<Arc<Summarizer>> {
+    config.enabled.then(|| Arc::new(Summarizer::new(config.clone(), transport)))
+}
+
+fn summary_cache_key(mode: SummaryMode, url: &str) -> String {
+    let hash: String = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}:{}", SUMMARY_CACHE_PREFIX, mode.as_str(), hash)
+}
+
+/// 按句末标点和换行切分句子（英文句点后需跟空白，中文标点直接切分）
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            sentences.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+        let boundary = match c {
+            '。' | '！' | '？' | '；' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|next| next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// 句子的词集合：拉丁文字取小写单词，中日韩文字取相邻两字
+fn sentence_terms(sentence: &str) -> HashSet<String> {
+    let mut terms = HashSet::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+    for c in sentence.chars() {
+        if is_cjk(c) {
+            if let Some(previous) = previous_cjk {
+                terms.insert([previous, c].iter().collect());
+            }
+            previous_cjk = Some(c);
+        } else {
+            previous_cjk = None;
+        }
+        if c.is_alphanumeric() && !is_cjk(c) {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            if word.chars().count() > 1 {
+                terms.insert(word.clone());
+            }
+            word.clear();
+        }
+    }
+    if word.chars().count() > 1 {
+        terms.insert(word);
+    }
+    terms
+}
+
+/// TextRank 抽取式摘要：按句子间词重叠建图做 PageRank，取得分最高的 `sentences` 句并保持原文顺序，
+/// 结果不超过 `max_chars` 个字符
+pub fn textrank_summary(text: &str, sentences: usize, max_chars: usize) -> String {
+    let candidates: Vec<String> = split_sentences(text)
+        .into_iter()
+        .filter(|s| s.chars().count() >= MIN_SENTENCE_CHARS)
+        .collect();
+    let selected: Vec<usize> = if candidates.len() <= sentences {
+        (0..candidates.len()).collect()
+    } else {
+        let scores = textrank_scores(&candidates);
+        let mut ranked: Vec<usize> = (0..candidates.len()).collect();
+        ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+        ranked.truncate(sentences);
+        ranked.sort_unstable();
+        ranked
+    };
+
+    let mut summary = String::new();
+    for index in selected {
+        let sentence = &candidates[index];
+        let ends_cjk = summary.chars().last().is_some_and(|c| is_cjk(c) || "。！？；".contains(c));
+        if !summary.is_empty() && !ends_cjk {
+            summary.push(' ');
+        }
+        summary.push_str(sentence);
+    }
+    truncate_chars(&summary, max_chars)
+}
+
+/// 计算每个句子的 TextRank 得分
+fn textrank_scores(sentences: &[String]) -> Vec<f64> {
+    let terms: Vec<HashSet<String>> = sentences.iter().map(|s| sentence_terms(s)).collect();
+    let n = sentences.len();
+    let mut weights = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let overlap = terms[i].intersection(&terms[j]).count();
+            if overlap == 0 {
+                continue;
+            }
+            let norm = ((terms[i].len() + 1) as f64).ln() + ((terms[j].len() + 1) as f64).ln();
+            let weight = overlap as f64 / norm;
+            weights[i][j] = weight;
+            weights[j][i] = weight;
+        }
+    }
+    let totals: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+
+    let mut scores = vec![1.0; n];
+    for _ in 0..MAX_ITERATIONS {
+        let next: Vec<f64> = (0..n)
+            .map(|i| {
+                let incoming: f64 = (0..n)
+                    .filter(|&j| totals[j] > 0.0)
+                    .map(|j| weights[j][i] / totals[j] * scores[j])
+                    .sum();
+                (1.0 - DAMPING) + DAMPING * incoming
+            })
+            .collect();
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < 1e-6 {
+            break;
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    fn item(url: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            display_url: None,
+            site_name: None,
+            score: 0.0,
+            result_type: Default::default(),
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_split_sentences_cjk_and_latin() {
+        let sentences = split_sentences("Rust is fast. Version 1.75 is out!\n所有权是核心概念。借用检查器很严格");
+        assert_eq!(sentences, vec!["Rust is fast.", "Version 1.75 is out!", "所有权是核心概念。", "借用检查器很严格"]);
+    }
+
+    #[test]
+    fn test_textrank_prefers_central_sentences() {
+        let text = "Rust is a systems programming language focused on memory safety. \
+            The weather was pleasant on the day of the conference. \
+            Memory safety in Rust comes from ownership and borrowing. \
+            Ownership and borrowing let Rust guarantee memory safety without a garbage collector. \
+            Lunch was served at noon.";
+        let summary = textrank_summary(text, 2, 400);
+        assert!(summary.contains("ownership and borrowing") || summary.contains("Ownership and borrowing"));
+        assert!(!summary.contains("weather") && !summary.contains("Lunch"));
+        assert_eq!(textrank_summary(text, 2, 10).chars().count(), 10);
+
+        let summary = textrank_summary("所有权是 Rust 的核心概念。借用规则保证了内存安全。所有权和借用规则一起保证内存安全。今天天气不错适合出门。", 2, 400);
+        assert!(summary.contains("借用规则") && !summary.contains("天气"));
+    }
+
+    #[tokio::test]
+    async fn test_annotate_caches_by_url() {
+        let page = "<html><body><article><p>Rust guarantees memory safety through ownership.</p>\
+            <p>The borrow checker enforces ownership rules at compile time.</p></article></body></html>";
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        let transport = Arc::new(MockTransport::new()
+            .respond(format!("page-{}", suffix), MockResponse::ok(page).header("content-type", "text/html")));
+        let cache = CacheManager::temporary();
+        let config = SummaryConfig { enabled: true, max_items: 1, ..Default::default() };
+        let summarizer = create_summarizer(&config, transport.clone()).unwrap();
+
+        let url = format!("https://example.com/page-{}", suffix);
+        let mut items = vec![item(&url), item("https://example.com/not-summarized")];
+        summarizer.annotate(Some(&cache), &mut items).await;
+        let summary = items[0].metadata.get(SUMMARY_KEY).unwrap();
+        assert!(summary.starts_with("Rust guarantees memory safety"));
+        assert!(!items[1].metadata.contains_key(SUMMARY_KEY));
+        assert_eq!(transport.requests().len(), 1);
+
+        let mut again = vec![item(&url)];
+        summarizer.annotate(Some(&cache), &mut again).await;
+        assert_eq!(again[0].metadata.get(SUMMARY_KEY), Some(summary));
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
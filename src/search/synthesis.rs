@@ -0,0 +1,319 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! 基于搜索结果的答案生成
+//!
+//! 把排名靠前的结果（标题 + 页面正文，抓取失败时用引擎摘要）编号后交给 OpenAI 兼容的
+//! 对话接口，要求模型只依据这些结果回答并用 `[n]` 标注来源，再从答案中解析出引用。
+//! 默认关闭；每个来源和整个提示词的长度、生成的 token 数都受配置限制。
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::derive::SearchResultItem;
+use crate::net::client::HttpTransport;
+use super::extract::{fetch_page_text, truncate_chars};
+use super::llm::{ChatMessage, LlmClient, LlmConfig, TokenUsage};
+
+/// 默认系统提示词
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You answer questions using only the numbered search results provided. \
+Cite the supporting results with [n] right after each claim. If the results do not contain the answer, say so. \
+Answer concisely in the language of the question.";
+
+/// 答案中的引用标记（`[1]`、`[2, 3]`）
+static CITATION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").expect("valid regex"));
+
+/// 答案生成配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynthesisConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 对话接口
+    #[serde(default)]
+    pub llm: LlmConfig,
+    /// 交给模型的最多结果数
+    #[serde(default = "default_max_sources")]
+    pub max_sources: usize,
+    /// 是否抓取结果页面提取正文（关闭时只用引擎摘要）
+    #[serde(default = "default_fetch_pages")]
+    pub fetch_pages: bool,
+    /// 抓取单个页面的超时（毫秒）
+    #[serde(default = "default_fetch_timeout_ms")]
+    pub fetch_timeout_ms: u64,
+    /// 每个来源最多的字符数
+    #[serde(default = "default_max_source_chars")]
+    pub max_source_chars: usize,
+    /// 提示词（问题和所有来源）最多的字符数，超出时截断靠后的来源
+    #[serde(default = "default_max_prompt_chars")]
+    pub max_prompt_chars: usize,
+    /// 最多生成的 token 数
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// 自定义系统提示词（默认见 [`DEFAULT_SYSTEM_PROMPT`]）
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+fn default_max_sources() -> usize {
+    5
+}
+
+fn default_fetch_pages() -> bool {
+    true
+}
+
+fn default_fetch_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_max_source_chars() -> usize {
+    2000
+}
+
+fn default_max_prompt_chars() -> usize {
+    12_000
+}
+
+fn default_max_tokens() -> u32 {
+    512
+}
+
+impl Default for SynthesisConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            llm: LlmConfig::default(),
+            max_sources: default_max_sources(),
+            fetch_pages: default_fetch_pages(),
+            fetch_timeout_ms: default_fetch_timeout_ms(),
+            max_source_chars: default_max_source_chars(),
+            max_prompt_chars: default_max_prompt_chars(),
+            max_tokens: default_max_tokens(),
+            system_prompt: None,
+        }
+    }
+}
+
+/// 交给模型的一个来源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerSource {
+    /// 编号（从 1 开始，与答案中的 `[n]` 对应）
+    pub index: usize,
+    /// 标题
+    pub title: String,
+    /// 链接
+    pub url: String,
+}
+
+/// 生成的答案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynthesizedAnswer {
+    /// 答案文本（含 `[n]` 引用标记）
+    pub answer: String,
+    /// 答案引用的来源编号（升序，忽略超出范围的编号）
+    pub citations: Vec<usize>,
+    /// 交给模型的全部来源
+    pub sources: Vec<AnswerSource>,
+    /// 接口报告的模型名
+    pub model: Option<String>,
+    /// token 用量
+    pub usage: Option<TokenUsage>,
+}
+
+impl SynthesizedAnswer {
+    /// 被引用的来源
+    pub fn cited_sources(&self) -> impl Iterator<Item = &AnswerSource> {
+        self.sources.iter().filter(|source| self.citations.contains(&source.index))
+    }
+}
+
+/// 答案生成器
+pub struct AnswerSynthesizer {
+    config: SynthesisConfig,
+    client: LlmClient,
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl AnswerSynthesizer {
+    /// 创建答案生成器（页面抓取和对话接口共用传输层）
+    pub fn new(config: SynthesisConfig, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            client: LlmClient::new(transport.clone(), config.llm.clone()),
+            config,
+            transport,
+        }
+    }
+
+    /// 生成配置
+    pub fn config(&self) -> &SynthesisConfig {
+        &self.config
+    }
+
+    /// 根据排好序的结果生成答案，最多使用 `max_sources` 条（不超过配置）
+    pub async fn synthesize(
+        &self,
+        query: &str,
+        items: &[SearchResultItem],
+        max_sources: Option<usize>,
+    ) -> Result<SynthesizedAnswer, Box<dyn Error + Send + Sync>> {
+        let limit = max_sources.unwrap_or(self.config.max_sources).min(self.config.max_sources);
+        let items = &items[..items.len().min(limit)];
+        if items.is_empty() {
+            return Err("没有可用的搜索结果".into());
+        }
+
+        let contents = self.source_contents(items).await;
+        let (prompt, sources) = self.build_prompt(query, items, &contents);
+        let system = self.config.system_prompt.as_deref().unwrap_or(DEFAULT_SYSTEM_PROMPT);
+        let messages = [ChatMessage::system(system), ChatMessage::user(prompt)];
+        let completion = self.client.complete(&messages, self.config.max_tokens).await?;
+
+        Ok(SynthesizedAnswer {
+            citations: parse_citations(&completion.content, sources.len()),
+            answer: completion.content,
+            sources,
+            model: completion.model,
+            usage: completion.usage,
+        })
+    }
+
+    /// 每条结果的正文：并发抓取页面，失败或为空时使用引擎摘要
+    async fn source_contents(&self, items: &[SearchResultItem]) -> Vec<String> {
+        if !self.config.fetch_pages {
+            return items.iter().map(|item| item.content.clone()).collect();
+        }
+        let timeout = Duration::from_millis(self.config.fetch_timeout_ms);
+        let fetches = items.iter().map(|item| async move {
+            match fetch_page_text(self.transport.as_ref(), &item.url, self.config.max_source_chars, timeout).await {
+                Ok(text) if !text.trim().is_empty() => text,
+                Ok(_) => item.content.clone(),
+                Err(e) => {
+                    tracing::debug!("Using snippet for {}: {}", item.url, e);
+                    item.content.clone()
+                }
+            }
+        });
+        futures::future::join_all(fetches).await
+    }
+
+    /// 组装用户提示词，来源按编号排列，整体不超过 `max_prompt_chars`
+    fn build_prompt(&self, query: &str, items: &[SearchResultItem], contents: &[String]) -> (String, Vec<AnswerSource>) {
+        let mut prompt = format!("Question: {}\n\nSearch results:\n", query);
+        let mut sources = Vec::new();
+        for (item, content) in items.iter().zip(contents) {
+            let index = sources.len() + 1;
+            let header = format!("\n[{}] {}\nURL: {}\n", index, item.title, item.url);
+            let remaining = self.config.max_prompt_chars.saturating_sub(prompt.chars().count() + header.chars().count());
+            if remaining == 0 {
+                break;
+            }
+            let budget = remaining.min(self.config.max_source_chars);
+            prompt.push_str(&header);
+            prompt.push_str(&truncate_chars(content.trim(), budget));
+            prompt.push('\n');
+            sources.push(AnswerSource { index, title: item.title.clone(), url: item.url.clone() });
+        }
+        (prompt, sources)
+    }
+}
+
+/// 解析答案中的 `[n]` 引用，返回 1..=`source_count` 内的编号（升序去重）
+pub fn parse_citations(answer: &str, source_count: usize) -> Vec<usize> {
+    let mut cited = BTreeSet::new();
+    for captures in CITATION.captures_iter(answer) {
+        for number in captures[1].split(',') {
+            if let Ok(index) = number.trim().parse::<usize>()
+                && (1..=source_count).contains(&index)
+            {
+                cited.insert(index);
+            }
+        }
+    }
+    cited.into_iter().collect()
+}
+
+/// 根据配置创建答案生成器（未启用时返回 None）
+pub fn create_synthesizer(config: &SynthesisConfig, transport: Arc<dyn HttpTransport>) -> Option<Arc<AnswerSynthesizer>> {
+    config.enabled.then(|| Arc::new(AnswerSynthesizer::new(config.clone(), transport)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derive::ResultType;
+    use crate::net::client::{MockResponse, MockTransport};
+    use std::collections::HashMap;
+
+    fn item(title: &str, url: &str, content: &str) -> SearchResultItem {
+        SearchResultItem {
+            title: title.to_string(),
+            url: url.to_string(),
+            content: content.to_string(),
+            display_url: None,
+            site_name: None,
+            score: 1.0,
+            result_type: ResultType::Web,
+            thumbnail: None,
+            published_date: None,
+            template: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_citations() {
+        assert_eq!(parse_citations("Rust is fast [2]. It is safe [1, 3][9].", 3), vec![1, 2, 3]);
+        assert!(parse_citations("no citations", 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_synthesize_with_page_content_and_limits() {
+        let page = "<html><body><article><p>Rust guarantees memory safety without a garbage collector.</p></article></body></html>";
+        let completion = r#"{"choices": [{"message": {"role": "assistant", "content": "Rust is memory safe [1]."}}]}"#;
+        let transport = Arc::new(MockTransport::new()
+            .respond("/v1/chat/completions", MockResponse::ok(completion))
+            .respond("rust-lang.org", MockResponse::ok(page).header("content-type", "text/html")));
+        let config = SynthesisConfig { enabled: true, max_sources: 2, max_source_chars: 40, ..SynthesisConfig::default() };
+        let synthesizer = create_synthesizer(&config, transport.clone()).unwrap();
+
+        let items = vec![
+            item("Rust", "https://www.rust-lang.org/", "snippet one"),
+            item("Unreachable", "https://unreachable.example/", "fallback snippet"),
+            item("Third", "https://third.example/", "not used"),
+        ];
+        let answer = synthesizer.synthesize("is rust safe?", &items, Some(5)).await.unwrap();
+        assert_eq!(answer.answer, "Rust is memory safe [1].");
+        assert_eq!(answer.citations, vec![1]);
+        assert_eq!(answer.sources.len(), 2);
+        assert_eq!(answer.cited_sources().next().unwrap().url, "https://www.rust-lang.org/");
+
+        let request = transport.requests().into_iter().find(|r| r.method == "POST").unwrap();
+        let sent: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let prompt = sent["messages"][1]["content"].as_str().unwrap();
+        assert!(prompt.contains("[1] Rust") && prompt.contains("Rust guarantees memory safety without "));
+        assert!(!prompt.contains("garbage collector"));
+        assert!(prompt.contains("fallback snippet") && !prompt.contains("not used"));
+        assert_eq!(sent["max_tokens"], 512);
+
+        assert!(synthesizer.synthesize("q", &[], None).await.is_err());
+    }
+}
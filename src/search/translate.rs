@@ -0,0 +1,290 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 查询翻译（翻译后搜索）
+//!
+//! 启用后，英文查询会额外翻译成中文发给中文引擎（百度、搜狗等），
+//! 中文查询会翻译成英文发给英文引擎，结果与原查询的结果合并。
+//! 默认关闭；翻译服务通过 [`Translator`] trait 接入。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::Arc;
+
+use crate::net::client::HttpClient;
+
+/// 翻译结果在结果项 metadata 中的键
+pub const TRANSLATED_QUERY_KEY: &str = "translated_query";
+
+/// 翻译服务提供方
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranslationProvider {
+    /// LibreTranslate 兼容接口（`POST {endpoint}`，返回 `translatedText`）
+    LibreTranslate {
+        /// 接口地址，如 `https://libretranslate.com/translate`
+        endpoint: String,
+        /// API 密钥（可选）
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+}
+
+impl Default for TranslationProvider {
+    fn default() -> Self {
+        Self::LibreTranslate {
+            endpoint: "http://127.0.0.1:5000/translate".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+/// 翻译配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 翻译服务提供方
+    #[serde(default)]
+    pub provider: TranslationProvider,
+    /// 接收中文查询的引擎
+    #[serde(default = "default_chinese_engines")]
+    pub chinese_engines: Vec<String>,
+    /// 接收英文查询的引擎
+    #[serde(default = "default_english_engines")]
+    pub english_engines: Vec<String>,
+}
+
+fn default_chinese_engines() -> Vec<String> {
+    ["baidu", "sogou", "so", "bilibili", "sogou_videos", "sogou_wechat"]
+        .iter()
+        .map(|e| e.to_string())
+        .collect()
+}
+
+fn default_english_engines() -> Vec<String> {
+    ["bing", "yandex"].iter().map(|e| e.to_string()).collect()
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TranslationProvider::default(),
+            chinese_engines: default_chinese_engines(),
+            english_engines: default_english_engines(),
+        }
+    }
+}
+
+/// 查询语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLanguage {
+    /// 中文
+    Chinese,
+    /// 英文
+    English,
+}
+
+impl QueryLanguage {
+    /// 语言代码
+    pub fn code(self) -> &'static str {
+        match self {
+            QueryLanguage::Chinese => "zh",
+            QueryLanguage::English => "en",
+        }
+    }
+
+    /// 翻译的目标语言
+    pub fn opposite(self) -> Self {
+        match self {
+            QueryLanguage::Chinese => QueryLanguage::English,
+            QueryLanguage::English => QueryLanguage::Chinese,
+        }
+    }
+}
+
+/// 检测查询语言
+///
+/// 含汉字即视为中文；只含 ASCII 且至少有一个字母视为英文；其他情况返回 None
+pub fn detect_query_language(text: &str) -> Option<QueryLanguage> {
+    let is_cjk = |c: char| matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}');
+    if text.chars().any(is_cjk) {
+        Some(QueryLanguage::Chinese)
+    } else if text.is_ascii() && text.chars().any(|c| c.is_ascii_alphabetic()) {
+        Some(QueryLanguage::English)
+    } else {
+        None
+    }
+}
+
+/// 翻译服务
+#[async_trait]
+pub trait Translator: Send + Sync {
+    /// 将文本从 `from` 语言翻译为 `to` 语言
+    async fn translate(
+        &self,
+        text: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<String, Box<dyn Error + Send + Sync>>;
+}
+
+/// LibreTranslate 兼容的翻译服务
+pub struct LibreTranslator {
+    client: Arc<HttpClient>,
+    endpoint: String,
+    api_key: Option<String>,
+}
+
+impl LibreTranslator {
+    /// 创建翻译服务
+    pub fn new(client: Arc<HttpClient>, endpoint: impl Into<String>, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            endpoint: endpoint.into(),
+            api_key,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LibreTranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+#[async_trait]
+impl Translator for LibreTranslator {
+    async fn translate(
+        &self,
+        text: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let request = LibreTranslateRequest {
+            q: text,
+            source: from,
+            target: to,
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+        let response = self
+            .client
+            .post_json(&self.endpoint, &request, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("翻译服务返回 HTTP {}", response.status()).into());
+        }
+        let body: LibreTranslateResponse = response.json().await?;
+        Ok(body.translated_text)
+    }
+}
+
+/// 根据配置创建翻译服务（未启用时返回 None）
+pub fn create_translator(config: &TranslationConfig, client: Arc<HttpClient>) -> Option<Arc<dyn Translator>> {
+    if !config.enabled {
+        return None;
+    }
+    match &config.provider {
+        TranslationProvider::LibreTranslate { endpoint, api_key } => {
+            Some(Arc::new(LibreTranslator::new(client, endpoint.clone(), api_key.clone())))
+        }
+    }
+}
+
+/// 翻译计划：翻译后的查询及接收它的引擎
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationPlan {
+    /// 原查询语言
+    pub source: QueryLanguage,
+    /// 目标语言
+    pub target: QueryLanguage,
+    /// 接收翻译后查询的引擎（已与本次搜索的引擎取交集）
+    pub engines: Vec<String>,
+}
+
+impl TranslationConfig {
+    /// 为查询生成翻译计划
+    ///
+    /// 无法识别查询语言或本次搜索中没有目标语言的引擎时返回 None
+    pub fn plan(&self, query: &str, engines: &[String]) -> Option<TranslationPlan> {
+        let source = detect_query_language(query)?;
+        let target = source.opposite();
+        let target_engines = match target {
+            QueryLanguage::Chinese => &self.chinese_engines,
+            QueryLanguage::English => &self.english_engines,
+        };
+        let engines: Vec<String> = engines
+            .iter()
+            .filter(|e| target_engines.contains(e))
+            .cloned()
+            .collect();
+        if engines.is_empty() {
+            return None;
+        }
+        Some(TranslationPlan { source, target, engines })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_query_language() {
+        assert_eq!(detect_query_language("rust async runtime"), Some(QueryLanguage::English));
+        assert_eq!(detect_query_language("rust 异步运行时"), Some(QueryLanguage::Chinese));
+        assert_eq!(detect_query_language("12345"), None);
+        assert_eq!(detect_query_language("Привет"), None);
+    }
+
+    #[test]
+    fn test_translation_plan() {
+        let config = TranslationConfig::default();
+        let engines: Vec<String> = ["bing", "baidu", "sogou"].iter().map(|e| e.to_string()).collect();
+
+        let plan = config.plan("machine learning", &engines).unwrap();
+        assert_eq!(plan.target, QueryLanguage::Chinese);
+        assert_eq!(plan.engines, vec!["baidu", "sogou"]);
+
+        let plan = config.plan("机器学习", &engines).unwrap();
+        assert_eq!(plan.target, QueryLanguage::English);
+        assert_eq!(plan.engines, vec!["bing"]);
+
+        // 没有目标语言的引擎时不翻译
+        assert!(config.plan("机器学习", &["baidu".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_translation_config_disabled_by_default() {
+        let config = TranslationConfig::default();
+        assert!(!config.enabled);
+        let client = Arc::new(HttpClient::new(Default::default()).unwrap());
+        assert!(create_translator(&config, client).is_none());
+    }
+}
@@ -16,9 +16,31 @@
 //!
 //! 定义搜索模块使用的核心类型和数据结构
 
-use crate::derive::{SearchQuery, SearchResult};
+use crate::derive::{SearchQuery, SearchResult, SearchResultItem};
+use super::scoring::ScoringWeights;
+use super::engines::{JsonApiConfig, LocalFilesConfig, NominatimConfig, SearchIndexConfig, XPathConfig};
+#[cfg(feature = "torrent")]
+use super::engines::TorznabConfig;
+#[cfg(feature = "semantic")]
+use super::semantic::SemanticConfig;
+use super::translate::TranslationConfig;
+use super::answers::AnswersConfig;
+use super::archive::ArchiveConfig;
+use super::anomaly::AnomalyConfig;
+use super::slowlog::SlowQueryConfig;
+use super::latency::LatencyConfig;
+use super::summarize::SummaryConfig;
+use super::synthesis::SynthesisConfig;
+use super::filter::ResultFilter;
+use super::pipeline::PipelineConfig;
+use super::sanitize::SanitizeConfig;
+use super::security::SecurityFlagsConfig;
+use crate::net::types::NetworkConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
 /// 搜索请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +57,46 @@ pub struct SearchRequest {
     pub force: bool,
     /// 缓存刷新时间线（秒），超过此时间强制刷新
     pub cache_timeline: Option<u64>,
+    /// 提前返回条件（为 None 时等待所有引擎）
+    #[serde(default)]
+    pub early_response: Option<EarlyResponse>,
+    /// 结果过滤条件（聚合评分后应用）
+    #[serde(default)]
+    pub filter: ResultFilter,
+    /// 本次请求的引擎权重系数（覆盖配置中同名引擎的默认权重）
+    #[serde(default)]
+    pub engine_weights: HashMap<String, f64>,
+    /// 本次请求每个域名的结果上限（覆盖配置，0 表示不做多样性重排）
+    #[serde(default)]
+    pub max_per_domain: Option<usize>,
+    /// 丢弃（而不是降权）与 `query.language` 不符的结果
+    #[serde(default)]
+    pub lang_strict: bool,
+    /// 丢弃（而不是仅标记）明文 HTTP、短链接和跟踪链接结果
+    #[serde(default)]
+    pub security_strict: bool,
+    /// 取消令牌：取消后中止进行中的引擎请求，搜索返回错误
+    #[serde(skip)]
+    pub cancellation: Option<CancellationToken>,
+}
+
+/// 提前返回条件
+///
+/// 满足任一条件即返回已完成引擎的结果，其余引擎在后台继续执行，
+/// 结果写入结果缓存供后续查询使用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EarlyResponse {
+    /// 返回非空结果的引擎数达到该值时返回
+    pub min_engines: Option<usize>,
+    /// 软截止时间，到达时返回已完成的结果
+    pub max_wait: Option<Duration>,
+}
+
+impl EarlyResponse {
+    /// 是否设置了任一条件
+    pub fn is_active(&self) -> bool {
+        self.min_engines.is_some() || self.max_wait.is_some()
+    }
 }
 
 impl Default for SearchRequest {
@@ -46,6 +108,13 @@ impl Default for SearchRequest {
             max_results: Some(100),
             force: false,
             cache_timeline: Some(3600), // 默认1小时刷新
+            early_response: None,
+            filter: ResultFilter::default(),
+            engine_weights: HashMap::new(),
+            max_per_domain: None,
+            lang_strict: false,
+            security_strict: false,
+            cancellation: None,
         }
     }
 }
@@ -65,6 +134,105 @@ pub struct SearchResponse {
     pub query: SearchQuery,
     /// 是否从缓存获取
     pub cached: bool,
+    /// 翻译后的查询（启用翻译后搜索且执行了翻译时存在）
+    #[serde(default)]
+    pub translated_query: Option<String>,
+    /// 拼写提示（纠正后的完整查询）
+    #[serde(default)]
+    pub spell_hints: Vec<String>,
+    /// 各引擎的执行结果和耗时（提前返回时不含仍在后台执行的引擎）
+    #[serde(default)]
+    pub engine_timings: Vec<EngineTiming>,
+    /// 选中引擎的执行概况（成功、失败、被跳过的数量）
+    #[serde(default)]
+    pub engine_counts: EngineCounts,
+}
+
+/// 一次搜索中选中引擎的执行概况
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineCounts {
+    /// 选中的引擎数
+    pub selected: usize,
+    /// 成功返回的引擎数（含零结果）
+    pub succeeded: usize,
+    /// 返回错误或超时的引擎数
+    pub failed: usize,
+    /// 因熔断或零结果退避被临时禁用而跳过的引擎数
+    pub disabled: usize,
+    /// 无法创建而跳过的引擎数（缺少配置等）
+    pub skipped: usize,
+    /// 提前返回时仍在后台执行的引擎数
+    pub pending: usize,
+}
+
+impl EngineCounts {
+    /// 累加另一次搜索（例如翻译后搜索）的概况
+    pub fn merge(&mut self, other: &EngineCounts) {
+        self.selected += other.selected;
+        self.succeeded += other.succeeded;
+        self.failed += other.failed;
+        self.disabled += other.disabled;
+        self.skipped += other.skipped;
+        self.pending += other.pending;
+    }
+
+    /// 搜索完整程度：所有应答的引擎都成功为 `complete`；没有引擎成功，或成功的不到应答
+    /// 引擎（选中数减去仍在后台执行的）的一半为 `degraded`；其余为 `partial`
+    pub fn status(&self) -> SearchStatus {
+        let expected = self.selected.saturating_sub(self.pending);
+        if self.succeeded >= expected {
+            SearchStatus::Complete
+        } else if self.succeeded == 0 || self.succeeded * 2 < expected {
+            SearchStatus::Degraded
+        } else {
+            SearchStatus::Partial
+        }
+    }
+}
+
+/// 搜索完整程度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStatus {
+    /// 所有选中的引擎都成功返回
+    #[default]
+    Complete,
+    /// 部分引擎失败或被跳过，但至少一半成功
+    Partial,
+    /// 没有引擎成功，或成功的不到一半，结果可能明显不全
+    Degraded,
+}
+
+/// 引擎执行结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineTimingStatus {
+    /// 返回了结果（可能为零结果）
+    Success,
+    /// 引擎返回错误
+    Error,
+    /// 超时
+    Timeout,
+}
+
+/// 单个引擎在一次搜索中的耗时
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineTiming {
+    /// 引擎名称
+    pub engine: String,
+    /// 执行结果
+    pub status: EngineTimingStatus,
+    /// 耗时（毫秒，不含等待并发名额的时间）
+    pub elapsed_ms: u64,
+    /// 结果数
+    pub items: usize,
+}
+
+impl SearchResponse {
+    /// 按顺序遍历所有引擎的结果项
+    pub fn items(&self) -> impl Iterator<Item = &SearchResultItem> {
+        self.results.iter().flat_map(|result| result.items.iter())
+    }
 }
 
 /// 搜索配置
@@ -74,8 +242,144 @@ pub struct SearchConfig {
     pub default_timeout: Duration,
     /// 启用缓存
     pub enable_cache: bool,
-    /// 最大并发引擎数
+    /// 单个请求的最大并发引擎请求数（0 表示不限制）
     pub max_concurrent_engines: usize,
+    /// 所有请求合计的最大并发引擎请求数（0 表示不限制），超出时排队等待
+    #[serde(default = "default_max_concurrent_fetches")]
+    pub max_concurrent_fetches: usize,
+    /// 时效性评分半衰期（小时）
+    pub recency_half_life_hours: f64,
+    /// 翻译后搜索配置（默认关闭）
+    #[serde(default)]
+    pub translation: TranslationConfig,
+    /// 引擎使用的网络配置（包括 HTTP 传输后端）
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// 按引擎名配置的 API 密钥（如 `unsplash` 的 Access Key），配置后该引擎改用官方 API
+    #[serde(default)]
+    pub engine_api_keys: HashMap<String, String>,
+    /// 本地文件引擎配置（未配置目录时该引擎不可用）
+    #[serde(default)]
+    pub local_files: LocalFilesConfig,
+    /// Nominatim 地点搜索配置（未配置联系邮箱时该引擎不可用）
+    #[serde(default)]
+    pub nominatim: NominatimConfig,
+    /// 声明式 JSON API 引擎（按 `name` 在请求中选择）
+    #[serde(default)]
+    pub json_engines: Vec<JsonApiConfig>,
+    /// 声明式 HTML 抓取引擎（按 `name` 在请求中选择）
+    #[serde(default)]
+    pub xpath_engines: Vec<XPathConfig>,
+    /// 内部搜索索引引擎（Meilisearch / Elasticsearch，按 `name` 在请求中选择）
+    #[serde(default)]
+    pub index_engines: Vec<SearchIndexConfig>,
+    /// Torznab 引擎配置（需启用 `torrent` feature，未配置接口地址时该引擎不可用）
+    #[cfg(feature = "torrent")]
+    #[serde(default)]
+    pub torznab: TorznabConfig,
+    /// 即时答案（汇率换算、股票报价）
+    #[serde(default)]
+    pub answers: AnswersConfig,
+    /// Wayback Machine 快照链接（默认关闭，启用后每次搜索会额外请求 archive.org）
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    /// 结果摘要（默认关闭，启用后每次搜索会抓取靠前结果的页面）
+    #[serde(default)]
+    pub summary: SummaryConfig,
+    /// 引擎响应异常检测（结果数相对基线骤降时告警）
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
+    /// 慢查询日志（总耗时超过阈值的搜索及各引擎耗时）
+    #[serde(default)]
+    pub slow_queries: SlowQueryConfig,
+    /// 引擎延迟统计与自适应超时（按引擎的 p95 设置超时）
+    #[serde(default)]
+    pub latency: LatencyConfig,
+    /// 基于搜索结果的答案生成（`POST /api/answer`，默认关闭）
+    #[serde(default)]
+    pub synthesis: SynthesisConfig,
+    /// 语义重排（需启用 `semantic` feature，默认关闭）
+    #[cfg(feature = "semantic")]
+    #[serde(default)]
+    pub semantic: SemanticConfig,
+    /// 默认引擎权重系数（引擎名 -> 分数乘数，未列出的引擎为 1.0）
+    #[serde(default)]
+    pub engine_weights: HashMap<String, f64>,
+    /// 按相关性排序时每个域名最多排在前面的结果数（0 表示不做多样性重排）
+    #[serde(default = "default_max_per_domain")]
+    pub max_per_domain: usize,
+    /// 查询预处理流水线（阶段顺序、bang 别名、安全搜索下限、拼写纠正）
+    #[serde(default)]
+    pub pipeline: PipelineConfig,
+    /// 结果标题和摘要的 HTML 清理
+    #[serde(default)]
+    pub sanitize: SanitizeConfig,
+    /// 结果安全标记（明文 HTTP、短链接、跟踪链接）
+    #[serde(default)]
+    pub security_flags: SecurityFlagsConfig,
+    /// Python 引擎脚本目录（如 `engines.d`，需启用 `python` feature），启动时加载其中的 `*.py`
+    #[serde(default)]
+    pub engines_dir: Option<PathBuf>,
+    /// 从 SearXNG 配置导入引擎（需启用 `python` feature）
+    #[serde(default)]
+    pub searxng: Option<SearxngImportConfig>,
+}
+
+/// SearXNG 引擎导入配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearxngImportConfig {
+    /// SearXNG 的 `settings.yml`
+    pub settings: PathBuf,
+    /// SearXNG 引擎模块目录（如 `searx/engines`）
+    pub modules_dir: PathBuf,
+}
+
+impl SearchConfig {
+    /// 合并配置的默认引擎权重和请求的引擎权重（请求中的同名引擎优先）
+    pub fn engine_weights_for(&self, request: &SearchRequest) -> HashMap<String, f64> {
+        let mut weights = self.engine_weights.clone();
+        weights.extend(request.engine_weights.iter().map(|(engine, weight)| (engine.clone(), *weight)));
+        weights
+    }
+
+    /// 是否为配置中声明的引擎（JSON API、HTML 抓取或搜索索引引擎）
+    pub fn is_declared_engine(&self, name: &str) -> bool {
+        self.json_engines.iter().any(|engine| engine.name == name)
+            || self.xpath_engines.iter().any(|engine| engine.name == name)
+            || self.index_engines.iter().any(|engine| engine.name == name)
+    }
+
+    /// 声明式引擎的快捷方式（快捷方式 -> 引擎名）
+    pub fn declared_shortcuts(&self) -> Vec<(String, String)> {
+        let json = self.json_engines.iter().map(|engine| (&engine.shortcut, &engine.name));
+        let xpath = self.xpath_engines.iter().map(|engine| (&engine.shortcut, &engine.name));
+        let index = self.index_engines.iter().map(|engine| (&engine.shortcut, &engine.name));
+        json.chain(xpath)
+            .chain(index)
+            .filter_map(|(shortcut, name)| Some((shortcut.clone()?, name.clone())))
+            .collect()
+    }
+
+    /// 本次请求使用的域名结果上限（请求未指定时使用配置）
+    pub fn max_per_domain_for(&self, request: &SearchRequest) -> usize {
+        request.max_per_domain.unwrap_or(self.max_per_domain)
+    }
+
+    /// 根据配置生成评分权重
+    pub fn scoring_weights(&self) -> ScoringWeights {
+        ScoringWeights {
+            recency_half_life_hours: self.recency_half_life_hours,
+            ..ScoringWeights::default()
+        }
+    }
+}
+
+fn default_max_concurrent_fetches() -> usize {
+    64
+}
+
+fn default_max_per_domain() -> usize {
+    3
 }
 
 impl Default for SearchConfig {
@@ -84,6 +388,34 @@ impl Default for SearchConfig {
             default_timeout: Duration::from_secs(60),  // 增加到60秒
             enable_cache: true,
             max_concurrent_engines: 20,          // 拉满并发数
+            max_concurrent_fetches: default_max_concurrent_fetches(),
+            recency_half_life_hours: 72.0,       // 3天半衰期
+            translation: TranslationConfig::default(),
+            network: NetworkConfig::default(),
+            engine_api_keys: HashMap::new(),
+            local_files: LocalFilesConfig::default(),
+            nominatim: NominatimConfig::default(),
+            json_engines: Vec::new(),
+            xpath_engines: Vec::new(),
+            index_engines: Vec::new(),
+            #[cfg(feature = "torrent")]
+            torznab: TorznabConfig::default(),
+            answers: AnswersConfig::default(),
+            archive: ArchiveConfig::default(),
+            summary: SummaryConfig::default(),
+            anomaly: AnomalyConfig::default(),
+            slow_queries: SlowQueryConfig::default(),
+            latency: LatencyConfig::default(),
+            synthesis: SynthesisConfig::default(),
+            #[cfg(feature = "semantic")]
+            semantic: SemanticConfig::default(),
+            engine_weights: HashMap::new(),
+            max_per_domain: default_max_per_domain(),
+            pipeline: PipelineConfig::default(),
+            sanitize: SanitizeConfig::default(),
+            security_flags: SecurityFlagsConfig::default(),
+            engines_dir: None,
+            searxng: None,
         }
     }
 }
@@ -115,7 +447,33 @@ mod tests {
             query_time_ms: 100,
             query: SearchQuery::default(),
             cached: false,
+            translated_query: None,
+            spell_hints: Vec::new(),
+            engine_timings: Vec::new(),
+            engine_counts: EngineCounts::default(),
         };
         assert_eq!(response.engines_used.len(), 1);
     }
+
+    #[test]
+    fn test_engine_counts_status() {
+        let counts = |selected, succeeded, pending| EngineCounts {
+            selected,
+            succeeded,
+            failed: selected - succeeded - pending,
+            pending,
+            ..EngineCounts::default()
+        };
+        assert_eq!(counts(4, 4, 0).status(), SearchStatus::Complete);
+        // 提前返回时仍在后台执行的引擎不算失败
+        assert_eq!(counts(4, 2, 2).status(), SearchStatus::Complete);
+        assert_eq!(counts(4, 2, 0).status(), SearchStatus::Partial);
+        assert_eq!(counts(5, 2, 0).status(), SearchStatus::Degraded);
+        assert_eq!(counts(1, 0, 0).status(), SearchStatus::Degraded);
+
+        let mut merged = counts(4, 4, 0);
+        merged.merge(&EngineCounts { selected: 2, disabled: 2, ..EngineCounts::default() });
+        assert_eq!(merged.selected, 6);
+        assert_eq!(merged.status(), SearchStatus::Partial);
+    }
 }
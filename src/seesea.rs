@@ -0,0 +1,315 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 库入口
+//!
+//! [`SeeSea`] 把搜索接口、缓存和引擎选择封装在一起，在其他 Rust 程序中嵌入
+//! 元搜索只需要几行代码：
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! use seesea_core::SeeSea;
+//!
+//! let seesea = SeeSea::builder()
+//!     .engines(["bing", "yandex"])
+//!     .cache("./data/cache.db")
+//!     .build()?;
+//!
+//! let response = seesea.search("rust async").await?;
+//! for item in response.items() {
+//!     println!("{} - {}", item.title, item.url);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 需要更细的控制时，可以通过 [`SeeSea::interface`] 使用底层的 [`SearchInterface`]。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::{CacheImplConfig, CacheInterface};
+use crate::net::client::HttpTransport;
+use crate::net::types::NetworkConfig;
+use crate::search::{SearchConfig, SearchInterface, SearchRequest, SearchResponse, Translator};
+
+/// 构建和搜索时的错误
+pub type SeeSeaError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 缓存来源
+enum CacheChoice {
+    /// 默认路径（`./data/cache.db`），打开失败时不使用缓存
+    Default,
+    /// 指定的数据库路径，打开失败时构建失败
+    Path(PathBuf),
+    /// 已打开的缓存接口
+    Shared(Arc<CacheInterface>),
+    /// 不使用缓存
+    Disabled,
+}
+
+/// 嵌入式元搜索引擎
+///
+/// 克隆开销很小，克隆出的实例共享引擎、连接池和缓存
+#[derive(Clone)]
+pub struct SeeSea {
+    interface: Arc<SearchInterface>,
+    engines: Vec<String>,
+    max_results: Option<usize>,
+}
+
+impl SeeSea {
+    /// 使用默认配置创建（默认引擎、默认缓存路径）
+    pub fn new() -> Result<Self, SeeSeaError> {
+        Self::builder().build()
+    }
+
+    /// 创建构建器
+    pub fn builder() -> SeeSeaBuilder {
+        SeeSeaBuilder::default()
+    }
+
+    /// 使用配置的引擎搜索
+    pub async fn search(&self, query: &str) -> Result<SearchResponse, SeeSeaError> {
+        let mut request = SearchRequest::default();
+        request.query.query = query.to_string();
+        self.search_request(request).await
+    }
+
+    /// 执行完整的搜索请求
+    ///
+    /// 请求未指定引擎或最大结果数时使用构建器中的设置
+    pub async fn search_request(&self, mut request: SearchRequest) -> Result<SearchResponse, SeeSeaError> {
+        if request.engines.is_empty() {
+            request.engines = self.engines.clone();
+        }
+        if request.max_results.is_none() {
+            request.max_results = self.max_results;
+        }
+        self.interface.search(&request).await
+    }
+
+    /// 默认使用的引擎（为空表示使用内置的全局引擎列表）
+    pub fn engines(&self) -> &[String] {
+        &self.engines
+    }
+
+    /// 可用的内置引擎
+    pub fn available_engines(&self) -> Vec<String> {
+        self.interface.list_engines()
+    }
+
+    /// 共享的缓存接口（未启用缓存时为 None）
+    pub fn cache(&self) -> Option<&Arc<CacheInterface>> {
+        self.interface.cache()
+    }
+
+    /// 底层搜索接口
+    pub fn interface(&self) -> &Arc<SearchInterface> {
+        &self.interface
+    }
+}
+
+/// [`SeeSea`] 构建器
+pub struct SeeSeaBuilder {
+    config: SearchConfig,
+    engines: Vec<String>,
+    cache: CacheChoice,
+    max_results: Option<usize>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    translator: Option<Arc<dyn Translator>>,
+}
+
+impl Default for SeeSeaBuilder {
+    fn default() -> Self {
+        Self {
+            config: SearchConfig::default(),
+            engines: Vec::new(),
+            cache: CacheChoice::Default,
+            max_results: None,
+            transport: None,
+            translator: None,
+        }
+    }
+}
+
+impl SeeSeaBuilder {
+    /// 默认使用的引擎（如 `["bing", "yandex"]`），构建时检查引擎是否存在
+    pub fn engines<I, S>(mut self, engines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.engines = engines.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 缓存数据库路径
+    ///
+    /// 缓存数据库在进程内只打开一次，进程中已经打开了其他路径的数据库时构建失败
+    pub fn cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache = CacheChoice::Path(path.into());
+        self
+    }
+
+    /// 使用已打开的缓存接口（例如与 API 服务共用）
+    pub fn shared_cache(mut self, cache: Arc<CacheInterface>) -> Self {
+        self.cache = CacheChoice::Shared(cache);
+        self
+    }
+
+    /// 不使用缓存（全文搜索和本地文件引擎不可用）
+    pub fn without_cache(mut self) -> Self {
+        self.cache = CacheChoice::Disabled;
+        self
+    }
+
+    /// 单个引擎的超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.default_timeout = timeout;
+        self
+    }
+
+    /// 每次搜索返回的最大结果数
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// 引擎使用的网络配置（代理、传输后端等）
+    pub fn network(mut self, network: NetworkConfig) -> Self {
+        self.config.network = network;
+        self
+    }
+
+    /// 完整的搜索配置（覆盖之前的 `timeout`、`network` 设置）
+    pub fn config(mut self, config: SearchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 所有引擎使用自定义传输层（例如测试中的 `MockTransport`）
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// 使用翻译服务（同时启用翻译后搜索）
+    pub fn translator(mut self, translator: Arc<dyn Translator>) -> Self {
+        self.translator = Some(translator);
+        self
+    }
+
+    /// 构建搜索引擎
+    ///
+    /// # 错误
+    ///
+    /// 指定的缓存路径无法打开或进程中已打开其他路径的缓存、HTTP 客户端创建失败或引擎不存在时返回错误
+    pub fn build(self) -> Result<SeeSea, SeeSeaError> {
+        let mut interface = match self.cache {
+            CacheChoice::Default => SearchInterface::new(self.config)?,
+            CacheChoice::Path(path) => {
+                let cache = CacheInterface::new(CacheImplConfig {
+                    db_path: path.to_string_lossy().into_owned(),
+                    ..Default::default()
+                })
+                .map_err(|e| format!("Failed to open cache at {}: {}", path.display(), e))?;
+                let opened = Path::new(cache.manager().db_path());
+                if !same_path(opened, &path) {
+                    return Err(format!(
+                        "Cache is already open at {}, cannot open {} in the same process",
+                        opened.display(),
+                        path.display()
+                    )
+                    .into());
+                }
+                SearchInterface::new_with_cache(self.config, Some(Arc::new(cache)))?
+            }
+            CacheChoice::Shared(cache) => SearchInterface::new_with_cache(self.config, Some(cache))?,
+            CacheChoice::Disabled => SearchInterface::new_with_cache(self.config, None)?,
+        };
+        if let Some(transport) = self.transport {
+            interface = interface.with_transport(transport);
+        }
+        if let Some(translator) = self.translator {
+            interface = interface.with_translator(translator);
+        }
+
+        for engine in &self.engines {
+            interface.check_engine(engine)?;
+        }
+
+        Ok(SeeSea {
+            interface: Arc::new(interface),
+            engines: self.engines,
+            max_results: self.max_results,
+        })
+    }
+}
+
+/// 两个路径是否指向同一位置（都存在时比较规范化后的路径）
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::client::{MockResponse, MockTransport};
+
+    #[tokio::test]
+    async fn test_builder_searches_configured_engines() {
+        let transport = Arc::new(
+            MockTransport::new().respond("www.bing.com/search", MockResponse::ok(
+                r#"<ol id="b_results"><li class="b_algo"><h2><a href="https://example.com/">Example</a></h2><p>An example page</p></li></ol>"#,
+            )),
+        );
+        let seesea = SeeSea::builder()
+            .engines(["bing"])
+            .without_cache()
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        assert!(seesea.cache().is_none());
+        let response = seesea.search("example").await.unwrap();
+        assert_eq!(response.engines_used, vec!["bing".to_string()]);
+        assert_eq!(response.total_count, 1);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_builder_rejects_unknown_engine() {
+        let error = SeeSea::builder().engines(["no_such_engine"]).without_cache().build().err().unwrap();
+        assert!(error.to_string().contains("no_such_engine"));
+    }
+
+    #[test]
+    fn test_builder_rejects_cache_path_of_other_database() {
+        use crate::cache::CacheManager;
+
+        // 进程中已打开默认路径的缓存，指定其他路径时不能静默沿用
+        let manager = CacheManager::instance(CacheImplConfig::default()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let error = SeeSea::builder().cache(dir.path().join("cache.db")).build().err().unwrap();
+        assert!(error.to_string().contains("already open"));
+
+        let seesea = SeeSea::builder().cache(manager.db_path()).build().unwrap();
+        assert!(seesea.cache().is_some());
+    }
+}
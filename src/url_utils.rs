@@ -0,0 +1,143 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! URL 规范化工具
+//!
+//! 引擎解析器、去重和 API 共用的 URL 处理：
+//!
+//! - [`parse_http_url`] / [`is_http_url`]：只接受 http(s) 绝对地址（代替 `starts_with("http")`）
+//! - [`resolve_url`]：按引擎页面地址补全相对链接和协议相对链接（`//host/path`）
+//! - [`normalize_url`]：scheme 和主机名小写、国际化域名转为 punycode、去掉默认端口和片段
+//! - [`dedup_key`] / [`domain`]：去重和按域名分组用的键（忽略 scheme、`www.` 和末尾的 `/`）
+//! - [`display_url`]：展示用地址（主机名还原为 Unicode，路径百分号解码）
+
+use url::Url;
+
+/// 解析 http(s) 绝对地址，其他 scheme（`javascript:`、`data:` 等）和相对地址返回 None
+pub fn parse_http_url(url: &str) -> Option<Url> {
+    let parsed = Url::parse(url.trim()).ok()?;
+    (matches!(parsed.scheme(), "http" | "https") && parsed.host_str().is_some()).then_some(parsed)
+}
+
+/// 是否为 http(s) 绝对地址
+pub fn is_http_url(url: &str) -> bool {
+    parse_http_url(url).is_some()
+}
+
+/// 按页面地址补全链接，只返回 http(s) 地址
+///
+/// `base` 为 None 时只接受绝对地址和协议相对地址（按 https 补全）
+pub fn resolve_url(base: Option<&Url>, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() {
+        return None;
+    }
+    let resolved = match base {
+        Some(base) => base.join(href).ok()?,
+        None if href.starts_with("//") => Url::parse(&format!("https:{}", href)).ok()?,
+        None => Url::parse(href).ok()?,
+    };
+    parse_http_url(resolved.as_str()).map(|url| url.to_string())
+}
+
+/// 规范化地址：scheme 和主机名小写、国际化域名转为 punycode、去掉默认端口、
+/// 主机名末尾的 `.` 和片段；无法解析的地址原样返回（去掉首尾空白）
+pub fn normalize_url(url: &str) -> String {
+    let Some(mut parsed) = parse_http_url(url) else {
+        return url.trim().to_string();
+    };
+    parsed.set_fragment(None);
+    if let Some(host) = parsed.host_str().filter(|host| host.ends_with('.')).map(|host| host.trim_end_matches('.').to_string()) {
+        let _ = parsed.set_host(Some(&host));
+    }
+    parsed.to_string()
+}
+
+/// 主机名（小写、punycode、去掉 `www.` 前缀）
+pub fn domain(url: &str) -> Option<String> {
+    let parsed = parse_http_url(url)?;
+    let host = parsed.host_str()?.trim_end_matches('.');
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+/// 去重键：规范化后忽略 scheme、`www.` 前缀和路径末尾的 `/`；无法解析时为小写的原地址
+pub fn dedup_key(url: &str) -> String {
+    let Some(parsed) = parse_http_url(url) else {
+        return url.trim().to_lowercase();
+    };
+    let host = domain(url).unwrap_or_default();
+    let port = parsed.port().map(|port| format!(":{}", port)).unwrap_or_default();
+    let path = parsed.path().trim_end_matches('/');
+    let query = parsed.query().map(|query| format!("?{}", query)).unwrap_or_default();
+    format!("{}{}{}{}", host, port, path, query)
+}
+
+/// 展示用地址：去掉 scheme，主机名还原为 Unicode，路径和查询百分号解码（非法 UTF-8 保留原样）
+pub fn display_url(url: &str) -> String {
+    let Some(parsed) = parse_http_url(url) else {
+        return url.trim().to_string();
+    };
+    let host = parsed.host_str().unwrap_or_default();
+    let (unicode_host, result) = idna::domain_to_unicode(host);
+    let host = if result.is_ok() { unicode_host } else { host.to_string() };
+    let port = parsed.port().map(|port| format!(":{}", port)).unwrap_or_default();
+    let mut rest = parsed.path().to_string();
+    if let Some(query) = parsed.query() {
+        rest.push('?');
+        rest.push_str(query);
+    }
+    let rest = percent_decode(&rest).unwrap_or(rest);
+    format!("{}{}{}", host, port, rest.trim_end_matches('/'))
+}
+
+/// 百分号解码，结果不是合法 UTF-8 时返回 None
+fn percent_decode(text: &str) -> Option<String> {
+    urlencoding::decode(text).ok().map(|decoded| decoded.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        assert!(is_http_url("https://example.com/a"));
+        assert!(!is_http_url("javascript:alert(1)"));
+        assert!(!is_http_url("/relative/path"));
+        assert!(!is_http_url("httpfoo"));
+
+        let base = Url::parse("https://www.sogou.com/web?query=rust").unwrap();
+        assert_eq!(resolve_url(Some(&base), "/link?url=abc").as_deref(), Some("https://www.sogou.com/link?url=abc"));
+        assert_eq!(resolve_url(Some(&base), "//img.example.com/a.jpg").as_deref(), Some("https://img.example.com/a.jpg"));
+        assert_eq!(resolve_url(None, "//img.example.com/a.jpg").as_deref(), Some("https://img.example.com/a.jpg"));
+        assert_eq!(resolve_url(Some(&base), "javascript:void(0)"), None);
+        assert_eq!(resolve_url(None, "/relative"), None);
+    }
+
+    #[test]
+    fn test_normalize_and_dedup() {
+        assert_eq!(normalize_url("HTTPS://Example.COM.:443/Path#frag"), "https://example.com/Path");
+        assert_eq!(normalize_url("https://例子.测试/"), "https://xn--fsqu00a.xn--0zwm56d/");
+        assert_eq!(dedup_key("https://www.Example.com/a/"), dedup_key("http://example.com/a"));
+        assert_ne!(dedup_key("https://example.com/a?x=1"), dedup_key("https://example.com/a?x=2"));
+        assert_eq!(domain("https://WWW.例子.测试/x").as_deref(), Some("xn--fsqu00a.xn--0zwm56d"));
+    }
+
+    #[test]
+    fn test_display_url() {
+        assert_eq!(display_url("https://xn--fsqu00a.xn--0zwm56d/%E6%96%87%E6%A1%A3/"), "例子.测试/文档");
+        assert_eq!(display_url("https://example.com/a%20b?q=%E4%B8%AD"), "example.com/a b?q=中");
+        assert_eq!(display_url("not a url"), "not a url");
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 通用工具
+//!
+//! 点击跳转、预览链接和 webhook 共用的 HMAC-SHA256 签名
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> HmacSha256 {
+    // HMAC 接受任意长度的密钥，new_from_slice 不会失败
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac
+}
+
+/// 计算 HMAC-SHA256，返回小写十六进制字符串
+pub fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    hmac_sha256(key, message)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 校验十六进制 HMAC-SHA256 签名（常量时间比较）
+pub fn verify_hmac_sha256_hex(key: &[u8], message: &[u8], signature: &str) -> bool {
+    let Some(bytes) = decode_hex(signature) else {
+        return false;
+    };
+    hmac_sha256(key, message).verify_slice(&bytes).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // RFC 4231 测试用例 2
+        let mac = hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(mac, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        assert!(verify_hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?", &mac));
+        assert!(verify_hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?", &mac.to_uppercase()));
+        assert!(!verify_hmac_sha256_hex(b"Jefe", b"what do ya want", &mac));
+        assert!(!verify_hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?", &mac[..62]));
+        assert!(!verify_hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?", "zz"));
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2025 nostalgiatan
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! 浏览器端（wasm32）接口
+//!
+//! 浏览器扩展通过自己的传输层请求各引擎，再调用这里的函数在本地完成查询解析、
+//! 结果合并、去重和排序，得到与服务端相同的排序结果。
+//!
+//! 构建方式：
+//!
+//! ```bash
+//! cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm
+//! wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/debug/seesea_core.wasm
+//! ```
+//!
+//! 所有函数以 JSON 字符串作为输入和输出，结果项格式与 `SearchResultItem` 的序列化格式相同。
+//! 输入的结果项只有 `title` 和 `url` 是必填的。
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::derive::{ResultType, SearchQuery, SearchResult, SearchResultItem};
+use crate::search::{
+    deduplicate_by_url, score_and_sort_results, standardize_item, AggregationStrategy, QueryParser,
+    SearchAggregator, SortBy,
+};
+
+/// 扩展传入的单个结果项（未提供的字段使用默认值）
+#[derive(Debug, Deserialize)]
+struct ItemInput {
+    title: String,
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    display_url: Option<String>,
+    #[serde(default)]
+    site_name: Option<String>,
+    #[serde(default)]
+    score: f64,
+    #[serde(default)]
+    result_type: ResultType,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    published_date: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+impl From<ItemInput> for SearchResultItem {
+    fn from(input: ItemInput) -> Self {
+        SearchResultItem {
+            title: input.title,
+            url: input.url,
+            content: input.content,
+            display_url: input.display_url,
+            site_name: input.site_name,
+            score: input.score,
+            result_type: input.result_type,
+            thumbnail: input.thumbnail,
+            published_date: input.published_date,
+            template: None,
+            metadata: input.metadata,
+        }
+    }
+}
+
+/// 一个引擎返回的结果
+#[derive(Debug, Deserialize)]
+struct EngineResultsInput {
+    engine: String,
+    #[serde(default)]
+    items: Vec<ItemInput>,
+}
+
+/// 合并选项
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AggregateOptions {
+    /// 排序方式：`relevance`、`date` 或 `source`
+    sort: Option<String>,
+    /// 引擎权重系数（引擎名 -> 乘数）
+    engine_weights: HashMap<String, f64>,
+    /// 每个域名的结果上限（0 表示不做多样性重排）
+    max_per_domain: usize,
+}
+
+fn parse_items(items_json: &str) -> Result<Vec<SearchResultItem>, String> {
+    let items: Vec<ItemInput> = serde_json::from_str(items_json).map_err(|e| format!("无效的结果列表: {}", e))?;
+    Ok(items.into_iter().map(Into::into).collect())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("序列化失败: {}", e))
+}
+
+fn search_query(query: &str) -> SearchQuery {
+    SearchQuery {
+        query: query.to_string(),
+        ..Default::default()
+    }
+}
+
+fn parse_query_json(query: &str) -> Result<String, String> {
+    let parsed = QueryParser::default().parse(query);
+    to_json(&serde_json::json!({
+        "original": parsed.original,
+        "normalized": parsed.normalized,
+        "intent": format!("{:?}", parsed.intent).to_lowercase(),
+        "language": parsed.language,
+        "region": parsed.region,
+        "expanded_terms": parsed.expanded_terms,
+    }))
+}
+
+fn aggregate_json(query: &str, results_json: &str, options_json: Option<String>) -> Result<String, String> {
+    let results: Vec<EngineResultsInput> =
+        serde_json::from_str(results_json).map_err(|e| format!("无效的引擎结果: {}", e))?;
+    let options: AggregateOptions = match options_json.as_deref() {
+        Some(json) if !json.trim().is_empty() => {
+            serde_json::from_str(json).map_err(|e| format!("无效的合并选项: {}", e))?
+        }
+        _ => AggregateOptions::default(),
+    };
+    let sort_by = match options.sort.as_deref() {
+        Some(sort) => SortBy::from_param(sort).ok_or_else(|| format!("不支持的排序方式: {}", sort))?,
+        None => SortBy::Relevance,
+    };
+
+    let results = results
+        .into_iter()
+        .map(|r| SearchResult {
+            engine_name: r.engine,
+            total_results: Some(r.items.len()),
+            elapsed_ms: 0,
+            items: r.items.into_iter().map(Into::into).collect(),
+            pagination: None,
+            suggestions: Vec::new(),
+            metadata: HashMap::new(),
+        })
+        .collect();
+
+    let aggregated = SearchAggregator::new(AggregationStrategy::Merged, sort_by)
+        .with_engine_weights(options.engine_weights)
+        .with_max_per_domain(options.max_per_domain)
+        .aggregate_with_scoring(results, &search_query(query));
+    to_json(&aggregated.items)
+}
+
+fn deduplicate_json(items_json: &str) -> Result<String, String> {
+    let mut items = parse_items(items_json)?;
+    items.iter_mut().for_each(standardize_item);
+    deduplicate_by_url(&mut items);
+    to_json(&items)
+}
+
+fn rank_json(query: &str, items_json: &str) -> Result<String, String> {
+    let mut items = parse_items(items_json)?;
+    score_and_sort_results(&mut items, &search_query(query), "aggregated", None);
+    to_json(&items)
+}
+
+/// 解析查询（意图、规范化查询等）
+#[wasm_bindgen(js_name = parseQuery)]
+pub fn parse_query(query: &str) -> Result<String, JsError> {
+    parse_query_json(query).map_err(|e| JsError::new(&e))
+}
+
+/// 合并多个引擎的结果：标准化、去重、评分并排序
+///
+/// `results_json` 是 `[{"engine": "bing", "items": [...]}, ...]`，
+/// `options_json` 可选，如 `{"sort": "date", "engine_weights": {"bing": 1.5}, "max_per_domain": 3}`
+#[wasm_bindgen]
+pub fn aggregate(query: &str, results_json: &str, options_json: Option<String>) -> Result<String, JsError> {
+    aggregate_json(query, results_json, options_json).map_err(|e| JsError::new(&e))
+}
+
+/// 标准化结果项并按 URL 去重（保留第一次出现的结果）
+#[wasm_bindgen]
+pub fn deduplicate(items_json: &str) -> Result<String, JsError> {
+    deduplicate_json(items_json).map_err(|e| JsError::new(&e))
+}
+
+/// 按查询相关性为结果项评分并排序
+#[wasm_bindgen]
+pub fn rank(query: &str, items_json: &str) -> Result<String, JsError> {
+    rank_json(query, items_json).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_merges_and_dedups() {
+        let results = r#"[
+            {"engine": "bing", "items": [
+                {"title": "Rust Programming Language", "url": "https://www.rust-lang.org/", "content": "Rust is fast"},
+                {"title": "Other", "url": "https://example.com/"}
+            ]},
+            {"engine": "yandex", "items": [
+                {"title": "Rust", "url": "https://www.rust-lang.org/"}
+            ]}
+        ]"#;
+
+        let items: Vec<SearchResultItem> =
+            serde_json::from_str(&aggregate_json("rust programming", results, None).unwrap()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].url, "https://www.rust-lang.org/");
+        assert_eq!(items[0].metadata.get("engine").map(String::as_str), Some("bing"));
+        assert!(items[0].score >= items[1].score);
+
+        let error = aggregate_json("rust", results, Some(r#"{"sort": "random"}"#.to_string())).unwrap_err();
+        assert!(error.contains("random"));
+    }
+
+    #[test]
+    fn test_deduplicate_and_parse_query() {
+        let items = r#"[{"title": "A", "url": "https://a.com/"}, {"title": "A again", "url": "https://a.com/"}]"#;
+        let deduped: Vec<SearchResultItem> = serde_json::from_str(&deduplicate_json(items).unwrap()).unwrap();
+        assert_eq!(deduped.len(), 1);
+
+        let parsed: serde_json::Value = serde_json::from_str(&parse_query_json("  Rust  ").unwrap()).unwrap();
+        assert_eq!(parsed["original"], "  Rust  ");
+    }
+}
@@ -0,0 +1,108 @@
+// SeeSea 管理面板：定时拉取指标、缓存、引擎状态和最近错误
+
+const REFRESH_INTERVAL_MS = 5000;
+
+function escapeHtml(text) {
+    const div = document.createElement('div');
+    div.textContent = String(text);
+    return div.innerHTML;
+}
+
+function renderCards(id, entries) {
+    document.getElementById(id).innerHTML = entries
+        .map(([label, value]) =>
+            `<div class="card"><div class="label">${escapeHtml(label)}</div>` +
+            `<div class="value">${escapeHtml(value)}</div></div>`)
+        .join('');
+}
+
+function renderRows(id, rows, columns) {
+    const body = document.getElementById(id);
+    if (rows.length === 0) {
+        body.innerHTML = `<tr><td class="empty" colspan="${columns}">暂无数据</td></tr>`;
+        return;
+    }
+    body.innerHTML = rows.join('');
+}
+
+function formatBytes(bytes) {
+    const units = ['B', 'KB', 'MB', 'GB'];
+    let value = bytes;
+    let unit = 0;
+    while (value >= 1024 && unit < units.length - 1) {
+        value /= 1024;
+        unit++;
+    }
+    return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+async function fetchJson(url) {
+    const response = await fetch(url);
+    if (!response.ok) {
+        throw new Error(`${url}: ${response.status}`);
+    }
+    return response.json();
+}
+
+async function refreshMetrics() {
+    const m = await fetchJson('/api/metrics/realtime');
+    renderCards('metrics', [
+        ['总请求', m.total_requests],
+        ['成功', m.successful_requests],
+        ['失败', m.failed_requests],
+        ['平均耗时', `${m.avg_response_time_ms.toFixed(1)} ms`],
+        ['活跃连接', m.active_connections],
+        ['限流拒绝', m.rate_limited],
+        ['熔断拒绝', m.circuit_breaker_trips],
+        ['运行时间', `${m.uptime_seconds} s`],
+    ]);
+}
+
+async function refreshCache() {
+    const c = await fetchJson('/api/admin/cache');
+    renderCards('cache', [
+        ['命中率', `${(c.hit_rate * 100).toFixed(1)}%`],
+        ['命中', c.hits],
+        ['未命中', c.misses],
+        ['键数', c.total_keys],
+        ['大小', formatBytes(c.estimated_size_bytes)],
+        ['过期清理', c.evictions],
+    ]);
+}
+
+async function refreshEngines() {
+    const engines = await fetchJson('/api/admin/engines');
+    renderRows('engines', engines.map(e => {
+        const status = e.available
+            ? '<span class="ok">可用</span>'
+            : `<span class="bad">禁用${e.disabled_remaining_secs != null ? ` (${e.disabled_remaining_secs}s)` : ''}</span>`;
+        return `<tr><td>${escapeHtml(e.name)}</td><td>${status}</td>` +
+            `<td>${e.consecutive_failures}</td><td>${e.total_requests}</td>` +
+            `<td>${e.successful_requests}</td><td>${e.failed_requests}</td>` +
+            `<td>${e.avg_response_time_ms} ms</td></tr>`;
+    }), 7);
+}
+
+async function refreshErrors() {
+    const errors = await fetchJson('/api/admin/errors');
+    renderRows('errors', errors.map(e =>
+        `<tr><td>${escapeHtml(new Date(e.timestamp).toLocaleTimeString())}</td>` +
+        `<td>${escapeHtml(e.engine)}</td><td>${escapeHtml(e.message)}</td></tr>`), 3);
+}
+
+async function refresh() {
+    const results = await Promise.allSettled([
+        refreshMetrics(),
+        refreshCache(),
+        refreshEngines(),
+        refreshErrors(),
+    ]);
+    results
+        .filter(r => r.status === 'rejected')
+        .forEach(r => console.error('刷新失败', r.reason));
+    document.getElementById('updated').textContent =
+        `更新于 ${new Date().toLocaleTimeString()}`;
+}
+
+refresh();
+setInterval(refresh, REFRESH_INTERVAL_MS);
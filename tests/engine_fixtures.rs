@@ -0,0 +1,247 @@
+//! Golden-output tests for engine response parsing
+//!
+//! Every engine's `response()` is run against a captured response in
+//! `tests/fixtures/engines/` and compared with the matching `*.expected.json`.
+//! Run with `SEESEA_UPDATE_FIXTURES=1` to regenerate the expected files after
+//! an intentional parser change, then review the diff.
+//!
+//! The `fetch` tests swap the HTTP client for a `MockTransport`, so the whole
+//! request → fetch → response path runs without network access.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use seesea_core::derive::{RequestResponseEngine, SearchEngine, SearchQuery, SearchResultItem};
+use seesea_core::net::client::{MockResponse, MockTransport};
+use seesea_core::search::engines::*;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/engines").join(name)
+}
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(fixture_path(name))
+        .unwrap_or_else(|e| panic!("missing fixture {}: {}", name, e))
+}
+
+/// Compare parsed items with `<engine>.expected.json`
+fn assert_golden(engine: &str, items: &[SearchResultItem]) {
+    let actual = serde_json::to_value(items).unwrap();
+    let path = fixture_path(&format!("{}.expected.json", engine));
+
+    if std::env::var_os("SEESEA_UPDATE_FIXTURES").is_some() {
+        std::fs::write(&path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let expected: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("missing golden file {}: {}", path.display(), e)),
+    )
+    .unwrap();
+    assert_eq!(
+        actual,
+        expected,
+        "{} output differs from {} (set SEESEA_UPDATE_FIXTURES=1 to regenerate)",
+        engine,
+        path.display()
+    );
+}
+
+fn query(text: &str) -> SearchQuery {
+    SearchQuery {
+        query: text.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_bing_fixture() {
+    let items = BingEngine::new().response(fixture("bing.html")).unwrap();
+    assert_eq!(items.len(), 3);
+    assert_golden("bing", &items);
+}
+
+#[test]
+fn test_bing_images_fixture() {
+    let items = BingImagesEngine::new().response(fixture("bing_images.html")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_golden("bing_images", &items);
+}
+
+#[test]
+fn test_baidu_fixture() {
+    let items = BaiduEngine::new().response((fixture("baidu.json"), None)).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("baidu", &items);
+}
+
+#[test]
+fn test_baidu_html_fallback_fixture() {
+    // JSON API blocked: Baidu serves the regular result page instead
+    let items = BaiduEngine::new().response((fixture("baidu_serp.html"), None)).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("baidu_serp", &items);
+}
+
+#[test]
+fn test_yandex_fixture() {
+    let items = YandexEngine::new().response((fixture("yandex.html"), None)).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("yandex", &items);
+}
+
+#[test]
+fn test_sogou_fixture() {
+    let items = SogouEngine::new().response(fixture("sogou.html")).unwrap();
+    assert_eq!(items.len(), 4);
+    assert_golden("sogou", &items);
+}
+
+#[test]
+fn test_sogou_wechat_fixture() {
+    let items = SogouWechatEngine::new().response(fixture("sogou_wechat.html")).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("sogou_wechat", &items);
+}
+
+#[test]
+fn test_sogou_videos_fixture() {
+    let items = SogouVideosEngine::new().response(fixture("sogou_videos.html")).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("sogou_videos", &items);
+}
+
+#[test]
+fn test_so_fixture() {
+    let items = SoEngine::new().response(fixture("so.html")).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_golden("so", &items);
+}
+
+#[test]
+fn test_bilibili_fixture() {
+    let items = BilibiliEngine::new().response(fixture("bilibili.json")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_golden("bilibili", &items);
+}
+
+#[test]
+fn test_unsplash_fixture() {
+    let items = UnsplashEngine::new().response(fixture("unsplash.json")).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_golden("unsplash", &items);
+}
+
+#[tokio::test]
+async fn test_bing_fetch_with_mock_transport() {
+    let transport = Arc::new(
+        MockTransport::new().respond("www.bing.com/search", MockResponse::ok(fixture("bing.html"))),
+    );
+    let engine = BingEngine::with_client(transport.clone());
+
+    let result = SearchEngine::search(&engine, &query("rust programming")).await.unwrap();
+    assert_eq!(result.engine_name, "Bing");
+    assert_eq!(result.items.len(), 3);
+    assert_eq!(result.items[0].url, "https://www.rust-lang.org/");
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].url.contains("q=rust+programming") || requests[0].url.contains("q=rust%20programming"));
+    assert!(requests[0].header("cookie").is_some());
+}
+
+#[tokio::test]
+async fn test_fetch_maps_http_errors() {
+    let transport = Arc::new(MockTransport::new().respond("www.bing.com", MockResponse::new(429, "")));
+    let engine = BingEngine::with_client(transport);
+    let err = SearchEngine::search(&engine, &query("rust")).await.unwrap_err();
+    assert!(err.to_string().contains("频繁"), "unexpected error: {}", err);
+
+    // Baidu redirects to a captcha page
+    let transport = Arc::new(MockTransport::new().respond(
+        "www.baidu.com",
+        MockResponse::new(302, "").header("location", "https://wappass.baidu.com/static/captcha/tuxing.html"),
+    ));
+    let engine = BaiduEngine::with_client(transport);
+    let err = SearchEngine::search(&engine, &query("rust")).await.unwrap_err();
+    assert!(err.to_string().contains("CAPTCHA"), "unexpected error: {}", err);
+}
+
+#[tokio::test]
+async fn test_json_engines_fetch_with_mock_transport() {
+    let transport = Arc::new(
+        MockTransport::new()
+            .respond(
+                "api.bilibili.com/x/web-interface/nav",
+                MockResponse::ok(r#"{"code":-101,"data":{"wbi_img":{"img_url":"https://i0.hdslb.com/bfs/wbi/7cd084941338484aae1ad9425b84077c.png","sub_url":"https://i0.hdslb.com/bfs/wbi/4932caff0ff746eab6f01bf08b70ac45.png"}}}"#),
+            )
+            .respond("api.bilibili.com/x/web-interface/wbi/search", MockResponse::ok(fixture("bilibili.json")))
+            .respond("unsplash.com/napi", MockResponse::ok(fixture("unsplash.json"))),
+    );
+
+    let bilibili = BilibiliEngine::with_client(transport.clone());
+    let result = SearchEngine::search(&bilibili, &query("rust")).await.unwrap();
+    assert_eq!(result.items.len(), 1);
+
+    let unsplash = UnsplashEngine::with_client(transport.clone());
+    let result = SearchEngine::search(&unsplash, &query("crab")).await.unwrap();
+    assert_eq!(result.items.len(), 1);
+
+    let urls: Vec<String> = transport.requests().into_iter().map(|r| r.url).collect();
+    let search = urls.iter().find(|u| u.contains("/wbi/search/type")).unwrap();
+    assert!(search.contains("keyword=rust") && search.contains("w_rid="));
+    assert!(urls.last().unwrap().contains("query=crab"));
+}
+
+#[tokio::test]
+async fn test_unsplash_official_api_with_access_key() {
+    let transport = Arc::new(
+        MockTransport::new()
+            .respond("api.unsplash.com/search/photos?query=crab", MockResponse::ok(fixture("unsplash.json")))
+            .respond("api.unsplash.com", MockResponse::new(401, r#"{"errors":["OAuth error: The access token is invalid"]}"#)),
+    );
+    let engine = UnsplashEngine::with_access_key(transport.clone(), "test-access-key");
+    assert!(engine.uses_official_api());
+    assert!(engine.info().about.use_official_api);
+
+    let result = SearchEngine::search(&engine, &query("crab")).await.unwrap();
+    let item = &result.items[0];
+    assert_eq!(item.url, "https://unsplash.com/photos/red-crab-Yv7qCnkO7Xc?utm_source=seesea&utm_medium=referral");
+    let image = item.image().unwrap();
+    assert_eq!(image.author_url.as_deref(), Some("https://unsplash.com/@janedoe?utm_source=seesea&utm_medium=referral"));
+    assert_eq!(image.license.as_deref(), Some("Unsplash License"));
+
+    let request = &transport.requests()[0];
+    assert!(request.url.starts_with("https://api.unsplash.com/search/photos?"));
+    assert_eq!(request.header("authorization"), Some("Client-ID test-access-key"));
+    assert_eq!(request.header("accept-version"), Some("v1"));
+
+    let err = SearchEngine::search(&engine, &query("forbidden")).await.unwrap_err();
+    assert!(err.to_string().contains("access key"), "unexpected error: {}", err);
+
+    // 空密钥回退到网页接口
+    assert!(!UnsplashEngine::with_access_key(transport, " ").uses_official_api());
+}
+
+#[tokio::test]
+async fn test_sogou_wechat_resolves_redirect_links() {
+    let transport = Arc::new(
+        MockTransport::new()
+            .respond("weixin.sogou.com/weixin", MockResponse::ok(fixture("sogou_wechat.html")))
+            .respond(
+                "weixin.sogou.com/link?url=dn9a",
+                MockResponse::ok("<script>var url = '';\nurl += 'https://mp.';\nurl += 'weixin.qq.com/s?src=11&timestamp=1722470400&sig@nature=abc';\nwindow.location.replace(url)</script>"),
+            ),
+    );
+    let engine = SogouWechatEngine::with_client(transport.clone());
+
+    let result = SearchEngine::search(&engine, &query("rust")).await.unwrap();
+    let urls: Vec<&str> = result.items.iter().map(|item| item.url.as_str()).collect();
+    assert_eq!(urls, [
+        "https://mp.weixin.qq.com/s?src=11&timestamp=1722470400&signature=abc",
+        // 可直接解码的链接无需请求跳转页
+        "https://mp.weixin.qq.com/s/rustAsync",
+    ]);
+    assert_eq!(transport.requests().len(), 2);
+}
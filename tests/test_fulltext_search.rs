@@ -208,6 +208,8 @@ fn test_rss_cache_fulltext_search() {
             categories: vec!["programming".to_string(), "rust".to_string()],
             guid: Some("rust-1.70".to_string()),
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: HashMap::new(),
         },
         RssFeedItem {
@@ -220,6 +222,8 @@ fn test_rss_cache_fulltext_search() {
             categories: vec!["programming".to_string(), "python".to_string()],
             guid: Some("python-3.12".to_string()),
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: HashMap::new(),
         },
     ];
@@ -29,6 +29,14 @@ class TestSearchClient:
         assert hasattr(client, 'search')
         assert callable(client.search)
         
+    def test_client_has_async_methods(self):
+        """Test that SearchClient exposes async search and async streaming"""
+        import inspect
+        from seesea import SearchClient
+        client = SearchClient()
+        assert inspect.iscoroutinefunction(client.search_async)
+        assert inspect.isasyncgenfunction(client.search_stream)
+        
     def test_client_has_get_stats_method(self):
         """Test that SearchClient has get_stats method"""
         from seesea import SearchClient
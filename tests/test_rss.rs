@@ -16,6 +16,8 @@ mod rss_tests {
             guid: Some("unique-id-123".to_string()),
             categories: vec!["tech".to_string(), "rust".to_string()],
             enclosures: vec![],
+            tags: vec![],
+            alternate_sources: vec![],
             custom_fields: std::collections::HashMap::new(),
         };
         